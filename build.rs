@@ -0,0 +1,46 @@
+//! Compiles build metadata served by `GET /version` into the binary:
+//! `OZ_MONITOR_GIT_SHA` from the `GIT_SHA` environment variable (for builds
+//! without a checkout, e.g. Docker) or `git rev-parse HEAD`, and
+//! `OZ_MONITOR_LIBRARY_VERSION`, the openzeppelin-monitor version in Cargo.lock.
+
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    println!("cargo:rerun-if-changed=.git/HEAD");
+    println!("cargo:rerun-if-changed=.git/refs");
+    println!("cargo:rerun-if-changed=Cargo.lock");
+
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(head_commit)
+        .unwrap_or_else(|| "unknown".to_string());
+    println!("cargo:rustc-env=OZ_MONITOR_GIT_SHA={}", git_sha);
+
+    let library_version =
+        locked_version("openzeppelin-monitor").unwrap_or_else(|| "unknown".into());
+    println!(
+        "cargo:rustc-env=OZ_MONITOR_LIBRARY_VERSION={}",
+        library_version
+    );
+}
+
+fn head_commit() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let sha = String::from_utf8(output.stdout).ok()?;
+    Some(sha.trim().to_string()).filter(|sha| !sha.is_empty())
+}
+
+/// Version of a package as resolved in Cargo.lock
+fn locked_version(package: &str) -> Option<String> {
+    let lock = std::fs::read_to_string("Cargo.lock").ok()?;
+    let name = format!("name = \"{}\"", package);
+    let mut lines = lock.lines().skip_while(|line| line.trim() != name).skip(1);
+    let version = lines.next()?.trim().strip_prefix("version = \"")?;
+    Some(version.trim_end_matches('"').to_string())
+}
@@ -0,0 +1,32 @@
+-- Quiet hours during which tenant notifications are held and later sent as a digest.
+-- Rows are managed by the tenant isolation API and read by the orchestrator.
+CREATE TABLE IF NOT EXISTS tenant_quiet_hours (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    -- Local start/end of the window; end < start wraps past midnight
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    -- Offset of the tenant's local time from UTC
+    utc_offset_minutes INTEGER NOT NULL DEFAULT 0,
+    -- Days the window starts on (0 = Monday .. 6 = Sunday); empty means every day
+    days_of_week SMALLINT[] NOT NULL DEFAULT '{}',
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_quiet_hours_tenant
+    ON tenant_quiet_hours (tenant_id) WHERE is_active;
+
+-- Matches whose notifications were held during quiet hours
+CREATE TABLE IF NOT EXISTS held_notifications (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    monitor_name TEXT NOT NULL,
+    match_data JSONB NOT NULL,
+    held_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_held_notifications_pending
+    ON held_notifications (tenant_id, held_at) WHERE delivered_at IS NULL;
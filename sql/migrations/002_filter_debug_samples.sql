@@ -0,0 +1,29 @@
+-- Per-tenant filter debugging. While enabled, a sampled fraction of blocks
+-- has its full filter inputs and outputs recorded for support investigations.
+CREATE TABLE IF NOT EXISTS tenant_filter_debug (
+    tenant_id UUID PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    -- Fraction of blocks recorded, between 0 and 1
+    sample_rate DOUBLE PRECISION NOT NULL CHECK (sample_rate >= 0 AND sample_rate <= 1),
+    -- Sampling stops after this time; NULL keeps it on until disabled
+    enabled_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Recorded filter runs for sampled blocks
+CREATE TABLE IF NOT EXISTS filter_debug_samples (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    network_slug TEXT NOT NULL,
+    block_number BIGINT,
+    -- Inputs: the block and the monitors it was filtered against
+    block JSONB NOT NULL,
+    monitors JSONB NOT NULL,
+    -- Outputs: raw filter service matches and the matches kept after trigger conditions
+    filter_matches JSONB NOT NULL,
+    accepted_matches JSONB NOT NULL,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_filter_debug_samples_tenant
+    ON filter_debug_samples (tenant_id, network_slug, block_number);
@@ -0,0 +1,18 @@
+-- Enforcement of tenants.max_rpc_requests_per_minute.
+-- Monitors marked critical keep running under the skip_non_critical action.
+ALTER TABLE tenant_monitors
+    ADD COLUMN IF NOT EXISTS is_critical BOOLEAN NOT NULL DEFAULT false;
+
+-- Cap violations, surfaced to tenants by the tenant isolation API
+CREATE TABLE IF NOT EXISTS tenant_rpc_cap_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    -- Actions applied, e.g. {throttle,notify}
+    actions TEXT[] NOT NULL,
+    requests INTEGER NOT NULL,
+    request_limit INTEGER NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_rpc_cap_events_tenant
+    ON tenant_rpc_cap_events (tenant_id, created_at);
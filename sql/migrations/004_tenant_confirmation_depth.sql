@@ -0,0 +1,5 @@
+-- Per-tenant override of a network's confirmation_blocks.
+-- NULL keeps the depth from the network configuration.
+ALTER TABLE tenant_networks
+    ADD COLUMN IF NOT EXISTS confirmation_blocks INTEGER
+        CHECK (confirmation_blocks IS NULL OR confirmation_blocks >= 0);
@@ -0,0 +1,24 @@
+-- Monitor matches and their confirmation lifecycle: provisional when first
+-- seen in a block short of the tenant's confirmation depth, finalized once the
+-- block is deep enough, orphaned if a reorg replaced the block before that.
+CREATE TABLE IF NOT EXISTS monitor_matches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    network_slug TEXT NOT NULL,
+    monitor_name TEXT NOT NULL,
+    block_number BIGINT,
+    -- Hash of the block the match was seen in, compared again before finalizing
+    block_hash TEXT,
+    state TEXT NOT NULL CHECK (state IN ('provisional', 'finalized', 'orphaned')),
+    match_data JSONB NOT NULL,
+    first_seen_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_monitor_matches_tenant
+    ON monitor_matches (tenant_id, network_slug, block_number);
+
+-- Match states that fire the tenant's triggers on a network
+ALTER TABLE tenant_networks
+    ADD COLUMN IF NOT EXISTS trigger_on_states TEXT[] NOT NULL DEFAULT '{provisional,finalized}'
+        CHECK (trigger_on_states <@ ARRAY['provisional', 'finalized', 'orphaned']);
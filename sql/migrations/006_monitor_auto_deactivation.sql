@@ -0,0 +1,18 @@
+-- Monitors deactivated after failing on too many consecutive blocks keep the
+-- reason, so tenants can fix the configuration and reactivate them.
+ALTER TABLE tenant_monitors
+    ADD COLUMN IF NOT EXISTS error_message TEXT,
+    ADD COLUMN IF NOT EXISTS errored_at TIMESTAMPTZ;
+
+-- Automatic deactivations, surfaced to tenants by the tenant isolation API
+CREATE TABLE IF NOT EXISTS tenant_monitor_deactivations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    monitor_name TEXT NOT NULL,
+    consecutive_failures INTEGER NOT NULL,
+    last_error TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_monitor_deactivations_tenant
+    ON tenant_monitor_deactivations (tenant_id, created_at);
@@ -0,0 +1,25 @@
+-- Notifications whose trigger delivery failed after retries. Support can
+-- inspect them through the management API and requeue them, after which the
+-- worker owning the tenant redelivers them on its next digest flush.
+CREATE TABLE IF NOT EXISTS trigger_dead_letters (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    monitor_name TEXT NOT NULL,
+    -- Triggers that failed; only these are redelivered
+    trigger_names TEXT[] NOT NULL,
+    match_state TEXT NOT NULL,
+    match_data JSONB NOT NULL,
+    error TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'failed'
+        CHECK (status IN ('failed', 'requeued', 'retrying', 'delivered')),
+    attempts INTEGER NOT NULL DEFAULT 1,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    requeued_at TIMESTAMPTZ,
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_trigger_dead_letters_tenant
+    ON trigger_dead_letters (tenant_id, failed_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_trigger_dead_letters_requeued
+    ON trigger_dead_letters (tenant_id) WHERE status = 'requeued';
@@ -0,0 +1,16 @@
+-- Daily RPC usage attributed to tenants for chargeback. Filter requests are
+-- charged to their tenant; block fetches shared by a network's tenants are
+-- split in proportion to their active monitors, hence fractional counts.
+CREATE TABLE IF NOT EXISTS tenant_rpc_usage (
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    network_slug TEXT NOT NULL,
+    usage_date DATE NOT NULL,
+    -- Requests sent to RPC
+    rpc_requests DOUBLE PRECISION NOT NULL DEFAULT 0,
+    -- Requests answered from the block cache
+    cached_requests DOUBLE PRECISION NOT NULL DEFAULT 0,
+    PRIMARY KEY (tenant_id, network_slug, usage_date)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_rpc_usage_date
+    ON tenant_rpc_usage (usage_date);
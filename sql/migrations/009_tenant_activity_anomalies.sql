@@ -0,0 +1,40 @@
+-- Tenant activity sampled by the anomaly analyzer. A tenant's current rates
+-- are compared against the mean of its recent samples.
+CREATE TABLE IF NOT EXISTS tenant_metrics_history (
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    collected_at TIMESTAMPTZ NOT NULL,
+    monitors_count INTEGER NOT NULL,
+    matches_per_minute DOUBLE PRECISION NOT NULL,
+    total_matches_last_hour INTEGER NOT NULL,
+    rpc_calls_per_minute DOUBLE PRECISION NOT NULL,
+    -- Attributed RPC requests of the sample's day so far, to derive the next rate
+    rpc_requests_today DOUBLE PRECISION NOT NULL,
+    last_active TIMESTAMPTZ,
+    PRIMARY KEY (tenant_id, collected_at)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_metrics_history_collected
+    ON tenant_metrics_history (collected_at);
+
+-- Spikes in tenant activity. Auto-throttled RPC spikes cap the tenant's RPC
+-- requests per minute at throttle_limit until throttled_until.
+CREATE TABLE IF NOT EXISTS tenant_activity_anomalies (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('match_rate', 'rpc_rate')),
+    current_rate DOUBLE PRECISION NOT NULL,
+    baseline_rate DOUBLE PRECISION NOT NULL,
+    throttle_limit INTEGER,
+    throttled_until TIMESTAMPTZ,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_activity_anomalies_tenant
+    ON tenant_activity_anomalies (tenant_id, kind, detected_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_activity_anomalies_throttled
+    ON tenant_activity_anomalies (tenant_id) WHERE throttled_until IS NOT NULL;
+
+-- Matches per tenant over the last sampling window
+CREATE INDEX IF NOT EXISTS idx_monitor_matches_tenant_seen
+    ON monitor_matches (tenant_id, first_seen_at);
@@ -0,0 +1,13 @@
+-- Highest block each tenant has processed per network. Rewinding a
+-- checkpoint replays the blocks after it for that tenant; the rewind is kept
+-- for auditing until the next one.
+CREATE TABLE IF NOT EXISTS tenant_block_checkpoints (
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    network_slug TEXT NOT NULL,
+    last_processed_block BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Checkpoint before the latest rewind
+    rewound_from BIGINT,
+    rewound_at TIMESTAMPTZ,
+    PRIMARY KEY (tenant_id, network_slug)
+);
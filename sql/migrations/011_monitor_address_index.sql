@@ -0,0 +1,48 @@
+-- Reverse index from contract address to the monitors watching it, kept in
+-- sync with tenant_monitors by a trigger so writes from the tenant isolation
+-- API are indexed too. Addresses are stored lowercased.
+CREATE TABLE IF NOT EXISTS tenant_monitor_addresses (
+    monitor_id UUID NOT NULL REFERENCES tenant_monitors(id) ON DELETE CASCADE,
+    tenant_id UUID NOT NULL,
+    -- tenant_networks.id of the monitor's network
+    network_id UUID NOT NULL,
+    address TEXT NOT NULL,
+    PRIMARY KEY (monitor_id, address)
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_monitor_addresses_address
+    ON tenant_monitor_addresses (address, network_id);
+
+CREATE OR REPLACE FUNCTION index_tenant_monitor_addresses() RETURNS TRIGGER AS $$
+BEGIN
+    DELETE FROM tenant_monitor_addresses WHERE monitor_id = NEW.id;
+    INSERT INTO tenant_monitor_addresses (monitor_id, tenant_id, network_id, address)
+    SELECT DISTINCT NEW.id, NEW.tenant_id, NEW.network_id, LOWER(entry ->> 'address')
+    FROM jsonb_array_elements(
+        CASE WHEN jsonb_typeof(NEW.configuration::jsonb -> 'addresses') = 'array'
+             THEN NEW.configuration::jsonb -> 'addresses'
+             ELSE '[]'::jsonb
+        END
+    ) AS a(entry)
+    WHERE entry ->> 'address' IS NOT NULL;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS tenant_monitors_index_addresses ON tenant_monitors;
+CREATE TRIGGER tenant_monitors_index_addresses
+    AFTER INSERT OR UPDATE OF configuration, network_id, tenant_id ON tenant_monitors
+    FOR EACH ROW EXECUTE FUNCTION index_tenant_monitor_addresses();
+
+-- Index monitors created before this migration
+INSERT INTO tenant_monitor_addresses (monitor_id, tenant_id, network_id, address)
+SELECT DISTINCT m.id, m.tenant_id, m.network_id, LOWER(a.entry ->> 'address')
+FROM tenant_monitors m,
+     jsonb_array_elements(
+         CASE WHEN jsonb_typeof(m.configuration::jsonb -> 'addresses') = 'array'
+              THEN m.configuration::jsonb -> 'addresses'
+              ELSE '[]'::jsonb
+         END
+     ) AS a(entry)
+WHERE a.entry ->> 'address' IS NOT NULL
+ON CONFLICT DO NOTHING;
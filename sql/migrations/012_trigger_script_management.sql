@@ -0,0 +1,18 @@
+-- Trigger condition scripts managed through the management API. The tenant
+-- isolation schema may already provide the table, so missing columns are
+-- added; scripts stored before then are Python, the only language it ran.
+CREATE TABLE IF NOT EXISTS trigger_scripts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    content TEXT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE trigger_scripts
+    ADD COLUMN IF NOT EXISTS language TEXT NOT NULL DEFAULT 'Python',
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_trigger_scripts_tenant_name
+    ON trigger_scripts (tenant_id, name);
@@ -0,0 +1,23 @@
+-- Mutating management API calls: who made them (the API token), what they
+-- targeted and how they ended. Payloads are kept as hashes only.
+CREATE TABLE IF NOT EXISTS orchestrator_audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    -- Prefix of the hash of the API token used
+    actor TEXT NOT NULL,
+    -- Method and route, e.g. "POST /tenants/:tenant_id/suspend"
+    action TEXT NOT NULL,
+    path TEXT NOT NULL,
+    tenant_id UUID,
+    worker_id TEXT,
+    -- Hex SHA-256 of the request body; NULL without one
+    payload_hash TEXT,
+    status_code INTEGER NOT NULL,
+    outcome TEXT NOT NULL CHECK (outcome IN ('success', 'failure'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_orchestrator_audit_log_occurred
+    ON orchestrator_audit_log (occurred_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_orchestrator_audit_log_tenant
+    ON orchestrator_audit_log (tenant_id, occurred_at DESC) WHERE tenant_id IS NOT NULL;
@@ -0,0 +1,11 @@
+-- Firehose webhook of a tenant, receiving every match its monitors produce
+-- independently of their triggers. Workers batch matches per tenant and sign
+-- each delivery with the webhook's secret.
+CREATE TABLE IF NOT EXISTS tenant_match_webhooks (
+    tenant_id UUID PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    secret TEXT NOT NULL,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
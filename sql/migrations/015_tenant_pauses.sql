@@ -0,0 +1,8 @@
+-- Tenants paused through the management API. Paused tenants stay assigned to
+-- their workers, keeping affinity and checkpoints, but their blocks are not
+-- processed until they are resumed.
+CREATE TABLE IF NOT EXISTS tenant_pauses (
+    tenant_id UUID PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    reason TEXT,
+    paused_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,15 @@
+-- Tenant assignments to workers. The load balancer writes every placement
+-- through to this table so restarted coordinators and workers start from the
+-- previous assignment map. `version` is the assignment's version: a write
+-- only replaces a row holding an older version, so a stale writer cannot
+-- overwrite a newer placement.
+CREATE TABLE IF NOT EXISTS tenant_assignments (
+    tenant_id UUID PRIMARY KEY REFERENCES tenants(id) ON DELETE CASCADE,
+    worker_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    assigned_at TIMESTAMPTZ NOT NULL,
+    version INTEGER NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_assignments_worker_id ON tenant_assignments(worker_id);
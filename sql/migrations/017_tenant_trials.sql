@@ -0,0 +1,5 @@
+-- Tenants in their trial period. Active trial tenants are reported with the
+-- trial status and scheduled in the trial class configured by worker.trial:
+-- evaluated on fewer blocks, with fewer monitors and a lower RPC cap.
+ALTER TABLE tenants
+    ADD COLUMN IF NOT EXISTS is_trial BOOLEAN NOT NULL DEFAULT false;
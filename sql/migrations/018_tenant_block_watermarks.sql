@@ -0,0 +1,6 @@
+-- Block up to which every block of a network is accounted for per tenant:
+-- evaluated against its monitors or skipped while the tenant was paused,
+-- outside its trial schedule or over its RPC cap. Unlike the checkpoint it
+-- never moves past a block that was not processed.
+ALTER TABLE tenant_block_checkpoints
+    ADD COLUMN IF NOT EXISTS watermark_block BIGINT;
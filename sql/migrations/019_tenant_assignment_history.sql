@@ -0,0 +1,15 @@
+-- Every assignment the load balancer saved to tenant_assignments, in the
+-- order they were saved, for the reassignment timeline of a tenant. Versions
+-- start over at 1 when a tenant is placed again after losing its assignment,
+-- so rows are ordered by `id` rather than `version`.
+CREATE TABLE IF NOT EXISTS tenant_assignment_history (
+    id BIGSERIAL PRIMARY KEY,
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    worker_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    assigned_at TIMESTAMPTZ NOT NULL,
+    version INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tenant_assignment_history_tenant_id
+    ON tenant_assignment_history(tenant_id, id);
@@ -0,0 +1,24 @@
+-- Severity rules of tenants' monitors. A match is tagged with the highest
+-- severity whose rule script, one of the tenant's trigger_scripts run like a
+-- trigger condition, returns true for it, and `info` when none does.
+-- Rows are managed by the tenant isolation API and read by the orchestrator.
+CREATE TABLE IF NOT EXISTS tenant_severity_rules (
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    monitor_name TEXT NOT NULL,
+    severity TEXT NOT NULL CHECK (severity IN ('info', 'warning', 'critical')),
+    script_name TEXT NOT NULL,
+    arguments TEXT,
+    timeout_ms INTEGER NOT NULL DEFAULT 1000 CHECK (timeout_ms > 0),
+    PRIMARY KEY (tenant_id, monitor_name, severity)
+);
+
+-- Delivery of each severity of a tenant's matches. Non-empty `triggers`
+-- replace the monitor's own triggers, and notifications of the severity
+-- beyond `max_notifications_per_minute` (unlimited when NULL) are dropped.
+CREATE TABLE IF NOT EXISTS tenant_severity_routes (
+    tenant_id UUID NOT NULL REFERENCES tenants(id) ON DELETE CASCADE,
+    severity TEXT NOT NULL CHECK (severity IN ('info', 'warning', 'critical')),
+    triggers TEXT[] NOT NULL DEFAULT '{}',
+    max_notifications_per_minute INTEGER CHECK (max_notifications_per_minute > 0),
+    PRIMARY KEY (tenant_id, severity)
+);
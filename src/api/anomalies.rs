@@ -0,0 +1,57 @@
+//! Tenant activity anomaly endpoints
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::TenantAnomaly;
+use crate::services::AnomalyStore;
+
+/// Query of `GET /anomalies`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnomalyQuery {
+    /// Only anomalies of this tenant
+    pub tenant_id: Option<Uuid>,
+
+    /// Maximum anomalies returned, newest first
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+/// Result of lifting a tenant's anomaly throttles
+#[derive(Debug, Clone, Serialize)]
+pub struct LiftThrottleResponse {
+    pub tenant_id: Uuid,
+
+    /// Unexpired throttles ended
+    pub lifted: u64,
+}
+
+/// List recent spikes in tenant activity
+pub async fn list_anomalies(
+    State(state): State<ApiState>,
+    Query(query): Query<AnomalyQuery>,
+) -> ApiResult<Vec<TenantAnomaly>> {
+    let anomalies = AnomalyStore::new(state.db.clone())
+        .list(query.tenant_id, query.limit.clamp(1, 1000))
+        .await?;
+    Ok(Json(anomalies))
+}
+
+/// End a tenant's anomaly throttles before they expire
+pub async fn lift_throttle(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<LiftThrottleResponse> {
+    let lifted = AnomalyStore::new(state.db.clone())
+        .lift_throttles(tenant_id)
+        .await?;
+    Ok(Json(LiftThrottleResponse { tenant_id, lifted }))
+}
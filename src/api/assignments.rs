@@ -0,0 +1,80 @@
+//! Tenant assignment endpoints
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::TenantAssignment;
+
+/// Query of `GET /assignments`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssignmentQuery {
+    /// Only tenants assigned to this worker
+    pub worker_id: Option<String>,
+
+    /// Only assignments made after this time, e.g. the `as_of` of a previous response
+    pub changed_since: Option<DateTime<Utc>>,
+}
+
+/// Response of `GET /assignments`
+#[derive(Debug, Clone, Serialize)]
+pub struct AssignmentsResponse {
+    /// Assignments in order of assignment time
+    pub assignments: Vec<TenantAssignment>,
+
+    /// When the assignments were read, to pass as `changed_since` on the next poll
+    pub as_of: DateTime<Utc>,
+}
+
+/// Dump the load balancer's tenant-to-worker map.
+///
+/// Tenants split into shards are not included, and tenants taken off their
+/// worker simply disappear rather than showing up as changes.
+pub async fn list_assignments(
+    State(state): State<ApiState>,
+    Query(query): Query<AssignmentQuery>,
+) -> ApiResult<AssignmentsResponse> {
+    let as_of = Utc::now();
+    let mut assignments = state.load_balancer.all_assignments().await;
+    if let Some(worker_id) = &query.worker_id {
+        assignments.retain(|assignment| &assignment.worker_id == worker_id);
+    }
+    if let Some(changed_since) = query.changed_since {
+        assignments.retain(|assignment| assignment.assigned_at > changed_since);
+    }
+
+    Ok(Json(AssignmentsResponse { assignments, as_of }))
+}
+
+/// Query of `GET /tenants/:tenant_id/assignment-history`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssignmentHistoryQuery {
+    /// Maximum assignments returned, the latest ones
+    #[serde(default = "default_history_limit")]
+    pub limit: usize,
+}
+
+fn default_history_limit() -> usize {
+    50
+}
+
+/// Timeline of a tenant's assignments, oldest first.
+///
+/// Each placement, manual move, rebalance and worker failover shows up with
+/// its reason and version. Versions start over at 1 when a tenant is placed
+/// again after losing its assignment, e.g. when its worker is evicted.
+pub async fn get_assignment_history(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<AssignmentHistoryQuery>,
+) -> ApiResult<Vec<TenantAssignment>> {
+    let history = state
+        .load_balancer
+        .assignment_history(tenant_id, query.limit.clamp(1, 1000))
+        .await?;
+    Ok(Json(history))
+}
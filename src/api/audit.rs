@@ -0,0 +1,180 @@
+//! Audit log of mutating API calls
+//!
+//! [`record_mutations`] runs inside token authentication on every matched
+//! route and records each request other than GET, HEAD and OPTIONS with the
+//! token used, the route, the tenant and worker it targets, a hash of its
+//! body and the response status. Writing the entry never affects the
+//! response.
+
+use axum::body::{to_bytes, Body};
+use axum::extract::{MatchedPath, Query, Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::auth::ApiActor;
+use crate::api::error::{ApiError, ApiResult};
+use crate::api::pagination::{Page, PageQuery};
+use crate::api::ApiState;
+use crate::models::AuditEntry;
+use crate::services::{AuditFilter, AuditLog, AuditRecord, ServiceError};
+
+/// Largest body buffered for hashing; matches the largest route body limit
+const MAX_AUDITED_BODY: usize = super::IMPORT_BODY_LIMIT;
+
+/// Filters of `GET /audit`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuditQuery {
+    /// Only calls at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only calls before this time
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only calls targeting this tenant
+    pub tenant_id: Option<Uuid>,
+
+    /// Only calls targeting this worker
+    pub worker_id: Option<String>,
+
+    /// Only calls made with this token id
+    pub actor: Option<String>,
+
+    /// Only calls to this route, e.g. `POST /v1/tenants/:tenant_id/suspend`
+    pub action: Option<String>,
+}
+
+/// List a page of audited calls, newest first
+pub async fn list_audit(
+    State(state): State<ApiState>,
+    Query(page): Query<PageQuery>,
+    Query(query): Query<AuditQuery>,
+) -> ApiResult<Page<AuditEntry>> {
+    let filter = AuditFilter {
+        since: query.since,
+        until: query.until,
+        tenant_id: query.tenant_id,
+        worker_id: query.worker_id,
+        actor: query.actor,
+        action: query.action,
+    };
+
+    let audit_log = AuditLog::new(state.db.clone());
+    let entries = audit_log.list(&filter, page.limit(), page.offset).await?;
+    let total = audit_log.count(&filter).await?;
+    Ok(Json(page.page(entries, total)))
+}
+
+/// Record mutating requests in the audit log once they are answered
+pub async fn record_mutations(
+    State(audit_log): State<Arc<AuditLog>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().clone();
+    if matches!(method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        return next.run(request).await;
+    }
+
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let path = request.uri().path().to_string();
+    let actor = request
+        .extensions()
+        .get::<ApiActor>()
+        .map(|actor| actor.token_id.clone())
+        .unwrap_or_else(|| "anonymous".to_string());
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_AUDITED_BODY).await {
+        Ok(body) => body,
+        Err(_) => {
+            return ApiError(ServiceError::ResourceLimitExceeded(format!(
+                "request body exceeds {} bytes",
+                MAX_AUDITED_BODY
+            )))
+            .into_response()
+        }
+    };
+    let (tenant_id, worker_id) = targets(&route, &path, &body);
+    let payload_hash = (!body.is_empty()).then(|| hex::encode(Sha256::digest(&body)));
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+    audit_log.record_in_background(AuditRecord {
+        actor,
+        action: format!("{} {}", method, route),
+        path,
+        tenant_id,
+        worker_id,
+        payload_hash,
+        status_code: response.status().as_u16(),
+    });
+    response
+}
+
+/// Tenant and worker a request targets, from its path parameters or else a
+/// `worker_id` in its JSON body
+fn targets(route: &str, path: &str, body: &[u8]) -> (Option<Uuid>, Option<String>) {
+    let mut tenant_id = None;
+    let mut worker_id = None;
+    let resource = route.strip_prefix(super::API_PREFIX).unwrap_or(route);
+    for (template, segment) in route.split('/').zip(path.split('/')) {
+        match template {
+            ":tenant_id" => tenant_id = segment.parse().ok(),
+            ":id" if resource.starts_with("/workers/") => worker_id = Some(segment.to_string()),
+            _ => {}
+        }
+    }
+
+    if worker_id.is_none() {
+        worker_id = serde_json::from_slice::<serde_json::Value>(body)
+            .ok()
+            .and_then(|body| body.get("worker_id")?.as_str().map(str::to_string));
+    }
+    (tenant_id, worker_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_targets_come_from_path_then_body() {
+        let tenant_id = Uuid::new_v4();
+
+        assert_eq!(
+            targets(
+                "/tenants/:tenant_id/assign",
+                &format!("/tenants/{}/assign", tenant_id),
+                br#"{"worker_id": "worker-2"}"#,
+            ),
+            (Some(tenant_id), Some("worker-2".to_string()))
+        );
+        assert_eq!(
+            targets("/workers/:id/drain", "/workers/worker-1/drain", b""),
+            (None, Some("worker-1".to_string()))
+        );
+        assert_eq!(
+            targets("/v1/workers/:id/drain", "/v1/workers/worker-1/drain", b""),
+            (None, Some("worker-1".to_string()))
+        );
+        assert_eq!(
+            targets(
+                "/dead-letters/:id/requeue",
+                "/dead-letters/abc/requeue",
+                b""
+            ),
+            (None, None)
+        );
+    }
+}
@@ -0,0 +1,161 @@
+//! Bearer token authentication
+//!
+//! Tokens are configured as hex SHA-256 hashes, so the configuration never
+//! holds usable credentials. A presented token is hashed and compared against
+//! every configured hash in constant time. Mutating requests always need a
+//! token; read-only (GET and HEAD) requests are public when `public_read` is set.
+//! Authenticated requests carry an [`ApiActor`] naming the token for auditing.
+
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use subtle::ConstantTimeEq;
+use tracing::warn;
+
+use crate::api::error::ApiError;
+use crate::config::ApiConfig;
+use crate::services::ServiceError;
+
+/// Length of the hash prefix identifying a token
+const TOKEN_ID_LEN: usize = 12;
+
+/// Token an authenticated request was made with, added to its extensions
+#[derive(Debug, Clone)]
+pub struct ApiActor {
+    /// Prefix of the token's configured hash, safe to log
+    pub token_id: String,
+}
+
+/// Accepted tokens and access rules
+pub struct ApiAuth {
+    token_hashes: Vec<[u8; 32]>,
+    public_read: bool,
+}
+
+impl ApiAuth {
+    /// Build from the API configuration, skipping hashes that are not valid SHA-256 digests
+    pub fn from_config(config: &ApiConfig) -> Self {
+        let token_hashes: Vec<[u8; 32]> = config
+            .token_hashes()
+            .iter()
+            .filter_map(|hash| hex::decode(hash).ok()?.try_into().ok())
+            .collect();
+        if token_hashes.is_empty() {
+            warn!("No API tokens configured, mutating API endpoints reject every request");
+        }
+
+        Self {
+            token_hashes,
+            public_read: config.public_read,
+        }
+    }
+
+    /// Whether a request needs a token
+    fn requires_token(&self, method: &Method) -> bool {
+        !(self.public_read && (method == Method::GET || method == Method::HEAD))
+    }
+
+    /// Id of the configured token matching a presented one, checked against
+    /// every configured hash in constant time
+    fn token_id(&self, token: &str) -> Option<String> {
+        let digest = Sha256::digest(token.as_bytes());
+        let mut matched = None;
+        for hash in &self.token_hashes {
+            if bool::from(hash.as_slice().ct_eq(digest.as_slice())) {
+                matched = Some(hash);
+            }
+        }
+        matched.map(|hash| hex::encode(hash)[..TOKEN_ID_LEN].to_string())
+    }
+}
+
+/// Reject requests without a valid bearer token where one is required
+pub async fn require_token(
+    State(auth): State<Arc<ApiAuth>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if !auth.requires_token(request.method()) {
+        return next.run(request).await;
+    }
+
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    let reason = match token.map(|token| auth.token_id(token.trim())) {
+        Some(Some(token_id)) => {
+            request.extensions_mut().insert(ApiActor { token_id });
+            return next.run(request).await;
+        }
+        Some(None) => "invalid bearer token",
+        None => "missing bearer token",
+    };
+
+    let mut response = ApiError(ServiceError::Unauthorized(reason.to_string())).into_response();
+    response
+        .headers_mut()
+        .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn auth(tokens: &[&str], public_read: bool) -> ApiAuth {
+        let config = ApiConfig {
+            token_hashes: tokens
+                .iter()
+                .map(|token| hex::encode(Sha256::digest(token.as_bytes())))
+                .collect(),
+            token_hashes_env: "OZ_MONITOR_TEST_UNSET_TOKEN_HASHES".to_string(),
+            public_read,
+            ..Default::default()
+        };
+        ApiAuth::from_config(&config)
+    }
+
+    #[test]
+    fn test_accepts_configured_tokens_only() {
+        let auth = auth(&["first", "second"], false);
+
+        assert!(auth.token_id("first").is_some());
+        assert!(auth.token_id("second").is_some());
+        assert!(auth.token_id("third").is_none());
+        assert!(auth.token_id("").is_none());
+    }
+
+    #[test]
+    fn test_token_id_is_a_prefix_of_its_hash() {
+        let auth = auth(&["first", "second"], false);
+        let hash = hex::encode(Sha256::digest(b"second"));
+
+        assert_eq!(
+            auth.token_id("second"),
+            Some(hash[..TOKEN_ID_LEN].to_string())
+        );
+        assert_eq!(auth.token_id("third"), None);
+    }
+
+    #[test]
+    fn test_no_tokens_accepts_nothing() {
+        assert!(auth(&[], false).token_id("anything").is_none());
+    }
+
+    #[test]
+    fn test_public_read_only_opens_safe_methods() {
+        let public = auth(&["token"], true);
+        assert!(!public.requires_token(&Method::GET));
+        assert!(!public.requires_token(&Method::HEAD));
+        assert!(public.requires_token(&Method::POST));
+
+        let private = auth(&["token"], false);
+        assert!(private.requires_token(&Method::GET));
+    }
+}
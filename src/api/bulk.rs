@@ -0,0 +1,229 @@
+//! Bulk tenant endpoints
+//!
+//! Suspend, reassign or reload many tenants in one call. Each tenant is
+//! handled as by its single-tenant endpoint and one tenant failing does not
+//! stop the others: the response reports every tenant's result, with the
+//! error it would have got on its own. Workers in this process are reloaded
+//! once after all tenants are handled rather than once per tenant.
+
+use axum::extract::State;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+use uuid::Uuid;
+
+use crate::api::error::{ApiError, ApiResult};
+use crate::api::tenants::{self, AssignTenantResponse, ReloadTenantResponse};
+use crate::api::ApiState;
+use crate::services::{ErrorResponse, ServiceError};
+
+/// Most tenants a bulk request may name
+pub const MAX_BULK_TENANTS: usize = 1000;
+
+/// Body of `POST /tenants/bulk/suspend` and `POST /tenants/bulk/reload`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkTenantsRequest {
+    pub tenant_ids: Vec<Uuid>,
+}
+
+/// Tenant to move in `POST /tenants/bulk/reassign`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkAssignment {
+    pub tenant_id: Uuid,
+    pub worker_id: String,
+}
+
+/// Body of `POST /tenants/bulk/reassign`
+#[derive(Debug, Clone, Deserialize)]
+pub struct BulkReassignRequest {
+    pub assignments: Vec<BulkAssignment>,
+}
+
+/// Outcome for one tenant of a bulk request
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkResult<T> {
+    pub tenant_id: Uuid,
+
+    /// Status the single-tenant endpoint would have answered with
+    pub status: u16,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorResponse>,
+}
+
+/// Result of a bulk request, in the order the tenants were given
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkResponse<T> {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkResult<T>>,
+}
+
+impl<T> BulkResponse<T> {
+    fn new() -> Self {
+        Self {
+            succeeded: 0,
+            failed: 0,
+            results: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, tenant_id: Uuid, outcome: Result<T, ApiError>) {
+        let result = match outcome {
+            Ok(result) => {
+                self.succeeded += 1;
+                BulkResult {
+                    tenant_id,
+                    status: 200,
+                    result: Some(result),
+                    error: None,
+                }
+            }
+            Err(ApiError(e)) => {
+                self.failed += 1;
+                BulkResult {
+                    tenant_id,
+                    status: e.http_status(),
+                    result: None,
+                    error: Some(ErrorResponse::from(&e)),
+                }
+            }
+        };
+        self.results.push(result);
+    }
+}
+
+/// Suspended tenant of `POST /tenants/bulk/suspend`
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkSuspended {
+    /// Workers the tenant was taken off
+    pub worker_ids: Vec<String>,
+}
+
+/// Suspend tenants and take them off their workers
+pub async fn suspend_tenants(
+    State(state): State<ApiState>,
+    Json(request): Json<BulkTenantsRequest>,
+) -> ApiResult<BulkResponse<BulkSuspended>> {
+    let tenant_ids = distinct(request.tenant_ids, |tenant_id| *tenant_id)?;
+
+    let mut response = BulkResponse::new();
+    let mut affected_workers = BTreeSet::new();
+    for tenant_id in tenant_ids {
+        let outcome = tenants::suspend(&state, tenant_id).await.map(|suspended| {
+            affected_workers.extend(suspended.worker_ids.iter().cloned());
+            BulkSuspended {
+                worker_ids: suspended.worker_ids,
+            }
+        });
+        response.push(tenant_id, outcome);
+    }
+    reload_local_workers(&state, &affected_workers).await?;
+
+    Ok(Json(response))
+}
+
+/// Move tenants to the given workers
+pub async fn reassign_tenants(
+    State(state): State<ApiState>,
+    Json(request): Json<BulkReassignRequest>,
+) -> ApiResult<BulkResponse<AssignTenantResponse>> {
+    let assignments = distinct(request.assignments, |assignment| assignment.tenant_id)?;
+
+    let mut response = BulkResponse::new();
+    let mut affected_workers = BTreeSet::new();
+    for assignment in assignments {
+        let outcome = tenants::assign(&state, assignment.tenant_id, &assignment.worker_id)
+            .await
+            .map(|assigned| {
+                affected_workers.extend(assigned.worker_ids().cloned());
+                assigned
+            });
+        response.push(assignment.tenant_id, outcome);
+    }
+    reload_local_workers(&state, &affected_workers).await?;
+
+    Ok(Json(response))
+}
+
+/// Reload tenants' monitors on the workers owning them
+pub async fn reload_tenants(
+    State(state): State<ApiState>,
+    Json(request): Json<BulkTenantsRequest>,
+) -> ApiResult<BulkResponse<ReloadTenantResponse>> {
+    let tenant_ids = distinct(request.tenant_ids, |tenant_id| *tenant_id)?;
+
+    let mut response = BulkResponse::new();
+    for tenant_id in tenant_ids {
+        response.push(tenant_id, tenants::reload(&state, tenant_id).await);
+    }
+
+    Ok(Json(response))
+}
+
+/// Items of a bulk request with repeated tenants dropped, rejecting requests
+/// naming more than [`MAX_BULK_TENANTS`] tenants
+fn distinct<T>(items: Vec<T>, tenant_id: impl Fn(&T) -> Uuid) -> Result<Vec<T>, ApiError> {
+    let mut seen = HashSet::new();
+    let items: Vec<T> = items
+        .into_iter()
+        .filter(|item| seen.insert(tenant_id(item)))
+        .collect();
+    if items.len() > MAX_BULK_TENANTS {
+        return Err(ServiceError::ResourceLimitExceeded(format!(
+            "{} tenants requested, at most {} per request",
+            items.len(),
+            MAX_BULK_TENANTS
+        ))
+        .into());
+    }
+    Ok(items)
+}
+
+async fn reload_local_workers(
+    state: &ApiState,
+    worker_ids: &BTreeSet<String>,
+) -> Result<(), ApiError> {
+    for worker_id in worker_ids {
+        state.reload_local_worker(worker_id).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_count_partial_failures() {
+        let ok = Uuid::new_v4();
+        let missing = Uuid::new_v4();
+        let mut response = BulkResponse::new();
+        response.push(ok, Ok(()));
+        response.push(missing, Err(ServiceError::TenantNotFound(missing).into()));
+
+        assert_eq!(response.succeeded, 1);
+        assert_eq!(response.failed, 1);
+        assert_eq!(response.results[0].status, 200);
+        assert!(response.results[0].error.is_none());
+        assert_eq!(response.results[1].tenant_id, missing);
+        assert_eq!(response.results[1].status, 404);
+        assert_eq!(
+            response.results[1].error.as_ref().unwrap().code,
+            "TENANT_NOT_FOUND"
+        );
+    }
+
+    #[test]
+    fn test_distinct_drops_repeats_and_caps_requests() {
+        let tenant_id = Uuid::new_v4();
+        let tenant_ids = distinct(vec![tenant_id, tenant_id], |id| *id).unwrap();
+        assert_eq!(tenant_ids, vec![tenant_id]);
+
+        let too_many: Vec<Uuid> = (0..=MAX_BULK_TENANTS).map(|_| Uuid::new_v4()).collect();
+        assert!(distinct(too_many, |id| *id).is_err());
+    }
+}
@@ -0,0 +1,13 @@
+//! Capacity endpoint
+
+use axum::extract::State;
+use axum::Json;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::CapacityReport;
+
+/// Report worker utilization and headroom with a suggested worker count
+pub async fn get_capacity(State(state): State<ApiState>) -> ApiResult<CapacityReport> {
+    Ok(Json(state.load_balancer.capacity().await))
+}
@@ -0,0 +1,106 @@
+//! Tenant block checkpoint endpoints
+
+use axum::extract::{Path, State};
+use axum::Json;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::{TenantCheckpoint, TenantWatermark};
+use crate::repositories::RepositoryError;
+use crate::services::{CheckpointLedger, CheckpointRewind, ServiceError};
+
+/// Response of `POST /tenants/{tenant_id}/checkpoints/{network_slug}/rewind`
+#[derive(Debug, Clone, Serialize)]
+pub struct RewindResponse {
+    pub checkpoint: TenantCheckpoint,
+
+    /// Blocks re-broadcast to the tenant's workers
+    pub blocks_replayed: usize,
+}
+
+/// List a tenant's checkpoint on every network it processed
+pub async fn list_checkpoints(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<Vec<TenantCheckpoint>> {
+    let checkpoints = CheckpointLedger::new(state.db.clone())
+        .list(tenant_id)
+        .await?;
+    Ok(Json(checkpoints))
+}
+
+/// List the block up to which a tenant's monitors have evaluated every block,
+/// per network, with how far it is behind the network's latest block
+pub async fn list_watermarks(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<Vec<TenantWatermark>> {
+    let checkpoints = CheckpointLedger::new(state.db.clone())
+        .list(tenant_id)
+        .await?;
+
+    let mut watermarks = Vec::new();
+    for checkpoint in checkpoints {
+        let Some(watermark_block) = checkpoint.watermark_block else {
+            continue;
+        };
+        let watermark_block = watermark_block.max(0) as u64;
+        let latest_block = state
+            .block_watcher
+            .cached_latest_block(&checkpoint.network_slug)
+            .await;
+        watermarks.push(TenantWatermark {
+            network_slug: checkpoint.network_slug,
+            watermark_block,
+            latest_block,
+            lag: latest_block.map(|latest| latest.saturating_sub(watermark_block)),
+            updated_at: checkpoint.updated_at,
+        });
+    }
+    Ok(Json(watermarks))
+}
+
+/// Move a tenant's checkpoint on a network back and re-evaluate the blocks after it.
+///
+/// The body is `{"blocks": n}` or `{"to_block": n}`. The blocks between the
+/// new and the old checkpoint are replayed for this tenant only, so the
+/// network must be watched by the block watcher of this process.
+pub async fn rewind_checkpoint(
+    State(state): State<ApiState>,
+    Path((tenant_id, network_slug)): Path<(Uuid, String)>,
+    Json(rewind): Json<CheckpointRewind>,
+) -> ApiResult<RewindResponse> {
+    let ledger = CheckpointLedger::new(state.db.clone());
+    let current = ledger
+        .get(tenant_id, &network_slug)
+        .await?
+        .ok_or_else(|| RepositoryError::NotFound {
+            entity_type: "checkpoint".to_string(),
+            id: format!("{}/{}", tenant_id, network_slug),
+        })
+        .map_err(ServiceError::from)?;
+    let last_processed = current.last_processed_block.max(0) as u64;
+    let target = rewind.target(last_processed)?;
+
+    let blocks_replayed = state
+        .block_watcher
+        .replay_blocks(
+            state.client_pool.as_ref(),
+            &network_slug,
+            target + 1,
+            last_processed,
+            &[tenant_id],
+        )
+        .await?;
+
+    let checkpoint = ledger
+        .rewind(tenant_id, &network_slug, target)
+        .await?
+        .unwrap_or(current);
+    Ok(Json(RewindResponse {
+        checkpoint,
+        blocks_replayed,
+    }))
+}
@@ -0,0 +1,13 @@
+//! RPC client endpoints
+
+use axum::extract::State;
+use axum::Json;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::services::ClientReuseStats;
+
+/// List the RPC clients of this process with how often each was reused
+pub async fn list_clients(State(state): State<ApiState>) -> ApiResult<Vec<ClientReuseStats>> {
+    Ok(Json(state.client_pool.client_stats()))
+}
@@ -0,0 +1,103 @@
+//! Cross-origin access
+//!
+//! With `cors_enabled`, browsers on `cors_allowed_origins` may call the API
+//! with the standard methods and an `Authorization` header. Without it,
+//! cross-origin preflights are rejected, so browsers never send the request.
+
+use axum::extract::Request;
+use axum::http::{header, HeaderValue, Method, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::config::ApiConfig;
+
+/// CORS layer for the configured origins
+pub fn cors_layer(config: &ApiConfig) -> CorsLayer {
+    let origins = if config.cors_allowed_origins.iter().any(|o| o == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            config
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|origin| HeaderValue::from_str(origin).ok()),
+        )
+    };
+
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods([
+            Method::GET,
+            Method::HEAD,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+        ])
+        .allow_headers([header::AUTHORIZATION, header::CONTENT_TYPE])
+        .expose_headers([header::RETRY_AFTER, header::WWW_AUTHENTICATE])
+        .max_age(config.cors_max_age)
+}
+
+/// Reject CORS preflights with 403 while CORS is disabled
+pub async fn reject_preflight(request: Request, next: Next) -> Response {
+    if is_preflight(&request) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    next.run(request).await
+}
+
+fn is_preflight(request: &Request) -> bool {
+    request.method() == Method::OPTIONS
+        && request.headers().contains_key(header::ORIGIN)
+        && request
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+
+    #[test]
+    fn test_preflight_needs_origin_and_requested_method() {
+        let preflight = Request::builder()
+            .method(Method::OPTIONS)
+            .header(header::ORIGIN, "https://dashboard.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+            .body(Body::empty())
+            .unwrap();
+        assert!(is_preflight(&preflight));
+
+        let options = Request::builder()
+            .method(Method::OPTIONS)
+            .body(Body::empty())
+            .unwrap();
+        assert!(!is_preflight(&options));
+    }
+
+    #[test]
+    fn test_origin_validation() {
+        let with_origins = |origins: &[&str]| ApiConfig {
+            cors_allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+            ..Default::default()
+        };
+
+        assert!(with_origins(&["*"]).validate().is_ok());
+        assert!(
+            with_origins(&["https://dashboard.example.com", "http://localhost:5173"])
+                .validate()
+                .is_ok()
+        );
+        assert!(with_origins(&[]).validate().is_err());
+        assert!(with_origins(&["*", "https://dashboard.example.com"])
+            .validate()
+            .is_err());
+        assert!(with_origins(&["dashboard.example.com"]).validate().is_err());
+        assert!(with_origins(&["https://dashboard.example.com/"])
+            .validate()
+            .is_err());
+    }
+}
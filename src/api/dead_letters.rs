@@ -0,0 +1,64 @@
+//! Dead letter endpoints
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::{DeadLetter, DeadLetterStatus};
+use crate::repositories::RepositoryError;
+use crate::services::{DeadLetterStore, ServiceError};
+
+/// Query of `GET /tenants/{tenant_id}/dead-letters`
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeadLetterQuery {
+    /// Only dead letters in this status
+    pub status: Option<DeadLetterStatus>,
+
+    /// Maximum dead letters returned, newest first
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+/// List a tenant's failed notifications
+pub async fn list_dead_letters(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<DeadLetterQuery>,
+) -> ApiResult<Vec<DeadLetter>> {
+    let dead_letters = DeadLetterStore::new(state.db.clone())
+        .list(tenant_id, query.status, query.limit.clamp(1, 1000))
+        .await?;
+    Ok(Json(dead_letters))
+}
+
+/// Queue a failed notification for redelivery by the worker owning its tenant
+pub async fn requeue_dead_letter(
+    State(state): State<ApiState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<DeadLetter> {
+    let store = DeadLetterStore::new(state.db.clone());
+    if let Some(dead_letter) = store.requeue(id).await? {
+        return Ok(Json(dead_letter));
+    }
+
+    let error = match store.get(id).await? {
+        Some(dead_letter) => ServiceError::InvalidState(format!(
+            "Dead letter {} is {}, only failed dead letters can be requeued",
+            id,
+            dead_letter.status.as_str()
+        )),
+        None => RepositoryError::NotFound {
+            entity_type: "dead letter".to_string(),
+            id: id.to_string(),
+        }
+        .into(),
+    };
+    Err(error.into())
+}
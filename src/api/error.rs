@@ -0,0 +1,34 @@
+//! API error responses
+
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use crate::services::{ErrorResponse, ServiceError};
+
+/// Error returned by API handlers, rendered as an [`ErrorResponse`]
+#[derive(Debug)]
+pub struct ApiError(pub ServiceError);
+
+impl From<ServiceError> for ApiError {
+    fn from(err: ServiceError) -> Self {
+        Self(err)
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(err: anyhow::Error) -> Self {
+        Self(err.into())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status =
+            StatusCode::from_u16(self.0.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(ErrorResponse::from(&self.0))).into_response()
+    }
+}
+
+/// Result type of API handlers
+pub type ApiResult<T> = Result<Json<T>, ApiError>;
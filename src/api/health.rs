@@ -0,0 +1,163 @@
+//! Liveness and readiness probes
+//!
+//! Served on their own listener in every service mode, since workers and block
+//! watchers do not run the management API. Liveness only confirms the process
+//! answers requests; readiness checks what the service mode depends on.
+
+use anyhow::{Context, Result};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use serde::Serialize;
+use sqlx::PgPool;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use crate::config::{HealthConfig, ServiceMode};
+use crate::services::worker_pool::WorkerStatus;
+use crate::services::{BlockCacheService, MonitorWorkerPool, SharedBlockWatcher};
+
+/// How long a single dependency check may take before it counts as failed
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Services inspected by the readiness probe
+#[derive(Clone)]
+pub struct HealthState {
+    pub mode: ServiceMode,
+    pub db: Arc<PgPool>,
+    pub cache: Arc<BlockCacheService>,
+    pub worker_pool: Arc<MonitorWorkerPool>,
+    pub block_watcher: Arc<SharedBlockWatcher>,
+}
+
+/// Outcome of one readiness check
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub name: &'static str,
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Body of `GET /readyz`
+#[derive(Debug, Clone, Serialize)]
+pub struct Readiness {
+    pub ready: bool,
+    pub checks: Vec<HealthCheck>,
+}
+
+impl HealthCheck {
+    fn from_result(name: &'static str, result: Result<()>) -> Self {
+        Self {
+            name,
+            ok: result.is_ok(),
+            detail: result.err().map(|e| format!("{:#}", e)),
+        }
+    }
+}
+
+/// Build the probe router
+pub fn router(state: HealthState) -> Router {
+    Router::new()
+        .route("/healthz", get(liveness))
+        .route("/readyz", get(readiness))
+        .with_state(state)
+}
+
+/// Serve the probes until `shutdown` completes
+pub async fn serve(
+    config: &HealthConfig,
+    state: HealthState,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(config.socket_addr())
+        .await
+        .with_context(|| format!("Failed to bind health server to {}", config.socket_addr()))?;
+    info!("Health server listening on {}", config.socket_addr());
+
+    axum::serve(listener, router(state))
+        .with_graceful_shutdown(shutdown)
+        .await
+        .context("Health server failed")
+}
+
+/// The process is up and serving requests
+async fn liveness() -> &'static str {
+    "ok"
+}
+
+/// Check the dependencies of the running service mode
+async fn readiness(State(state): State<HealthState>) -> (StatusCode, Json<Readiness>) {
+    let mode = &state.mode;
+    let mut checks = Vec::new();
+
+    if matches!(
+        mode,
+        ServiceMode::Worker | ServiceMode::Api | ServiceMode::All
+    ) {
+        checks.push(HealthCheck::from_result(
+            "database",
+            check_database(&state.db).await,
+        ));
+        checks.push(HealthCheck::from_result(
+            "redis",
+            check_redis(&state.cache).await,
+        ));
+    }
+    if matches!(mode, ServiceMode::Worker | ServiceMode::All) {
+        checks.push(HealthCheck::from_result(
+            "workers",
+            check_workers(&state.worker_pool).await,
+        ));
+    }
+    if matches!(mode, ServiceMode::BlockWatcher | ServiceMode::All) {
+        checks.push(HealthCheck::from_result(
+            "block_watcher",
+            check_block_watcher(&state.block_watcher).await,
+        ));
+    }
+
+    let ready = checks.iter().all(|check| check.ok);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(Readiness { ready, checks }))
+}
+
+async fn check_database(db: &PgPool) -> Result<()> {
+    tokio::time::timeout(CHECK_TIMEOUT, sqlx::query("SELECT 1").execute(db))
+        .await
+        .context("Timed out")??;
+    Ok(())
+}
+
+async fn check_redis(cache: &BlockCacheService) -> Result<()> {
+    tokio::time::timeout(CHECK_TIMEOUT, cache.ping())
+        .await
+        .context("Timed out")?
+}
+
+async fn check_workers(worker_pool: &MonitorWorkerPool) -> Result<()> {
+    let workers = worker_pool.list_workers().await;
+    if workers.iter().any(|(_, status, _)| {
+        matches!(status.status, WorkerStatus::Running | WorkerStatus::Standby)
+    }) {
+        return Ok(());
+    }
+    anyhow::bail!(
+        "None of {} workers is running or standing by",
+        workers.len()
+    )
+}
+
+async fn check_block_watcher(block_watcher: &SharedBlockWatcher) -> Result<()> {
+    if block_watcher.running_watchers().await > 0 {
+        return Ok(());
+    }
+    anyhow::bail!("No network watcher task is alive")
+}
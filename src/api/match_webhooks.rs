@@ -0,0 +1,65 @@
+//! Tenant match webhook endpoints
+//!
+//! Workers pick up a changed webhook within a minute; the secret is never
+//! returned.
+
+use axum::extract::{Path, State};
+use axum::Json;
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::{MatchWebhook, MatchWebhookInput};
+use crate::repositories::RepositoryError;
+use crate::services::{MatchWebhookStore, ServiceError, TenantStore};
+
+/// Get a tenant's match webhook
+pub async fn get_match_webhook(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<MatchWebhook> {
+    let webhook = MatchWebhookStore::new(state.db.clone())
+        .get(tenant_id)
+        .await?
+        .ok_or_else(|| webhook_not_found(tenant_id))?;
+    Ok(Json(webhook))
+}
+
+/// Register or replace the webhook receiving every match of a tenant.
+///
+/// The url must be http or https and the secret at least 16 bytes.
+pub async fn put_match_webhook(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Json(input): Json<MatchWebhookInput>,
+) -> ApiResult<MatchWebhook> {
+    TenantStore::new(state.db.clone())
+        .get(tenant_id)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+
+    let webhook = MatchWebhookStore::new(state.db.clone())
+        .upsert(tenant_id, &input)
+        .await?;
+    Ok(Json(webhook))
+}
+
+/// Remove a tenant's match webhook, returning it
+pub async fn delete_match_webhook(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<MatchWebhook> {
+    let webhook = MatchWebhookStore::new(state.db.clone())
+        .delete(tenant_id)
+        .await?
+        .ok_or_else(|| webhook_not_found(tenant_id))?;
+    Ok(Json(webhook))
+}
+
+fn webhook_not_found(tenant_id: Uuid) -> ServiceError {
+    RepositoryError::NotFound {
+        entity_type: "match webhook".to_string(),
+        id: tenant_id.to_string(),
+    }
+    .into()
+}
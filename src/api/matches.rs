@@ -0,0 +1,63 @@
+//! Match stream endpoint
+
+use axum::extract::{Path, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::stream::{self, Stream};
+use std::convert::Infallible;
+use std::time::Duration;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::api::error::ApiError;
+use crate::api::ApiState;
+
+/// Interval of heartbeat comments keeping idle streams open through proxies
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How soon clients should reconnect after the server shuts down
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Stream a tenant's monitor matches as server-sent `match` events.
+///
+/// The Redis subscription is dropped as soon as the client disconnects. On
+/// server shutdown the stream ends with a `shutdown` event asking the client
+/// to reconnect, which reaches another replica or this one once restarted.
+pub async fn stream_matches(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, ApiError> {
+    let receiver = state.match_feed.subscribe(tenant_id).await?;
+    let shutdown = state.shutdown.clone();
+
+    let events = stream::unfold(Some(receiver), move |receiver| {
+        let shutdown = shutdown.clone();
+        async move {
+            let mut receiver = receiver?;
+            loop {
+                let event = tokio::select! {
+                    event = receiver.recv() => event?,
+                    _ = shutdown.wait() => {
+                        let sse_event = Event::default()
+                            .event("shutdown")
+                            .data("server shutting down")
+                            .retry(RECONNECT_DELAY);
+                        return Some((Ok(sse_event), None));
+                    }
+                };
+                match Event::default().event("match").json_data(&event) {
+                    Ok(sse_event) => return Some((Ok(sse_event), Some(receiver))),
+                    Err(e) => warn!(
+                        "Failed to encode match of monitor {}: {}",
+                        event.monitor_name, e
+                    ),
+                }
+            }
+        }
+    });
+
+    Ok(Sse::new(events).keep_alive(
+        KeepAlive::new()
+            .interval(HEARTBEAT_INTERVAL)
+            .text("heartbeat"),
+    ))
+}
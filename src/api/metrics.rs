@@ -0,0 +1,38 @@
+//! Prometheus metrics endpoint
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+
+use crate::api::ApiState;
+use crate::services::metrics;
+
+/// Render all metrics in the Prometheus text format.
+///
+/// Gauges are refreshed from the load balancer and block watcher of this
+/// process, so a scrape never touches the database.
+pub async fn render_metrics(State(state): State<ApiState>) -> impl IntoResponse {
+    metrics::observe_workers(&state.load_balancer.worker_metrics().await);
+    metrics::observe_tenants(&state.load_balancer.tenant_metrics().await);
+
+    let lags: Vec<u64> = state
+        .block_watcher
+        .network_statuses()
+        .await
+        .into_iter()
+        .filter_map(|status| status.lag)
+        .collect();
+
+    let mut system = state.load_balancer.system_metrics().await;
+    system.cache_hit_rate = metrics::cache_hit_rate();
+    if !lags.is_empty() {
+        system.avg_block_lag = lags.iter().sum::<u64>() as f64 / lags.len() as f64;
+    }
+    system.calculate_health_score();
+    metrics::observe_system(&system);
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::gather(),
+    )
+}
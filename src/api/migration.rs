@@ -0,0 +1,65 @@
+//! Tenant export and import endpoints
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::{Duration, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::{MigrationPlan, TenantSnapshot};
+use crate::services::{ServiceError, TenantMigrationService};
+
+/// Query of `GET /tenants/{tenant_id}/export`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExportQuery {
+    /// Days of recorded matches included; 0 exports configuration only
+    #[serde(default = "default_match_days")]
+    pub match_days: i64,
+}
+
+fn default_match_days() -> i64 {
+    7
+}
+
+/// Query of `POST /tenants/import`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ImportQuery {
+    /// Only compute the plan without writing it
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Import under this tenant id instead of the snapshot's
+    pub target_tenant_id: Option<Uuid>,
+}
+
+/// Export a tenant's configuration and recent matches
+pub async fn export_tenant(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<ExportQuery>,
+) -> ApiResult<TenantSnapshot> {
+    let matches_since =
+        (query.match_days > 0).then(|| Utc::now() - Duration::days(query.match_days.min(365)));
+    let snapshot = TenantMigrationService::new(state.db.clone())
+        .export(tenant_id, matches_since)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+    Ok(Json(snapshot))
+}
+
+/// Import a tenant snapshot exported from another orchestrator, returning the plan.
+///
+/// Networks of chain types not enabled here are rejected with 422.
+pub async fn import_tenant(
+    State(state): State<ApiState>,
+    Query(query): Query<ImportQuery>,
+    Json(snapshot): Json<TenantSnapshot>,
+) -> ApiResult<MigrationPlan> {
+    let plan = TenantMigrationService::new(state.db.clone())
+        .with_chain_support(state.chains.as_ref().clone())
+        .import(&snapshot, query.target_tenant_id, query.dry_run)
+        .await?;
+    Ok(Json(plan))
+}
@@ -0,0 +1,288 @@
+//! Management API
+//!
+//! HTTP server exposing live orchestrator state as JSON. Handlers read the
+//! worker pool, load balancer and block watcher of the running process;
+//! workers running in other processes are visible through the load
+//! balancer's assignments.
+
+pub mod anomalies;
+pub mod assignments;
+pub mod audit;
+pub mod auth;
+pub mod bulk;
+pub mod capacity;
+pub mod checkpoints;
+pub mod clients;
+pub mod cors;
+pub mod dead_letters;
+pub mod error;
+pub mod health;
+pub mod match_webhooks;
+pub mod matches;
+pub mod metrics;
+pub mod migration;
+pub mod monitors;
+pub mod networks;
+pub mod pagination;
+pub mod rate_limit;
+pub mod rebalance;
+pub mod rpc_costs;
+pub mod scripts;
+pub mod tenants;
+pub mod tls;
+pub mod version;
+pub mod workers;
+
+use anyhow::{Context, Result};
+use axum::extract::DefaultBodyLimit;
+use axum::middleware;
+use axum::routing::{get, post};
+use axum::Router;
+use sqlx::PgPool;
+use std::future::IntoFuture;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tower_http::trace::TraceLayer;
+use tracing::{info, warn};
+
+use crate::config::{ApiConfig, ServiceMode};
+use crate::models::WorkerAssignment;
+use crate::services::{
+    AuditLog, CachedClientPool, ChainSupport, LoadBalancer, MatchFeed, MonitorWorkerPool,
+    NetworkSettingsResolver, RpcCostTracker, SharedBlockWatcher, ShutdownSignal,
+    TriggerScriptStore,
+};
+
+pub use error::{ApiError, ApiResult};
+
+/// Services shared by all API handlers
+#[derive(Clone)]
+pub struct ApiState {
+    pub worker_pool: Arc<MonitorWorkerPool>,
+    pub load_balancer: Arc<LoadBalancer>,
+    pub block_watcher: Arc<SharedBlockWatcher>,
+    pub client_pool: Arc<CachedClientPool>,
+    pub db: Arc<PgPool>,
+    pub match_feed: Arc<MatchFeed>,
+    /// RPC cost attribution; None if disabled
+    pub rpc_costs: Option<Arc<RpcCostTracker>>,
+    /// Chain types networks may use
+    pub chains: Arc<ChainSupport>,
+    /// Process shutdown, ending streaming responses so the server can drain
+    pub shutdown: ShutdownSignal,
+    /// Tenants' trigger condition scripts, limited to `api.max_script_size`
+    pub trigger_scripts: Arc<TriggerScriptStore>,
+    /// Service mode of this process
+    pub mode: ServiceMode,
+    /// Resolves networks' settings after all override layers
+    pub network_settings: Arc<NetworkSettingsResolver>,
+}
+
+impl ApiState {
+    /// Reload a worker's tenants from the load balancer if it runs in this process.
+    ///
+    /// Workers in other processes pick up changes through the control channel.
+    pub async fn reload_local_worker(&self, worker_id: &str) -> Result<(), ApiError> {
+        if self
+            .worker_pool
+            .get_worker_status(worker_id)
+            .await
+            .is_none()
+        {
+            return Ok(());
+        }
+
+        let mut assignment = WorkerAssignment::new(worker_id.to_string());
+        assignment.tenant_ids = self.load_balancer.get_worker_assignments(worker_id).await?;
+        assignment.shards = self.load_balancer.get_worker_shards(worker_id).await;
+        if let Err(e) = self
+            .worker_pool
+            .reassign_tenants(worker_id, assignment.processed_tenant_ids())
+            .await
+        {
+            warn!("Failed to reload tenants of worker {}: {}", worker_id, e);
+        }
+        Ok(())
+    }
+}
+
+/// Prefix of every management API route but `/version`
+pub const API_PREFIX: &str = "/v1";
+
+/// Largest accepted tenant snapshot; exports with matches exceed axum's 2 MB default
+const IMPORT_BODY_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Build the API router, with the management routes under [`API_PREFIX`]
+pub fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/version", get(version::get_version))
+        .nest(API_PREFIX, v1_routes())
+        .with_state(state)
+}
+
+/// Routes of the first version of the management API
+fn v1_routes() -> Router<ApiState> {
+    Router::new()
+        .route("/workers", get(workers::list_workers))
+        .route("/workers/:id", get(workers::get_worker))
+        .route("/workers/:id/drain", post(workers::drain_worker))
+        .route("/workers/:id/drain-status", get(workers::get_drain_status))
+        .route("/capacity", get(capacity::get_capacity))
+        .route("/clients", get(clients::list_clients))
+        .route("/metrics", get(metrics::render_metrics))
+        .route("/monitors", get(monitors::list_monitors))
+        .route("/networks", get(networks::list_networks))
+        .route("/networks/:slug/replay", post(networks::replay_blocks))
+        .route("/networks/:slug/settings", get(networks::get_settings))
+        .route(
+            "/dead-letters/:id/requeue",
+            post(dead_letters::requeue_dead_letter),
+        )
+        .route("/anomalies", get(anomalies::list_anomalies))
+        .route("/assignments", get(assignments::list_assignments))
+        .route("/audit", get(audit::list_audit))
+        .route("/rebalance", post(rebalance::rebalance))
+        .route("/rpc-costs", get(rpc_costs::list_rpc_costs))
+        .route("/tenants", get(tenants::list_tenants))
+        .route(
+            "/tenants/import",
+            post(migration::import_tenant).layer(DefaultBodyLimit::max(IMPORT_BODY_LIMIT)),
+        )
+        .route("/tenants/bulk/suspend", post(bulk::suspend_tenants))
+        .route("/tenants/bulk/reassign", post(bulk::reassign_tenants))
+        .route("/tenants/bulk/reload", post(bulk::reload_tenants))
+        .route("/tenants/:tenant_id/export", get(migration::export_tenant))
+        .route(
+            "/tenants/:tenant_id/assignment-history",
+            get(assignments::get_assignment_history),
+        )
+        .route("/tenants/:tenant_id/suspend", post(tenants::suspend_tenant))
+        .route(
+            "/tenants/:tenant_id/activate",
+            post(tenants::activate_tenant),
+        )
+        .route("/tenants/:tenant_id/reload", post(tenants::reload_tenant))
+        .route("/tenants/:tenant_id/pause", post(tenants::pause_tenant))
+        .route("/tenants/:tenant_id/resume", post(tenants::resume_tenant))
+        .route(
+            "/tenants/:tenant_id/metrics",
+            get(tenants::get_tenant_metrics),
+        )
+        .route(
+            "/tenants/:tenant_id/coverage",
+            get(tenants::get_tenant_coverage),
+        )
+        .route(
+            "/tenants/:tenant_id/throttle/lift",
+            post(anomalies::lift_throttle),
+        )
+        .route(
+            "/tenants/:tenant_id/checkpoints",
+            get(checkpoints::list_checkpoints),
+        )
+        .route(
+            "/tenants/:tenant_id/watermarks",
+            get(checkpoints::list_watermarks),
+        )
+        .route(
+            "/tenants/:tenant_id/checkpoints/:network_slug/rewind",
+            post(checkpoints::rewind_checkpoint),
+        )
+        .route(
+            "/tenants/:tenant_id/rpc-costs",
+            get(rpc_costs::get_tenant_rpc_cost),
+        )
+        .route(
+            "/tenants/:tenant_id/dead-letters",
+            get(dead_letters::list_dead_letters),
+        )
+        .route(
+            "/tenants/:tenant_id/matches/stream",
+            get(matches::stream_matches),
+        )
+        .route("/tenants/:tenant_id/assign", post(tenants::assign_tenant))
+        .route(
+            "/tenants/:tenant_id/match-webhook",
+            get(match_webhooks::get_match_webhook)
+                .put(match_webhooks::put_match_webhook)
+                .delete(match_webhooks::delete_match_webhook),
+        )
+        .route(
+            "/tenants/:tenant_id/scripts/:name",
+            get(scripts::get_script)
+                .put(scripts::put_script)
+                .delete(scripts::delete_script),
+        )
+        .route(
+            "/tenants/:tenant_id/triggers/:trigger_name/test",
+            post(tenants::test_trigger),
+        )
+}
+
+/// Serve the API until the state's shutdown signal is triggered, over TLS if
+/// a certificate and key are configured.
+///
+/// Once it is, the listener stops accepting connections and in-flight
+/// requests get `config.shutdown_timeout` to complete; streaming responses end
+/// on their own. Connections still open after the timeout are dropped.
+pub async fn serve(config: &ApiConfig, state: ApiState) -> Result<()> {
+    let shutdown = state.shutdown.clone();
+    let audit_log = Arc::new(AuditLog::new(state.db.clone()));
+    let auth = Arc::new(auth::ApiAuth::from_config(config));
+    let rate_limit = Arc::new(rate_limit::ApiRateLimit::from_config(config));
+    // Rate limiting runs first so failed token guesses count against the client,
+    // and auditing runs after authentication to see the token used
+    let mut app = router(state)
+        .route_layer(middleware::from_fn_with_state(
+            audit_log,
+            audit::record_mutations,
+        ))
+        .layer(middleware::from_fn_with_state(auth, auth::require_token))
+        .layer(middleware::from_fn_with_state(
+            rate_limit,
+            rate_limit::limit_rate,
+        ))
+        .layer(TraceLayer::new_for_http());
+    // CORS runs outermost so preflights are answered without a token
+    app = if config.cors_enabled {
+        app.layer(cors::cors_layer(config))
+    } else {
+        app.layer(middleware::from_fn(cors::reject_preflight))
+    };
+
+    if let Some((cert_path, key_path)) = config.tls_paths() {
+        return tls::serve(config, app, shutdown, cert_path, key_path).await;
+    }
+
+    let listener = tokio::net::TcpListener::bind(config.socket_addr())
+        .await
+        .with_context(|| format!("Failed to bind API server to {}", config.socket_addr()))?;
+    info!("API server listening on {}", config.socket_addr());
+
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown({
+        let shutdown = shutdown.clone();
+        async move {
+            shutdown.wait().await;
+            info!("API server draining connections");
+        }
+    });
+    let drain_deadline = async {
+        shutdown.wait().await;
+        tokio::time::sleep(config.shutdown_timeout).await;
+    };
+
+    tokio::select! {
+        result = server.into_future() => result.context("API server failed"),
+        _ = drain_deadline => {
+            warn!(
+                "API requests still running after {:?}, closing their connections",
+                config.shutdown_timeout
+            );
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,47 @@
+//! Platform-wide monitor listing
+
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::pagination::{Page, PageQuery};
+use crate::api::ApiState;
+use crate::models::MonitorSummary;
+use crate::services::{MonitorFilter, MonitorStore};
+
+/// Filters of `GET /monitors`
+#[derive(Debug, Clone, Deserialize)]
+pub struct MonitorQuery {
+    /// Only monitors on this network
+    pub network: Option<String>,
+
+    /// Only active or only inactive monitors
+    pub active: Option<bool>,
+
+    /// Only monitors of this tenant
+    pub tenant: Option<Uuid>,
+
+    /// Only monitors watching this contract address
+    pub address: Option<String>,
+}
+
+/// List a page of monitors across all tenants
+pub async fn list_monitors(
+    State(state): State<ApiState>,
+    Query(page): Query<PageQuery>,
+    Query(query): Query<MonitorQuery>,
+) -> ApiResult<Page<MonitorSummary>> {
+    let filter = MonitorFilter {
+        network_slug: query.network,
+        active: query.active,
+        tenant_id: query.tenant,
+        address: query.address,
+    };
+
+    let store = MonitorStore::new(state.db.clone());
+    let monitors = store.list(&filter, page.limit(), page.offset).await?;
+    let total = store.count(&filter).await?;
+    Ok(Json(page.page(monitors, total)))
+}
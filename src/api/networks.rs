@@ -0,0 +1,126 @@
+//! Network endpoints
+
+use axum::extract::{Path, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::services::shared_block_watcher::NetworkWatcherStatus;
+use crate::services::EffectiveNetworkSettings;
+
+/// Network as listed by `GET /networks`
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkSummary {
+    pub network_slug: String,
+
+    /// Active tenants with the network configured
+    pub tenant_count: usize,
+
+    /// Latest block in the shared block cache; None if no watcher polled the network recently
+    pub latest_block: Option<u64>,
+
+    /// Block watcher state in this process; None if the network is not watched here
+    pub watcher: Option<NetworkWatcherStatus>,
+}
+
+/// Body of `POST /networks/{slug}/replay`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplayRequest {
+    pub from_block: u64,
+    /// Last block replayed, inclusive
+    pub to_block: u64,
+}
+
+/// Response of `POST /networks/{slug}/replay`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayResponse {
+    pub network_slug: String,
+    /// Blocks re-broadcast to workers
+    pub blocks_replayed: usize,
+}
+
+/// List tenant networks with the state of their block watchers
+pub async fn list_networks(State(state): State<ApiState>) -> ApiResult<Vec<NetworkSummary>> {
+    let tenant_counts = sqlx::query_as::<_, (String, i64)>(
+        r#"
+        SELECT network_id, COUNT(DISTINCT tenant_id)
+        FROM tenant_networks
+        WHERE is_active = true
+        GROUP BY network_id
+        "#,
+    )
+    .fetch_all(&*state.db)
+    .await
+    .map_err(anyhow::Error::from)?;
+
+    let mut networks: BTreeMap<String, NetworkSummary> = tenant_counts
+        .into_iter()
+        .map(|(network_slug, tenant_count)| {
+            let summary = NetworkSummary {
+                network_slug: network_slug.clone(),
+                tenant_count: tenant_count.max(0) as usize,
+                latest_block: None,
+                watcher: None,
+            };
+            (network_slug, summary)
+        })
+        .collect();
+
+    // Watched networks without active tenants are still listed
+    for status in state.block_watcher.network_statuses().await {
+        networks
+            .entry(status.network_slug.clone())
+            .or_insert_with(|| NetworkSummary {
+                network_slug: status.network_slug.clone(),
+                tenant_count: 0,
+                latest_block: None,
+                watcher: None,
+            })
+            .watcher = Some(status);
+    }
+
+    // Known even where the watcher runs in another process
+    for summary in networks.values_mut() {
+        summary.latest_block = state
+            .block_watcher
+            .cached_latest_block(&summary.network_slug)
+            .await;
+    }
+
+    Ok(Json(networks.into_values().collect()))
+}
+
+/// Re-broadcast a range of blocks to the workers as replay events.
+///
+/// The network must be watched by the block watcher of this process.
+pub async fn replay_blocks(
+    State(state): State<ApiState>,
+    Path(network_slug): Path<String>,
+    Json(request): Json<ReplayRequest>,
+) -> ApiResult<ReplayResponse> {
+    let blocks_replayed = state
+        .block_watcher
+        .replay_blocks(
+            state.client_pool.as_ref(),
+            &network_slug,
+            request.from_block,
+            request.to_block,
+            &[],
+        )
+        .await?;
+    Ok(Json(ReplayResponse {
+        network_slug,
+        blocks_replayed,
+    }))
+}
+
+/// Settings a network runs with after all override layers, each with the
+/// layer it came from
+pub async fn get_settings(
+    State(state): State<ApiState>,
+    Path(network_slug): Path<String>,
+) -> ApiResult<EffectiveNetworkSettings> {
+    Ok(Json(state.network_settings.resolve(&network_slug).await?))
+}
@@ -0,0 +1,104 @@
+//! Pagination of list endpoints
+
+use serde::{Deserialize, Serialize};
+
+/// Largest page a client can request
+const MAX_LIMIT: u64 = 1000;
+
+/// Page requested through `limit` and `offset` (or the `cursor` of a previous page)
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PageQuery {
+    /// Maximum items returned
+    #[serde(default = "default_limit")]
+    pub limit: u64,
+
+    /// Items skipped
+    #[serde(default, alias = "cursor")]
+    pub offset: u64,
+}
+
+fn default_limit() -> u64 {
+    100
+}
+
+impl PageQuery {
+    /// Requested limit within the accepted bounds
+    pub fn limit(&self) -> u64 {
+        self.limit.clamp(1, MAX_LIMIT)
+    }
+
+    /// Slice one page out of items already held in memory
+    pub fn paginate<T>(&self, items: Vec<T>) -> Page<T> {
+        let total = items.len() as u64;
+        let items = items
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(self.limit() as usize)
+            .collect();
+        self.page(items, total)
+    }
+
+    /// Wrap a page of items fetched with this query's limit and offset
+    pub fn page<T>(&self, items: Vec<T>, total: u64) -> Page<T> {
+        let end = self.offset.saturating_add(items.len() as u64);
+        Page {
+            items,
+            total,
+            limit: self.limit(),
+            next_cursor: (end < total).then_some(end),
+        }
+    }
+}
+
+/// One page of a list endpoint
+#[derive(Debug, Clone, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+
+    /// Items matching the filters across all pages
+    pub total: u64,
+
+    pub limit: u64,
+
+    /// Offset of the next page, passed back as `cursor`; None on the last page
+    pub next_cursor: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paginate_sets_next_cursor_until_last_page() {
+        let query = PageQuery {
+            limit: 2,
+            offset: 0,
+        };
+        let page = query.paginate(vec![1, 2, 3]);
+        assert_eq!(page.items, vec![1, 2]);
+        assert_eq!(page.total, 3);
+        assert_eq!(page.next_cursor, Some(2));
+
+        let query = PageQuery {
+            limit: 2,
+            offset: 2,
+        };
+        let page = query.paginate(vec![1, 2, 3]);
+        assert_eq!(page.items, vec![3]);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_limit_is_clamped() {
+        let query = PageQuery {
+            limit: 0,
+            offset: 0,
+        };
+        assert_eq!(query.limit(), 1);
+        let query = PageQuery {
+            limit: 5000,
+            offset: 0,
+        };
+        assert_eq!(query.limit(), MAX_LIMIT);
+    }
+}
@@ -0,0 +1,225 @@
+//! Per-client rate limiting
+//!
+//! Each client IP gets a token bucket holding `rate_limit` requests that
+//! refills evenly over a minute. Buckets live behind [`RateLimitStore`] so the
+//! in-process store can be replaced by a shared one (e.g. Redis) when several
+//! API replicas should enforce a common limit. Exempt paths, such as health
+//! probes, are never counted.
+
+use async_trait::async_trait;
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, HeaderValue};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use dashmap::DashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::api::error::ApiError;
+use crate::config::ApiConfig;
+use crate::services::ServiceError;
+
+/// Idle buckets are dropped once this many clients are tracked
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Outcome of counting one request against a client's limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitDecision {
+    Allowed,
+    /// Over the limit; a request succeeds again after `retry_after`
+    Limited {
+        retry_after: Duration,
+    },
+}
+
+/// Storage of per-client request budgets
+#[async_trait]
+pub trait RateLimitStore: Send + Sync {
+    /// Count one request from a client
+    async fn take(&self, client: IpAddr) -> RateLimitDecision;
+}
+
+/// Token bucket refilled continuously up to its capacity
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn full(capacity: f64, now: Instant) -> Self {
+        Self {
+            tokens: capacity,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, capacity: f64, per_second: f64, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * per_second).min(capacity);
+        self.updated_at = now;
+    }
+
+    fn take(&mut self, capacity: f64, per_second: f64, now: Instant) -> RateLimitDecision {
+        self.refill(capacity, per_second, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            RateLimitDecision::Allowed
+        } else {
+            let wait = (1.0 - self.tokens) / per_second;
+            RateLimitDecision::Limited {
+                retry_after: Duration::from_secs_f64(wait),
+            }
+        }
+    }
+}
+
+/// In-process token buckets, one per client IP
+pub struct InMemoryRateLimitStore {
+    buckets: DashMap<IpAddr, TokenBucket>,
+    capacity: f64,
+    per_second: f64,
+}
+
+impl InMemoryRateLimitStore {
+    /// Allow `requests_per_minute` requests per client, with bursts up to the same number
+    pub fn new(requests_per_minute: u32) -> Self {
+        let capacity = f64::from(requests_per_minute.max(1));
+        Self {
+            buckets: DashMap::new(),
+            capacity,
+            per_second: capacity / 60.0,
+        }
+    }
+
+    /// Drop buckets that refilled completely; they are recreated full on demand
+    fn prune(&self, now: Instant) {
+        let (capacity, per_second) = (self.capacity, self.per_second);
+        self.buckets.retain(|_, bucket| {
+            bucket.refill(capacity, per_second, now);
+            bucket.tokens < capacity
+        });
+    }
+}
+
+#[async_trait]
+impl RateLimitStore for InMemoryRateLimitStore {
+    async fn take(&self, client: IpAddr) -> RateLimitDecision {
+        let now = Instant::now();
+        if self.buckets.len() >= MAX_TRACKED_CLIENTS {
+            self.prune(now);
+        }
+
+        self.buckets
+            .entry(client)
+            .or_insert_with(|| TokenBucket::full(self.capacity, now))
+            .take(self.capacity, self.per_second, now)
+    }
+}
+
+/// Rate limiting rules of the API server
+pub struct ApiRateLimit {
+    store: Arc<dyn RateLimitStore>,
+    exempt_paths: Vec<String>,
+}
+
+impl ApiRateLimit {
+    /// Limit clients to `rate_limit` requests per minute, tracked in process
+    pub fn from_config(config: &ApiConfig) -> Self {
+        Self::with_store(
+            config,
+            Arc::new(InMemoryRateLimitStore::new(config.rate_limit)),
+        )
+    }
+
+    /// Limit clients using the given store
+    pub fn with_store(config: &ApiConfig, store: Arc<dyn RateLimitStore>) -> Self {
+        Self {
+            store,
+            exempt_paths: config.rate_limit_exempt.clone(),
+        }
+    }
+
+    fn is_exempt(&self, path: &str) -> bool {
+        self.exempt_paths.iter().any(|exempt| exempt == path)
+    }
+}
+
+/// Reject requests from clients over their rate limit with 429 and `Retry-After`
+pub async fn limit_rate(
+    State(limit): State<Arc<ApiRateLimit>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if limit.is_exempt(request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    let retry_after = match limit.store.take(addr.ip()).await {
+        RateLimitDecision::Allowed => return next.run(request).await,
+        RateLimitDecision::Limited { retry_after } => retry_after,
+    };
+
+    let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+    let mut response = ApiError(ServiceError::RateLimited(format!(
+        "too many requests from {}, retry in {}s",
+        addr.ip(),
+        seconds
+    )))
+    .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_allows_burst_then_limits() {
+        let now = Instant::now();
+        let mut bucket = TokenBucket::full(3.0, now);
+
+        for _ in 0..3 {
+            assert_eq!(bucket.take(3.0, 1.0, now), RateLimitDecision::Allowed);
+        }
+        assert_eq!(
+            bucket.take(3.0, 1.0, now),
+            RateLimitDecision::Limited {
+                retry_after: Duration::from_secs(1)
+            }
+        );
+    }
+
+    #[test]
+    fn test_bucket_refills_over_time_up_to_capacity() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::full(2.0, start);
+        bucket.take(2.0, 1.0, start);
+        bucket.take(2.0, 1.0, start);
+
+        let later = start + Duration::from_secs(1);
+        assert_eq!(bucket.take(2.0, 1.0, later), RateLimitDecision::Allowed);
+
+        bucket.refill(2.0, 1.0, later + Duration::from_secs(60));
+        assert_eq!(bucket.tokens, 2.0);
+    }
+
+    #[tokio::test]
+    async fn test_store_limits_clients_independently() {
+        let store = InMemoryRateLimitStore::new(1);
+        let first: IpAddr = "10.0.0.1".parse().unwrap();
+        let second: IpAddr = "10.0.0.2".parse().unwrap();
+
+        assert_eq!(store.take(first).await, RateLimitDecision::Allowed);
+        assert!(matches!(
+            store.take(first).await,
+            RateLimitDecision::Limited { .. }
+        ));
+        assert_eq!(store.take(second).await, RateLimitDecision::Allowed);
+    }
+}
@@ -0,0 +1,49 @@
+//! Rebalance endpoint
+
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::RebalancePlan;
+
+/// Query of `POST /rebalance`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RebalanceQuery {
+    /// Compute the new distribution without applying it
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// Result of a rebalance
+#[derive(Debug, Clone, Serialize)]
+pub struct RebalanceResponse {
+    pub dry_run: bool,
+
+    #[serde(flatten)]
+    pub plan: RebalancePlan,
+}
+
+/// Redistribute tenants across workers by activity
+pub async fn rebalance(
+    State(state): State<ApiState>,
+    Query(query): Query<RebalanceQuery>,
+) -> ApiResult<RebalanceResponse> {
+    if query.dry_run {
+        return Ok(Json(RebalanceResponse {
+            dry_run: true,
+            plan: state.load_balancer.plan_rebalance().await,
+        }));
+    }
+
+    let plan = state.load_balancer.rebalance().await?;
+    for worker_id in &plan.affected_workers {
+        state.reload_local_worker(worker_id).await?;
+    }
+
+    Ok(Json(RebalanceResponse {
+        dry_run: false,
+        plan,
+    }))
+}
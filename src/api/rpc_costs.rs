@@ -0,0 +1,59 @@
+//! RPC cost endpoints
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Deserialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::TenantRpcCost;
+use crate::services::{RpcCostTracker, ServiceError};
+
+/// Query of the RPC cost endpoints
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcCostQuery {
+    /// Days of usage included, counting today
+    #[serde(default = "default_days")]
+    pub days: i64,
+}
+
+fn default_days() -> i64 {
+    30
+}
+
+impl RpcCostQuery {
+    /// First day included
+    fn since(&self) -> NaiveDate {
+        Utc::now().date_naive() - Duration::days(self.days.clamp(1, 366) - 1)
+    }
+}
+
+/// Estimated RPC usage and cost of every tenant, most expensive first
+pub async fn list_rpc_costs(
+    State(state): State<ApiState>,
+    Query(query): Query<RpcCostQuery>,
+) -> ApiResult<Vec<TenantRpcCost>> {
+    let costs = tracker(&state)?.costs(query.since()).await?;
+    Ok(Json(costs))
+}
+
+/// Estimated RPC usage and cost of one tenant per network
+pub async fn get_tenant_rpc_cost(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Query(query): Query<RpcCostQuery>,
+) -> ApiResult<TenantRpcCost> {
+    let cost = tracker(&state)?
+        .tenant_cost(tenant_id, query.since())
+        .await?;
+    Ok(Json(cost))
+}
+
+fn tracker(state: &ApiState) -> Result<Arc<RpcCostTracker>, ServiceError> {
+    state.rpc_costs.clone().ok_or_else(|| {
+        ServiceError::ServiceUnavailable("RPC cost attribution is disabled".to_string())
+    })
+}
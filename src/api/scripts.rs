@@ -0,0 +1,104 @@
+//! Trigger script endpoints
+//!
+//! Every change publishes a script invalidation so workers in all processes
+//! drop their cached copy of the script, and a configuration change on the
+//! event bus.
+
+use axum::extract::{Path, State};
+use axum::Json;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::ApiState;
+use crate::models::{TriggerScript, TriggerScriptInput};
+use crate::repositories::RepositoryError;
+use crate::services::{
+    ConfigChange, Event, ScriptInvalidation, ScriptInvalidationService, ServiceError, TenantStore,
+};
+
+/// Get a tenant's trigger script
+pub async fn get_script(
+    State(state): State<ApiState>,
+    Path((tenant_id, name)): Path<(Uuid, String)>,
+) -> ApiResult<TriggerScript> {
+    let script = state
+        .trigger_scripts
+        .get(tenant_id, &name)
+        .await?
+        .ok_or_else(|| script_not_found(tenant_id, &name))?;
+    Ok(Json(script))
+}
+
+/// Create or replace a tenant's trigger script.
+///
+/// The language must be one the script executors run (Python, JavaScript or
+/// Bash) and the content at most `api.max_script_size` bytes.
+pub async fn put_script(
+    State(state): State<ApiState>,
+    Path((tenant_id, name)): Path<(Uuid, String)>,
+    Json(input): Json<TriggerScriptInput>,
+) -> ApiResult<TriggerScript> {
+    TenantStore::new(state.db.clone())
+        .get(tenant_id)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+
+    let script = state
+        .trigger_scripts
+        .upsert(tenant_id, &name, &input)
+        .await?;
+    invalidate(&state, tenant_id, &name).await;
+    Ok(Json(script))
+}
+
+/// Delete a tenant's trigger script, returning it
+pub async fn delete_script(
+    State(state): State<ApiState>,
+    Path((tenant_id, name)): Path<(Uuid, String)>,
+) -> ApiResult<TriggerScript> {
+    let script = state
+        .trigger_scripts
+        .delete(tenant_id, &name)
+        .await?
+        .ok_or_else(|| script_not_found(tenant_id, &name))?;
+    invalidate(&state, tenant_id, &name).await;
+    Ok(Json(script))
+}
+
+/// Tell workers to drop the cached script. The change is already stored, so
+/// a failure only delays it until the cache entry expires.
+async fn invalidate(state: &ApiState, tenant_id: Uuid, name: &str) {
+    let cache = state.client_pool.cache();
+    let invalidation = ScriptInvalidation {
+        tenant_id,
+        script_name: name.to_string(),
+    };
+    if let Err(e) = ScriptInvalidationService::new(cache.redis_client(), &cache.key_prefix())
+        .publish(&invalidation)
+        .await
+    {
+        warn!(
+            "Failed to publish invalidation of script {} of tenant {}: {}",
+            name, tenant_id, e
+        );
+    }
+
+    let change = Event::ConfigChanged(ConfigChange {
+        tenant_ids: vec![tenant_id],
+    });
+    if let Err(e) = state.block_watcher.event_bus().publish(change).await {
+        warn!(
+            "Failed to publish configuration change of tenant {}: {}",
+            tenant_id, e
+        );
+    }
+}
+
+fn script_not_found(tenant_id: Uuid, name: &str) -> ServiceError {
+    RepositoryError::NotFound {
+        entity_type: "trigger script".to_string(),
+        id: format!("{}/{}", tenant_id, name),
+    }
+    .into()
+}
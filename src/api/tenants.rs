@@ -0,0 +1,513 @@
+//! Tenant endpoints
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::api::error::{ApiError, ApiResult};
+use crate::api::pagination::{Page, PageQuery};
+use crate::api::ApiState;
+use crate::models::{
+    CoverageReport, TenantAssignment, TenantInfo, TenantMetrics, TenantPause, TenantStatus,
+    TriggerTestResult,
+};
+use crate::repositories::RepositoryError;
+use crate::services::{
+    ControlCommand, CoverageReporter, OzMonitorServices, ServiceError, TenantFilter, TenantPauses,
+    TenantStore,
+};
+
+/// Filters of `GET /tenants`
+#[derive(Debug, Clone, Deserialize)]
+pub struct TenantQuery {
+    /// Only tenants with this status
+    pub status: Option<TenantStatus>,
+
+    /// Only tenants or tenant shards assigned to this worker
+    pub worker_id: Option<String>,
+
+    /// Only tenants with this network configured
+    pub network: Option<String>,
+}
+
+/// Tenant as listed by `GET /tenants`
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantSummary {
+    #[serde(flatten)]
+    pub tenant: TenantInfo,
+
+    /// Worker holding the tenant; None if unassigned or split into shards
+    pub assignment: Option<TenantAssignment>,
+}
+
+/// Result of suspending or activating a tenant
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantLifecycleResponse {
+    #[serde(flatten)]
+    pub tenant: TenantInfo,
+
+    /// Workers the tenant was taken off (suspend) or placed on (activate)
+    pub worker_ids: Vec<String>,
+}
+
+/// Latest activity metrics of a tenant
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantMetricsResponse {
+    pub tenant_id: Uuid,
+
+    /// Worker processing the tenant; None if unassigned or split into shards
+    pub worker_id: Option<String>,
+
+    /// Activity score (0-1) of the metrics
+    pub activity_score: Option<f64>,
+
+    /// Latest metrics, stamped with `collected_at`; None until a worker has
+    /// processed the tenant
+    pub metrics: Option<TenantMetrics>,
+}
+
+/// List a page of tenants with the worker each one is assigned to
+pub async fn list_tenants(
+    State(state): State<ApiState>,
+    Query(page): Query<PageQuery>,
+    Query(query): Query<TenantQuery>,
+) -> ApiResult<Page<TenantSummary>> {
+    let tenant_ids = match &query.worker_id {
+        Some(worker_id) => {
+            let mut tenant_ids = state
+                .load_balancer
+                .get_worker_assignments(worker_id)
+                .await?;
+            tenant_ids.extend(
+                state
+                    .load_balancer
+                    .get_worker_shards(worker_id)
+                    .await
+                    .into_iter()
+                    .map(|shard| shard.tenant_id),
+            );
+            Some(tenant_ids)
+        }
+        None => None,
+    };
+    let filter = TenantFilter {
+        active: query
+            .status
+            .map(|status| matches!(status, TenantStatus::Active | TenantStatus::Trial)),
+        trial: query.status.and_then(|status| match status {
+            TenantStatus::Active => Some(false),
+            TenantStatus::Trial => Some(true),
+            TenantStatus::Suspended | TenantStatus::Inactive => None,
+        }),
+        tenant_ids,
+        network_slug: query.network,
+    };
+
+    let store = TenantStore::new(state.db.clone());
+    let tenants = store.list(&filter, page.limit(), page.offset).await?;
+    let total = store.count(&filter).await?;
+    let mut assignments = state.load_balancer.tenant_assignments().await;
+
+    let tenants = tenants
+        .into_iter()
+        .map(|tenant| TenantSummary {
+            assignment: assignments.remove(&tenant.id),
+            tenant,
+        })
+        .collect();
+    Ok(Json(page.page(tenants, total)))
+}
+
+/// Latest activity metrics of a tenant and the worker processing it
+pub async fn get_tenant_metrics(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<TenantMetricsResponse> {
+    TenantStore::new(state.db.clone())
+        .get(tenant_id)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+
+    let metrics = state.load_balancer.latest_tenant_metrics(tenant_id).await;
+    Ok(Json(TenantMetricsResponse {
+        tenant_id,
+        worker_id: state.load_balancer.get_worker_for_tenant(tenant_id).await,
+        activity_score: metrics.as_ref().map(TenantMetrics::activity_score),
+        metrics,
+    }))
+}
+
+/// Report which of a tenant's networks are watched, which monitors loaded and
+/// which triggers resolve to a notification channel
+pub async fn get_tenant_coverage(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<CoverageReport> {
+    TenantStore::new(state.db.clone())
+        .get(tenant_id)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+
+    let mut report = CoverageReporter::new(state.db.clone(), state.block_watcher.clone())
+        .with_notification_channels(state.worker_pool.notification_channels())
+        .report(tenant_id)
+        .await?;
+    report.worker_id = state.load_balancer.get_worker_for_tenant(tenant_id).await;
+    Ok(Json(report))
+}
+
+/// Suspend a tenant and take it off its workers
+pub async fn suspend_tenant(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<TenantLifecycleResponse> {
+    let response = suspend(&state, tenant_id).await?;
+    for worker_id in &response.worker_ids {
+        state.reload_local_worker(worker_id).await?;
+    }
+
+    Ok(Json(response))
+}
+
+/// Suspend a tenant and unassign it; workers in this process are left for
+/// the caller to reload
+pub(crate) async fn suspend(
+    state: &ApiState,
+    tenant_id: Uuid,
+) -> Result<TenantLifecycleResponse, ApiError> {
+    let tenant = TenantStore::new(state.db.clone())
+        .set_active(tenant_id, false)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+
+    let worker_ids = state.load_balancer.unassign_tenant(tenant_id).await?;
+    Ok(TenantLifecycleResponse { tenant, worker_ids })
+}
+
+/// Activate a tenant and assign it to a worker
+pub async fn activate_tenant(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<TenantLifecycleResponse> {
+    let tenant = TenantStore::new(state.db.clone())
+        .set_active(tenant_id, true)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+
+    let load_balancer = &state.load_balancer;
+    let mut worker_ids = if load_balancer.is_sharded(&tenant_id) {
+        load_balancer
+            .assign_tenant_shards(tenant_id)
+            .await?
+            .into_iter()
+            .map(|(worker_id, _)| worker_id)
+            .collect()
+    } else if let Some(worker_id) = load_balancer.get_worker_for_tenant(tenant_id).await {
+        vec![worker_id]
+    } else {
+        vec![load_balancer.assign_tenant(tenant_id).await?]
+    };
+    worker_ids.sort();
+    worker_ids.dedup();
+
+    for worker_id in &worker_ids {
+        state.reload_local_worker(worker_id).await?;
+    }
+
+    Ok(Json(TenantLifecycleResponse { tenant, worker_ids }))
+}
+
+/// Body of `POST /tenants/{tenant_id}/pause`
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PauseTenantRequest {
+    /// Why the tenant is paused, kept with the pause
+    pub reason: Option<String>,
+}
+
+/// Result of pausing or resuming a tenant
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantPauseResponse {
+    pub tenant_id: Uuid,
+
+    /// Pause in effect after a pause, or lifted by a resume; None when resuming
+    /// a tenant that was not paused
+    pub pause: Option<TenantPause>,
+
+    /// Workers holding the tenant, told to pick up the change
+    pub worker_ids: Vec<String>,
+}
+
+/// Pause processing a tenant's blocks while keeping it assigned, so its
+/// affinity and checkpoints are kept
+pub async fn pause_tenant(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    request: Option<Json<PauseTenantRequest>>,
+) -> ApiResult<TenantPauseResponse> {
+    processable_tenant(&state, tenant_id).await?;
+
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    let pause = TenantPauses::new(state.db.clone())
+        .pause(tenant_id, request.reason.as_deref())
+        .await?;
+    let worker_ids = invalidate_on_workers(&state, tenant_id).await;
+
+    Ok(Json(TenantPauseResponse {
+        tenant_id,
+        pause: Some(pause),
+        worker_ids,
+    }))
+}
+
+/// Resume processing a paused tenant's blocks
+pub async fn resume_tenant(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<TenantPauseResponse> {
+    TenantStore::new(state.db.clone())
+        .get(tenant_id)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+
+    let pause = TenantPauses::new(state.db.clone())
+        .resume(tenant_id)
+        .await?;
+    let worker_ids = invalidate_on_workers(&state, tenant_id).await;
+
+    Ok(Json(TenantPauseResponse {
+        tenant_id,
+        pause,
+        worker_ids,
+    }))
+}
+
+/// Look up a tenant whose processing can be changed, rejecting suspended
+/// tenants, which are not processed until activated
+async fn processable_tenant(state: &ApiState, tenant_id: Uuid) -> Result<(), ApiError> {
+    let tenant = TenantStore::new(state.db.clone())
+        .get(tenant_id)
+        .await?
+        .ok_or(ServiceError::TenantNotFound(tenant_id))?;
+    Ok(ensure_not_suspended(&tenant)?)
+}
+
+/// Fail with [`ServiceError::TenantSuspended`] for a suspended tenant
+fn ensure_not_suspended(tenant: &TenantInfo) -> Result<(), ServiceError> {
+    if tenant.status == TenantStatus::Suspended {
+        return Err(ServiceError::TenantSuspended(tenant.id));
+    }
+    Ok(())
+}
+
+/// Tell the workers holding a tenant to drop its cached configuration. The
+/// change is already stored, so a worker missing it only picks it up once its
+/// cache expires.
+async fn invalidate_on_workers(state: &ApiState, tenant_id: Uuid) -> Vec<String> {
+    let worker_ids = state.load_balancer.workers_for_tenant(tenant_id).await;
+    for worker_id in &worker_ids {
+        let notified = if state
+            .worker_pool
+            .get_worker_status(worker_id)
+            .await
+            .is_some()
+        {
+            state
+                .worker_pool
+                .reload_tenant(worker_id, tenant_id)
+                .await
+                .map(|_| true)
+        } else {
+            let command = ControlCommand::InvalidateConfig {
+                tenant_ids: vec![tenant_id],
+            };
+            state.load_balancer.send_control(worker_id, &command).await
+        };
+        match notified {
+            Ok(true) => {}
+            Ok(false) => warn!(
+                "Worker {} is not listening for control commands, tenant {} changes apply once its cache expires",
+                worker_id, tenant_id
+            ),
+            Err(e) => warn!(
+                "Failed to notify worker {} of changes to tenant {}: {}",
+                worker_id, tenant_id, e
+            ),
+        }
+    }
+    worker_ids
+}
+
+/// Result of `POST /tenants/{tenant_id}/reload`
+#[derive(Debug, Clone, Serialize)]
+pub struct ReloadTenantResponse {
+    pub tenant_id: Uuid,
+
+    /// Worker owning the tenant
+    pub worker_id: String,
+
+    /// Monitors loaded after the reload; None when the worker runs in another
+    /// process and reloads on receiving the control command
+    pub monitors_loaded: Option<usize>,
+}
+
+/// Reload a tenant's monitors on the worker owning it without waiting for the reload interval
+pub async fn reload_tenant(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+) -> ApiResult<ReloadTenantResponse> {
+    Ok(Json(reload(&state, tenant_id).await?))
+}
+
+/// Reload a tenant's monitors on the worker owning it
+pub(crate) async fn reload(
+    state: &ApiState,
+    tenant_id: Uuid,
+) -> Result<ReloadTenantResponse, ApiError> {
+    processable_tenant(state, tenant_id).await?;
+    let worker_id = state
+        .load_balancer
+        .get_worker_for_tenant(tenant_id)
+        .await
+        .ok_or_else(|| RepositoryError::NotFound {
+            entity_type: "tenant assignment".to_string(),
+            id: tenant_id.to_string(),
+        })
+        .map_err(ServiceError::from)?;
+
+    let monitors_loaded = if state
+        .worker_pool
+        .get_worker_status(&worker_id)
+        .await
+        .is_some()
+    {
+        state
+            .worker_pool
+            .reload_tenant(&worker_id, tenant_id)
+            .await?
+    } else {
+        let command = ControlCommand::InvalidateConfig {
+            tenant_ids: vec![tenant_id],
+        };
+        if !state
+            .load_balancer
+            .send_control(&worker_id, &command)
+            .await?
+        {
+            return Err(ServiceError::CommunicationError(format!(
+                "Worker {} is not listening for control commands",
+                worker_id
+            ))
+            .into());
+        }
+        None
+    };
+
+    Ok(ReloadTenantResponse {
+        tenant_id,
+        worker_id,
+        monitors_loaded,
+    })
+}
+
+/// Body of `POST /tenants/{tenant_id}/assign`
+#[derive(Debug, Clone, Deserialize)]
+pub struct AssignTenantRequest {
+    /// Worker to move the tenant to
+    pub worker_id: String,
+}
+
+/// Result of a manual assignment
+#[derive(Debug, Clone, Serialize)]
+pub struct AssignTenantResponse {
+    #[serde(flatten)]
+    pub assignment: TenantAssignment,
+
+    /// Worker the tenant was taken from, if it was assigned elsewhere
+    pub previous_worker_id: Option<String>,
+}
+
+impl AssignTenantResponse {
+    /// Workers whose tenants changed: the new worker and the previous one
+    pub fn worker_ids(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.assignment.worker_id).chain(self.previous_worker_id.as_ref())
+    }
+}
+
+/// Move a tenant to a specific worker without waiting for a rebalance
+pub async fn assign_tenant(
+    State(state): State<ApiState>,
+    Path(tenant_id): Path<Uuid>,
+    Json(request): Json<AssignTenantRequest>,
+) -> ApiResult<AssignTenantResponse> {
+    let response = assign(&state, tenant_id, &request.worker_id).await?;
+    for worker_id in response.worker_ids() {
+        state.reload_local_worker(worker_id).await?;
+    }
+
+    Ok(Json(response))
+}
+
+/// Move a tenant to a worker; workers in this process are left for the
+/// caller to reload
+pub(crate) async fn assign(
+    state: &ApiState,
+    tenant_id: Uuid,
+    worker_id: &str,
+) -> Result<AssignTenantResponse, ApiError> {
+    processable_tenant(state, tenant_id).await?;
+    let (assignment, previous_worker_id) = state
+        .load_balancer
+        .assign_tenant_to_worker(tenant_id, worker_id)
+        .await?;
+    Ok(AssignTenantResponse {
+        assignment,
+        previous_worker_id,
+    })
+}
+
+/// Send a test notification through a tenant's trigger and report whether it was delivered
+pub async fn test_trigger(
+    State(state): State<ApiState>,
+    Path((tenant_id, trigger_name)): Path<(Uuid, String)>,
+) -> ApiResult<TriggerTestResult> {
+    let oz_services =
+        OzMonitorServices::new(state.db.clone(), vec![tenant_id], state.client_pool.clone())
+            .await?
+            .with_notification_channels(state.worker_pool.notification_channels());
+
+    let result = oz_services.test_trigger(tenant_id, &trigger_name).await?;
+    Ok(Json(result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TenantPriority;
+
+    fn tenant(status: TenantStatus) -> TenantInfo {
+        TenantInfo {
+            id: Uuid::new_v4(),
+            name: "tenant".to_string(),
+            status,
+            priority: TenantPriority::Normal,
+            max_monitors: 10,
+            max_rpc_requests_per_minute: 600,
+            created_at: chrono::Utc::now(),
+            last_active_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_suspended_tenants_are_rejected() {
+        assert!(ensure_not_suspended(&tenant(TenantStatus::Active)).is_ok());
+        assert!(ensure_not_suspended(&tenant(TenantStatus::Trial)).is_ok());
+
+        let suspended = tenant(TenantStatus::Suspended);
+        let err = ensure_not_suspended(&suspended).unwrap_err();
+        assert!(matches!(err, ServiceError::TenantSuspended(id) if id == suspended.id));
+        assert_eq!(err.http_status(), 403);
+        assert_eq!(err.code(), "TENANT_SUSPENDED");
+    }
+}
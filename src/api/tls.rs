@@ -0,0 +1,170 @@
+//! TLS Listener
+//!
+//! Serves the API over rustls when `api.tls_cert_path` and `api.tls_key_path`
+//! are set. The certificate is reloaded without a restart on SIGHUP and when
+//! either file changes on disk, so rotations by cert-manager or a similar
+//! tool are picked up while connections keep being served. A reload that
+//! fails keeps the previous certificate.
+
+use anyhow::{Context, Result};
+use axum::Router;
+use axum_server::tls_rustls::RustlsConfig;
+use axum_server::Handle;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
+use tracing::{info, warn};
+
+use crate::config::ApiConfig;
+use crate::services::ShutdownSignal;
+
+/// How often the certificate and key files are checked for changes
+const RELOAD_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Serve `app` over TLS until `shutdown` is triggered, then drain like the
+/// plain listener does
+pub async fn serve(
+    config: &ApiConfig,
+    app: Router,
+    shutdown: ShutdownSignal,
+    cert_path: &Path,
+    key_path: &Path,
+) -> Result<()> {
+    let tls = RustlsConfig::from_pem_file(cert_path, key_path)
+        .await
+        .with_context(|| {
+            format!(
+                "Failed to load TLS certificate {} and key {}",
+                cert_path.display(),
+                key_path.display()
+            )
+        })?;
+
+    let listener = std::net::TcpListener::bind(config.socket_addr())
+        .with_context(|| format!("Failed to bind API server to {}", config.socket_addr()))?;
+    listener
+        .set_nonblocking(true)
+        .context("Failed to configure API listener")?;
+    info!("API server listening on {} (TLS)", config.socket_addr());
+
+    let reload_task = watch_certificate(tls.clone(), cert_path.into(), key_path.into());
+
+    let handle = Handle::new();
+    let drain_task = tokio::spawn({
+        let handle = handle.clone();
+        let timeout = config.shutdown_timeout;
+        async move {
+            shutdown.wait().await;
+            info!("API server draining connections");
+            // Connections still open after the timeout are closed by the server
+            handle.graceful_shutdown(Some(timeout));
+        }
+    });
+
+    let result = axum_server::from_tcp_rustls(listener, tls)
+        .handle(handle)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .context("API server failed");
+
+    reload_task.abort();
+    drain_task.abort();
+    result
+}
+
+/// Reload the certificate on SIGHUP or when its files change
+fn watch_certificate(
+    tls: RustlsConfig,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        let mut hangup = match signal(SignalKind::hangup()) {
+            Ok(hangup) => Some(hangup),
+            Err(e) => {
+                warn!(
+                    "Failed to install SIGHUP handler, TLS reload on SIGHUP disabled: {}",
+                    e
+                );
+                None
+            }
+        };
+
+        let mut modified = modified_times(&cert_path, &key_path);
+        let mut ticker = tokio::time::interval(RELOAD_POLL_INTERVAL);
+        ticker.tick().await;
+
+        loop {
+            #[cfg(unix)]
+            let hangup_received = async {
+                match hangup.as_mut() {
+                    Some(hangup) => hangup.recv().await,
+                    None => std::future::pending().await,
+                }
+            };
+            #[cfg(not(unix))]
+            let hangup_received = std::future::pending::<Option<()>>();
+
+            tokio::select! {
+                _ = hangup_received => {
+                    info!("Received SIGHUP, reloading TLS certificate");
+                }
+                _ = ticker.tick() => {
+                    let current = modified_times(&cert_path, &key_path);
+                    if current == modified {
+                        continue;
+                    }
+                    modified = current;
+                    info!("TLS certificate files changed, reloading");
+                }
+            }
+
+            match tls.reload_from_pem_file(&cert_path, &key_path).await {
+                Ok(()) => info!("Reloaded TLS certificate {}", cert_path.display()),
+                Err(e) => warn!(
+                    "Failed to reload TLS certificate {}, keeping the previous one: {}",
+                    cert_path.display(),
+                    e
+                ),
+            }
+        }
+    })
+}
+
+/// Modification times of the certificate and key, following symlinks so
+/// swapped Kubernetes secret mounts are detected
+fn modified_times(cert_path: &Path, key_path: &Path) -> (Option<SystemTime>, Option<SystemTime>) {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    (modified(cert_path), modified(key_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cert_and_key_must_be_set_together() {
+        let with_paths = |cert: Option<&str>, key: Option<&str>| ApiConfig {
+            tls_cert_path: cert.map(PathBuf::from),
+            tls_key_path: key.map(PathBuf::from),
+            ..Default::default()
+        };
+
+        assert!(with_paths(None, None).validate().is_ok());
+        assert!(with_paths(None, None).tls_paths().is_none());
+        assert!(with_paths(Some("tls.crt"), Some("tls.key"))
+            .tls_paths()
+            .is_some());
+        assert!(with_paths(Some("tls.crt"), None)
+            .validate()
+            .unwrap_err()
+            .contains("tls_key_path"));
+        assert!(with_paths(None, Some("tls.key"))
+            .validate()
+            .unwrap_err()
+            .contains("tls_cert_path"));
+    }
+}
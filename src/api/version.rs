@@ -0,0 +1,44 @@
+//! Version endpoint
+//!
+//! Served outside the versioned API prefix so deploy tooling can compare the
+//! builds of a mixed-version fleet whichever API versions they serve.
+
+use axum::extract::State;
+use axum::Json;
+use serde::Serialize;
+
+use crate::api::ApiState;
+use crate::config::ServiceMode;
+
+/// Build and mode of this process
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionInfo {
+    /// Orchestrator crate version
+    pub version: &'static str,
+    /// Commit the binary was built from, `unknown` if it was built without git
+    pub git_sha: &'static str,
+    /// OpenZeppelin Monitor library version the binary was built against
+    pub monitor_version: &'static str,
+    /// Version of the management API routes, which are served under `/<api_version>`
+    pub api_version: &'static str,
+    /// Service mode this process runs in
+    pub service_mode: ServiceMode,
+}
+
+impl VersionInfo {
+    /// Version information of this build running in the given mode
+    pub fn new(service_mode: ServiceMode) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            git_sha: env!("OZ_MONITOR_GIT_SHA"),
+            monitor_version: env!("OZ_MONITOR_LIBRARY_VERSION"),
+            api_version: super::API_PREFIX.trim_start_matches('/'),
+            service_mode,
+        }
+    }
+}
+
+/// Report the build and service mode of this process
+pub async fn get_version(State(state): State<ApiState>) -> Json<VersionInfo> {
+    Json(VersionInfo::new(state.mode.clone()))
+}
@@ -0,0 +1,156 @@
+//! Worker endpoints
+
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::api::error::ApiResult;
+use crate::api::pagination::{Page, PageQuery};
+use crate::api::ApiState;
+use crate::models::{TenantShard, WorkerDrain};
+use crate::repositories::RepositoryError;
+use crate::services::worker_pool::WorkerState;
+use crate::services::ServiceError;
+
+/// Worker as listed by `GET /workers`
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerSummary {
+    pub worker_id: String,
+
+    /// Status of a worker running in this process; None for remote workers
+    pub status: Option<WorkerState>,
+
+    pub tenant_count: usize,
+
+    /// Capacity relative to a worker of weight 1; None for workers not
+    /// registered with the load balancer, such as standbys
+    pub capacity_weight: Option<f64>,
+}
+
+/// Filters of `GET /workers`
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkerQuery {
+    /// Only workers of this process in this status (e.g. `running`, `standby`)
+    pub status: Option<String>,
+}
+
+/// Worker as returned by `GET /workers/{id}`
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerDetail {
+    pub worker_id: String,
+
+    /// Status of a worker running in this process; None for remote workers
+    pub status: Option<WorkerState>,
+
+    /// Capacity relative to a worker of weight 1; None for workers not
+    /// registered with the load balancer, such as standbys
+    pub capacity_weight: Option<f64>,
+
+    /// Tenants the load balancer assigned to the worker
+    pub tenant_ids: Vec<Uuid>,
+
+    /// Shards of tenants split across workers
+    pub shards: Vec<TenantShard>,
+}
+
+/// List a page of the workers running in this process and workers known to the load balancer
+pub async fn list_workers(
+    State(state): State<ApiState>,
+    Query(page): Query<PageQuery>,
+    Query(query): Query<WorkerQuery>,
+) -> ApiResult<Page<WorkerSummary>> {
+    let mut workers: Vec<WorkerSummary> = state
+        .worker_pool
+        .list_workers()
+        .await
+        .into_iter()
+        .map(|(worker_id, status, tenant_count)| WorkerSummary {
+            worker_id,
+            status: Some(status),
+            tenant_count,
+            capacity_weight: None,
+        })
+        .collect();
+    for worker in &mut workers {
+        worker.capacity_weight = state.load_balancer.capacity_weight(&worker.worker_id).await;
+    }
+
+    for worker_id in state.load_balancer.worker_ids().await {
+        if workers.iter().any(|w| w.worker_id == worker_id) {
+            continue;
+        }
+        let tenant_count = state
+            .load_balancer
+            .get_worker_assignments(&worker_id)
+            .await?
+            .len();
+        let capacity_weight = state.load_balancer.capacity_weight(&worker_id).await;
+        workers.push(WorkerSummary {
+            worker_id,
+            status: None,
+            tenant_count,
+            capacity_weight,
+        });
+    }
+
+    if let Some(status) = &query.status {
+        workers.retain(|w| w.status.as_ref().map(|s| s.status.as_str()) == Some(status.as_str()));
+    }
+
+    workers.sort_by(|a, b| a.worker_id.cmp(&b.worker_id));
+    Ok(Json(page.paginate(workers)))
+}
+
+/// Get a worker's status and assigned tenants
+pub async fn get_worker(
+    State(state): State<ApiState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<WorkerDetail> {
+    let status = state.worker_pool.get_worker_status(&worker_id).await;
+    let known = state.load_balancer.worker_ids().await.contains(&worker_id);
+    if status.is_none() && !known {
+        return Err(ServiceError::WorkerNotFound(worker_id).into());
+    }
+
+    let tenant_ids = state
+        .load_balancer
+        .get_worker_assignments(&worker_id)
+        .await?;
+    let shards = state.load_balancer.get_worker_shards(&worker_id).await;
+    let capacity_weight = state.load_balancer.capacity_weight(&worker_id).await;
+
+    Ok(Json(WorkerDetail {
+        worker_id,
+        status,
+        capacity_weight,
+        tenant_ids,
+        shards,
+    }))
+}
+
+/// Start moving a worker's tenants onto other workers, returning the drain to poll
+pub async fn drain_worker(
+    State(state): State<ApiState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<WorkerDrain> {
+    let drain = state.load_balancer.start_drain(&worker_id).await?;
+    Ok(Json(drain))
+}
+
+/// Get the progress of a worker's latest drain
+pub async fn get_drain_status(
+    State(state): State<ApiState>,
+    Path(worker_id): Path<String>,
+) -> ApiResult<WorkerDrain> {
+    let drain = state
+        .load_balancer
+        .drain_status(&worker_id)
+        .await
+        .ok_or_else(|| RepositoryError::NotFound {
+            entity_type: "worker drain".to_string(),
+            id: worker_id,
+        })
+        .map_err(ServiceError::from)?;
+    Ok(Json(drain))
+}
@@ -0,0 +1,166 @@
+//! Tenant activity anomaly detection configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Detection of sudden spikes in tenant match or RPC rates
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyConfig {
+    /// Run the anomaly analyzer in API processes
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// How often tenant activity is sampled and analyzed
+    #[serde(default = "default_interval", with = "humantime_serde")]
+    pub interval: Duration,
+
+    /// Recent samples averaged into a tenant's baseline; tenants with fewer are not analyzed
+    #[serde(default = "default_baseline_samples")]
+    pub baseline_samples: usize,
+
+    /// How many times its baseline a rate must reach to count as a spike
+    #[serde(default = "default_spike_factor")]
+    pub spike_factor: f64,
+
+    /// Match rate per minute below which spikes are ignored
+    #[serde(default = "default_min_matches_per_minute")]
+    pub min_matches_per_minute: f64,
+
+    /// RPC rate per minute below which spikes are ignored
+    #[serde(default = "default_min_rpc_calls_per_minute")]
+    pub min_rpc_calls_per_minute: f64,
+
+    /// Cap the RPC requests of a tenant whose activity spiked
+    #[serde(default)]
+    pub auto_throttle: bool,
+
+    /// RPC requests per minute allowed to a throttled tenant
+    #[serde(default = "default_throttle_rpc_requests_per_minute")]
+    pub throttle_rpc_requests_per_minute: u32,
+
+    /// How long a throttle lasts
+    #[serde(default = "default_throttle_duration", with = "humantime_serde")]
+    pub throttle_duration: Duration,
+
+    /// Minimum time between two alerts of the same kind for a tenant
+    #[serde(default = "default_alert_cooldown", with = "humantime_serde")]
+    pub alert_cooldown: Duration,
+
+    /// How long activity samples are kept
+    #[serde(default = "default_history_retention", with = "humantime_serde")]
+    pub history_retention: Duration,
+}
+
+fn default_interval() -> Duration {
+    Duration::from_secs(300)
+}
+
+fn default_baseline_samples() -> usize {
+    12
+}
+
+fn default_spike_factor() -> f64 {
+    3.0
+}
+
+fn default_min_matches_per_minute() -> f64 {
+    10.0
+}
+
+fn default_min_rpc_calls_per_minute() -> f64 {
+    100.0
+}
+
+fn default_throttle_rpc_requests_per_minute() -> u32 {
+    60
+}
+
+fn default_throttle_duration() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_alert_cooldown() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_history_retention() -> Duration {
+    Duration::from_secs(7 * 24 * 3600)
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval: default_interval(),
+            baseline_samples: default_baseline_samples(),
+            spike_factor: default_spike_factor(),
+            min_matches_per_minute: default_min_matches_per_minute(),
+            min_rpc_calls_per_minute: default_min_rpc_calls_per_minute(),
+            auto_throttle: false,
+            throttle_rpc_requests_per_minute: default_throttle_rpc_requests_per_minute(),
+            throttle_duration: default_throttle_duration(),
+            alert_cooldown: default_alert_cooldown(),
+            history_retention: default_history_retention(),
+        }
+    }
+}
+
+impl AnomalyConfig {
+    /// Validate anomaly detection configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval < Duration::from_secs(10) {
+            return Err("anomalies interval must be at least 10 seconds".to_string());
+        }
+
+        if self.baseline_samples == 0 {
+            return Err("anomalies baseline_samples must be greater than 0".to_string());
+        }
+
+        if self.spike_factor.is_nan() || self.spike_factor <= 1.0 {
+            return Err("anomalies spike_factor must be greater than 1.0".to_string());
+        }
+
+        if self.min_matches_per_minute.is_nan()
+            || self.min_matches_per_minute < 0.0
+            || self.min_rpc_calls_per_minute.is_nan()
+            || self.min_rpc_calls_per_minute < 0.0
+        {
+            return Err("anomalies minimum rates must not be negative".to_string());
+        }
+
+        if self.throttle_rpc_requests_per_minute == 0 {
+            return Err(
+                "anomalies throttle_rpc_requests_per_minute must be greater than 0".to_string(),
+            );
+        }
+
+        if self.history_retention <= self.interval * self.baseline_samples as u32 {
+            return Err(
+                "anomalies history_retention must cover baseline_samples intervals".to_string(),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// Re-export for backward compatibility with services
+impl From<AnomalyConfig> for crate::services::activity_anomalies::AnomalyConfig {
+    fn from(config: AnomalyConfig) -> Self {
+        crate::services::activity_anomalies::AnomalyConfig {
+            interval: config.interval,
+            baseline_samples: config.baseline_samples,
+            spike_factor: config.spike_factor,
+            min_matches_per_minute: config.min_matches_per_minute,
+            min_rpc_calls_per_minute: config.min_rpc_calls_per_minute,
+            throttle: config.auto_throttle.then_some(
+                crate::services::activity_anomalies::AnomalyThrottle {
+                    rpc_requests_per_minute: config.throttle_rpc_requests_per_minute,
+                    duration: config.throttle_duration,
+                },
+            ),
+            alert_cooldown: config.alert_cooldown,
+            history_retention: config.history_retention,
+        }
+    }
+}
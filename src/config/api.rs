@@ -1,6 +1,9 @@
 //! API server configuration
 
+use axum::http::HeaderValue;
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// API server configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,19 +14,84 @@ pub struct ApiConfig {
     /// Port number to listen on
     pub port: u16,
 
-    /// Enable CORS
+    /// Enable CORS; when disabled, cross-origin preflights are rejected
     #[serde(default = "default_cors")]
     pub cors_enabled: bool,
 
+    /// Origins allowed to call the API from a browser; `*` allows any origin
+    #[serde(default = "default_cors_allowed_origins")]
+    pub cors_allowed_origins: Vec<String>,
+
+    /// How long browsers may cache a preflight response
+    #[serde(default = "default_cors_max_age", with = "humantime_serde")]
+    pub cors_max_age: Duration,
+
     /// API rate limit (requests per minute)
     #[serde(default = "default_rate_limit")]
     pub rate_limit: u32,
+
+    /// Paths never rate limited, so probes are not throttled
+    #[serde(default = "default_rate_limit_exempt")]
+    pub rate_limit_exempt: Vec<String>,
+
+    /// Hex SHA-256 hashes of accepted bearer tokens
+    #[serde(default)]
+    pub token_hashes: Vec<String>,
+
+    /// Environment variable with more comma-separated token hashes
+    #[serde(default = "default_token_hashes_env")]
+    pub token_hashes_env: String,
+
+    /// Serve read-only (GET) endpoints without a token
+    #[serde(default)]
+    pub public_read: bool,
+
+    /// How long in-flight requests may run after shutdown starts before
+    /// their connections are closed
+    #[serde(default = "default_shutdown_timeout", with = "humantime_serde")]
+    pub shutdown_timeout: Duration,
+
+    /// PEM certificate chain; with `tls_key_path`, the API is served over TLS
+    #[serde(default)]
+    pub tls_cert_path: Option<PathBuf>,
+
+    /// PEM private key for `tls_cert_path`
+    #[serde(default)]
+    pub tls_key_path: Option<PathBuf>,
+
+    /// Largest trigger script accepted by `PUT /tenants/{id}/scripts/{name}`, in bytes
+    #[serde(default = "default_max_script_size")]
+    pub max_script_size: usize,
+}
+
+fn default_max_script_size() -> usize {
+    crate::services::trigger_scripts::DEFAULT_MAX_SCRIPT_SIZE
+}
+
+fn default_shutdown_timeout() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_rate_limit_exempt() -> Vec<String> {
+    vec!["/healthz".to_string(), "/readyz".to_string()]
+}
+
+fn default_token_hashes_env() -> String {
+    "OZ_MONITOR_API_TOKEN_HASHES".to_string()
 }
 
 fn default_cors() -> bool {
     true
 }
 
+fn default_cors_allowed_origins() -> Vec<String> {
+    vec!["*".to_string()]
+}
+
+fn default_cors_max_age() -> Duration {
+    Duration::from_secs(3600)
+}
+
 fn default_rate_limit() -> u32 {
     100
 }
@@ -34,7 +102,17 @@ impl Default for ApiConfig {
             host: "0.0.0.0".to_string(),
             port: 3000,
             cors_enabled: true,
+            cors_allowed_origins: default_cors_allowed_origins(),
+            cors_max_age: default_cors_max_age(),
             rate_limit: 100,
+            rate_limit_exempt: default_rate_limit_exempt(),
+            token_hashes: Vec::new(),
+            token_hashes_env: default_token_hashes_env(),
+            public_read: false,
+            shutdown_timeout: default_shutdown_timeout(),
+            tls_cert_path: None,
+            tls_key_path: None,
+            max_script_size: default_max_script_size(),
         }
     }
 }
@@ -50,13 +128,88 @@ impl ApiConfig {
             return Err("port must be greater than 0".to_string());
         }
 
+        if self.cors_enabled {
+            self.validate_cors()?;
+        }
+
+        if self.shutdown_timeout.is_zero() {
+            return Err("shutdown_timeout must be greater than 0".to_string());
+        }
+
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(_), None) => {
+                return Err("tls_key_path must be set when tls_cert_path is".to_string())
+            }
+            (None, Some(_)) => {
+                return Err("tls_cert_path must be set when tls_key_path is".to_string())
+            }
+            _ => {}
+        }
+
+        if self.max_script_size == 0 {
+            return Err("max_script_size must be greater than 0".to_string());
+        }
+
         if self.rate_limit == 0 {
             return Err("rate_limit must be greater than 0".to_string());
         }
 
+        for hash in self.token_hashes() {
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err("token hashes must be hex-encoded SHA-256 digests".to_string());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_cors(&self) -> Result<(), String> {
+        if self.cors_allowed_origins.is_empty() {
+            return Err("cors_allowed_origins cannot be empty".to_string());
+        }
+
+        let any_origin = self.cors_allowed_origins.iter().any(|origin| origin == "*");
+        if any_origin && self.cors_allowed_origins.len() > 1 {
+            return Err("cors_allowed_origins cannot combine * with other origins".to_string());
+        }
+
+        for origin in self.cors_allowed_origins.iter().filter(|o| *o != "*") {
+            let scheme_ok = origin.starts_with("http://") || origin.starts_with("https://");
+            if !scheme_ok || origin.ends_with('/') || HeaderValue::from_str(origin).is_err() {
+                return Err(format!(
+                    "invalid CORS origin {}, expected scheme://host[:port]",
+                    origin
+                ));
+            }
+        }
+
+        // Browsers cap preflight caching at 2 hours (Chromium) to 24 hours (Firefox)
+        if self.cors_max_age > Duration::from_secs(86_400) {
+            return Err("cors_max_age must be at most 24 hours".to_string());
+        }
+
         Ok(())
     }
 
+    /// Accepted token hashes from the configuration and `token_hashes_env`
+    pub fn token_hashes(&self) -> Vec<String> {
+        let from_env = std::env::var(&self.token_hashes_env).unwrap_or_default();
+        self.token_hashes
+            .iter()
+            .map(|hash| hash.trim().to_string())
+            .chain(from_env.split(',').map(|hash| hash.trim().to_string()))
+            .filter(|hash| !hash.is_empty())
+            .collect()
+    }
+
+    /// Certificate and key paths if the API is served over TLS
+    pub fn tls_paths(&self) -> Option<(&Path, &Path)> {
+        match (&self.tls_cert_path, &self.tls_key_path) {
+            (Some(cert), Some(key)) => Some((cert.as_path(), key.as_path())),
+            _ => None,
+        }
+    }
+
     /// Get the socket address for binding
     pub fn socket_addr(&self) -> String {
         format!("{}:{}", self.host, self.port)
@@ -1,6 +1,7 @@
 //! Shared block watcher configuration
 
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 /// Shared block watcher configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +17,42 @@ pub struct SharedBlockWatcherConfig {
 
     /// Retry delay in milliseconds
     pub retry_delay_ms: u64,
+
+    /// Number of latest blocks to pre-fetch per network on startup (0 disables)
+    #[serde(default = "default_warm_cache_depth")]
+    pub warm_cache_depth: u64,
+
+    /// Maximum networks warmed concurrently on startup
+    #[serde(default = "default_warm_cache_concurrency")]
+    pub warm_cache_concurrency: usize,
+
+    /// Hand cursors over to a successor replica through Redis during deploys
+    #[serde(default)]
+    pub handoff: bool,
+
+    /// Lease TTL for the active replica when handoff is enabled
+    #[serde(default = "default_handoff_lease_ttl", with = "humantime_serde")]
+    pub handoff_lease_ttl: Duration,
+
+    /// Maximum blocks re-broadcast by one `POST /networks/{slug}/replay` request
+    #[serde(default = "default_max_replay_blocks")]
+    pub max_replay_blocks: u64,
+}
+
+fn default_warm_cache_depth() -> u64 {
+    10
+}
+
+fn default_warm_cache_concurrency() -> usize {
+    4
+}
+
+fn default_handoff_lease_ttl() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_max_replay_blocks() -> u64 {
+    1000
 }
 
 impl Default for SharedBlockWatcherConfig {
@@ -25,6 +62,11 @@ impl Default for SharedBlockWatcherConfig {
             max_blocks_per_fetch: 100,
             retry_attempts: 3,
             retry_delay_ms: 1000,
+            warm_cache_depth: default_warm_cache_depth(),
+            warm_cache_concurrency: default_warm_cache_concurrency(),
+            handoff: false,
+            handoff_lease_ttl: default_handoff_lease_ttl(),
+            max_replay_blocks: default_max_replay_blocks(),
         }
     }
 }
@@ -48,6 +90,22 @@ impl SharedBlockWatcherConfig {
             return Err("retry_delay_ms must be greater than 0".to_string());
         }
 
+        if self.warm_cache_depth > self.max_blocks_per_fetch {
+            return Err("warm_cache_depth must not exceed max_blocks_per_fetch".to_string());
+        }
+
+        if self.warm_cache_concurrency == 0 {
+            return Err("warm_cache_concurrency must be greater than 0".to_string());
+        }
+
+        if self.handoff_lease_ttl < Duration::from_secs(3) {
+            return Err("handoff_lease_ttl must be at least 3 seconds".to_string());
+        }
+
+        if self.max_replay_blocks == 0 {
+            return Err("max_replay_blocks must be greater than 0".to_string());
+        }
+
         Ok(())
     }
 }
@@ -60,8 +118,14 @@ impl From<SharedBlockWatcherConfig>
         crate::services::shared_block_watcher::SharedBlockWatcherConfig {
             channel_buffer_size: config.channel_buffer_size,
             max_blocks_per_fetch: config.max_blocks_per_fetch,
-            retry_attempts: config.retry_attempts,
-            retry_delay_ms: config.retry_delay_ms,
+            retry: crate::services::retry::RetryPolicy::new(
+                config.retry_attempts,
+                std::time::Duration::from_millis(config.retry_delay_ms),
+            ),
+            warm_cache_depth: config.warm_cache_depth,
+            warm_cache_concurrency: config.warm_cache_concurrency,
+            backpressure: false,
+            max_replay_blocks: config.max_replay_blocks,
         }
     }
 }
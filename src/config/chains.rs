@@ -0,0 +1,58 @@
+//! Chain type configuration
+
+use serde::{Deserialize, Serialize};
+
+use crate::services::chain_support::{ChainSupport, IMPLEMENTED_CHAINS};
+
+/// Chain types accepted by this deployment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChainsConfig {
+    /// Enabled chain types (e.g. `evm`, `stellar`); networks of other types are rejected
+    #[serde(default = "default_enabled")]
+    pub enabled: Vec<String>,
+}
+
+fn default_enabled() -> Vec<String> {
+    IMPLEMENTED_CHAINS
+        .iter()
+        .map(|chain| chain.to_string())
+        .collect()
+}
+
+impl Default for ChainsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+        }
+    }
+}
+
+impl ChainsConfig {
+    /// Validate chain type configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled.is_empty() {
+            return Err("chains.enabled must list at least one chain type".to_string());
+        }
+
+        if let Some(chain) = self
+            .enabled
+            .iter()
+            .find(|chain| !ChainSupport::is_implemented(chain))
+        {
+            return Err(format!(
+                "chain type {} in chains.enabled is not supported (supported: {})",
+                chain,
+                IMPLEMENTED_CHAINS.join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+// Re-export for backward compatibility with services
+impl From<ChainsConfig> for ChainSupport {
+    fn from(config: ChainsConfig) -> Self {
+        ChainSupport::new(config.enabled)
+    }
+}
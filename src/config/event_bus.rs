@@ -0,0 +1,65 @@
+//! Event bus configuration
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub use crate::services::event_bus::EventBusBackend;
+
+/// Bus the block watcher, workers and API exchange events on
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventBusConfig {
+    /// Deliver events within the process or to every process through Redis
+    #[serde(default)]
+    pub backend: EventBusBackend,
+
+    /// Directory block events are spilled to while Redis is unreachable
+    #[serde(default = "default_buffer_dir")]
+    pub buffer_dir: PathBuf,
+
+    /// Block events held in memory while Redis is unreachable before spilling to disk
+    #[serde(default = "default_buffer_memory")]
+    pub buffer_memory: usize,
+
+    /// How often publishing buffered block events is retried
+    #[serde(default = "default_buffer_retry_interval", with = "humantime_serde")]
+    pub buffer_retry_interval: Duration,
+}
+
+fn default_buffer_dir() -> PathBuf {
+    std::env::temp_dir().join("oz-monitor-orchestrator")
+}
+
+fn default_buffer_memory() -> usize {
+    1000
+}
+
+fn default_buffer_retry_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+impl Default for EventBusConfig {
+    fn default() -> Self {
+        Self {
+            backend: EventBusBackend::default(),
+            buffer_dir: default_buffer_dir(),
+            buffer_memory: default_buffer_memory(),
+            buffer_retry_interval: default_buffer_retry_interval(),
+        }
+    }
+}
+
+impl EventBusConfig {
+    /// Validate event bus configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.buffer_memory == 0 {
+            return Err("event_bus.buffer_memory must be greater than 0".to_string());
+        }
+
+        if self.buffer_retry_interval.is_zero() {
+            return Err("event_bus.buffer_retry_interval must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+}
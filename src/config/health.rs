@@ -0,0 +1,61 @@
+//! Health server configuration
+
+use serde::{Deserialize, Serialize};
+
+/// Liveness and readiness probe server, served in every service mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthConfig {
+    /// Serve `/healthz` and `/readyz`
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// Host address to bind to
+    #[serde(default = "default_host")]
+    pub host: String,
+
+    /// Port number to listen on
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_host() -> String {
+    "0.0.0.0".to_string()
+}
+
+fn default_port() -> u16 {
+    8080
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            host: default_host(),
+            port: default_port(),
+        }
+    }
+}
+
+impl HealthConfig {
+    /// Validate health server configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.host.is_empty() {
+            return Err("health host cannot be empty".to_string());
+        }
+
+        if self.port == 0 {
+            return Err("health port must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Get the socket address for binding
+    pub fn socket_addr(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+}
@@ -1,7 +1,11 @@
 //! Load balancer configuration
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::time::Duration;
+use uuid::Uuid;
+
+use crate::models::ShardBy;
 
 /// Load balancing strategy
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -18,6 +22,9 @@ pub enum LoadBalancingStrategy {
 
     /// Activity-based distribution
     ActivityBased,
+
+    /// Strategy registered at runtime under the given name
+    Custom(String),
 }
 
 impl Default for LoadBalancingStrategy {
@@ -26,6 +33,19 @@ impl Default for LoadBalancingStrategy {
     }
 }
 
+/// Tenant whose monitors are split across several workers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardedTenantConfig {
+    /// Tenant identifier
+    pub tenant_id: Uuid,
+
+    /// Split by monitor or by network
+    pub shard_by: ShardBy,
+
+    /// Number of shards (at least 2)
+    pub shards: u32,
+}
+
 /// Load balancer configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoadBalancerConfig {
@@ -42,6 +62,102 @@ pub struct LoadBalancerConfig {
     /// Minimum interval between rebalances
     #[serde(with = "humantime_serde")]
     pub min_rebalance_interval: Duration,
+
+    /// Check every `min_rebalance_interval` whether the pool needs
+    /// rebalancing and rebalance it; when disabled, only `POST /rebalance` does
+    #[serde(default = "default_auto_rebalance")]
+    pub auto_rebalance: bool,
+
+    /// Age after which workers' CPU and memory samples are too stale for the
+    /// rebalance check, which then compares tenant counts instead of load scores
+    #[serde(default = "default_load_metrics_max_age", with = "humantime_serde")]
+    pub load_metrics_max_age: Duration,
+
+    /// Tenants too large for one worker, split across several
+    #[serde(default)]
+    pub sharded_tenants: Vec<ShardedTenantConfig>,
+
+    /// Utilization (0.0 to 1.0) `GET /capacity` sizes the worker pool for
+    #[serde(default = "default_target_utilization")]
+    pub target_utilization: f64,
+
+    /// Fewest workers `GET /capacity` suggests
+    #[serde(default = "default_min_workers")]
+    pub min_workers: usize,
+
+    /// Most workers `GET /capacity` suggests; unbounded if unset
+    #[serde(default)]
+    pub max_workers: Option<usize>,
+
+    /// How long a worker that gave up a tenant is preferred when the tenant
+    /// is placed again, while its caches may still be warm; 0 disables
+    #[serde(default = "default_affinity_ttl", with = "humantime_serde")]
+    pub affinity_ttl: Duration,
+
+    /// Points each worker takes on the consistent hash ring
+    #[serde(default = "default_virtual_nodes")]
+    pub virtual_nodes: u32,
+
+    /// How long a worker may go without a heartbeat before it is evicted and
+    /// its tenants are reassigned
+    #[serde(default = "default_worker_timeout", with = "humantime_serde")]
+    pub worker_timeout: Duration,
+
+    /// Assignment changes kept in memory per tenant for
+    /// `GET /tenants/{id}/assignment-history`; the oldest are dropped first
+    #[serde(default = "default_max_assignment_history")]
+    pub max_assignment_history: usize,
+
+    /// How long a process reuses its copy of the assignments and worker
+    /// registry shared in Redis before reading them again
+    #[serde(default = "default_shared_state_ttl", with = "humantime_serde")]
+    pub shared_state_ttl: Duration,
+
+    /// How long consistent hashing keeps a tenant pinned to its worker after
+    /// the pin was set or last honored; an expired pin is dropped and the
+    /// tenant placed from the ring again. 0 keeps pins until their worker leaves
+    #[serde(default = "default_pin_ttl", with = "humantime_serde")]
+    pub pin_ttl: Duration,
+}
+
+fn default_auto_rebalance() -> bool {
+    true
+}
+
+fn default_load_metrics_max_age() -> Duration {
+    Duration::from_secs(120)
+}
+
+fn default_target_utilization() -> f64 {
+    0.7
+}
+
+fn default_min_workers() -> usize {
+    1
+}
+
+fn default_affinity_ttl() -> Duration {
+    Duration::from_secs(600)
+}
+
+fn default_virtual_nodes() -> u32 {
+    100
+}
+
+fn default_worker_timeout() -> Duration {
+    Duration::from_secs(90)
+}
+
+fn default_max_assignment_history() -> usize {
+    50
+}
+
+fn default_shared_state_ttl() -> Duration {
+    Duration::from_secs(2)
+}
+
+fn default_pin_ttl() -> Duration {
+    Duration::from_secs(86_400)
 }
 
 impl Default for LoadBalancerConfig {
@@ -51,6 +167,18 @@ impl Default for LoadBalancerConfig {
             max_tenants_per_worker: 50,
             rebalance_threshold: 0.2, // 20% imbalance triggers rebalance
             min_rebalance_interval: Duration::from_secs(300), // 5 minutes
+            auto_rebalance: default_auto_rebalance(),
+            load_metrics_max_age: default_load_metrics_max_age(),
+            sharded_tenants: Vec::new(),
+            target_utilization: default_target_utilization(),
+            min_workers: default_min_workers(),
+            max_workers: None,
+            affinity_ttl: default_affinity_ttl(),
+            virtual_nodes: default_virtual_nodes(),
+            worker_timeout: default_worker_timeout(),
+            max_assignment_history: default_max_assignment_history(),
+            shared_state_ttl: default_shared_state_ttl(),
+            pin_ttl: default_pin_ttl(),
         }
     }
 }
@@ -66,10 +194,56 @@ impl LoadBalancerConfig {
             return Err("rebalance_threshold must be between 0.0 and 1.0".to_string());
         }
 
+        if self.target_utilization <= 0.0 || self.target_utilization > 1.0 {
+            return Err("target_utilization must be greater than 0.0 and at most 1.0".to_string());
+        }
+
+        if self.load_metrics_max_age.is_zero() {
+            return Err("load_metrics_max_age must be greater than 0".to_string());
+        }
+
+        if self.virtual_nodes == 0 {
+            return Err("virtual_nodes must be greater than 0".to_string());
+        }
+
+        if self.worker_timeout.is_zero() {
+            return Err("worker_timeout must be greater than 0".to_string());
+        }
+
+        if self.max_assignment_history == 0 {
+            return Err("max_assignment_history must be greater than 0".to_string());
+        }
+
+        if self.max_workers.is_some_and(|max| max < self.min_workers) {
+            return Err("max_workers must not be less than min_workers".to_string());
+        }
+
+        if let LoadBalancingStrategy::Custom(name) = &self.strategy {
+            if name.is_empty() {
+                return Err("custom strategy name must not be empty".to_string());
+            }
+        }
+
         if self.min_rebalance_interval.as_secs() < 60 {
             return Err("min_rebalance_interval must be at least 60 seconds".to_string());
         }
 
+        let mut seen = HashSet::new();
+        for sharded in &self.sharded_tenants {
+            if sharded.shards < 2 {
+                return Err(format!(
+                    "sharded tenant {} must have at least 2 shards",
+                    sharded.tenant_id
+                ));
+            }
+            if !seen.insert(sharded.tenant_id) {
+                return Err(format!(
+                    "sharded tenant {} is configured more than once",
+                    sharded.tenant_id
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -91,6 +265,9 @@ impl From<LoadBalancerConfig> for crate::services::load_balancer::LoadBalancerCo
             LoadBalancingStrategy::ActivityBased => {
                 crate::services::load_balancer::LoadBalancingStrategy::ActivityBased
             }
+            LoadBalancingStrategy::Custom(name) => {
+                crate::services::load_balancer::LoadBalancingStrategy::Custom(name)
+            }
         };
 
         crate::services::load_balancer::LoadBalancerConfig {
@@ -98,6 +275,29 @@ impl From<LoadBalancerConfig> for crate::services::load_balancer::LoadBalancerCo
             max_tenants_per_worker: config.max_tenants_per_worker,
             rebalance_threshold: config.rebalance_threshold,
             min_rebalance_interval: config.min_rebalance_interval,
+            load_metrics_max_age: config.load_metrics_max_age,
+            sharded_tenants: config
+                .sharded_tenants
+                .into_iter()
+                .map(|sharded| {
+                    (
+                        sharded.tenant_id,
+                        crate::services::load_balancer::TenantSharding {
+                            shard_by: sharded.shard_by,
+                            shard_count: sharded.shards,
+                        },
+                    )
+                })
+                .collect(),
+            target_utilization: config.target_utilization,
+            min_workers: config.min_workers,
+            max_workers: config.max_workers,
+            affinity_ttl: config.affinity_ttl,
+            virtual_nodes: config.virtual_nodes,
+            worker_timeout: config.worker_timeout,
+            max_assignment_history: config.max_assignment_history,
+            shared_state_ttl: config.shared_state_ttl,
+            pin_ttl: config.pin_ttl,
         }
     }
 }
@@ -4,21 +4,43 @@
 //! following a similar pattern to OpenZeppelin Monitor's configuration layout.
 
 // Sub-modules for each configuration type
+pub mod anomalies;
 pub mod api;
 pub mod block_cache;
 pub mod block_watcher;
+pub mod chains;
 pub mod error;
+pub mod event_bus;
+pub mod health;
 pub mod load_balancer;
 pub mod orchestrator;
+pub mod retry;
+pub mod rpc_costs;
 pub mod service_mode;
+pub mod shutdown;
+pub mod startup_checks;
+pub mod stellar_endpoints;
+pub mod webhooks;
 pub mod worker;
+pub mod worker_identity;
 
 // Re-export main types
+pub use anomalies::AnomalyConfig;
 pub use api::ApiConfig;
 pub use block_cache::BlockCacheConfig;
 pub use block_watcher::SharedBlockWatcherConfig;
+pub use chains::ChainsConfig;
 pub use error::ConfigError;
-pub use load_balancer::{LoadBalancerConfig, LoadBalancingStrategy};
+pub use event_bus::EventBusConfig;
+pub use health::HealthConfig;
+pub use load_balancer::{LoadBalancerConfig, LoadBalancingStrategy, ShardedTenantConfig};
 pub use orchestrator::OrchestratorConfig;
+pub use retry::RetryConfig;
+pub use rpc_costs::RpcCostConfig;
 pub use service_mode::ServiceMode;
+pub use shutdown::ShutdownConfig;
+pub use startup_checks::StartupChecksConfig;
+pub use stellar_endpoints::StellarEndpointsConfig;
+pub use webhooks::AssignmentWebhookConfig;
 pub use worker::WorkerConfig;
+pub use worker_identity::WorkerIdentity;
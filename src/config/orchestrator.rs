@@ -4,7 +4,9 @@ use config::{Config, ConfigError, File};
 use serde::{Deserialize, Serialize};
 
 use super::{
-    ApiConfig, BlockCacheConfig, LoadBalancerConfig, ServiceMode, SharedBlockWatcherConfig,
+    AnomalyConfig, ApiConfig, AssignmentWebhookConfig, BlockCacheConfig, ChainsConfig,
+    EventBusConfig, HealthConfig, LoadBalancerConfig, RetryConfig, RpcCostConfig, ServiceMode,
+    SharedBlockWatcherConfig, ShutdownConfig, StartupChecksConfig, StellarEndpointsConfig,
     WorkerConfig,
 };
 
@@ -17,6 +19,10 @@ pub struct OrchestratorConfig {
     /// Redis connection URL
     pub redis_url: String,
 
+    /// Deployment namespace prepended to every Redis key and channel
+    #[serde(default)]
+    pub redis_namespace: Option<String>,
+
     /// Service mode (worker, block-watcher, api)
     #[serde(default = "default_service_mode")]
     pub service_mode: ServiceMode,
@@ -40,6 +46,46 @@ pub struct OrchestratorConfig {
     /// API server configuration
     #[serde(default)]
     pub api: ApiConfig,
+
+    /// Liveness and readiness probe server
+    #[serde(default)]
+    pub health: HealthConfig,
+
+    /// Webhooks fired on assignment lifecycle events
+    #[serde(default)]
+    pub webhooks: Vec<AssignmentWebhookConfig>,
+
+    /// Retry policy for RPC clients, cache connections and notifications
+    #[serde(default)]
+    pub retry: RetryConfig,
+
+    /// Attribution of RPC usage to tenants for chargeback
+    #[serde(default)]
+    pub rpc_costs: RpcCostConfig,
+
+    /// Detection of sudden spikes in tenant activity
+    #[serde(default)]
+    pub anomalies: AnomalyConfig,
+
+    /// Chain types networks may use
+    #[serde(default)]
+    pub chains: ChainsConfig,
+
+    /// Dependency checks run before the service mode starts
+    #[serde(default)]
+    pub startup_checks: StartupChecksConfig,
+
+    /// Bus the block watcher, workers and API exchange events on
+    #[serde(default)]
+    pub event_bus: EventBusConfig,
+
+    /// Health checks and failover of Stellar Horizon and Soroban RPC endpoints
+    #[serde(default)]
+    pub stellar_endpoints: StellarEndpointsConfig,
+
+    /// Ordered, bounded stop on Ctrl+C or SIGTERM
+    #[serde(default)]
+    pub shutdown: ShutdownConfig,
 }
 
 fn default_service_mode() -> ServiceMode {
@@ -72,15 +118,69 @@ impl OrchestratorConfig {
             return Err("Redis URL is required".to_string());
         }
 
+        if let Some(namespace) = &self.redis_namespace {
+            if namespace.is_empty() || namespace.contains(char::is_whitespace) {
+                return Err("redis_namespace must be non-empty without whitespace".to_string());
+            }
+        }
+
+        if self.worker.standby && self.service_mode != ServiceMode::Worker {
+            return Err("worker.standby is only supported in worker mode".to_string());
+        }
+
+        if self.load_balancer.worker_timeout <= self.worker.health_check_interval {
+            return Err(
+                "load_balancer.worker_timeout must be longer than worker.health_check_interval"
+                    .to_string(),
+            );
+        }
+
+        if self.shutdown.grace_period <= self.api.shutdown_timeout {
+            return Err(
+                "shutdown.grace_period must be longer than api.shutdown_timeout".to_string(),
+            );
+        }
+
         // Delegate validation to sub-configs
         self.worker.validate()?;
         self.load_balancer.validate()?;
         self.block_watcher.validate()?;
+        self.retry.validate()?;
+        self.api.validate()?;
+        self.health.validate()?;
+        self.rpc_costs.validate()?;
+        self.anomalies.validate()?;
+        self.chains.validate()?;
+        self.startup_checks.validate()?;
+        self.event_bus.validate()?;
+        self.stellar_endpoints.validate()?;
+        self.shutdown.validate()?;
+
+        for webhook in &self.webhooks {
+            webhook.validate()?;
+        }
 
         Ok(())
     }
 }
 
+impl From<&OrchestratorConfig> for crate::services::network_settings::NetworkSettingsDefaults {
+    fn from(config: &OrchestratorConfig) -> Self {
+        crate::services::network_settings::NetworkSettingsDefaults {
+            max_blocks_per_fetch: config.block_watcher.max_blocks_per_fetch,
+            fetch_retry_attempts: config.block_watcher.retry_attempts,
+            fetch_retry_delay: std::time::Duration::from_millis(
+                config.block_watcher.retry_delay_ms,
+            ),
+            rpc_retry_attempts: config.retry.max_attempts,
+            rpc_retry_base_delay: config.retry.base_delay,
+            rpc_retry_max_delay: config.retry.max_delay,
+            block_ttl: std::time::Duration::from_secs(config.block_cache.block_ttl),
+            latest_block_ttl: std::time::Duration::from_secs(config.block_cache.latest_block_ttl),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -90,12 +190,23 @@ mod tests {
         let config = OrchestratorConfig {
             database_url: "postgresql://test".to_string(),
             redis_url: "redis://test".to_string(),
+            redis_namespace: None,
             service_mode: ServiceMode::Worker,
             worker: Default::default(),
             block_cache: Default::default(),
             load_balancer: Default::default(),
             block_watcher: Default::default(),
             api: Default::default(),
+            health: Default::default(),
+            webhooks: Vec::new(),
+            retry: Default::default(),
+            rpc_costs: Default::default(),
+            anomalies: Default::default(),
+            chains: Default::default(),
+            startup_checks: Default::default(),
+            event_bus: Default::default(),
+            stellar_endpoints: Default::default(),
+            shutdown: Default::default(),
         };
 
         assert_eq!(config.validate(), Ok(()));
@@ -106,14 +217,55 @@ mod tests {
         let config = OrchestratorConfig {
             database_url: "".to_string(),
             redis_url: "redis://test".to_string(),
+            redis_namespace: None,
             service_mode: ServiceMode::Worker,
             worker: Default::default(),
             block_cache: Default::default(),
             load_balancer: Default::default(),
             block_watcher: Default::default(),
             api: Default::default(),
+            health: Default::default(),
+            webhooks: Vec::new(),
+            retry: Default::default(),
+            rpc_costs: Default::default(),
+            anomalies: Default::default(),
+            chains: Default::default(),
+            startup_checks: Default::default(),
+            event_bus: Default::default(),
+            stellar_endpoints: Default::default(),
+            shutdown: Default::default(),
         };
 
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_standby_requires_worker_mode() {
+        let mut config = OrchestratorConfig {
+            database_url: "postgresql://test".to_string(),
+            redis_url: "redis://test".to_string(),
+            redis_namespace: None,
+            service_mode: ServiceMode::All,
+            worker: Default::default(),
+            block_cache: Default::default(),
+            load_balancer: Default::default(),
+            block_watcher: Default::default(),
+            api: Default::default(),
+            health: Default::default(),
+            webhooks: Vec::new(),
+            retry: Default::default(),
+            rpc_costs: Default::default(),
+            anomalies: Default::default(),
+            chains: Default::default(),
+            startup_checks: Default::default(),
+            event_bus: Default::default(),
+            stellar_endpoints: Default::default(),
+            shutdown: Default::default(),
+        };
+        config.worker.standby = true;
+        assert!(config.validate().is_err());
+
+        config.service_mode = ServiceMode::Worker;
+        assert_eq!(config.validate(), Ok(()));
+    }
 }
@@ -0,0 +1,61 @@
+//! Retry configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Retry policy shared by RPC clients, cache connections and notification delivery
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Maximum attempts including the first call
+    pub max_attempts: u32,
+
+    /// Delay before the first retry
+    #[serde(with = "humantime_serde")]
+    pub base_delay: Duration,
+
+    /// Upper bound for any single delay
+    #[serde(with = "humantime_serde")]
+    pub max_delay: Duration,
+
+    /// Fraction of each delay randomized away (0.0 to 1.0)
+    pub jitter: f64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Validate retry configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_attempts == 0 {
+            return Err("retry max_attempts must be greater than 0".to_string());
+        }
+
+        if self.base_delay > self.max_delay {
+            return Err("retry base_delay must not exceed max_delay".to_string());
+        }
+
+        if !(0.0..=1.0).contains(&self.jitter) {
+            return Err("retry jitter must be between 0.0 and 1.0".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// Re-export for backward compatibility with services
+impl From<RetryConfig> for crate::services::retry::RetryPolicy {
+    fn from(config: RetryConfig) -> Self {
+        crate::services::retry::RetryPolicy::new(config.max_attempts, config.base_delay)
+            .with_max_delay(config.max_delay)
+            .with_jitter(config.jitter)
+    }
+}
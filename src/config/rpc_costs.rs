@@ -0,0 +1,77 @@
+//! RPC cost attribution configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Attribution of RPC usage to tenants for chargeback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RpcCostConfig {
+    /// Record per-tenant RPC usage
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Price of one million RPC requests, in the billing currency
+    #[serde(default = "default_cost_per_million_requests")]
+    pub cost_per_million_requests: f64,
+
+    /// Share of an RPC request's price charged for a request served from the block cache (0.0 to 1.0)
+    #[serde(default = "default_cached_request_weight")]
+    pub cached_request_weight: f64,
+
+    /// How often recorded usage is written to the database
+    #[serde(default = "default_flush_interval", with = "humantime_serde")]
+    pub flush_interval: Duration,
+}
+
+fn default_cost_per_million_requests() -> f64 {
+    1.0
+}
+
+fn default_cached_request_weight() -> f64 {
+    0.1
+}
+
+fn default_flush_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+impl Default for RpcCostConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            cost_per_million_requests: default_cost_per_million_requests(),
+            cached_request_weight: default_cached_request_weight(),
+            flush_interval: default_flush_interval(),
+        }
+    }
+}
+
+impl RpcCostConfig {
+    /// Validate RPC cost configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.cost_per_million_requests.is_nan() || self.cost_per_million_requests < 0.0 {
+            return Err("rpc_costs cost_per_million_requests must not be negative".to_string());
+        }
+
+        if !(0.0..=1.0).contains(&self.cached_request_weight) {
+            return Err("rpc_costs cached_request_weight must be between 0.0 and 1.0".to_string());
+        }
+
+        if self.flush_interval < Duration::from_secs(1) {
+            return Err("rpc_costs flush_interval must be at least 1 second".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// Re-export for backward compatibility with services
+impl From<RpcCostConfig> for crate::services::rpc_costs::RpcCostConfig {
+    fn from(config: RpcCostConfig) -> Self {
+        crate::services::rpc_costs::RpcCostConfig {
+            cost_per_million_requests: config.cost_per_million_requests,
+            cached_request_weight: config.cached_request_weight,
+            flush_interval: config.flush_interval,
+        }
+    }
+}
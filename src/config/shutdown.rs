@@ -0,0 +1,36 @@
+//! Graceful shutdown configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// How the service modes stop on Ctrl+C or SIGTERM
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// Time the API, workers, block watcher and cleanup have to stop, in that
+    /// order, before the rest is abandoned
+    #[serde(default = "default_grace_period", with = "humantime_serde")]
+    pub grace_period: Duration,
+}
+
+fn default_grace_period() -> Duration {
+    Duration::from_secs(60)
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            grace_period: default_grace_period(),
+        }
+    }
+}
+
+impl ShutdownConfig {
+    /// Validate shutdown configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.grace_period.is_zero() {
+            return Err("shutdown.grace_period must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+}
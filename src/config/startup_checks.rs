@@ -0,0 +1,62 @@
+//! Startup dependency check configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::services::startup_checks::StartupCheckOptions;
+
+/// Dependency checks run before a service mode starts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StartupChecksConfig {
+    /// Check the database schema, Redis and RPC endpoints before starting
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+
+    /// How long each check may take before it fails
+    #[serde(default = "default_timeout", with = "humantime_serde")]
+    pub timeout: Duration,
+
+    /// Refuse to start when an active network's RPC endpoints are unreachable;
+    /// otherwise unreachable networks are only reported
+    #[serde(default)]
+    pub require_rpc: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+impl Default for StartupChecksConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            timeout: default_timeout(),
+            require_rpc: false,
+        }
+    }
+}
+
+impl StartupChecksConfig {
+    /// Validate startup check configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.timeout.is_zero() {
+            return Err("startup_checks.timeout must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// Re-export for backward compatibility with services
+impl From<StartupChecksConfig> for StartupCheckOptions {
+    fn from(config: StartupChecksConfig) -> Self {
+        StartupCheckOptions {
+            timeout: config.timeout,
+            require_rpc: config.require_rpc,
+        }
+    }
+}
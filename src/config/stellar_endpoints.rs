@@ -0,0 +1,86 @@
+//! Stellar endpoint failover configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Health checks of the Horizon and Soroban RPC endpoints listed in Stellar
+/// networks' `rpc_urls`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StellarEndpointsConfig {
+    /// Leave endpoints failing their health checks out of new clients
+    #[serde(default = "default_failover")]
+    pub failover: bool,
+
+    /// How often every endpoint in use is checked
+    #[serde(default = "default_health_check_interval", with = "humantime_serde")]
+    pub health_check_interval: Duration,
+
+    /// Timeout of one health check
+    #[serde(default = "default_health_check_timeout", with = "humantime_serde")]
+    pub health_check_timeout: Duration,
+
+    /// Failed checks in a row before an endpoint is failed over
+    #[serde(default = "default_failure_threshold")]
+    pub failure_threshold: u32,
+}
+
+fn default_failover() -> bool {
+    true
+}
+
+fn default_health_check_interval() -> Duration {
+    Duration::from_secs(30)
+}
+
+fn default_health_check_timeout() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_failure_threshold() -> u32 {
+    2
+}
+
+impl Default for StellarEndpointsConfig {
+    fn default() -> Self {
+        Self {
+            failover: default_failover(),
+            health_check_interval: default_health_check_interval(),
+            health_check_timeout: default_health_check_timeout(),
+            failure_threshold: default_failure_threshold(),
+        }
+    }
+}
+
+impl StellarEndpointsConfig {
+    /// Validate Stellar endpoint configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if self.health_check_interval.is_zero() {
+            return Err(
+                "stellar_endpoints.health_check_interval must be greater than 0".to_string(),
+            );
+        }
+
+        if self.health_check_timeout.is_zero() {
+            return Err(
+                "stellar_endpoints.health_check_timeout must be greater than 0".to_string(),
+            );
+        }
+
+        if self.failure_threshold == 0 {
+            return Err("stellar_endpoints.failure_threshold must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// Re-export for backward compatibility with services
+impl From<StellarEndpointsConfig> for crate::services::endpoint_health::EndpointHealthConfig {
+    fn from(config: StellarEndpointsConfig) -> Self {
+        crate::services::endpoint_health::EndpointHealthConfig {
+            health_check_interval: config.health_check_interval,
+            timeout: config.health_check_timeout,
+            failure_threshold: config.failure_threshold,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Assignment webhook configuration
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+use crate::models::AssignmentEventKind;
+
+/// Outbound webhook fired on assignment lifecycle events
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentWebhookConfig {
+    /// Destination URL
+    pub url: String,
+
+    /// Events delivered to this webhook (empty means all events)
+    #[serde(default)]
+    pub events: Vec<AssignmentEventKind>,
+
+    /// Optional secret sent as a bearer token
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// Request timeout
+    #[serde(default = "default_timeout", with = "humantime_serde")]
+    pub timeout: Duration,
+}
+
+fn default_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+impl AssignmentWebhookConfig {
+    /// Validate webhook configuration
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.url.starts_with("http://") && !self.url.starts_with("https://") {
+            return Err(format!("webhook url must be http(s): {}", self.url));
+        }
+
+        if self.timeout.is_zero() {
+            return Err("webhook timeout must be greater than 0".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+// Re-export for backward compatibility with services
+impl From<AssignmentWebhookConfig> for crate::services::assignment_webhooks::AssignmentWebhook {
+    fn from(config: AssignmentWebhookConfig) -> Self {
+        crate::services::assignment_webhooks::AssignmentWebhook {
+            url: config.url,
+            events: config.events.into_iter().collect(),
+            secret: config.secret,
+            timeout: config.timeout,
+        }
+    }
+}
@@ -1,14 +1,27 @@
 //! Worker configuration
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
 
+pub use crate::services::trial_tenants::TrialLimits;
+pub use crate::services::worker_pool::BlockOverflowPolicy;
+
+use super::WorkerIdentity;
+use crate::models::RpcCapAction;
+
 /// Worker configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkerConfig {
     /// Maximum number of tenants per worker
     pub max_tenants_per_worker: usize,
 
+    /// Capacity of this worker relative to a worker of weight 1, scaling the
+    /// load balancer's tenant cap for it and normalizing its load; overridden
+    /// by `WORKER_CAPACITY_WEIGHT`
+    #[serde(default = "default_capacity_weight")]
+    pub capacity_weight: f64,
+
     /// Worker health check interval
     #[serde(with = "humantime_serde")]
     pub health_check_interval: Duration,
@@ -16,14 +29,242 @@ pub struct WorkerConfig {
     /// Tenant configuration reload interval
     #[serde(with = "humantime_serde")]
     pub tenant_reload_interval: Duration,
+
+    /// Interval for delivering notifications held during quiet hours
+    #[serde(default = "default_digest_flush_interval", with = "humantime_serde")]
+    pub digest_flush_interval: Duration,
+
+    /// Interval for writing the highest block each tenant processed per network
+    #[serde(
+        default = "default_checkpoint_flush_interval",
+        with = "humantime_serde"
+    )]
+    pub checkpoint_flush_interval: Duration,
+
+    /// Interval for sampling the worker process's CPU and memory usage
+    #[serde(default = "default_resource_sample_interval", with = "humantime_serde")]
+    pub resource_sample_interval: Duration,
+
+    /// Interval for publishing the activity metrics of the worker's tenants
+    #[serde(default = "default_tenant_metrics_interval", with = "humantime_serde")]
+    pub tenant_metrics_interval: Duration,
+
+    /// What happens to block events when the worker falls behind
+    #[serde(default)]
+    pub overflow_policy: BlockOverflowPolicy,
+
+    /// Directory for spilling block events, required by the `spill` overflow policy
+    #[serde(default)]
+    pub spill_dir: Option<PathBuf>,
+
+    /// Block events held in memory before spilling to disk
+    #[serde(default = "default_spill_threshold")]
+    pub spill_threshold: usize,
+
+    /// Skip Stellar ledgers without events from monitored contracts when all monitors are event-only
+    #[serde(default = "default_stellar_event_prefilter")]
+    pub stellar_event_prefilter: bool,
+
+    /// Actions applied to tenants exceeding `max_rpc_requests_per_minute` (empty disables enforcement)
+    #[serde(default = "default_rpc_cap_actions")]
+    pub rpc_cap_actions: Vec<RpcCapAction>,
+
+    /// Maximum monitors cached across all tenants of the worker
+    #[serde(default = "default_monitor_cache_capacity")]
+    pub monitor_cache_capacity: u64,
+
+    /// How long a tenant's monitors are cached before being reloaded
+    #[serde(default = "default_monitor_cache_ttl", with = "humantime_serde")]
+    pub monitor_cache_ttl: Duration,
+
+    /// Maximum contract specs cached by the worker
+    #[serde(default = "default_contract_spec_cache_capacity")]
+    pub contract_spec_cache_capacity: u64,
+
+    /// How long a contract spec is cached
+    #[serde(default = "default_contract_spec_cache_ttl", with = "humantime_serde")]
+    pub contract_spec_cache_ttl: Duration,
+
+    /// Maximum trigger condition results cached by the worker
+    #[serde(default = "default_trigger_condition_cache_capacity")]
+    pub trigger_condition_cache_capacity: u64,
+
+    /// How long a trigger condition result is reused, e.g. by dead letter redeliveries
+    #[serde(
+        default = "default_trigger_condition_cache_ttl",
+        with = "humantime_serde"
+    )]
+    pub trigger_condition_cache_ttl: Duration,
+
+    /// Consecutive failing blocks after which a monitor is deactivated (0 disables)
+    #[serde(default = "default_monitor_failure_threshold")]
+    pub monitor_failure_threshold: u32,
+
+    /// Directory to record block events and monitor snapshots to, for `replay`
+    #[serde(default)]
+    pub record_dir: Option<PathBuf>,
+
+    /// How long a worker records after starting
+    #[serde(default = "default_record_window", with = "humantime_serde")]
+    pub record_window: Duration,
+
+    /// Start as a warm standby without tenants, promoted by the coordinator
+    /// when a worker fails or the pool runs over its target utilization
+    #[serde(default)]
+    pub standby: bool,
+
+    /// Where the worker ID comes from when `WORKER_ID` is not set; a stable
+    /// identity lets a restarted worker take back its previous assignments
+    #[serde(default)]
+    pub identity: WorkerIdentity,
+
+    /// Matches posted to a tenant's match webhook in one request
+    #[serde(default = "default_match_webhook_batch_size")]
+    pub match_webhook_batch_size: usize,
+
+    /// Interval for posting matches waiting for a tenant's match webhook
+    #[serde(
+        default = "default_match_webhook_flush_interval",
+        with = "humantime_serde"
+    )]
+    pub match_webhook_flush_interval: Duration,
+
+    /// Timeout of each match webhook request
+    #[serde(default = "default_match_webhook_timeout", with = "humantime_serde")]
+    pub match_webhook_timeout: Duration,
+
+    /// Scheduling class of tenants on trial: fewer blocks, monitors and RPC requests
+    #[serde(default)]
+    pub trial: TrialLimits,
+
+    /// How often the database is checked; while it is unreachable the worker
+    /// filters with cached configurations and queues match writes
+    #[serde(default = "default_db_probe_interval", with = "humantime_serde")]
+    pub db_probe_interval: Duration,
+
+    /// Match writes queued while the database is unreachable before the
+    /// oldest are dropped
+    #[serde(default = "default_max_queued_writes")]
+    pub max_queued_writes: usize,
+}
+
+fn default_capacity_weight() -> f64 {
+    1.0
+}
+
+fn default_db_probe_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_max_queued_writes() -> usize {
+    10_000
+}
+
+fn default_match_webhook_batch_size() -> usize {
+    100
+}
+
+fn default_match_webhook_flush_interval() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_match_webhook_timeout() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_record_window() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_monitor_failure_threshold() -> u32 {
+    100
+}
+
+fn default_monitor_cache_capacity() -> u64 {
+    10_000
+}
+
+fn default_monitor_cache_ttl() -> Duration {
+    Duration::from_secs(600)
+}
+
+fn default_contract_spec_cache_capacity() -> u64 {
+    10_000
+}
+
+fn default_contract_spec_cache_ttl() -> Duration {
+    Duration::from_secs(3600)
+}
+
+fn default_trigger_condition_cache_capacity() -> u64 {
+    10_000
+}
+
+fn default_trigger_condition_cache_ttl() -> Duration {
+    Duration::from_secs(86_400)
+}
+
+fn default_rpc_cap_actions() -> Vec<RpcCapAction> {
+    vec![RpcCapAction::Throttle, RpcCapAction::Notify]
+}
+
+fn default_stellar_event_prefilter() -> bool {
+    true
+}
+
+fn default_spill_threshold() -> usize {
+    1000
+}
+
+fn default_digest_flush_interval() -> Duration {
+    Duration::from_secs(60)
+}
+
+fn default_checkpoint_flush_interval() -> Duration {
+    Duration::from_secs(10)
+}
+
+fn default_resource_sample_interval() -> Duration {
+    Duration::from_secs(15)
+}
+
+fn default_tenant_metrics_interval() -> Duration {
+    Duration::from_secs(60)
 }
 
 impl Default for WorkerConfig {
     fn default() -> Self {
         Self {
             max_tenants_per_worker: 50,
+            capacity_weight: default_capacity_weight(),
             health_check_interval: Duration::from_secs(30),
             tenant_reload_interval: Duration::from_secs(300), // 5 minutes
+            digest_flush_interval: default_digest_flush_interval(),
+            checkpoint_flush_interval: default_checkpoint_flush_interval(),
+            resource_sample_interval: default_resource_sample_interval(),
+            tenant_metrics_interval: default_tenant_metrics_interval(),
+            overflow_policy: BlockOverflowPolicy::default(),
+            spill_dir: None,
+            spill_threshold: default_spill_threshold(),
+            stellar_event_prefilter: default_stellar_event_prefilter(),
+            rpc_cap_actions: default_rpc_cap_actions(),
+            monitor_cache_capacity: default_monitor_cache_capacity(),
+            monitor_cache_ttl: default_monitor_cache_ttl(),
+            contract_spec_cache_capacity: default_contract_spec_cache_capacity(),
+            contract_spec_cache_ttl: default_contract_spec_cache_ttl(),
+            trigger_condition_cache_capacity: default_trigger_condition_cache_capacity(),
+            trigger_condition_cache_ttl: default_trigger_condition_cache_ttl(),
+            monitor_failure_threshold: default_monitor_failure_threshold(),
+            record_dir: None,
+            record_window: default_record_window(),
+            standby: false,
+            identity: WorkerIdentity::default(),
+            match_webhook_batch_size: default_match_webhook_batch_size(),
+            match_webhook_flush_interval: default_match_webhook_flush_interval(),
+            match_webhook_timeout: default_match_webhook_timeout(),
+            trial: TrialLimits::default(),
+            db_probe_interval: default_db_probe_interval(),
+            max_queued_writes: default_max_queued_writes(),
         }
     }
 }
@@ -35,6 +276,10 @@ impl WorkerConfig {
             return Err("max_tenants_per_worker must be greater than 0".to_string());
         }
 
+        if !(self.capacity_weight.is_finite() && self.capacity_weight > 0.0) {
+            return Err("capacity_weight must be greater than 0".to_string());
+        }
+
         if self.health_check_interval.as_secs() < 5 {
             return Err("health_check_interval must be at least 5 seconds".to_string());
         }
@@ -43,7 +288,76 @@ impl WorkerConfig {
             return Err("tenant_reload_interval must be at least 30 seconds".to_string());
         }
 
-        Ok(())
+        if self.digest_flush_interval.is_zero() {
+            return Err("digest_flush_interval must be greater than 0".to_string());
+        }
+
+        if self.checkpoint_flush_interval.is_zero() {
+            return Err("checkpoint_flush_interval must be greater than 0".to_string());
+        }
+
+        if self.resource_sample_interval.as_secs() < 1 {
+            return Err("resource_sample_interval must be at least 1 second".to_string());
+        }
+
+        if self.tenant_metrics_interval.as_secs() < 1 {
+            return Err("tenant_metrics_interval must be at least 1 second".to_string());
+        }
+
+        match (self.overflow_policy, &self.spill_dir) {
+            (BlockOverflowPolicy::Spill, None) => {
+                return Err("overflow_policy spill requires spill_dir".to_string());
+            }
+            (BlockOverflowPolicy::Block | BlockOverflowPolicy::DropOldest, Some(_)) => {
+                return Err("spill_dir is only used with overflow_policy spill".to_string());
+            }
+            _ => {}
+        }
+
+        if self.spill_threshold == 0 {
+            return Err("spill_threshold must be greater than 0".to_string());
+        }
+
+        if self.monitor_cache_capacity == 0
+            || self.contract_spec_cache_capacity == 0
+            || self.trigger_condition_cache_capacity == 0
+        {
+            return Err("cache capacities must be greater than 0".to_string());
+        }
+
+        if self.monitor_cache_ttl.is_zero()
+            || self.contract_spec_cache_ttl.is_zero()
+            || self.trigger_condition_cache_ttl.is_zero()
+        {
+            return Err("cache TTLs must be greater than 0".to_string());
+        }
+
+        if self.record_dir.is_some() && self.record_window.is_zero() {
+            return Err("record_window must be greater than 0".to_string());
+        }
+
+        if self.match_webhook_batch_size == 0 {
+            return Err("match_webhook_batch_size must be greater than 0".to_string());
+        }
+
+        if self.match_webhook_flush_interval.is_zero() || self.match_webhook_timeout.is_zero() {
+            return Err(
+                "match_webhook_flush_interval and match_webhook_timeout must be greater than 0"
+                    .to_string(),
+            );
+        }
+
+        if self.db_probe_interval.is_zero() {
+            return Err("db_probe_interval must be greater than 0".to_string());
+        }
+
+        if self.max_queued_writes == 0 {
+            return Err("max_queued_writes must be greater than 0".to_string());
+        }
+
+        self.trial.validate()?;
+
+        self.identity.validate()
     }
 }
 
@@ -54,6 +368,32 @@ impl From<WorkerConfig> for crate::services::worker_pool::WorkerConfig {
             max_tenants_per_worker: config.max_tenants_per_worker,
             health_check_interval: config.health_check_interval,
             tenant_reload_interval: config.tenant_reload_interval,
+            digest_flush_interval: config.digest_flush_interval,
+            checkpoint_flush_interval: config.checkpoint_flush_interval,
+            overflow_policy: config.overflow_policy,
+            spill_dir: config.spill_dir,
+            spill_threshold: config.spill_threshold,
+            stellar_event_prefilter: config.stellar_event_prefilter,
+            rpc_cap_actions: config.rpc_cap_actions,
+            cache: crate::services::oz_monitor_integration::OzMonitorCacheConfig {
+                monitor_capacity: config.monitor_cache_capacity,
+                monitor_ttl: config.monitor_cache_ttl,
+                contract_spec_capacity: config.contract_spec_cache_capacity,
+                contract_spec_ttl: config.contract_spec_cache_ttl,
+                trigger_condition_capacity: config.trigger_condition_cache_capacity,
+                trigger_condition_ttl: config.trigger_condition_cache_ttl,
+                ..Default::default()
+            },
+            monitor_failure_threshold: config.monitor_failure_threshold,
+            record_dir: config.record_dir,
+            record_window: config.record_window,
+            standby: config.standby,
+            match_webhook_batch_size: config.match_webhook_batch_size,
+            match_webhook_flush_interval: config.match_webhook_flush_interval,
+            match_webhook_timeout: config.match_webhook_timeout,
+            trial: config.trial,
+            db_probe_interval: config.db_probe_interval,
+            max_queued_writes: config.max_queued_writes,
         }
     }
 }
@@ -0,0 +1,134 @@
+//! Worker identity configuration
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Where a worker's ID comes from when `WORKER_ID` is not set.
+///
+/// Assignments, consistent hashing affinity and the assignment store are
+/// keyed by worker ID, so a worker restarting under the same ID takes back
+/// its previous tenants; a random ID makes every restart a new worker.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum WorkerIdentity {
+    /// `worker-<uuid>`, new on every start
+    #[default]
+    Random,
+
+    /// `worker-<hostname>`, stable wherever the hostname survives restarts
+    Hostname,
+
+    /// `worker-<ordinal>` from a Kubernetes StatefulSet pod's hostname
+    /// (`<statefulset>-<ordinal>`); only unique with one worker StatefulSet
+    /// per Redis namespace
+    #[serde(rename = "statefulset_ordinal")]
+    StatefulSetOrdinal,
+
+    /// Value of an environment variable, e.g. one set from the pod name
+    Env {
+        /// Variable holding the worker ID
+        var: String,
+    },
+}
+
+impl WorkerIdentity {
+    /// Validate the identity source
+    pub fn validate(&self) -> Result<(), String> {
+        if let WorkerIdentity::Env { var } = self {
+            if var.is_empty() {
+                return Err("worker.identity.var must not be empty".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Worker ID from this source
+    pub fn resolve(&self) -> Result<String> {
+        match self {
+            WorkerIdentity::Random => Ok(format!("worker-{}", Uuid::new_v4())),
+            WorkerIdentity::Hostname => Ok(format!("worker-{}", hostname()?)),
+            WorkerIdentity::StatefulSetOrdinal => {
+                let hostname = hostname()?;
+                let ordinal = statefulset_ordinal(&hostname).with_context(|| {
+                    format!(
+                        "Hostname {} does not end in a StatefulSet ordinal",
+                        hostname
+                    )
+                })?;
+                Ok(format!("worker-{}", ordinal))
+            }
+            WorkerIdentity::Env { var } => std::env::var(var)
+                .ok()
+                .map(|id| id.trim().to_string())
+                .filter(|id| !id.is_empty())
+                .with_context(|| format!("Worker ID variable {} is not set", var)),
+        }
+    }
+}
+
+/// Hostname of this machine or pod
+fn hostname() -> Result<String> {
+    std::env::var("HOSTNAME")
+        .ok()
+        .or_else(|| std::fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+        .map(|hostname| hostname.trim().to_string())
+        .filter(|hostname| !hostname.is_empty())
+        .context("Failed to determine the hostname")
+}
+
+/// Ordinal of a StatefulSet pod from its `<statefulset>-<ordinal>` hostname
+fn statefulset_ordinal(hostname: &str) -> Option<u32> {
+    let (name, ordinal) = hostname.rsplit_once('-')?;
+    if name.is_empty() || ordinal.starts_with('+') {
+        return None;
+    }
+    ordinal.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_statefulset_ordinal() {
+        assert_eq!(statefulset_ordinal("oz-monitor-worker-0"), Some(0));
+        assert_eq!(statefulset_ordinal("oz-monitor-worker-12"), Some(12));
+        assert_eq!(statefulset_ordinal("oz-monitor-worker-7f9c4"), None);
+        assert_eq!(statefulset_ordinal("worker"), None);
+        assert_eq!(statefulset_ordinal("-3"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert!(WorkerIdentity::Random
+            .resolve()
+            .unwrap()
+            .starts_with("worker-"));
+
+        let identity = WorkerIdentity::Env {
+            var: "OZ_MONITOR_TEST_WORKER_IDENTITY".to_string(),
+        };
+        assert!(identity.resolve().is_err());
+        std::env::set_var("OZ_MONITOR_TEST_WORKER_IDENTITY", "worker-eu-1");
+        assert_eq!(identity.resolve().unwrap(), "worker-eu-1");
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let identity: WorkerIdentity =
+            serde_json::from_value(serde_json::json!({"source": "statefulset_ordinal"})).unwrap();
+        assert_eq!(identity, WorkerIdentity::StatefulSetOrdinal);
+
+        let identity: WorkerIdentity =
+            serde_json::from_value(serde_json::json!({"source": "env", "var": "POD_NAME"}))
+                .unwrap();
+        assert_eq!(
+            identity,
+            WorkerIdentity::Env {
+                var: "POD_NAME".to_string()
+            }
+        );
+    }
+}
@@ -1,6 +1,9 @@
+pub mod api;
 pub mod config;
 pub mod models;
+pub mod orchestrator;
 pub mod repositories;
 pub mod services;
 
 pub use config::OrchestratorConfig;
+pub use orchestrator::{Orchestrator, OrchestratorBuilder};
@@ -5,20 +5,24 @@
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use openzeppelin_monitor::repositories::NetworkRepositoryTrait;
+use sqlx::PgPool;
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::signal;
-use tracing::{error, info};
+use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use uuid::Uuid;
 
+use openzeppelin_monitor::models::Monitor;
 use oz_monitor_orchestrator::{
     config::{OrchestratorConfig, ServiceMode},
-    repositories::TenantAwareNetworkRepository,
+    models::MigrationAction,
     services::{
-        block_cache::BlockCacheService, cached_client_pool::CachedClientPool,
-        load_balancer::LoadBalancer, oz_monitor_integration::OzMonitorServices,
-        shared_block_watcher::SharedBlockWatcher, worker_pool::MonitorWorkerPool,
+        BlockCacheService, CachedClientPool, FilterDebugService, NetworkSettingsResolver,
+        OzMonitorServices, RecordedSession, RedisKeyspace, ReplayMatch, TemplateCatalog,
+        TemplateService, TenantMigrationService,
     },
+    Orchestrator,
 };
 
 #[derive(Parser)]
@@ -39,361 +43,396 @@ enum Commands {
     Api,
     /// Run all services (for development)
     All,
+    /// Browse and instantiate monitor templates
+    Templates {
+        #[command(subcommand)]
+        command: TemplateCommands,
+    },
+    /// Toggle filter debug sampling for a tenant
+    FilterDebug {
+        #[command(subcommand)]
+        command: FilterDebugCommands,
+    },
+    /// Re-run a recorded worker session through the filters
+    Replay {
+        /// Recording written by a worker with `worker.record_dir` set
+        session: PathBuf,
+        /// JSON file mapping tenant IDs to monitors that replace the recorded ones
+        #[arg(long)]
+        monitors: Option<PathBuf>,
+    },
+    /// Copy a tenant from this orchestrator's database to another one
+    MigrateTenant {
+        /// Tenant to copy
+        #[arg(long)]
+        tenant: Uuid,
+        /// Database the tenant is copied into
+        #[arg(long)]
+        target_database_url: String,
+        /// Tenant ID on the target; defaults to the source tenant ID
+        #[arg(long)]
+        target_tenant: Option<Uuid>,
+        /// Days of recorded matches copied along; 0 copies configuration only
+        #[arg(long, default_value_t = 7)]
+        match_days: i64,
+        /// Print the changes without writing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Print a network's settings after all override layers, with where each came from
+    NetworkSettings {
+        /// Network slug
+        network: String,
+    },
 }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::new(
-            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
-        ))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
-
-    // Parse CLI arguments
-    let cli = Cli::parse();
-
-    // Load configuration
-    let config = OrchestratorConfig::load().context("Failed to load configuration")?;
-
-    config
-        .validate()
-        .map_err(|e| anyhow::anyhow!("Invalid configuration: {}", e))?;
-
-    info!("Starting OZ Monitor Orchestrator");
-
-    // Determine service mode
-    let service_mode = match cli.command {
-        Some(Commands::Worker) => ServiceMode::Worker,
-        Some(Commands::BlockWatcher) => ServiceMode::BlockWatcher,
-        Some(Commands::Api) => ServiceMode::Api,
-        Some(Commands::All) => ServiceMode::All,
-        None => config.service_mode.clone(),
-    };
-
-    // Connect to database
-    let db_pool = Arc::new(
-        sqlx::PgPool::connect(&config.database_url)
-            .await
-            .context("Failed to connect to database")?,
-    );
-
-    // Initialize services based on mode
-    match service_mode {
-        ServiceMode::Worker => run_worker(config, db_pool).await?,
-        ServiceMode::BlockWatcher => run_block_watcher(config, db_pool).await?,
-        ServiceMode::Api => run_api(config, db_pool).await?,
-        ServiceMode::All => run_all(config, db_pool).await?,
-    }
-
-    Ok(())
+#[derive(Subcommand)]
+enum TemplateCommands {
+    /// List available templates and their parameters
+    List,
+    /// Create a monitor and trigger for a tenant from a template
+    Instantiate {
+        /// Template identifier
+        template: String,
+        /// Tenant to create the monitor for
+        #[arg(long)]
+        tenant: Uuid,
+        /// Network slug the monitor watches
+        #[arg(long)]
+        network: String,
+        /// Template parameters as key=value
+        #[arg(long = "param", value_parser = parse_param)]
+        params: Vec<(String, String)>,
+    },
 }
 
-async fn run_worker(config: OrchestratorConfig, db_pool: Arc<sqlx::PgPool>) -> Result<()> {
-    info!("Starting in Worker mode");
-
-    // Initialize block cache
-    let cache = Arc::new(
-        BlockCacheService::new(&config.redis_url, config.block_cache.into())
-            .await
-            .context("Failed to initialize block cache")?,
-    );
-
-    // Initialize cached client pool
-    let client_pool = Arc::new(CachedClientPool::new(cache.clone()));
-
-    // Initialize shared block watcher to receive block events
-    let block_watcher = Arc::new(SharedBlockWatcher::new(
-        cache.clone(),
-        config.block_watcher.into(),
-    ));
-
-    // Initialize worker pool
-    let worker_pool = MonitorWorkerPool::new(db_pool.clone(), cache.clone(), config.worker.into());
-
-    // Initialize load balancer
-    let load_balancer = Arc::new(LoadBalancer::new(config.load_balancer.into()));
-
-    // Get worker ID from environment or generate
-    let worker_id =
-        std::env::var("WORKER_ID").unwrap_or_else(|_| format!("worker-{}", uuid::Uuid::new_v4()));
-
-    info!("Worker ID: {}", worker_id);
-
-    // Register with load balancer
-    load_balancer.add_worker(worker_id.clone()).await?;
-
-    // Get initial tenant assignments
-    let mut assigned_tenants = load_balancer.get_worker_assignments(&worker_id).await?;
+#[derive(Subcommand)]
+enum FilterDebugCommands {
+    /// Record filter inputs and outputs for a fraction of the tenant's blocks
+    Enable {
+        /// Tenant to debug
+        #[arg(long)]
+        tenant: Uuid,
+        /// Fraction of blocks to record, between 0 and 1
+        #[arg(long, default_value_t = 0.1)]
+        sample_rate: f64,
+        /// Stop sampling after this many minutes; 0 keeps it on until disabled
+        #[arg(long, default_value_t = 60)]
+        minutes: i64,
+    },
+    /// Stop recording samples for the tenant
+    Disable {
+        /// Tenant to stop debugging
+        #[arg(long)]
+        tenant: Uuid,
+    },
+}
 
-    // If no tenants assigned and this is the first worker, assign all tenants
-    if assigned_tenants.is_empty() {
-        info!("No tenants assigned to worker, checking for unassigned tenants...");
-        let all_tenant_ids = get_all_tenant_ids(&db_pool).await?;
-        info!("Found {} tenants in database", all_tenant_ids.len());
+/// Parse a `key=value` template parameter
+fn parse_param(s: &str) -> Result<(String, String), String> {
+    s.split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected key=value, got {}", s))
+}
 
-        // Assign each tenant to this worker
-        for tenant_id in &all_tenant_ids {
-            match load_balancer.assign_tenant(*tenant_id).await {
-                Ok(assigned_worker_id) => {
-                    if assigned_worker_id == worker_id {
-                        assigned_tenants.push(*tenant_id);
-                        info!("Assigned tenant {} to worker {}", tenant_id, worker_id);
-                    }
-                }
-                Err(e) => {
-                    error!("Failed to assign tenant {} to worker: {}", tenant_id, e);
+/// Run a template subcommand
+async fn run_templates(config: &OrchestratorConfig, command: TemplateCommands) -> Result<()> {
+    let catalog = TemplateCatalog::default();
+
+    match command {
+        TemplateCommands::List => {
+            for template in catalog.list() {
+                println!("{}: {}", template.id, template.description);
+                for parameter in &template.parameters {
+                    let default = parameter
+                        .default
+                        .as_ref()
+                        .map(|d| format!(" (default: {})", d))
+                        .unwrap_or_default();
+                    println!(
+                        "  --param {}=<{:?}>  {}{}",
+                        parameter.name, parameter.kind, parameter.description, default
+                    );
                 }
             }
         }
+        TemplateCommands::Instantiate {
+            template,
+            tenant,
+            network,
+            params,
+        } => {
+            let db = PgPool::connect(&config.database_url)
+                .await
+                .context("Failed to connect to database")?;
+            let params: HashMap<String, String> = params.into_iter().collect();
+
+            let created = TemplateService::new(Arc::new(db), catalog)
+                .instantiate(tenant, &template, &network, &params)
+                .await?;
+            println!(
+                "Created monitor {} and trigger {}",
+                created.monitor_id, created.trigger_id
+            );
+        }
     }
 
-    info!(
-        "Worker {} assigned {} tenants",
-        worker_id,
-        assigned_tenants.len()
-    );
-
-    // Create and start the worker
-    worker_pool
-        .create_worker(
-            worker_id.clone(),
-            assigned_tenants,
-            block_watcher.clone(),
-            client_pool,
-        )
-        .await?;
-
-    info!("Worker started successfully");
-    wait_for_shutdown().await;
-
     Ok(())
 }
 
-async fn run_block_watcher(config: OrchestratorConfig, db_pool: Arc<sqlx::PgPool>) -> Result<()> {
-    info!("Starting in Block Watcher mode");
-
-    // Initialize block cache
-    let cache = Arc::new(
-        BlockCacheService::new(&config.redis_url, config.block_cache.into())
-            .await
-            .context("Failed to initialize block cache")?,
-    );
-
-    // Initialize cached client pool
-    let client_pool = Arc::new(CachedClientPool::new(cache.clone()));
-
-    // Initialize shared block watcher
-    let block_watcher = Arc::new(SharedBlockWatcher::new(
-        cache.clone(),
-        config.block_watcher.into(),
-    ));
-
-    // Initialize OZ Monitor services to get network configurations
-    // In block watcher mode, we need all tenant IDs to get all networks
-    let all_tenant_ids = get_all_tenant_ids(&db_pool).await?;
-    let oz_services = Arc::new(
-        OzMonitorServices::new(db_pool.clone(), all_tenant_ids.clone(), client_pool.clone())
-            .await
-            .context("Failed to initialize OZ Monitor services")?,
-    );
-
-    // Get all active networks from OZ services
-    let active_networks = oz_services.get_active_networks().await?;
-
-    // Load network configurations from database
-    let network_repo = TenantAwareNetworkRepository::new(db_pool.clone(), all_tenant_ids);
-    let all_networks = network_repo.get_all();
-
-    // Add networks with active monitors to the block watcher
-    for slug in active_networks {
-        if let Some(network) = all_networks.get(&slug) {
-            block_watcher.add_network(network.clone()).await?;
-            info!("Added network {} to block watcher", slug);
+/// Run a filter debug subcommand
+async fn run_filter_debug(config: &OrchestratorConfig, command: FilterDebugCommands) -> Result<()> {
+    let db = PgPool::connect(&config.database_url)
+        .await
+        .context("Failed to connect to database")?;
+    let filter_debug = FilterDebugService::new(Arc::new(db));
+
+    match command {
+        FilterDebugCommands::Enable {
+            tenant,
+            sample_rate,
+            minutes,
+        } => {
+            let enabled_until =
+                (minutes > 0).then(|| chrono::Utc::now() + chrono::Duration::minutes(minutes));
+            filter_debug
+                .enable(tenant, sample_rate, enabled_until)
+                .await?;
+            match enabled_until {
+                Some(until) => println!("Sampling tenant {} until {}", tenant, until),
+                None => println!("Sampling tenant {} until disabled", tenant),
+            }
+        }
+        FilterDebugCommands::Disable { tenant } => {
+            filter_debug.disable(tenant).await?;
+            println!("Stopped sampling tenant {}", tenant);
         }
     }
 
-    // Start watching blocks
-    block_watcher.start(client_pool).await?;
-
-    info!("Block watcher started successfully");
-    wait_for_shutdown().await;
-
     Ok(())
 }
 
-/// Get all tenant IDs from the database
-async fn get_all_tenant_ids(db_pool: &sqlx::PgPool) -> Result<Vec<uuid::Uuid>> {
-    let tenant_ids = sqlx::query_scalar::<_, uuid::Uuid>(
-        "SELECT DISTINCT tenant_id FROM tenant_monitors WHERE is_active = true",
-    )
-    .fetch_all(db_pool)
-    .await
-    .context("Failed to fetch tenant IDs")?;
-
-    Ok(tenant_ids)
+/// Print the effective settings of a network
+async fn run_network_settings(config: &OrchestratorConfig, network: &str) -> Result<()> {
+    let db = PgPool::connect(&config.database_url)
+        .await
+        .context("Failed to connect to database")?;
+    let resolver = NetworkSettingsResolver::new(Arc::new(db), config.into());
+    let settings = resolver.resolve(network).await?;
+    println!("{}", serde_json::to_string_pretty(&settings)?);
+    Ok(())
 }
 
-async fn run_api(config: OrchestratorConfig, _db_pool: Arc<sqlx::PgPool>) -> Result<()> {
-    info!("Starting in API mode");
+/// Replay a recorded session, comparing recorded and modified monitors if given
+async fn run_replay(
+    config: &OrchestratorConfig,
+    session: PathBuf,
+    monitors: Option<PathBuf>,
+) -> Result<()> {
+    let session = RecordedSession::load(&session).await?;
+    let overrides: HashMap<Uuid, Vec<Monitor>> = match &monitors {
+        Some(path) => serde_json::from_slice(
+            &tokio::fs::read(path)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))?,
+        )
+        .with_context(|| format!("Invalid monitors in {}", path.display()))?,
+        None => HashMap::new(),
+    };
 
-    // TODO: Implement API server with endpoints for:
-    // - Worker management
-    // - Tenant assignment
-    // - Metrics and monitoring
-    // - Manual rebalancing
+    let db = PgPool::connect(&config.database_url)
+        .await
+        .context("Failed to connect to database")?;
+    let cache = BlockCacheService::new(&config.redis_url, config.block_cache.clone().into())
+        .await
+        .context("Failed to connect to Redis")?
+        .with_keyspace(RedisKeyspace::new(config.redis_namespace.clone()));
+    let client_pool = Arc::new(CachedClientPool::new(Arc::new(cache)));
+    let oz_services = OzMonitorServices::new(Arc::new(db), session.tenant_ids(), client_pool)
+        .await?
+        .for_replay();
+
+    println!(
+        "Replaying {} blocks recorded by worker {} from {}",
+        session.block_count(),
+        session.worker_id,
+        session.started_at
+    );
+    let recorded: BTreeSet<ReplayMatch> = session
+        .replay(&oz_services, &HashMap::new())
+        .await?
+        .into_iter()
+        .collect();
+
+    let print = |m: &ReplayMatch| {
+        println!(
+            "  tenant {} monitor {} on {} block {}",
+            m.tenant_id,
+            m.monitor_name,
+            m.network_slug,
+            m.block_number
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "?".to_string())
+        )
+    };
 
-    let addr = format!("{}:{}", config.api.host, config.api.port);
-    info!("API server listening on {}", addr);
+    if overrides.is_empty() {
+        println!("{} matches:", recorded.len());
+        recorded.iter().for_each(print);
+        return Ok(());
+    }
 
-    wait_for_shutdown().await;
+    let modified: BTreeSet<ReplayMatch> = session
+        .replay(&oz_services, &overrides)
+        .await?
+        .into_iter()
+        .collect();
+    println!(
+        "{} matches with recorded monitors, {} with modified monitors",
+        recorded.len(),
+        modified.len()
+    );
+    println!("Only with modified monitors:");
+    modified.difference(&recorded).for_each(print);
+    println!("Only with recorded monitors:");
+    recorded.difference(&modified).for_each(print);
 
     Ok(())
 }
 
-async fn run_all(config: OrchestratorConfig, db_pool: Arc<sqlx::PgPool>) -> Result<()> {
-    info!("Starting all services");
+/// Copy a tenant to another database, printing the changes made on the target
+async fn run_migrate_tenant(
+    config: &OrchestratorConfig,
+    tenant: Uuid,
+    target_database_url: &str,
+    target_tenant: Option<Uuid>,
+    match_days: i64,
+    dry_run: bool,
+) -> Result<()> {
+    let source = PgPool::connect(&config.database_url)
+        .await
+        .context("Failed to connect to source database")?;
+    let target = PgPool::connect(target_database_url)
+        .await
+        .context("Failed to connect to target database")?;
+
+    let matches_since =
+        (match_days > 0).then(|| chrono::Utc::now() - chrono::Duration::days(match_days));
+    let snapshot = TenantMigrationService::new(Arc::new(source))
+        .export(tenant, matches_since)
+        .await?
+        .with_context(|| format!("Tenant {} not found", tenant))?;
+
+    let plan = TenantMigrationService::new(Arc::new(target))
+        .with_chain_support(config.chains.clone().into())
+        .import(&snapshot, target_tenant, dry_run)
+        .await?;
 
-    // Initialize shared components
-    let cache = Arc::new(
-        BlockCacheService::new(&config.redis_url, config.block_cache.clone().into())
-            .await
-            .context("Failed to initialize block cache")?,
+    for change in &plan.changes {
+        let target_id = if change.action == MigrationAction::Create {
+            format!("new id {}", change.target_id)
+        } else {
+            change.target_id.to_string()
+        };
+        println!(
+            "{:?} {:?} {} ({})",
+            change.action, change.kind, change.key, target_id
+        );
+        if !change.changed_fields.is_empty() {
+            println!("  changed: {}", change.changed_fields.join(", "));
+        }
+    }
+    println!(
+        "{} created, {} updated, {} unchanged; {} quiet hours windows, {} new matches",
+        plan.count(MigrationAction::Create),
+        plan.count(MigrationAction::Update),
+        plan.count(MigrationAction::Unchanged),
+        plan.quiet_hours,
+        plan.new_matches
     );
+    if plan.applied {
+        println!(
+            "Imported tenant {} as {}",
+            plan.source_tenant_id, plan.target_tenant_id
+        );
+    } else {
+        println!("Dry run, nothing written");
+    }
 
-    let client_pool = Arc::new(CachedClientPool::new(cache.clone()));
-
-    // Initialize shared block watcher
-    let block_watcher = Arc::new(SharedBlockWatcher::new(
-        cache.clone(),
-        config.block_watcher.clone().into(),
-    ));
+    Ok(())
+}
 
-    // Initialize worker pool and load balancer
-    let worker_pool =
-        MonitorWorkerPool::new(db_pool.clone(), cache.clone(), config.worker.clone().into());
-    let load_balancer = Arc::new(LoadBalancer::new(config.load_balancer.clone().into()));
+#[tokio::main]
+async fn main() -> Result<()> {
+    // Initialize tracing
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::EnvFilter::new(
+            std::env::var("RUST_LOG").unwrap_or_else(|_| "info".into()),
+        ))
+        .with(tracing_subscriber::fmt::layer())
+        .init();
 
-    // Get all tenant IDs and active networks
-    let all_tenant_ids = get_all_tenant_ids(&db_pool).await?;
-    let oz_services = Arc::new(
-        OzMonitorServices::new(db_pool.clone(), all_tenant_ids.clone(), client_pool.clone())
-            .await
-            .context("Failed to initialize OZ Monitor services")?,
-    );
+    // Parse CLI arguments
+    let cli = Cli::parse();
 
-    let active_networks = oz_services.get_active_networks().await?;
-    let network_repo = TenantAwareNetworkRepository::new(db_pool.clone(), all_tenant_ids.clone());
-    let all_networks = network_repo.get_all();
+    // Load configuration
+    let config = OrchestratorConfig::load().context("Failed to load configuration")?;
 
-    // Add networks to block watcher
-    for slug in active_networks {
-        if let Some(network) = all_networks.get(&slug) {
-            block_watcher.add_network(network.clone()).await?;
-            info!("Added network {} to block watcher", slug);
-        }
-    }
+    config
+        .validate()
+        .map_err(|e| anyhow::anyhow!("Invalid configuration: {}", e))?;
 
-    // Start block watcher
-    let block_watcher_for_spawn = block_watcher.clone();
-    let client_pool_for_spawn = client_pool.clone();
-    let block_watcher_handle = tokio::spawn(async move {
-        info!("Block watcher task spawned, calling start()");
-        match block_watcher_for_spawn.start(client_pool_for_spawn).await {
-            Ok(_) => {
-                info!("Block watcher start() completed successfully, now running...");
-                // Keep the block watcher running
-                if let Err(e) = block_watcher_for_spawn.run().await {
-                    error!("Block watcher run failed: {:?}", e);
-                }
-            }
-            Err(e) => error!("Block watcher start failed: {:?}", e),
+    // Template, filter debug, replay, migration and settings commands run once and exit
+    match cli.command {
+        Some(Commands::Templates { command }) => return run_templates(&config, command).await,
+        Some(Commands::FilterDebug { command }) => return run_filter_debug(&config, command).await,
+        Some(Commands::Replay { session, monitors }) => {
+            return run_replay(&config, session, monitors).await
         }
-        info!("Block watcher task exiting");
-    });
-
-    // Create and start worker
-    let worker_id = format!("worker-{}", uuid::Uuid::new_v4());
-    info!("Worker ID: {}", worker_id);
-
-    load_balancer.add_worker(worker_id.clone()).await?;
-
-    // Assign all tenants to this worker
-    let mut assigned_tenants = Vec::new();
-    for tenant_id in &all_tenant_ids {
-        match load_balancer.assign_tenant(*tenant_id).await {
-            Ok(assigned_worker_id) => {
-                if assigned_worker_id == worker_id {
-                    assigned_tenants.push(*tenant_id);
-                    info!("Assigned tenant {} to worker {}", tenant_id, worker_id);
-                }
-            }
-            Err(e) => {
-                error!("Failed to assign tenant {} to worker: {}", tenant_id, e);
-            }
+        Some(Commands::NetworkSettings { network }) => {
+            return run_network_settings(&config, &network).await
         }
-    }
-
-    // Create worker with shared block watcher
-    worker_pool
-        .create_worker(
-            worker_id.clone(),
-            assigned_tenants,
-            block_watcher.clone(),
-            client_pool.clone(),
-        )
-        .await?;
-
-    // Start API server
-    let api_handle = tokio::spawn({
-        let config = config.clone();
-        let db_pool = db_pool.clone();
-        async move {
-            if let Err(e) = run_api(config, db_pool).await {
-                error!("API server failed: {}", e);
-            }
-        }
-    });
-
-    info!("All services started successfully");
-
-    // Wait for any service to fail
-    tokio::select! {
-        _ = block_watcher_handle => error!("Block watcher exited"),
-        _ = api_handle => error!("API server exited"),
-        _ = signal::ctrl_c() => {
-            info!("Received Ctrl+C, shutting down");
+        Some(Commands::MigrateTenant {
+            tenant,
+            target_database_url,
+            target_tenant,
+            match_days,
+            dry_run,
+        }) => {
+            return run_migrate_tenant(
+                &config,
+                tenant,
+                &target_database_url,
+                target_tenant,
+                match_days,
+                dry_run,
+            )
+            .await
         }
+        _ => {}
     }
 
-    Ok(())
-}
-
-async fn wait_for_shutdown() {
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("Failed to install Ctrl+C handler");
-    };
+    info!("Starting OZ Monitor Orchestrator");
 
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("Failed to install SIGTERM handler")
-            .recv()
-            .await;
+    // Determine service mode
+    let service_mode = match cli.command {
+        Some(Commands::Worker) => ServiceMode::Worker,
+        Some(Commands::BlockWatcher) => ServiceMode::BlockWatcher,
+        Some(Commands::Api) => ServiceMode::Api,
+        Some(Commands::All) => ServiceMode::All,
+        Some(Commands::Templates { .. })
+        | Some(Commands::FilterDebug { .. })
+        | Some(Commands::Replay { .. })
+        | Some(Commands::MigrateTenant { .. })
+        | Some(Commands::NetworkSettings { .. })
+        | None => config.service_mode.clone(),
     };
 
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {
-            info!("Received Ctrl+C, shutting down");
-        }
-        _ = terminate => {
-            info!("Received SIGTERM, shutting down");
-        }
-    }
+    Orchestrator::builder()
+        .config(config)
+        .mode(service_mode)
+        .build()
+        .await?
+        .run()
+        .await
 }
@@ -0,0 +1,77 @@
+//! Tenant activity anomaly models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::ModelError;
+
+/// Activity rate that spiked
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalyKind {
+    /// Monitor matches per minute
+    MatchRate,
+
+    /// Attributed RPC requests per minute
+    RpcRate,
+}
+
+impl AnomalyKind {
+    /// Name used in storage and metric labels
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnomalyKind::MatchRate => "match_rate",
+            AnomalyKind::RpcRate => "rpc_rate",
+        }
+    }
+}
+
+impl FromStr for AnomalyKind {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "match_rate" => Ok(AnomalyKind::MatchRate),
+            "rpc_rate" => Ok(AnomalyKind::RpcRate),
+            _ => Err(ModelError::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for AnomalyKind {
+    type Error = ModelError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Sudden spike in a tenant's activity
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TenantAnomaly {
+    /// Anomaly identifier
+    pub id: Uuid,
+
+    /// Tenant whose activity spiked
+    pub tenant_id: Uuid,
+
+    #[sqlx(try_from = "String")]
+    pub kind: AnomalyKind,
+
+    /// Rate per minute when the spike was detected
+    pub current_rate: f64,
+
+    /// Mean rate per minute of the tenant's recent samples
+    pub baseline_rate: f64,
+
+    /// RPC cap applied to the tenant by auto-throttling
+    pub throttle_limit: Option<i32>,
+
+    /// When the throttle expires
+    pub throttled_until: Option<DateTime<Utc>>,
+
+    /// When the spike was detected
+    pub detected_at: DateTime<Utc>,
+}
@@ -2,8 +2,11 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::models::bloom::fnv1a;
+
 /// Tenant assignment to a worker
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TenantAssignment {
@@ -61,10 +64,240 @@ pub struct WorkerAssignment {
     /// Number of high-priority tenants
     pub high_priority_count: usize,
 
+    /// Shards of tenants too large for a single worker
+    #[serde(default)]
+    pub shards: Vec<TenantShard>,
+
     /// Last updated timestamp
     pub updated_at: DateTime<Utc>,
 }
 
+/// Dimension along which a tenant's monitors are split across workers
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardBy {
+    /// Each monitor belongs to exactly one shard
+    Monitor,
+
+    /// Each network belongs to exactly one shard
+    Network,
+}
+
+/// One slice of a sharded tenant's monitors
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TenantShard {
+    /// Tenant identifier
+    pub tenant_id: Uuid,
+
+    /// How the tenant's monitors are split
+    pub shard_by: ShardBy,
+
+    /// Index of this shard (0-based)
+    pub index: u32,
+
+    /// Total number of shards for the tenant
+    pub count: u32,
+}
+
+/// Kind of assignment lifecycle event
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentEventKind {
+    /// Tenant assigned to a worker for the first time
+    TenantAssigned,
+
+    /// Tenant moved from one worker to another
+    TenantReassigned,
+
+    /// Tenant taken off its worker, e.g. because it was suspended
+    TenantUnassigned,
+
+    /// Worker removed with tenants still assigned to it
+    WorkerFailed,
+
+    /// Standby worker promoted to take tenants
+    StandbyPromoted,
+
+    /// Rebalance run completed
+    RebalanceCompleted,
+
+    /// Startup reconciliation of persisted assignments completed
+    ReconciliationCompleted,
+}
+
+/// Assignment lifecycle event delivered to external systems
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AssignmentEvent {
+    /// Tenant assigned to a worker for the first time
+    TenantAssigned { assignment: TenantAssignment },
+
+    /// Tenant moved from one worker to another
+    TenantReassigned {
+        previous_worker_id: String,
+        assignment: TenantAssignment,
+    },
+
+    /// Tenant taken off its workers, e.g. because it was suspended
+    TenantUnassigned {
+        tenant_id: Uuid,
+        worker_ids: Vec<String>,
+    },
+
+    /// Worker removed with tenants still assigned to it
+    WorkerFailed {
+        worker_id: String,
+        orphaned_tenants: Vec<Uuid>,
+    },
+
+    /// Standby worker promoted, replacing a failed worker or adding capacity
+    StandbyPromoted {
+        worker_id: String,
+        /// Failed worker whose tenants it took over; None when promoted for load
+        replaced_worker_id: Option<String>,
+    },
+
+    /// Rebalance run completed with the resulting distribution
+    RebalanceCompleted {
+        distribution: HashMap<String, Vec<Uuid>>,
+    },
+
+    /// Startup reconciliation completed with the resulting report
+    ReconciliationCompleted { report: ReconciliationReport },
+}
+
+/// Tenant moved off a worker, e.g. one that is no longer alive, being drained
+/// or rebalanced
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReassignedTenant {
+    /// Tenant identifier
+    pub tenant_id: Uuid,
+
+    /// Worker the tenant was taken from
+    pub previous_worker_id: String,
+
+    /// Worker now holding the tenant
+    pub worker_id: String,
+}
+
+/// Progress of a worker drain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DrainState {
+    /// Tenants are being moved off the worker
+    InProgress,
+
+    /// Every tenant was moved and the worker told to stop
+    Completed,
+
+    /// Some tenants could not be moved; the worker keeps them and takes new tenants again
+    Failed,
+}
+
+/// Tenants moved off a worker before it is shut down
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerDrain {
+    /// Drain identifier
+    pub drain_id: Uuid,
+
+    /// Worker being drained
+    pub worker_id: String,
+
+    pub state: DrainState,
+
+    /// Tenants the worker held when the drain started
+    pub tenants_total: usize,
+
+    /// Tenants still on the worker
+    pub remaining: Vec<Uuid>,
+
+    /// Tenants moved so far and where they went
+    pub moved: Vec<ReassignedTenant>,
+
+    /// Tenant shards moved off the worker
+    pub shards_moved: usize,
+
+    /// Why the drain failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    pub started_at: DateTime<Utc>,
+
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+impl WorkerDrain {
+    /// Whether the worker is kept out of placement; false once a drain failed
+    pub fn excludes_worker(&self) -> bool {
+        self.state != DrainState::Failed
+    }
+}
+
+/// Tenant distribution computed by a rebalance
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RebalancePlan {
+    /// Tenants held by each worker after the rebalance
+    pub distribution: HashMap<String, Vec<Uuid>>,
+
+    /// Tenants that end up on a different worker than before
+    pub tenants_moved: usize,
+
+    /// Tenants taken off one worker and put on another, by tenant ID; only
+    /// these are reassigned when the plan is applied
+    #[serde(default)]
+    pub moves: Vec<ReassignedTenant>,
+
+    /// Workers whose tenants change
+    pub affected_workers: Vec<String>,
+
+    /// Tenants whose move lost to a placement made by another writer
+    /// meanwhile; they keep that placement and are not counted as moved
+    #[serde(default)]
+    pub conflicts: usize,
+}
+
+/// Outcome of reconciling persisted assignments against live workers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReconciliationReport {
+    /// Workers with a recent heartbeat
+    pub live_workers: Vec<String>,
+
+    /// Tenants whose persisted assignment points at a live worker
+    pub restored: Vec<Uuid>,
+
+    /// Tenants moved off dead workers
+    pub reassigned: Vec<ReassignedTenant>,
+
+    /// Active tenants without any assignment that were assigned now
+    pub newly_assigned: Vec<Uuid>,
+
+    /// Active tenants still without a worker, e.g. because none is alive
+    pub unassigned: Vec<Uuid>,
+
+    /// Persisted assignments of tenants that are no longer active
+    pub dropped: Vec<Uuid>,
+
+    /// When the reconciliation ran
+    pub completed_at: DateTime<Utc>,
+}
+
+impl AssignmentEvent {
+    /// Get the kind of this event
+    pub fn kind(&self) -> AssignmentEventKind {
+        match self {
+            AssignmentEvent::TenantAssigned { .. } => AssignmentEventKind::TenantAssigned,
+            AssignmentEvent::TenantReassigned { .. } => AssignmentEventKind::TenantReassigned,
+            AssignmentEvent::TenantUnassigned { .. } => AssignmentEventKind::TenantUnassigned,
+            AssignmentEvent::WorkerFailed { .. } => AssignmentEventKind::WorkerFailed,
+            AssignmentEvent::StandbyPromoted { .. } => AssignmentEventKind::StandbyPromoted,
+            AssignmentEvent::RebalanceCompleted { .. } => AssignmentEventKind::RebalanceCompleted,
+            AssignmentEvent::ReconciliationCompleted { .. } => {
+                AssignmentEventKind::ReconciliationCompleted
+            }
+        }
+    }
+}
+
 impl TenantAssignment {
     /// Create a new tenant assignment
     pub fn new(tenant_id: Uuid, worker_id: String, reason: AssignmentReason) -> Self {
@@ -89,6 +322,35 @@ impl TenantAssignment {
     }
 }
 
+impl ReconciliationReport {
+    /// Check if every persisted assignment was valid and every tenant assigned
+    pub fn is_clean(&self) -> bool {
+        self.reassigned.is_empty()
+            && self.newly_assigned.is_empty()
+            && self.unassigned.is_empty()
+            && self.dropped.is_empty()
+    }
+}
+
+impl TenantShard {
+    /// Create a shard of a tenant
+    pub fn new(tenant_id: Uuid, shard_by: ShardBy, index: u32, count: u32) -> Self {
+        Self {
+            tenant_id,
+            shard_by,
+            index,
+            count,
+        }
+    }
+
+    /// Check if a monitor name or network slug (per `shard_by`) falls in this shard.
+    ///
+    /// Uses a stable hash so every worker agrees on the split.
+    pub fn owns(&self, key: &str) -> bool {
+        self.count <= 1 || fnv1a(key, 0xcbf29ce484222325) % self.count as u64 == self.index as u64
+    }
+}
+
 impl WorkerAssignment {
     /// Create a new worker assignment
     pub fn new(worker_id: String) -> Self {
@@ -97,6 +359,7 @@ impl WorkerAssignment {
             tenant_ids: Vec::new(),
             load_score: 0.0,
             high_priority_count: 0,
+            shards: Vec::new(),
             updated_at: Utc::now(),
         }
     }
@@ -120,6 +383,38 @@ impl WorkerAssignment {
         removed
     }
 
+    /// Add a tenant shard to this worker
+    pub fn add_shard(&mut self, shard: TenantShard) {
+        if !self.shards.contains(&shard) {
+            self.shards.push(shard);
+            self.updated_at = Utc::now();
+        }
+    }
+
+    /// Remove all shards of a tenant from this worker, returning them
+    pub fn remove_shards(&mut self, tenant_id: &Uuid) -> Vec<TenantShard> {
+        let (removed, kept) = self
+            .shards
+            .drain(..)
+            .partition(|shard| shard.tenant_id == *tenant_id);
+        self.shards = kept;
+        if !removed.is_empty() {
+            self.updated_at = Utc::now();
+        }
+        removed
+    }
+
+    /// Tenants this worker processes, whole or as shards
+    pub fn processed_tenant_ids(&self) -> Vec<Uuid> {
+        let mut tenant_ids = self.tenant_ids.clone();
+        for shard in &self.shards {
+            if !tenant_ids.contains(&shard.tenant_id) {
+                tenant_ids.push(shard.tenant_id);
+            }
+        }
+        tenant_ids
+    }
+
     /// Get tenant count
     pub fn tenant_count(&self) -> usize {
         self.tenant_ids.len()
@@ -130,3 +425,36 @@ impl WorkerAssignment {
         self.tenant_ids.len() < max_tenants
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shards_partition_keys() {
+        let tenant_id = Uuid::new_v4();
+        let shards: Vec<TenantShard> = (0..3)
+            .map(|index| TenantShard::new(tenant_id, ShardBy::Monitor, index, 3))
+            .collect();
+
+        for i in 0..100 {
+            let monitor_name = format!("monitor-{}", i);
+            let owners = shards.iter().filter(|s| s.owns(&monitor_name)).count();
+            assert_eq!(owners, 1);
+        }
+    }
+
+    #[test]
+    fn test_processed_tenant_ids_include_shards() {
+        let whole = Uuid::new_v4();
+        let sharded = Uuid::new_v4();
+        let mut assignment = WorkerAssignment::new("worker-1".to_string());
+        assignment.add_tenant(whole);
+        assignment.add_shard(TenantShard::new(sharded, ShardBy::Network, 0, 2));
+        assignment.add_shard(TenantShard::new(sharded, ShardBy::Network, 1, 2));
+
+        assert_eq!(assignment.processed_tenant_ids(), vec![whole, sharded]);
+        assert_eq!(assignment.remove_shards(&sharded).len(), 2);
+        assert_eq!(assignment.processed_tenant_ids(), vec![whole]);
+    }
+}
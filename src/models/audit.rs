@@ -0,0 +1,89 @@
+//! Management API audit models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::ModelError;
+
+/// How an audited request ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    /// Answered with a 2xx status
+    Success,
+    /// Answered with any other status
+    Failure,
+}
+
+impl AuditOutcome {
+    /// Outcome of a response with the given HTTP status
+    pub fn from_status(status: u16) -> Self {
+        if (200..300).contains(&status) {
+            AuditOutcome::Success
+        } else {
+            AuditOutcome::Failure
+        }
+    }
+
+    /// Name used in storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditOutcome::Success => "success",
+            AuditOutcome::Failure => "failure",
+        }
+    }
+}
+
+impl FromStr for AuditOutcome {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "success" => Ok(AuditOutcome::Success),
+            "failure" => Ok(AuditOutcome::Failure),
+            _ => Err(ModelError::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for AuditOutcome {
+    type Error = ModelError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Mutating management API call
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct AuditEntry {
+    pub id: Uuid,
+
+    pub occurred_at: DateTime<Utc>,
+
+    /// Id of the API token used (a prefix of its configured hash)
+    pub actor: String,
+
+    /// Method and route, e.g. `POST /tenants/:tenant_id/suspend`
+    pub action: String,
+
+    /// Requested path
+    pub path: String,
+
+    /// Tenant targeted, if any
+    pub tenant_id: Option<Uuid>,
+
+    /// Worker targeted, if any
+    pub worker_id: Option<String>,
+
+    /// Hex SHA-256 of the request body
+    pub payload_hash: Option<String>,
+
+    /// HTTP status of the response
+    pub status_code: i32,
+
+    #[sqlx(try_from = "String")]
+    pub outcome: AuditOutcome,
+}
@@ -0,0 +1,98 @@
+//! Address bloom filter for block routing
+
+use serde::{Deserialize, Serialize};
+
+/// Number of bits in the filter
+const BLOOM_BITS: usize = 2048;
+
+/// Number of bit positions set per address
+const BLOOM_HASHES: u64 = 3;
+
+/// Compact bloom filter over the addresses touched by a block event.
+///
+/// Addresses are compared case-insensitively. A negative answer is exact;
+/// a positive answer may be a false positive.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AddressBloom {
+    bits: Vec<u64>,
+}
+
+impl Default for AddressBloom {
+    fn default() -> Self {
+        Self {
+            bits: vec![0; BLOOM_BITS / 64],
+        }
+    }
+}
+
+impl AddressBloom {
+    /// Create an empty filter
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an address to the filter
+    pub fn insert(&mut self, address: &str) {
+        for position in positions(address) {
+            self.bits[position / 64] |= 1 << (position % 64);
+        }
+    }
+
+    /// Check if the address may be in the filter
+    pub fn contains(&self, address: &str) -> bool {
+        positions(address).all(|position| self.bits[position / 64] & (1 << (position % 64)) != 0)
+    }
+
+    /// Check if any of the addresses may be in the filter
+    pub fn contains_any<'a>(&self, addresses: impl IntoIterator<Item = &'a String>) -> bool {
+        addresses.into_iter().any(|address| self.contains(address))
+    }
+}
+
+/// Bit positions for an address using double hashing over FNV-1a.
+///
+/// FNV is used instead of the std hasher so watchers and workers built
+/// separately agree on the filter layout.
+fn positions(address: &str) -> impl Iterator<Item = usize> {
+    let h1 = fnv1a(address, 0xcbf29ce484222325);
+    let h2 = fnv1a(address, 0x84222325cbf29ce4) | 1;
+    (0..BLOOM_HASHES)
+        .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % BLOOM_BITS as u64) as usize)
+}
+
+/// 64-bit FNV-1a over the lowercased address
+pub(crate) fn fnv1a(address: &str, offset_basis: u64) -> u64 {
+    address
+        .bytes()
+        .map(|b| b.to_ascii_lowercase())
+        .fold(offset_basis, |hash, b| {
+            (hash ^ b as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contains_inserted_addresses() {
+        let mut bloom = AddressBloom::new();
+        bloom.insert("0xAbC0000000000000000000000000000000000001");
+
+        assert!(bloom.contains("0xabc0000000000000000000000000000000000001"));
+        assert!(!AddressBloom::new().contains("0xabc0000000000000000000000000000000000001"));
+    }
+
+    #[test]
+    fn test_contains_any() {
+        let mut bloom = AddressBloom::new();
+        bloom.insert("0x0000000000000000000000000000000000000001");
+
+        let monitored = vec![
+            "0x0000000000000000000000000000000000000002".to_string(),
+            "0x0000000000000000000000000000000000000001".to_string(),
+        ];
+        assert!(bloom.contains_any(&monitored));
+        assert!(!bloom.contains_any(&monitored[..1]));
+    }
+}
@@ -0,0 +1,47 @@
+//! Tenant block processing checkpoint models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Highest block a tenant has processed on a network
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TenantCheckpoint {
+    pub tenant_id: Uuid,
+
+    pub network_slug: String,
+
+    /// Last block the tenant's monitors were evaluated against
+    pub last_processed_block: i64,
+
+    /// Every block up to and including this one has been evaluated against the
+    /// tenant's monitors or skipped by its schedule; None until first written
+    pub watermark_block: Option<i64>,
+
+    /// When the checkpoint last moved
+    pub updated_at: DateTime<Utc>,
+
+    /// Checkpoint before the latest rewind
+    pub rewound_from: Option<i64>,
+
+    /// When the checkpoint was last rewound
+    pub rewound_at: Option<DateTime<Utc>>,
+}
+
+/// Block up to which a tenant's monitors have fully evaluated a network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantWatermark {
+    pub network_slug: String,
+
+    /// Every block up to and including this one is evaluated
+    pub watermark_block: u64,
+
+    /// Latest block of the network in the block cache
+    pub latest_block: Option<u64>,
+
+    /// Blocks between the watermark and the latest block
+    pub lag: Option<u64>,
+
+    /// When the checkpoint last moved
+    pub updated_at: DateTime<Utc>,
+}
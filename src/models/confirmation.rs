@@ -0,0 +1,104 @@
+//! Block confirmation models
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::models::ModelError;
+
+/// Lifecycle state of a monitor match
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchState {
+    /// Seen in a block with fewer confirmations than the tenant requires
+    Provisional,
+
+    /// Block has reached the tenant's confirmation depth
+    #[default]
+    #[serde(alias = "confirmed")]
+    Finalized,
+
+    /// Block was replaced by a reorg before reaching the confirmation depth
+    Orphaned,
+}
+
+impl MatchState {
+    /// State of a match in `block_number` given the chain head and required depth.
+    ///
+    /// Without a known chain head the block is taken as finalized, since the
+    /// watcher only broadcasts blocks past the network's confirmation depth.
+    pub fn at(block_number: Option<u64>, latest_block: Option<u64>, required: u64) -> Self {
+        match (block_number, latest_block) {
+            (Some(block_number), Some(latest_block))
+                if latest_block.saturating_sub(block_number) < required =>
+            {
+                MatchState::Provisional
+            }
+            _ => MatchState::Finalized,
+        }
+    }
+
+    /// States that fire triggers unless a tenant configures otherwise
+    pub fn default_trigger_states() -> Vec<MatchState> {
+        vec![MatchState::Provisional, MatchState::Finalized]
+    }
+
+    /// Name used in trigger variables and storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchState::Provisional => "provisional",
+            MatchState::Finalized => "finalized",
+            MatchState::Orphaned => "orphaned",
+        }
+    }
+}
+
+impl FromStr for MatchState {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "provisional" => Ok(MatchState::Provisional),
+            "finalized" | "confirmed" => Ok(MatchState::Finalized),
+            "orphaned" => Ok(MatchState::Orphaned),
+            _ => Err(ModelError::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_follows_confirmations() {
+        assert_eq!(
+            MatchState::at(Some(100), Some(105), 12),
+            MatchState::Provisional
+        );
+        assert_eq!(
+            MatchState::at(Some(100), Some(112), 12),
+            MatchState::Finalized
+        );
+        assert_eq!(
+            MatchState::at(Some(100), Some(100), 0),
+            MatchState::Finalized
+        );
+        assert_eq!(MatchState::at(Some(100), None, 12), MatchState::Finalized);
+    }
+
+    #[test]
+    fn test_state_round_trips_through_storage_name() {
+        for state in [
+            MatchState::Provisional,
+            MatchState::Finalized,
+            MatchState::Orphaned,
+        ] {
+            assert_eq!(state.as_str().parse::<MatchState>().unwrap(), state);
+        }
+        assert_eq!(
+            "confirmed".parse::<MatchState>().unwrap(),
+            MatchState::Finalized
+        );
+        assert!("pending".parse::<MatchState>().is_err());
+    }
+}
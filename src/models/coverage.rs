@@ -0,0 +1,76 @@
+//! Monitoring coverage models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::DeliveryRoute;
+
+/// What of a tenant's configuration is actually monitored, catching
+/// configurations that are silently skipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageReport {
+    pub tenant_id: Uuid,
+
+    /// Worker processing the tenant; None if unassigned or split into shards
+    pub worker_id: Option<String>,
+
+    pub networks: Vec<NetworkCoverage>,
+
+    pub monitors: Vec<MonitorCoverage>,
+
+    /// Triggers of the loaded monitors
+    pub triggers: Vec<TriggerCoverage>,
+
+    /// Networks, monitors and triggers with an error
+    pub issues: usize,
+
+    pub generated_at: DateTime<Utc>,
+}
+
+/// Whether a tenant network is loaded and watched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkCoverage {
+    pub network_slug: String,
+
+    pub is_active: bool,
+
+    /// Whether workers load the network's configuration
+    pub loaded: bool,
+
+    /// Whether a block watcher polls the network, in this or another process
+    pub watched: bool,
+
+    /// Why an active network is not loaded or not watched
+    pub error: Option<String>,
+}
+
+/// Whether a tenant monitor is loaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorCoverage {
+    pub name: String,
+
+    pub network_slug: String,
+
+    pub is_active: bool,
+
+    /// Whether workers run the monitor on the network's blocks
+    pub loaded: bool,
+
+    /// Why the monitor is not loaded, or why it was deactivated
+    pub error: Option<String>,
+}
+
+/// Whether a trigger of a loaded monitor resolves to a notification channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerCoverage {
+    pub monitor_name: String,
+
+    pub trigger_name: String,
+
+    /// Path the trigger's notifications take; None if the trigger does not resolve
+    pub route: Option<DeliveryRoute>,
+
+    /// Why the trigger does not resolve
+    pub error: Option<String>,
+}
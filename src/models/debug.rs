@@ -0,0 +1,71 @@
+//! Filter debugging models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Filter debug sampling enabled for a tenant
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FilterDebugSettings {
+    /// Tenant being debugged
+    pub tenant_id: Uuid,
+
+    /// Fraction of blocks recorded, between 0 and 1
+    pub sample_rate: f64,
+
+    /// When sampling stops; never if unset
+    pub enabled_until: Option<DateTime<Utc>>,
+}
+
+impl FilterDebugSettings {
+    /// Decide whether to record a block given a uniform roll in `0..1`
+    pub fn samples(&self, at: DateTime<Utc>, roll: f64) -> bool {
+        let expired = self.enabled_until.is_some_and(|until| at >= until);
+        !expired && roll < self.sample_rate
+    }
+}
+
+/// Filter inputs and outputs recorded for one tenant and block
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilterDebugSample {
+    /// Tenant whose monitors were evaluated
+    pub tenant_id: Uuid,
+
+    /// Network the block belongs to
+    pub network_slug: String,
+
+    /// Block or ledger number
+    pub block_number: Option<u64>,
+
+    /// Serialized block as passed to the filter service
+    pub block: serde_json::Value,
+
+    /// Serialized monitors the block was filtered against
+    pub monitors: serde_json::Value,
+
+    /// Matches returned by the filter service
+    pub filter_matches: serde_json::Value,
+
+    /// Matches kept after trigger conditions, by monitor name
+    pub accepted_matches: serde_json::Value,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_samples_respects_rate_and_expiry() {
+        let now = Utc::now();
+        let settings = FilterDebugSettings {
+            tenant_id: Uuid::new_v4(),
+            sample_rate: 0.25,
+            enabled_until: Some(now + Duration::minutes(5)),
+        };
+
+        assert!(settings.samples(now, 0.1));
+        assert!(!settings.samples(now, 0.5));
+        assert!(!settings.samples(now + Duration::minutes(5), 0.1));
+    }
+}
@@ -0,0 +1,39 @@
+//! Tenant match webhook models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Webhook receiving every match of a tenant's monitors
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MatchWebhook {
+    pub tenant_id: Uuid,
+
+    pub url: String,
+
+    /// Key deliveries are signed with; never returned by the API
+    #[serde(skip_serializing)]
+    pub secret: String,
+
+    /// Inactive webhooks receive nothing
+    pub is_active: bool,
+
+    pub created_at: DateTime<Utc>,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body of `PUT /tenants/{tenant_id}/match-webhook`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchWebhookInput {
+    pub url: String,
+
+    pub secret: String,
+
+    #[serde(default = "default_is_active")]
+    pub is_active: bool,
+}
+
+fn default_is_active() -> bool {
+    true
+}
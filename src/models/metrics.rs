@@ -1,6 +1,6 @@
 //! Metrics models
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -38,6 +38,11 @@ pub struct WorkerMetrics {
     /// Worker identifier
     pub worker_id: String,
 
+    /// Capacity relative to a worker of weight 1, e.g. 2 on a node with
+    /// twice the CPUs; scales the worker's tenant cap and normalizes its load
+    #[serde(default = "default_capacity_weight")]
+    pub capacity_weight: f64,
+
     /// Number of assigned tenants
     pub tenant_count: usize,
 
@@ -63,6 +68,23 @@ pub struct WorkerMetrics {
     pub collected_at: DateTime<Utc>,
 }
 
+fn default_capacity_weight() -> f64 {
+    1.0
+}
+
+/// CPU and memory usage sampled from a worker process
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WorkerUsage {
+    /// CPU usage percentage across the cores available to the process (0-100)
+    pub cpu_usage: f64,
+
+    /// Resident memory as a percentage of the memory limit (0-100)
+    pub memory_usage: f64,
+
+    /// Sample timestamp
+    pub collected_at: DateTime<Utc>,
+}
+
 /// System-wide metrics
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMetrics {
@@ -111,12 +133,28 @@ impl WorkerMetrics {
     pub fn load_score(&self) -> f64 {
         let cpu_score = self.cpu_usage / 100.0;
         let memory_score = self.memory_usage / 100.0;
-        let tenant_score = (self.tenant_count as f64 / 50.0).min(1.0); // Assuming 50 is max
+        let tenant_score = (self.weighted_tenants() / 50.0).min(1.0); // Assuming 50 is max
 
         // Weighted average
         (cpu_score * 0.4 + memory_score * 0.4 + tenant_score * 0.2).min(1.0)
     }
 
+    /// Tenant count divided by capacity weight, comparable across workers
+    pub fn weighted_tenants(&self) -> f64 {
+        self.tenant_count as f64 / self.capacity_weight
+    }
+
+    /// Most tenants the worker takes: `max_tenants_per_worker` scaled by its
+    /// capacity weight, at least one
+    pub fn tenant_cap(&self, max_tenants_per_worker: usize) -> usize {
+        ((max_tenants_per_worker as f64 * self.capacity_weight).round() as usize).max(1)
+    }
+
+    /// Whether the worker holds fewer tenants than its cap
+    pub fn has_room(&self, max_tenants_per_worker: usize) -> bool {
+        self.tenant_count < self.tenant_cap(max_tenants_per_worker)
+    }
+
     /// Check if worker is healthy
     pub fn is_healthy(&self) -> bool {
         self.cpu_usage < 90.0 && self.memory_usage < 90.0 && self.errors_last_hour < 10
@@ -156,3 +194,272 @@ impl SystemMetrics {
         self.health_score = score.max(0.0);
     }
 }
+
+/// Sizing targets used to suggest a worker count
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityTargets {
+    /// Tenants a worker can hold at full utilization
+    pub max_tenants_per_worker: usize,
+
+    /// Utilization (0-1) workers should run at
+    pub target_utilization: f64,
+
+    /// Fewest workers to suggest
+    pub min_workers: usize,
+
+    /// Most workers to suggest; unbounded if unset
+    pub max_workers: Option<usize>,
+}
+
+/// Direction the worker pool should be scaled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScalingAction {
+    ScaleUp,
+    ScaleDown,
+    Hold,
+}
+
+/// Capacity of one worker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerCapacity {
+    pub worker_id: String,
+
+    pub capacity_weight: f64,
+
+    pub tenant_count: usize,
+
+    /// Tenants the worker takes at most, `max_tenants_per_worker` scaled by its weight
+    pub tenant_cap: usize,
+
+    /// Busiest of tenant slots, CPU and memory (0-1)
+    pub utilization: f64,
+
+    /// Utilization left before the target is reached (0 if above it)
+    pub headroom: f64,
+
+    /// Tenants that can be added before the target is reached
+    pub tenant_headroom: usize,
+
+    pub healthy: bool,
+}
+
+/// Worker capacity and the suggested pool size
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapacityReport {
+    pub workers: Vec<WorkerCapacity>,
+
+    pub current_workers: usize,
+
+    /// Workers needed to keep every worker at or below the target utilization
+    pub suggested_workers: usize,
+
+    pub action: ScalingAction,
+
+    pub targets: CapacityTargets,
+}
+
+/// Estimated RPC usage and cost of a tenant on one network
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetworkRpcCost {
+    pub network_slug: String,
+
+    /// Requests sent to RPC, including the tenant's share of shared block fetches
+    pub rpc_requests: f64,
+
+    /// Requests answered from the block cache
+    pub cached_requests: f64,
+
+    pub estimated_cost: f64,
+}
+
+/// Estimated RPC usage and cost of a tenant since a day, for chargeback
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantRpcCost {
+    pub tenant_id: Uuid,
+
+    /// First day included (UTC)
+    pub since: NaiveDate,
+
+    pub rpc_requests: f64,
+
+    pub cached_requests: f64,
+
+    pub estimated_cost: f64,
+
+    pub networks: Vec<NetworkRpcCost>,
+}
+
+impl WorkerMetrics {
+    /// Utilization of the worker's binding resource (0-1)
+    pub fn utilization(&self, max_tenants_per_worker: usize) -> f64 {
+        let tenants = self.tenant_count as f64 / self.tenant_cap(max_tenants_per_worker) as f64;
+        tenants
+            .max(self.cpu_usage / 100.0)
+            .max(self.memory_usage / 100.0)
+            .clamp(0.0, 1.0)
+    }
+}
+
+impl CapacityReport {
+    /// Size the worker pool for the given targets
+    pub fn plan(workers: &[WorkerMetrics], targets: CapacityTargets) -> Self {
+        let target = targets.target_utilization;
+        let tenants_at_target =
+            |tenant_cap: usize| ((tenant_cap as f64 * target).floor() as usize).max(1);
+
+        let mut capacities: Vec<WorkerCapacity> = workers
+            .iter()
+            .map(|worker| {
+                let utilization = worker.utilization(targets.max_tenants_per_worker);
+                let tenant_cap = worker.tenant_cap(targets.max_tenants_per_worker);
+                WorkerCapacity {
+                    worker_id: worker.worker_id.clone(),
+                    capacity_weight: worker.capacity_weight,
+                    tenant_count: worker.tenant_count,
+                    tenant_cap,
+                    utilization,
+                    headroom: (target - utilization).max(0.0),
+                    tenant_headroom: tenants_at_target(tenant_cap)
+                        .saturating_sub(worker.tenant_count),
+                    healthy: worker.is_healthy(),
+                }
+            })
+            .collect();
+        capacities.sort_by(|a, b| a.worker_id.cmp(&b.worker_id));
+
+        // Enough workers for the total load, and for the tenant count alone,
+        // sized like the current workers on average
+        let total_utilization: f64 = capacities.iter().map(|w| w.utilization).sum();
+        let total_tenants: usize = capacities.iter().map(|w| w.tenant_count).sum();
+        let average_weight = if capacities.is_empty() {
+            1.0
+        } else {
+            capacities.iter().map(|w| w.capacity_weight).sum::<f64>() / capacities.len() as f64
+        };
+        let by_load = (total_utilization / target).ceil() as usize;
+        let by_tenants = total_tenants.div_ceil(tenants_at_target(
+            (targets.max_tenants_per_worker as f64 * average_weight).round() as usize,
+        ));
+
+        let mut suggested_workers = by_load.max(by_tenants).max(targets.min_workers);
+        if let Some(max_workers) = targets.max_workers {
+            suggested_workers = suggested_workers.min(max_workers);
+        }
+
+        let current_workers = capacities.len();
+        let action = match suggested_workers.cmp(&current_workers) {
+            std::cmp::Ordering::Greater => ScalingAction::ScaleUp,
+            std::cmp::Ordering::Less => ScalingAction::ScaleDown,
+            std::cmp::Ordering::Equal => ScalingAction::Hold,
+        };
+
+        Self {
+            workers: capacities,
+            current_workers,
+            suggested_workers,
+            action,
+            targets,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn worker(id: &str, tenant_count: usize, cpu_usage: f64) -> WorkerMetrics {
+        WorkerMetrics {
+            worker_id: id.to_string(),
+            capacity_weight: 1.0,
+            tenant_count,
+            cpu_usage,
+            memory_usage: 10.0,
+            rpc_rate: 0.0,
+            avg_processing_time_ms: 0.0,
+            errors_last_hour: 0,
+            uptime_seconds: 0,
+            collected_at: Utc::now(),
+        }
+    }
+
+    fn targets() -> CapacityTargets {
+        CapacityTargets {
+            max_tenants_per_worker: 50,
+            target_utilization: 0.5,
+            min_workers: 1,
+            max_workers: Some(10),
+        }
+    }
+
+    #[test]
+    fn test_capacity_suggests_scale_up_above_target() {
+        let report =
+            CapacityReport::plan(&[worker("a", 40, 20.0), worker("b", 40, 20.0)], targets());
+
+        // 80 tenants at 25 per worker
+        assert_eq!(report.suggested_workers, 4);
+        assert_eq!(report.action, ScalingAction::ScaleUp);
+        assert_eq!(report.workers[0].utilization, 0.8);
+        assert_eq!(report.workers[0].headroom, 0.0);
+        assert_eq!(report.workers[0].tenant_headroom, 0);
+    }
+
+    #[test]
+    fn test_capacity_uses_busiest_resource() {
+        let report = CapacityReport::plan(&[worker("a", 5, 90.0), worker("b", 5, 90.0)], targets());
+
+        assert_eq!(report.workers[0].utilization, 0.9);
+        assert_eq!(report.workers[0].tenant_headroom, 20);
+        assert_eq!(report.suggested_workers, 4);
+    }
+
+    #[test]
+    fn test_capacity_scales_tenant_caps_by_weight() {
+        let large = WorkerMetrics {
+            capacity_weight: 2.0,
+            ..worker("a", 40, 20.0)
+        };
+        let report = CapacityReport::plan(&[large, worker("b", 20, 20.0)], targets());
+
+        // Twice the tenants at the same utilization
+        assert_eq!(report.workers[0].tenant_cap, 100);
+        assert_eq!(report.workers[1].tenant_cap, 50);
+        assert_eq!(report.workers[0].utilization, 0.4);
+        assert_eq!(report.workers[1].utilization, 0.4);
+        assert_eq!(report.workers[0].tenant_headroom, 10);
+        assert_eq!(report.workers[1].tenant_headroom, 5);
+    }
+
+    #[test]
+    fn test_capacity_scales_down_within_bounds() {
+        let idle: Vec<WorkerMetrics> = (0..4).map(|i| worker(&i.to_string(), 1, 5.0)).collect();
+
+        let report = CapacityReport::plan(&idle, targets());
+        assert_eq!(report.suggested_workers, 1);
+        assert_eq!(report.action, ScalingAction::ScaleDown);
+
+        let report = CapacityReport::plan(
+            &idle,
+            CapacityTargets {
+                min_workers: 4,
+                ..targets()
+            },
+        );
+        assert_eq!(report.action, ScalingAction::Hold);
+    }
+
+    #[test]
+    fn test_capacity_respects_max_workers() {
+        let busy: Vec<WorkerMetrics> = (0..3).map(|i| worker(&i.to_string(), 50, 95.0)).collect();
+
+        let report = CapacityReport::plan(
+            &busy,
+            CapacityTargets {
+                max_workers: Some(5),
+                ..targets()
+            },
+        );
+        assert_eq!(report.suggested_workers, 5);
+    }
+}
@@ -0,0 +1,164 @@
+//! Tenant migration models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::models::QuietHours;
+
+/// Tenant row as exported for migration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TenantRecord {
+    pub id: Uuid,
+    pub name: String,
+    pub is_active: bool,
+    pub max_monitors: i32,
+    pub max_rpc_requests_per_minute: i32,
+}
+
+/// Tenant network as exported for migration
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct NetworkRecord {
+    pub id: Uuid,
+    /// Network slug, unique per tenant
+    pub network_id: String,
+    pub name: String,
+    pub blockchain: String,
+    pub configuration: JsonValue,
+    pub is_active: bool,
+    pub confirmation_blocks: Option<i32>,
+    pub trigger_on_states: Vec<String>,
+}
+
+/// Tenant monitor as exported for migration
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MonitorRecord {
+    pub id: Uuid,
+    pub monitor_id: String,
+    /// Monitor name, unique per tenant
+    pub name: String,
+    /// Row id of the monitor's network in `tenant_networks`
+    pub network_id: Uuid,
+    pub configuration: JsonValue,
+    pub is_active: bool,
+    pub is_critical: bool,
+}
+
+/// Tenant trigger as exported for migration
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TriggerRecord {
+    pub id: Uuid,
+    pub trigger_id: String,
+    /// Row id of the trigger's monitor in `tenant_monitors`
+    pub monitor_id: Uuid,
+    /// Trigger name, unique per tenant
+    pub name: String,
+    #[sqlx(rename = "type")]
+    #[serde(rename = "type")]
+    pub trigger_type: String,
+    pub configuration: JsonValue,
+    pub is_active: bool,
+}
+
+/// Recorded match as exported for migration
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MatchRecord {
+    pub network_slug: String,
+    pub monitor_name: String,
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub state: String,
+    pub match_data: JsonValue,
+    pub first_seen_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A tenant's configuration and recent matches, copied between databases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantSnapshot {
+    pub tenant: TenantRecord,
+    pub networks: Vec<NetworkRecord>,
+    pub monitors: Vec<MonitorRecord>,
+    pub triggers: Vec<TriggerRecord>,
+    pub quiet_hours: Vec<QuietHours>,
+    /// Matches recorded since `matches_since`
+    pub matches: Vec<MatchRecord>,
+    pub matches_since: Option<DateTime<Utc>>,
+    pub exported_at: DateTime<Utc>,
+}
+
+/// Kind of record changed by a migration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationRecordKind {
+    Tenant,
+    Network,
+    Monitor,
+    Trigger,
+}
+
+/// What a migration does to a record on the target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationAction {
+    Create,
+    Update,
+    Unchanged,
+}
+
+/// One record of the snapshot matched against the target by its natural key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationChange {
+    pub kind: MigrationRecordKind,
+
+    /// Tenant id, network slug, monitor name or trigger name
+    pub key: String,
+
+    pub action: MigrationAction,
+
+    /// Row id in the source database
+    pub source_id: Uuid,
+
+    /// Row id in the target database, newly generated for created records
+    pub target_id: Uuid,
+
+    /// Fields that differ on the target; empty unless updated
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changed_fields: Vec<String>,
+}
+
+/// Diff of a snapshot against the target database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationPlan {
+    pub source_tenant_id: Uuid,
+    pub target_tenant_id: Uuid,
+    pub changes: Vec<MigrationChange>,
+
+    /// Quiet hours windows replacing the target tenant's windows
+    pub quiet_hours: usize,
+
+    /// Snapshot matches not yet recorded on the target
+    pub new_matches: usize,
+
+    /// Whether the plan was written to the target
+    pub applied: bool,
+}
+
+impl MigrationPlan {
+    /// Target row id of a source record, if the plan covers it
+    pub fn target_id(&self, kind: MigrationRecordKind, source_id: Uuid) -> Option<Uuid> {
+        self.changes
+            .iter()
+            .find(|change| change.kind == kind && change.source_id == source_id)
+            .map(|change| change.target_id)
+    }
+
+    /// Number of changes with the given action
+    pub fn count(&self, action: MigrationAction) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.action == action)
+            .count()
+    }
+}
@@ -3,13 +3,55 @@
 //! This module contains all the data structures used throughout the orchestrator,
 //! organized similarly to OpenZeppelin Monitor's models structure.
 
+pub mod anomaly;
 pub mod assignment;
+pub mod audit;
+pub mod bloom;
+pub mod checkpoint;
+pub mod confirmation;
+pub mod coverage;
+pub mod debug;
 pub mod error;
+pub mod match_webhook;
 pub mod metrics;
+pub mod migration;
+pub mod monitor;
+pub mod notification;
+pub mod schedule;
+pub mod severity;
+pub mod template;
 pub mod tenant;
+pub mod trigger_script;
 
 // Re-export main types
-pub use assignment::{AssignmentReason, TenantAssignment, WorkerAssignment};
+pub use anomaly::{AnomalyKind, TenantAnomaly};
+pub use assignment::{
+    AssignmentEvent, AssignmentEventKind, AssignmentReason, DrainState, ReassignedTenant,
+    RebalancePlan, ReconciliationReport, ShardBy, TenantAssignment, TenantShard, WorkerAssignment,
+    WorkerDrain,
+};
+pub use audit::{AuditEntry, AuditOutcome};
+pub use bloom::AddressBloom;
+pub use checkpoint::{TenantCheckpoint, TenantWatermark};
+pub use confirmation::MatchState;
+pub use coverage::{CoverageReport, MonitorCoverage, NetworkCoverage, TriggerCoverage};
+pub use debug::{FilterDebugSample, FilterDebugSettings};
 pub use error::ModelError;
-pub use metrics::{SystemMetrics, TenantMetrics, WorkerMetrics};
-pub use tenant::{TenantInfo, TenantPriority, TenantStatus};
+pub use match_webhook::{MatchWebhook, MatchWebhookInput};
+pub use metrics::{
+    CapacityReport, CapacityTargets, NetworkRpcCost, ScalingAction, SystemMetrics, TenantMetrics,
+    TenantRpcCost, WorkerCapacity, WorkerMetrics, WorkerUsage,
+};
+pub use migration::{
+    MatchRecord, MigrationAction, MigrationChange, MigrationPlan, MigrationRecordKind,
+    MonitorRecord, NetworkRecord, TenantRecord, TenantSnapshot, TriggerRecord,
+};
+pub use monitor::MonitorSummary;
+pub use notification::{DeadLetter, DeadLetterStatus, DeliveryRoute, TriggerTestResult};
+pub use schedule::{HeldNotification, QuietHours};
+pub use severity::{MatchSeverity, SeverityRoute, SeverityRule};
+pub use template::{
+    builtin_templates, MonitorTemplate, ParameterKind, RenderedTemplate, TemplateParameter,
+};
+pub use tenant::{RpcCapAction, TenantInfo, TenantPause, TenantPriority, TenantStatus};
+pub use trigger_script::{TriggerScript, TriggerScriptInput};
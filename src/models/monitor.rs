@@ -0,0 +1,36 @@
+//! Monitor listing models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Monitor of any tenant, as listed for platform-wide audits
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MonitorSummary {
+    /// Row id in `tenant_monitors`
+    pub id: Uuid,
+
+    pub tenant_id: Uuid,
+
+    pub tenant_name: String,
+
+    pub monitor_id: String,
+
+    pub name: String,
+
+    pub network_slug: String,
+
+    /// Contract addresses the monitor watches
+    pub addresses: Vec<String>,
+
+    pub is_active: bool,
+
+    pub is_critical: bool,
+
+    /// Why the monitor was deactivated after failing repeatedly
+    pub error_message: Option<String>,
+
+    pub created_at: DateTime<Utc>,
+
+    pub updated_at: DateTime<Utc>,
+}
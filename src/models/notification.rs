@@ -0,0 +1,131 @@
+//! Notification delivery models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use uuid::Uuid;
+
+use crate::models::ModelError;
+
+/// Path a trigger's notifications take
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryRoute {
+    /// Custom notification channel registered with the orchestrator
+    Custom,
+    /// OpenZeppelin Monitor's trigger execution service
+    Upstream,
+}
+
+/// Outcome of sending a test notification through a trigger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerTestResult {
+    /// Trigger that was fired
+    pub trigger_name: String,
+
+    /// Monitor whose match was used as the test payload
+    pub monitor_name: String,
+
+    /// Recorded match the test payload was built from
+    pub source_match_id: Uuid,
+
+    /// Whether the trigger went to a custom channel or upstream
+    pub route: DeliveryRoute,
+
+    /// Whether the notification was accepted by the channel
+    pub delivered: bool,
+
+    /// Delivery error, if any
+    pub error: Option<String>,
+
+    /// Time spent delivering, including retries
+    pub duration_ms: u64,
+}
+
+/// Redelivery state of a dead-lettered notification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeadLetterStatus {
+    /// Delivery failed; waiting for someone to requeue it
+    Failed,
+    /// Requeued; redelivered on the owning worker's next digest flush
+    Requeued,
+    /// Claimed by a worker for redelivery
+    Retrying,
+    /// Redelivered successfully
+    Delivered,
+}
+
+impl DeadLetterStatus {
+    /// Name used in storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeadLetterStatus::Failed => "failed",
+            DeadLetterStatus::Requeued => "requeued",
+            DeadLetterStatus::Retrying => "retrying",
+            DeadLetterStatus::Delivered => "delivered",
+        }
+    }
+}
+
+impl FromStr for DeadLetterStatus {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "failed" => Ok(DeadLetterStatus::Failed),
+            "requeued" => Ok(DeadLetterStatus::Requeued),
+            "retrying" => Ok(DeadLetterStatus::Retrying),
+            "delivered" => Ok(DeadLetterStatus::Delivered),
+            _ => Err(ModelError::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+impl TryFrom<String> for DeadLetterStatus {
+    type Error = ModelError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Notification whose trigger delivery failed
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DeadLetter {
+    /// Dead letter identifier
+    pub id: Uuid,
+
+    /// Owning tenant
+    pub tenant_id: Uuid,
+
+    /// Monitor that produced the match
+    pub monitor_name: String,
+
+    /// Triggers that failed to deliver
+    pub trigger_names: Vec<String>,
+
+    /// State of the match when delivery was attempted
+    pub match_state: String,
+
+    /// Serialized monitor match
+    pub match_data: serde_json::Value,
+
+    /// Error of the latest delivery attempt
+    pub error: String,
+
+    #[sqlx(try_from = "String")]
+    pub status: DeadLetterStatus,
+
+    /// Delivery attempts so far, including the original one
+    pub attempts: i32,
+
+    /// When the original delivery failed
+    pub failed_at: DateTime<Utc>,
+
+    /// When the notification was last requeued
+    pub requeued_at: Option<DateTime<Utc>>,
+
+    /// When redelivery succeeded
+    pub delivered_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,119 @@
+//! Notification schedule models
+
+use chrono::{DateTime, Datelike, Duration, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Window during which a tenant's notifications are held
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QuietHours {
+    /// Window identifier
+    pub id: Uuid,
+
+    /// Owning tenant
+    pub tenant_id: Uuid,
+
+    /// Local start of the window
+    pub start_time: NaiveTime,
+
+    /// Local end of the window (before `start_time` wraps past midnight)
+    pub end_time: NaiveTime,
+
+    /// Offset of the tenant's local time from UTC
+    pub utc_offset_minutes: i32,
+
+    /// Days the window starts on (0 = Monday .. 6 = Sunday); empty means every day
+    pub days_of_week: Vec<i16>,
+}
+
+impl QuietHours {
+    /// Check if the given instant falls inside this window
+    pub fn contains(&self, at: DateTime<Utc>) -> bool {
+        let local = at.naive_utc() + Duration::minutes(self.utc_offset_minutes as i64);
+        let time = local.time();
+
+        if self.start_time <= self.end_time {
+            time >= self.start_time && time < self.end_time && self.starts_on(local.weekday())
+        } else if time >= self.start_time {
+            // Evening part of a window that wraps past midnight
+            self.starts_on(local.weekday())
+        } else if time < self.end_time {
+            // Morning part, the window started the previous day
+            self.starts_on(local.weekday().pred())
+        } else {
+            false
+        }
+    }
+
+    fn starts_on(&self, weekday: chrono::Weekday) -> bool {
+        self.days_of_week.is_empty()
+            || self
+                .days_of_week
+                .contains(&(weekday.num_days_from_monday() as i16))
+    }
+}
+
+/// Notification held during quiet hours
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct HeldNotification {
+    /// Held notification identifier
+    pub id: Uuid,
+
+    /// Owning tenant
+    pub tenant_id: Uuid,
+
+    /// Monitor that produced the match
+    pub monitor_name: String,
+
+    /// Serialized monitor match
+    pub match_data: serde_json::Value,
+
+    /// When the notification was held
+    pub held_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn window(start: (u32, u32), end: (u32, u32), days: Vec<i16>) -> QuietHours {
+        QuietHours {
+            id: Uuid::new_v4(),
+            tenant_id: Uuid::new_v4(),
+            start_time: NaiveTime::from_hms_opt(start.0, start.1, 0).unwrap(),
+            end_time: NaiveTime::from_hms_opt(end.0, end.1, 0).unwrap(),
+            utc_offset_minutes: 0,
+            days_of_week: days,
+        }
+    }
+
+    #[test]
+    fn test_same_day_window() {
+        let quiet = window((12, 0), (13, 0), vec![]);
+
+        assert!(quiet.contains(Utc.with_ymd_and_hms(2024, 1, 1, 12, 30, 0).unwrap()));
+        assert!(!quiet.contains(Utc.with_ymd_and_hms(2024, 1, 1, 13, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_window_wrapping_midnight_uses_start_day() {
+        // Friday 22:00 to Saturday 07:00 only
+        let quiet = window((22, 0), (7, 0), vec![4]);
+
+        // 2024-01-05 is a Friday
+        assert!(quiet.contains(Utc.with_ymd_and_hms(2024, 1, 5, 23, 0, 0).unwrap()));
+        assert!(quiet.contains(Utc.with_ymd_and_hms(2024, 1, 6, 6, 0, 0).unwrap()));
+        assert!(!quiet.contains(Utc.with_ymd_and_hms(2024, 1, 6, 23, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_utc_offset() {
+        let mut quiet = window((9, 0), (10, 0), vec![]);
+        quiet.utc_offset_minutes = -300;
+
+        // 14:30 UTC is 09:30 at UTC-5
+        assert!(quiet.contains(Utc.with_ymd_and_hms(2024, 1, 1, 14, 30, 0).unwrap()));
+        assert!(!quiet.contains(Utc.with_ymd_and_hms(2024, 1, 1, 9, 30, 0).unwrap()));
+    }
+}
@@ -0,0 +1,93 @@
+//! Match severity models
+
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::models::ModelError;
+
+/// Severity a tenant's rules give a monitor match
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchSeverity {
+    /// No rule of a higher severity matched
+    #[default]
+    Info,
+    Warning,
+    Critical,
+}
+
+impl MatchSeverity {
+    /// Name used in trigger variables and storage
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchSeverity::Info => "info",
+            MatchSeverity::Warning => "warning",
+            MatchSeverity::Critical => "critical",
+        }
+    }
+}
+
+impl FromStr for MatchSeverity {
+    type Err = ModelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(MatchSeverity::Info),
+            "warning" => Ok(MatchSeverity::Warning),
+            "critical" => Ok(MatchSeverity::Critical),
+            _ => Err(ModelError::InvalidStatus(s.to_string())),
+        }
+    }
+}
+
+/// Condition giving a monitor's matches a severity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeverityRule {
+    pub monitor_name: String,
+
+    pub severity: MatchSeverity,
+
+    /// Tenant trigger script returning true for matches of this severity
+    pub script_name: String,
+
+    /// Arguments passed to the script
+    pub arguments: Option<String>,
+
+    pub timeout_ms: u32,
+}
+
+/// Delivery of a tenant's matches of one severity
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeverityRoute {
+    pub severity: MatchSeverity,
+
+    /// Triggers notified instead of the monitor's own; empty keeps the monitor's
+    pub triggers: Vec<String>,
+
+    /// Notifications of this severity sent per minute; unlimited if unset
+    pub max_notifications_per_minute: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity_round_trips_through_storage_name() {
+        for severity in [
+            MatchSeverity::Info,
+            MatchSeverity::Warning,
+            MatchSeverity::Critical,
+        ] {
+            assert_eq!(
+                severity.as_str().parse::<MatchSeverity>().unwrap(),
+                severity
+            );
+        }
+        assert!("error".parse::<MatchSeverity>().is_err());
+        assert!(MatchSeverity::Critical > MatchSeverity::Warning);
+        assert!(MatchSeverity::Warning > MatchSeverity::Info);
+    }
+}
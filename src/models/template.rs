@@ -0,0 +1,329 @@
+//! Monitor template models
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use std::collections::HashMap;
+
+use crate::models::ModelError;
+
+/// Kind of value a template parameter accepts
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ParameterKind {
+    /// EVM (0x-prefixed) or Stellar contract address
+    Address,
+
+    /// Unsigned integer, e.g. a token amount in base units
+    Uint,
+
+    /// HTTP(S) URL
+    Url,
+
+    /// Free-form text
+    Text,
+}
+
+impl ParameterKind {
+    /// Check if a value is acceptable for this kind
+    pub fn accepts(&self, value: &str) -> bool {
+        match self {
+            ParameterKind::Address => is_evm_address(value) || is_stellar_contract(value),
+            ParameterKind::Uint => !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()),
+            ParameterKind::Url => value.starts_with("http://") || value.starts_with("https://"),
+            ParameterKind::Text => !value.is_empty(),
+        }
+    }
+}
+
+/// Parameter filled in when instantiating a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateParameter {
+    /// Placeholder name, referenced as `{{name}}` in the template
+    pub name: String,
+
+    /// Human-readable description
+    pub description: String,
+
+    /// Accepted value kind
+    pub kind: ParameterKind,
+
+    /// Value used when the parameter is omitted; required if unset
+    pub default: Option<String>,
+}
+
+/// Platform-level monitor and trigger blueprint that tenants instantiate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonitorTemplate {
+    /// Template identifier
+    pub id: String,
+
+    /// Human-readable description
+    pub description: String,
+
+    /// Parameters substituted into the monitor and trigger
+    pub parameters: Vec<TemplateParameter>,
+
+    /// Monitor configuration with `{{parameter}}` placeholders
+    pub monitor: JsonValue,
+
+    /// Trigger configuration with `{{parameter}}` placeholders
+    pub trigger: JsonValue,
+}
+
+/// Monitor and trigger configurations produced from a template
+#[derive(Debug, Clone)]
+pub struct RenderedTemplate {
+    pub monitor: JsonValue,
+    pub trigger: JsonValue,
+}
+
+impl MonitorTemplate {
+    /// Validate parameters and substitute them into the monitor and trigger
+    pub fn render(&self, params: &HashMap<String, String>) -> Result<RenderedTemplate, ModelError> {
+        if let Some(unknown) = params
+            .keys()
+            .find(|key| !self.parameters.iter().any(|p| &p.name == *key))
+        {
+            return Err(ModelError::ValidationError(format!(
+                "template {} has no parameter {}",
+                self.id, unknown
+            )));
+        }
+
+        let mut values = HashMap::new();
+        for parameter in &self.parameters {
+            let value = params
+                .get(&parameter.name)
+                .or(parameter.default.as_ref())
+                .ok_or_else(|| {
+                    ModelError::ValidationError(format!(
+                        "missing required parameter {}",
+                        parameter.name
+                    ))
+                })?;
+
+            if !parameter.kind.accepts(value) {
+                return Err(ModelError::ValidationError(format!(
+                    "invalid {:?} value for parameter {}: {}",
+                    parameter.kind, parameter.name, value
+                )));
+            }
+            values.insert(parameter.name.as_str(), value.as_str());
+        }
+
+        Ok(RenderedTemplate {
+            monitor: substitute(&self.monitor, &values)?,
+            trigger: substitute(&self.trigger, &values)?,
+        })
+    }
+}
+
+/// Replace `{{name}}` placeholders in every string of a JSON value
+fn substitute(value: &JsonValue, values: &HashMap<&str, &str>) -> Result<JsonValue, ModelError> {
+    Ok(match value {
+        JsonValue::String(s) => {
+            let mut rendered = s.clone();
+            for (name, value) in values {
+                rendered = rendered.replace(&format!("{{{{{}}}}}", name), value);
+            }
+            if rendered.contains("{{") {
+                return Err(ModelError::ValidationError(format!(
+                    "unresolved placeholder in {}",
+                    s
+                )));
+            }
+            JsonValue::String(rendered)
+        }
+        JsonValue::Array(items) => JsonValue::Array(
+            items
+                .iter()
+                .map(|item| substitute(item, values))
+                .collect::<Result<_, _>>()?,
+        ),
+        JsonValue::Object(fields) => JsonValue::Object(
+            fields
+                .iter()
+                .map(|(key, item)| Ok((key.clone(), substitute(item, values)?)))
+                .collect::<Result<_, ModelError>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn is_evm_address(value: &str) -> bool {
+    value.len() == 42
+        && value.starts_with("0x")
+        && value[2..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+fn is_stellar_contract(value: &str) -> bool {
+    value.len() == 56
+        && value.starts_with('C')
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_uppercase() || (b'2'..=b'7').contains(&b))
+}
+
+fn parameter(name: &str, description: &str, kind: ParameterKind) -> TemplateParameter {
+    TemplateParameter {
+        name: name.to_string(),
+        description: description.to_string(),
+        kind,
+        default: None,
+    }
+}
+
+/// Webhook trigger shared by the built-in templates
+fn webhook_trigger(title: &str) -> JsonValue {
+    json!({
+        "name": "{{name}}-webhook",
+        "trigger_type": "webhook",
+        "config": {
+            "url": { "type": "plain", "value": "{{webhook_url}}" },
+            "method": "POST",
+            "headers": { "Content-Type": "application/json" },
+            "message": {
+                "title": title,
+                "body": "${monitor.name} matched transaction ${transaction.hash}"
+            }
+        }
+    })
+}
+
+/// Event-only monitor on a single contract shared by the built-in templates
+fn event_monitor(signature: &str, expression: Option<&str>) -> JsonValue {
+    json!({
+        "name": "{{name}}",
+        "networks": [],
+        "paused": false,
+        "addresses": [{ "address": "{{contract}}" }],
+        "match_conditions": {
+            "functions": [],
+            "events": [{ "signature": signature, "expression": expression }],
+            "transactions": []
+        },
+        "trigger_conditions": [],
+        "triggers": ["{{name}}-webhook"]
+    })
+}
+
+/// Templates shipped with the orchestrator
+pub fn builtin_templates() -> Vec<MonitorTemplate> {
+    let name = parameter("name", "Monitor name", ParameterKind::Text);
+    let webhook_url = parameter(
+        "webhook_url",
+        "Webhook receiving alerts",
+        ParameterKind::Url,
+    );
+
+    vec![
+        MonitorTemplate {
+            id: "erc20_transfer".to_string(),
+            description: "Watch ERC20 transfers of a token above a minimum amount".to_string(),
+            parameters: vec![
+                name.clone(),
+                parameter("contract", "Token contract address", ParameterKind::Address),
+                TemplateParameter {
+                    default: Some("0".to_string()),
+                    ..parameter(
+                        "min_amount",
+                        "Minimum amount in base units",
+                        ParameterKind::Uint,
+                    )
+                },
+                webhook_url.clone(),
+            ],
+            monitor: event_monitor(
+                "Transfer(address,address,uint256)",
+                Some("value > {{min_amount}}"),
+            ),
+            trigger: webhook_trigger("Token transfer"),
+        },
+        MonitorTemplate {
+            id: "ownership_change".to_string(),
+            description: "Alert when ownership of a contract is transferred".to_string(),
+            parameters: vec![
+                name.clone(),
+                parameter("contract", "Owned contract address", ParameterKind::Address),
+                webhook_url.clone(),
+            ],
+            monitor: event_monitor("OwnershipTransferred(address,address)", None),
+            trigger: webhook_trigger("Ownership transferred"),
+        },
+        MonitorTemplate {
+            id: "large_withdrawal".to_string(),
+            description: "Alert on withdrawals from a contract above a threshold".to_string(),
+            parameters: vec![
+                name,
+                parameter("contract", "Contract address", ParameterKind::Address),
+                parameter(
+                    "threshold",
+                    "Withdrawal amount in base units",
+                    ParameterKind::Uint,
+                ),
+                webhook_url,
+            ],
+            monitor: event_monitor("Withdrawal(address,uint256)", Some("wad > {{threshold}}")),
+            trigger: webhook_trigger("Large withdrawal"),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn erc20_params() -> HashMap<String, String> {
+        HashMap::from([
+            ("name".to_string(), "usdc-transfers".to_string()),
+            (
+                "contract".to_string(),
+                "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".to_string(),
+            ),
+            (
+                "webhook_url".to_string(),
+                "https://example.com/hook".to_string(),
+            ),
+        ])
+    }
+
+    fn erc20_template() -> MonitorTemplate {
+        builtin_templates()
+            .into_iter()
+            .find(|t| t.id == "erc20_transfer")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_substitutes_parameters_and_defaults() {
+        let rendered = erc20_template().render(&erc20_params()).unwrap();
+
+        assert_eq!(rendered.monitor["name"], "usdc-transfers");
+        assert_eq!(
+            rendered.monitor["match_conditions"]["events"][0]["expression"],
+            "value > 0"
+        );
+        assert_eq!(rendered.trigger["name"], "usdc-transfers-webhook");
+        assert_eq!(
+            rendered.trigger["config"]["url"]["value"],
+            "https://example.com/hook"
+        );
+    }
+
+    #[test]
+    fn test_render_rejects_invalid_parameters() {
+        let template = erc20_template();
+
+        let mut params = erc20_params();
+        params.insert("contract".to_string(), "not-an-address".to_string());
+        assert!(template.render(&params).is_err());
+
+        let mut params = erc20_params();
+        params.remove("webhook_url");
+        assert!(template.render(&params).is_err());
+
+        let mut params = erc20_params();
+        params.insert("unknown".to_string(), "value".to_string());
+        assert!(template.render(&params).is_err());
+    }
+}
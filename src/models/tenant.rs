@@ -32,6 +32,17 @@ pub struct TenantInfo {
     pub last_active_at: DateTime<Utc>,
 }
 
+/// Pause of a tenant's block processing, kept until the tenant is resumed
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TenantPause {
+    pub tenant_id: Uuid,
+
+    /// Why the tenant was paused, as given by the operator
+    pub reason: Option<String>,
+
+    pub paused_at: DateTime<Utc>,
+}
+
 /// Tenant status
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -83,3 +94,32 @@ impl TenantInfo {
         self.priority as u8
     }
 }
+
+/// Action taken when a tenant exceeds `max_rpc_requests_per_minute`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcCapAction {
+    /// Skip the tenant's blocks for the rest of the minute
+    Throttle,
+
+    /// Keep processing only monitors marked critical for the rest of the minute
+    SkipNonCritical,
+
+    /// Record a cap event the tenant is notified about
+    Notify,
+
+    /// Deactivate the tenant until it is re-enabled
+    Suspend,
+}
+
+impl RpcCapAction {
+    /// Configuration name of the action
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RpcCapAction::Throttle => "throttle",
+            RpcCapAction::SkipNonCritical => "skip_non_critical",
+            RpcCapAction::Notify => "notify",
+            RpcCapAction::Suspend => "suspend",
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Trigger condition script models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Script a tenant's monitors run as a trigger condition
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct TriggerScript {
+    pub tenant_id: Uuid,
+
+    /// Name trigger conditions refer to in their `script_path`
+    pub name: String,
+
+    /// Executor language (`Python`, `JavaScript` or `Bash`)
+    pub language: String,
+
+    pub content: String,
+
+    /// Inactive scripts are not loaded by workers
+    pub is_active: bool,
+
+    pub created_at: DateTime<Utc>,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Body of `PUT /tenants/{tenant_id}/scripts/{name}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TriggerScriptInput {
+    pub language: String,
+
+    pub content: String,
+
+    #[serde(default = "default_is_active")]
+    pub is_active: bool,
+}
+
+fn default_is_active() -> bool {
+    true
+}
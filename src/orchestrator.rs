@@ -0,0 +1,1098 @@
+//! Orchestrator assembly
+//!
+//! Wires the orchestrator services together for each service mode so that the
+//! binary and other crates embedding the orchestrator share the same startup
+//! logic, with the option to inject custom components through the builder.
+
+use anyhow::{Context, Result};
+use openzeppelin_monitor::repositories::NetworkRepositoryTrait;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::signal;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::api::health::HealthState;
+use crate::api::{self, ApiState};
+use crate::config::{OrchestratorConfig, ServiceMode};
+use crate::models::WorkerAssignment;
+use crate::repositories::TenantAwareNetworkRepository;
+use crate::services::{
+    activity_anomalies::ActivityAnomalyDetector,
+    assignment_store::AssignmentStore,
+    assignment_webhooks::AssignmentWebhookNotifier,
+    block_cache::{BlockCacheConfig, BlockCacheService},
+    cached_client_pool::CachedClientPool,
+    chain_support::ChainSupport,
+    confirmations::ConfirmationDepths,
+    control_channel::ControlChannel,
+    distributed_lock::DistributedLock,
+    endpoint_health::EndpointHealth,
+    event_bus::{EventBusBackend, RedisEventBus},
+    hooks::{LifecycleHook, LifecycleHooks},
+    load_balancer::{LoadBalancer, PlacementStrategy},
+    match_feed::MatchFeed,
+    network_settings::NetworkSettingsResolver,
+    notification_channels::{NotificationChannel, NotificationChannels},
+    oz_monitor_integration::OzMonitorServices,
+    publish_buffer::PublishBuffer,
+    rebalance_scheduler::RebalanceScheduler,
+    redis_keyspace::RedisKeyspace,
+    resource_usage::ResourceSampler,
+    retry::RetryPolicy,
+    rpc_costs::RpcCostTracker,
+    shared_block_watcher::{SharedBlockWatcher, SharedBlockWatcherConfig},
+    shutdown::{ShutdownSequence, ShutdownSignal},
+    startup_checks::{CheckStatus, StartupChecks},
+    trigger_scripts::TriggerScriptStore,
+    watcher_handoff::WatcherHandoff,
+    worker_pool::{BlockOverflowPolicy, MonitorWorkerPool},
+};
+
+/// How long a crashed coordinator keeps other coordinators from rebalancing
+const REBALANCE_LOCK_TTL: Duration = Duration::from_secs(30);
+
+/// How often tenant pins unused for `load_balancer.pin_ttl` are dropped
+const PIN_PRUNE_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Fully wired orchestrator ready to run in a service mode
+#[derive(Clone)]
+pub struct Orchestrator {
+    config: OrchestratorConfig,
+    mode: ServiceMode,
+    worker_id: String,
+    /// This worker's capacity relative to a worker of weight 1
+    capacity_weight: f64,
+    db: Arc<PgPool>,
+    cache: Arc<BlockCacheService>,
+    client_pool: Arc<CachedClientPool>,
+    block_watcher: Arc<SharedBlockWatcher>,
+    worker_pool: Arc<MonitorWorkerPool>,
+    load_balancer: Arc<LoadBalancer>,
+    assignment_store: Arc<AssignmentStore>,
+    /// Sampler of this process's CPU and memory usage; None where sampling is unsupported
+    resource_usage: Option<Arc<ResourceSampler>>,
+    /// Shutdown shared by the API server and the service modes
+    shutdown: ShutdownSignal,
+}
+
+/// Builder for [`Orchestrator`]
+///
+/// Only the configuration is required; every component that is not injected
+/// is created from the configuration the same way the binary does.
+#[derive(Default)]
+pub struct OrchestratorBuilder {
+    config: Option<OrchestratorConfig>,
+    mode: Option<ServiceMode>,
+    worker_id: Option<String>,
+    db: Option<Arc<PgPool>>,
+    cache_config: Option<BlockCacheConfig>,
+    cache: Option<Arc<BlockCacheService>>,
+    client_pool: Option<Arc<CachedClientPool>>,
+    block_watcher: Option<Arc<SharedBlockWatcher>>,
+    load_balancer: Option<Arc<LoadBalancer>>,
+    hooks: LifecycleHooks,
+    notification_channels: NotificationChannels,
+    strategies: Vec<(String, Arc<dyn PlacementStrategy>)>,
+    shutdown: Option<ShutdownSignal>,
+}
+
+impl OrchestratorBuilder {
+    /// Set the orchestrator configuration
+    pub fn config(mut self, config: OrchestratorConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Set the service mode (defaults to `config.service_mode`)
+    pub fn mode(mut self, mode: ServiceMode) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Set the worker identifier (defaults to `WORKER_ID` or `config.worker.identity`)
+    pub fn worker_id(mut self, worker_id: impl Into<String>) -> Self {
+        self.worker_id = Some(worker_id.into());
+        self
+    }
+
+    /// Use an existing database pool instead of connecting to `config.database_url`
+    pub fn database(mut self, db: impl Into<Arc<PgPool>>) -> Self {
+        self.db = Some(db.into());
+        self
+    }
+
+    /// Override the block cache configuration
+    pub fn cache(mut self, cache_config: BlockCacheConfig) -> Self {
+        self.cache_config = Some(cache_config);
+        self
+    }
+
+    /// Use an existing block cache service
+    pub fn cache_service(mut self, cache: Arc<BlockCacheService>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Use an existing client pool
+    pub fn client_pool(mut self, client_pool: Arc<CachedClientPool>) -> Self {
+        self.client_pool = Some(client_pool);
+        self
+    }
+
+    /// Use an existing shared block watcher
+    pub fn block_watcher(mut self, block_watcher: Arc<SharedBlockWatcher>) -> Self {
+        self.block_watcher = Some(block_watcher);
+        self
+    }
+
+    /// Use an existing load balancer
+    pub fn load_balancer(mut self, load_balancer: Arc<LoadBalancer>) -> Self {
+        self.load_balancer = Some(load_balancer);
+        self
+    }
+
+    /// Shut down when the given signal is triggered, in addition to Ctrl+C and SIGTERM
+    pub fn shutdown_signal(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = Some(shutdown);
+        self
+    }
+
+    /// Register a worker lifecycle hook
+    pub fn hook(mut self, hook: Arc<dyn LifecycleHook>) -> Self {
+        self.hooks.register(hook);
+        self
+    }
+
+    /// Register a custom notification channel for a trigger name
+    pub fn notification_channel(
+        mut self,
+        trigger_name: impl Into<String>,
+        channel: Arc<dyn NotificationChannel>,
+    ) -> Self {
+        self.notification_channels.register(trigger_name, channel);
+        self
+    }
+
+    /// Register a load balancing strategy selectable by name from configuration
+    pub fn placement_strategy(
+        mut self,
+        name: impl Into<String>,
+        strategy: Arc<dyn PlacementStrategy>,
+    ) -> Self {
+        self.strategies.push((name.into(), strategy));
+        self
+    }
+
+    /// Connect to dependencies and wire all services
+    pub async fn build(self) -> Result<Orchestrator> {
+        let config = self
+            .config
+            .context("Orchestrator configuration is required")?;
+        let mode = self.mode.unwrap_or_else(|| config.service_mode.clone());
+
+        let worker_id = match self.worker_id.or_else(|| std::env::var("WORKER_ID").ok()) {
+            Some(worker_id) => worker_id,
+            None => config
+                .worker
+                .identity
+                .resolve()
+                .context("Failed to resolve the worker ID")?,
+        };
+        let capacity_weight = match std::env::var("WORKER_CAPACITY_WEIGHT") {
+            Ok(weight) => weight
+                .parse::<f64>()
+                .ok()
+                .filter(|weight| weight.is_finite() && *weight > 0.0)
+                .with_context(|| {
+                    format!(
+                        "WORKER_CAPACITY_WEIGHT must be a number greater than 0, got {}",
+                        weight
+                    )
+                })?,
+            Err(_) => config.worker.capacity_weight,
+        };
+
+        // Connect to database
+        let db = match self.db {
+            Some(db) => db,
+            None => Arc::new(
+                PgPool::connect(&config.database_url)
+                    .await
+                    .context("Failed to connect to database")?,
+            ),
+        };
+
+        let retry_policy: RetryPolicy = config.retry.clone().into();
+
+        // Initialize block cache
+        let cache = match self.cache {
+            Some(cache) => cache,
+            None => {
+                let cache_config = self
+                    .cache_config
+                    .unwrap_or_else(|| config.block_cache.clone().into());
+                let cache = retry_policy
+                    .retry(|| BlockCacheService::new(&config.redis_url, cache_config.clone()))
+                    .await
+                    .context("Failed to initialize block cache")?
+                    .with_keyspace(RedisKeyspace::new(config.redis_namespace.clone()));
+                if config.rpc_costs.enabled {
+                    let rpc_costs =
+                        RpcCostTracker::new(db.clone(), config.rpc_costs.clone().into());
+                    Arc::new(cache.with_rpc_costs(Arc::new(rpc_costs)))
+                } else {
+                    Arc::new(cache)
+                }
+            }
+        };
+
+        // Initialize cached client pool
+        let client_pool = self.client_pool.unwrap_or_else(|| {
+            let client_pool =
+                CachedClientPool::new(cache.clone()).with_retry_policy(retry_policy.clone());
+            if config.stellar_endpoints.failover {
+                let endpoint_health = EndpointHealth::new(config.stellar_endpoints.clone().into());
+                Arc::new(client_pool.with_endpoint_health(Arc::new(endpoint_health)))
+            } else {
+                Arc::new(client_pool)
+            }
+        });
+
+        // Every component stops on a child of the process shutdown signal
+        let shutdown = self.shutdown.unwrap_or_default();
+
+        // Initialize shared block watcher
+        let block_watcher = match self.block_watcher {
+            Some(block_watcher) => block_watcher,
+            None => {
+                let mut watcher_config: SharedBlockWatcherConfig =
+                    config.block_watcher.clone().into();
+                watcher_config.backpressure =
+                    config.worker.overflow_policy == BlockOverflowPolicy::Block;
+                let buffer_size = watcher_config.channel_buffer_size;
+                let mut block_watcher = SharedBlockWatcher::new(cache.clone(), watcher_config)
+                    .with_chain_support(config.chains.clone().into())
+                    .with_shutdown(shutdown.child());
+                let handoff = config.block_watcher.handoff.then(|| {
+                    Arc::new(WatcherHandoff::new(
+                        cache.redis_client(),
+                        cache.keyspace().clone(),
+                        format!("{}:{}", worker_id, Uuid::new_v4()),
+                        config.block_watcher.handoff_lease_ttl,
+                    ))
+                });
+                if config.event_bus.backend == EventBusBackend::Redis {
+                    // Block events are buffered locally while Redis is unreachable
+                    let mut publish_buffer = PublishBuffer::new(
+                        Arc::new(RedisEventBus::new(
+                            cache.redis_client(),
+                            cache.keyspace().clone(),
+                            buffer_size,
+                        )),
+                        &config.event_bus.buffer_dir,
+                        &format!("{}-block-events", worker_id),
+                        config.event_bus.buffer_memory,
+                        config.event_bus.buffer_retry_interval,
+                    )
+                    .await
+                    .context("Failed to create the block event publish buffer")?;
+                    if let Some(handoff) = &handoff {
+                        publish_buffer = publish_buffer.with_handoff(handoff.clone());
+                    }
+                    block_watcher = block_watcher.with_publish_buffer(Arc::new(publish_buffer));
+                }
+                match handoff {
+                    Some(handoff) => Arc::new(block_watcher.with_handoff(handoff)),
+                    None => Arc::new(block_watcher),
+                }
+            }
+        };
+
+        let resource_usage = match ResourceSampler::new(worker_id.clone()) {
+            Ok(sampler) => Some(Arc::new(sampler)),
+            Err(e) => {
+                warn!("Resource usage sampling disabled: {}", e);
+                None
+            }
+        };
+
+        // Initialize worker pool
+        let mut notification_channels = self.notification_channels;
+        notification_channels.set_retry_policy(retry_policy.clone());
+        let mut worker_pool =
+            MonitorWorkerPool::new(db.clone(), cache.clone(), config.worker.clone().into())
+                .with_hooks(self.hooks)
+                .with_notification_channels(notification_channels)
+                .with_shutdown(shutdown.child());
+        if let Some(resource_usage) = &resource_usage {
+            worker_pool = worker_pool.with_resource_usage(resource_usage.clone());
+        }
+        let worker_pool = Arc::new(worker_pool);
+
+        // Workers silent for longer than the worker timeout are considered dead
+        let assignment_store = Arc::new(AssignmentStore::new(
+            db.clone(),
+            cache.redis_client(),
+            cache.keyspace().clone(),
+            config.load_balancer.worker_timeout,
+        ));
+
+        // Initialize load balancer
+        let load_balancer = self.load_balancer.unwrap_or_else(|| {
+            let webhooks = AssignmentWebhookNotifier::new(
+                config.webhooks.iter().cloned().map(Into::into).collect(),
+            )
+            .with_retry_policy(retry_policy.clone());
+            let load_balancer = self.strategies.into_iter().fold(
+                LoadBalancer::new(config.load_balancer.clone().into()),
+                |load_balancer, (name, strategy)| load_balancer.register_strategy(name, strategy),
+            );
+            let control = ControlChannel::new(cache.redis_client(), cache.keyspace().clone());
+            Arc::new(
+                load_balancer
+                    .with_webhooks(Arc::new(webhooks))
+                    .with_control_channel(Arc::new(control))
+                    .with_assignment_store(assignment_store.clone())
+                    .with_rebalance_lock(DistributedLock::new(
+                        cache.redis_client(),
+                        cache.keyspace(),
+                        "lock:rebalance",
+                        format!("{}:{}", worker_id, Uuid::new_v4()),
+                        REBALANCE_LOCK_TTL,
+                    )),
+            )
+        });
+        load_balancer.validate_strategy()?;
+
+        Ok(Orchestrator {
+            config,
+            mode,
+            worker_id,
+            capacity_weight,
+            db,
+            cache,
+            client_pool,
+            block_watcher,
+            worker_pool,
+            load_balancer,
+            assignment_store,
+            resource_usage,
+            shutdown,
+        })
+    }
+}
+
+impl Orchestrator {
+    /// Create a builder for embedding the orchestrator
+    pub fn builder() -> OrchestratorBuilder {
+        OrchestratorBuilder::default()
+    }
+
+    /// Get the configured service mode
+    pub fn mode(&self) -> &ServiceMode {
+        &self.mode
+    }
+
+    /// Get the load balancer
+    pub fn load_balancer(&self) -> Arc<LoadBalancer> {
+        self.load_balancer.clone()
+    }
+
+    /// Get the worker pool
+    pub fn worker_pool(&self) -> Arc<MonitorWorkerPool> {
+        self.worker_pool.clone()
+    }
+
+    /// Get the shared block watcher
+    pub fn block_watcher(&self) -> Arc<SharedBlockWatcher> {
+        self.block_watcher.clone()
+    }
+
+    /// Shutdown signal of the running service mode
+    pub fn shutdown_signal(&self) -> ShutdownSignal {
+        self.shutdown.clone()
+    }
+
+    /// Run the configured service mode until shutdown
+    pub async fn run(self) -> Result<()> {
+        if self.config.startup_checks.enabled {
+            self.check_dependencies().await?;
+        }
+
+        let signals = self.shutdown.trigger_on_signal();
+        let health = self.start_health_server();
+        let rpc_costs = self.cache.rpc_costs();
+        let rpc_cost_flush = rpc_costs.as_ref().map(|rpc_costs| rpc_costs.start_flush());
+
+        let result = match self.mode {
+            ServiceMode::Worker => self.run_worker().await,
+            ServiceMode::BlockWatcher => self.run_block_watcher().await,
+            ServiceMode::Api => self.run_api().await,
+            ServiceMode::All => self.run_all().await,
+        };
+
+        signals.abort();
+        if let Some(health) = health {
+            health.abort();
+        }
+        if let (Some(rpc_costs), Some(flush)) = (rpc_costs, rpc_cost_flush) {
+            flush.abort();
+            if let Err(e) = rpc_costs.flush().await {
+                warn!("Failed to flush RPC usage on shutdown: {}", e);
+            }
+        }
+        result
+    }
+
+    /// Verify the database schema, Redis and RPC endpoints the service mode
+    /// depends on, failing with a report of what is wrong
+    async fn check_dependencies(&self) -> Result<()> {
+        let checks = StartupChecks::new(
+            self.db.clone(),
+            self.cache.clone(),
+            self.client_pool.clone(),
+            self.config.startup_checks.clone().into(),
+        )
+        .with_chain_support(ChainSupport::from(self.config.chains.clone()));
+        let report = checks.run(&self.mode).await;
+
+        for check in &report.checks {
+            match check.status {
+                CheckStatus::Passed => info!("Startup check {}: {}", check.name, check.detail),
+                CheckStatus::Warning => warn!(
+                    "Startup check {}: {} ({})",
+                    check.name,
+                    check.detail,
+                    check.hint.as_deref().unwrap_or_default()
+                ),
+                CheckStatus::Failed => error!(
+                    "Startup check {} failed: {} ({})",
+                    check.name,
+                    check.detail,
+                    check.hint.as_deref().unwrap_or_default()
+                ),
+            }
+        }
+        if !report.passed() {
+            anyhow::bail!("Startup checks failed:\n{}", report);
+        }
+        Ok(())
+    }
+
+    /// Serve liveness and readiness probes for the service mode
+    fn start_health_server(&self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.config.health.enabled {
+            return None;
+        }
+
+        let config = self.config.health.clone();
+        let state = HealthState {
+            mode: self.mode.clone(),
+            db: self.db.clone(),
+            cache: self.cache.clone(),
+            worker_pool: self.worker_pool.clone(),
+            block_watcher: self.block_watcher.clone(),
+        };
+        Some(tokio::spawn(async move {
+            if let Err(e) = api::health::serve(&config, state, std::future::pending()).await {
+                error!("Health server failed: {:#}", e);
+            }
+        }))
+    }
+
+    async fn run_worker(&self) -> Result<()> {
+        info!("Starting in Worker mode");
+        info!("Worker ID: {}", self.worker_id);
+
+        if self.config.worker.standby {
+            return self.run_standby_worker().await;
+        }
+
+        // Register with load balancer
+        self.load_balancer
+            .add_worker(self.worker_id.clone(), self.capacity_weight)
+            .await?;
+        let heartbeat = self.start_heartbeat().await;
+        let sampler = self.start_resource_sampler();
+        let tenant_metrics = self.start_tenant_metrics();
+        let endpoint_health = self.start_endpoint_health();
+        let pin_pruning = self.load_balancer.spawn_pin_pruning(PIN_PRUNE_INTERVAL);
+
+        // Take back the tenants this worker had before a restart
+        if let Err(e) = self.load_balancer.load_state().await {
+            warn!("Failed to load persisted assignments: {}", e);
+        }
+
+        // Get initial tenant assignments
+        let mut assignment = WorkerAssignment::new(self.worker_id.clone());
+        assignment.tenant_ids = self
+            .load_balancer
+            .get_worker_assignments(&self.worker_id)
+            .await?;
+        assignment.shards = self.load_balancer.get_worker_shards(&self.worker_id).await;
+
+        // If no tenants assigned, take the tenants no live worker holds
+        if assignment.tenant_ids.is_empty() && assignment.shards.is_empty() {
+            info!("No tenants assigned to worker, checking for unassigned tenants...");
+            let all_tenant_ids = get_all_tenant_ids(&self.db).await?;
+            let assigned = self.load_balancer.tenant_assignments().await;
+            let live_workers = self
+                .assignment_store
+                .live_workers()
+                .await
+                .unwrap_or_else(|e| {
+                    warn!("Failed to read live workers: {}", e);
+                    Default::default()
+                });
+            let unassigned: Vec<Uuid> = all_tenant_ids
+                .into_iter()
+                .filter(|tenant_id| {
+                    assigned
+                        .get(tenant_id)
+                        .map_or(true, |a| !live_workers.contains(&a.worker_id))
+                })
+                .collect();
+            info!("Found {} unassigned tenants in database", unassigned.len());
+            assignment = self.assign_tenants(&unassigned).await;
+        }
+
+        info!(
+            "Worker {} assigned {} tenants and {} tenant shards",
+            self.worker_id,
+            assignment.tenant_count(),
+            assignment.shards.len()
+        );
+
+        // Create and start the worker
+        self.worker_pool
+            .create_worker_with_assignment(
+                assignment,
+                self.block_watcher.clone(),
+                self.client_pool.clone(),
+            )
+            .await?;
+
+        info!("Worker started successfully");
+        self.shutdown.wait().await;
+        ShutdownSequence::new(self.config.shutdown.grace_period)
+            .stage("workers", self.worker_pool.shutdown())
+            .finally("cleanup", async move {
+                pin_pruning.abort();
+                if let Some(sampler) = sampler {
+                    sampler.abort();
+                }
+                if let Some(endpoint_health) = endpoint_health {
+                    endpoint_health.abort();
+                }
+                tenant_metrics.abort();
+                self.stop_heartbeat(heartbeat).await;
+            })
+            .run()
+            .await;
+
+        Ok(())
+    }
+
+    /// Run a warm worker without tenants until a coordinator promotes it
+    async fn run_standby_worker(&self) -> Result<()> {
+        self.assignment_store
+            .register_standby(&self.worker_id)
+            .await
+            .context("Failed to register standby worker")?;
+        // Read by the coordinator promoting this worker
+        if let Err(e) = self
+            .assignment_store
+            .report_capacity_weight(&self.worker_id, self.capacity_weight)
+            .await
+        {
+            warn!(
+                "Failed to publish capacity weight of worker {}: {}",
+                self.worker_id, e
+            );
+        }
+        let heartbeat = self.start_heartbeat().await;
+        let sampler = self.start_resource_sampler();
+        let tenant_metrics = self.start_tenant_metrics();
+        let endpoint_health = self.start_endpoint_health();
+
+        // Tenants arrive over the control channel once promoted
+        self.worker_pool
+            .create_worker_with_assignment(
+                WorkerAssignment::new(self.worker_id.clone()),
+                self.block_watcher.clone(),
+                self.client_pool.clone(),
+            )
+            .await?;
+
+        info!("Worker {} started as standby", self.worker_id);
+        self.shutdown.wait().await;
+        ShutdownSequence::new(self.config.shutdown.grace_period)
+            .stage("workers", self.worker_pool.shutdown())
+            .finally("cleanup", async move {
+                if let Some(sampler) = sampler {
+                    sampler.abort();
+                }
+                if let Some(endpoint_health) = endpoint_health {
+                    endpoint_health.abort();
+                }
+                tenant_metrics.abort();
+                self.stop_heartbeat(heartbeat).await;
+            })
+            .run()
+            .await;
+
+        Ok(())
+    }
+
+    async fn run_block_watcher(&self) -> Result<()> {
+        info!("Starting in Block Watcher mode");
+
+        self.add_active_networks().await?;
+
+        // Start watching blocks
+        let endpoint_health = self.start_endpoint_health();
+        self.block_watcher.start(self.client_pool.clone()).await?;
+
+        info!("Block watcher started successfully");
+        self.shutdown.wait().await;
+        ShutdownSequence::new(self.config.shutdown.grace_period)
+            .stage("block watcher", self.stop_block_watcher())
+            .finally("cleanup", async move {
+                if let Some(endpoint_health) = endpoint_health {
+                    endpoint_health.abort();
+                }
+            })
+            .run()
+            .await;
+
+        Ok(())
+    }
+
+    async fn run_api(&self) -> Result<()> {
+        info!("Starting in API mode");
+
+        let all_tenant_ids = get_all_tenant_ids(&self.db).await?;
+        if let Err(e) = self.load_balancer.reconcile(&all_tenant_ids).await {
+            warn!("Skipping assignment reconciliation: {}", e);
+        }
+        self.serve_api().await
+    }
+
+    /// Serve the API until shutdown, supervising workers, pruning tenant pins
+    /// and detecting activity anomalies alongside it. Assignments are expected
+    /// to be reconciled already.
+    async fn serve_api(&self) -> Result<()> {
+        let state = ApiState {
+            worker_pool: self.worker_pool.clone(),
+            load_balancer: self.load_balancer.clone(),
+            block_watcher: self.block_watcher.clone(),
+            client_pool: self.client_pool.clone(),
+            db: self.db.clone(),
+            match_feed: Arc::new(MatchFeed::new(
+                self.cache.redis_client(),
+                self.cache.keyspace().clone(),
+            )),
+            rpc_costs: self.cache.rpc_costs(),
+            chains: Arc::new(self.config.chains.clone().into()),
+            shutdown: self.shutdown.clone(),
+            trigger_scripts: Arc::new(
+                TriggerScriptStore::new(self.db.clone())
+                    .with_max_size(self.config.api.max_script_size),
+            ),
+            mode: self.mode.clone(),
+            network_settings: Arc::new(NetworkSettingsResolver::new(
+                self.db.clone(),
+                (&self.config).into(),
+            )),
+        };
+        let supervisor = self.start_supervisor();
+        let pin_pruning = self.load_balancer.spawn_pin_pruning(PIN_PRUNE_INTERVAL);
+        let anomaly_detector = self.start_anomaly_detector();
+        let result = api::serve(&self.config.api, state).await;
+        supervisor.abort();
+        pin_pruning.abort();
+        if let Some(anomaly_detector) = anomaly_detector {
+            anomaly_detector.abort();
+        }
+        result
+    }
+
+    /// Analyze tenant activity for spikes if enabled, in one API process at a time
+    fn start_anomaly_detector(&self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.config.anomalies.enabled {
+            return None;
+        }
+
+        let detector =
+            ActivityAnomalyDetector::new(self.db.clone(), self.config.anomalies.clone().into())
+                .with_load_balancer(self.load_balancer.clone())
+                .with_lock(DistributedLock::new(
+                    self.cache.redis_client(),
+                    self.cache.keyspace(),
+                    "lock:anomalies",
+                    format!("{}:{}", self.worker_id, Uuid::new_v4()),
+                    self.config.anomalies.interval,
+                ));
+        Some(Arc::new(detector).start())
+    }
+
+    /// Fail over dead workers and promote standbys every health check interval
+    fn start_supervisor(&self) -> tokio::task::JoinHandle<()> {
+        let load_balancer = self.load_balancer.clone();
+        let interval = self.config.worker.health_check_interval;
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = load_balancer.supervise_workers().await {
+                    warn!("Worker supervision failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Rebalance the worker pool when it needs it, unless automatic
+    /// rebalancing is disabled
+    fn start_rebalancer(&self) -> Option<tokio::task::JoinHandle<()>> {
+        if !self.config.load_balancer.auto_rebalance {
+            return None;
+        }
+
+        let scheduler = RebalanceScheduler::new(
+            self.load_balancer.clone(),
+            self.config.load_balancer.min_rebalance_interval,
+        )
+        .with_shutdown(self.shutdown.child());
+        Some(Arc::new(scheduler).start())
+    }
+
+    async fn run_all(&self) -> Result<()> {
+        info!("Starting all services");
+
+        let all_tenant_ids = self.add_active_networks().await?;
+
+        // Start block watcher
+        let block_watcher = self.block_watcher.clone();
+        let client_pool = self.client_pool.clone();
+        let block_watcher_handle = tokio::spawn(async move {
+            info!("Block watcher task spawned, calling start()");
+            match block_watcher.start(client_pool).await {
+                Ok(_) => {
+                    info!("Block watcher start() completed successfully, now running...");
+                    // Keep the block watcher running
+                    if let Err(e) = block_watcher.run().await {
+                        error!("Block watcher run failed: {:?}", e);
+                    }
+                }
+                Err(e) => error!("Block watcher start failed: {:?}", e),
+            }
+            info!("Block watcher task exiting");
+        });
+
+        // Create and start worker
+        info!("Worker ID: {}", self.worker_id);
+        self.load_balancer
+            .add_worker(self.worker_id.clone(), self.capacity_weight)
+            .await?;
+        let heartbeat = self.start_heartbeat().await;
+        let sampler = self.start_resource_sampler();
+        let tenant_metrics = self.start_tenant_metrics();
+        let endpoint_health = self.start_endpoint_health();
+
+        // Reconcile persisted assignments, assigning tenants of dead workers and new tenants
+        let assignment = self.reconcile_assignments(&all_tenant_ids).await;
+        let rebalancer = self.start_rebalancer();
+
+        // Create worker with shared block watcher
+        self.worker_pool
+            .create_worker_with_assignment(
+                assignment,
+                self.block_watcher.clone(),
+                self.client_pool.clone(),
+            )
+            .await?;
+
+        // Start API server
+        let mut api_handle = tokio::spawn({
+            let orchestrator = self.clone();
+            async move {
+                if let Err(e) = orchestrator.serve_api().await {
+                    error!("API server failed: {}", e);
+                }
+            }
+        });
+
+        info!("All services started successfully");
+
+        // Wait for shutdown or any service to fail
+        tokio::select! {
+            _ = block_watcher_handle => error!("Block watcher exited"),
+            _ = &mut api_handle => error!("API server exited"),
+            _ = self.shutdown.wait() => {}
+        }
+
+        // Stop moving tenants and taking requests first, then work, then block fetching
+        self.shutdown.trigger();
+        ShutdownSequence::new(self.config.shutdown.grace_period)
+            .stage("rebalancer", async move {
+                if let Some(rebalancer) = rebalancer {
+                    if let Err(e) = rebalancer.await {
+                        error!("Rebalance scheduler task failed: {}", e);
+                    }
+                }
+            })
+            .stage("api", async move {
+                if !api_handle.is_finished() {
+                    if let Err(e) = api_handle.await {
+                        error!("API server task failed: {}", e);
+                    }
+                }
+            })
+            .stage("workers", self.worker_pool.shutdown())
+            .stage("block watcher", self.stop_block_watcher())
+            .finally("cleanup", async move {
+                if let Some(sampler) = sampler {
+                    sampler.abort();
+                }
+                if let Some(endpoint_health) = endpoint_health {
+                    endpoint_health.abort();
+                }
+                tenant_metrics.abort();
+                self.stop_heartbeat(heartbeat).await;
+            })
+            .run()
+            .await;
+
+        Ok(())
+    }
+
+    /// Record this worker as alive now and keep refreshing its heartbeat
+    async fn start_heartbeat(&self) -> tokio::task::JoinHandle<()> {
+        if let Err(e) = self.load_balancer.heartbeat(&self.worker_id).await {
+            warn!(
+                "Failed to record heartbeat of worker {}: {}",
+                self.worker_id, e
+            );
+        }
+        self.load_balancer.spawn_heartbeat(
+            self.worker_id.clone(),
+            self.config.worker.health_check_interval,
+        )
+    }
+
+    /// Sample this process's resource usage for the load balancer and other coordinators
+    fn start_resource_sampler(&self) -> Option<tokio::task::JoinHandle<()>> {
+        self.resource_usage.as_ref().map(|sampler| {
+            sampler.start(
+                self.config.worker.resource_sample_interval,
+                self.load_balancer.clone(),
+                Some(self.assignment_store.clone()),
+            )
+        })
+    }
+
+    /// Health-check the Stellar endpoints the client pool hands out, if failover is enabled
+    fn start_endpoint_health(&self) -> Option<tokio::task::JoinHandle<()>> {
+        self.client_pool
+            .endpoint_health()
+            .map(|endpoint_health| endpoint_health.start())
+    }
+
+    /// Publish the activity metrics of this process's tenants for the load balancer and API
+    fn start_tenant_metrics(&self) -> tokio::task::JoinHandle<()> {
+        self.worker_pool.tenant_activity().start(
+            self.config.worker.tenant_metrics_interval,
+            self.load_balancer.clone(),
+            Some(self.assignment_store.clone()),
+        )
+    }
+
+    /// Stop fetching blocks and hand the watcher lease to the next replica
+    async fn stop_block_watcher(&self) {
+        if let Err(e) = self.block_watcher.stop().await {
+            warn!("Failed to stop the block watcher: {}", e);
+        }
+    }
+
+    /// Stop refreshing the heartbeat and leave the worker registry
+    async fn stop_heartbeat(&self, heartbeat: tokio::task::JoinHandle<()>) {
+        heartbeat.abort();
+        if let Err(e) = self.assignment_store.deregister(&self.worker_id).await {
+            warn!("Failed to deregister worker {}: {}", self.worker_id, e);
+        }
+    }
+
+    /// Reconcile persisted assignments and return what this worker holds.
+    ///
+    /// Falls back to assigning every tenant through the load balancer if the
+    /// persisted assignments cannot be read.
+    async fn reconcile_assignments(&self, tenant_ids: &[Uuid]) -> WorkerAssignment {
+        if let Err(e) = self.load_balancer.reconcile(tenant_ids).await {
+            warn!(
+                "Assignment reconciliation failed, assigning all tenants: {}",
+                e
+            );
+            return self.assign_tenants(tenant_ids).await;
+        }
+
+        // Sharded tenants are not persisted and are placed afresh
+        let sharded: Vec<Uuid> = tenant_ids
+            .iter()
+            .filter(|tenant_id| self.load_balancer.is_sharded(tenant_id))
+            .copied()
+            .collect();
+        let mut assignment = self.assign_tenants(&sharded).await;
+
+        match self
+            .load_balancer
+            .get_worker_assignments(&self.worker_id)
+            .await
+        {
+            Ok(tenant_ids) => tenant_ids
+                .into_iter()
+                .for_each(|tenant_id| assignment.add_tenant(tenant_id)),
+            Err(e) => error!("Failed to read assignments of {}: {}", self.worker_id, e),
+        }
+        assignment
+    }
+
+    /// Add every network with active monitors to the block watcher.
+    ///
+    /// Returns all tenant IDs, since they are needed to resolve networks.
+    async fn add_active_networks(&self) -> Result<Vec<Uuid>> {
+        // We need all tenant IDs to get all networks
+        let all_tenant_ids = get_all_tenant_ids(&self.db).await?;
+        let oz_services = Arc::new(
+            OzMonitorServices::new(
+                self.db.clone(),
+                all_tenant_ids.clone(),
+                self.client_pool.clone(),
+            )
+            .await
+            .context("Failed to initialize OZ Monitor services")?,
+        );
+
+        // Get all active networks from OZ services
+        let active_networks = oz_services.get_active_networks().await?;
+
+        // Load network configurations from database
+        let network_repo =
+            TenantAwareNetworkRepository::new(self.db.clone(), all_tenant_ids.clone());
+        let all_networks = network_repo.get_all();
+
+        // Add networks with active monitors to the block watcher, watching at
+        // the shallowest depth any tenant asks for
+        let chains = ChainSupport::from(self.config.chains.clone());
+        for slug in active_networks {
+            if let Some(network) = all_networks.get(&slug) {
+                if let Err(e) = chains.check(&network.network_type) {
+                    error!("Not watching network {}: {}", slug, e);
+                    continue;
+                }
+                let mut network = network.clone();
+                match ConfirmationDepths::shallowest(&self.db, &slug).await {
+                    Ok(Some(depth)) if depth < network.confirmation_blocks => {
+                        info!(
+                            "Watching network {} at {} confirmations instead of {} for tenant overrides",
+                            slug, depth, network.confirmation_blocks
+                        );
+                        network.confirmation_blocks = depth;
+                    }
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to load confirmation depths for {}: {}", slug, e),
+                }
+                self.block_watcher.add_network(network).await?;
+                info!("Added network {} to block watcher", slug);
+            }
+        }
+
+        Ok(all_tenant_ids)
+    }
+
+    /// Assign tenants through the load balancer, returning what was placed on this worker
+    async fn assign_tenants(&self, tenant_ids: &[Uuid]) -> WorkerAssignment {
+        let mut assignment = WorkerAssignment::new(self.worker_id.clone());
+
+        for tenant_id in tenant_ids {
+            if self.load_balancer.is_sharded(tenant_id) {
+                match self.load_balancer.assign_tenant_shards(*tenant_id).await {
+                    Ok(placements) => {
+                        for (worker_id, shard) in placements {
+                            if worker_id == self.worker_id {
+                                info!(
+                                    "Assigned shard {}/{} of tenant {} to worker {}",
+                                    shard.index + 1,
+                                    shard.count,
+                                    tenant_id,
+                                    self.worker_id
+                                );
+                                assignment.add_shard(shard);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        error!("Failed to assign shards of tenant {}: {}", tenant_id, e);
+                    }
+                }
+                continue;
+            }
+
+            match self.load_balancer.assign_tenant(*tenant_id).await {
+                Ok(assigned_worker_id) => {
+                    if assigned_worker_id == self.worker_id {
+                        assignment.add_tenant(*tenant_id);
+                        info!("Assigned tenant {} to worker {}", tenant_id, self.worker_id);
+                    }
+                }
+                Err(e) => {
+                    error!("Failed to assign tenant {} to worker: {}", tenant_id, e);
+                }
+            }
+        }
+
+        assignment
+    }
+}
+
+/// Get all tenant IDs from the database
+async fn get_all_tenant_ids(db_pool: &PgPool) -> Result<Vec<Uuid>> {
+    let tenant_ids = sqlx::query_scalar::<_, Uuid>(
+        "SELECT DISTINCT tenant_id FROM tenant_monitors WHERE is_active = true",
+    )
+    .fetch_all(db_pool)
+    .await
+    .context("Failed to fetch tenant IDs")?;
+
+    Ok(tenant_ids)
+}
+
+/// Wait for Ctrl+C or SIGTERM
+pub async fn wait_for_shutdown() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {
+            info!("Received Ctrl+C, shutting down");
+        }
+        _ = terminate => {
+            info!("Received SIGTERM, shutting down");
+        }
+    }
+}
@@ -35,6 +35,33 @@ pub enum RepositoryError {
     ConstraintViolation(String),
 }
 
+impl RepositoryError {
+    /// Stable machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            RepositoryError::ConnectionError(_) => "DATABASE_UNAVAILABLE",
+            RepositoryError::QueryError(_) => "QUERY_FAILED",
+            RepositoryError::NotFound { .. } => "NOT_FOUND",
+            RepositoryError::TenantNotFound(_) => "TENANT_NOT_FOUND",
+            RepositoryError::SerializationError(_) => "SERIALIZATION_ERROR",
+            RepositoryError::TransactionError(_) => "TRANSACTION_FAILED",
+            RepositoryError::ConstraintViolation(_) => "CONSTRAINT_VIOLATION",
+        }
+    }
+
+    /// HTTP status code used when surfacing this error through the API
+    pub fn http_status(&self) -> u16 {
+        match self {
+            RepositoryError::NotFound { .. } | RepositoryError::TenantNotFound(_) => 404,
+            RepositoryError::ConstraintViolation(_) => 409,
+            RepositoryError::ConnectionError(_) => 503,
+            RepositoryError::QueryError(_)
+            | RepositoryError::SerializationError(_)
+            | RepositoryError::TransactionError(_) => 500,
+        }
+    }
+}
+
 impl From<sqlx::Error> for RepositoryError {
     fn from(err: sqlx::Error) -> Self {
         match err {
@@ -3,5 +3,6 @@ pub mod tenant;
 
 pub use error::RepositoryError;
 pub use tenant::{
-    TenantAwareMonitorRepository, TenantAwareNetworkRepository, TenantAwareTriggerRepository,
+    InvalidMonitor, TenantAwareMonitorRepository, TenantAwareNetworkRepository,
+    TenantAwareTriggerRepository,
 };
@@ -5,6 +5,7 @@
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use sqlx::{FromRow, PgPool};
@@ -131,11 +132,37 @@ struct DbTrigger {
 pub struct TenantAwareMonitorRepository {
     db: Arc<PgPool>,
     tenant_filter: Vec<Uuid>,
+    /// Monitors skipped on the last load because their configuration is invalid
+    invalid: Arc<DashMap<(Uuid, String), InvalidMonitor>>,
+}
+
+/// Active monitor whose configuration could not be deserialized
+#[derive(Debug, Clone)]
+pub struct InvalidMonitor {
+    pub tenant_id: Uuid,
+    pub name: String,
+    pub networks: Vec<String>,
+    pub error: String,
 }
 
 impl TenantAwareMonitorRepository {
     pub fn new(db: Arc<PgPool>, tenant_filter: Vec<Uuid>) -> Self {
-        Self { db, tenant_filter }
+        Self {
+            db,
+            tenant_filter,
+            invalid: Arc::new(DashMap::new()),
+        }
+    }
+
+    /// Monitors of a tenant on a network skipped on the last load as invalid
+    pub fn invalid_monitors(&self, tenant_id: Uuid, network_slug: &str) -> Vec<InvalidMonitor> {
+        self.invalid
+            .iter()
+            .filter(|entry| {
+                entry.tenant_id == tenant_id && entry.networks.iter().any(|n| n == network_slug)
+            })
+            .map(|entry| entry.value().clone())
+            .collect()
     }
 
     /// Update the tenant filter for this repository
@@ -253,13 +280,30 @@ impl TenantAwareMonitorRepository {
         .await
         .map_err(|e| to_oz_error(RepositoryError::from(e)))?;
 
+        // Skip invalid monitors instead of failing every monitor of every tenant
+        self.invalid.clear();
         let mut result = HashMap::new();
         for db_monitor in monitors {
+            let tenant_id = db_monitor.tenant_id;
             let name = db_monitor.name.clone();
-            let monitor = self
-                .db_to_oz_monitor(db_monitor)
-                .map_err(anyhow_to_oz_error)?;
-            result.insert(name, monitor);
+            let networks = db_monitor.networks.clone();
+            match self.db_to_oz_monitor(db_monitor) {
+                Ok(monitor) => {
+                    result.insert(name, monitor);
+                }
+                Err(e) => {
+                    tracing::error!("Skipping monitor {} of tenant {}: {:#}", name, tenant_id, e);
+                    self.invalid.insert(
+                        (tenant_id, name.clone()),
+                        InvalidMonitor {
+                            tenant_id,
+                            name,
+                            networks,
+                            error: format!("{:#}", e),
+                        },
+                    );
+                }
+            }
         }
 
         Ok(result)
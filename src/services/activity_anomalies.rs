@@ -0,0 +1,601 @@
+//! Activity Anomalies
+//!
+//! Flags tenants whose match or RPC rate suddenly spikes, which usually means
+//! a misconfigured monitor or abuse. Every interval the analyzer samples each
+//! active tenant's activity into `tenant_metrics_history` and compares the
+//! current rates against the mean of the tenant's recent samples. A spike is
+//! logged, counted and recorded as an anomaly, and with auto-throttling the
+//! tenant is held to a low RPC cap by the workers' RPC limiter until the
+//! throttle expires. RPC rates come from attributed usage in
+//! `tenant_rpc_usage`, so they stay at zero unless `rpc_costs` is enabled.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration as ChronoDuration, Timelike, Utc};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::models::{AnomalyKind, TenantAnomaly, TenantMetrics};
+use crate::services::distributed_lock::DistributedLock;
+use crate::services::load_balancer::LoadBalancer;
+use crate::services::metrics;
+
+/// RPC cap applied to tenants whose activity spiked
+#[derive(Debug, Clone, Copy)]
+pub struct AnomalyThrottle {
+    pub rpc_requests_per_minute: u32,
+    pub duration: Duration,
+}
+
+/// Sampling and spike thresholds of the analyzer
+#[derive(Debug, Clone)]
+pub struct AnomalyConfig {
+    /// How often tenant activity is sampled
+    pub interval: Duration,
+    /// Recent samples averaged into a baseline
+    pub baseline_samples: usize,
+    /// Multiple of the baseline a rate must reach to spike
+    pub spike_factor: f64,
+    /// Match rate per minute below which spikes are ignored
+    pub min_matches_per_minute: f64,
+    /// RPC rate per minute below which spikes are ignored
+    pub min_rpc_calls_per_minute: f64,
+    /// Throttle applied on a spike; None only alerts
+    pub throttle: Option<AnomalyThrottle>,
+    /// Minimum time between alerts of the same kind for a tenant
+    pub alert_cooldown: Duration,
+    /// How long samples are kept
+    pub history_retention: Duration,
+}
+
+impl Default for AnomalyConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(300),
+            baseline_samples: 12,
+            spike_factor: 3.0,
+            min_matches_per_minute: 10.0,
+            min_rpc_calls_per_minute: 100.0,
+            throttle: None,
+            alert_cooldown: Duration::from_secs(3600),
+            history_retention: Duration::from_secs(7 * 24 * 3600),
+        }
+    }
+}
+
+impl AnomalyConfig {
+    /// Whether a rate spiked above its baseline
+    pub fn is_spike(&self, kind: AnomalyKind, current: f64, baseline: &Baseline) -> bool {
+        let min_rate = match kind {
+            AnomalyKind::MatchRate => self.min_matches_per_minute,
+            AnomalyKind::RpcRate => self.min_rpc_calls_per_minute,
+        };
+        baseline.samples >= self.baseline_samples
+            && current >= min_rate
+            && current > baseline.rate(kind) * self.spike_factor
+    }
+}
+
+/// Mean rates of a tenant's recent samples
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Baseline {
+    pub matches_per_minute: f64,
+    pub rpc_calls_per_minute: f64,
+    /// Samples averaged, at most the configured baseline samples
+    pub samples: usize,
+}
+
+impl Baseline {
+    fn rate(&self, kind: AnomalyKind) -> f64 {
+        match kind {
+            AnomalyKind::MatchRate => self.matches_per_minute,
+            AnomalyKind::RpcRate => self.rpc_calls_per_minute,
+        }
+    }
+}
+
+/// Activity of a tenant as read from the database
+#[derive(Debug, Clone, sqlx::FromRow)]
+struct ActivityRow {
+    tenant_id: Uuid,
+    monitors_count: i64,
+    window_start: DateTime<Utc>,
+    window_matches: i64,
+    matches_last_hour: i64,
+    last_match_at: Option<DateTime<Utc>>,
+    rpc_requests_today: f64,
+    previous_collected_at: Option<DateTime<Utc>>,
+    previous_rpc_requests_today: Option<f64>,
+}
+
+/// One sample of a tenant's activity
+#[derive(Debug, Clone)]
+struct ActivitySample {
+    tenant_id: Uuid,
+    monitors_count: i64,
+    matches_per_minute: f64,
+    matches_last_hour: i64,
+    rpc_calls_per_minute: f64,
+    rpc_requests_today: f64,
+    last_active: Option<DateTime<Utc>>,
+}
+
+impl ActivitySample {
+    fn from_row(row: ActivityRow, now: DateTime<Utc>) -> Self {
+        let previous = row
+            .previous_collected_at
+            .zip(row.previous_rpc_requests_today);
+        Self {
+            tenant_id: row.tenant_id,
+            monitors_count: row.monitors_count,
+            matches_per_minute: row.window_matches as f64 / minutes(now - row.window_start),
+            matches_last_hour: row.matches_last_hour,
+            rpc_calls_per_minute: rpc_rate(now, row.rpc_requests_today, previous),
+            rpc_requests_today: row.rpc_requests_today,
+            last_active: row.last_match_at,
+        }
+    }
+
+    fn rate(&self, kind: AnomalyKind) -> f64 {
+        match kind {
+            AnomalyKind::MatchRate => self.matches_per_minute,
+            AnomalyKind::RpcRate => self.rpc_calls_per_minute,
+        }
+    }
+
+    fn metrics(&self, collected_at: DateTime<Utc>) -> TenantMetrics {
+        TenantMetrics {
+            tenant_id: self.tenant_id,
+            monitors_count: self.monitors_count as usize,
+            avg_rpc_calls_per_minute: self.rpc_calls_per_minute,
+            avg_filter_complexity: 0.0,
+            total_matches_last_hour: self.matches_last_hour as usize,
+            notifications_sent_last_hour: 0,
+            last_active: self.last_active.unwrap_or_default(),
+            collected_at,
+        }
+    }
+}
+
+/// Minutes in a duration, at least one
+fn minutes(duration: ChronoDuration) -> f64 {
+    (duration.num_milliseconds() as f64 / 60_000.0).max(1.0)
+}
+
+/// RPC requests per minute since the previous sample, from today's running total.
+///
+/// Totals restart every UTC day, so without a previous sample from today the
+/// rate is averaged since midnight.
+fn rpc_rate(now: DateTime<Utc>, today: f64, previous: Option<(DateTime<Utc>, f64)>) -> f64 {
+    match previous {
+        Some((collected_at, total))
+            if collected_at.date_naive() == now.date_naive()
+                && collected_at < now
+                && total <= today =>
+        {
+            (today - total) / minutes(now - collected_at)
+        }
+        _ => {
+            let since_midnight = ChronoDuration::seconds(now.num_seconds_from_midnight() as i64);
+            today / minutes(since_midnight)
+        }
+    }
+}
+
+/// Activity history and anomalies in Postgres
+pub struct AnomalyStore {
+    db: Arc<PgPool>,
+}
+
+impl AnomalyStore {
+    /// Create a new anomaly store
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// Recent anomalies, newest first, optionally of one tenant
+    pub async fn list(&self, tenant_id: Option<Uuid>, limit: i64) -> Result<Vec<TenantAnomaly>> {
+        Ok(sqlx::query_as::<_, TenantAnomaly>(
+            r#"
+            SELECT id, tenant_id, kind, current_rate, baseline_rate,
+                   throttle_limit, throttled_until, detected_at
+            FROM tenant_activity_anomalies
+            WHERE $1::uuid IS NULL OR tenant_id = $1
+            ORDER BY detected_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(limit)
+        .fetch_all(&*self.db)
+        .await?)
+    }
+
+    /// End a tenant's unexpired throttles, returning how many were lifted.
+    ///
+    /// Workers apply the change once their cached caps expire.
+    pub async fn lift_throttles(&self, tenant_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE tenant_activity_anomalies
+            SET throttled_until = now()
+            WHERE tenant_id = $1 AND throttled_until > now()
+            "#,
+        )
+        .bind(tenant_id)
+        .execute(&*self.db)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Activity of every active tenant since its previous sample
+    async fn activity(&self, now: DateTime<Utc>, interval: Duration) -> Result<Vec<ActivityRow>> {
+        // A stale previous sample would average the rate over a long outage
+        let earliest_window = now - ChronoDuration::from_std(interval * 2)?;
+        Ok(sqlx::query_as::<_, ActivityRow>(
+            r#"
+            SELECT t.id AS tenant_id,
+                   (SELECT COUNT(*) FROM tenant_monitors m
+                    WHERE m.tenant_id = t.id AND m.is_active) AS monitors_count,
+                   GREATEST(prev.collected_at, $1) AS window_start,
+                   (SELECT COUNT(*) FROM monitor_matches mm
+                    WHERE mm.tenant_id = t.id
+                      AND mm.first_seen_at > GREATEST(prev.collected_at, $1)) AS window_matches,
+                   (SELECT COUNT(*) FROM monitor_matches mm
+                    WHERE mm.tenant_id = t.id AND mm.first_seen_at > $2) AS matches_last_hour,
+                   (SELECT MAX(mm.first_seen_at) FROM monitor_matches mm
+                    WHERE mm.tenant_id = t.id) AS last_match_at,
+                   (SELECT COALESCE(SUM(u.rpc_requests + u.cached_requests), 0)
+                    FROM tenant_rpc_usage u
+                    WHERE u.tenant_id = t.id AND u.usage_date = $3) AS rpc_requests_today,
+                   prev.collected_at AS previous_collected_at,
+                   prev.rpc_requests_today AS previous_rpc_requests_today
+            FROM tenants t
+            LEFT JOIN LATERAL (
+                SELECT h.collected_at, h.rpc_requests_today
+                FROM tenant_metrics_history h
+                WHERE h.tenant_id = t.id
+                ORDER BY h.collected_at DESC
+                LIMIT 1
+            ) prev ON true
+            WHERE t.is_active
+            "#,
+        )
+        .bind(earliest_window)
+        .bind(now - ChronoDuration::hours(1))
+        .bind(now.date_naive())
+        .fetch_all(&*self.db)
+        .await?)
+    }
+
+    /// Mean rates of each tenant's latest samples
+    async fn baselines(
+        &self,
+        samples: usize,
+        since: DateTime<Utc>,
+    ) -> Result<HashMap<Uuid, Baseline>> {
+        let rows = sqlx::query_as::<_, (Uuid, f64, f64, i64)>(
+            r#"
+            SELECT tenant_id, AVG(matches_per_minute), AVG(rpc_calls_per_minute), COUNT(*)
+            FROM (
+                SELECT tenant_id, matches_per_minute, rpc_calls_per_minute,
+                       ROW_NUMBER() OVER (PARTITION BY tenant_id ORDER BY collected_at DESC) AS n
+                FROM tenant_metrics_history
+                WHERE collected_at > $2
+            ) h
+            WHERE n <= $1
+            GROUP BY tenant_id
+            "#,
+        )
+        .bind(samples as i64)
+        .bind(since)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(tenant_id, matches_per_minute, rpc_calls_per_minute, samples)| {
+                    (
+                        tenant_id,
+                        Baseline {
+                            matches_per_minute,
+                            rpc_calls_per_minute,
+                            samples: samples as usize,
+                        },
+                    )
+                },
+            )
+            .collect())
+    }
+
+    /// Tenants and kinds alerted on since a time
+    async fn alerted_since(&self, since: DateTime<Utc>) -> Result<HashSet<(Uuid, AnomalyKind)>> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT DISTINCT tenant_id, kind
+            FROM tenant_activity_anomalies
+            WHERE detected_at > $1
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(tenant_id, kind)| Some((tenant_id, kind.parse().ok()?)))
+            .collect())
+    }
+
+    async fn record_samples(
+        &self,
+        samples: &[ActivitySample],
+        collected_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        for sample in samples {
+            sqlx::query(
+                r#"
+                INSERT INTO tenant_metrics_history
+                    (tenant_id, collected_at, monitors_count, matches_per_minute,
+                     total_matches_last_hour, rpc_calls_per_minute, rpc_requests_today,
+                     last_active)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                ON CONFLICT (tenant_id, collected_at) DO NOTHING
+                "#,
+            )
+            .bind(sample.tenant_id)
+            .bind(collected_at)
+            .bind(sample.monitors_count as i32)
+            .bind(sample.matches_per_minute)
+            .bind(sample.matches_last_hour as i32)
+            .bind(sample.rpc_calls_per_minute)
+            .bind(sample.rpc_requests_today)
+            .bind(sample.last_active)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn record_anomaly(
+        &self,
+        tenant_id: Uuid,
+        kind: AnomalyKind,
+        current_rate: f64,
+        baseline_rate: f64,
+        throttle: Option<(i32, DateTime<Utc>)>,
+    ) -> Result<TenantAnomaly> {
+        Ok(sqlx::query_as::<_, TenantAnomaly>(
+            r#"
+            INSERT INTO tenant_activity_anomalies
+                (tenant_id, kind, current_rate, baseline_rate, throttle_limit, throttled_until)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id, tenant_id, kind, current_rate, baseline_rate,
+                      throttle_limit, throttled_until, detected_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(kind.as_str())
+        .bind(current_rate)
+        .bind(baseline_rate)
+        .bind(throttle.map(|(limit, _)| limit))
+        .bind(throttle.map(|(_, until)| until))
+        .fetch_one(&*self.db)
+        .await?)
+    }
+
+    async fn prune_history(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM tenant_metrics_history WHERE collected_at < $1")
+            .bind(before)
+            .execute(&*self.db)
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+/// Samples tenant activity and flags sudden spikes
+pub struct ActivityAnomalyDetector {
+    store: AnomalyStore,
+    config: AnomalyConfig,
+    load_balancer: Option<Arc<LoadBalancer>>,
+    lock: Option<DistributedLock>,
+}
+
+impl ActivityAnomalyDetector {
+    /// Create a detector reading and writing the given database
+    pub fn new(db: Arc<PgPool>, config: AnomalyConfig) -> Self {
+        Self {
+            store: AnomalyStore::new(db),
+            config,
+            load_balancer: None,
+            lock: None,
+        }
+    }
+
+    /// Feed every sample to the load balancer's tenant metrics
+    pub fn with_load_balancer(mut self, load_balancer: Arc<LoadBalancer>) -> Self {
+        self.load_balancer = Some(load_balancer);
+        self
+    }
+
+    /// Analyze only while holding this lock, so one process samples per interval
+    pub fn with_lock(mut self, lock: DistributedLock) -> Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    /// Sample every active tenant and record the spikes found
+    pub async fn analyze(&self) -> Result<Vec<TenantAnomaly>> {
+        let now = Utc::now();
+        let retention_start = now - ChronoDuration::from_std(self.config.history_retention)?;
+        let cooldown_start = now - ChronoDuration::from_std(self.config.alert_cooldown)?;
+
+        let samples: Vec<ActivitySample> = self
+            .store
+            .activity(now, self.config.interval)
+            .await?
+            .into_iter()
+            .map(|row| ActivitySample::from_row(row, now))
+            .collect();
+        let baselines = self
+            .store
+            .baselines(self.config.baseline_samples, retention_start)
+            .await?;
+        let alerted = self.store.alerted_since(cooldown_start).await?;
+        self.store.record_samples(&samples, now).await?;
+
+        let mut anomalies = Vec::new();
+        for sample in &samples {
+            if let Some(load_balancer) = &self.load_balancer {
+                load_balancer
+                    .update_tenant_metrics(sample.metrics(now))
+                    .await?;
+            }
+
+            let Some(baseline) = baselines.get(&sample.tenant_id) else {
+                continue;
+            };
+            for kind in [AnomalyKind::MatchRate, AnomalyKind::RpcRate] {
+                let current = sample.rate(kind);
+                if alerted.contains(&(sample.tenant_id, kind))
+                    || !self.config.is_spike(kind, current, baseline)
+                {
+                    continue;
+                }
+                anomalies.push(
+                    self.alert(sample.tenant_id, kind, current, baseline.rate(kind), now)
+                        .await?,
+                );
+            }
+        }
+
+        let pruned = self.store.prune_history(retention_start).await?;
+        debug!(
+            "Analyzed activity of {} tenants, {} anomalies, pruned {} samples",
+            samples.len(),
+            anomalies.len(),
+            pruned
+        );
+        Ok(anomalies)
+    }
+
+    /// Record a spike and throttle the tenant if configured
+    async fn alert(
+        &self,
+        tenant_id: Uuid,
+        kind: AnomalyKind,
+        current: f64,
+        baseline: f64,
+        now: DateTime<Utc>,
+    ) -> Result<TenantAnomaly> {
+        let throttle = match self.config.throttle {
+            Some(throttle) => Some((
+                throttle.rpc_requests_per_minute as i32,
+                now + ChronoDuration::from_std(throttle.duration)?,
+            )),
+            None => None,
+        };
+
+        warn!(
+            "Tenant {} {} spiked to {:.1}/min against a baseline of {:.1}/min{}",
+            tenant_id,
+            kind.as_str(),
+            current,
+            baseline,
+            match throttle {
+                Some((limit, _)) => format!(", throttling to {} RPC requests per minute", limit),
+                None => String::new(),
+            }
+        );
+        metrics::TENANT_ANOMALIES
+            .with_label_values(&[&tenant_id.to_string(), kind.as_str()])
+            .inc();
+
+        self.store
+            .record_anomaly(tenant_id, kind, current, baseline, throttle)
+            .await
+    }
+
+    /// Analyze every interval until aborted.
+    ///
+    /// The lock is left to expire rather than released, so other processes
+    /// skip the rest of the interval instead of sampling again.
+    pub fn start(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let detector = self.clone();
+        tokio::spawn(async move {
+            info!(
+                "Analyzing tenant activity every {:?}",
+                detector.config.interval
+            );
+            let mut ticker = tokio::time::interval(detector.config.interval);
+            loop {
+                ticker.tick().await;
+                let _guard = match &detector.lock {
+                    Some(lock) => match lock.try_acquire().await {
+                        Ok(Some(guard)) => Some(guard),
+                        Ok(None) => {
+                            debug!("Tenant activity analyzed by another process");
+                            continue;
+                        }
+                        Err(e) => {
+                            warn!("Failed to take the anomaly analysis lock: {}", e);
+                            continue;
+                        }
+                    },
+                    None => None,
+                };
+                if let Err(e) = detector.analyze().await {
+                    warn!("Tenant activity analysis failed: {}", e);
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn baseline(matches_per_minute: f64, samples: usize) -> Baseline {
+        Baseline {
+            matches_per_minute,
+            rpc_calls_per_minute: 0.0,
+            samples,
+        }
+    }
+
+    #[test]
+    fn test_spike_needs_factor_minimum_and_full_baseline() {
+        let config = AnomalyConfig::default();
+        let kind = AnomalyKind::MatchRate;
+
+        assert!(config.is_spike(kind, 31.0, &baseline(10.0, 12)));
+        assert!(!config.is_spike(kind, 29.0, &baseline(10.0, 12)));
+
+        // Below the minimum rate, even from silence
+        assert!(!config.is_spike(kind, 5.0, &baseline(0.0, 12)));
+        assert!(config.is_spike(kind, 10.0, &baseline(0.0, 12)));
+
+        // Tenants without enough history are not judged yet
+        assert!(!config.is_spike(kind, 100.0, &baseline(1.0, 3)));
+    }
+
+    #[test]
+    fn test_rpc_rate_uses_previous_sample_of_the_day() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let previous = now - ChronoDuration::minutes(5);
+        assert_eq!(rpc_rate(now, 1500.0, Some((previous, 1000.0))), 100.0);
+
+        // Yesterday's total or none at all: average since midnight
+        let yesterday = now - ChronoDuration::days(1);
+        assert_eq!(rpc_rate(now, 720.0, Some((yesterday, 5000.0))), 1.0);
+        assert_eq!(rpc_rate(now, 720.0, None), 1.0);
+    }
+}
@@ -0,0 +1,166 @@
+//! Address Index
+//!
+//! In-memory reverse index from a network and contract address to the tenant
+//! monitors watching it, so a filter match is attributed to its monitor with
+//! a lookup instead of a scan over every monitor of the tenant. A tenant's
+//! entries are replaced whenever its monitors are loaded, which happens after
+//! every configuration change invalidates them. Monitors watching all
+//! addresses are not indexed. The platform-wide counterpart for admin
+//! searches is the `tenant_monitor_addresses` table.
+
+use dashmap::DashMap;
+use openzeppelin_monitor::models::Monitor;
+use std::collections::{BTreeSet, HashMap};
+use uuid::Uuid;
+
+/// Network slug and lowercased contract address
+type IndexKey = (String, String);
+
+/// Reverse index of the monitors loaded by a worker
+#[derive(Debug, Default)]
+pub struct AddressIndex {
+    /// Tenant and monitor names by network and address
+    monitors: DashMap<IndexKey, BTreeSet<(Uuid, String)>>,
+    /// Keys holding each tenant's monitors, for replacing them
+    tenant_keys: DashMap<Uuid, Vec<IndexKey>>,
+}
+
+impl AddressIndex {
+    /// Create an empty index
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace a tenant's entries with the addresses of its current monitors
+    pub fn index_tenant(&self, tenant_id: Uuid, monitors: &HashMap<String, Monitor>) {
+        self.replace_tenant(
+            tenant_id,
+            monitors.iter().flat_map(|(name, monitor)| {
+                monitor.networks.iter().flat_map(move |network| {
+                    monitor.addresses.iter().map(move |address| {
+                        (network.clone(), address.address.clone(), name.clone())
+                    })
+                })
+            }),
+        );
+    }
+
+    /// Replace a tenant's entries with `(network, address, monitor name)` triples
+    pub fn replace_tenant(
+        &self,
+        tenant_id: Uuid,
+        entries: impl IntoIterator<Item = (String, String, String)>,
+    ) {
+        self.remove_tenant(tenant_id);
+
+        let mut keys = Vec::new();
+        for (network, address, monitor_name) in entries {
+            let key = (network, address.to_lowercase());
+            self.monitors
+                .entry(key.clone())
+                .or_default()
+                .insert((tenant_id, monitor_name));
+            keys.push(key);
+        }
+        if !keys.is_empty() {
+            self.tenant_keys.insert(tenant_id, keys);
+        }
+    }
+
+    /// Drop all entries of a tenant
+    pub fn remove_tenant(&self, tenant_id: Uuid) {
+        let Some((_, keys)) = self.tenant_keys.remove(&tenant_id) else {
+            return;
+        };
+        for key in keys {
+            self.monitors.remove_if_mut(&key, |_, monitors| {
+                monitors.retain(|(id, _)| *id != tenant_id);
+                monitors.is_empty()
+            });
+        }
+    }
+
+    /// Tenants and monitors watching an address on a network, ordered by tenant and name
+    pub fn monitors(&self, network_slug: &str, address: &str) -> Vec<(Uuid, String)> {
+        self.monitors
+            .get(&(network_slug.to_string(), address.to_lowercase()))
+            .map(|monitors| monitors.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Names of a tenant's monitors watching an address on a network
+    pub fn tenant_monitors(
+        &self,
+        tenant_id: Uuid,
+        network_slug: &str,
+        address: &str,
+    ) -> Vec<String> {
+        self.monitors
+            .get(&(network_slug.to_string(), address.to_lowercase()))
+            .map(|monitors| {
+                monitors
+                    .iter()
+                    .filter(|(id, _)| *id == tenant_id)
+                    .map(|(_, name)| name.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(network: &str, address: &str, monitor: &str) -> (String, String, String) {
+        (
+            network.to_string(),
+            address.to_string(),
+            monitor.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_lookup_is_case_insensitive_and_per_network() {
+        let index = AddressIndex::new();
+        let tenant_a = Uuid::new_v4();
+        let tenant_b = Uuid::new_v4();
+        index.replace_tenant(tenant_a, vec![entry("ethereum", "0xABCD", "usdc")]);
+        index.replace_tenant(
+            tenant_b,
+            vec![
+                entry("ethereum", "0xabcd", "transfers"),
+                entry("sepolia", "0xabcd", "testnet"),
+            ],
+        );
+
+        assert_eq!(index.monitors("ethereum", "0xAbCd").len(), 2);
+        assert_eq!(
+            index.tenant_monitors(tenant_b, "ethereum", "0xABCD"),
+            vec!["transfers".to_string()]
+        );
+        assert_eq!(
+            index.tenant_monitors(tenant_b, "sepolia", "0xabcd"),
+            vec!["testnet".to_string()]
+        );
+        assert!(index.monitors("polygon", "0xabcd").is_empty());
+    }
+
+    #[test]
+    fn test_reindexing_a_tenant_replaces_its_entries() {
+        let index = AddressIndex::new();
+        let tenant_id = Uuid::new_v4();
+        index.replace_tenant(tenant_id, vec![entry("ethereum", "0x01", "old")]);
+        index.replace_tenant(tenant_id, vec![entry("ethereum", "0x02", "new")]);
+
+        assert!(index.monitors("ethereum", "0x01").is_empty());
+        assert_eq!(
+            index.tenant_monitors(tenant_id, "ethereum", "0x02"),
+            vec!["new".to_string()]
+        );
+
+        index.remove_tenant(tenant_id);
+        assert!(index.monitors("ethereum", "0x02").is_empty());
+        assert!(index.monitors.is_empty());
+    }
+}
@@ -0,0 +1,554 @@
+//! Assignment Store
+//!
+//! Persists tenant assignments in Postgres and worker heartbeats in Redis so a
+//! restarted coordinator can tell which persisted assignments still point at
+//! live workers, and a restarted worker takes back the tenants it had.
+//! Assignments are written with their version, and a write only replaces a
+//! stored assignment with an older version, so a writer working from a stale
+//! assignment fails instead of overwriting a newer placement. Every saved
+//! assignment is also appended to the tenant's assignment history and copied
+//! to a Redis hash by tenant, which every process reads as the shared view of
+//! the assignments; copies are version-checked in a Lua script, so writes
+//! arriving out of order never put an older placement back. Workers refresh
+//! their heartbeat periodically; a worker whose
+//! heartbeat is older than the liveness window is considered dead. Standby
+//! workers heartbeat like any other worker but are also listed in a standby
+//! set until a coordinator claims them. Workers also publish their capacity
+//! weight and latest CPU and memory usage for coordinators placing tenants,
+//! and the activity metrics of the tenants they process.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use redis::{AsyncCommands, Client as RedisClient, Script};
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::{AssignmentReason, TenantAssignment, TenantMetrics, WorkerUsage};
+use crate::services::redis_keyspace::RedisKeyspace;
+
+/// Copy an assignment to the shared hashes unless a copy at the same or a
+/// newer version is there; 1 if copied
+const SHARE_SCRIPT: &str = r#"
+local current = tonumber(redis.call("HGET", KEYS[2], ARGV[1]) or "0")
+if current >= tonumber(ARGV[2]) then
+    return 0
+end
+redis.call("HSET", KEYS[1], ARGV[1], ARGV[3])
+redis.call("HSET", KEYS[2], ARGV[1], ARGV[2])
+return 1
+"#;
+
+/// Save of an assignment the stored one is at the same or a newer version
+/// than, i.e. another writer placed the tenant since it was read
+#[derive(Debug, Error)]
+#[error("assignment version {version} of tenant {tenant_id} is stale")]
+pub struct StaleAssignment {
+    pub tenant_id: Uuid,
+    pub version: u32,
+}
+
+/// Row of the `tenant_assignments` or `tenant_assignment_history` table
+#[derive(sqlx::FromRow)]
+struct AssignmentRow {
+    tenant_id: Uuid,
+    worker_id: String,
+    reason: String,
+    assigned_at: DateTime<Utc>,
+    version: i32,
+}
+
+impl AssignmentRow {
+    fn into_assignment(self) -> Result<TenantAssignment> {
+        Ok(TenantAssignment {
+            tenant_id: self.tenant_id,
+            worker_id: self.worker_id,
+            assigned_at: self.assigned_at,
+            version: u32::try_from(self.version)?,
+            reason: serde_json::from_value(serde_json::Value::String(self.reason))?,
+        })
+    }
+}
+
+/// Store of tenant assignments (Postgres) and worker heartbeats (Redis)
+pub struct AssignmentStore {
+    db: Arc<PgPool>,
+    redis: Arc<RedisClient>,
+    keyspace: RedisKeyspace,
+    liveness: Duration,
+}
+
+impl AssignmentStore {
+    /// Create a store treating workers silent for longer than `liveness` as dead
+    pub fn new(
+        db: Arc<PgPool>,
+        redis: Arc<RedisClient>,
+        keyspace: RedisKeyspace,
+        liveness: Duration,
+    ) -> Self {
+        Self {
+            db,
+            redis,
+            keyspace,
+            liveness,
+        }
+    }
+
+    /// Record that a worker is alive
+    pub async fn heartbeat(&self, worker_id: &str) -> Result<()> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = conn
+            .zadd(self.workers_key(), worker_id, Utc::now().timestamp_millis())
+            .await?;
+        Ok(())
+    }
+
+    /// Remove a worker from the registry on clean shutdown
+    pub async fn deregister(&self, worker_id: &str) -> Result<()> {
+        let mut pipe = redis::pipe();
+        pipe.zrem(self.workers_key(), worker_id)
+            .srem(self.standby_key(), worker_id)
+            .hdel(self.usage_key(), worker_id)
+            .hdel(self.weights_key(), worker_id);
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = pipe.query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    /// Publish a worker's capacity weight
+    pub async fn report_capacity_weight(&self, worker_id: &str, weight: f64) -> Result<()> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = conn.hset(self.weights_key(), worker_id, weight).await?;
+        Ok(())
+    }
+
+    /// Published capacity weight by worker, skipping weights that are not positive
+    pub async fn load_capacity_weights(&self) -> Result<HashMap<String, f64>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let entries: HashMap<String, f64> = conn.hgetall(self.weights_key()).await?;
+
+        Ok(entries
+            .into_iter()
+            .filter(|(worker_id, weight)| {
+                let valid = weight.is_finite() && *weight > 0.0;
+                if !valid {
+                    warn!(
+                        "Ignoring capacity weight {} of worker {}",
+                        weight, worker_id
+                    );
+                }
+                valid
+            })
+            .collect())
+    }
+
+    /// Publish a worker's latest resource usage
+    pub async fn report_usage(&self, worker_id: &str, usage: &WorkerUsage) -> Result<()> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = conn
+            .hset(self.usage_key(), worker_id, serde_json::to_string(usage)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Latest published resource usage by worker, skipping unreadable entries
+    pub async fn load_usage(&self) -> Result<HashMap<String, WorkerUsage>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let entries: HashMap<String, String> = conn.hgetall(self.usage_key()).await?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(
+                |(worker_id, payload)| match serde_json::from_str(&payload) {
+                    Ok(usage) => Some((worker_id, usage)),
+                    Err(e) => {
+                        warn!(
+                            "Ignoring unreadable resource usage of worker {}: {}",
+                            worker_id, e
+                        );
+                        None
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// Publish the latest activity metrics of tenants
+    pub async fn report_tenant_metrics(&self, metrics: &[TenantMetrics]) -> Result<()> {
+        let entries = metrics
+            .iter()
+            .map(|m| Ok((m.tenant_id.to_string(), serde_json::to_string(m)?)))
+            .collect::<Result<Vec<(String, String)>>>()?;
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = conn
+            .hset_multiple(self.tenant_metrics_key(), &entries)
+            .await?;
+        Ok(())
+    }
+
+    /// Latest published activity metrics of a tenant
+    pub async fn tenant_metrics(&self, tenant_id: Uuid) -> Result<Option<TenantMetrics>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let payload: Option<String> = conn
+            .hget(self.tenant_metrics_key(), tenant_id.to_string())
+            .await?;
+        Ok(payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()?)
+    }
+
+    /// Latest published activity metrics by tenant, skipping unreadable entries
+    pub async fn load_tenant_metrics(&self) -> Result<HashMap<Uuid, TenantMetrics>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let entries: HashMap<String, String> = conn.hgetall(self.tenant_metrics_key()).await?;
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|(tenant_id, payload)| {
+                match serde_json::from_str::<TenantMetrics>(&payload) {
+                    Ok(metrics) => Some((metrics.tenant_id, metrics)),
+                    Err(e) => {
+                        warn!("Ignoring unreadable metrics of tenant {}: {}", tenant_id, e);
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+
+    /// List a worker as standby, available for promotion
+    pub async fn register_standby(&self, worker_id: &str) -> Result<()> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = conn.sadd(self.standby_key(), worker_id).await?;
+        Ok(())
+    }
+
+    /// Take a worker off the standby set, returning false if another coordinator claimed it first
+    pub async fn claim_standby(&self, worker_id: &str) -> Result<bool> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let removed: i64 = conn.srem(self.standby_key(), worker_id).await?;
+        Ok(removed > 0)
+    }
+
+    /// Workers other than standbys with a heartbeat inside the liveness window
+    pub async fn live_workers(&self) -> Result<HashSet<String>> {
+        let (workers, standby) = self.live_and_standby().await?;
+        Ok(workers.difference(&standby).cloned().collect())
+    }
+
+    /// Standby workers with a heartbeat inside the liveness window, sorted by id
+    pub async fn live_standby_workers(&self) -> Result<Vec<String>> {
+        let (workers, standby) = self.live_and_standby().await?;
+        let mut standby: Vec<String> = standby.intersection(&workers).cloned().collect();
+        standby.sort();
+        Ok(standby)
+    }
+
+    async fn live_and_standby(&self) -> Result<(HashSet<String>, HashSet<String>)> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let cutoff = Utc::now().timestamp_millis() - self.liveness.as_millis() as i64;
+        let workers: Vec<String> = conn
+            .zrangebyscore(self.workers_key(), cutoff, "+inf")
+            .await?;
+        let standby: Vec<String> = conn.smembers(self.standby_key()).await?;
+        Ok((workers.into_iter().collect(), standby.into_iter().collect()))
+    }
+
+    /// Persist a tenant's assignment and append it to the tenant's history.
+    ///
+    /// Fails with [`StaleAssignment`] if the stored assignment is at the same
+    /// or a newer version, i.e. another writer placed the tenant since this
+    /// one read it.
+    pub async fn save(&self, assignment: &TenantAssignment) -> Result<()> {
+        let result = sqlx::query(
+            r#"
+            WITH saved AS (
+                INSERT INTO tenant_assignments
+                    (tenant_id, worker_id, reason, assigned_at, version)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (tenant_id) DO UPDATE
+                SET worker_id = EXCLUDED.worker_id,
+                    reason = EXCLUDED.reason,
+                    assigned_at = EXCLUDED.assigned_at,
+                    version = EXCLUDED.version,
+                    updated_at = now()
+                WHERE tenant_assignments.version < EXCLUDED.version
+                RETURNING tenant_id, worker_id, reason, assigned_at, version
+            )
+            INSERT INTO tenant_assignment_history
+                (tenant_id, worker_id, reason, assigned_at, version)
+            SELECT tenant_id, worker_id, reason, assigned_at, version FROM saved
+            "#,
+        )
+        .bind(assignment.tenant_id)
+        .bind(&assignment.worker_id)
+        .bind(reason_name(&assignment.reason)?)
+        .bind(assignment.assigned_at)
+        .bind(i32::try_from(assignment.version)?)
+        .execute(&*self.db)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(StaleAssignment {
+                tenant_id: assignment.tenant_id,
+                version: assignment.version,
+            }
+            .into());
+        }
+        self.share(assignment).await?;
+        Ok(())
+    }
+
+    /// Copy an assignment to the shared view unless it already holds the
+    /// same or a newer version
+    async fn share(&self, assignment: &TenantAssignment) -> Result<()> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let shared: i64 = Script::new(SHARE_SCRIPT)
+            .key(self.shared_key())
+            .key(self.shared_versions_key())
+            .arg(assignment.tenant_id.to_string())
+            .arg(assignment.version)
+            .arg(serde_json::to_string(assignment)?)
+            .invoke_async(&mut conn)
+            .await?;
+        if shared == 0 {
+            debug!(
+                "Shared assignment of tenant {} is already at version {} or newer",
+                assignment.tenant_id, assignment.version
+            );
+        }
+        Ok(())
+    }
+
+    /// Assignments every process sees, skipping unreadable entries.
+    ///
+    /// The view is rebuilt from Postgres when Redis holds none, e.g. after
+    /// Redis lost its data.
+    pub async fn load_shared(&self) -> Result<HashMap<Uuid, TenantAssignment>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let entries: HashMap<String, String> = conn.hgetall(self.shared_key()).await?;
+        if entries.is_empty() {
+            let persisted = self.load_all().await?;
+            for assignment in persisted.values() {
+                self.share(assignment).await?;
+            }
+            return Ok(persisted);
+        }
+
+        Ok(entries
+            .into_iter()
+            .filter_map(|(tenant_id, payload)| {
+                match serde_json::from_str::<TenantAssignment>(&payload) {
+                    Ok(assignment) => Some((assignment.tenant_id, assignment)),
+                    Err(e) => {
+                        warn!(
+                            "Ignoring unreadable shared assignment of tenant {}: {}",
+                            tenant_id, e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect())
+    }
+
+    /// Replace all persisted assignments
+    pub async fn replace_all<'a>(
+        &self,
+        assignments: impl IntoIterator<Item = &'a TenantAssignment>,
+    ) -> Result<()> {
+        let assignments: Vec<&TenantAssignment> = assignments.into_iter().collect();
+        let tenant_ids: Vec<Uuid> = assignments.iter().map(|a| a.tenant_id).collect();
+
+        let mut tx = self.db.begin().await?;
+        sqlx::query("DELETE FROM tenant_assignments WHERE NOT (tenant_id = ANY($1))")
+            .bind(&tenant_ids)
+            .execute(&mut *tx)
+            .await?;
+        for assignment in &assignments {
+            sqlx::query(
+                r#"
+                INSERT INTO tenant_assignments
+                    (tenant_id, worker_id, reason, assigned_at, version)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (tenant_id) DO UPDATE
+                SET worker_id = EXCLUDED.worker_id,
+                    reason = EXCLUDED.reason,
+                    assigned_at = EXCLUDED.assigned_at,
+                    version = EXCLUDED.version,
+                    updated_at = now()
+                "#,
+            )
+            .bind(assignment.tenant_id)
+            .bind(&assignment.worker_id)
+            .bind(reason_name(&assignment.reason)?)
+            .bind(assignment.assigned_at)
+            .bind(i32::try_from(assignment.version)?)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .del(self.shared_key())
+            .del(self.shared_versions_key());
+        for assignment in assignments {
+            let tenant_id = assignment.tenant_id.to_string();
+            pipe.hset(
+                self.shared_key(),
+                &tenant_id,
+                serde_json::to_string(assignment)?,
+            )
+            .hset(self.shared_versions_key(), &tenant_id, assignment.version);
+        }
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = pipe.query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    /// Drop the persisted assignments of the given tenants
+    pub async fn remove(&self, tenant_ids: &[Uuid]) -> Result<()> {
+        if tenant_ids.is_empty() {
+            return Ok(());
+        }
+        sqlx::query("DELETE FROM tenant_assignments WHERE tenant_id = ANY($1)")
+            .bind(tenant_ids)
+            .execute(&*self.db)
+            .await?;
+
+        let tenant_ids: Vec<String> = tenant_ids.iter().map(Uuid::to_string).collect();
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .hdel(self.shared_key(), &tenant_ids)
+            .hdel(self.shared_versions_key(), &tenant_ids);
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let _: () = pipe.query_async(&mut conn).await?;
+        Ok(())
+    }
+
+    /// Latest `limit` saved assignments of a tenant, oldest first, skipping
+    /// unreadable rows
+    pub async fn history(&self, tenant_id: Uuid, limit: usize) -> Result<Vec<TenantAssignment>> {
+        let rows = sqlx::query_as::<_, AssignmentRow>(
+            r#"
+            SELECT tenant_id, worker_id, reason, assigned_at, version
+            FROM (
+                SELECT id, tenant_id, worker_id, reason, assigned_at, version
+                FROM tenant_assignment_history
+                WHERE tenant_id = $1
+                ORDER BY id DESC
+                LIMIT $2
+            ) latest
+            ORDER BY id
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(i64::try_from(limit)?)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| match row.into_assignment() {
+                Ok(assignment) => Some(assignment),
+                Err(e) => {
+                    warn!(
+                        "Ignoring unreadable assignment history of tenant {}: {}",
+                        tenant_id, e
+                    );
+                    None
+                }
+            })
+            .collect())
+    }
+
+    /// Persisted assignment of a tenant
+    pub async fn load(&self, tenant_id: Uuid) -> Result<Option<TenantAssignment>> {
+        let row = sqlx::query_as::<_, AssignmentRow>(
+            r#"
+            SELECT tenant_id, worker_id, reason, assigned_at, version
+            FROM tenant_assignments
+            WHERE tenant_id = $1
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        row.map(AssignmentRow::into_assignment).transpose()
+    }
+
+    /// Load all persisted assignments, skipping unreadable rows
+    pub async fn load_all(&self) -> Result<HashMap<Uuid, TenantAssignment>> {
+        let rows = sqlx::query_as::<_, AssignmentRow>(
+            r#"
+            SELECT tenant_id, worker_id, reason, assigned_at, version
+            FROM tenant_assignments
+            "#,
+        )
+        .fetch_all(&*self.db)
+        .await?;
+
+        let mut assignments = HashMap::with_capacity(rows.len());
+        for row in rows {
+            let tenant_id = row.tenant_id;
+            match row.into_assignment() {
+                Ok(assignment) => {
+                    assignments.insert(tenant_id, assignment);
+                }
+                Err(e) => warn!(
+                    "Ignoring unreadable persisted assignment of tenant {}: {}",
+                    tenant_id, e
+                ),
+            }
+        }
+
+        debug!("Loaded {} persisted assignments", assignments.len());
+        Ok(assignments)
+    }
+
+    fn workers_key(&self) -> String {
+        self.keyspace.key("assignments:workers")
+    }
+
+    fn standby_key(&self) -> String {
+        self.keyspace.key("assignments:standby")
+    }
+
+    fn usage_key(&self) -> String {
+        self.keyspace.key("assignments:usage")
+    }
+
+    fn weights_key(&self) -> String {
+        self.keyspace.key("assignments:weights")
+    }
+
+    fn tenant_metrics_key(&self) -> String {
+        self.keyspace.key("assignments:tenant_metrics")
+    }
+
+    fn shared_key(&self) -> String {
+        self.keyspace.key("assignments:tenants")
+    }
+
+    fn shared_versions_key(&self) -> String {
+        self.keyspace.key("assignments:versions")
+    }
+}
+
+/// Stored name of an assignment reason, as serialized
+fn reason_name(reason: &AssignmentReason) -> Result<String> {
+    match serde_json::to_value(reason)? {
+        serde_json::Value::String(name) => Ok(name),
+        other => anyhow::bail!("Unexpected assignment reason {}", other),
+    }
+}
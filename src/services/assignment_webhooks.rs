@@ -0,0 +1,140 @@
+//! Assignment Webhook Service
+//!
+//! Delivers assignment lifecycle events (assignment, reassignment, worker
+//! failure, rebalance completion) to external systems such as provisioning
+//! or billing so they stay in sync with tenant placement.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::{AssignmentEvent, AssignmentEventKind};
+use crate::services::retry::RetryPolicy;
+
+/// Registered outbound webhook
+#[derive(Debug, Clone)]
+pub struct AssignmentWebhook {
+    /// Destination URL
+    pub url: String,
+    /// Subscribed events (empty means all events)
+    pub events: HashSet<AssignmentEventKind>,
+    /// Optional secret sent as a bearer token
+    pub secret: Option<String>,
+    /// Request timeout
+    pub timeout: Duration,
+}
+
+impl AssignmentWebhook {
+    /// Check if this webhook subscribes to the given event kind
+    fn accepts(&self, kind: AssignmentEventKind) -> bool {
+        self.events.is_empty() || self.events.contains(&kind)
+    }
+}
+
+/// Payload posted to webhooks
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    /// Unique delivery identifier for idempotent consumers
+    id: Uuid,
+    /// Event timestamp
+    occurred_at: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a AssignmentEvent,
+}
+
+/// Fires assignment lifecycle events at registered webhooks
+pub struct AssignmentWebhookNotifier {
+    client: reqwest::Client,
+    webhooks: Vec<AssignmentWebhook>,
+    retry: RetryPolicy,
+}
+
+impl AssignmentWebhookNotifier {
+    /// Create a notifier for the given webhooks
+    pub fn new(webhooks: Vec<AssignmentWebhook>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            webhooks,
+            retry: RetryPolicy::default().with_classifier(is_retryable),
+        }
+    }
+
+    /// Retry failed deliveries with the given policy.
+    ///
+    /// Client errors other than 429 are never retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry.with_classifier(is_retryable);
+        self
+    }
+
+    /// Check if any webhooks are registered
+    pub fn is_empty(&self) -> bool {
+        self.webhooks.is_empty()
+    }
+
+    /// Deliver an event to all subscribed webhooks.
+    ///
+    /// Delivery happens in the background so assignment operations never
+    /// wait on external systems.
+    pub fn notify(&self, event: AssignmentEvent) {
+        let kind = event.kind();
+        let occurred_at = Utc::now();
+
+        for webhook in self.webhooks.iter().filter(|w| w.accepts(kind)) {
+            let client = self.client.clone();
+            let webhook = webhook.clone();
+            let event = event.clone();
+            let retry = self.retry.clone();
+
+            tokio::spawn(async move {
+                let payload = WebhookPayload {
+                    id: Uuid::new_v4(),
+                    occurred_at,
+                    event: &event,
+                };
+
+                let result = retry
+                    .retry(|| {
+                        let mut request = client
+                            .post(&webhook.url)
+                            .timeout(webhook.timeout)
+                            .json(&payload);
+                        if let Some(secret) = &webhook.secret {
+                            request = request.bearer_auth(secret);
+                        }
+
+                        async move { request.send().await?.error_for_status().map(|_| ()) }
+                    })
+                    .await;
+
+                match result {
+                    Ok(()) => {
+                        debug!("Delivered {:?} event to {}", kind, webhook.url);
+                    }
+                    Err(e) => {
+                        warn!(
+                            "Failed to deliver {:?} event to {}: {}",
+                            kind, webhook.url, e
+                        );
+                    }
+                }
+            });
+        }
+    }
+}
+
+/// Retry transport failures, server errors and rate limiting
+pub(crate) fn is_retryable(error: &anyhow::Error) -> bool {
+    match error
+        .downcast_ref::<reqwest::Error>()
+        .and_then(|e| e.status())
+    {
+        Some(status) => {
+            status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        }
+        None => true,
+    }
+}
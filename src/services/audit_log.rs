@@ -0,0 +1,155 @@
+//! Audit Log
+//!
+//! Records mutating management API calls in `orchestrator_audit_log`. Writes
+//! are best effort and happen in the background, so an unavailable database
+//! never fails or delays the call being audited; failed writes are logged and
+//! counted in `oz_monitor_audit_write_failures_total`.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::{AuditEntry, AuditOutcome};
+use crate::services::metrics::AUDIT_WRITE_FAILURES;
+
+/// Columns selected into [`AuditEntry`]
+const COLUMNS: &str = "id, occurred_at, actor, action, path, tenant_id, worker_id, \
+                       payload_hash, status_code, outcome";
+
+/// WHERE clause applying an [`AuditFilter`] bound as `$1`..`$6`
+const AUDIT_FILTER: &str = r#"
+    WHERE ($1::TIMESTAMPTZ IS NULL OR occurred_at >= $1)
+      AND ($2::TIMESTAMPTZ IS NULL OR occurred_at < $2)
+      AND ($3::UUID IS NULL OR tenant_id = $3)
+      AND ($4::TEXT IS NULL OR worker_id = $4)
+      AND ($5::TEXT IS NULL OR actor = $5)
+      AND ($6::TEXT IS NULL OR action = $6)
+"#;
+
+/// Audited API call, before it is stored
+#[derive(Debug, Clone)]
+pub struct AuditRecord {
+    pub actor: String,
+    pub action: String,
+    pub path: String,
+    pub tenant_id: Option<Uuid>,
+    pub worker_id: Option<String>,
+    pub payload_hash: Option<String>,
+    pub status_code: u16,
+}
+
+/// Conditions on listed entries; unset fields match every entry
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Only entries at or after this time
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only entries before this time
+    pub until: Option<DateTime<Utc>>,
+
+    pub tenant_id: Option<Uuid>,
+
+    pub worker_id: Option<String>,
+
+    pub actor: Option<String>,
+
+    pub action: Option<String>,
+}
+
+/// Audit trail of mutating API calls
+pub struct AuditLog {
+    db: Arc<PgPool>,
+}
+
+impl AuditLog {
+    /// Create an audit log writing to the given database
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// Store an audited call
+    pub async fn record(&self, record: &AuditRecord) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO orchestrator_audit_log
+                (actor, action, path, tenant_id, worker_id, payload_hash, status_code, outcome)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+        .bind(&record.actor)
+        .bind(&record.action)
+        .bind(&record.path)
+        .bind(record.tenant_id)
+        .bind(&record.worker_id)
+        .bind(&record.payload_hash)
+        .bind(i32::from(record.status_code))
+        .bind(AuditOutcome::from_status(record.status_code).as_str())
+        .execute(&*self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Store an audited call in the background, counting failures
+    pub fn record_in_background(self: &Arc<Self>, record: AuditRecord) {
+        let audit_log = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = audit_log.record(&record).await {
+                AUDIT_WRITE_FAILURES
+                    .with_label_values(&[&record.action])
+                    .inc();
+                warn!(
+                    "Failed to write audit log entry for {} by {}: {}",
+                    record.action, record.actor, e
+                );
+            }
+        });
+    }
+
+    /// One page of the entries matching a filter, newest first
+    pub async fn list(
+        &self,
+        filter: &AuditFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<AuditEntry>> {
+        let entries = sqlx::query_as::<_, AuditEntry>(&format!(
+            r#"
+            SELECT {COLUMNS}
+            FROM orchestrator_audit_log
+            {AUDIT_FILTER}
+            ORDER BY occurred_at DESC, id
+            LIMIT $7 OFFSET $8
+            "#
+        ))
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.tenant_id)
+        .bind(&filter.worker_id)
+        .bind(&filter.actor)
+        .bind(&filter.action)
+        .bind(limit.min(i64::MAX as u64) as i64)
+        .bind(offset.min(i64::MAX as u64) as i64)
+        .fetch_all(&*self.db)
+        .await?;
+        Ok(entries)
+    }
+
+    /// Number of entries matching a filter
+    pub async fn count(&self, filter: &AuditFilter) -> Result<u64> {
+        let count = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT COUNT(*) FROM orchestrator_audit_log {AUDIT_FILTER}"
+        ))
+        .bind(filter.since)
+        .bind(filter.until)
+        .bind(filter.tenant_id)
+        .bind(&filter.worker_id)
+        .bind(&filter.actor)
+        .bind(&filter.action)
+        .fetch_one(&*self.db)
+        .await?;
+        Ok(count.max(0) as u64)
+    }
+}
@@ -7,7 +7,10 @@ use anyhow::Result;
 use async_trait::async_trait;
 use redis::{AsyncCommands, Client as RedisClient};
 use std::sync::Arc;
-use tracing::{debug, instrument};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info, instrument};
+use uuid::Uuid;
 
 // Import OpenZeppelin Monitor types
 use openzeppelin_monitor::{
@@ -15,6 +18,17 @@ use openzeppelin_monitor::{
     services::blockchain::BlockChainClient,
 };
 
+use crate::services::distributed_lock::DistributedLock;
+use crate::services::metrics::{CACHE_HITS, CACHE_MISSES};
+use crate::services::redis_keyspace::RedisKeyspace;
+use crate::services::rpc_costs::RpcCostTracker;
+
+/// How long a single-flight fetch keeps other processes waiting for its result
+const FETCH_LOCK_TTL: Duration = Duration::from_secs(10);
+
+/// How often processes waiting on another's fetch check the cache
+const FETCH_WAIT_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Configuration for the block cache
 #[derive(Debug, Clone)]
 pub struct BlockCacheConfig {
@@ -40,6 +54,8 @@ impl Default for BlockCacheConfig {
 pub struct BlockCacheService {
     redis: Arc<RedisClient>,
     config: BlockCacheConfig,
+    keyspace: RedisKeyspace,
+    rpc_costs: Option<Arc<RpcCostTracker>>,
 }
 
 impl BlockCacheService {
@@ -53,9 +69,163 @@ impl BlockCacheService {
         Ok(Self {
             redis: Arc::new(redis),
             config,
+            keyspace: RedisKeyspace::default(),
+            rpc_costs: None,
         })
     }
 
+    /// Scope all keys written through this service to the given keyspace
+    pub fn with_keyspace(mut self, keyspace: RedisKeyspace) -> Self {
+        self.keyspace = keyspace;
+        self
+    }
+
+    /// Attribute block fetches through this cache to the tenants of their network
+    pub fn with_rpc_costs(mut self, rpc_costs: Arc<RpcCostTracker>) -> Self {
+        self.rpc_costs = Some(rpc_costs);
+        self
+    }
+
+    /// Get the RPC cost tracker, if cost attribution is enabled
+    pub fn rpc_costs(&self) -> Option<Arc<RpcCostTracker>> {
+        self.rpc_costs.clone()
+    }
+
+    /// Charge requests to every tenant of a network
+    fn record_shared_requests(&self, network_slug: &str, requests: usize, cached: bool) {
+        if let Some(rpc_costs) = &self.rpc_costs {
+            rpc_costs.record_shared(network_slug, requests as f64, cached);
+        }
+    }
+
+    /// Get the deployment keyspace shared by all Redis consumers
+    pub fn keyspace(&self) -> &RedisKeyspace {
+        &self.keyspace
+    }
+
+    /// Check that Redis answers
+    pub async fn ping(&self) -> Result<()> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        redis::cmd("PING").query_async::<()>(&mut conn).await?;
+        Ok(())
+    }
+
+    /// Get the underlying Redis client for services sharing the connection
+    pub fn redis_client(&self) -> Arc<RedisClient> {
+        self.redis.clone()
+    }
+
+    /// Get the configured Redis key prefix, scoped to the keyspace
+    pub fn key_prefix(&self) -> String {
+        self.keyspace.key(&self.config.key_prefix)
+    }
+
+    /// Cache key for a block range on a network
+    pub fn blocks_key(&self, network_slug: &str, start: u64, end: Option<u64>) -> String {
+        format!(
+            "{}:blocks:{}:{}:{:?}",
+            self.key_prefix(),
+            network_slug,
+            start,
+            end
+        )
+    }
+
+    /// Lock letting one process fetch a block range on a cache miss while
+    /// others wait for it to land in the cache
+    pub fn fetch_lock(&self, network_slug: &str, start: u64, end: Option<u64>) -> DistributedLock {
+        DistributedLock::new(
+            self.redis.clone(),
+            &self.keyspace,
+            &format!("lock:blocks:{}:{}:{:?}", network_slug, start, end),
+            Uuid::new_v4().to_string(),
+            FETCH_LOCK_TTL,
+        )
+    }
+
+    /// Cache key for the latest block number of a network
+    pub fn latest_block_key(&self, network_slug: &str) -> String {
+        format!("{}:latest:{}", self.key_prefix(), network_slug)
+    }
+
+    /// Pre-fetch the latest block number and the latest `depth` confirmed blocks
+    /// of a network so the first processing cycles are served from cache.
+    ///
+    /// Blocks are cached individually so any later range request covering them
+    /// can be assembled without an RPC call. Returns the number of cached blocks.
+    pub async fn warm_network<C: BlockChainClient>(
+        &self,
+        client: &C,
+        network: &Network,
+        depth: u64,
+    ) -> Result<usize> {
+        let latest_block = client.get_latest_block_number().await?;
+        self.record_shared_requests(&network.slug, 1, false);
+        self.cache_latest_block(
+            &self.latest_block_key(&network.slug),
+            latest_block,
+            self.config.latest_block_ttl,
+        )
+        .await?;
+
+        if depth == 0 {
+            return Ok(0);
+        }
+
+        let end = latest_block.saturating_sub(network.confirmation_blocks);
+        let start = end.saturating_sub(depth - 1);
+        let blocks = client.get_blocks(start, Some(end)).await?;
+        self.record_shared_requests(&network.slug, blocks.len(), false);
+
+        let mut cached = 0;
+        for block in &blocks {
+            if let Some(number) = block.number() {
+                let key = self.blocks_key(&network.slug, number, Some(number));
+                self.cache_blocks(&key, std::slice::from_ref(block), self.config.block_ttl)
+                    .await?;
+                cached += 1;
+            }
+        }
+
+        info!(
+            "Warmed cache for network {} with {} blocks ({} to {})",
+            network.slug, cached, start, end
+        );
+        Ok(cached)
+    }
+
+    /// Assemble a block range from individually cached blocks.
+    ///
+    /// Returns None unless every block in the range is cached.
+    async fn get_cached_block_range(
+        &self,
+        network_slug: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Option<Vec<BlockType>>> {
+        if end < start {
+            return Ok(None);
+        }
+
+        let keys: Vec<String> = (start..=end)
+            .map(|number| self.blocks_key(network_slug, number, Some(number)))
+            .collect();
+
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let data: Vec<Option<Vec<u8>>> =
+            redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+
+        let mut blocks = Vec::with_capacity(keys.len());
+        for bytes in data {
+            match bytes {
+                Some(bytes) => blocks.extend(serde_json::from_slice::<Vec<BlockType>>(&bytes)?),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(blocks))
+    }
+
     /// Get cached blocks or None if not found
     async fn get_cached_blocks(&self, key: &str) -> Result<Option<Vec<BlockType>>> {
         let mut conn = self.redis.get_multiplexed_async_connection().await?;
@@ -70,6 +240,18 @@ impl BlockCacheService {
         }
     }
 
+    /// Poll for blocks another process is fetching, giving up after `timeout`
+    async fn wait_for_cached_blocks(&self, key: &str, timeout: Duration) -> Option<Vec<BlockType>> {
+        let deadline = Instant::now() + timeout;
+        while Instant::now() < deadline {
+            tokio::time::sleep(FETCH_WAIT_INTERVAL).await;
+            if let Ok(Some(blocks)) = self.get_cached_blocks(key).await {
+                return Some(blocks);
+            }
+        }
+        None
+    }
+
     /// Cache blocks with TTL
     async fn cache_blocks(&self, key: &str, blocks: &[BlockType], ttl: u64) -> Result<()> {
         let mut conn = self.redis.get_multiplexed_async_connection().await?;
@@ -78,6 +260,12 @@ impl BlockCacheService {
         Ok(())
     }
 
+    /// Latest block number of a network as last cached by any process; None once it expires
+    pub async fn latest_block(&self, network_slug: &str) -> Result<Option<u64>> {
+        self.get_cached_latest_block(&self.latest_block_key(network_slug))
+            .await
+    }
+
     /// Get cached latest block number
     async fn get_cached_latest_block(&self, key: &str) -> Result<Option<u64>> {
         let mut conn = self.redis.get_multiplexed_async_connection().await?;
@@ -104,8 +292,17 @@ pub struct CachedBlockClient<C: BlockChainClient> {
 
 impl<C: BlockChainClient> CachedBlockClient<C> {
     pub fn new(inner_client: C, cache: Arc<BlockCacheService>, network: &Network) -> Self {
+        Self::from_arc(Arc::new(inner_client), cache, network)
+    }
+
+    /// Wrap a client shared with a client pool
+    pub fn from_arc(
+        inner_client: Arc<C>,
+        cache: Arc<BlockCacheService>,
+        network: &Network,
+    ) -> Self {
         Self {
-            inner_client: Arc::new(inner_client),
+            inner_client,
             cache,
             network_slug: network.slug.clone(),
             _chain_type: network.network_type.clone(),
@@ -113,17 +310,11 @@ impl<C: BlockChainClient> CachedBlockClient<C> {
     }
 
     fn block_cache_key(&self, start: u64, end: Option<u64>) -> String {
-        format!(
-            "{}:blocks:{}:{}:{:?}",
-            self.cache.config.key_prefix, self.network_slug, start, end
-        )
+        self.cache.blocks_key(&self.network_slug, start, end)
     }
 
     fn latest_block_cache_key(&self) -> String {
-        format!(
-            "{}:latest:{}",
-            self.cache.config.key_prefix, self.network_slug
-        )
+        self.cache.latest_block_key(&self.network_slug)
     }
 }
 
@@ -141,6 +332,9 @@ impl<C: BlockChainClient + Send + Sync> BlockChainClient for CachedBlockClient<C
         match self.cache.get_cached_blocks(&cache_key).await {
             Ok(Some(blocks)) => {
                 debug!("Cache hit for blocks {} to {:?}", start, end);
+                CACHE_HITS.with_label_values(&[&self.network_slug]).inc();
+                self.cache
+                    .record_shared_requests(&self.network_slug, blocks.len(), true);
                 return Ok(blocks);
             }
             Ok(None) => {
@@ -151,8 +345,60 @@ impl<C: BlockChainClient + Send + Sync> BlockChainClient for CachedBlockClient<C
             }
         }
 
+        // Fall back to individually cached blocks (e.g. from cache warming)
+        if let Some(end) = end {
+            if let Ok(Some(blocks)) = self
+                .cache
+                .get_cached_block_range(&self.network_slug, start, end)
+                .await
+            {
+                debug!("Assembled blocks {} to {} from cache", start, end);
+                CACHE_HITS.with_label_values(&[&self.network_slug]).inc();
+                self.cache
+                    .record_shared_requests(&self.network_slug, blocks.len(), true);
+                return Ok(blocks);
+            }
+        }
+
+        // Only one process fetches a missing range; the others wait for it to be cached
+        let fetch_lock = self.cache.fetch_lock(&self.network_slug, start, end);
+        let guard = match fetch_lock.try_acquire().await {
+            Ok(Some(guard)) => Some(guard),
+            Ok(None) => {
+                debug!("Waiting for another fetch of blocks {} to {:?}", start, end);
+                if let Some(blocks) = self
+                    .cache
+                    .wait_for_cached_blocks(&cache_key, fetch_lock.ttl())
+                    .await
+                {
+                    CACHE_HITS.with_label_values(&[&self.network_slug]).inc();
+                    self.cache
+                        .record_shared_requests(&self.network_slug, blocks.len(), true);
+                    return Ok(blocks);
+                }
+                None
+            }
+            Err(e) => {
+                debug!("Failed to take fetch lock, fetching anyway: {}", e);
+                None
+            }
+        };
+
         // Fetch from RPC
-        let blocks = self.inner_client.get_blocks(start, end).await?;
+        CACHE_MISSES.with_label_values(&[&self.network_slug]).inc();
+        let fetched = self.inner_client.get_blocks(start, end).await;
+        let blocks = match fetched {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                if let Some(guard) = guard {
+                    let _ = guard.release().await;
+                }
+                return Err(e);
+            }
+        };
+
+        self.cache
+            .record_shared_requests(&self.network_slug, blocks.len(), false);
 
         // Cache the result
         if let Err(e) = self
@@ -162,6 +408,11 @@ impl<C: BlockChainClient + Send + Sync> BlockChainClient for CachedBlockClient<C
         {
             debug!("Failed to cache blocks: {}", e);
         }
+        if let Some(guard) = guard {
+            if let Err(e) = guard.release().await {
+                debug!("Failed to release fetch lock: {}", e);
+            }
+        }
 
         Ok(blocks)
     }
@@ -174,6 +425,8 @@ impl<C: BlockChainClient + Send + Sync> BlockChainClient for CachedBlockClient<C
         match self.cache.get_cached_latest_block(&cache_key).await {
             Ok(Some(number)) => {
                 debug!("Cache hit for latest block number: {}", number);
+                self.cache
+                    .record_shared_requests(&self.network_slug, 1, true);
                 return Ok(number);
             }
             Ok(None) => {
@@ -186,6 +439,8 @@ impl<C: BlockChainClient + Send + Sync> BlockChainClient for CachedBlockClient<C
 
         // Fetch from RPC
         let block_number = self.inner_client.get_latest_block_number().await?;
+        self.cache
+            .record_shared_requests(&self.network_slug, 1, false);
 
         // Cache the result
         if let Err(e) = self
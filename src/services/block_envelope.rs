@@ -0,0 +1,183 @@
+//! Block Event Envelope
+//!
+//! Versioned binary framing for block events sent between processes. A fixed
+//! header carries the schema version, network type and compression flag ahead
+//! of the JSON payload, so watchers and workers can be upgraded independently:
+//! a reader rejects versions it does not know instead of misparsing them, and
+//! can skip networks it does not serve without decoding the payload.
+//!
+//! Layout: `OZBE` magic, schema version (u16, big endian), network type (u8),
+//! flags (u8), payload.
+
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{de::DeserializeOwned, Serialize};
+use std::io::Read;
+
+use openzeppelin_monitor::models::BlockChainType;
+
+use crate::services::shared_block_watcher::BlockEvent;
+
+/// Schema version written by this build
+pub const BLOCK_EVENT_SCHEMA_VERSION: u16 = 1;
+
+const MAGIC: &[u8; 4] = b"OZBE";
+const HEADER_LEN: usize = 8;
+const FLAG_GZIP: u8 = 0b0000_0001;
+
+/// Network type recorded in the envelope header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeNetworkType {
+    Evm,
+    Stellar,
+    /// Written by a newer build for a chain this build does not know
+    Unknown(u8),
+}
+
+impl EnvelopeNetworkType {
+    fn from_byte(byte: u8) -> Self {
+        match byte {
+            0 => EnvelopeNetworkType::Evm,
+            1 => EnvelopeNetworkType::Stellar,
+            other => EnvelopeNetworkType::Unknown(other),
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            EnvelopeNetworkType::Evm => 0,
+            EnvelopeNetworkType::Stellar => 1,
+            EnvelopeNetworkType::Unknown(other) => other,
+        }
+    }
+}
+
+impl From<&BlockChainType> for EnvelopeNetworkType {
+    fn from(network_type: &BlockChainType) -> Self {
+        match network_type {
+            BlockChainType::EVM => EnvelopeNetworkType::Evm,
+            BlockChainType::Stellar => EnvelopeNetworkType::Stellar,
+            #[allow(unreachable_patterns)]
+            _ => EnvelopeNetworkType::Unknown(u8::MAX),
+        }
+    }
+}
+
+/// Envelope header, readable without decoding the payload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnvelopeHeader {
+    pub schema_version: u16,
+    pub network_type: EnvelopeNetworkType,
+    pub compressed: bool,
+}
+
+impl EnvelopeHeader {
+    /// Read the header of an encoded event
+    pub fn parse(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            anyhow::bail!("Not a block event envelope");
+        }
+
+        let flags = bytes[7];
+        if flags & !FLAG_GZIP != 0 {
+            anyhow::bail!("Unsupported block event envelope flags {:#010b}", flags);
+        }
+
+        Ok(Self {
+            schema_version: u16::from_be_bytes([bytes[4], bytes[5]]),
+            network_type: EnvelopeNetworkType::from_byte(bytes[6]),
+            compressed: flags & FLAG_GZIP != 0,
+        })
+    }
+
+    /// Check if this build can decode the payload
+    pub fn is_supported(&self) -> bool {
+        self.schema_version <= BLOCK_EVENT_SCHEMA_VERSION
+    }
+}
+
+/// Encode a block event, gzip-compressing the payload if requested
+pub fn encode(event: &BlockEvent, compress: bool) -> Result<Vec<u8>> {
+    encode_payload(
+        EnvelopeNetworkType::from(&event.network.network_type),
+        event,
+        compress,
+    )
+}
+
+/// Decode a block event written by this or an older build
+pub fn decode(bytes: &[u8]) -> Result<BlockEvent> {
+    decode_payload(bytes)
+}
+
+fn encode_payload<T: Serialize>(
+    network_type: EnvelopeNetworkType,
+    value: &T,
+    compress: bool,
+) -> Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&BLOCK_EVENT_SCHEMA_VERSION.to_be_bytes());
+    bytes.push(network_type.to_byte());
+    bytes.push(if compress { FLAG_GZIP } else { 0 });
+
+    if compress {
+        let mut encoder = GzEncoder::new(bytes, flate2::Compression::fast());
+        serde_json::to_writer(&mut encoder, value)?;
+        bytes = encoder.finish()?;
+    } else {
+        serde_json::to_writer(&mut bytes, value)?;
+    }
+    Ok(bytes)
+}
+
+fn decode_payload<T: DeserializeOwned>(bytes: &[u8]) -> Result<T> {
+    let header = EnvelopeHeader::parse(bytes)?;
+    if !header.is_supported() {
+        anyhow::bail!(
+            "Block event schema version {} is newer than supported version {}",
+            header.schema_version,
+            BLOCK_EVENT_SCHEMA_VERSION
+        );
+    }
+
+    let payload = &bytes[HEADER_LEN..];
+    if header.compressed {
+        let mut json = Vec::new();
+        GzDecoder::new(payload)
+            .read_to_end(&mut json)
+            .context("Corrupt compressed block event")?;
+        Ok(serde_json::from_slice(&json)?)
+    } else {
+        Ok(serde_json::from_slice(payload)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_round_trip_with_and_without_compression() {
+        let value = json!({ "blocks": [1, 2, 3], "network": "ethereum_mainnet" });
+
+        for compress in [false, true] {
+            let bytes = encode_payload(EnvelopeNetworkType::Evm, &value, compress).unwrap();
+            let header = EnvelopeHeader::parse(&bytes).unwrap();
+            assert_eq!(header.network_type, EnvelopeNetworkType::Evm);
+            assert_eq!(header.compressed, compress);
+            assert_eq!(decode_payload::<serde_json::Value>(&bytes).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_rejects_newer_schema_versions() {
+        let mut bytes = encode_payload(EnvelopeNetworkType::Stellar, &json!({}), false).unwrap();
+        bytes[4..6].copy_from_slice(&(BLOCK_EVENT_SCHEMA_VERSION + 1).to_be_bytes());
+
+        assert!(!EnvelopeHeader::parse(&bytes).unwrap().is_supported());
+        assert!(decode_payload::<serde_json::Value>(&bytes).is_err());
+    }
+}
@@ -7,9 +7,26 @@
 //! The caching strategy is implemented at a higher level (in SharedBlockWatcher)
 //! rather than wrapping individual clients, which simplifies the implementation
 //! while still providing the performance benefits of caching.
+//!
+//! Clients are shared per network and set of RPC endpoints, so every tenant
+//! monitoring a chain through the same endpoints uses one client, while
+//! tenants bringing their own endpoints for the same network slug get their
+//! own. Reuse of each client is counted and reported by
+//! [`CachedClientPool::client_stats`].
+//!
+//! With [`EndpointHealth`], Stellar clients are created with only the Horizon
+//! and Soroban RPC endpoints passing their health checks. A failed over
+//! endpoint changes the network's endpoint set, so the next request gets a
+//! client without it.
 
 use anyhow::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use serde::Serialize;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use openzeppelin_monitor::{
@@ -18,32 +35,140 @@ use openzeppelin_monitor::{
 };
 
 use super::block_cache::BlockCacheService;
+use super::endpoint_health::EndpointHealth;
+use super::retry::RetryPolicy;
+
+/// Identity of a shared client: the network and the endpoints used to reach it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    network_slug: String,
+    /// Hash of the network's RPC URLs, so credentials never leave the pool
+    endpoints: u64,
+}
+
+impl ClientKey {
+    fn new(network: &Network) -> Self {
+        let mut hasher = DefaultHasher::new();
+        serde_json::to_string(&network.rpc_urls)
+            .unwrap_or_default()
+            .hash(&mut hasher);
+        Self {
+            network_slug: network.slug.clone(),
+            endpoints: hasher.finish(),
+        }
+    }
+}
+
+/// Client shared by every caller with the same [`ClientKey`]
+struct SharedClient {
+    /// Pool holding the single client for this key
+    pool: ClientPool,
+    requests: AtomicU64,
+    created_at: DateTime<Utc>,
+}
+
+/// Reuse of one shared client
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientReuseStats {
+    pub network_slug: String,
+
+    /// Fingerprint of the RPC endpoints the client uses
+    pub endpoints: String,
+
+    /// Times the client was handed out
+    pub requests: u64,
+
+    /// Requests served by the existing client instead of a new one
+    pub reused: u64,
+
+    pub created_at: DateTime<Utc>,
+}
 
 /// Cached client pool implementation
 ///
 /// This implementation provides a caching layer over the standard ClientPool.
-/// While we pass through client creation to the underlying pool, we could
-/// enhance this in the future to provide caching at the client level.
+/// Client creation is passed through to one underlying pool per network and
+/// endpoint set.
 pub struct CachedClientPool {
-    /// The underlying client pool
-    inner: ClientPool,
+    /// Shared clients by network and endpoints
+    clients: DashMap<ClientKey, Arc<SharedClient>>,
     /// Block cache service for caching blockchain data
     cache: Arc<BlockCacheService>,
+    /// Retry policy for client creation
+    retry: RetryPolicy,
+    /// Health of Stellar endpoints, to fail over from unhealthy ones
+    endpoint_health: Option<Arc<EndpointHealth>>,
 }
 
 impl CachedClientPool {
     /// Create a new cached client pool
     pub fn new(cache: Arc<BlockCacheService>) -> Self {
         Self {
-            inner: ClientPool::new(),
+            clients: DashMap::new(),
             cache,
+            retry: RetryPolicy::default(),
+            endpoint_health: None,
         }
     }
 
+    /// Retry client creation with the given policy
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Create Stellar clients with the endpoints the given tracker finds healthy
+    pub fn with_endpoint_health(mut self, endpoint_health: Arc<EndpointHealth>) -> Self {
+        self.endpoint_health = Some(endpoint_health);
+        self
+    }
+
+    /// Health of Stellar endpoints, if failover is enabled
+    pub fn endpoint_health(&self) -> Option<Arc<EndpointHealth>> {
+        self.endpoint_health.clone()
+    }
+
     /// Get the cache service
     pub fn cache(&self) -> Arc<BlockCacheService> {
         self.cache.clone()
     }
+
+    /// Reuse counts of every client created so far
+    pub fn client_stats(&self) -> Vec<ClientReuseStats> {
+        let mut stats: Vec<ClientReuseStats> = self
+            .clients
+            .iter()
+            .map(|entry| {
+                let requests = entry.requests.load(Ordering::Relaxed);
+                ClientReuseStats {
+                    network_slug: entry.key().network_slug.clone(),
+                    endpoints: format!("{:016x}", entry.key().endpoints),
+                    requests,
+                    reused: requests.saturating_sub(1),
+                    created_at: entry.created_at,
+                }
+            })
+            .collect();
+        stats.sort_by(|a, b| (&a.network_slug, &a.endpoints).cmp(&(&b.network_slug, &b.endpoints)));
+        stats
+    }
+
+    /// Shared client entry for a network, counting the request
+    fn shared_client(&self, network: &Network) -> Arc<SharedClient> {
+        let client = self
+            .clients
+            .entry(ClientKey::new(network))
+            .or_insert_with(|| {
+                Arc::new(SharedClient {
+                    pool: ClientPool::new(),
+                    requests: AtomicU64::new(0),
+                    created_at: Utc::now(),
+                })
+            })
+            .clone();
+        client.requests.fetch_add(1, Ordering::Relaxed);
+        client
+    }
 }
 
 #[async_trait]
@@ -52,14 +177,30 @@ impl ClientPoolTrait for CachedClientPool {
     type StellarClient = <ClientPool as ClientPoolTrait>::StellarClient;
 
     async fn get_evm_client(&self, network: &Network) -> Result<Arc<Self::EvmClient>> {
-        // Pass through to the underlying pool
+        // Pass through to the pool shared by callers with the same endpoints
         // Caching is handled at the SharedBlockWatcher level
-        self.inner.get_evm_client(network).await
+        let client = self.shared_client(network);
+        self.retry
+            .retry(|| client.pool.get_evm_client(network))
+            .await
     }
 
     async fn get_stellar_client(&self, network: &Network) -> Result<Arc<Self::StellarClient>> {
-        // Pass through to the underlying pool
+        // Leave out endpoints that are failing their health checks
+        let healthy;
+        let network = match &self.endpoint_health {
+            Some(endpoint_health) => {
+                healthy = endpoint_health.healthy_network(network);
+                &healthy
+            }
+            None => network,
+        };
+
+        // Pass through to the pool shared by callers with the same endpoints
         // Caching is handled at the SharedBlockWatcher level
-        self.inner.get_stellar_client(network).await
+        let client = self.shared_client(network);
+        self.retry
+            .retry(|| client.pool.get_stellar_client(network))
+            .await
     }
 }
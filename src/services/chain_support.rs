@@ -0,0 +1,116 @@
+//! Chain Support
+//!
+//! Registry of the blockchain types a deployment accepts. The block watcher,
+//! block cache and filter layer implement a fixed set of chain types, and a
+//! deployment enables some of them in its configuration. Networks of any
+//! other type, including variants newer upstream releases add, are rejected
+//! when they are created or added to the block watcher instead of being
+//! skipped with a warning. Support for a new variant is added to
+//! [`IMPLEMENTED_CHAINS`] once it is handled everywhere, and each deployment
+//! then opts in through `chains.enabled`.
+
+use openzeppelin_monitor::models::BlockChainType;
+use std::collections::BTreeSet;
+
+use crate::services::ServiceError;
+
+/// Chain types the block watcher, block cache and filter layer can process
+pub const IMPLEMENTED_CHAINS: &[&str] = &["evm", "stellar"];
+
+/// Chain types enabled in this deployment
+#[derive(Debug, Clone)]
+pub struct ChainSupport {
+    enabled: BTreeSet<String>,
+}
+
+impl Default for ChainSupport {
+    fn default() -> Self {
+        Self::new(IMPLEMENTED_CHAINS.iter().map(|chain| chain.to_string()))
+    }
+}
+
+impl ChainSupport {
+    /// Enable the given chain types, ignoring ones this build does not implement
+    pub fn new(enabled: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            enabled: enabled
+                .into_iter()
+                .map(|chain| chain.to_lowercase())
+                .filter(|chain| Self::is_implemented(chain))
+                .collect(),
+        }
+    }
+
+    /// Name of a chain type, as used in configuration (e.g. `evm`, `stellar`)
+    pub fn chain_name(network_type: &BlockChainType) -> String {
+        format!("{:?}", network_type).to_lowercase()
+    }
+
+    /// Whether this build can process a chain type
+    pub fn is_implemented(chain: &str) -> bool {
+        IMPLEMENTED_CHAINS.contains(&chain.to_lowercase().as_str())
+    }
+
+    /// Chain types enabled in this deployment
+    pub fn enabled(&self) -> impl Iterator<Item = &str> {
+        self.enabled.iter().map(String::as_str)
+    }
+
+    /// Fail with [`ServiceError::UnsupportedChain`] unless a chain type is enabled
+    pub fn check(&self, network_type: &BlockChainType) -> Result<(), ServiceError> {
+        self.check_name(&Self::chain_name(network_type))
+    }
+
+    /// Fail with [`ServiceError::UnsupportedChain`] unless a chain type, by name, is enabled
+    pub fn check_name(&self, chain: &str) -> Result<(), ServiceError> {
+        let chain = chain.to_lowercase();
+        if self.enabled.contains(&chain) {
+            return Ok(());
+        }
+
+        let enabled = self.enabled().collect::<Vec<_>>().join(", ");
+        Err(ServiceError::UnsupportedChain(
+            if Self::is_implemented(&chain) {
+                format!(
+                    "chain type {} is not enabled in this deployment (chains.enabled: {})",
+                    chain, enabled
+                )
+            } else {
+                format!(
+                    "chain type {} is not supported by this orchestrator (supported: {})",
+                    chain,
+                    IMPLEMENTED_CHAINS.join(", ")
+                )
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_name_matches_configuration() {
+        assert_eq!(ChainSupport::chain_name(&BlockChainType::EVM), "evm");
+        assert_eq!(
+            ChainSupport::chain_name(&BlockChainType::Stellar),
+            "stellar"
+        );
+    }
+
+    #[test]
+    fn test_only_enabled_chains_pass() {
+        let chains = ChainSupport::new(vec!["EVM".to_string(), "midnight".to_string()]);
+        assert!(chains.check(&BlockChainType::EVM).is_ok());
+        assert!(matches!(
+            chains.check(&BlockChainType::Stellar),
+            Err(ServiceError::UnsupportedChain(_))
+        ));
+        assert!(matches!(
+            chains.check_name("midnight"),
+            Err(ServiceError::UnsupportedChain(_))
+        ));
+        assert_eq!(chains.enabled().collect::<Vec<_>>(), vec!["evm"]);
+    }
+}
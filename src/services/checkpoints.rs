@@ -0,0 +1,463 @@
+//! Tenant Block Checkpoints
+//!
+//! Ledger of the highest block each tenant has processed per network.
+//! Workers record every block a tenant's monitors were evaluated against;
+//! the highest block per tenant and network is kept in memory and written to
+//! Postgres on every flush, never moving a stored checkpoint backwards. Only
+//! a rewind moves a checkpoint back, after which the blocks following it are
+//! replayed for the tenant.
+//!
+//! Next to the checkpoint the ledger keeps each tenant's watermark: the block
+//! up to which every block is accounted for, either evaluated or skipped by
+//! the tenant's own state (paused, outside its trial schedule, over its RPC
+//! cap). A block that failed or was never delivered holds the watermark back
+//! until it is processed, e.g. by rewinding to before it. Workers pick up the
+//! stored watermark when they first see a tenant or find a gap, so a tenant
+//! moving between workers keeps its watermark.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use serde::Deserialize;
+use sqlx::PgPool;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::TenantCheckpoint;
+use crate::services::{metrics, ServiceError};
+
+/// Blocks past a gap kept per tenant and network waiting for the gap to close
+const MAX_AHEAD_BLOCKS: usize = 10_000;
+
+/// How far a checkpoint is moved back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckpointRewind {
+    /// Move back this many blocks
+    Blocks(u64),
+
+    /// Move back to this block; the blocks after it are processed again
+    ToBlock(u64),
+}
+
+impl CheckpointRewind {
+    /// Last processed block after rewinding from `last_processed`
+    pub fn target(&self, last_processed: u64) -> Result<u64, ServiceError> {
+        match *self {
+            CheckpointRewind::Blocks(0) => Err(ServiceError::InvalidState(
+                "rewind must move back at least one block".to_string(),
+            )),
+            CheckpointRewind::Blocks(blocks) => {
+                last_processed.checked_sub(blocks).ok_or_else(|| {
+                    ServiceError::InvalidState(format!(
+                        "cannot rewind {} blocks from block {}",
+                        blocks, last_processed
+                    ))
+                })
+            }
+            CheckpointRewind::ToBlock(block) if block >= last_processed => {
+                Err(ServiceError::InvalidState(format!(
+                    "block {} is not before the checkpoint at block {}",
+                    block, last_processed
+                )))
+            }
+            CheckpointRewind::ToBlock(block) => Ok(block),
+        }
+    }
+}
+
+/// Contiguous run of accounted blocks of a tenant on a network
+#[derive(Debug, Default)]
+struct Watermark {
+    /// Every block up to and including this one is accounted for; None until
+    /// the stored watermark has been read
+    block: Option<u64>,
+    /// Accounted blocks after a gap following `block`
+    ahead: BTreeSet<u64>,
+    /// Whether `block` moved since it was last written
+    moved: bool,
+}
+
+impl Watermark {
+    fn mark(&mut self, block_number: u64) {
+        if self.block.is_some_and(|block| block_number <= block) {
+            return;
+        }
+        self.ahead.insert(block_number);
+        if self.ahead.len() > MAX_AHEAD_BLOCKS {
+            self.ahead.pop_first();
+        }
+        self.advance();
+    }
+
+    /// Whether the stored watermark should be read: never read yet, or
+    /// stuck behind a gap another worker may have closed
+    fn needs_seed(&self) -> bool {
+        self.block.is_none() || !self.ahead.is_empty()
+    }
+
+    /// Catch up with the stored watermark; without one the watermark starts
+    /// at the first accounted block
+    fn seed(&mut self, stored: Option<u64>) {
+        let block = match (self.block, stored) {
+            (Some(block), Some(stored)) => Some(block.max(stored)),
+            (block, stored) => block.or(stored),
+        };
+        let block = block.or_else(|| self.ahead.pop_first());
+        if block > self.block {
+            self.block = block;
+            self.moved = true;
+        }
+        if let Some(block) = self.block {
+            self.ahead = self.ahead.split_off(&(block + 1));
+        }
+        self.advance();
+    }
+
+    fn advance(&mut self) {
+        let Some(mut block) = self.block else {
+            return;
+        };
+        while self.ahead.first() == Some(&(block + 1)) {
+            self.ahead.pop_first();
+            block += 1;
+            self.moved = true;
+        }
+        self.block = Some(block);
+    }
+}
+
+/// Records and adjusts per-tenant block processing checkpoints
+pub struct CheckpointLedger {
+    db: Arc<PgPool>,
+    /// Highest block processed since the last flush, by tenant and network
+    pending: DashMap<(Uuid, String), u64>,
+    /// Watermark by tenant and network
+    watermarks: DashMap<(Uuid, String), Watermark>,
+}
+
+impl CheckpointLedger {
+    /// Create a ledger writing to the given database
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            pending: DashMap::new(),
+            watermarks: DashMap::new(),
+        }
+    }
+
+    /// Record that a tenant's monitors were evaluated against a block
+    pub fn record(&self, tenant_id: Uuid, network_slug: &str, block_number: u64) {
+        let mut entry = self
+            .pending
+            .entry((tenant_id, network_slug.to_string()))
+            .or_insert(block_number);
+        *entry = (*entry).max(block_number);
+        drop(entry);
+        self.pass(tenant_id, network_slug, block_number);
+    }
+
+    /// Record that a tenant skipped a block by its own state, counting it
+    /// towards the watermark but not the checkpoint
+    pub fn pass(&self, tenant_id: Uuid, network_slug: &str, block_number: u64) {
+        self.watermarks
+            .entry((tenant_id, network_slug.to_string()))
+            .or_default()
+            .mark(block_number);
+    }
+
+    /// Write the checkpoints and watermarks recorded since the last flush
+    pub async fn flush(&self) -> Result<()> {
+        self.flush_checkpoints().await?;
+        self.flush_watermarks().await
+    }
+
+    /// Advance stored checkpoints to the blocks recorded since the last flush
+    async fn flush_checkpoints(&self) -> Result<()> {
+        let keys: Vec<(Uuid, String)> = self.pending.iter().map(|e| e.key().clone()).collect();
+        let mut blocks = BTreeMap::new();
+        for key in keys {
+            if let Some((key, block_number)) = self.pending.remove(&key) {
+                blocks.insert(key, block_number);
+            }
+        }
+        if blocks.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.write_checkpoints(&blocks).await {
+            // Keep them for the next flush
+            for ((tenant_id, network_slug), block_number) in blocks {
+                self.record(tenant_id, &network_slug, block_number);
+            }
+            return Err(e);
+        }
+
+        debug!("Flushed checkpoints of {} tenant networks", blocks.len());
+        Ok(())
+    }
+
+    /// Catch up with stored watermarks where needed and advance the stored
+    /// ones to the watermarks that moved
+    async fn flush_watermarks(&self) -> Result<()> {
+        let stale: Vec<(Uuid, String)> = self
+            .watermarks
+            .iter()
+            .filter(|e| e.value().needs_seed())
+            .map(|e| e.key().clone())
+            .collect();
+        if !stale.is_empty() {
+            let stored = self.stored_watermarks(&stale).await?;
+            for key in stale {
+                if let Some(mut watermark) = self.watermarks.get_mut(&key) {
+                    watermark.seed(stored.get(&key).copied());
+                }
+            }
+        }
+
+        let mut moved = BTreeMap::new();
+        for mut entry in self.watermarks.iter_mut() {
+            if let (true, Some(block)) = (entry.moved, entry.block) {
+                entry.moved = false;
+                moved.insert(entry.key().clone(), block);
+            }
+        }
+        if moved.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.write_watermarks(&moved).await {
+            // Written again on the next flush
+            for key in moved.keys() {
+                if let Some(mut watermark) = self.watermarks.get_mut(key) {
+                    watermark.moved = true;
+                }
+            }
+            return Err(e);
+        }
+
+        for ((tenant_id, network_slug), block) in &moved {
+            metrics::TENANT_WATERMARK
+                .with_label_values(&[&tenant_id.to_string(), network_slug])
+                .set(*block as i64);
+        }
+        debug!("Flushed watermarks of {} tenant networks", moved.len());
+        Ok(())
+    }
+
+    /// Flush recorded blocks every `interval` until aborted
+    pub fn start_flush(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let ledger = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = ledger.flush().await {
+                    warn!("Failed to flush block checkpoints: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Checkpoints of a tenant on every network it processed
+    pub async fn list(&self, tenant_id: Uuid) -> Result<Vec<TenantCheckpoint>> {
+        let checkpoints = sqlx::query_as::<_, TenantCheckpoint>(
+            r#"
+            SELECT tenant_id, network_slug, last_processed_block, watermark_block,
+                   updated_at, rewound_from, rewound_at
+            FROM tenant_block_checkpoints
+            WHERE tenant_id = $1
+            ORDER BY network_slug
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await?;
+        Ok(checkpoints)
+    }
+
+    /// Checkpoint of a tenant on a network; None if it processed no block there
+    pub async fn get(
+        &self,
+        tenant_id: Uuid,
+        network_slug: &str,
+    ) -> Result<Option<TenantCheckpoint>> {
+        let checkpoint = sqlx::query_as::<_, TenantCheckpoint>(
+            r#"
+            SELECT tenant_id, network_slug, last_processed_block, watermark_block,
+                   updated_at, rewound_from, rewound_at
+            FROM tenant_block_checkpoints
+            WHERE tenant_id = $1 AND network_slug = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(network_slug)
+        .fetch_optional(&*self.db)
+        .await?;
+        Ok(checkpoint)
+    }
+
+    /// Move a checkpoint back to `block_number`, remembering where it was.
+    ///
+    /// Returns None if the tenant has no checkpoint on the network.
+    pub async fn rewind(
+        &self,
+        tenant_id: Uuid,
+        network_slug: &str,
+        block_number: u64,
+    ) -> Result<Option<TenantCheckpoint>> {
+        let checkpoint = sqlx::query_as::<_, TenantCheckpoint>(
+            r#"
+            UPDATE tenant_block_checkpoints
+            SET rewound_from = last_processed_block,
+                last_processed_block = $3,
+                rewound_at = NOW(),
+                updated_at = NOW()
+            WHERE tenant_id = $1 AND network_slug = $2
+            RETURNING tenant_id, network_slug, last_processed_block, watermark_block,
+                      updated_at, rewound_from, rewound_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(network_slug)
+        .bind(block_number as i64)
+        .fetch_optional(&*self.db)
+        .await?;
+        Ok(checkpoint)
+    }
+
+    async fn write_checkpoints(&self, blocks: &BTreeMap<(Uuid, String), u64>) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        for ((tenant_id, network_slug), block_number) in blocks {
+            sqlx::query(
+                r#"
+                INSERT INTO tenant_block_checkpoints
+                    (tenant_id, network_slug, last_processed_block, updated_at)
+                VALUES ($1, $2, $3, NOW())
+                ON CONFLICT (tenant_id, network_slug) DO UPDATE
+                SET last_processed_block = EXCLUDED.last_processed_block,
+                    updated_at = NOW()
+                WHERE tenant_block_checkpoints.last_processed_block
+                    < EXCLUDED.last_processed_block
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(network_slug)
+            .bind(*block_number as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn stored_watermarks(
+        &self,
+        keys: &[(Uuid, String)],
+    ) -> Result<BTreeMap<(Uuid, String), u64>> {
+        let (tenant_ids, network_slugs): (Vec<Uuid>, Vec<String>) = keys.iter().cloned().unzip();
+        let rows = sqlx::query_as::<_, (Uuid, String, i64)>(
+            r#"
+            SELECT c.tenant_id, c.network_slug, c.watermark_block
+            FROM tenant_block_checkpoints c
+            JOIN UNNEST($1::uuid[], $2::text[]) AS k(tenant_id, network_slug)
+                ON c.tenant_id = k.tenant_id AND c.network_slug = k.network_slug
+            WHERE c.watermark_block IS NOT NULL
+            "#,
+        )
+        .bind(tenant_ids)
+        .bind(network_slugs)
+        .fetch_all(&*self.db)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(|(tenant_id, network_slug, block)| {
+                ((tenant_id, network_slug), block.max(0) as u64)
+            })
+            .collect())
+    }
+
+    /// Advance stored watermarks; tenants without a checkpoint row have
+    /// nothing to advance yet
+    async fn write_watermarks(&self, blocks: &BTreeMap<(Uuid, String), u64>) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+        for ((tenant_id, network_slug), block_number) in blocks {
+            sqlx::query(
+                r#"
+                UPDATE tenant_block_checkpoints
+                SET watermark_block = GREATEST(watermark_block, $3)
+                WHERE tenant_id = $1 AND network_slug = $2
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(network_slug)
+            .bind(*block_number as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewind_target() {
+        assert_eq!(CheckpointRewind::Blocks(100).target(1000).unwrap(), 900);
+        assert_eq!(CheckpointRewind::ToBlock(900).target(1000).unwrap(), 900);
+        assert!(CheckpointRewind::Blocks(0).target(1000).is_err());
+        assert!(CheckpointRewind::Blocks(1001).target(1000).is_err());
+        assert!(CheckpointRewind::ToBlock(1000).target(1000).is_err());
+        assert!(CheckpointRewind::ToBlock(1001).target(1000).is_err());
+    }
+
+    #[test]
+    fn test_watermark_stops_at_gaps() {
+        let mut watermark = Watermark::default();
+        watermark.mark(101);
+        watermark.mark(103);
+        // Nothing stored: starts at the first accounted block
+        watermark.seed(None);
+        assert_eq!(watermark.block, Some(101));
+        assert!(watermark.needs_seed());
+
+        watermark.mark(102);
+        assert_eq!(watermark.block, Some(103));
+        assert!(!watermark.needs_seed());
+        watermark.mark(100);
+        assert_eq!(watermark.block, Some(103));
+    }
+
+    #[test]
+    fn test_watermark_catches_up_with_stored() {
+        // Another worker processed up to 150 before this one took the tenant
+        let mut watermark = Watermark::default();
+        watermark.mark(140);
+        watermark.mark(151);
+        watermark.mark(152);
+        watermark.seed(Some(150));
+        assert_eq!(watermark.block, Some(152));
+        assert!(watermark.ahead.is_empty());
+        assert!(watermark.moved);
+
+        // A stored watermark behind this worker's does not move it back
+        watermark.moved = false;
+        watermark.seed(Some(120));
+        assert_eq!(watermark.block, Some(152));
+        assert!(!watermark.moved);
+    }
+
+    #[test]
+    fn test_rewind_deserializes_from_either_field() {
+        let blocks: CheckpointRewind = serde_json::from_str(r#"{"blocks": 100}"#).unwrap();
+        assert_eq!(blocks, CheckpointRewind::Blocks(100));
+        let to_block: CheckpointRewind = serde_json::from_str(r#"{"to_block": 900}"#).unwrap();
+        assert_eq!(to_block, CheckpointRewind::ToBlock(900));
+    }
+}
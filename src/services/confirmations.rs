@@ -0,0 +1,154 @@
+//! Confirmation Depths
+//!
+//! Resolves how many confirmations each tenant requires per network, either
+//! the `tenant_networks.confirmation_blocks` override or the depth from the
+//! tenant's network configuration, and which match states fire the tenant's
+//! triggers (`tenant_networks.trigger_on_states`). The block watcher runs at
+//! the shallowest depth any tenant asks for; workers mark matches in blocks
+//! that are not yet deep enough for a tenant as provisional and finalize or
+//! orphan them later.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+use uuid::Uuid;
+
+use openzeppelin_monitor::models::Network;
+
+use crate::models::MatchState;
+use crate::services::db_outage::cached_or;
+
+/// How long loaded depths are reused before re-reading the database
+const DEPTHS_TTL: Duration = Duration::from_secs(60);
+
+/// Confirmation settings of a tenant on one network
+#[derive(Debug, Clone)]
+struct NetworkConfirmation {
+    /// Override of the network's depth, if any
+    depth: Option<u64>,
+    /// Match states that fire triggers
+    trigger_on: Vec<MatchState>,
+}
+
+/// Per-tenant confirmation depth lookup
+pub struct ConfirmationDepths {
+    db: Arc<PgPool>,
+    depths: DashMap<Uuid, (Instant, Arc<HashMap<String, NetworkConfirmation>>)>,
+}
+
+impl ConfirmationDepths {
+    /// Create a new lookup
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            depths: DashMap::new(),
+        }
+    }
+
+    /// Confirmations a tenant requires on a network
+    pub async fn required(&self, tenant_id: Uuid, network: &Network) -> Result<u64> {
+        Ok(self
+            .depths_for(tenant_id)
+            .await?
+            .get(&network.slug)
+            .and_then(|settings| settings.depth)
+            .unwrap_or(network.confirmation_blocks))
+    }
+
+    /// Whether a match in the given state fires the tenant's triggers on a network
+    pub async fn fires_on(
+        &self,
+        tenant_id: Uuid,
+        network_slug: &str,
+        state: MatchState,
+    ) -> Result<bool> {
+        Ok(match self.depths_for(tenant_id).await?.get(network_slug) {
+            Some(settings) => settings.trigger_on.contains(&state),
+            None => MatchState::default_trigger_states().contains(&state),
+        })
+    }
+
+    /// Shallowest depth any active tenant requires on a network, if any tenant uses it
+    pub async fn shallowest(db: &PgPool, network_slug: &str) -> Result<Option<u64>> {
+        let depth = sqlx::query_scalar::<_, Option<i64>>(
+            r#"
+            SELECT MIN(COALESCE(confirmation_blocks::BIGINT, (configuration->>'confirmation_blocks')::BIGINT))
+            FROM tenant_networks
+            WHERE network_id = $1 AND is_active = true
+            "#,
+        )
+        .bind(network_slug)
+        .fetch_one(db)
+        .await?;
+        Ok(depth.map(|depth| depth.max(0) as u64))
+    }
+
+    /// Drop cached depths so the next lookup re-reads them
+    pub fn invalidate(&self, tenant_id: Uuid) {
+        self.depths.remove(&tenant_id);
+    }
+
+    async fn depths_for(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Arc<HashMap<String, NetworkConfirmation>>> {
+        if let Some(entry) = self.depths.get(&tenant_id) {
+            let (loaded_at, depths) = entry.value();
+            if loaded_at.elapsed() < DEPTHS_TTL {
+                return Ok(depths.clone());
+            }
+        }
+
+        let rows = match sqlx::query_as::<_, (String, Option<i64>, Vec<String>)>(
+            r#"
+            SELECT network_id,
+                   COALESCE(confirmation_blocks::BIGINT, (configuration->>'confirmation_blocks')::BIGINT),
+                   trigger_on_states
+            FROM tenant_networks
+            WHERE tenant_id = $1 AND is_active = true
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                return cached_or(e, self.depths.get(&tenant_id).map(|entry| entry.1.clone()))
+            }
+        };
+
+        let depths = Arc::new(
+            rows.into_iter()
+                .map(|(slug, depth, states)| {
+                    let trigger_on = states
+                        .iter()
+                        .filter_map(|state| match state.parse() {
+                            Ok(state) => Some(state),
+                            Err(e) => {
+                                warn!(
+                                    "Ignoring trigger state of tenant {} on {}: {}",
+                                    tenant_id, slug, e
+                                );
+                                None
+                            }
+                        })
+                        .collect();
+                    let settings = NetworkConfirmation {
+                        depth: depth.map(|depth| depth.max(0) as u64),
+                        trigger_on,
+                    };
+                    (slug, settings)
+                })
+                .collect::<HashMap<_, _>>(),
+        );
+
+        self.depths
+            .insert(tenant_id, (Instant::now(), depths.clone()));
+        Ok(depths)
+    }
+}
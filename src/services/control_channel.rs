@@ -0,0 +1,170 @@
+//! Worker Control Channel
+//!
+//! Persistent channel from the coordinator to each worker over Redis pub/sub.
+//! Assignment changes, pause/resume commands and configuration invalidations
+//! reach workers as soon as they are published instead of on the next polling
+//! interval. Polling stays in place as a fallback for missed messages.
+
+use anyhow::Result;
+use futures::StreamExt;
+use redis::{AsyncCommands, Client as RedisClient};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::models::TenantShard;
+use crate::services::redis_keyspace::RedisKeyspace;
+
+/// Delay before re-subscribing after the pub/sub connection drops
+const RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Commands queued per worker before the listener applies them
+const COMMAND_BUFFER: usize = 64;
+
+/// Command pushed from the coordinator to workers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ControlCommand {
+    /// Replace the worker's tenants and tenant shards
+    AssignTenants {
+        tenant_ids: Vec<Uuid>,
+        #[serde(default)]
+        shards: Vec<TenantShard>,
+    },
+
+    /// Stop consuming block events until resumed
+    Pause,
+
+    /// Resume consuming block events
+    Resume,
+
+    /// Drop cached configuration for the given tenants; all assigned tenants if empty
+    InvalidateConfig {
+        #[serde(default)]
+        tenant_ids: Vec<Uuid>,
+    },
+
+    /// Stop consuming block events for good; sent once the worker was drained
+    Stop,
+}
+
+/// Publishes and consumes worker control commands
+pub struct ControlChannel {
+    redis: Arc<RedisClient>,
+    keyspace: RedisKeyspace,
+}
+
+impl ControlChannel {
+    /// Create a control channel on the given Redis client
+    pub fn new(redis: Arc<RedisClient>, keyspace: RedisKeyspace) -> Self {
+        Self { redis, keyspace }
+    }
+
+    /// Send a command to a single worker, returning whether it was listening
+    pub async fn send(&self, worker_id: &str, command: &ControlCommand) -> Result<bool> {
+        let receivers = self
+            .publish(&self.worker_channel(worker_id), command)
+            .await?;
+        if receivers == 0 {
+            warn!(
+                "Worker {} is not listening for control commands, it will catch up by polling",
+                worker_id
+            );
+        }
+        Ok(receivers > 0)
+    }
+
+    /// Send a command to every worker, returning how many received it
+    pub async fn broadcast(&self, command: &ControlCommand) -> Result<i64> {
+        self.publish(&self.broadcast_channel(), command).await
+    }
+
+    async fn publish(&self, channel: &str, command: &ControlCommand) -> Result<i64> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let payload = serde_json::to_string(command)?;
+        let receivers: i64 = conn.publish(channel, payload).await?;
+
+        debug!(
+            "Published control command {:?} on {} to {} subscribers",
+            command, channel, receivers
+        );
+        Ok(receivers)
+    }
+
+    /// Subscribe a worker to its own and broadcast commands.
+    ///
+    /// The background task re-subscribes if the Redis connection drops and
+    /// stops once the returned receiver is dropped.
+    pub fn subscribe(&self, worker_id: &str) -> mpsc::Receiver<ControlCommand> {
+        let (sender, receiver) = mpsc::channel(COMMAND_BUFFER);
+        let redis = self.redis.clone();
+        let channels = vec![self.worker_channel(worker_id), self.broadcast_channel()];
+        let worker_id = worker_id.to_string();
+
+        tokio::spawn(async move {
+            while !sender.is_closed() {
+                if let Err(e) = listen(&redis, &channels, &sender).await {
+                    error!(
+                        "Control channel subscription for {} failed: {}",
+                        worker_id, e
+                    );
+                }
+                if sender.is_closed() {
+                    break;
+                }
+                warn!(
+                    "Control channel subscription for {} ended, retrying in {:?}",
+                    worker_id, RESUBSCRIBE_DELAY
+                );
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        });
+
+        receiver
+    }
+
+    fn worker_channel(&self, worker_id: &str) -> String {
+        self.keyspace.key(&format!("control:worker:{}", worker_id))
+    }
+
+    fn broadcast_channel(&self) -> String {
+        self.keyspace.key("control:all")
+    }
+}
+
+/// Forward control commands until the connection closes or the receiver is dropped
+async fn listen(
+    redis: &RedisClient,
+    channels: &[String],
+    sender: &mpsc::Sender<ControlCommand>,
+) -> Result<()> {
+    let mut pubsub = redis.get_async_pubsub().await?;
+    for channel in channels {
+        pubsub.subscribe(channel).await?;
+    }
+    info!("Subscribed to control commands on {:?}", channels);
+
+    let mut messages = pubsub.on_message();
+    while let Some(msg) = messages.next().await {
+        let payload: String = match msg.get_payload() {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Ignoring unreadable control command: {}", e);
+                continue;
+            }
+        };
+
+        match serde_json::from_str::<ControlCommand>(&payload) {
+            Ok(command) => {
+                if sender.send(command).await.is_err() {
+                    return Ok(());
+                }
+            }
+            Err(e) => warn!("Ignoring malformed control command: {}", e),
+        }
+    }
+
+    Ok(())
+}
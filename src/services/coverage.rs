@@ -0,0 +1,375 @@
+//! Monitoring Coverage
+//!
+//! Reports which of a tenant's networks, monitors and triggers are actually
+//! in effect. Workers skip configurations they cannot load with no more than
+//! a log line: an invalid network configuration fails every network of the
+//! tenant, an invalid monitor is skipped, and a trigger naming neither a
+//! custom channel nor an active trigger never notifies. The report loads the
+//! tenant's rows the way workers do and says why each one is not in effect.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use openzeppelin_monitor::models::{Monitor, Network, Trigger};
+use serde_json::Value as JsonValue;
+use sqlx::PgPool;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::{
+    CoverageReport, DeliveryRoute, MonitorCoverage, NetworkCoverage, TriggerCoverage,
+};
+use crate::services::{NotificationChannels, SharedBlockWatcher};
+
+/// Row of the `tenant_networks` table
+#[derive(sqlx::FromRow)]
+struct NetworkRow {
+    network_id: String,
+    is_active: bool,
+    configuration: JsonValue,
+}
+
+/// Row of the `tenant_monitors` table with the slug of its network
+#[derive(sqlx::FromRow)]
+struct MonitorRow {
+    name: String,
+    network_slug: String,
+    is_active: bool,
+    error_message: Option<String>,
+    configuration: JsonValue,
+}
+
+/// Row of the `tenant_triggers` table
+#[derive(sqlx::FromRow)]
+struct TriggerRow {
+    name: String,
+    is_active: bool,
+    configuration: JsonValue,
+}
+
+/// Builds monitoring coverage reports
+pub struct CoverageReporter {
+    db: Arc<PgPool>,
+    block_watcher: Arc<SharedBlockWatcher>,
+    notification_channels: Arc<NotificationChannels>,
+}
+
+impl CoverageReporter {
+    /// Create a reporter checking watched networks against `block_watcher`
+    pub fn new(db: Arc<PgPool>, block_watcher: Arc<SharedBlockWatcher>) -> Self {
+        Self {
+            db,
+            block_watcher,
+            notification_channels: Arc::new(NotificationChannels::new()),
+        }
+    }
+
+    /// Resolve triggers against the custom channels registered with the workers
+    pub fn with_notification_channels(mut self, channels: Arc<NotificationChannels>) -> Self {
+        self.notification_channels = channels;
+        self
+    }
+
+    /// Coverage of a tenant's configuration; `worker_id` is left for the caller
+    pub async fn report(&self, tenant_id: Uuid) -> Result<CoverageReport> {
+        let networks = sqlx::query_as::<_, NetworkRow>(
+            r#"
+            SELECT network_id, is_active, configuration
+            FROM tenant_networks
+            WHERE tenant_id = $1
+            ORDER BY network_id
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await?;
+
+        let monitors = sqlx::query_as::<_, MonitorRow>(
+            r#"
+            SELECT m.name, n.network_id AS network_slug, m.is_active,
+                   m.error_message, m.configuration
+            FROM tenant_monitors m
+            JOIN tenant_networks n ON m.network_id = n.id
+            WHERE m.tenant_id = $1
+            ORDER BY m.name
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await?;
+
+        let triggers = sqlx::query_as::<_, TriggerRow>(
+            r#"
+            SELECT name, is_active, configuration
+            FROM tenant_triggers
+            WHERE tenant_id = $1
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await?;
+
+        // Watched here, or by a watcher in another process keeping the cache fresh
+        let mut watched: HashSet<String> = self
+            .block_watcher
+            .network_statuses()
+            .await
+            .into_iter()
+            .filter(|status| status.watching)
+            .map(|status| status.network_slug)
+            .collect();
+        for network in &networks {
+            if self
+                .block_watcher
+                .cached_latest_block(&network.network_id)
+                .await
+                .is_some()
+            {
+                watched.insert(network.network_id.clone());
+            }
+        }
+
+        Ok(build_report(
+            tenant_id,
+            networks,
+            monitors,
+            triggers,
+            &watched,
+            |name| self.notification_channels.get(name).is_some(),
+        ))
+    }
+}
+
+/// Report on loaded rows, mirroring how the tenant-aware repositories load them
+fn build_report(
+    tenant_id: Uuid,
+    networks: Vec<NetworkRow>,
+    monitors: Vec<MonitorRow>,
+    triggers: Vec<TriggerRow>,
+    watched: &HashSet<String>,
+    has_custom_channel: impl Fn(&str) -> bool,
+) -> CoverageReport {
+    // Networks load all or nothing: one invalid configuration fails them all
+    let network_errors: Vec<(String, String)> = networks
+        .iter()
+        .filter(|network| network.is_active)
+        .filter_map(|network| {
+            deserialize::<Network>(&network.configuration, "network")
+                .err()
+                .map(|e| (network.network_id.clone(), e))
+        })
+        .collect();
+    let networks: Vec<NetworkCoverage> = networks
+        .into_iter()
+        .map(|network| {
+            let slug = network.network_id;
+            let error = if !network.is_active {
+                None
+            } else if let Some((_, e)) = network_errors.iter().find(|(s, _)| *s == slug) {
+                Some(e.clone())
+            } else if let Some((invalid, _)) = network_errors.first() {
+                Some(format!(
+                    "Not loaded: network {} has an invalid configuration",
+                    invalid
+                ))
+            } else if !watched.contains(&slug) {
+                Some("No block watcher is polling the network".to_string())
+            } else {
+                None
+            };
+            NetworkCoverage {
+                loaded: network.is_active && network_errors.is_empty(),
+                watched: watched.contains(&slug),
+                network_slug: slug,
+                is_active: network.is_active,
+                error,
+            }
+        })
+        .collect();
+    let loaded_networks: HashSet<&str> = networks
+        .iter()
+        .filter(|network| network.loaded)
+        .map(|network| network.network_slug.as_str())
+        .collect();
+
+    let mut loaded_monitors = Vec::new();
+    let monitors: Vec<MonitorCoverage> = monitors
+        .into_iter()
+        .map(|row| {
+            let error = if !row.is_active {
+                row.error_message.map(|e| format!("Deactivated: {}", e))
+            } else {
+                match deserialize::<Monitor>(&row.configuration, "monitor") {
+                    Err(e) => Some(e),
+                    Ok(_) if !loaded_networks.contains(row.network_slug.as_str()) => {
+                        Some(format!("Network {} is not loaded", row.network_slug))
+                    }
+                    Ok(monitor) => {
+                        loaded_monitors.push((row.name.clone(), monitor.triggers));
+                        None
+                    }
+                }
+            };
+            MonitorCoverage {
+                loaded: row.is_active && error.is_none(),
+                name: row.name,
+                network_slug: row.network_slug,
+                is_active: row.is_active,
+                error,
+            }
+        })
+        .collect();
+
+    // Triggers also load all or nothing
+    let trigger_states: HashMap<String, Result<(), String>> = triggers
+        .iter()
+        .map(|row| {
+            let state = if row.is_active {
+                deserialize::<Trigger>(&row.configuration, "trigger").map(|_| ())
+            } else {
+                Err(format!("Trigger {} is inactive", row.name))
+            };
+            (row.name.clone(), state)
+        })
+        .collect();
+    let invalid_trigger = triggers.iter().find_map(|row| {
+        matches!(trigger_states.get(&row.name), Some(Err(_)) if row.is_active)
+            .then(|| row.name.clone())
+    });
+    let triggers: Vec<TriggerCoverage> = loaded_monitors
+        .into_iter()
+        .flat_map(|(monitor_name, trigger_names)| {
+            trigger_names
+                .into_iter()
+                .map(move |trigger_name| (monitor_name.clone(), trigger_name))
+        })
+        .map(|(monitor_name, trigger_name)| {
+            let resolved = if has_custom_channel(&trigger_name) {
+                Ok(DeliveryRoute::Custom)
+            } else {
+                match (trigger_states.get(&trigger_name), &invalid_trigger) {
+                    (None, _) => Err(format!("No trigger named {}", trigger_name)),
+                    (Some(Err(e)), _) => Err(e.clone()),
+                    (Some(Ok(())), Some(invalid)) => Err(format!(
+                        "Not loaded: trigger {} has an invalid configuration",
+                        invalid
+                    )),
+                    (Some(Ok(())), None) => Ok(DeliveryRoute::Upstream),
+                }
+            };
+            TriggerCoverage {
+                monitor_name,
+                trigger_name,
+                route: resolved.as_ref().ok().copied(),
+                error: resolved.err(),
+            }
+        })
+        .collect();
+
+    let issues = networks.iter().filter(|n| n.error.is_some()).count()
+        + monitors.iter().filter(|m| m.error.is_some()).count()
+        + triggers.iter().filter(|t| t.error.is_some()).count();
+
+    CoverageReport {
+        tenant_id,
+        worker_id: None,
+        networks,
+        monitors,
+        triggers,
+        issues,
+        generated_at: Utc::now(),
+    }
+}
+
+/// Deserialize a configuration as the repositories do, with the error as text
+fn deserialize<T: serde::de::DeserializeOwned>(
+    configuration: &JsonValue,
+    kind: &str,
+) -> Result<T, String> {
+    serde_json::from_value(configuration.clone())
+        .with_context(|| format!("Failed to deserialize {} configuration", kind))
+        .map_err(|e| format!("{:#}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn network(slug: &str, is_active: bool) -> NetworkRow {
+        NetworkRow {
+            network_id: slug.to_string(),
+            is_active,
+            configuration: json!({}),
+        }
+    }
+
+    fn monitor(name: &str, network_slug: &str, is_active: bool) -> MonitorRow {
+        MonitorRow {
+            name: name.to_string(),
+            network_slug: network_slug.to_string(),
+            is_active,
+            error_message: None,
+            configuration: json!({}),
+        }
+    }
+
+    #[test]
+    fn test_invalid_network_fails_every_network() {
+        let watched = HashSet::from(["ethereum_mainnet".to_string()]);
+        let report = build_report(
+            Uuid::new_v4(),
+            vec![network("ethereum_mainnet", true), network("sepolia", false)],
+            vec![],
+            vec![],
+            &watched,
+            |_| false,
+        );
+
+        let mainnet = &report.networks[0];
+        assert!(!mainnet.loaded);
+        assert!(mainnet.watched);
+        assert!(mainnet
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("network configuration"));
+
+        // Inactive networks are not expected to be loaded
+        assert!(!report.networks[1].loaded);
+        assert!(report.networks[1].error.is_none());
+        assert_eq!(report.issues, 1);
+    }
+
+    #[test]
+    fn test_monitor_errors() {
+        let mut deactivated = monitor("transfers", "ethereum_mainnet", false);
+        deactivated.error_message = Some("Filter failed".to_string());
+        let report = build_report(
+            Uuid::new_v4(),
+            vec![],
+            vec![
+                monitor("approvals", "ethereum_mainnet", true),
+                monitor("disabled", "ethereum_mainnet", false),
+                deactivated,
+            ],
+            vec![],
+            &HashSet::new(),
+            |_| false,
+        );
+
+        assert!(report.monitors.iter().all(|m| !m.loaded));
+        assert!(report.monitors[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("monitor configuration"));
+        assert!(report.monitors[1].error.is_none());
+        assert_eq!(
+            report.monitors[2].error.as_deref(),
+            Some("Deactivated: Filter failed")
+        );
+        assert!(report.triggers.is_empty());
+        assert_eq!(report.issues, 2);
+    }
+}
@@ -0,0 +1,318 @@
+//! Database Outage
+//!
+//! Degraded mode of a worker while Postgres is unreachable. The worker probes
+//! the database every `db_probe_interval`; while it is down, tenants keep
+//! being filtered with the monitors, networks and triggers last loaded for
+//! them, and the pause, trial, confirmation, quiet hours, severity and RPC cap
+//! lookups fall back to their cached values. Match records and match state
+//! changes are queued in memory, at most `max_queued_writes` of them with the
+//! oldest dropped first, and written in order once a probe succeeds again.
+//! Block checkpoints and RPC usage are already kept until their next flush
+//! succeeds, and tenant metrics are republished every interval, so neither
+//! is queued here.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
+
+use crate::models::MatchState;
+use crate::services::match_store::MatchStore;
+use crate::services::metrics;
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+
+/// Whether an error means the database could not be reached at all, as
+/// opposed to a query the database rejected
+pub fn is_unreachable(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<sqlx::Error>(),
+            Some(
+                sqlx::Error::Io(_)
+                    | sqlx::Error::Tls(_)
+                    | sqlx::Error::PoolTimedOut
+                    | sqlx::Error::PoolClosed
+                    | sqlx::Error::WorkerCrashed
+            )
+        )
+    })
+}
+
+/// The cached value of a lookup whose reload failed because the database is
+/// unreachable. Other errors, and outages with nothing cached, are returned.
+pub fn cached_or<T>(error: impl Into<anyhow::Error>, cached: Option<T>) -> Result<T> {
+    let error = error.into();
+    match cached {
+        Some(value) if is_unreachable(&error) => {
+            debug!("Database unreachable, using cached value: {}", error);
+            Ok(value)
+        }
+        _ => Err(error),
+    }
+}
+
+/// Write held back until the database is reachable again
+#[derive(Debug, Clone)]
+enum QueuedWrite {
+    RecordMatch {
+        id: Uuid,
+        tenant_match: Box<TenantMonitorMatch>,
+        network_slug: String,
+        block_number: Option<u64>,
+        block_hash: Option<String>,
+        seen_at: DateTime<Utc>,
+    },
+    SetMatchState {
+        id: Uuid,
+        state: MatchState,
+    },
+}
+
+impl QueuedWrite {
+    async fn apply(&self, match_store: &MatchStore) -> Result<()> {
+        match self {
+            QueuedWrite::RecordMatch {
+                id,
+                tenant_match,
+                network_slug,
+                block_number,
+                block_hash,
+                seen_at,
+            } => {
+                match_store
+                    .insert(
+                        *id,
+                        tenant_match,
+                        network_slug,
+                        *block_number,
+                        block_hash.as_deref(),
+                        *seen_at,
+                    )
+                    .await
+            }
+            QueuedWrite::SetMatchState { id, state } => match_store.set_state(*id, *state).await,
+        }
+    }
+}
+
+/// Database reachability of one worker and the writes it holds back
+pub struct DatabaseOutage {
+    worker_id: String,
+    db: Arc<PgPool>,
+    match_store: Arc<MatchStore>,
+    /// When the database was found unreachable; None while it is reachable
+    degraded_since: Mutex<Option<Instant>>,
+    queue: Mutex<VecDeque<QueuedWrite>>,
+    max_queued_writes: usize,
+}
+
+impl DatabaseOutage {
+    /// Create a tracker holding back at most `max_queued_writes` writes
+    pub fn new(worker_id: &str, db: Arc<PgPool>, max_queued_writes: usize) -> Self {
+        Self {
+            worker_id: worker_id.to_string(),
+            match_store: Arc::new(MatchStore::new(db.clone())),
+            db,
+            degraded_since: Mutex::new(None),
+            queue: Mutex::new(VecDeque::new()),
+            max_queued_writes,
+        }
+    }
+
+    /// Whether the database was unreachable at the last attempt to use it
+    pub fn is_degraded(&self) -> bool {
+        self.degraded_since.lock().unwrap().is_some()
+    }
+
+    /// Enter degraded mode after failing to reach the database
+    pub fn enter(&self, error: &anyhow::Error) {
+        let mut since = self.degraded_since.lock().unwrap();
+        if since.is_none() {
+            warn!(
+                "Worker {} lost its database connection, filtering with cached configurations and queueing writes: {}",
+                self.worker_id, error
+            );
+            *since = Some(Instant::now());
+            metrics::WORKER_DATABASE_DEGRADED
+                .with_label_values(&[&self.worker_id])
+                .set(1);
+        }
+    }
+
+    /// Record a newly seen match, or queue it while the database is
+    /// unreachable, returning the id it is recorded under either way
+    pub async fn record_match(
+        &self,
+        tenant_match: &TenantMonitorMatch,
+        network_slug: &str,
+        block_number: Option<u64>,
+        block_hash: Option<&str>,
+    ) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+        self.write(QueuedWrite::RecordMatch {
+            id,
+            tenant_match: Box::new(tenant_match.clone()),
+            network_slug: network_slug.to_string(),
+            block_number,
+            block_hash: block_hash.map(str::to_string),
+            seen_at: Utc::now(),
+        })
+        .await?;
+        Ok(id)
+    }
+
+    /// Move a recorded match to a new state, or queue the change while the
+    /// database is unreachable
+    pub async fn set_match_state(&self, id: Uuid, state: MatchState) -> Result<()> {
+        self.write(QueuedWrite::SetMatchState { id, state }).await
+    }
+
+    /// Writes waiting for the database
+    pub fn queued(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    /// Check the database, leaving degraded mode and writing the queued
+    /// writes in order if it is reachable.
+    ///
+    /// Writes failing for another reason than an outage are logged and
+    /// dropped, so one bad write cannot hold back the rest.
+    pub async fn probe_and_replay(&self) {
+        if let Err(e) = sqlx::query("SELECT 1").execute(&*self.db).await {
+            let e = anyhow::Error::from(e);
+            if is_unreachable(&e) {
+                self.enter(&e);
+            } else {
+                warn!("Database probe of worker {} failed: {}", self.worker_id, e);
+            }
+            return;
+        }
+
+        let mut replayed = 0;
+        while let Some(write) = self.pop() {
+            if let Err(e) = write.apply(&self.match_store).await {
+                if is_unreachable(&e) {
+                    self.queue.lock().unwrap().push_front(write);
+                    self.enter(&e);
+                    self.observe_queue();
+                    return;
+                }
+                warn!(
+                    "Dropping queued write of worker {}: {:?}: {}",
+                    self.worker_id, write, e
+                );
+            } else {
+                replayed += 1;
+            }
+        }
+        self.observe_queue();
+
+        if let Some(since) = self.degraded_since.lock().unwrap().take() {
+            info!(
+                "Worker {} reconnected to the database after {:?}, replayed {} queued writes",
+                self.worker_id,
+                since.elapsed(),
+                replayed
+            );
+            metrics::WORKER_DATABASE_DEGRADED
+                .with_label_values(&[&self.worker_id])
+                .set(0);
+        }
+    }
+
+    /// Probe the database every `interval` until aborted
+    pub fn start(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let outage = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                outage.probe_and_replay().await;
+            }
+        })
+    }
+
+    /// Write now, or queue the write while the database is unreachable
+    async fn write(&self, write: QueuedWrite) -> Result<()> {
+        if self.is_degraded() {
+            self.push(write);
+            return Ok(());
+        }
+        match write.apply(&self.match_store).await {
+            Err(e) if is_unreachable(&e) => {
+                self.enter(&e);
+                self.push(write);
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Queue a write, dropping the oldest one when the queue is full
+    fn push(&self, write: QueuedWrite) {
+        let dropped = {
+            let mut queue = self.queue.lock().unwrap();
+            queue.push_back(write);
+            let excess = queue.len().saturating_sub(self.max_queued_writes);
+            queue.drain(..excess).count()
+        };
+        if dropped > 0 {
+            warn!(
+                "Worker {} dropped {} queued writes over max_queued_writes",
+                self.worker_id, dropped
+            );
+            metrics::QUEUED_WRITES_DROPPED
+                .with_label_values(&[&self.worker_id])
+                .inc_by(dropped as u64);
+        }
+        self.observe_queue();
+    }
+
+    fn pop(&self) -> Option<QueuedWrite> {
+        self.queue.lock().unwrap().pop_front()
+    }
+
+    fn observe_queue(&self) {
+        metrics::QUEUED_WRITES
+            .with_label_values(&[&self.worker_id])
+            .set(self.queued() as i64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_queue_drops_oldest_writes_when_full() {
+        let db = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let outage = DatabaseOutage::new("worker-test", Arc::new(db), 2);
+        outage.enter(&anyhow::Error::from(sqlx::Error::PoolTimedOut));
+        assert!(outage.is_degraded());
+
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for id in &ids {
+            outage
+                .set_match_state(*id, MatchState::Finalized)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(outage.queued(), 2);
+        let QueuedWrite::SetMatchState { id, .. } = outage.pop().unwrap() else {
+            panic!("expected a match state change");
+        };
+        assert_eq!(id, ids[1]);
+    }
+
+    #[test]
+    fn test_only_connection_failures_fall_back_to_cache() {
+        assert_eq!(cached_or(sqlx::Error::PoolTimedOut, Some(3)).unwrap(), 3);
+        assert!(cached_or(sqlx::Error::PoolTimedOut, None::<u32>).is_err());
+        assert!(cached_or(sqlx::Error::RowNotFound, Some(3)).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! Dead Letter Store
+//!
+//! Keeps notifications whose trigger delivery failed in `trigger_dead_letters`
+//! so they can be inspected and redriven. Requeued notifications are claimed
+//! and redelivered by the worker owning the tenant.
+
+use anyhow::Result;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::{DeadLetter, DeadLetterStatus};
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+
+/// Columns selected into [`DeadLetter`]
+const COLUMNS: &str = "id, tenant_id, monitor_name, trigger_names, match_state, match_data, \
+                       error, status, attempts, failed_at, requeued_at, delivered_at";
+
+/// Failed trigger deliveries awaiting inspection or redelivery
+pub struct DeadLetterStore {
+    db: Arc<PgPool>,
+}
+
+impl DeadLetterStore {
+    /// Create a new dead letter store
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// Record a match whose delivery to the given triggers failed
+    pub async fn record(
+        &self,
+        tenant_match: &TenantMonitorMatch,
+        trigger_names: &[String],
+        error: &str,
+    ) -> Result<Uuid> {
+        let id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO trigger_dead_letters
+                (tenant_id, monitor_name, trigger_names, match_state, match_data, error)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            RETURNING id
+            "#,
+        )
+        .bind(tenant_match.tenant_id)
+        .bind(&tenant_match.monitor_name)
+        .bind(trigger_names)
+        .bind(tenant_match.state.as_str())
+        .bind(serde_json::to_value(&tenant_match.monitor_match)?)
+        .bind(error)
+        .fetch_one(&*self.db)
+        .await?;
+        Ok(id)
+    }
+
+    /// A tenant's dead letters, newest first, optionally only those in one status
+    pub async fn list(
+        &self,
+        tenant_id: Uuid,
+        status: Option<DeadLetterStatus>,
+        limit: i64,
+    ) -> Result<Vec<DeadLetter>> {
+        Ok(sqlx::query_as::<_, DeadLetter>(&format!(
+            r#"
+            SELECT {COLUMNS}
+            FROM trigger_dead_letters
+            WHERE tenant_id = $1 AND ($2::TEXT IS NULL OR status = $2)
+            ORDER BY failed_at DESC
+            LIMIT $3
+            "#
+        ))
+        .bind(tenant_id)
+        .bind(status.map(|status| status.as_str()))
+        .bind(limit)
+        .fetch_all(&*self.db)
+        .await?)
+    }
+
+    /// Get a dead letter by id
+    pub async fn get(&self, id: Uuid) -> Result<Option<DeadLetter>> {
+        Ok(sqlx::query_as::<_, DeadLetter>(&format!(
+            "SELECT {COLUMNS} FROM trigger_dead_letters WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&*self.db)
+        .await?)
+    }
+
+    /// Queue a failed dead letter for redelivery.
+    ///
+    /// Returns None if it does not exist or is not in the failed state.
+    pub async fn requeue(&self, id: Uuid) -> Result<Option<DeadLetter>> {
+        Ok(sqlx::query_as::<_, DeadLetter>(&format!(
+            r#"
+            UPDATE trigger_dead_letters
+            SET status = 'requeued', requeued_at = now()
+            WHERE id = $1 AND status = 'failed'
+            RETURNING {COLUMNS}
+            "#
+        ))
+        .bind(id)
+        .fetch_optional(&*self.db)
+        .await?)
+    }
+
+    /// Claim requeued dead letters of the given tenants for redelivery.
+    ///
+    /// Claimed letters move to retrying, so each requeue is redelivered once.
+    pub async fn claim_requeued(&self, tenant_ids: &[Uuid]) -> Result<Vec<DeadLetter>> {
+        if tenant_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(sqlx::query_as::<_, DeadLetter>(&format!(
+            r#"
+            UPDATE trigger_dead_letters
+            SET status = 'retrying'
+            WHERE tenant_id = ANY($1) AND status = 'requeued'
+            RETURNING {COLUMNS}
+            "#
+        ))
+        .bind(tenant_ids)
+        .fetch_all(&*self.db)
+        .await?)
+    }
+
+    /// Mark a redelivered dead letter as delivered
+    pub async fn mark_delivered(&self, id: Uuid) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE trigger_dead_letters
+            SET status = 'delivered', delivered_at = now(), attempts = attempts + 1
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .execute(&*self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Return a dead letter whose redelivery failed to the failed state
+    pub async fn mark_failed(&self, id: Uuid, trigger_names: &[String], error: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE trigger_dead_letters
+            SET status = 'failed', trigger_names = $2, error = $3, attempts = attempts + 1
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(trigger_names)
+        .bind(error)
+        .execute(&*self.db)
+        .await?;
+        Ok(())
+    }
+}
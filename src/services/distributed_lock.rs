@@ -0,0 +1,240 @@
+//! Distributed Lock
+//!
+//! Redis lock shared by features that need one holder across processes: the
+//! block watcher lease, rebalance leadership and single-flight RPC fetches on
+//! block cache misses. Every acquisition gets a fencing token from a counter
+//! that only grows, so writes guarded by the lock can reject a holder that
+//! lost it. Held locks are renewed in the background until released; a
+//! holder that stops renewing loses the lock once the TTL expires.
+
+use anyhow::Result;
+use redis::{Client as RedisClient, Script};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+use crate::services::redis_keyspace::RedisKeyspace;
+
+/// Take the lock if free (or already ours) and return its fencing token; 0 if held by someone else
+const ACQUIRE_SCRIPT: &str = r##"
+local prefix = ARGV[1] .. "#"
+local current = redis.call("GET", KEYS[1])
+if not current then
+    local token = redis.call("INCR", KEYS[2])
+    redis.call("SET", KEYS[1], prefix .. token, "PX", ARGV[2])
+    return token
+end
+if string.sub(current, 1, #prefix) == prefix then
+    redis.call("PEXPIRE", KEYS[1], ARGV[2])
+    return tonumber(string.sub(current, #prefix + 1))
+end
+return 0
+"##;
+
+/// Refresh the TTL only while still holding the lock
+const RENEW_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("PEXPIRE", KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Delete the lock only while still holding it
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+/// Named lock in Redis, held by at most one owner at a time
+#[derive(Clone)]
+pub struct DistributedLock {
+    redis: Arc<RedisClient>,
+    key: String,
+    owner: String,
+    ttl: Duration,
+}
+
+impl DistributedLock {
+    /// Create a lock stored under `name` in the keyspace, acquired as `owner`
+    pub fn new(
+        redis: Arc<RedisClient>,
+        keyspace: &RedisKeyspace,
+        name: &str,
+        owner: impl Into<String>,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            redis,
+            key: keyspace.key(name),
+            owner: owner.into(),
+            ttl,
+        }
+    }
+
+    /// Identifier of this lock's owner
+    pub fn owner(&self) -> &str {
+        &self.owner
+    }
+
+    /// How long the lock survives without renewal
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Redis key holding the lock, for scripts fencing writes with [`LockGuard::value`]
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// Take the lock if it is free, without waiting
+    pub async fn try_acquire(&self) -> Result<Option<LockGuard>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let token: u64 = Script::new(ACQUIRE_SCRIPT)
+            .key(&self.key)
+            .key(self.fence_key())
+            .arg(&self.owner)
+            .arg(self.ttl.as_millis() as u64)
+            .invoke_async(&mut conn)
+            .await?;
+        if token == 0 {
+            return Ok(None);
+        }
+
+        debug!(
+            "{} acquired lock {} with token {}",
+            self.owner, self.key, token
+        );
+        Ok(Some(LockGuard::start(self.clone(), token)))
+    }
+
+    /// Wait until the lock is taken, polling every quarter TTL.
+    ///
+    /// A holder that releases the lock hands it over on the next poll; one
+    /// that crashed loses it once the TTL expires.
+    pub async fn acquire(&self) -> Result<LockGuard> {
+        loop {
+            if let Some(guard) = self.try_acquire().await? {
+                return Ok(guard);
+            }
+            debug!(
+                "{} waiting for lock {} held by {}",
+                self.owner,
+                self.key,
+                self.holder().await?.as_deref().unwrap_or("unknown")
+            );
+            tokio::time::sleep(self.ttl / 4).await;
+        }
+    }
+
+    /// Current holder of the lock, as `owner#token`
+    pub async fn holder(&self) -> Result<Option<String>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        Ok(redis::cmd("GET")
+            .arg(&self.key)
+            .query_async(&mut conn)
+            .await?)
+    }
+
+    fn fence_key(&self) -> String {
+        format!("{}:fence", self.key)
+    }
+}
+
+/// Held lock, renewed in the background until released or dropped
+pub struct LockGuard {
+    lock: DistributedLock,
+    token: u64,
+    value: String,
+    lost: watch::Receiver<bool>,
+    renewal: tokio::task::JoinHandle<()>,
+}
+
+impl LockGuard {
+    fn start(lock: DistributedLock, token: u64) -> Self {
+        let value = format!("{}#{}", lock.owner, token);
+        let (lost_tx, lost) = watch::channel(false);
+
+        let renewal = {
+            let lock = lock.clone();
+            let value = value.clone();
+            tokio::spawn(async move {
+                let interval = lock.ttl / 3;
+                loop {
+                    tokio::time::sleep(interval).await;
+                    match renew(&lock, &value).await {
+                        Ok(true) => {}
+                        Ok(false) => {
+                            warn!("{} lost lock {}", lock.owner, lock.key);
+                            lost_tx.send_replace(true);
+                            break;
+                        }
+                        Err(e) => warn!("Failed to renew lock {}: {}", lock.key, e),
+                    }
+                }
+            })
+        };
+
+        Self {
+            lock,
+            token,
+            value,
+            lost,
+            renewal,
+        }
+    }
+
+    /// Fencing token of this acquisition; later acquisitions get larger tokens
+    pub fn token(&self) -> u64 {
+        self.token
+    }
+
+    /// Value stored under the lock key while this guard holds it
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Whether renewal has not yet found the lock taken over
+    pub fn is_held(&self) -> bool {
+        !*self.lost.borrow()
+    }
+
+    /// Signal set once renewal finds the lock taken over.
+    ///
+    /// The sender closes without setting it when the guard is released.
+    pub fn lost_signal(&self) -> watch::Receiver<bool> {
+        self.lost.clone()
+    }
+
+    /// Stop renewing and delete the lock, returning whether it was still held
+    pub async fn release(self) -> Result<bool> {
+        self.renewal.abort();
+        let mut conn = self.lock.redis.get_multiplexed_async_connection().await?;
+        let released: i64 = Script::new(RELEASE_SCRIPT)
+            .key(&self.lock.key)
+            .arg(&self.value)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(released == 1)
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        // The lock expires after its TTL once renewal stops
+        self.renewal.abort();
+    }
+}
+
+async fn renew(lock: &DistributedLock, value: &str) -> Result<bool> {
+    let mut conn = lock.redis.get_multiplexed_async_connection().await?;
+    let renewed: i64 = Script::new(RENEW_SCRIPT)
+        .key(&lock.key)
+        .arg(value)
+        .arg(lock.ttl.as_millis() as u64)
+        .invoke_async(&mut conn)
+        .await?;
+    Ok(renewed == 1)
+}
@@ -0,0 +1,321 @@
+//! Endpoint Health
+//!
+//! Health of the Horizon and Soroban RPC endpoints of Stellar networks.
+//! Public Stellar endpoints are frequently rate-limited, so a network may
+//! list several of each in its `rpc_urls` (`type: horizon` and `type: rpc`).
+//! Every endpoint the client pool hands to a Stellar client is probed each
+//! `health_check_interval`, Soroban RPC with `getHealth` and Horizon with a
+//! request for its root, and an endpoint failing `failure_threshold` checks
+//! in a row is left out of new clients until a check passes again. When every
+//! endpoint of a kind is down all of them are kept, so clients still have
+//! something to try.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use openzeppelin_monitor::models::{BlockChainType, Network, RpcUrl};
+
+use crate::services::metrics;
+
+/// How Stellar endpoints are health-checked
+#[derive(Debug, Clone)]
+pub struct EndpointHealthConfig {
+    pub health_check_interval: Duration,
+    /// Timeout of one probe
+    pub timeout: Duration,
+    /// Failed probes in a row before an endpoint is left out of clients
+    pub failure_threshold: u32,
+}
+
+impl Default for EndpointHealthConfig {
+    fn default() -> Self {
+        Self {
+            health_check_interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(5),
+            failure_threshold: 2,
+        }
+    }
+}
+
+/// Kind of Stellar endpoint, from the `type` of the RPC URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EndpointKind {
+    Horizon,
+    SorobanRpc,
+}
+
+impl EndpointKind {
+    fn of(rpc_url: &RpcUrl) -> Option<Self> {
+        match rpc_url.type_.as_str() {
+            "horizon" => Some(Self::Horizon),
+            "rpc" => Some(Self::SorobanRpc),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Horizon => "horizon",
+            Self::SorobanRpc => "soroban_rpc",
+        }
+    }
+}
+
+/// Probe state of one endpoint
+#[derive(Debug, Clone)]
+struct EndpointState {
+    network_slug: String,
+    kind: EndpointKind,
+    consecutive_failures: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetHealthResponse {
+    result: Option<GetHealthResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetHealthResult {
+    status: String,
+}
+
+/// Health of the Stellar endpoints handed out by the client pool
+pub struct EndpointHealth {
+    config: EndpointHealthConfig,
+    client: reqwest::Client,
+    /// Probe state by endpoint URL
+    endpoints: DashMap<String, EndpointState>,
+}
+
+impl EndpointHealth {
+    /// Create a health tracker probing with the given configuration
+    pub fn new(config: EndpointHealthConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .build()
+            .unwrap_or_default();
+        Self {
+            config,
+            client,
+            endpoints: DashMap::new(),
+        }
+    }
+
+    /// The network with its Stellar endpoints that failed their latest
+    /// health checks left out, starting to track endpoints seen for the
+    /// first time. Other networks are returned unchanged.
+    pub fn healthy_network(&self, network: &Network) -> Network {
+        let mut network = network.clone();
+        if !matches!(network.network_type, BlockChainType::Stellar) {
+            return network;
+        }
+
+        for rpc_url in &network.rpc_urls {
+            if let Some(kind) = EndpointKind::of(rpc_url) {
+                self.endpoints
+                    .entry(rpc_url.url.as_ref().to_string())
+                    .or_insert_with(|| EndpointState {
+                        network_slug: network.slug.clone(),
+                        kind,
+                        consecutive_failures: 0,
+                    });
+            }
+        }
+
+        network.rpc_urls = select_endpoints(network.rpc_urls, |url| self.is_healthy(url));
+        network
+    }
+
+    /// Probe every tracked endpoint once
+    pub async fn check_all(&self) {
+        let endpoints: Vec<(String, EndpointState)> = self
+            .endpoints
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().clone()))
+            .collect();
+
+        let probes = endpoints.iter().map(|(url, state)| async move {
+            let result = match state.kind {
+                EndpointKind::Horizon => self.probe_horizon(url).await,
+                EndpointKind::SorobanRpc => self.probe_soroban_rpc(url).await,
+            };
+            (url, state, result)
+        });
+        for (url, state, result) in futures::future::join_all(probes).await {
+            let fingerprint = fingerprint(url);
+            let failures = match &result {
+                Ok(()) => 0,
+                Err(_) => state.consecutive_failures.saturating_add(1),
+            };
+            let was_healthy = state.consecutive_failures < self.config.failure_threshold;
+            let healthy = failures < self.config.failure_threshold;
+            match (&result, was_healthy, healthy) {
+                (Err(e), true, false) => warn!(
+                    "{} endpoint {} of network {} is unhealthy after {} failed checks, failing over: {}",
+                    state.kind.as_str(),
+                    fingerprint,
+                    state.network_slug,
+                    failures,
+                    e
+                ),
+                (Ok(()), false, true) => info!(
+                    "{} endpoint {} of network {} is healthy again",
+                    state.kind.as_str(),
+                    fingerprint,
+                    state.network_slug
+                ),
+                (Err(e), _, _) => debug!(
+                    "Health check of {} endpoint {} of network {} failed: {}",
+                    state.kind.as_str(),
+                    fingerprint,
+                    state.network_slug,
+                    e
+                ),
+                _ => {}
+            }
+
+            if let Some(mut entry) = self.endpoints.get_mut(url) {
+                entry.consecutive_failures = failures;
+            }
+            metrics::STELLAR_ENDPOINT_HEALTHY
+                .with_label_values(&[&state.network_slug, state.kind.as_str(), &fingerprint])
+                .set(healthy as i64);
+        }
+    }
+
+    /// Probe the tracked endpoints every health check interval
+    pub fn start(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let health = self.clone();
+        tokio::spawn(async move {
+            info!(
+                "Checking Stellar endpoint health every {:?}",
+                health.config.health_check_interval
+            );
+            let mut ticker = tokio::time::interval(health.config.health_check_interval);
+            loop {
+                ticker.tick().await;
+                health.check_all().await;
+            }
+        })
+    }
+
+    fn is_healthy(&self, url: &str) -> bool {
+        self.endpoints.get(url).map_or(true, |state| {
+            state.consecutive_failures < self.config.failure_threshold
+        })
+    }
+
+    async fn probe_horizon(&self, url: &str) -> Result<()> {
+        self.client.get(url).send().await?.error_for_status()?;
+        Ok(())
+    }
+
+    async fn probe_soroban_rpc(&self, url: &str) -> Result<()> {
+        let response: GetHealthResponse = self
+            .client
+            .post(url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getHealth",
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        match response.result {
+            Some(result) if result.status == "healthy" => Ok(()),
+            Some(result) => anyhow::bail!("getHealth reported {}", result.status),
+            None => anyhow::bail!("getHealth returned no result"),
+        }
+    }
+}
+
+/// Endpoints to hand to a client: every endpoint whose kind is not health
+/// checked, and the healthy ones of each checked kind, or all of a kind when
+/// none of it is healthy
+fn select_endpoints(rpc_urls: Vec<RpcUrl>, is_healthy: impl Fn(&str) -> bool) -> Vec<RpcUrl> {
+    let kind_has_healthy = |kind: EndpointKind| {
+        rpc_urls.iter().any(|rpc_url| {
+            EndpointKind::of(rpc_url) == Some(kind) && is_healthy(rpc_url.url.as_ref())
+        })
+    };
+    let keep_all_horizon = !kind_has_healthy(EndpointKind::Horizon);
+    let keep_all_soroban = !kind_has_healthy(EndpointKind::SorobanRpc);
+
+    rpc_urls
+        .into_iter()
+        .filter(|rpc_url| match EndpointKind::of(rpc_url) {
+            Some(EndpointKind::Horizon) => keep_all_horizon || is_healthy(rpc_url.url.as_ref()),
+            Some(EndpointKind::SorobanRpc) => keep_all_soroban || is_healthy(rpc_url.url.as_ref()),
+            None => true,
+        })
+        .collect()
+}
+
+/// Fingerprint of an endpoint for logs and metrics, so credentials in the URL
+/// are never exposed
+fn fingerprint(url: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    url.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rpc_url(type_: &str, url: &str) -> RpcUrl {
+        serde_json::from_value(json!({
+            "type_": type_,
+            "url": { "type": "plain", "value": url },
+            "weight": 100,
+        }))
+        .unwrap()
+    }
+
+    fn urls(rpc_urls: &[RpcUrl]) -> Vec<&str> {
+        rpc_urls
+            .iter()
+            .map(|rpc_url| rpc_url.url.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn test_unhealthy_endpoints_are_left_out() {
+        let rpc_urls = vec![
+            rpc_url("horizon", "https://horizon-a"),
+            rpc_url("horizon", "https://horizon-b"),
+            rpc_url("rpc", "https://soroban-a"),
+            rpc_url("rpc", "https://soroban-b"),
+        ];
+        let selected = select_endpoints(rpc_urls, |url| url.ends_with("-b"));
+        assert_eq!(
+            urls(&selected),
+            vec!["https://horizon-b", "https://soroban-b"]
+        );
+    }
+
+    #[test]
+    fn test_kind_without_healthy_endpoints_keeps_them_all() {
+        let rpc_urls = vec![
+            rpc_url("horizon", "https://horizon-a"),
+            rpc_url("rpc", "https://soroban-a"),
+            rpc_url("rpc", "https://soroban-b"),
+        ];
+        let selected = select_endpoints(rpc_urls, |url| url == "https://soroban-a");
+        assert_eq!(
+            urls(&selected),
+            vec!["https://horizon-a", "https://soroban-a"]
+        );
+    }
+}
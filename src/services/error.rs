@@ -1,5 +1,6 @@
 //! Service layer error types
 
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -25,6 +26,10 @@ pub enum ServiceError {
     #[error("Tenant not found: {0}")]
     TenantNotFound(Uuid),
 
+    /// Tenant is suspended and cannot be processed
+    #[error("Tenant suspended: {0}")]
+    TenantSuspended(Uuid),
+
     /// Resource limit exceeded
     #[error("Resource limit exceeded: {0}")]
     ResourceLimitExceeded(String),
@@ -52,6 +57,78 @@ pub enum ServiceError {
     /// Load balancing error
     #[error("Load balancing error: {0}")]
     LoadBalancingError(String),
+
+    /// Missing or invalid API credentials
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// Client exceeded the API rate limit
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    /// Network of a chain type this deployment does not accept
+    #[error("Unsupported chain: {0}")]
+    UnsupportedChain(String),
+}
+
+impl ServiceError {
+    /// Stable machine-readable error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            ServiceError::Repository(err) => err.code(),
+            ServiceError::Configuration(_) => "CONFIGURATION_ERROR",
+            ServiceError::WorkerNotFound(_) => "WORKER_NOT_FOUND",
+            ServiceError::TenantNotFound(_) => "TENANT_NOT_FOUND",
+            ServiceError::TenantSuspended(_) => "TENANT_SUSPENDED",
+            ServiceError::ResourceLimitExceeded(_) => "RESOURCE_LIMIT_EXCEEDED",
+            ServiceError::ServiceUnavailable(_) => "SERVICE_UNAVAILABLE",
+            ServiceError::InvalidState(_) => "INVALID_STATE",
+            ServiceError::CommunicationError(_) => "COMMUNICATION_ERROR",
+            ServiceError::CacheError(_) => "CACHE_ERROR",
+            ServiceError::BlockProcessingError(_) => "BLOCK_PROCESSING_ERROR",
+            ServiceError::LoadBalancingError(_) => "LOAD_BALANCING_ERROR",
+            ServiceError::Unauthorized(_) => "UNAUTHORIZED",
+            ServiceError::RateLimited(_) => "RATE_LIMITED",
+            ServiceError::UnsupportedChain(_) => "UNSUPPORTED_CHAIN",
+        }
+    }
+
+    /// HTTP status code used when surfacing this error through the API
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ServiceError::Repository(err) => err.http_status(),
+            ServiceError::WorkerNotFound(_) | ServiceError::TenantNotFound(_) => 404,
+            ServiceError::Unauthorized(_) => 401,
+            ServiceError::RateLimited(_) => 429,
+            ServiceError::UnsupportedChain(_) => 422,
+            ServiceError::TenantSuspended(_) => 403,
+            ServiceError::ResourceLimitExceeded(_) | ServiceError::InvalidState(_) => 409,
+            ServiceError::ServiceUnavailable(_) | ServiceError::CacheError(_) => 503,
+            ServiceError::CommunicationError(_) => 502,
+            ServiceError::Configuration(_)
+            | ServiceError::BlockProcessingError(_)
+            | ServiceError::LoadBalancingError(_) => 500,
+        }
+    }
+}
+
+/// Machine-readable error body returned by the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    /// Stable error code clients can branch on (e.g. `TENANT_SUSPENDED`)
+    pub code: String,
+
+    /// Human-readable error message
+    pub message: String,
+}
+
+impl From<&ServiceError> for ErrorResponse {
+    fn from(err: &ServiceError) -> Self {
+        Self {
+            code: err.code().to_string(),
+            message: err.to_string(),
+        }
+    }
 }
 
 impl From<redis::RedisError> for ServiceError {
@@ -62,6 +139,10 @@ impl From<redis::RedisError> for ServiceError {
 
 impl From<anyhow::Error> for ServiceError {
     fn from(err: anyhow::Error) -> Self {
-        ServiceError::ServiceUnavailable(err.to_string())
+        // Service errors passed through anyhow keep their code and status
+        match err.downcast::<ServiceError>() {
+            Ok(err) => err,
+            Err(err) => ServiceError::ServiceUnavailable(err.to_string()),
+        }
     }
 }
@@ -0,0 +1,325 @@
+//! Event Bus
+//!
+//! Typed publish/subscribe for the events the block watcher, workers and API
+//! exchange: new blocks, assignment changes, configuration changes and
+//! matches. Components publish to and subscribe from an [`EventBus`] instead
+//! of holding each other's channels. [`InProcessEventBus`] delivers events
+//! within the process; [`RedisEventBus`] routes every event through Redis
+//! pub/sub so subscribers in other processes receive it as well.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use redis::{AsyncCommands, Client as RedisClient};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Weak};
+use tokio::sync::broadcast;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::models::TenantShard;
+use crate::services::match_feed::MatchEvent;
+use crate::services::redis_keyspace::RedisKeyspace;
+use crate::services::shared_block_watcher::BlockEvent;
+
+/// Delay before re-subscribing after the pub/sub connection drops
+const RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Events buffered per subscriber for the low-volume topics
+const CONTROL_EVENT_BUFFER: usize = 256;
+
+/// Every topic, as published on Redis
+const TOPICS: [&str; 4] = ["blocks", "assignments", "config", "matches"];
+
+/// Where events are delivered
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventBusBackend {
+    /// Only to subscribers in this process
+    #[default]
+    InProcess,
+    /// To subscribers in every process sharing the Redis keyspace
+    Redis,
+}
+
+/// Tenants and shards a worker took over
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssignmentChange {
+    pub worker_id: String,
+    pub tenant_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub shards: Vec<TenantShard>,
+}
+
+/// Tenants whose configuration must be reloaded
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChange {
+    pub tenant_ids: Vec<Uuid>,
+}
+
+/// Event published on the bus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// Blocks fetched by the block watcher
+    Block(BlockEvent),
+    /// A worker's assignment was replaced
+    AssignmentChanged(AssignmentChange),
+    /// Tenant configuration was invalidated
+    ConfigChanged(ConfigChange),
+    /// A worker dispatched a monitor match
+    MatchFound(MatchEvent),
+}
+
+impl Event {
+    /// Topic the event is published on
+    pub fn topic(&self) -> &'static str {
+        match self {
+            Event::Block(_) => TOPICS[0],
+            Event::AssignmentChanged(_) => TOPICS[1],
+            Event::ConfigChanged(_) => TOPICS[2],
+            Event::MatchFound(_) => TOPICS[3],
+        }
+    }
+}
+
+/// Local channels subscribers of a bus receive events from, one per topic
+pub struct EventChannels {
+    blocks: broadcast::Sender<BlockEvent>,
+    assignments: broadcast::Sender<AssignmentChange>,
+    config: broadcast::Sender<ConfigChange>,
+    matches: broadcast::Sender<MatchEvent>,
+    block_capacity: usize,
+}
+
+impl EventChannels {
+    /// Create channels buffering up to `block_capacity` block events per subscriber
+    pub fn new(block_capacity: usize) -> Self {
+        Self {
+            blocks: broadcast::channel(block_capacity).0,
+            assignments: broadcast::channel(CONTROL_EVENT_BUFFER).0,
+            config: broadcast::channel(CONTROL_EVENT_BUFFER).0,
+            matches: broadcast::channel(CONTROL_EVENT_BUFFER).0,
+            block_capacity,
+        }
+    }
+
+    /// Deliver an event to local subscribers, returning how many received it
+    pub fn deliver(&self, event: Event) -> usize {
+        match event {
+            Event::Block(event) => self.blocks.send(event),
+            Event::AssignmentChanged(event) => self.assignments.send(event),
+            Event::ConfigChanged(event) => self.config.send(event),
+            Event::MatchFound(event) => self.matches.send(event),
+        }
+        .unwrap_or(0)
+    }
+
+    /// Block events the slowest local subscriber has not received yet
+    pub fn pending_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Block events buffered per subscriber
+    pub fn block_capacity(&self) -> usize {
+        self.block_capacity
+    }
+}
+
+/// Publish/subscribe of orchestrator events
+#[async_trait]
+pub trait EventBus: Send + Sync {
+    /// Publish an event, returning how many subscribers received it
+    async fn publish(&self, event: Event) -> Result<usize>;
+
+    /// Channels this process's subscribers receive events from
+    fn channels(&self) -> &EventChannels;
+
+    /// Subscribe to block events
+    fn subscribe_blocks(&self) -> broadcast::Receiver<BlockEvent> {
+        self.channels().blocks.subscribe()
+    }
+
+    /// Subscribe to assignment changes
+    fn subscribe_assignments(&self) -> broadcast::Receiver<AssignmentChange> {
+        self.channels().assignments.subscribe()
+    }
+
+    /// Subscribe to configuration changes
+    fn subscribe_config(&self) -> broadcast::Receiver<ConfigChange> {
+        self.channels().config.subscribe()
+    }
+
+    /// Subscribe to matches
+    fn subscribe_matches(&self) -> broadcast::Receiver<MatchEvent> {
+        self.channels().matches.subscribe()
+    }
+}
+
+/// Bus delivering events to subscribers in this process
+pub struct InProcessEventBus {
+    channels: EventChannels,
+}
+
+impl InProcessEventBus {
+    /// Create a bus buffering up to `block_capacity` block events per subscriber
+    pub fn new(block_capacity: usize) -> Self {
+        Self {
+            channels: EventChannels::new(block_capacity),
+        }
+    }
+}
+
+#[async_trait]
+impl EventBus for InProcessEventBus {
+    async fn publish(&self, event: Event) -> Result<usize> {
+        Ok(self.channels.deliver(event))
+    }
+
+    fn channels(&self) -> &EventChannels {
+        &self.channels
+    }
+}
+
+/// Bus delivering events through Redis pub/sub.
+///
+/// Published events reach this process's subscribers through Redis like
+/// everyone else's, so all processes see events in the same order.
+pub struct RedisEventBus {
+    redis: Arc<RedisClient>,
+    keyspace: RedisKeyspace,
+    channels: Arc<EventChannels>,
+}
+
+impl RedisEventBus {
+    /// Create a bus on the given Redis client and start forwarding its events
+    /// to local subscribers until the bus is dropped
+    pub fn new(redis: Arc<RedisClient>, keyspace: RedisKeyspace, block_capacity: usize) -> Self {
+        let bus = Self {
+            redis,
+            keyspace,
+            channels: Arc::new(EventChannels::new(block_capacity)),
+        };
+        bus.start_listener();
+        bus
+    }
+
+    fn start_listener(&self) {
+        let redis = self.redis.clone();
+        let topics: Vec<String> = TOPICS.iter().map(|topic| self.channel(topic)).collect();
+        let channels = Arc::downgrade(&self.channels);
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = listen(&redis, &topics, &channels).await {
+                    error!("Event bus subscription failed: {}", e);
+                }
+                if channels.strong_count() == 0 {
+                    break;
+                }
+                warn!(
+                    "Event bus subscription ended, retrying in {:?}",
+                    RESUBSCRIBE_DELAY
+                );
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        });
+    }
+
+    fn channel(&self, topic: &str) -> String {
+        self.keyspace.key(&format!("events:{}", topic))
+    }
+}
+
+#[async_trait]
+impl EventBus for RedisEventBus {
+    async fn publish(&self, event: Event) -> Result<usize> {
+        let channel = self.channel(event.topic());
+        let payload = serde_json::to_string(&event)?;
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let receivers: i64 = conn.publish(&channel, payload).await?;
+
+        debug!("Published event on {} to {} processes", channel, receivers);
+        Ok(receivers.max(0) as usize)
+    }
+
+    fn channels(&self) -> &EventChannels {
+        &self.channels
+    }
+}
+
+/// Forward events until the connection closes or the bus is dropped
+async fn listen(
+    redis: &RedisClient,
+    topics: &[String],
+    channels: &Weak<EventChannels>,
+) -> Result<()> {
+    let mut pubsub = redis.get_async_pubsub().await?;
+    for topic in topics {
+        pubsub.subscribe(topic).await?;
+    }
+    info!("Subscribed to events on {:?}", topics);
+
+    let mut messages = pubsub.on_message();
+    while let Some(msg) = messages.next().await {
+        let Some(channels) = channels.upgrade() else {
+            return Ok(());
+        };
+
+        let event = msg
+            .get_payload::<String>()
+            .map_err(anyhow::Error::from)
+            .and_then(|payload| Ok(serde_json::from_str::<Event>(&payload)?));
+        match event {
+            Ok(event) => {
+                channels.deliver(event);
+            }
+            Err(e) => warn!(
+                "Ignoring malformed event on {}: {}",
+                msg.get_channel_name(),
+                e
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_in_process_bus_delivers_by_topic() {
+        let bus = InProcessEventBus::new(4);
+        let mut config = bus.subscribe_config();
+        let mut assignments = bus.subscribe_assignments();
+        let tenant_id = Uuid::new_v4();
+
+        let received = bus
+            .publish(Event::ConfigChanged(ConfigChange {
+                tenant_ids: vec![tenant_id],
+            }))
+            .await
+            .unwrap();
+
+        assert_eq!(received, 1);
+        assert_eq!(config.recv().await.unwrap().tenant_ids, vec![tenant_id]);
+        assert!(assignments.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_events_round_trip_with_their_topic() {
+        let event = Event::AssignmentChanged(AssignmentChange {
+            worker_id: "worker-1".to_string(),
+            tenant_ids: vec![Uuid::new_v4()],
+            shards: Vec::new(),
+        });
+
+        let payload = serde_json::to_string(&event).unwrap();
+        assert!(payload.contains(r#""type":"assignment_changed""#));
+
+        let decoded: Event = serde_json::from_str(&payload).unwrap();
+        assert_eq!(decoded.topic(), "assignments");
+    }
+}
@@ -0,0 +1,147 @@
+//! Filter Debug Service
+//!
+//! Records the full filter inputs and outputs for a sampled fraction of blocks
+//! of tenants with debugging enabled, so support can explain why a monitor did
+//! or did not match without reproducing the block locally.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use rand::Rng;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+use uuid::Uuid;
+
+use crate::models::{FilterDebugSample, FilterDebugSettings};
+
+/// How long loaded settings are reused before re-reading the database
+const SETTINGS_TTL: Duration = Duration::from_secs(60);
+
+/// Samples filter runs of tenants with debugging enabled
+pub struct FilterDebugService {
+    db: Arc<PgPool>,
+    settings: DashMap<Uuid, (Instant, Option<FilterDebugSettings>)>,
+}
+
+impl FilterDebugService {
+    /// Create a new filter debug service
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            settings: DashMap::new(),
+        }
+    }
+
+    /// Decide whether the current block should be recorded for a tenant
+    pub async fn should_sample(&self, tenant_id: Uuid) -> Result<bool> {
+        let Some(settings) = self.settings_for(tenant_id).await? else {
+            return Ok(false);
+        };
+        Ok(settings.samples(Utc::now(), rand::thread_rng().gen()))
+    }
+
+    /// Store a recorded filter run
+    pub async fn record(&self, sample: &FilterDebugSample) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO filter_debug_samples
+                (tenant_id, network_slug, block_number, block, monitors, filter_matches, accepted_matches)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(sample.tenant_id)
+        .bind(&sample.network_slug)
+        .bind(sample.block_number.map(|n| n as i64))
+        .bind(&sample.block)
+        .bind(&sample.monitors)
+        .bind(&sample.filter_matches)
+        .bind(&sample.accepted_matches)
+        .execute(&*self.db)
+        .await?;
+
+        debug!(
+            "Recorded filter debug sample for tenant {} at block {:?} on {}",
+            sample.tenant_id, sample.block_number, sample.network_slug
+        );
+        Ok(())
+    }
+
+    /// Enable sampling for a tenant, replacing any previous settings
+    pub async fn enable(
+        &self,
+        tenant_id: Uuid,
+        sample_rate: f64,
+        enabled_until: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        if !(0.0..=1.0).contains(&sample_rate) {
+            anyhow::bail!("Sample rate must be between 0 and 1, got {}", sample_rate);
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_filter_debug (tenant_id, sample_rate, enabled_until)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (tenant_id) DO UPDATE
+            SET sample_rate = EXCLUDED.sample_rate,
+                enabled_until = EXCLUDED.enabled_until,
+                updated_at = now()
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(sample_rate)
+        .bind(enabled_until)
+        .execute(&*self.db)
+        .await?;
+
+        self.invalidate(tenant_id);
+        info!(
+            "Enabled filter debug sampling for tenant {} at rate {}",
+            tenant_id, sample_rate
+        );
+        Ok(())
+    }
+
+    /// Stop sampling for a tenant; recorded samples are kept
+    pub async fn disable(&self, tenant_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM tenant_filter_debug WHERE tenant_id = $1")
+            .bind(tenant_id)
+            .execute(&*self.db)
+            .await?;
+
+        self.invalidate(tenant_id);
+        info!("Disabled filter debug sampling for tenant {}", tenant_id);
+        Ok(())
+    }
+
+    /// Drop cached settings so the next check re-reads them
+    pub fn invalidate(&self, tenant_id: Uuid) {
+        self.settings.remove(&tenant_id);
+    }
+
+    /// Get settings for a tenant, loading them if the cache is stale
+    async fn settings_for(&self, tenant_id: Uuid) -> Result<Option<FilterDebugSettings>> {
+        if let Some(entry) = self.settings.get(&tenant_id) {
+            let (loaded_at, settings) = entry.value();
+            if loaded_at.elapsed() < SETTINGS_TTL {
+                return Ok(settings.clone());
+            }
+        }
+
+        let settings = sqlx::query_as::<_, FilterDebugSettings>(
+            r#"
+            SELECT tenant_id, sample_rate, enabled_until
+            FROM tenant_filter_debug
+            WHERE tenant_id = $1
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        self.settings
+            .insert(tenant_id, (Instant::now(), settings.clone()));
+        Ok(settings)
+    }
+}
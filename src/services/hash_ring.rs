@@ -0,0 +1,178 @@
+//! Hash Ring
+//!
+//! Consistent hash ring placing every worker at a number of virtual nodes.
+//! A key belongs to the first virtual node at or after its hash, so adding or
+//! removing a worker only moves the keys on the arcs its virtual nodes cover,
+//! roughly 1/N of them, instead of remapping nearly every key.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Consistent hash ring of workers
+#[derive(Debug, Clone)]
+pub struct HashRing {
+    virtual_nodes: u32,
+    nodes: BTreeMap<u64, String>,
+    workers: HashSet<String>,
+}
+
+impl HashRing {
+    /// Create an empty ring placing each worker at `virtual_nodes` points
+    pub fn new(virtual_nodes: u32) -> Self {
+        Self {
+            virtual_nodes: virtual_nodes.max(1),
+            nodes: BTreeMap::new(),
+            workers: HashSet::new(),
+        }
+    }
+
+    /// Add a worker; adding a worker already on the ring does nothing
+    pub fn add(&mut self, worker_id: &str) {
+        if !self.workers.insert(worker_id.to_string()) {
+            return;
+        }
+        for replica in 0..self.virtual_nodes {
+            // On the rare collision the smaller worker ID keeps the point, so
+            // the ring does not depend on the order workers joined in
+            let point = hash(&format!("{}#{}", worker_id, replica));
+            match self.nodes.get(&point) {
+                Some(existing) if existing.as_str() <= worker_id => {}
+                _ => {
+                    self.nodes.insert(point, worker_id.to_string());
+                }
+            }
+        }
+    }
+
+    /// Remove a worker and its virtual nodes
+    pub fn remove(&mut self, worker_id: &str) {
+        if self.workers.remove(worker_id) {
+            // Rebuilt so points it had won on a collision go back to the other worker
+            let workers = std::mem::take(&mut self.workers);
+            self.nodes.clear();
+            for worker_id in &workers {
+                self.add(worker_id);
+            }
+        }
+    }
+
+    /// Whether a worker is on the ring
+    pub fn contains(&self, worker_id: &str) -> bool {
+        self.workers.contains(worker_id)
+    }
+
+    /// Whether the ring has no workers
+    pub fn is_empty(&self) -> bool {
+        self.workers.is_empty()
+    }
+
+    /// Worker owning a key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.walk(key).next()
+    }
+
+    /// Distinct workers in ring order starting from the key's owner, for
+    /// falling back to the next worker when the owner cannot take the key
+    pub fn walk<'a>(&'a self, key: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let point = hash(key);
+        let mut seen = HashSet::new();
+        self.nodes
+            .range(point..)
+            .chain(self.nodes.range(..point))
+            .map(|(_, worker_id)| worker_id.as_str())
+            .filter(move |worker_id| seen.insert(*worker_id))
+            .take(self.workers.len())
+    }
+}
+
+fn hash(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    fn ring(workers: usize) -> HashRing {
+        let mut ring = HashRing::new(100);
+        for i in 0..workers {
+            ring.add(&format!("worker-{}", i));
+        }
+        ring
+    }
+
+    #[test]
+    fn test_adding_a_worker_moves_about_one_in_n_keys() {
+        let keys: Vec<String> = (0..10_000).map(|_| Uuid::new_v4().to_string()).collect();
+        let mut ring = ring(4);
+        let before: Vec<String> = keys
+            .iter()
+            .map(|key| ring.get(key).unwrap().to_string())
+            .collect();
+
+        ring.add("worker-4");
+        let moved = keys
+            .iter()
+            .zip(&before)
+            .filter(|(key, owner)| ring.get(key).unwrap() != owner.as_str())
+            .count();
+
+        // Ideally 1/5 of the keys, all of them onto the new worker
+        assert!(moved > 0);
+        assert!(
+            (moved as f64) < keys.len() as f64 * 0.3,
+            "{} of {} keys moved",
+            moved,
+            keys.len()
+        );
+        assert!(keys.iter().zip(&before).all(
+            |(key, owner)| ring.get(key).unwrap() == owner || ring.get(key) == Some("worker-4")
+        ));
+    }
+
+    #[test]
+    fn test_removing_a_worker_only_moves_its_keys() {
+        let keys: Vec<String> = (0..1_000).map(|_| Uuid::new_v4().to_string()).collect();
+        let mut ring = ring(5);
+        let before: Vec<String> = keys
+            .iter()
+            .map(|key| ring.get(key).unwrap().to_string())
+            .collect();
+
+        ring.remove("worker-2");
+        assert!(!ring.contains("worker-2"));
+        for (key, owner) in keys.iter().zip(&before) {
+            if owner != "worker-2" {
+                assert_eq!(ring.get(key).unwrap(), owner);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ring_is_independent_of_join_order() {
+        let mut forward = HashRing::new(100);
+        let mut backward = HashRing::new(100);
+        for i in 0..5 {
+            forward.add(&format!("worker-{}", i));
+            backward.add(&format!("worker-{}", 4 - i));
+        }
+        for _ in 0..100 {
+            let key = Uuid::new_v4().to_string();
+            assert_eq!(forward.get(&key), backward.get(&key));
+        }
+    }
+
+    #[test]
+    fn test_walk_visits_every_worker_once() {
+        let ring = ring(3);
+        let mut workers: Vec<&str> = ring.walk("tenant").collect();
+        assert_eq!(workers[0], ring.get("tenant").unwrap());
+        workers.sort();
+        assert_eq!(workers, vec!["worker-0", "worker-1", "worker-2"]);
+        assert!(HashRing::new(100).get("tenant").is_none());
+    }
+}
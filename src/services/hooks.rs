@@ -0,0 +1,100 @@
+//! Lifecycle Hooks
+//!
+//! Registration point for integrator logic (metrics, billing events, audit
+//! trails) that should run on worker lifecycle events without patching the
+//! worker pool itself.
+
+use async_trait::async_trait;
+use openzeppelin_monitor::models::Network;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+
+/// Hook invoked on worker lifecycle events.
+///
+/// All methods default to no-ops so implementations only override the events
+/// they care about. Hooks run inline on the worker task and should hand off
+/// slow work to a background task.
+#[async_trait]
+pub trait LifecycleHook: Send + Sync {
+    /// Called when a worker starts processing its assigned tenants
+    async fn on_worker_start(&self, _worker_id: &str, _tenant_ids: &[Uuid]) {}
+
+    /// Called after a block has been processed for all assigned tenants.
+    ///
+    /// Not called for blocks replayed on request, so progress tracked here
+    /// only ever reflects newly watched blocks.
+    async fn on_block_processed(
+        &self,
+        _worker_id: &str,
+        _network: &Network,
+        _block_number: Option<u64>,
+        _match_count: usize,
+    ) {
+    }
+
+    /// Called for every monitor match found by a worker
+    async fn on_match(&self, _worker_id: &str, _tenant_match: &TenantMonitorMatch) {}
+
+    /// Called when a worker stops
+    async fn on_shutdown(&self, _worker_id: &str) {}
+}
+
+/// Ordered set of registered lifecycle hooks
+#[derive(Clone, Default)]
+pub struct LifecycleHooks {
+    hooks: Vec<Arc<dyn LifecycleHook>>,
+}
+
+impl LifecycleHooks {
+    /// Create an empty hook registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a hook; hooks run in registration order
+    pub fn register(&mut self, hook: Arc<dyn LifecycleHook>) {
+        self.hooks.push(hook);
+    }
+
+    /// Check if any hooks are registered
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run `on_worker_start` on all hooks
+    pub async fn worker_started(&self, worker_id: &str, tenant_ids: &[Uuid]) {
+        for hook in &self.hooks {
+            hook.on_worker_start(worker_id, tenant_ids).await;
+        }
+    }
+
+    /// Run `on_block_processed` on all hooks
+    pub async fn block_processed(
+        &self,
+        worker_id: &str,
+        network: &Network,
+        block_number: Option<u64>,
+        match_count: usize,
+    ) {
+        for hook in &self.hooks {
+            hook.on_block_processed(worker_id, network, block_number, match_count)
+                .await;
+        }
+    }
+
+    /// Run `on_match` on all hooks
+    pub async fn matched(&self, worker_id: &str, tenant_match: &TenantMonitorMatch) {
+        for hook in &self.hooks {
+            hook.on_match(worker_id, tenant_match).await;
+        }
+    }
+
+    /// Run `on_shutdown` on all hooks
+    pub async fn shutdown(&self, worker_id: &str) {
+        for hook in &self.hooks {
+            hook.on_shutdown(worker_id).await;
+        }
+    }
+}
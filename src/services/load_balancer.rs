@@ -3,21 +3,32 @@
 //! Distributes tenants across workers based on resource usage and activity.
 
 use anyhow::Result;
-use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
-use std::hash::{Hash, Hasher};
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::{info, instrument};
+use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
 // Import models from our models module
-use crate::models::{AssignmentReason, TenantAssignment, TenantMetrics, WorkerMetrics};
+use crate::models::{
+    AssignmentEvent, AssignmentReason, CapacityReport, CapacityTargets, DrainState,
+    ReassignedTenant, RebalancePlan, ReconciliationReport, ScalingAction, ShardBy, SystemMetrics,
+    TenantAssignment, TenantMetrics, TenantShard, WorkerAssignment, WorkerDrain, WorkerMetrics,
+    WorkerUsage,
+};
+use crate::services::assignment_store::{AssignmentStore, StaleAssignment};
+use crate::services::assignment_webhooks::AssignmentWebhookNotifier;
+use crate::services::control_channel::{ControlChannel, ControlCommand};
+use crate::services::distributed_lock::DistributedLock;
+use crate::services::error::ServiceError;
+use crate::services::hash_ring::HashRing;
+use crate::services::metrics;
 
 /// Load balancing strategy
 #[derive(Debug, Clone)]
 pub enum LoadBalancingStrategy {
-    /// Round-robin distribution
+    /// Workers in turn, skipping those at capacity
     RoundRobin,
     /// Least loaded worker first
     LeastLoaded,
@@ -25,6 +36,26 @@ pub enum LoadBalancingStrategy {
     ConsistentHashing,
     /// Activity-based distribution
     ActivityBased,
+    /// Strategy registered at runtime under the given name
+    Custom(String),
+}
+
+/// Pluggable placement logic selectable by name from configuration
+pub trait PlacementStrategy: Send + Sync {
+    /// Pick a worker for the tenant, or `None` if no worker is suitable
+    fn select_worker(
+        &self,
+        tenant_id: Uuid,
+        worker_loads: &HashMap<String, WorkerMetrics>,
+        tenant_metrics: Option<&TenantMetrics>,
+    ) -> Option<String>;
+}
+
+/// How a tenant too large for one worker is split
+#[derive(Debug, Clone, Copy)]
+pub struct TenantSharding {
+    pub shard_by: ShardBy,
+    pub shard_count: u32,
 }
 
 /// Load balancer configuration
@@ -34,6 +65,29 @@ pub struct LoadBalancerConfig {
     pub max_tenants_per_worker: usize,
     pub rebalance_threshold: f64,
     pub min_rebalance_interval: std::time::Duration,
+    /// Age after which worker CPU and memory samples no longer count towards
+    /// the rebalance check
+    pub load_metrics_max_age: std::time::Duration,
+    /// Tenants whose monitors are split across several workers
+    pub sharded_tenants: HashMap<Uuid, TenantSharding>,
+    /// Utilization the worker pool is sized for
+    pub target_utilization: f64,
+    pub min_workers: usize,
+    pub max_workers: Option<usize>,
+    /// How long a worker that gave up a tenant is preferred for it
+    pub affinity_ttl: std::time::Duration,
+    /// Points each worker takes on the consistent hash ring
+    pub virtual_nodes: u32,
+    /// Silence after which a worker is evicted and its tenants reassigned
+    pub worker_timeout: std::time::Duration,
+    /// Assignment changes kept in memory per tenant
+    pub max_assignment_history: usize,
+    /// How long the shared assignments and worker registry read from the
+    /// assignment store are reused
+    pub shared_state_ttl: std::time::Duration,
+    /// How long a tenant's pin is honored after it was set or last honored;
+    /// zero keeps pins until their worker leaves
+    pub pin_ttl: std::time::Duration,
 }
 
 impl Default for LoadBalancerConfig {
@@ -43,19 +97,94 @@ impl Default for LoadBalancerConfig {
             max_tenants_per_worker: 50,
             rebalance_threshold: 0.2, // 20% imbalance triggers rebalance
             min_rebalance_interval: std::time::Duration::from_secs(300), // 5 minutes
+            load_metrics_max_age: std::time::Duration::from_secs(120),
+            sharded_tenants: HashMap::new(),
+            target_utilization: 0.7,
+            min_workers: 1,
+            max_workers: None,
+            affinity_ttl: std::time::Duration::from_secs(600),
+            virtual_nodes: 100,
+            worker_timeout: std::time::Duration::from_secs(90),
+            max_assignment_history: 50,
+            shared_state_ttl: std::time::Duration::from_secs(2),
+            pin_ttl: std::time::Duration::from_secs(86_400),
+        }
+    }
+}
+
+/// A tenant's pin to a worker
+#[derive(Debug, Clone)]
+struct TenantPin {
+    worker_id: String,
+    /// When the pin was set or last honored
+    last_used: chrono::DateTime<chrono::Utc>,
+}
+
+impl TenantPin {
+    fn new(worker_id: impl Into<String>) -> Self {
+        Self::since(worker_id, chrono::Utc::now())
+    }
+
+    fn since(worker_id: impl Into<String>, last_used: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            worker_id: worker_id.into(),
+            last_used,
         }
     }
 }
 
 /// Load balancer service
+///
+/// When more than one of `worker_loads`, `ring`, `rotation`,
+/// `tenant_worker_map` and `assignments` is held at once they are taken in
+/// that order, the order `remove_worker` uses.
 pub struct LoadBalancer {
     assignments: Arc<RwLock<HashMap<Uuid, TenantAssignment>>>,
     worker_loads: Arc<RwLock<HashMap<String, WorkerMetrics>>>,
     tenant_metrics: Arc<RwLock<HashMap<Uuid, TenantMetrics>>>,
-    /// Mapping from tenant to worker for consistent hashing
-    tenant_worker_map: Arc<RwLock<HashMap<String, String>>>,
+    /// Tenants pinned to a worker, taking precedence over the hash ring
+    /// until the pin goes unused for `pin_ttl`
+    tenant_worker_map: Arc<RwLock<HashMap<String, TenantPin>>>,
+    /// Consistent hash ring of registered workers
+    ring: Arc<RwLock<HashRing>>,
+    /// Registered workers in the order round-robin assignment visits them;
+    /// workers join at the end so the cursor keeps its place
+    rotation: Arc<RwLock<Vec<String>>>,
+    /// Index in `rotation` of the next worker round-robin assignment tries
+    rotation_cursor: Arc<AtomicUsize>,
     config: LoadBalancerConfig,
     last_rebalance: Arc<RwLock<chrono::DateTime<chrono::Utc>>>,
+    /// Outbound webhooks for assignment lifecycle events
+    webhooks: Option<Arc<AssignmentWebhookNotifier>>,
+    /// Channel pushing assignment changes to workers
+    control: Option<Arc<ControlChannel>>,
+    /// Persistent copy of assignments, used to reconcile after a restart
+    store: Option<Arc<AssignmentStore>>,
+    /// Placement strategies registered by name
+    custom_strategies: HashMap<String, Arc<dyn PlacementStrategy>>,
+    /// Shard bookkeeping for sharded tenants, by worker
+    worker_assignments: Arc<RwLock<HashMap<String, WorkerAssignment>>>,
+    /// Leadership lock so only one coordinator rebalances at a time
+    rebalance_lock: Option<DistributedLock>,
+    /// Latest drain of each worker; draining and drained workers get no new tenants
+    drains: Arc<RwLock<HashMap<String, WorkerDrain>>>,
+    /// Workers that gave up each tenant and when, whose monitor, contract
+    /// spec and script caches may still be warm for it
+    warm_workers: Arc<RwLock<HashMap<Uuid, HashMap<String, chrono::DateTime<chrono::Utc>>>>>,
+    /// Last heartbeat of each registered worker, for evicting silent workers
+    /// when there is no assignment store to read heartbeats from
+    heartbeats: Arc<RwLock<HashMap<String, chrono::DateTime<chrono::Utc>>>>,
+    /// Tenants orphaned while no worker could take them, with the assignment
+    /// they held on the worker they were orphaned from, placed when the next
+    /// worker registers
+    pending_orphans: Arc<RwLock<HashMap<Uuid, TenantAssignment>>>,
+    /// Latest assignment changes of each tenant, oldest first, at most
+    /// `max_assignment_history` per tenant
+    history: Arc<RwLock<HashMap<Uuid, VecDeque<TenantAssignment>>>>,
+    /// Live workers in the shared registry, including those of other processes
+    shared_workers: Arc<RwLock<HashSet<String>>>,
+    /// When the shared assignments and worker registry were last read
+    shared_synced_at: Arc<RwLock<Option<std::time::Instant>>>,
 }
 
 impl LoadBalancer {
@@ -65,18 +194,106 @@ impl LoadBalancer {
             worker_loads: Arc::new(RwLock::new(HashMap::new())),
             tenant_metrics: Arc::new(RwLock::new(HashMap::new())),
             tenant_worker_map: Arc::new(RwLock::new(HashMap::new())),
+            ring: Arc::new(RwLock::new(HashRing::new(config.virtual_nodes))),
+            rotation: Arc::new(RwLock::new(Vec::new())),
+            rotation_cursor: Arc::new(AtomicUsize::new(0)),
             config,
             last_rebalance: Arc::new(RwLock::new(chrono::Utc::now())),
+            webhooks: None,
+            control: None,
+            store: None,
+            custom_strategies: HashMap::new(),
+            worker_assignments: Arc::new(RwLock::new(HashMap::new())),
+            rebalance_lock: None,
+            drains: Arc::new(RwLock::new(HashMap::new())),
+            warm_workers: Arc::new(RwLock::new(HashMap::new())),
+            heartbeats: Arc::new(RwLock::new(HashMap::new())),
+            pending_orphans: Arc::new(RwLock::new(HashMap::new())),
+            history: Arc::new(RwLock::new(HashMap::new())),
+            shared_workers: Arc::new(RwLock::new(HashSet::new())),
+            shared_synced_at: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Register a placement strategy selectable as `custom: <name>` in configuration
+    pub fn register_strategy(
+        mut self,
+        name: impl Into<String>,
+        strategy: Arc<dyn PlacementStrategy>,
+    ) -> Self {
+        self.custom_strategies.insert(name.into(), strategy);
+        self
+    }
+
+    /// Check that the configured strategy can be used
+    pub fn validate_strategy(&self) -> Result<()> {
+        if let LoadBalancingStrategy::Custom(name) = &self.config.strategy {
+            if !self.custom_strategies.contains_key(name) {
+                anyhow::bail!("Load balancing strategy {} is not registered", name);
+            }
+        }
+        Ok(())
+    }
+
+    /// Fire assignment lifecycle events at the given webhooks
+    pub fn with_webhooks(mut self, webhooks: Arc<AssignmentWebhookNotifier>) -> Self {
+        if !webhooks.is_empty() {
+            self.webhooks = Some(webhooks);
+        }
+        self
+    }
+
+    /// Push assignment changes to workers over the given control channel
+    pub fn with_control_channel(mut self, control: Arc<ControlChannel>) -> Self {
+        self.control = Some(control);
+        self
+    }
+
+    /// Persist assignments to the given store
+    pub fn with_assignment_store(mut self, store: Arc<AssignmentStore>) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Rebalance only while holding the given lock, shared by all coordinators
+    pub fn with_rebalance_lock(mut self, lock: DistributedLock) -> Self {
+        self.rebalance_lock = Some(lock);
+        self
+    }
+
+    /// Emit an assignment lifecycle event
+    fn emit(&self, event: AssignmentEvent) {
+        if let Some(webhooks) = &self.webhooks {
+            webhooks.notify(event);
+        }
+    }
+
+    /// Add a new worker taking `capacity_weight` times the tenants of a
+    /// worker of weight 1, publishing its weight to the assignment store so
+    /// coordinators in other processes place tenants by it too
+    pub async fn add_worker(&self, worker_id: String, capacity_weight: f64) -> Result<()> {
+        if let Some(store) = &self.store {
+            if let Err(e) = store
+                .report_capacity_weight(&worker_id, capacity_weight)
+                .await
+            {
+                warn!(
+                    "Failed to publish capacity weight of worker {}: {}",
+                    worker_id, e
+                );
+            }
         }
+        self.register_worker(worker_id, capacity_weight).await
     }
 
-    /// Add a new worker
-    pub async fn add_worker(&self, worker_id: String) -> Result<()> {
+    /// Add a worker with the given capacity weight
+    async fn register_worker(&self, worker_id: String, capacity_weight: f64) -> Result<()> {
         let mut worker_loads = self.worker_loads.write().await;
         worker_loads.insert(
             worker_id.clone(),
             WorkerMetrics {
                 worker_id: worker_id.clone(),
+                capacity_weight,
                 tenant_count: 0,
                 cpu_usage: 0.0,
                 memory_usage: 0.0,
@@ -88,33 +305,114 @@ impl LoadBalancer {
             },
         );
 
+        drop(worker_loads);
+        self.ring.write().await.add(&worker_id);
+        self.join_rotation(&worker_id).await;
+
+        // A worker registering again after a drain takes tenants again
+        self.drains.write().await.remove(&worker_id);
+        self.heartbeats
+            .write()
+            .await
+            .insert(worker_id.clone(), chrono::Utc::now());
+
         // Update tenant-worker map will happen during assignment
 
-        info!("Added worker {} to load balancer", worker_id);
-        Ok(())
+        info!(
+            "Added worker {} with capacity weight {} to load balancer",
+            worker_id, capacity_weight
+        );
+        self.place_pending_orphans().await
     }
 
-    /// Remove a worker and reassign its tenants
-    pub async fn remove_worker(&self, worker_id: &str) -> Result<Vec<Uuid>> {
+    /// Remove a worker, returning the assignments of its tenants for
+    /// [`LoadBalancer::reassign_orphans`] to place.
+    ///
+    /// Shards held by the worker move to the least loaded remaining workers.
+    pub async fn remove_worker(&self, worker_id: &str) -> Result<Vec<TenantAssignment>> {
+        let excluded = self.excluded_workers().await;
+        self.heartbeats.write().await.remove(worker_id);
         let mut worker_loads = self.worker_loads.write().await;
         worker_loads.remove(worker_id);
+        self.ring.write().await.remove(worker_id);
+        self.leave_rotation(worker_id).await;
+
+        let orphaned_shards = self
+            .worker_assignments
+            .write()
+            .await
+            .remove(worker_id)
+            .map(|assignment| assignment.shards)
+            .unwrap_or_default();
+        for shard in orphaned_shards {
+            match Self::least_loaded_by_tenants(&worker_loads, &excluded) {
+                Some(target) => {
+                    info!(
+                        "Moving shard {}/{} of tenant {} from worker {} to {}",
+                        shard.index + 1,
+                        shard.count,
+                        shard.tenant_id,
+                        worker_id,
+                        target
+                    );
+                    if let Some(load) = worker_loads.get_mut(&target) {
+                        load.tenant_count += 1;
+                    }
+                    self.worker_assignments
+                        .write()
+                        .await
+                        .entry(target.clone())
+                        .or_insert_with(|| WorkerAssignment::new(target))
+                        .add_shard(shard);
+                }
+                None => warn!(
+                    "No workers left for shard {}/{} of tenant {}",
+                    shard.index + 1,
+                    shard.count,
+                    shard.tenant_id
+                ),
+            }
+        }
 
         // Remove from tenant-worker map
         let mut tenant_worker_map = self.tenant_worker_map.write().await;
-        tenant_worker_map.retain(|_, v| v != worker_id);
+        tenant_worker_map.retain(|_, pin| pin.worker_id != worker_id);
 
         // Find tenants assigned to this worker
         let mut assignments = self.assignments.write().await;
-        let mut reassigned_tenants = Vec::new();
+        let mut orphaned = Vec::new();
 
-        assignments.retain(|tenant_id, assignment| {
+        assignments.retain(|_, assignment| {
             if assignment.worker_id == worker_id {
-                reassigned_tenants.push(*tenant_id);
+                orphaned.push(assignment.clone());
                 false
             } else {
                 true
             }
         });
+        let reassigned_tenants: Vec<Uuid> = orphaned
+            .iter()
+            .map(|assignment| assignment.tenant_id)
+            .collect();
+
+        drop(assignments);
+        drop(tenant_worker_map);
+        drop(worker_loads);
+
+        // Its caches are gone with it
+        self.warm_workers.write().await.retain(|_, workers| {
+            workers.remove(worker_id);
+            !workers.is_empty()
+        });
+
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(&reassigned_tenants).await {
+                warn!(
+                    "Failed to drop persisted assignments of worker {}: {}",
+                    worker_id, e
+                );
+            }
+        }
 
         info!(
             "Removed worker {} from load balancer, {} tenants need reassignment",
@@ -122,282 +420,2150 @@ impl LoadBalancer {
             reassigned_tenants.len()
         );
 
-        Ok(reassigned_tenants)
-    }
-
-    /// Update worker load metrics
-    pub async fn update_worker_load(&self, metrics: WorkerMetrics) -> Result<()> {
-        let mut worker_loads = self.worker_loads.write().await;
-        worker_loads.insert(metrics.worker_id.clone(), metrics);
-        Ok(())
-    }
+        if !reassigned_tenants.is_empty() {
+            self.emit(AssignmentEvent::WorkerFailed {
+                worker_id: worker_id.to_string(),
+                orphaned_tenants: reassigned_tenants,
+            });
+        }
 
-    /// Update tenant metrics
-    pub async fn update_tenant_metrics(&self, metrics: TenantMetrics) -> Result<()> {
-        let mut tenant_metrics = self.tenant_metrics.write().await;
-        tenant_metrics.insert(metrics.tenant_id, metrics);
-        Ok(())
+        Ok(orphaned)
     }
 
-    /// Assign a tenant to a worker
-    #[instrument(skip(self))]
-    pub async fn assign_tenant(&self, tenant_id: Uuid) -> Result<String> {
-        let worker_id = match self.config.strategy {
-            LoadBalancingStrategy::RoundRobin => self.round_robin_assignment().await?,
-            LoadBalancingStrategy::LeastLoaded => self.least_loaded_assignment().await?,
-            LoadBalancingStrategy::ConsistentHashing => {
-                self.consistent_hash_assignment(tenant_id).await?
-            }
-            LoadBalancingStrategy::ActivityBased => {
-                self.activity_based_assignment(tenant_id).await?
-            }
-        };
-
-        // Record assignment
-        let mut assignments = self.assignments.write().await;
-        let reason = match self.config.strategy {
-            LoadBalancingStrategy::RoundRobin => AssignmentReason::Initial,
-            LoadBalancingStrategy::LeastLoaded => AssignmentReason::LoadRebalance,
-            LoadBalancingStrategy::ConsistentHashing => AssignmentReason::Initial,
-            LoadBalancingStrategy::ActivityBased => AssignmentReason::LoadRebalance,
+    /// Load the persisted assignments into memory.
+    ///
+    /// Assignments already placed by this load balancer are kept. Workers are
+    /// not registered, only the tenant counts of known workers are updated, so
+    /// a worker starting up sees its previous tenants without taking on the
+    /// tenants of other workers. Returns the number of assignments loaded.
+    pub async fn load_state(&self) -> Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
         };
-        assignments.insert(
-            tenant_id,
-            TenantAssignment::new(tenant_id, worker_id.clone(), reason),
-        );
+        let persisted = store.load_all().await?;
 
-        // Update worker load
         let mut worker_loads = self.worker_loads.write().await;
-        if let Some(load) = worker_loads.get_mut(&worker_id) {
-            load.tenant_count += 1;
+        let mut tenant_worker_map = self.tenant_worker_map.write().await;
+        let mut assignments = self.assignments.write().await;
+        let mut loaded = 0;
+        for (tenant_id, assignment) in persisted {
+            if assignments.contains_key(&tenant_id) {
+                continue;
+            }
+            if let Some(load) = worker_loads.get_mut(&assignment.worker_id) {
+                load.tenant_count += 1;
+            }
+            tenant_worker_map.insert(
+                tenant_id.to_string(),
+                TenantPin::since(&assignment.worker_id, assignment.assigned_at),
+            );
+            assignments.insert(tenant_id, assignment);
+            loaded += 1;
         }
 
-        info!("Assigned tenant {} to worker {}", tenant_id, worker_id);
-        Ok(worker_id)
-    }
-
-    /// Get worker for a tenant
-    pub async fn get_worker_for_tenant(&self, tenant_id: Uuid) -> Option<String> {
-        let assignments = self.assignments.read().await;
-        assignments.get(&tenant_id).map(|a| a.worker_id.clone())
+        info!("Loaded {} persisted assignments", loaded);
+        Ok(loaded)
     }
 
-    /// Check if rebalancing is needed
-    pub async fn needs_rebalancing(&self) -> bool {
-        // Check minimum interval
-        let last_rebalance = *self.last_rebalance.read().await;
-        if chrono::Utc::now() - last_rebalance
-            < chrono::Duration::from_std(self.config.min_rebalance_interval).unwrap()
+    /// Merge the shared view in the assignment store into this process's
+    /// assignments and refresh the live workers of the shared registry, at
+    /// most once per `shared_state_ttl`.
+    ///
+    /// Lets the API and workers in other processes see the assignments made
+    /// by any of them. The shared view is authoritative: tenants missing from
+    /// it were unassigned and are dropped here too, and a tenant takes its
+    /// shared assignment unless the local one is at a higher version and was
+    /// placed no earlier, i.e. is a placement made here that is not shared
+    /// yet. Versions restart once an assignment is removed, so a local copy
+    /// placed before the shared one is stale whatever its version. Tenant
+    /// counts of the workers registered here are recounted from the merged
+    /// assignments. Nothing is read without an assignment store, and the
+    /// local copy is kept if reading fails.
+    async fn sync_shared_state(&self) {
+        let Some(store) = &self.store else {
+            return;
+        };
         {
-            return false;
+            let mut synced_at = self.shared_synced_at.write().await;
+            if synced_at.is_some_and(|at| at.elapsed() < self.config.shared_state_ttl) {
+                return;
+            }
+            *synced_at = Some(std::time::Instant::now());
         }
 
-        // Check load imbalance
-        let worker_loads = self.worker_loads.read().await;
-        if worker_loads.len() < 2 {
-            return false;
-        }
+        let (shared, live_workers) =
+            match tokio::try_join!(store.load_shared(), store.live_workers()) {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!(
+                        "Failed to read shared assignments, keeping the local copy: {}",
+                        e
+                    );
+                    return;
+                }
+            };
 
-        let loads: Vec<f64> = worker_loads
-            .values()
-            .map(|l| l.tenant_count as f64)
-            .collect();
+        {
+            let mut worker_loads = self.worker_loads.write().await;
+            let worker_assignments = self.worker_assignments.read().await;
+            let mut tenant_worker_map = self.tenant_worker_map.write().await;
+            let mut assignments = self.assignments.write().await;
 
-        let avg_load = loads.iter().sum::<f64>() / loads.len() as f64;
-        let max_load = loads.iter().fold(0.0f64, |a, &b| a.max(b));
-        let min_load = loads.iter().fold(f64::MAX, |a, &b| a.min(b));
+            // Pins of placements made elsewhere date from the placement, and
+            // those already expired are not brought back
+            let now = chrono::Utc::now();
+            assignments.retain(|tenant_id, _| shared.contains_key(tenant_id));
+            for (tenant_id, assignment) in shared {
+                if assignments.get(&tenant_id).is_some_and(|local| {
+                    local.version >= assignment.version
+                        && local.assigned_at >= assignment.assigned_at
+                }) {
+                    continue;
+                }
+                let key = tenant_id.to_string();
+                if !tenant_worker_map
+                    .get(&key)
+                    .is_some_and(|pin| pin.worker_id == assignment.worker_id)
+                {
+                    let pin = TenantPin::since(&assignment.worker_id, assignment.assigned_at);
+                    if !self.pin_expired(&pin, now) {
+                        tenant_worker_map.insert(key, pin);
+                    }
+                }
+                assignments.insert(tenant_id, assignment);
+            }
 
-        let imbalance = (max_load - min_load) / avg_load;
-        imbalance > self.config.rebalance_threshold
+            for (worker_id, load) in worker_loads.iter_mut() {
+                let shards = worker_assignments
+                    .get(worker_id)
+                    .map_or(0, |assignment| assignment.shards.len());
+                load.tenant_count = assignments
+                    .values()
+                    .filter(|assignment| &assignment.worker_id == worker_id)
+                    .count()
+                    + shards;
+            }
+        }
+        *self.shared_workers.write().await = live_workers;
     }
 
-    /// Rebalance tenants across workers
-    #[instrument(skip(self))]
-    pub async fn rebalance(&self) -> Result<HashMap<String, Vec<Uuid>>> {
-        info!("Starting tenant rebalancing");
+    /// Capacity weight of a registered worker
+    pub async fn capacity_weight(&self, worker_id: &str) -> Option<f64> {
+        self.worker_loads
+            .read()
+            .await
+            .get(worker_id)
+            .map(|load| load.capacity_weight)
+    }
 
-        let tenant_metrics = self.tenant_metrics.read().await;
-        let worker_loads = self.worker_loads.read().await;
+    /// Capacity weights workers published to the assignment store, empty if
+    /// they cannot be read
+    async fn published_weights(&self, store: &AssignmentStore) -> HashMap<String, f64> {
+        store.load_capacity_weights().await.unwrap_or_else(|e| {
+            warn!("Failed to load worker capacity weights: {}", e);
+            HashMap::new()
+        })
+    }
+
+    /// Capacity weight a worker published, 1 if it published none
+    async fn published_weight(&self, store: &AssignmentStore, worker_id: &str) -> f64 {
+        self.published_weights(store)
+            .await
+            .get(worker_id)
+            .copied()
+            .unwrap_or(1.0)
+    }
 
-        if worker_loads.is_empty() {
-            return Ok(HashMap::new());
+    /// Live standby workers available for promotion; empty without an assignment store
+    pub async fn standby_workers(&self) -> Result<Vec<String>> {
+        match &self.store {
+            Some(store) => store.live_standby_workers().await,
+            None => Ok(Vec::new()),
         }
+    }
 
-        // Group tenants by activity level
-        let mut high_activity = Vec::new();
-        let mut medium_activity = Vec::new();
-        let mut low_activity = Vec::new();
+    /// Promote a live standby worker so tenants can be placed on it.
+    ///
+    /// Returns None if no standby is available. Standbys are claimed through
+    /// the assignment store, so concurrent coordinators never promote the same one.
+    pub async fn promote_standby(
+        &self,
+        replaced_worker_id: Option<&str>,
+    ) -> Result<Option<String>> {
+        let Some(store) = &self.store else {
+            return Ok(None);
+        };
 
-        for (tenant_id, metrics) in tenant_metrics.iter() {
-            let activity_score = metrics.activity_score();
-            if activity_score > 0.7 {
-                high_activity.push((*tenant_id, activity_score));
-            } else if activity_score > 0.3 {
-                medium_activity.push((*tenant_id, activity_score));
-            } else {
-                low_activity.push((*tenant_id, activity_score));
+        for worker_id in store.live_standby_workers().await? {
+            if !store.claim_standby(&worker_id).await? {
+                continue;
             }
+            let capacity_weight = self.published_weight(store, &worker_id).await;
+            self.register_worker(worker_id.clone(), capacity_weight)
+                .await?;
+            info!(
+                "Promoted standby worker {} (replacing {:?})",
+                worker_id, replaced_worker_id
+            );
+            self.emit(AssignmentEvent::StandbyPromoted {
+                worker_id: worker_id.clone(),
+                replaced_worker_id: replaced_worker_id.map(str::to_string),
+            });
+            return Ok(Some(worker_id));
         }
+        Ok(None)
+    }
 
-        // Sort by activity score
-        high_activity.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        medium_activity.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-        low_activity.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    /// Remove a dead worker and move its tenants onto a promoted standby.
+    ///
+    /// The standby takes up to its tenant cap, `max_tenants_per_worker`
+    /// scaled by its capacity weight; the rest, or
+    /// all of them if no standby is available, are placed by the strategy or
+    /// queued until a worker registers if no worker can take them.
+    /// Workers receiving tenants are sent them over the control channel.
+    #[instrument(skip(self))]
+    pub async fn fail_over_worker(&self, worker_id: &str) -> Result<Vec<ReassignedTenant>> {
+        // Promoted first so it is the least loaded target for the dead worker's shards
+        let standby = self.promote_standby(Some(worker_id)).await?;
 
-        // Create new assignments
-        let mut new_assignments: HashMap<String, Vec<Uuid>> = HashMap::new();
-        let worker_ids: Vec<String> = worker_loads.keys().cloned().collect();
-        let mut worker_scores: HashMap<String, f64> = HashMap::new();
+        let orphaned = self.remove_worker(worker_id).await?;
 
-        for worker_id in &worker_ids {
-            new_assignments.insert(worker_id.clone(), Vec::new());
-            worker_scores.insert(worker_id.clone(), 0.0);
+        let mut reassigned = Vec::new();
+        let mut orphaned = orphaned.into_iter();
+        if let Some(standby) = &standby {
+            let tenant_cap = self
+                .worker_loads
+                .read()
+                .await
+                .get(standby)
+                .map_or(self.config.max_tenants_per_worker, |load| {
+                    load.tenant_cap(self.config.max_tenants_per_worker)
+                });
+            for previous in orphaned.by_ref().take(tenant_cap) {
+                let tenant_id = previous.tenant_id;
+                // Recorded as a reassignment off the dead worker
+                self.assignments.write().await.insert(tenant_id, previous);
+                if let Err(e) = self
+                    .record_placement(tenant_id, standby, AssignmentReason::WorkerFailure)
+                    .await
+                {
+                    warn!(
+                        "Failed to move tenant {} from worker {} to standby {}: {}",
+                        tenant_id, worker_id, standby, e
+                    );
+                    continue;
+                }
+                // Keep consistent hashing from moving the tenant off the standby
+                self.tenant_worker_map
+                    .write()
+                    .await
+                    .insert(tenant_id.to_string(), TenantPin::new(standby));
+                reassigned.push(ReassignedTenant {
+                    tenant_id,
+                    previous_worker_id: worker_id.to_string(),
+                    worker_id: standby.clone(),
+                });
+            }
         }
+        reassigned.extend(self.place_orphans(orphaned).await);
 
-        // Assign high activity tenants first, distributing them evenly
-        for (tenant_id, score) in high_activity {
-            let worker_id = worker_scores
-                .iter()
-                .min_by_key(|(_, &score)| (score * 1000.0) as i64)
-                .map(|(id, _)| id.clone())
-                .unwrap();
+        let receivers = standby
+            .iter()
+            .cloned()
+            .chain(reassigned.iter().map(|moved| moved.worker_id.clone()));
+        self.push_to_workers(receivers).await?;
+
+        info!(
+            "Failed over worker {}: {} tenants reassigned, standby {:?}",
+            worker_id,
+            reassigned.len(),
+            standby
+        );
+        Ok(reassigned)
+    }
+
+    /// Place the tenants [`LoadBalancer::remove_worker`] returned for a removed
+    /// worker, sending every worker receiving tenants its new tenants.
+    ///
+    /// Tenants no worker can take, e.g. because no workers are left, are
+    /// queued and placed when the next worker registers instead of being
+    /// dropped. Returns where each placed tenant went.
+    pub async fn reassign_orphans(
+        &self,
+        orphaned: Vec<TenantAssignment>,
+    ) -> Result<Vec<ReassignedTenant>> {
+        let reassigned = self.place_orphans(orphaned).await;
+        self.push_to_workers(reassigned.iter().map(|moved| moved.worker_id.clone()))
+            .await?;
+        Ok(reassigned)
+    }
 
-            new_assignments.get_mut(&worker_id).unwrap().push(tenant_id);
-            *worker_scores.get_mut(&worker_id).unwrap() += score;
+    /// Place orphaned tenants from the assignment each held on the worker it
+    /// was orphaned from, queueing those no worker can take.
+    ///
+    /// Each placement reassigns the previous assignment, so the version keeps
+    /// increasing and wins over copies of it persisted before the failure.
+    async fn place_orphans(
+        &self,
+        orphaned: impl IntoIterator<Item = TenantAssignment>,
+    ) -> Vec<ReassignedTenant> {
+        let mut reassigned = Vec::new();
+        for previous in orphaned {
+            let tenant_id = previous.tenant_id;
+            let previous_worker_id = previous.worker_id.clone();
+            self.assignments
+                .write()
+                .await
+                .insert(tenant_id, previous.clone());
+            match self
+                .place_tenant(tenant_id, Some(AssignmentReason::WorkerFailure))
+                .await
+            {
+                Ok(worker_id) => reassigned.push(ReassignedTenant {
+                    tenant_id,
+                    previous_worker_id,
+                    worker_id,
+                }),
+                Err(e) => {
+                    warn!(
+                        "Queueing tenant {} from worker {} until a worker registers: {}",
+                        tenant_id, previous_worker_id, e
+                    );
+                    self.assignments.write().await.remove(&tenant_id);
+                    self.pending_orphans
+                        .write()
+                        .await
+                        .insert(tenant_id, previous);
+                }
+            }
         }
+        reassigned
+    }
 
-        // Then medium activity
-        for (tenant_id, score) in medium_activity {
-            let worker_id = worker_scores
-                .iter()
-                .min_by_key(|(_, &score)| (score * 1000.0) as i64)
-                .map(|(id, _)| id.clone())
-                .unwrap();
+    /// Place the tenants queued while no worker could take them
+    async fn place_pending_orphans(&self) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending_orphans.write().await);
+        if pending.is_empty() {
+            return Ok(());
+        }
 
-            new_assignments.get_mut(&worker_id).unwrap().push(tenant_id);
-            *worker_scores.get_mut(&worker_id).unwrap() += score;
+        // Tenants placed since they were queued, e.g. by reconciliation, stay put
+        let pending: Vec<TenantAssignment> = {
+            let assignments = self.assignments.read().await;
+            pending
+                .into_values()
+                .filter(|previous| !assignments.contains_key(&previous.tenant_id))
+                .collect()
+        };
+        let reassigned = self.place_orphans(pending).await;
+        if !reassigned.is_empty() {
+            info!(
+                "Placed {} tenants queued while no worker could take them",
+                reassigned.len()
+            );
         }
+        self.push_to_workers(reassigned.iter().map(|moved| moved.worker_id.clone()))
+            .await
+    }
 
-        // Finally low activity
-        for (tenant_id, score) in low_activity {
-            let worker_id = worker_scores
-                .iter()
-                .min_by_key(|(_, &score)| (score * 1000.0) as i64)
-                .map(|(id, _)| id.clone())
-                .unwrap();
+    /// Send each of the given workers all of its tenants
+    async fn push_to_workers(&self, worker_ids: impl IntoIterator<Item = String>) -> Result<()> {
+        let receivers: HashSet<String> = worker_ids.into_iter().collect();
+        let mut distribution = HashMap::new();
+        for receiver in receivers {
+            let tenant_ids = self.get_worker_assignments(&receiver).await?;
+            distribution.insert(receiver, tenant_ids);
+        }
+        self.push_assignments(&distribution).await;
+        Ok(())
+    }
 
-            new_assignments.get_mut(&worker_id).unwrap().push(tenant_id);
-            *worker_scores.get_mut(&worker_id).unwrap() += score;
+    /// Start moving a worker's tenants onto other workers before it is shut down.
+    ///
+    /// The worker gets no new tenants from now on. Its shards and tenants are
+    /// moved in the background onto the workers with the fewest tenants for
+    /// their capacity weight that are below their tenant cap, each target being sent its new
+    /// tenants as they arrive. Once the worker is empty it is told to stop
+    /// over the control channel. If a tenant cannot be placed the drain fails,
+    /// leaving the remaining tenants on the worker and lifting its exclusion.
+    /// Progress is reported by [`LoadBalancer::drain_status`].
+    #[instrument(skip(self))]
+    pub async fn start_drain(
+        self: &Arc<Self>,
+        worker_id: &str,
+    ) -> std::result::Result<WorkerDrain, ServiceError> {
+        if !self.worker_loads.read().await.contains_key(worker_id) {
+            return Err(ServiceError::WorkerNotFound(worker_id.to_string()));
         }
 
-        // Update assignments
-        let mut assignments = self.assignments.write().await;
-        assignments.clear();
+        let drain = {
+            let mut drains = self.drains.write().await;
+            if let Some(drain) = drains.get(worker_id).filter(|d| d.excludes_worker()) {
+                return Err(ServiceError::InvalidState(format!(
+                    "Worker {} is already {} by drain {}",
+                    worker_id,
+                    match drain.state {
+                        DrainState::InProgress => "being drained",
+                        _ => "drained",
+                    },
+                    drain.drain_id
+                )));
+            }
 
-        for (worker_id, tenant_ids) in &new_assignments {
-            for tenant_id in tenant_ids {
-                assignments.insert(
-                    *tenant_id,
-                    TenantAssignment::new(
-                        *tenant_id,
-                        worker_id.clone(),
-                        AssignmentReason::LoadRebalance,
-                    ),
-                );
+            let has_target = self.worker_loads.read().await.keys().any(|id| {
+                id != worker_id && !drains.get(id).is_some_and(WorkerDrain::excludes_worker)
+            });
+            if !has_target {
+                return Err(ServiceError::InvalidState(format!(
+                    "No other worker can take the tenants of worker {}",
+                    worker_id
+                )));
             }
-        }
 
-        *self.last_rebalance.write().await = chrono::Utc::now();
+            let remaining = self.get_worker_assignments(worker_id).await?;
+            let drain = WorkerDrain {
+                drain_id: Uuid::new_v4(),
+                worker_id: worker_id.to_string(),
+                state: DrainState::InProgress,
+                tenants_total: remaining.len(),
+                remaining,
+                moved: Vec::new(),
+                shards_moved: 0,
+                error: None,
+                started_at: chrono::Utc::now(),
+                finished_at: None,
+            };
+            drains.insert(worker_id.to_string(), drain.clone());
+            drain
+        };
 
         info!(
-            "Rebalancing complete. New distribution: {:?}",
-            new_assignments
-                .iter()
-                .map(|(k, v)| (k, v.len()))
-                .collect::<Vec<_>>()
+            "Draining worker {} ({} tenants), drain {}",
+            worker_id, drain.tenants_total, drain.drain_id
         );
+        let load_balancer = self.clone();
+        let worker_id = worker_id.to_string();
+        tokio::spawn(async move { load_balancer.run_drain(&worker_id).await });
+        Ok(drain)
+    }
 
-        Ok(new_assignments)
+    /// Latest drain of a worker
+    pub async fn drain_status(&self, worker_id: &str) -> Option<WorkerDrain> {
+        self.drains.read().await.get(worker_id).cloned()
     }
 
-    /// Round-robin assignment
-    async fn round_robin_assignment(&self) -> Result<String> {
-        let worker_loads = self.worker_loads.read().await;
+    async fn run_drain(&self, worker_id: &str) {
+        let result = self.drain_tenants(worker_id).await;
 
-        worker_loads
-            .iter()
-            .min_by_key(|(_, load)| load.tenant_count)
-            .map(|(id, _)| id.clone())
-            .ok_or_else(|| anyhow::anyhow!("No workers available"))
+        if result.is_ok() {
+            match self.send_control(worker_id, &ControlCommand::Stop).await {
+                Ok(true) => {}
+                Ok(false) => warn!(
+                    "Drained worker {} is not listening for control commands",
+                    worker_id
+                ),
+                Err(e) => warn!("Failed to stop drained worker {}: {}", worker_id, e),
+            }
+        }
+
+        let remaining = self
+            .get_worker_assignments(worker_id)
+            .await
+            .unwrap_or_default();
+        self.update_drain(worker_id, |drain| {
+            drain.remaining = remaining;
+            drain.finished_at = Some(chrono::Utc::now());
+            match &result {
+                Ok(()) => drain.state = DrainState::Completed,
+                Err(e) => {
+                    drain.state = DrainState::Failed;
+                    drain.error = Some(e.to_string());
+                }
+            }
+        })
+        .await;
+
+        match result {
+            Ok(()) => info!("Drained worker {}, told it to stop", worker_id),
+            Err(e) => error!("Drain of worker {} failed: {}", worker_id, e),
+        }
     }
 
-    /// Least loaded assignment
-    async fn least_loaded_assignment(&self) -> Result<String> {
-        let worker_loads = self.worker_loads.read().await;
+    /// Move a draining worker's shards, then its tenants, onto other workers
+    async fn drain_tenants(&self, worker_id: &str) -> Result<()> {
+        let shards = self
+            .worker_assignments
+            .write()
+            .await
+            .get_mut(worker_id)
+            .map(|assignment| std::mem::take(&mut assignment.shards))
+            .unwrap_or_default();
+        if !shards.is_empty() {
+            let excluded = self.excluded_workers().await;
+            let mut receivers = HashSet::from([worker_id.to_string()]);
+            let mut moved = 0;
+            let mut unplaced = Vec::new();
+            {
+                let mut worker_loads = self.worker_loads.write().await;
+                let mut worker_assignments = self.worker_assignments.write().await;
+                for shard in shards {
+                    let Some(target) = Self::least_loaded_by_tenants(&worker_loads, &excluded)
+                    else {
+                        unplaced.push(shard);
+                        continue;
+                    };
+                    if let Some(load) = worker_loads.get_mut(&target) {
+                        load.tenant_count += 1;
+                    }
+                    if let Some(load) = worker_loads.get_mut(worker_id) {
+                        load.tenant_count = load.tenant_count.saturating_sub(1);
+                    }
+                    worker_assignments
+                        .entry(target.clone())
+                        .or_insert_with(|| WorkerAssignment::new(target.clone()))
+                        .add_shard(shard);
+                    receivers.insert(target);
+                    moved += 1;
+                }
+                if let Some(assignment) = worker_assignments.get_mut(worker_id) {
+                    assignment.shards.extend(unplaced.iter().cloned());
+                }
+            }
 
-        worker_loads
-            .iter()
-            .min_by_key(|(_, load)| {
-                (load.cpu_usage * 100.0) as i32
-                    + (load.memory_usage * 100.0) as i32
-                    + load.tenant_count as i32
+            let mut distribution = HashMap::new();
+            for receiver in receivers {
+                let tenant_ids = self.get_worker_assignments(&receiver).await?;
+                distribution.insert(receiver, tenant_ids);
+            }
+            self.push_assignments(&distribution).await;
+
+            if !unplaced.is_empty() {
+                anyhow::bail!("No worker left for {} tenant shards", unplaced.len());
+            }
+            self.update_drain(worker_id, |drain| drain.shards_moved = moved)
+                .await;
+        }
+
+        for tenant_id in self.get_worker_assignments(worker_id).await? {
+            let target = self
+                .drain_target(worker_id)
+                .await
+                .ok_or_else(|| anyhow::anyhow!("No worker has room for tenant {}", tenant_id))?;
+            self.move_tenant(tenant_id, &target, AssignmentReason::Scaling)
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to move tenant {}: {}", tenant_id, e))?;
+
+            self.update_drain(worker_id, |drain| {
+                drain.remaining.retain(|remaining| *remaining != tenant_id);
+                drain.moved.push(ReassignedTenant {
+                    tenant_id,
+                    previous_worker_id: worker_id.to_string(),
+                    worker_id: target,
+                });
             })
-            .map(|(id, _)| id.clone())
-            .ok_or_else(|| anyhow::anyhow!("No workers available"))
+            .await;
+        }
+        Ok(())
     }
 
-    /// Consistent hash assignment
-    async fn consistent_hash_assignment(&self, tenant_id: Uuid) -> Result<String> {
-        let tenant_worker_map = self.tenant_worker_map.read().await;
+    /// Worker with the fewest tenants for its capacity weight that is below
+    /// its tenant cap and not drained
+    async fn drain_target(&self, worker_id: &str) -> Option<String> {
+        let excluded = self.excluded_workers().await;
         let worker_loads = self.worker_loads.read().await;
+        let assignments = self.assignments.read().await;
 
-        // Check if tenant already has an assigned worker
-        if let Some(worker_id) = tenant_worker_map.get(&tenant_id.to_string()) {
-            if worker_loads.contains_key(worker_id) {
-                return Ok(worker_id.clone());
+        let mut counts: HashMap<&str, WorkerMetrics> = worker_loads
+            .iter()
+            .filter(|(id, _)| *id != worker_id && !excluded.contains(id))
+            .map(|(id, load)| {
+                let mut load = load.clone();
+                load.tenant_count = 0;
+                (id.as_str(), load)
+            })
+            .collect();
+        for assignment in assignments.values() {
+            if let Some(load) = counts.get_mut(assignment.worker_id.as_str()) {
+                load.tenant_count += 1;
             }
         }
 
-        // If not, use simple hash-based assignment
-        let workers: Vec<String> = worker_loads.keys().cloned().collect();
-        if workers.is_empty() {
-            return Err(anyhow::anyhow!("No workers available"));
-        }
-
-        // Hash the tenant ID to select a worker
-        let mut hasher = DefaultHasher::new();
-        tenant_id.to_string().hash(&mut hasher);
-        let hash = hasher.finish();
-        let index = (hash as usize) % workers.len();
+        counts
+            .into_iter()
+            .filter(|(_, load)| load.has_room(self.config.max_tenants_per_worker))
+            .min_by(|(a_id, a), (b_id, b)| {
+                a.weighted_tenants()
+                    .total_cmp(&b.weighted_tenants())
+                    .then(a_id.cmp(b_id))
+            })
+            .map(|(id, _)| id.to_string())
+    }
 
-        Ok(workers[index].clone())
+    async fn update_drain(&self, worker_id: &str, update: impl FnOnce(&mut WorkerDrain)) {
+        if let Some(drain) = self.drains.write().await.get_mut(worker_id) {
+            update(drain);
+        }
     }
 
-    /// Activity-based assignment
-    async fn activity_based_assignment(&self, tenant_id: Uuid) -> Result<String> {
-        let tenant_metrics = self.tenant_metrics.read().await;
-        let _worker_loads = self.worker_loads.read().await;
+    /// Workers being drained or already drained
+    async fn excluded_workers(&self) -> Vec<String> {
+        self.drains
+            .read()
+            .await
+            .values()
+            .filter(|drain| drain.excludes_worker())
+            .map(|drain| drain.worker_id.clone())
+            .collect()
+    }
 
-        // Default to least loaded if no metrics
-        if let Some(metrics) = tenant_metrics.get(&tenant_id) {
-            let activity_score = metrics.activity_score();
+    /// Record that a worker is alive, in the assignment store if there is one
+    pub async fn heartbeat(&self, worker_id: &str) -> Result<()> {
+        if let Some(heartbeat) = self.heartbeats.write().await.get_mut(worker_id) {
+            *heartbeat = chrono::Utc::now();
+        }
+        if let Some(store) = &self.store {
+            store.heartbeat(worker_id).await?;
+        }
+        Ok(())
+    }
 
-            // High activity tenants go to least loaded workers
-            if activity_score > 0.7 {
-                return self.least_loaded_assignment().await;
+    /// Send a worker's heartbeat every `interval` until the task is aborted
+    pub fn spawn_heartbeat(
+        self: &Arc<Self>,
+        worker_id: String,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let load_balancer = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = load_balancer.heartbeat(&worker_id).await {
+                    warn!("Failed to record heartbeat of worker {}: {}", worker_id, e);
+                }
             }
-        }
+        })
+    }
 
-        // Low activity tenants use consistent hashing for affinity
-        self.consistent_hash_assignment(tenant_id).await
+    /// Registered workers whose last heartbeat is older than `worker_timeout`
+    async fn silent_workers(&self) -> Vec<String> {
+        let now = chrono::Utc::now();
+        let mut silent: Vec<String> = self
+            .heartbeats
+            .read()
+            .await
+            .iter()
+            .filter(|(_, last_heartbeat)| {
+                (now - **last_heartbeat)
+                    .to_std()
+                    .is_ok_and(|silence| silence > self.config.worker_timeout)
+            })
+            .map(|(worker_id, _)| worker_id.clone())
+            .collect();
+        silent.sort();
+        silent
     }
 
-    /// Get all tenant assignments for a specific worker
-    pub async fn get_worker_assignments(&self, worker_id: &str) -> Result<Vec<Uuid>> {
-        let assignments = self.assignments.read().await;
+    /// Remove a worker that stopped sending heartbeats and move its tenants
+    /// onto the remaining workers
+    async fn evict_worker(&self, worker_id: &str) -> Result<Vec<ReassignedTenant>> {
+        warn!(
+            "Worker {} sent no heartbeat for {:?}, evicting it",
+            worker_id, self.config.worker_timeout
+        );
+        metrics::WORKERS_EVICTED
+            .with_label_values(&[worker_id])
+            .inc();
+        self.fail_over_worker(worker_id).await
+    }
 
-        let tenant_ids: Vec<Uuid> = assignments
-            .iter()
+    /// Evict workers whose heartbeat stopped and promote a standby when the
+    /// pool needs more workers.
+    ///
+    /// Meant to run periodically on the coordinator. With an assignment
+    /// store, heartbeats are read from it and live workers registered by
+    /// other processes are added first; nothing is done while another
+    /// coordinator holds the rebalance lock. Without one, workers are evicted
+    /// by the heartbeats sent to this load balancer.
+    pub async fn supervise_workers(&self) -> Result<()> {
+        let Some(store) = &self.store else {
+            for worker_id in self.silent_workers().await {
+                self.evict_worker(&worker_id).await?;
+            }
+            return Ok(());
+        };
+        let guard = match &self.rebalance_lock {
+            Some(lock) => match lock.try_acquire().await? {
+                Some(guard) => Some(guard),
+                None => return Ok(()),
+            },
+            None => None,
+        };
+
+        let result = self.apply_supervision(store).await;
+
+        if let Some(guard) = guard {
+            if let Err(e) = guard.release().await {
+                warn!("Failed to release rebalance lock: {}", e);
+            }
+        }
+        result
+    }
+
+    async fn apply_supervision(&self, store: &AssignmentStore) -> Result<()> {
+        let live_workers = store.live_workers().await?;
+        // Workers registered here, not those only seen in the shared registry
+        let registered: Vec<String> = self.worker_loads.read().await.keys().cloned().collect();
+
+        let weights = self.published_weights(store).await;
+        for worker_id in &live_workers {
+            if !registered.contains(worker_id) {
+                let capacity_weight = weights.get(worker_id).copied().unwrap_or(1.0);
+                self.register_worker(worker_id.clone(), capacity_weight)
+                    .await?;
+            }
+        }
+        {
+            // Weights of workers that registered again with a new one
+            let mut worker_loads = self.worker_loads.write().await;
+            for (worker_id, capacity_weight) in &weights {
+                if let Some(load) = worker_loads.get_mut(worker_id) {
+                    load.capacity_weight = *capacity_weight;
+                }
+            }
+        }
+
+        for worker_id in registered
+            .iter()
+            .filter(|worker_id| !live_workers.contains(*worker_id))
+        {
+            self.evict_worker(worker_id).await?;
+        }
+
+        // Workers in other processes publish their usage next to their heartbeat
+        match store.load_usage().await {
+            Ok(usage) => {
+                for (worker_id, usage) in usage {
+                    self.record_worker_usage(&worker_id, &usage).await;
+                }
+            }
+            Err(e) => warn!("Failed to load worker resource usage: {}", e),
+        }
+        match store.load_tenant_metrics().await {
+            Ok(metrics) => {
+                let mut tenant_metrics = self.tenant_metrics.write().await;
+                for (tenant_id, metrics) in metrics {
+                    keep_newest(&mut tenant_metrics, tenant_id, metrics);
+                }
+            }
+            Err(e) => warn!("Failed to load tenant metrics: {}", e),
+        }
+
+        if self.capacity().await.action == ScalingAction::ScaleUp {
+            if let Some(worker_id) = self.promote_standby(None).await? {
+                info!(
+                    "Worker pool over target utilization, rebalancing onto {}",
+                    worker_id
+                );
+                self.apply_rebalance().await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Update worker load metrics
+    pub async fn update_worker_load(&self, metrics: WorkerMetrics) -> Result<()> {
+        let mut worker_loads = self.worker_loads.write().await;
+        let worker_id = metrics.worker_id.clone();
+        if worker_loads.insert(worker_id.clone(), metrics).is_none() {
+            self.ring.write().await.add(&worker_id);
+            self.join_rotation(&worker_id).await;
+        }
+        Ok(())
+    }
+
+    /// Record a worker's sampled CPU and memory usage, keeping its other metrics.
+    ///
+    /// Usage of workers not registered with this load balancer is ignored.
+    pub async fn record_worker_usage(&self, worker_id: &str, usage: &WorkerUsage) {
+        if let Some(load) = self.worker_loads.write().await.get_mut(worker_id) {
+            load.cpu_usage = usage.cpu_usage;
+            load.memory_usage = usage.memory_usage;
+            load.collected_at = usage.collected_at;
+        }
+    }
+
+    /// Update tenant metrics
+    pub async fn update_tenant_metrics(&self, metrics: TenantMetrics) -> Result<()> {
+        let mut tenant_metrics = self.tenant_metrics.write().await;
+        tenant_metrics.insert(metrics.tenant_id, metrics);
+        Ok(())
+    }
+
+    /// Latest metrics of a tenant, including those published by workers in
+    /// other processes since the last supervision run
+    pub async fn latest_tenant_metrics(&self, tenant_id: Uuid) -> Option<TenantMetrics> {
+        let published = match &self.store {
+            Some(store) => match store.tenant_metrics(tenant_id).await {
+                Ok(metrics) => metrics,
+                Err(e) => {
+                    warn!("Failed to load metrics of tenant {}: {}", tenant_id, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let mut tenant_metrics = self.tenant_metrics.write().await;
+        if let Some(published) = published {
+            keep_newest(&mut tenant_metrics, tenant_id, published);
+        }
+        tenant_metrics.get(&tenant_id).cloned()
+    }
+
+    /// Assign a tenant to a worker
+    #[instrument(skip(self))]
+    pub async fn assign_tenant(&self, tenant_id: Uuid) -> Result<String> {
+        self.place_tenant(tenant_id, None).await
+    }
+
+    /// Assign a tenant, recording `reason` instead of the strategy's default reason
+    async fn place_tenant(
+        &self,
+        tenant_id: Uuid,
+        reason: Option<AssignmentReason>,
+    ) -> Result<String> {
+        let excluded = self.excluded_workers().await;
+
+        // A returning tenant goes back to a worker whose caches may still be warm
+        if let Some(worker_id) = self.warm_worker(tenant_id, &excluded).await {
+            info!(
+                "Placing tenant {} on worker {} it recently left",
+                tenant_id, worker_id
+            );
+            // Keep consistent hashing from moving the tenant off it
+            self.tenant_worker_map
+                .write()
+                .await
+                .insert(tenant_id.to_string(), TenantPin::new(&worker_id));
+            let reason = reason.unwrap_or(AssignmentReason::Initial);
+            self.record_placement(tenant_id, &worker_id, reason).await?;
+            return Ok(worker_id);
+        }
+
+        let worker_id = match &self.config.strategy {
+            LoadBalancingStrategy::RoundRobin => self.round_robin_assignment().await?,
+            LoadBalancingStrategy::LeastLoaded => self.least_loaded_assignment().await?,
+            LoadBalancingStrategy::ConsistentHashing => {
+                self.consistent_hash_assignment(tenant_id).await?
+            }
+            LoadBalancingStrategy::ActivityBased => {
+                self.activity_based_assignment(tenant_id).await?
+            }
+            LoadBalancingStrategy::Custom(name) => self.custom_assignment(name, tenant_id).await?,
+        };
+        let worker_id = if excluded.contains(&worker_id) {
+            Self::least_loaded_by_tenants(&*self.worker_loads.read().await, &excluded)
+                .ok_or_else(|| anyhow::anyhow!("No workers available outside drained workers"))?
+        } else {
+            worker_id
+        };
+
+        let reason = reason.unwrap_or(match &self.config.strategy {
+            LoadBalancingStrategy::RoundRobin => AssignmentReason::Initial,
+            LoadBalancingStrategy::LeastLoaded => AssignmentReason::LoadRebalance,
+            LoadBalancingStrategy::ConsistentHashing => AssignmentReason::Initial,
+            LoadBalancingStrategy::ActivityBased => AssignmentReason::LoadRebalance,
+            LoadBalancingStrategy::Custom(_) => AssignmentReason::Initial,
+        });
+        self.record_placement(tenant_id, &worker_id, reason).await?;
+        Ok(worker_id)
+    }
+
+    /// Record a tenant's placement on a worker, persisting it and emitting the
+    /// event; fails without emitting it if another writer placed the tenant
+    /// since, see [`LoadBalancer::persist`]
+    async fn record_placement(
+        &self,
+        tenant_id: Uuid,
+        worker_id: &str,
+        reason: AssignmentReason,
+    ) -> std::result::Result<(), ServiceError> {
+        // Record assignment
+        let mut assignments = self.assignments.write().await;
+        let assignment = match assignments.get(&tenant_id) {
+            Some(previous) => previous.reassign(worker_id.to_string(), reason),
+            None => TenantAssignment::new(tenant_id, worker_id.to_string(), reason),
+        };
+        let previous = assignments.insert(tenant_id, assignment.clone());
+        drop(assignments);
+        self.record_history(&assignment).await;
+        self.track_warm_worker(
+            tenant_id,
+            previous
+                .as_ref()
+                .map(|previous| previous.worker_id.as_str()),
+            Some(worker_id),
+        )
+        .await;
+
+        // Update worker loads, moving the tenant's count off its previous worker
+        let previous_worker_id = previous
+            .as_ref()
+            .map(|previous| previous.worker_id.as_str());
+        if previous_worker_id != Some(worker_id) {
+            let mut worker_loads = self.worker_loads.write().await;
+            if let Some(load) = previous_worker_id.and_then(|id| worker_loads.get_mut(id)) {
+                load.tenant_count = load.tenant_count.saturating_sub(1);
+            }
+            if let Some(load) = worker_loads.get_mut(worker_id) {
+                load.tenant_count += 1;
+            }
+        }
+
+        self.persist(&assignment).await?;
+
+        match previous {
+            Some(previous) if previous.worker_id != worker_id => {
+                self.emit(AssignmentEvent::TenantReassigned {
+                    previous_worker_id: previous.worker_id,
+                    assignment,
+                });
+            }
+            Some(_) => {}
+            None => self.emit(AssignmentEvent::TenantAssigned { assignment }),
+        }
+
+        info!("Assigned tenant {} to worker {}", tenant_id, worker_id);
+        Ok(())
+    }
+
+    /// Persist an assignment made by this load balancer.
+    ///
+    /// If another writer placed the tenant since, the assignment it stored
+    /// replaces this one here and a conflict is returned. Other store
+    /// failures are only logged.
+    async fn persist(
+        &self,
+        assignment: &TenantAssignment,
+    ) -> std::result::Result<(), ServiceError> {
+        let Some(store) = &self.store else {
+            return Ok(());
+        };
+        let Err(e) = store.save(assignment).await else {
+            return Ok(());
+        };
+        if e.downcast_ref::<StaleAssignment>().is_none() {
+            warn!(
+                "Failed to persist assignment of tenant {}: {}",
+                assignment.tenant_id, e
+            );
+            return Ok(());
+        }
+
+        match store.load(assignment.tenant_id).await {
+            Ok(Some(stored)) => self.adopt_stored(assignment, stored).await,
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Failed to reload the stored assignment of tenant {}: {}",
+                assignment.tenant_id, e
+            ),
+        }
+        Err(ServiceError::InvalidState(format!(
+            "Tenant {} was placed by another writer, {}",
+            assignment.tenant_id, e
+        )))
+    }
+
+    /// Replace an assignment that lost to another writer with the one it stored
+    async fn adopt_stored(&self, lost: &TenantAssignment, stored: TenantAssignment) {
+        let mut worker_loads = self.worker_loads.write().await;
+        let mut tenant_worker_map = self.tenant_worker_map.write().await;
+        let mut assignments = self.assignments.write().await;
+        let current = match assignments.get(&stored.tenant_id) {
+            Some(current) if current.version <= stored.version => current.worker_id.clone(),
+            // Placed again or unassigned here since
+            _ => return,
+        };
+
+        if current != stored.worker_id {
+            if let Some(load) = worker_loads.get_mut(&current) {
+                load.tenant_count = load.tenant_count.saturating_sub(1);
+            }
+            if let Some(load) = worker_loads.get_mut(&stored.worker_id) {
+                load.tenant_count += 1;
+            }
+        }
+        tenant_worker_map.insert(
+            stored.tenant_id.to_string(),
+            TenantPin::since(&stored.worker_id, stored.assigned_at),
+        );
+        warn!(
+            "Tenant {} was placed on worker {} by another writer, not on {}",
+            stored.tenant_id, stored.worker_id, lost.worker_id
+        );
+        assignments.insert(stored.tenant_id, stored);
+    }
+
+    /// Append an assignment change to its tenant's history, dropping the
+    /// oldest entries past `max_assignment_history`
+    async fn record_history(&self, assignment: &TenantAssignment) {
+        let mut history = self.history.write().await;
+        let entries = history.entry(assignment.tenant_id).or_default();
+        entries.push_back(assignment.clone());
+        while entries.len() > self.config.max_assignment_history.max(1) {
+            entries.pop_front();
+        }
+    }
+
+    /// Latest assignment changes of a tenant, oldest first, with the reason and
+    /// version of each; at most `limit` of them.
+    ///
+    /// Read from the assignment store when there is one, so changes made by
+    /// other coordinators and before a restart are included, and from this
+    /// coordinator's in-memory history otherwise.
+    pub async fn assignment_history(
+        &self,
+        tenant_id: Uuid,
+        limit: usize,
+    ) -> Result<Vec<TenantAssignment>> {
+        if let Some(store) = &self.store {
+            return store.history(tenant_id, limit).await;
+        }
+
+        let history = self.history.read().await;
+        let entries = history.get(&tenant_id);
+        let skip = entries.map_or(0, |entries| entries.len().saturating_sub(limit));
+        Ok(entries.into_iter().flatten().skip(skip).cloned().collect())
+    }
+
+    /// Move a tenant to a specific worker, bypassing the placement strategy.
+    ///
+    /// Returns the new assignment and the worker the tenant was taken from.
+    /// Both workers are sent their updated tenants over the control channel,
+    /// unless another writer placed the tenant meanwhile, which is a conflict.
+    #[instrument(skip(self))]
+    pub async fn assign_tenant_to_worker(
+        &self,
+        tenant_id: Uuid,
+        worker_id: &str,
+    ) -> std::result::Result<(TenantAssignment, Option<String>), ServiceError> {
+        self.move_tenant(tenant_id, worker_id, AssignmentReason::Manual)
+            .await
+    }
+
+    /// Move a tenant to a worker with room for it, recording `reason`
+    async fn move_tenant(
+        &self,
+        tenant_id: Uuid,
+        worker_id: &str,
+        reason: AssignmentReason,
+    ) -> std::result::Result<(TenantAssignment, Option<String>), ServiceError> {
+        if self.is_sharded(&tenant_id) {
+            return Err(ServiceError::InvalidState(format!(
+                "Tenant {} is split across workers and cannot be assigned to one",
+                tenant_id
+            )));
+        }
+        // Read before taking the assignments, which are locked after worker loads
+        let tenant_cap = match self.worker_loads.read().await.get(worker_id) {
+            Some(load) => load.tenant_cap(self.config.max_tenants_per_worker),
+            None => return Err(ServiceError::WorkerNotFound(worker_id.to_string())),
+        };
+        if self
+            .excluded_workers()
+            .await
+            .iter()
+            .any(|id| id == worker_id)
+        {
+            return Err(ServiceError::InvalidState(format!(
+                "Worker {} is drained and takes no tenants",
+                worker_id
+            )));
+        }
+
+        let mut assignments = self.assignments.write().await;
+        let current = assignments.get(&tenant_id).cloned();
+        if let Some(current) = current.as_ref().filter(|a| a.worker_id == worker_id) {
+            return Ok((current.clone(), None));
+        }
+
+        let assigned = assignments
+            .values()
+            .filter(|assignment| assignment.worker_id == worker_id)
+            .count();
+        if assigned >= tenant_cap {
+            return Err(ServiceError::ResourceLimitExceeded(format!(
+                "Worker {} already has {} tenants (max {})",
+                worker_id, assigned, tenant_cap
+            )));
+        }
+
+        let assignment = match &current {
+            Some(previous) => previous.reassign(worker_id.to_string(), reason.clone()),
+            None => TenantAssignment::new(tenant_id, worker_id.to_string(), reason.clone()),
+        };
+        assignments.insert(tenant_id, assignment.clone());
+        drop(assignments);
+        self.record_history(&assignment).await;
+
+        let previous_worker_id = current.map(|previous| previous.worker_id);
+        self.track_warm_worker(tenant_id, previous_worker_id.as_deref(), Some(worker_id))
+            .await;
+        {
+            let mut worker_loads = self.worker_loads.write().await;
+            if let Some(load) = previous_worker_id
+                .as_ref()
+                .and_then(|previous| worker_loads.get_mut(previous))
+            {
+                load.tenant_count = load.tenant_count.saturating_sub(1);
+            }
+            if let Some(load) = worker_loads.get_mut(worker_id) {
+                load.tenant_count += 1;
+            }
+        }
+
+        // Keep consistent hashing from moving the tenant back
+        self.tenant_worker_map
+            .write()
+            .await
+            .insert(tenant_id.to_string(), TenantPin::new(worker_id));
+
+        self.persist(&assignment).await?;
+
+        match &previous_worker_id {
+            Some(previous) => self.emit(AssignmentEvent::TenantReassigned {
+                previous_worker_id: previous.clone(),
+                assignment: assignment.clone(),
+            }),
+            None => self.emit(AssignmentEvent::TenantAssigned {
+                assignment: assignment.clone(),
+            }),
+        }
+
+        let mut distribution = HashMap::new();
+        for affected in std::iter::once(worker_id).chain(previous_worker_id.as_deref()) {
+            distribution.insert(
+                affected.to_string(),
+                self.get_worker_assignments(affected).await?,
+            );
+        }
+        self.push_assignments(&distribution).await;
+
+        info!(
+            "Moved tenant {} to worker {} (previously {:?}, reason {:?})",
+            tenant_id, worker_id, previous_worker_id, reason
+        );
+        Ok((assignment, previous_worker_id))
+    }
+
+    /// Take a tenant off every worker holding it or one of its shards.
+    ///
+    /// Returns the affected workers, which are sent their remaining tenants
+    /// over the control channel. The tenant keeps its consistent hashing
+    /// affinity, so assigning it again usually places it where it was.
+    #[instrument(skip(self))]
+    pub async fn unassign_tenant(&self, tenant_id: Uuid) -> Result<Vec<String>> {
+        self.pending_orphans.write().await.remove(&tenant_id);
+        let mut worker_ids = Vec::new();
+        let removed = self.assignments.write().await.remove(&tenant_id);
+        {
+            let mut worker_loads = self.worker_loads.write().await;
+
+            if let Some(assignment) = removed {
+                if let Some(load) = worker_loads.get_mut(&assignment.worker_id) {
+                    load.tenant_count = load.tenant_count.saturating_sub(1);
+                }
+                worker_ids.push(assignment.worker_id);
+            }
+
+            for (worker_id, assignment) in self.worker_assignments.write().await.iter_mut() {
+                let released = assignment.remove_shards(&tenant_id).len();
+                if released == 0 {
+                    continue;
+                }
+                if let Some(load) = worker_loads.get_mut(worker_id) {
+                    load.tenant_count = load.tenant_count.saturating_sub(released);
+                }
+                if !worker_ids.contains(worker_id) {
+                    worker_ids.push(worker_id.clone());
+                }
+            }
+        }
+
+        if worker_ids.is_empty() {
+            return Ok(worker_ids);
+        }
+        worker_ids.sort();
+        for worker_id in &worker_ids {
+            self.track_warm_worker(tenant_id, Some(worker_id), None)
+                .await;
+        }
+
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(&[tenant_id]).await {
+                warn!(
+                    "Failed to remove persisted assignment of tenant {}: {}",
+                    tenant_id, e
+                );
+            }
+        }
+
+        self.emit(AssignmentEvent::TenantUnassigned {
+            tenant_id,
+            worker_ids: worker_ids.clone(),
+        });
+
+        let mut distribution = HashMap::new();
+        for worker_id in &worker_ids {
+            distribution.insert(
+                worker_id.clone(),
+                self.get_worker_assignments(worker_id).await?,
+            );
+        }
+        self.push_assignments(&distribution).await;
+
+        info!(
+            "Unassigned tenant {} from workers {:?}",
+            tenant_id, worker_ids
+        );
+        Ok(worker_ids)
+    }
+
+    /// Workers holding a tenant or one of its shards, sorted
+    pub async fn workers_for_tenant(&self, tenant_id: Uuid) -> Vec<String> {
+        let mut worker_ids: Vec<String> = self
+            .get_worker_for_tenant(tenant_id)
+            .await
+            .into_iter()
+            .collect();
+        for (worker_id, assignment) in self.worker_assignments.read().await.iter() {
+            if assignment.shards.iter().any(|s| s.tenant_id == tenant_id) {
+                worker_ids.push(worker_id.clone());
+            }
+        }
+        worker_ids.sort();
+        worker_ids.dedup();
+        worker_ids
+    }
+
+    /// Check if a tenant is configured to be split across workers
+    pub fn is_sharded(&self, tenant_id: &Uuid) -> bool {
+        self.config.sharded_tenants.contains_key(tenant_id)
+    }
+
+    /// Split a sharded tenant across the least loaded workers.
+    ///
+    /// Shards are spread over distinct workers where possible; with fewer
+    /// workers than shards, some workers hold several shards.
+    #[instrument(skip(self))]
+    pub async fn assign_tenant_shards(
+        &self,
+        tenant_id: Uuid,
+    ) -> Result<Vec<(String, TenantShard)>> {
+        let sharding = self
+            .config
+            .sharded_tenants
+            .get(&tenant_id)
+            .copied()
+            .ok_or_else(|| {
+                anyhow::anyhow!("Tenant {} is not configured for sharding", tenant_id)
+            })?;
+
+        let mut worker_loads = self.worker_loads.write().await;
+        let mut worker_assignments = self.worker_assignments.write().await;
+
+        // Release any previous placement of this tenant's shards
+        for (worker_id, assignment) in worker_assignments.iter_mut() {
+            let released = assignment.remove_shards(&tenant_id).len();
+            if let Some(load) = worker_loads.get_mut(worker_id) {
+                load.tenant_count = load.tenant_count.saturating_sub(released);
+            }
+        }
+
+        let mut placements = Vec::new();
+        let mut used: Vec<String> = Vec::new();
+        for index in 0..sharding.shard_count {
+            if used.len() == worker_loads.len() {
+                used.clear();
+            }
+            let worker_id = Self::least_loaded_by_tenants(&worker_loads, &used)
+                .ok_or_else(|| anyhow::anyhow!("No workers available"))?;
+
+            let shard = TenantShard::new(tenant_id, sharding.shard_by, index, sharding.shard_count);
+            worker_assignments
+                .entry(worker_id.clone())
+                .or_insert_with(|| WorkerAssignment::new(worker_id.clone()))
+                .add_shard(shard.clone());
+            if let Some(load) = worker_loads.get_mut(&worker_id) {
+                load.tenant_count += 1;
+            }
+
+            used.push(worker_id.clone());
+            placements.push((worker_id, shard));
+        }
+
+        info!(
+            "Split tenant {} into {} shards by {:?}",
+            tenant_id, sharding.shard_count, sharding.shard_by
+        );
+        Ok(placements)
+    }
+
+    /// Get the tenant shards assigned to a worker
+    pub async fn get_worker_shards(&self, worker_id: &str) -> Vec<TenantShard> {
+        self.worker_assignments
+            .read()
+            .await
+            .get(worker_id)
+            .map(|assignment| assignment.shards.clone())
+            .unwrap_or_default()
+    }
+
+    /// Record a tenant leaving `from` for `to`: `from` may still have the
+    /// tenant's caches warm, while `to` now holds the tenant.
+    async fn track_warm_worker(&self, tenant_id: Uuid, from: Option<&str>, to: Option<&str>) {
+        if self.config.affinity_ttl.is_zero() || from == to {
+            return;
+        }
+        // Unregistered workers are dead and took their caches with them
+        let from = match from {
+            Some(from) if self.worker_loads.read().await.contains_key(from) => Some(from),
+            _ => None,
+        };
+
+        let mut warm_workers = self.warm_workers.write().await;
+        let workers = warm_workers.entry(tenant_id).or_default();
+        if let Some(from) = from {
+            workers.insert(from.to_string(), chrono::Utc::now());
+        }
+        if let Some(to) = to {
+            workers.remove(to);
+        }
+        if workers.is_empty() {
+            warm_workers.remove(&tenant_id);
+        }
+    }
+
+    /// Worker that most recently gave up the tenant within `affinity_ttl`
+    /// and can take it back
+    async fn warm_worker(&self, tenant_id: Uuid, excluded: &[String]) -> Option<String> {
+        let ttl = chrono::Duration::from_std(self.config.affinity_ttl).ok()?;
+        let cutoff = chrono::Utc::now() - ttl;
+
+        let mut warm_workers = self.warm_workers.write().await;
+        let workers = warm_workers.get_mut(&tenant_id)?;
+        workers.retain(|_, released_at| *released_at > cutoff);
+        if workers.is_empty() {
+            warm_workers.remove(&tenant_id);
+            return None;
+        }
+
+        let worker_loads = self.worker_loads.read().await;
+        workers
+            .iter()
+            .filter(|(worker_id, _)| !excluded.contains(worker_id))
+            .filter(|(worker_id, _)| {
+                worker_loads
+                    .get(*worker_id)
+                    .is_some_and(|load| load.has_room(self.config.max_tenants_per_worker))
+            })
+            .max_by_key(|(_, released_at)| **released_at)
+            .map(|(worker_id, _)| worker_id.clone())
+    }
+
+    /// Worker with the fewest tenants, skipping the excluded ones
+    fn least_loaded_by_tenants(
+        worker_loads: &HashMap<String, WorkerMetrics>,
+        excluded: &[String],
+    ) -> Option<String> {
+        worker_loads
+            .iter()
+            .filter(|(id, _)| !excluded.contains(id))
+            .min_by(|(a_id, a), (b_id, b)| {
+                a.weighted_tenants()
+                    .total_cmp(&b.weighted_tenants())
+                    .then(a_id.cmp(b_id))
+            })
+            .map(|(id, _)| id.clone())
+    }
+
+    /// Latest load metrics of every registered worker
+    pub async fn worker_metrics(&self) -> Vec<WorkerMetrics> {
+        self.sync_shared_state().await;
+        self.worker_loads.read().await.values().cloned().collect()
+    }
+
+    /// Latest activity metrics of every tenant that reported them
+    pub async fn tenant_metrics(&self) -> Vec<TenantMetrics> {
+        self.tenant_metrics.read().await.values().cloned().collect()
+    }
+
+    /// System metrics derived from the load balancer's in-memory state.
+    ///
+    /// Cache hit rate and block lag are not known here and are left at zero;
+    /// the caller fills them in before computing the health score.
+    pub async fn system_metrics(&self) -> SystemMetrics {
+        self.sync_shared_state().await;
+        let worker_loads = self.worker_loads.read().await;
+        let tenant_metrics = self.tenant_metrics.read().await;
+
+        SystemMetrics {
+            active_workers: worker_loads.len(),
+            active_tenants: self.assignments.read().await.len(),
+            total_monitors: tenant_metrics.values().map(|m| m.monitors_count).sum(),
+            total_rpc_rate: worker_loads.values().map(|m| m.rpc_rate).sum(),
+            cache_hit_rate: 0.0,
+            avg_block_lag: 0.0,
+            total_matches_last_hour: tenant_metrics
+                .values()
+                .map(|m| m.total_matches_last_hour)
+                .sum(),
+            health_score: 0.0,
+            collected_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Utilization and headroom of every worker with a suggested worker count
+    pub async fn capacity(&self) -> CapacityReport {
+        let workers = self.worker_metrics().await;
+        CapacityReport::plan(
+            &workers,
+            CapacityTargets {
+                max_tenants_per_worker: self.config.max_tenants_per_worker,
+                target_utilization: self.config.target_utilization,
+                min_workers: self.config.min_workers,
+                max_workers: self.config.max_workers,
+            },
+        )
+    }
+
+    /// Get the identifiers of all registered workers, including live workers
+    /// registered by other processes
+    pub async fn worker_ids(&self) -> Vec<String> {
+        self.sync_shared_state().await;
+        let mut worker_ids: BTreeSet<String> =
+            self.worker_loads.read().await.keys().cloned().collect();
+        worker_ids.extend(self.shared_workers.read().await.iter().cloned());
+        worker_ids.into_iter().collect()
+    }
+
+    /// Current assignment of every tenant placed on a single worker
+    pub async fn tenant_assignments(&self) -> HashMap<Uuid, TenantAssignment> {
+        self.sync_shared_state().await;
+        self.assignments.read().await.clone()
+    }
+
+    /// Every tenant placed on a single worker, in order of assignment time
+    pub async fn all_assignments(&self) -> Vec<TenantAssignment> {
+        self.sync_shared_state().await;
+        let mut assignments: Vec<TenantAssignment> =
+            self.assignments.read().await.values().cloned().collect();
+        assignments.sort_by(|a, b| {
+            a.assigned_at
+                .cmp(&b.assigned_at)
+                .then(a.tenant_id.cmp(&b.tenant_id))
+        });
+        assignments
+    }
+
+    /// Get worker for a tenant
+    pub async fn get_worker_for_tenant(&self, tenant_id: Uuid) -> Option<String> {
+        self.sync_shared_state().await;
+        let assignments = self.assignments.read().await;
+        assignments.get(&tenant_id).map(|a| a.worker_id.clone())
+    }
+
+    /// Check if rebalancing is needed.
+    ///
+    /// Outside the minimum rebalance interval, a worker over its tenant cap
+    /// always needs it; otherwise the spread of worker load scores, or of
+    /// tenant counts per capacity weight while any worker's CPU and memory
+    /// sample is stale, is compared against `rebalance_threshold`.
+    pub async fn needs_rebalancing(&self) -> bool {
+        // Check minimum interval
+        let last_rebalance = *self.last_rebalance.read().await;
+        if chrono::Utc::now() - last_rebalance
+            < chrono::Duration::from_std(self.config.min_rebalance_interval).unwrap()
+        {
+            return false;
+        }
+
+        // A worker over its tenant limit needs relief however even the loads are
+        let worker_loads = self.worker_loads.read().await;
+        if worker_loads
+            .values()
+            .any(|load| load.tenant_count > load.tenant_cap(self.config.max_tenants_per_worker))
+        {
+            return true;
+        }
+
+        // Check load imbalance
+        if worker_loads.len() < 2 {
+            return false;
+        }
+        let loads: Vec<&WorkerMetrics> = worker_loads.values().collect();
+        load_imbalance(&loads, self.config.load_metrics_max_age) > self.config.rebalance_threshold
+    }
+
+    /// Rebalance tenants across workers, pushing the new distribution to them.
+    ///
+    /// Fails without changing anything while another coordinator holds the
+    /// rebalance lock. Tenants another writer placed meanwhile keep that
+    /// placement and are counted in the plan's `conflicts`.
+    #[instrument(skip(self))]
+    pub async fn rebalance(&self) -> Result<RebalancePlan> {
+        let guard = match &self.rebalance_lock {
+            Some(lock) => match lock.try_acquire().await? {
+                Some(guard) => Some(guard),
+                None => anyhow::bail!(
+                    "Rebalance already in progress on {}",
+                    lock.holder()
+                        .await?
+                        .as_deref()
+                        .unwrap_or("another coordinator")
+                ),
+            },
+            None => None,
+        };
+
+        let result = self.apply_rebalance().await;
+
+        if let Some(guard) = guard {
+            if let Err(e) = guard.release().await {
+                warn!("Failed to release rebalance lock: {}", e);
+            }
+        }
+        result
+    }
+
+    async fn apply_rebalance(&self) -> Result<RebalancePlan> {
+        info!("Starting tenant rebalancing");
+
+        let mut plan = self.plan_rebalance().await;
+        if plan.distribution.is_empty() {
+            return Ok(plan);
+        }
+
+        let mut worker_loads = self.worker_loads.write().await;
+        let mut tenant_worker_map = self.tenant_worker_map.write().await;
+        let mut assignments = self.assignments.write().await;
+
+        // Only tenants changing hands get a new assignment version; the rest
+        // keep theirs untouched
+        let mut changed = Vec::new();
+        for (worker_id, tenant_ids) in &plan.distribution {
+            for tenant_id in tenant_ids {
+                let assignment = match assignments.get(tenant_id) {
+                    Some(current) if current.worker_id == *worker_id => continue,
+                    Some(current) => {
+                        current.reassign(worker_id.clone(), AssignmentReason::LoadRebalance)
+                    }
+                    None => TenantAssignment::new(
+                        *tenant_id,
+                        worker_id.clone(),
+                        AssignmentReason::LoadRebalance,
+                    ),
+                };
+                assignments.insert(*tenant_id, assignment.clone());
+                changed.push(assignment);
+            }
+        }
+
+        // Keep consistent hashing and worker loads in line with the new placement.
+        // A consistent hashing rebalance puts tenants where the ring does, so
+        // their pins are dropped rather than renewed.
+        let on_ring = matches!(
+            self.config.strategy,
+            LoadBalancingStrategy::ConsistentHashing
+        );
+        for (worker_id, tenant_ids) in &plan.distribution {
+            for tenant_id in tenant_ids {
+                if on_ring {
+                    tenant_worker_map.remove(&tenant_id.to_string());
+                } else {
+                    tenant_worker_map.insert(tenant_id.to_string(), TenantPin::new(worker_id));
+                }
+            }
+            if let Some(load) = worker_loads.get_mut(worker_id) {
+                load.tenant_count = tenant_ids.len();
+            }
+        }
+
+        *self.last_rebalance.write().await = chrono::Utc::now();
+        drop(assignments);
+        drop(tenant_worker_map);
+        drop(worker_loads);
+        for assignment in &changed {
+            self.record_history(assignment).await;
+        }
+        let mut conflicted = HashSet::new();
+        for assignment in &changed {
+            if self.persist(assignment).await.is_err() {
+                conflicted.insert(assignment.tenant_id);
+            }
+        }
+        if !conflicted.is_empty() {
+            self.drop_conflicting_moves(&mut plan, &conflicted).await;
+        }
+        for moved in &plan.moves {
+            self.track_warm_worker(
+                moved.tenant_id,
+                Some(&moved.previous_worker_id),
+                Some(&moved.worker_id),
+            )
+            .await;
+        }
+
+        self.emit(AssignmentEvent::RebalanceCompleted {
+            distribution: plan.distribution.clone(),
+        });
+        let changed: HashMap<String, Vec<Uuid>> = plan
+            .distribution
+            .iter()
+            .filter(|(worker_id, _)| plan.affected_workers.contains(worker_id))
+            .map(|(worker_id, tenant_ids)| (worker_id.clone(), tenant_ids.clone()))
+            .collect();
+        self.push_assignments(&changed).await;
+
+        info!(
+            "Rebalancing complete, moved {} tenants. New distribution: {:?}",
+            plan.tenants_moved,
+            plan.distribution
+                .iter()
+                .map(|(k, v)| (k, v.len()))
+                .collect::<Vec<_>>()
+        );
+
+        Ok(plan)
+    }
+
+    /// Take tenants whose rebalanced assignment lost to another writer out
+    /// of the moves of an applied plan, listing them with the worker they
+    /// were adopted on instead
+    async fn drop_conflicting_moves(&self, plan: &mut RebalancePlan, conflicted: &HashSet<Uuid>) {
+        warn!(
+            "{} rebalanced tenants were placed by another writer meanwhile",
+            conflicted.len()
+        );
+        plan.moves
+            .retain(|moved| !conflicted.contains(&moved.tenant_id));
+        plan.tenants_moved = plan.tenants_moved.saturating_sub(conflicted.len());
+        plan.conflicts = conflicted.len();
+        for tenant_ids in plan.distribution.values_mut() {
+            tenant_ids.retain(|tenant_id| !conflicted.contains(tenant_id));
+        }
+
+        let assignments = self.assignments.read().await;
+        for tenant_id in conflicted {
+            let Some(assignment) = assignments.get(tenant_id) else {
+                continue;
+            };
+            if let Some(tenant_ids) = plan.distribution.get_mut(&assignment.worker_id) {
+                tenant_ids.push(*tenant_id);
+                if !plan.affected_workers.contains(&assignment.worker_id) {
+                    plan.affected_workers.push(assignment.worker_id.clone());
+                }
+            }
+        }
+        plan.affected_workers.sort();
+    }
+
+    /// Compute the distribution a rebalance would produce without applying it.
+    ///
+    /// Tenants are redistributed the way the configured strategy places them:
+    /// consistent hashing moves only tenants that are not on their worker on
+    /// the ring, least loaded moves tenants off the worker with the highest
+    /// load score while that narrows the gap to the least loaded one, round
+    /// robin evens out tenant counts per capacity weight while keeping as
+    /// many tenants in place as it can, and activity-based and custom
+    /// strategies spread tenants by activity score, busiest first, onto the
+    /// worker with the lowest accumulated score for its weight. Load scores
+    /// count tenants per capacity weight and workers take tenants up to
+    /// their weighted cap. Only assigned tenants are redistributed, and those
+    /// without metrics count as idle; sharded tenants keep their shard
+    /// placement. Draining and drained workers get no tenants.
+    pub async fn plan_rebalance(&self) -> RebalancePlan {
+        let excluded = self.excluded_workers().await;
+        let tenant_metrics = self.tenant_metrics.read().await;
+        let worker_loads = self.worker_loads.read().await;
+        let assignments = self.assignments.read().await;
+
+        let mut workers: Vec<String> = worker_loads
+            .keys()
+            .filter(|worker_id| !excluded.contains(*worker_id))
+            .cloned()
+            .collect();
+        if workers.is_empty() {
+            return RebalancePlan::default();
+        }
+        workers.sort();
+
+        // Tenants with metrics but no assignment, e.g. suspended ones, stay unassigned
+        let mut tenant_ids: Vec<Uuid> = assignments
+            .keys()
+            .filter(|tenant_id| !self.is_sharded(tenant_id))
+            .copied()
+            .collect();
+        tenant_ids.sort();
+
+        // Tenants on workers that keep their tenants
+        let current: HashMap<Uuid, String> = tenant_ids
+            .iter()
+            .filter_map(|tenant_id| {
+                assignments
+                    .get(tenant_id)
+                    .filter(|assignment| !excluded.contains(&assignment.worker_id))
+                    .filter(|assignment| worker_loads.contains_key(&assignment.worker_id))
+                    .map(|assignment| (*tenant_id, assignment.worker_id.clone()))
+            })
+            .collect();
+        let activity = |tenant_id: &Uuid| {
+            tenant_metrics
+                .get(tenant_id)
+                .map(|metrics| metrics.activity_score())
+                .unwrap_or(0.0)
+        };
+
+        let placement = match &self.config.strategy {
+            LoadBalancingStrategy::ConsistentHashing => {
+                let ring = self.ring.read().await;
+                ring_placement(&ring, &tenant_ids, &workers)
+            }
+            LoadBalancingStrategy::LeastLoaded => load_score_placement(
+                &tenant_ids,
+                &current,
+                &workers,
+                &worker_loads,
+                activity,
+                self.config.max_tenants_per_worker,
+            ),
+            LoadBalancingStrategy::RoundRobin => {
+                even_placement(&tenant_ids, &current, &workers, &worker_loads)
+            }
+            LoadBalancingStrategy::ActivityBased | LoadBalancingStrategy::Custom(_) => {
+                activity_placement(&tenant_ids, &workers, &worker_loads, activity)
+            }
+        };
+
+        // Drained workers stay in the distribution so they are sent their empty tenant list
+        let mut distribution: HashMap<String, Vec<Uuid>> = worker_loads
+            .keys()
+            .map(|worker_id| (worker_id.clone(), Vec::new()))
+            .collect();
+        for (tenant_id, worker_id) in placement {
+            distribution.entry(worker_id).or_default().push(tenant_id);
+        }
+
+        // Diff against the current placement
+        let mut tenants_moved = 0;
+        let mut moves = Vec::new();
+        let mut affected_workers = HashSet::new();
+        for (worker_id, tenant_ids) in &distribution {
+            for tenant_id in tenant_ids {
+                let current = assignments.get(tenant_id).map(|a| &a.worker_id);
+                if current != Some(worker_id) {
+                    tenants_moved += 1;
+                    affected_workers.insert(worker_id.clone());
+                    if let Some(current) = current {
+                        affected_workers.insert(current.clone());
+                        moves.push(ReassignedTenant {
+                            tenant_id: *tenant_id,
+                            previous_worker_id: current.clone(),
+                            worker_id: worker_id.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        moves.sort_by_key(|moved| moved.tenant_id);
+        let mut affected_workers: Vec<String> = affected_workers.into_iter().collect();
+        affected_workers.sort();
+
+        RebalancePlan {
+            distribution,
+            tenants_moved,
+            moves,
+            affected_workers,
+            conflicts: 0,
+        }
+    }
+
+    /// Reconcile persisted assignments against the workers that are alive.
+    ///
+    /// Meant to run once when the coordinator starts. Assignments pointing at
+    /// live workers are restored, tenants on dead workers are reassigned,
+    /// active tenants without any assignment are assigned and assignments of
+    /// tenants that are no longer active are dropped. Sharded tenants are left
+    /// to shard placement.
+    #[instrument(skip(self, active_tenant_ids))]
+    pub async fn reconcile(&self, active_tenant_ids: &[Uuid]) -> Result<ReconciliationReport> {
+        let store = self
+            .store
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Reconciliation requires an assignment store"))?;
+
+        let live_workers = store.live_workers().await?;
+        let persisted = store.load_all().await?;
+
+        let weights = self.published_weights(store).await;
+        for worker_id in &live_workers {
+            if !self.worker_loads.read().await.contains_key(worker_id) {
+                let capacity_weight = weights.get(worker_id).copied().unwrap_or(1.0);
+                self.register_worker(worker_id.clone(), capacity_weight)
+                    .await?;
+            }
+        }
+
+        let active: HashSet<Uuid> = active_tenant_ids
+            .iter()
+            .copied()
+            .filter(|tenant_id| !self.is_sharded(tenant_id))
+            .collect();
+
+        let mut report = ReconciliationReport {
+            live_workers: live_workers.iter().cloned().collect(),
+            completed_at: chrono::Utc::now(),
+            ..Default::default()
+        };
+        report.live_workers.sort();
+
+        let mut stale = Vec::new();
+        {
+            let mut worker_loads = self.worker_loads.write().await;
+            let mut assignments = self.assignments.write().await;
+            for (tenant_id, assignment) in persisted {
+                if !active.contains(&tenant_id) {
+                    report.dropped.push(tenant_id);
+                } else if assignments.contains_key(&tenant_id) {
+                    // Already placed since this coordinator started
+                    report.restored.push(tenant_id);
+                } else if live_workers.contains(&assignment.worker_id) {
+                    if let Some(load) = worker_loads.get_mut(&assignment.worker_id) {
+                        load.tenant_count += 1;
+                    }
+                    assignments.insert(tenant_id, assignment);
+                    report.restored.push(tenant_id);
+                } else {
+                    // Kept so the reassignment is recorded as a move off the dead worker
+                    stale.push((tenant_id, assignment.worker_id.clone()));
+                    assignments.insert(tenant_id, assignment);
+                }
+            }
+        }
+
+        if let Err(e) = store.remove(&report.dropped).await {
+            warn!("Failed to drop assignments of inactive tenants: {}", e);
+        }
+
+        for (tenant_id, previous_worker_id) in stale {
+            match self
+                .place_tenant(tenant_id, Some(AssignmentReason::WorkerFailure))
+                .await
+            {
+                Ok(worker_id) => report.reassigned.push(ReassignedTenant {
+                    tenant_id,
+                    previous_worker_id,
+                    worker_id,
+                }),
+                Err(e) => {
+                    warn!(
+                        "Failed to reassign tenant {} from dead worker {}: {}",
+                        tenant_id, previous_worker_id, e
+                    );
+                    self.assignments.write().await.remove(&tenant_id);
+                    report.unassigned.push(tenant_id);
+                }
+            }
+        }
+
+        let missing: Vec<Uuid> = {
+            let assignments = self.assignments.read().await;
+            active_tenant_ids
+                .iter()
+                .filter(|tenant_id| active.contains(tenant_id))
+                .filter(|tenant_id| !assignments.contains_key(tenant_id))
+                .copied()
+                .collect()
+        };
+        for tenant_id in missing {
+            match self.place_tenant(tenant_id, None).await {
+                Ok(_) => report.newly_assigned.push(tenant_id),
+                Err(e) => {
+                    warn!("Failed to assign unassigned tenant {}: {}", tenant_id, e);
+                    report.unassigned.push(tenant_id);
+                }
+            }
+        }
+
+        info!(
+            "Reconciled assignments with {} live workers: {} restored, {} reassigned, {} newly assigned, {} unassigned, {} dropped",
+            report.live_workers.len(),
+            report.restored.len(),
+            report.reassigned.len(),
+            report.newly_assigned.len(),
+            report.unassigned.len(),
+            report.dropped.len()
+        );
+        if !report.unassigned.is_empty() {
+            warn!(
+                "Tenants left without a worker after reconciliation: {:?}",
+                report.unassigned
+            );
+        }
+
+        if !report.is_clean() {
+            let mut distribution: HashMap<String, Vec<Uuid>> = HashMap::new();
+            for (tenant_id, assignment) in self.assignments.read().await.iter() {
+                distribution
+                    .entry(assignment.worker_id.clone())
+                    .or_default()
+                    .push(*tenant_id);
+            }
+            self.push_assignments(&distribution).await;
+        }
+
+        self.emit(AssignmentEvent::ReconciliationCompleted {
+            report: report.clone(),
+        });
+        Ok(report)
+    }
+
+    /// Send a command to a worker, returning whether it was listening.
+    ///
+    /// Always false without a control channel.
+    pub async fn send_control(&self, worker_id: &str, command: &ControlCommand) -> Result<bool> {
+        match &self.control {
+            Some(control) => control.send(worker_id, command).await,
+            None => Ok(false),
+        }
+    }
+
+    /// Send each worker its new tenants so it applies them without waiting to poll
+    async fn push_assignments(&self, distribution: &HashMap<String, Vec<Uuid>>) {
+        let Some(control) = &self.control else {
+            return;
+        };
+
+        for (worker_id, tenant_ids) in distribution {
+            let command = ControlCommand::AssignTenants {
+                tenant_ids: tenant_ids.clone(),
+                shards: self.get_worker_shards(worker_id).await,
+            };
+            if let Err(e) = control.send(worker_id, &command).await {
+                error!("Failed to push assignment to worker {}: {}", worker_id, e);
+            }
+        }
+    }
+
+    /// Round-robin assignment: the next worker in rotation order, advancing
+    /// the cursor past it and skipping drained workers and workers at their
+    /// tenant cap
+    async fn round_robin_assignment(&self) -> Result<String> {
+        let excluded = self.excluded_workers().await;
+        let worker_loads = self.worker_loads.read().await;
+        let rotation = self.rotation.read().await;
+        if rotation.is_empty() {
+            return Err(anyhow::anyhow!("No workers available"));
+        }
+
+        // The first worker from the cursor on that is not drained and has
+        // room, or the first not drained when all are at capacity
+        let len = rotation.len();
+        let pick = |cursor: usize| {
+            let mut candidates = (0..len)
+                .map(|step| (cursor + step) % len)
+                .filter(|index| !excluded.contains(&rotation[*index]));
+            let first = candidates.clone().next();
+            candidates
+                .find(|index| {
+                    worker_loads
+                        .get(&rotation[*index])
+                        .is_some_and(|load| load.has_room(self.config.max_tenants_per_worker))
+                })
+                .or(first)
+        };
+        let previous = self
+            .rotation_cursor
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |cursor| {
+                pick(cursor).map(|index| (index + 1) % len)
+            })
+            .map_err(|_| anyhow::anyhow!("No workers available outside drained workers"))?;
+        let index = pick(previous)
+            .ok_or_else(|| anyhow::anyhow!("No workers available outside drained workers"))?;
+        Ok(rotation[index].clone())
+    }
+
+    /// Add a worker to the end of the round-robin rotation
+    async fn join_rotation(&self, worker_id: &str) {
+        let mut rotation = self.rotation.write().await;
+        if !rotation.iter().any(|id| id == worker_id) {
+            rotation.push(worker_id.to_string());
+        }
+    }
+
+    /// Take a worker out of the round-robin rotation, keeping the cursor on
+    /// the worker it pointed at
+    async fn leave_rotation(&self, worker_id: &str) {
+        let mut rotation = self.rotation.write().await;
+        let Some(position) = rotation.iter().position(|id| id == worker_id) else {
+            return;
+        };
+        rotation.remove(position);
+        let len = rotation.len();
+        let _ = self
+            .rotation_cursor
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |cursor| {
+                let cursor = if position < cursor {
+                    cursor - 1
+                } else {
+                    cursor
+                };
+                Some(if cursor >= len { 0 } else { cursor })
+            });
+    }
+
+    /// Least loaded assignment, by sampled CPU and memory usage and tenant
+    /// count per capacity weight
+    async fn least_loaded_assignment(&self) -> Result<String> {
+        let worker_loads = self.worker_loads.read().await;
+
+        worker_loads
+            .iter()
+            .min_by(|(a_id, a), (b_id, b)| {
+                a.load_score()
+                    .total_cmp(&b.load_score())
+                    .then(a.weighted_tenants().total_cmp(&b.weighted_tenants()))
+                    .then(a_id.cmp(b_id))
+            })
+            .map(|(id, _)| id.clone())
+            .ok_or_else(|| anyhow::anyhow!("No workers available"))
+    }
+
+    /// Consistent hash assignment.
+    ///
+    /// Pinned tenants stay on their worker while the pin is fresh, renewing
+    /// it; others, and tenants whose pin expired, go to the first worker at
+    /// or after the tenant's point on the ring that is not drained.
+    async fn consistent_hash_assignment(&self, tenant_id: Uuid) -> Result<String> {
+        let excluded = self.excluded_workers().await;
+        let worker_loads = self.worker_loads.read().await;
+        let mut tenant_worker_map = self.tenant_worker_map.write().await;
+
+        // Check if tenant already has an assigned worker
+        let key = tenant_id.to_string();
+        let now = chrono::Utc::now();
+        if let Some(pin) = tenant_worker_map.get_mut(&key) {
+            if self.pin_expired(pin, now) {
+                debug!(
+                    "Pin of tenant {} to worker {} expired, placing it from the ring",
+                    tenant_id, pin.worker_id
+                );
+                tenant_worker_map.remove(&key);
+            } else if worker_loads.contains_key(&pin.worker_id) {
+                pin.last_used = now;
+                return Ok(pin.worker_id.clone());
+            }
+        }
+        drop(tenant_worker_map);
+
+        let ring = self.ring.read().await;
+        if ring.is_empty() {
+            return Err(anyhow::anyhow!("No workers available"));
+        }
+        let key = tenant_id.to_string();
+        let worker_id = ring
+            .walk(&key)
+            .find(|worker_id| !excluded.iter().any(|id| id == worker_id))
+            .or_else(|| ring.get(&key))
+            .ok_or_else(|| anyhow::anyhow!("No workers available"))?;
+
+        Ok(worker_id.to_string())
+    }
+
+    /// Whether a pin went unused for longer than `pin_ttl`
+    fn pin_expired(&self, pin: &TenantPin, now: chrono::DateTime<chrono::Utc>) -> bool {
+        !self.config.pin_ttl.is_zero()
+            && (now - pin.last_used)
+                .to_std()
+                .is_ok_and(|idle| idle > self.config.pin_ttl)
+    }
+
+    /// Drop pins unused for longer than `pin_ttl`, returning how many were dropped
+    pub async fn prune_expired_pins(&self) -> usize {
+        let now = chrono::Utc::now();
+        let mut tenant_worker_map = self.tenant_worker_map.write().await;
+        let before = tenant_worker_map.len();
+        tenant_worker_map.retain(|_, pin| !self.pin_expired(pin, now));
+        let pruned = before - tenant_worker_map.len();
+        if pruned > 0 {
+            debug!("Pruned {} expired tenant pins", pruned);
+        }
+        pruned
+    }
+
+    /// Prune expired pins every `interval` until the task is aborted
+    pub fn spawn_pin_pruning(
+        self: &Arc<Self>,
+        interval: std::time::Duration,
+    ) -> tokio::task::JoinHandle<()> {
+        let load_balancer = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                load_balancer.prune_expired_pins().await;
+            }
+        })
+    }
+
+    /// Activity-based assignment
+    async fn activity_based_assignment(&self, tenant_id: Uuid) -> Result<String> {
+        let tenant_metrics = self.tenant_metrics.read().await;
+        let _worker_loads = self.worker_loads.read().await;
+
+        // Default to least loaded if no metrics
+        if let Some(metrics) = tenant_metrics.get(&tenant_id) {
+            let activity_score = metrics.activity_score();
+
+            // High activity tenants go to least loaded workers
+            if activity_score > 0.7 {
+                return self.least_loaded_assignment().await;
+            }
+        }
+
+        // Low activity tenants use consistent hashing for affinity
+        self.consistent_hash_assignment(tenant_id).await
+    }
+
+    /// Assignment through a registered placement strategy
+    async fn custom_assignment(&self, name: &str, tenant_id: Uuid) -> Result<String> {
+        let strategy = self
+            .custom_strategies
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("Load balancing strategy {} is not registered", name))?;
+
+        let tenant_metrics = self.tenant_metrics.read().await;
+        let worker_loads = self.worker_loads.read().await;
+
+        let worker_id = strategy
+            .select_worker(tenant_id, &worker_loads, tenant_metrics.get(&tenant_id))
+            .ok_or_else(|| anyhow::anyhow!("No workers available"))?;
+
+        if !worker_loads.contains_key(&worker_id) {
+            anyhow::bail!("Strategy {} selected unknown worker {}", name, worker_id);
+        }
+
+        Ok(worker_id)
+    }
+
+    /// Get all tenant assignments for a specific worker
+    pub async fn get_worker_assignments(&self, worker_id: &str) -> Result<Vec<Uuid>> {
+        self.sync_shared_state().await;
+        let assignments = self.assignments.read().await;
+
+        let tenant_ids: Vec<Uuid> = assignments
+            .iter()
             .filter_map(|(tenant_id, assignment)| {
                 if assignment.worker_id == worker_id {
                     Some(*tenant_id)
@@ -410,3 +2576,858 @@ impl LoadBalancer {
         Ok(tenant_ids)
     }
 }
+
+/// Spread between the most and least loaded workers relative to the average.
+///
+/// Load scores, which weigh CPU and memory usage, are compared when every
+/// worker's sample is younger than `max_age`; tenant counts per capacity
+/// weight otherwise, as scores from stale samples would not reflect the
+/// workers' current load.
+fn load_imbalance(loads: &[&WorkerMetrics], max_age: std::time::Duration) -> f64 {
+    let now = chrono::Utc::now();
+    let fresh = loads.iter().all(|load| {
+        (now - load.collected_at)
+            .to_std()
+            .map_or(true, |age| age <= max_age)
+    });
+    let values: Vec<f64> = loads
+        .iter()
+        .map(|load| {
+            if fresh {
+                load.load_score()
+            } else {
+                load.weighted_tenants()
+            }
+        })
+        .collect();
+
+    let avg = values.iter().sum::<f64>() / values.len() as f64;
+    if avg <= 0.0 {
+        return 0.0;
+    }
+    let max = values.iter().copied().fold(f64::MIN, f64::max);
+    let min = values.iter().copied().fold(f64::MAX, f64::min);
+    (max - min) / avg
+}
+
+/// Every tenant on the first worker at or after its point on the ring that
+/// takes tenants
+fn ring_placement(ring: &HashRing, tenant_ids: &[Uuid], workers: &[String]) -> Vec<(Uuid, String)> {
+    tenant_ids
+        .iter()
+        .map(|tenant_id| {
+            let worker_id = ring
+                .walk(&tenant_id.to_string())
+                .find(|worker_id| workers.iter().any(|id| id == worker_id))
+                .unwrap_or(workers[0].as_str());
+            (*tenant_id, worker_id.to_string())
+        })
+        .collect()
+}
+
+/// Tenants moved one at a time, least active first, from the worker with the
+/// highest load score to the one with the lowest, while the gap is wider than
+/// moving a tenant closes.
+///
+/// A worker's CPU and memory usage is split evenly across the tenants it
+/// holds, and a tenant's share moves with it. Tenants without a worker go to
+/// the lowest scored worker below its tenant cap.
+fn load_score_placement(
+    tenant_ids: &[Uuid],
+    current: &HashMap<Uuid, String>,
+    workers: &[String],
+    worker_loads: &HashMap<String, WorkerMetrics>,
+    activity: impl Fn(&Uuid) -> f64,
+    max_tenants_per_worker: usize,
+) -> Vec<(Uuid, String)> {
+    let mut held: HashMap<&str, Vec<Uuid>> = workers
+        .iter()
+        .map(|worker_id| (worker_id.as_str(), Vec::new()))
+        .collect();
+    for (tenant_id, worker_id) in current {
+        if let Some(tenants) = held.get_mut(worker_id.as_str()) {
+            tenants.push(*tenant_id);
+        }
+    }
+
+    // Projected load of each worker, and each held tenant's share of it
+    let mut loads: HashMap<&str, WorkerMetrics> = HashMap::new();
+    let mut shares: HashMap<Uuid, (f64, f64)> = HashMap::new();
+    for worker_id in workers {
+        let mut load = worker_loads[worker_id].clone();
+        let tenants = &held[worker_id.as_str()];
+        load.tenant_count = tenants.len();
+        for tenant_id in tenants {
+            let count = tenants.len() as f64;
+            shares.insert(
+                *tenant_id,
+                (load.cpu_usage / count, load.memory_usage / count),
+            );
+        }
+        loads.insert(worker_id.as_str(), load);
+    }
+
+    for tenant_id in tenant_ids.iter().filter(|id| !current.contains_key(*id)) {
+        let ordered = by_load_score(workers, &loads);
+        let worker_id = ordered
+            .iter()
+            .find(|worker_id| loads[**worker_id].has_room(max_tenants_per_worker))
+            .unwrap_or(&ordered[0]);
+        held.get_mut(worker_id).unwrap().push(*tenant_id);
+        loads.get_mut(worker_id).unwrap().tenant_count += 1;
+    }
+
+    for _ in 0..tenant_ids.len() {
+        let ordered = by_load_score(workers, &loads);
+        let (lowest, highest) = (ordered[0], ordered[ordered.len() - 1]);
+        if lowest == highest || !loads[lowest].has_room(max_tenants_per_worker) {
+            break;
+        }
+        let Some(tenant_id) = held[highest]
+            .iter()
+            .copied()
+            .min_by(|a, b| activity(a).total_cmp(&activity(b)).then(a.cmp(b)))
+        else {
+            break;
+        };
+
+        let (cpu, memory) = shares.get(&tenant_id).copied().unwrap_or((0.0, 0.0));
+        let mut from = loads[highest].clone();
+        let mut to = loads[lowest].clone();
+        from.tenant_count -= 1;
+        from.cpu_usage -= cpu;
+        from.memory_usage -= memory;
+        to.tenant_count += 1;
+        to.cpu_usage += cpu;
+        to.memory_usage += memory;
+        // Stop once moving would only swap which worker is the busiest
+        if to.load_score() >= loads[highest].load_score() {
+            break;
+        }
+
+        held.get_mut(highest).unwrap().retain(|id| *id != tenant_id);
+        held.get_mut(lowest).unwrap().push(tenant_id);
+        loads.insert(highest, from);
+        loads.insert(lowest, to);
+    }
+
+    held.into_iter()
+        .flat_map(|(worker_id, tenant_ids)| {
+            tenant_ids
+                .into_iter()
+                .map(move |tenant_id| (tenant_id, worker_id.to_string()))
+        })
+        .collect()
+}
+
+/// Workers ordered from the lowest projected load score to the highest
+fn by_load_score<'a>(workers: &'a [String], loads: &HashMap<&str, WorkerMetrics>) -> Vec<&'a str> {
+    let mut ordered: Vec<&str> = workers.iter().map(String::as_str).collect();
+    ordered.sort_by(|a, b| {
+        loads[a]
+            .load_score()
+            .total_cmp(&loads[b].load_score())
+            .then(a.cmp(b))
+    });
+    ordered
+}
+
+/// Tenant counts evened out across workers in proportion to their capacity
+/// weights. The extra tenants of an uneven split go to the workers with the
+/// largest fractional shares, then to those holding the most tenants, and
+/// tenants stay on their worker while it is within its share.
+fn even_placement(
+    tenant_ids: &[Uuid],
+    current: &HashMap<Uuid, String>,
+    workers: &[String],
+    worker_loads: &HashMap<String, WorkerMetrics>,
+) -> Vec<(Uuid, String)> {
+    let mut held: HashMap<&str, usize> = HashMap::new();
+    for worker_id in current.values() {
+        *held.entry(worker_id.as_str()).or_default() += 1;
+    }
+    let mut ordered: Vec<&str> = workers.iter().map(String::as_str).collect();
+    ordered.sort_by(|a, b| {
+        held.get(b)
+            .unwrap_or(&0)
+            .cmp(held.get(a).unwrap_or(&0))
+            .then(a.cmp(b))
+    });
+
+    let weight = |worker_id: &str| {
+        worker_loads
+            .get(worker_id)
+            .map_or(1.0, |load| load.capacity_weight)
+    };
+    let total_weight: f64 = ordered.iter().map(|worker_id| weight(worker_id)).sum();
+    let mut shares: Vec<(&str, f64)> = ordered
+        .iter()
+        .map(|worker_id| {
+            let share = tenant_ids.len() as f64 * weight(worker_id) / total_weight;
+            (*worker_id, share)
+        })
+        .collect();
+    let mut room: HashMap<&str, usize> = shares
+        .iter()
+        .map(|(worker_id, share)| (*worker_id, share.floor() as usize))
+        .collect();
+    let extra = tenant_ids.len() - room.values().sum::<usize>();
+    // Stable, so equal fractions keep the order of the most tenants held
+    shares.sort_by(|(_, a), (_, b)| (b - b.floor()).total_cmp(&(a - a.floor())));
+    for (worker_id, _) in shares.iter().take(extra) {
+        *room.get_mut(worker_id).unwrap() += 1;
+    }
+
+    let mut placement = Vec::new();
+    let mut unplaced = Vec::new();
+    for tenant_id in tenant_ids {
+        match current.get(tenant_id) {
+            Some(worker_id) if room[worker_id.as_str()] > 0 => {
+                *room.get_mut(worker_id.as_str()).unwrap() -= 1;
+                placement.push((*tenant_id, worker_id.clone()));
+            }
+            _ => unplaced.push(*tenant_id),
+        }
+    }
+    let mut targets = ordered
+        .iter()
+        .flat_map(|worker_id| std::iter::repeat(*worker_id).take(room[worker_id]));
+    for tenant_id in unplaced {
+        if let Some(worker_id) = targets.next() {
+            placement.push((tenant_id, worker_id.to_string()));
+        }
+    }
+    placement
+}
+
+/// Tenants spread by activity score, busiest first, onto the worker with the
+/// lowest accumulated score for its capacity weight
+fn activity_placement(
+    tenant_ids: &[Uuid],
+    workers: &[String],
+    worker_loads: &HashMap<String, WorkerMetrics>,
+    activity: impl Fn(&Uuid) -> f64,
+) -> Vec<(Uuid, String)> {
+    // Group tenants by activity level
+    let mut high_activity = Vec::new();
+    let mut medium_activity = Vec::new();
+    let mut low_activity = Vec::new();
+    for tenant_id in tenant_ids {
+        let activity_score = activity(tenant_id);
+        if activity_score > 0.7 {
+            high_activity.push((*tenant_id, activity_score));
+        } else if activity_score > 0.3 {
+            medium_activity.push((*tenant_id, activity_score));
+        } else {
+            low_activity.push((*tenant_id, activity_score));
+        }
+    }
+
+    // Sort by activity score
+    high_activity.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    medium_activity.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    low_activity.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+
+    let mut worker_scores: HashMap<&str, f64> = workers
+        .iter()
+        .map(|worker_id| (worker_id.as_str(), 0.0))
+        .collect();
+
+    let weighted = |worker_id: &str, score: f64| {
+        let weight = worker_loads
+            .get(worker_id)
+            .map_or(1.0, |load| load.capacity_weight);
+        ((score / weight) * 1000.0) as i64
+    };
+
+    // Assign high activity tenants first, then medium, then low
+    let mut placement = Vec::new();
+    for (tenant_id, score) in high_activity
+        .into_iter()
+        .chain(medium_activity)
+        .chain(low_activity)
+    {
+        let worker_id = worker_scores
+            .iter()
+            .min_by_key(|(id, &score)| weighted(id, score))
+            .map(|(id, _)| *id)
+            .unwrap();
+
+        placement.push((tenant_id, worker_id.to_string()));
+        *worker_scores.get_mut(worker_id).unwrap() += score;
+    }
+    placement
+}
+
+/// Store a tenant's metrics unless those already held were collected later
+fn keep_newest(
+    tenant_metrics: &mut HashMap<Uuid, TenantMetrics>,
+    tenant_id: Uuid,
+    metrics: TenantMetrics,
+) {
+    let newer = tenant_metrics
+        .get(&tenant_id)
+        .map_or(true, |current| current.collected_at < metrics.collected_at);
+    if newer {
+        tenant_metrics.insert(tenant_id, metrics);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn balancer(strategy: LoadBalancingStrategy, workers: &[&str]) -> LoadBalancer {
+        let balancer = LoadBalancer::new(LoadBalancerConfig {
+            strategy,
+            ..Default::default()
+        });
+        for worker_id in workers {
+            balancer
+                .add_worker(worker_id.to_string(), 1.0)
+                .await
+                .unwrap();
+        }
+        balancer
+    }
+
+    #[tokio::test]
+    async fn test_adding_a_worker_moves_few_tenants() {
+        let balancer = balancer(
+            LoadBalancingStrategy::ConsistentHashing,
+            &["a", "b", "c", "d"],
+        )
+        .await;
+        let tenant_ids: Vec<Uuid> = (0..1_000).map(|_| Uuid::new_v4()).collect();
+        let mut before = Vec::new();
+        for tenant_id in &tenant_ids {
+            before.push(
+                balancer
+                    .consistent_hash_assignment(*tenant_id)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        balancer.add_worker("e".to_string(), 1.0).await.unwrap();
+        let mut moved = 0;
+        for (tenant_id, owner) in tenant_ids.iter().zip(&before) {
+            let worker_id = balancer
+                .consistent_hash_assignment(*tenant_id)
+                .await
+                .unwrap();
+            if worker_id != *owner {
+                assert_eq!(worker_id, "e");
+                moved += 1;
+            }
+        }
+
+        // Ideally 1 in 5; plain modulo hashing would move about 4 in 5
+        assert!(moved > 0 && moved < 300, "{} of 1000 tenants moved", moved);
+    }
+
+    #[tokio::test]
+    async fn test_removed_worker_leaves_the_ring() {
+        let balancer = balancer(LoadBalancingStrategy::ConsistentHashing, &["a", "b", "c"]).await;
+        let tenant_ids: Vec<Uuid> = (0..200).map(|_| Uuid::new_v4()).collect();
+        let mut before = Vec::new();
+        for tenant_id in &tenant_ids {
+            before.push(
+                balancer
+                    .consistent_hash_assignment(*tenant_id)
+                    .await
+                    .unwrap(),
+            );
+        }
+
+        balancer.remove_worker("b").await.unwrap();
+        for (tenant_id, owner) in tenant_ids.iter().zip(&before) {
+            let worker_id = balancer
+                .consistent_hash_assignment(*tenant_id)
+                .await
+                .unwrap();
+            assert_ne!(worker_id, "b");
+            if owner != "b" {
+                assert_eq!(worker_id, *owner);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_returning_tenant_goes_back_to_warm_worker() {
+        let balancer = balancer(LoadBalancingStrategy::LeastLoaded, &["a", "b"]).await;
+        let tenant_id = Uuid::new_v4();
+        balancer
+            .assign_tenant_to_worker(tenant_id, "a")
+            .await
+            .unwrap();
+        balancer
+            .assign_tenant_to_worker(Uuid::new_v4(), "a")
+            .await
+            .unwrap();
+        balancer.unassign_tenant(tenant_id).await.unwrap();
+
+        // Least loaded would pick b
+        assert_eq!(balancer.assign_tenant(tenant_id).await.unwrap(), "a");
+    }
+
+    #[tokio::test]
+    async fn test_placing_an_assigned_tenant_moves_its_count() {
+        let balancer = balancer(LoadBalancingStrategy::RoundRobin, &["a", "b"]).await;
+        let tenant_id = Uuid::new_v4();
+        let first = balancer.assign_tenant(tenant_id).await.unwrap();
+        let second = balancer.assign_tenant(tenant_id).await.unwrap();
+        assert_ne!(first, second);
+
+        // Placed back on the warm worker it just left
+        assert_eq!(balancer.assign_tenant(tenant_id).await.unwrap(), first);
+        let counts: HashMap<String, usize> = balancer
+            .worker_metrics()
+            .await
+            .into_iter()
+            .map(|load| (load.worker_id, load.tenant_count))
+            .collect();
+        assert_eq!(counts[&first], 1);
+        assert_eq!(counts[&second], 0);
+    }
+
+    #[tokio::test]
+    async fn test_unassigned_tenant_stays_out_of_rebalances() {
+        let balancer = LoadBalancer::new(LoadBalancerConfig {
+            strategy: LoadBalancingStrategy::ActivityBased,
+            min_rebalance_interval: std::time::Duration::ZERO,
+            ..Default::default()
+        });
+        balancer.add_worker("a".to_string(), 1.0).await.unwrap();
+        balancer.add_worker("b".to_string(), 1.0).await.unwrap();
+        let suspended = Uuid::new_v4();
+        for tenant_id in [suspended, Uuid::new_v4(), Uuid::new_v4()] {
+            balancer.assign_tenant(tenant_id).await.unwrap();
+            balancer
+                .update_tenant_metrics(TenantMetrics {
+                    tenant_id,
+                    monitors_count: 1,
+                    avg_rpc_calls_per_minute: 50.0,
+                    avg_filter_complexity: 1.0,
+                    total_matches_last_hour: 10,
+                    notifications_sent_last_hour: 0,
+                    last_active: chrono::Utc::now(),
+                    collected_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+        }
+
+        // Suspending a tenant unassigns it, keeping its last metrics
+        balancer.unassign_tenant(suspended).await.unwrap();
+        let plan = balancer.rebalance().await.unwrap();
+
+        assert!(plan
+            .distribution
+            .values()
+            .all(|tenant_ids| !tenant_ids.contains(&suspended)));
+        assert_eq!(balancer.get_worker_for_tenant(suspended).await, None);
+        assert_eq!(balancer.tenant_assignments().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_removed_worker_loses_affinity() {
+        let balancer = balancer(LoadBalancingStrategy::LeastLoaded, &["a", "b", "c"]).await;
+        let tenant_id = Uuid::new_v4();
+        balancer
+            .assign_tenant_to_worker(tenant_id, "a")
+            .await
+            .unwrap();
+        balancer
+            .assign_tenant_to_worker(tenant_id, "b")
+            .await
+            .unwrap();
+        balancer.unassign_tenant(tenant_id).await.unwrap();
+        balancer
+            .assign_tenant_to_worker(Uuid::new_v4(), "a")
+            .await
+            .unwrap();
+        balancer.remove_worker("b").await.unwrap();
+
+        // b left last but is gone; least loaded would pick c
+        assert_eq!(balancer.assign_tenant(tenant_id).await.unwrap(), "a");
+    }
+
+    #[tokio::test]
+    async fn test_expired_pins_are_placed_from_the_ring() {
+        let balancer = balancer(LoadBalancingStrategy::ConsistentHashing, &["a", "b", "c"]).await;
+        let stale = Uuid::new_v4();
+        let fresh = Uuid::new_v4();
+        let (stale_ring, fresh_ring) = {
+            let ring = balancer.ring.read().await;
+            let on_ring = |tenant_id: Uuid| ring.get(&tenant_id.to_string()).unwrap().to_string();
+            (on_ring(stale), on_ring(fresh))
+        };
+        let elsewhere = |worker_id: &str| if worker_id == "a" { "b" } else { "a" };
+
+        let long_ago = chrono::Utc::now() - chrono::Duration::hours(25);
+        {
+            let mut pins = balancer.tenant_worker_map.write().await;
+            pins.insert(
+                stale.to_string(),
+                TenantPin::since(elsewhere(&stale_ring), long_ago),
+            );
+            pins.insert(fresh.to_string(), TenantPin::new(elsewhere(&fresh_ring)));
+            pins.insert(Uuid::new_v4().to_string(), TenantPin::since("c", long_ago));
+        }
+
+        assert_eq!(balancer.assign_tenant(stale).await.unwrap(), stale_ring);
+        assert_eq!(
+            balancer.assign_tenant(fresh).await.unwrap(),
+            elsewhere(&fresh_ring)
+        );
+        assert_eq!(balancer.prune_expired_pins().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_consistent_hashing_rebalance_follows_the_ring() {
+        let balancer = balancer(LoadBalancingStrategy::ConsistentHashing, &["a", "b", "c"]).await;
+        let tenant_ids: Vec<Uuid> = (0..30).map(|_| Uuid::new_v4()).collect();
+        for tenant_id in &tenant_ids {
+            balancer
+                .assign_tenant_to_worker(*tenant_id, "a")
+                .await
+                .unwrap();
+        }
+        let owners: Vec<String> = {
+            let ring = balancer.ring.read().await;
+            tenant_ids
+                .iter()
+                .map(|tenant_id| ring.get(&tenant_id.to_string()).unwrap().to_string())
+                .collect()
+        };
+
+        // Only tenants away from their ring worker move
+        let plan = balancer.rebalance().await.unwrap();
+        let off_ring = owners.iter().filter(|owner| *owner != "a").count();
+        assert_eq!(plan.tenants_moved, off_ring);
+        for (tenant_id, owner) in tenant_ids.iter().zip(&owners) {
+            assert_eq!(
+                balancer.get_worker_for_tenant(*tenant_id).await.as_ref(),
+                Some(owner)
+            );
+            // Unpinned, so placing the tenant again keeps it where it is
+            assert_eq!(
+                balancer
+                    .consistent_hash_assignment(*tenant_id)
+                    .await
+                    .unwrap(),
+                *owner
+            );
+        }
+        assert_eq!(balancer.plan_rebalance().await.tenants_moved, 0);
+    }
+
+    #[tokio::test]
+    async fn test_least_loaded_rebalance_moves_off_the_busiest_worker() {
+        let balancer = balancer(LoadBalancingStrategy::LeastLoaded, &["a", "b"]).await;
+        let mut held = HashMap::new();
+        for worker_id in ["a", "b"] {
+            for _ in 0..10 {
+                let tenant_id = Uuid::new_v4();
+                balancer
+                    .assign_tenant_to_worker(tenant_id, worker_id)
+                    .await
+                    .unwrap();
+                held.insert(tenant_id, worker_id);
+            }
+        }
+        {
+            let mut worker_loads = balancer.worker_loads.write().await;
+            worker_loads.get_mut("a").unwrap().cpu_usage = 90.0;
+            worker_loads.get_mut("b").unwrap().cpu_usage = 10.0;
+        }
+
+        let plan = balancer.plan_rebalance().await;
+        let on_a = &plan.distribution["a"];
+        assert!(plan.tenants_moved > 0);
+        assert!(on_a.iter().all(|tenant_id| held[tenant_id] == "a"));
+        // Moves stop once the scores meet rather than emptying the busy worker
+        assert!(
+            !on_a.is_empty() && on_a.len() < 10,
+            "{} left on a",
+            on_a.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_counts_differ_by_at_most_one() {
+        for workers in 1..=6 {
+            let worker_ids: Vec<String> = (0..workers).map(|i| format!("w{}", i)).collect();
+            let names: Vec<&str> = worker_ids.iter().map(String::as_str).collect();
+            for tenants in 0..=(3 * workers + 2) {
+                let balancer = balancer(LoadBalancingStrategy::RoundRobin, &names).await;
+                let mut counts: HashMap<String, usize> = HashMap::new();
+                for _ in 0..tenants {
+                    let worker_id = balancer.assign_tenant(Uuid::new_v4()).await.unwrap();
+                    *counts.entry(worker_id).or_default() += 1;
+                }
+                let per_worker = names.iter().map(|id| counts.get(*id).copied().unwrap_or(0));
+                let (min, max) = (per_worker.clone().min().unwrap(), per_worker.max().unwrap());
+                assert!(
+                    max - min <= 1,
+                    "{} tenants on {} workers: {:?}",
+                    tenants,
+                    workers,
+                    counts
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_cursor_survives_pool_changes() {
+        let balancer = balancer(LoadBalancingStrategy::RoundRobin, &["a", "b", "c"]).await;
+        assert_eq!(balancer.assign_tenant(Uuid::new_v4()).await.unwrap(), "a");
+        assert_eq!(balancer.assign_tenant(Uuid::new_v4()).await.unwrap(), "b");
+
+        // Joining workers go to the end; leaving ones keep the cursor on c
+        balancer.add_worker("d".to_string(), 1.0).await.unwrap();
+        balancer.remove_worker("a").await.unwrap();
+        assert_eq!(balancer.assign_tenant(Uuid::new_v4()).await.unwrap(), "c");
+        assert_eq!(balancer.assign_tenant(Uuid::new_v4()).await.unwrap(), "d");
+        assert_eq!(balancer.assign_tenant(Uuid::new_v4()).await.unwrap(), "b");
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_rebalance_evens_out_counts() {
+        let balancer = balancer(LoadBalancingStrategy::RoundRobin, &["a", "b", "c"]).await;
+        for _ in 0..10 {
+            balancer
+                .assign_tenant_to_worker(Uuid::new_v4(), "a")
+                .await
+                .unwrap();
+        }
+
+        let plan = balancer.plan_rebalance().await;
+        let mut counts: Vec<usize> = plan.distribution.values().map(Vec::len).collect();
+        counts.sort();
+        assert_eq!(counts, vec![3, 3, 4]);
+        // a keeps the larger share, so only six tenants move
+        assert_eq!(plan.distribution["a"].len(), 4);
+        assert_eq!(plan.tenants_moved, 6);
+    }
+
+    #[tokio::test]
+    async fn test_weighted_workers_take_tenants_in_proportion() {
+        let mut balancer = LoadBalancer::new(LoadBalancerConfig {
+            strategy: LoadBalancingStrategy::RoundRobin,
+            max_tenants_per_worker: 4,
+            min_rebalance_interval: std::time::Duration::ZERO,
+            ..Default::default()
+        });
+        balancer.add_worker("a".to_string(), 2.0).await.unwrap();
+        balancer.add_worker("b".to_string(), 1.0).await.unwrap();
+        for _ in 0..4 {
+            balancer
+                .assign_tenant_to_worker(Uuid::new_v4(), "b")
+                .await
+                .unwrap();
+        }
+        // b is at its cap of 4 while a, at weight 2, may take 8
+        assert!(balancer
+            .assign_tenant_to_worker(Uuid::new_v4(), "b")
+            .await
+            .is_err());
+        for _ in 0..2 {
+            balancer
+                .assign_tenant_to_worker(Uuid::new_v4(), "a")
+                .await
+                .unwrap();
+        }
+
+        let plan = balancer.plan_rebalance().await;
+        assert_eq!(plan.distribution["a"].len(), 4);
+        assert_eq!(plan.distribution["b"].len(), 2);
+
+        // Over b's cap, but not a's
+        balancer.config.max_tenants_per_worker = 3;
+        assert!(balancer.needs_rebalancing().await);
+        balancer.rebalance().await.unwrap();
+        assert!(!balancer.needs_rebalancing().await);
+    }
+
+    #[tokio::test]
+    async fn test_rebalance_only_reassigns_moved_tenants() {
+        let balancer = balancer(LoadBalancingStrategy::RoundRobin, &["a", "b"]).await;
+        let tenant_ids: Vec<Uuid> = (0..4).map(|_| Uuid::new_v4()).collect();
+        for tenant_id in &tenant_ids {
+            balancer
+                .assign_tenant_to_worker(*tenant_id, "a")
+                .await
+                .unwrap();
+        }
+        let before = balancer.assignments.read().await.clone();
+
+        let plan = balancer.rebalance().await.unwrap();
+        assert_eq!(plan.moves.len(), 2);
+        assert!(plan
+            .moves
+            .iter()
+            .all(|moved| moved.previous_worker_id == "a" && moved.worker_id == "b"));
+
+        let assignments = balancer.assignments.read().await;
+        for tenant_id in &tenant_ids {
+            let moved = plan.moves.iter().any(|moved| moved.tenant_id == *tenant_id);
+            let version = before[tenant_id].version;
+            if moved {
+                assert_eq!(assignments[tenant_id].worker_id, "b");
+                assert_eq!(assignments[tenant_id].version, version + 1);
+            } else {
+                assert_eq!(assignments[tenant_id].worker_id, "a");
+                assert_eq!(assignments[tenant_id].version, version);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_silent_worker_is_evicted() {
+        let balancer = balancer(LoadBalancingStrategy::LeastLoaded, &["a", "b"]).await;
+        let tenant_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for tenant_id in &tenant_ids {
+            balancer
+                .assign_tenant_to_worker(*tenant_id, "a")
+                .await
+                .unwrap();
+        }
+
+        let silent_since = chrono::Utc::now() - chrono::Duration::seconds(120);
+        balancer
+            .heartbeats
+            .write()
+            .await
+            .insert("a".to_string(), silent_since);
+        balancer.heartbeat("b").await.unwrap();
+        balancer.supervise_workers().await.unwrap();
+
+        assert_eq!(balancer.worker_ids().await, vec!["b".to_string()]);
+        let assignments = balancer.assignments.read().await;
+        for tenant_id in &tenant_ids {
+            let assignment = &assignments[tenant_id];
+            assert_eq!(assignment.worker_id, "b");
+            assert!(matches!(assignment.reason, AssignmentReason::WorkerFailure));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_orphans_wait_for_the_next_worker() {
+        let balancer = balancer(LoadBalancingStrategy::LeastLoaded, &["a"]).await;
+        let tenant_ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for tenant_id in &tenant_ids {
+            balancer
+                .assign_tenant_to_worker(*tenant_id, "a")
+                .await
+                .unwrap();
+        }
+
+        let orphaned = balancer.remove_worker("a").await.unwrap();
+        let reassigned = balancer.reassign_orphans(orphaned).await.unwrap();
+        assert!(reassigned.is_empty());
+        assert_eq!(balancer.pending_orphans.read().await.len(), 3);
+
+        balancer.add_worker("b".to_string(), 1.0).await.unwrap();
+        assert!(balancer.pending_orphans.read().await.is_empty());
+        let mut placed = balancer.get_worker_assignments("b").await.unwrap();
+        placed.sort();
+        let mut expected = tenant_ids.clone();
+        expected.sort();
+        assert_eq!(placed, expected);
+        let assignments = balancer.assignments.read().await;
+        assert!(matches!(
+            assignments[&tenant_ids[0]].reason,
+            AssignmentReason::WorkerFailure
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_assignment_history_keeps_the_latest_changes() {
+        let mut balancer = balancer(LoadBalancingStrategy::RoundRobin, &["a", "b"]).await;
+        balancer.config.max_assignment_history = 3;
+        let tenant_id = Uuid::new_v4();
+        for worker_id in ["a", "b", "a", "b"] {
+            balancer
+                .assign_tenant_to_worker(tenant_id, worker_id)
+                .await
+                .unwrap();
+        }
+        let orphaned = balancer.remove_worker("b").await.unwrap();
+        balancer.reassign_orphans(orphaned).await.unwrap();
+
+        // The first two assignments were dropped for the failover onto a,
+        // which carries on from the version held on b
+        let history = balancer.assignment_history(tenant_id, 10).await.unwrap();
+        let timeline: Vec<(&str, u32)> = history
+            .iter()
+            .map(|assignment| (assignment.worker_id.as_str(), assignment.version))
+            .collect();
+        assert_eq!(timeline, vec![("a", 3), ("b", 4), ("a", 5)]);
+        assert!(matches!(history[0].reason, AssignmentReason::Manual));
+        assert!(matches!(history[2].reason, AssignmentReason::WorkerFailure));
+
+        let latest = balancer.assignment_history(tenant_id, 1).await.unwrap();
+        assert_eq!(latest.len(), 1);
+        assert!(matches!(latest[0].reason, AssignmentReason::WorkerFailure));
+        assert!(balancer
+            .assignment_history(Uuid::new_v4(), 10)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_equal_counts_with_skewed_cpu_need_rebalancing() {
+        let mut balancer = balancer(LoadBalancingStrategy::LeastLoaded, &["a", "b"]).await;
+        balancer.config.min_rebalance_interval = std::time::Duration::ZERO;
+        for worker_id in ["a", "b"] {
+            for _ in 0..5 {
+                balancer
+                    .assign_tenant_to_worker(Uuid::new_v4(), worker_id)
+                    .await
+                    .unwrap();
+            }
+        }
+        assert!(!balancer.needs_rebalancing().await);
+
+        let usage = |cpu_usage, memory_usage, age| WorkerUsage {
+            cpu_usage,
+            memory_usage,
+            collected_at: chrono::Utc::now() - chrono::Duration::seconds(age),
+        };
+        balancer
+            .record_worker_usage("a", &usage(95.0, 80.0, 0))
+            .await;
+        balancer
+            .record_worker_usage("b", &usage(5.0, 10.0, 0))
+            .await;
+        assert!(balancer.needs_rebalancing().await);
+
+        // A stale sample falls back to the equal tenant counts
+        balancer
+            .record_worker_usage("a", &usage(95.0, 80.0, 600))
+            .await;
+        assert!(!balancer.needs_rebalancing().await);
+    }
+
+    #[tokio::test]
+    async fn test_worker_over_its_tenant_limit_needs_rebalancing() {
+        let mut balancer = balancer(LoadBalancingStrategy::LeastLoaded, &["a", "b"]).await;
+        balancer.config.min_rebalance_interval = std::time::Duration::ZERO;
+        for worker_id in ["a", "b"] {
+            for _ in 0..5 {
+                balancer
+                    .assign_tenant_to_worker(Uuid::new_v4(), worker_id)
+                    .await
+                    .unwrap();
+            }
+        }
+        assert!(!balancer.needs_rebalancing().await);
+
+        balancer.config.max_tenants_per_worker = 4;
+        assert!(balancer.needs_rebalancing().await);
+    }
+}
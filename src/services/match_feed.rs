@@ -0,0 +1,121 @@
+//! Match Feed
+//!
+//! Live fan-out of monitor matches to API clients over Redis pub/sub, one
+//! channel per tenant. Workers publish every match they dispatch; the API
+//! subscribes to a tenant's channel for as long as a client is streaming, so
+//! the feed works whether the API runs with the workers or in its own process.
+//! Matches published while nobody is subscribed are not kept.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use redis::{AsyncCommands, Client as RedisClient};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use openzeppelin_monitor::models::MonitorMatch;
+
+use crate::models::MatchState;
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+use crate::services::redis_keyspace::RedisKeyspace;
+
+/// Matches queued per subscriber before the slowest ones are dropped
+const SUBSCRIBER_BUFFER: usize = 256;
+
+/// Match as published to the feed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchEvent {
+    pub tenant_id: Uuid,
+    pub monitor_name: String,
+    pub state: MatchState,
+    /// Worker that found the match
+    pub worker_id: String,
+    pub detected_at: DateTime<Utc>,
+    #[serde(rename = "match")]
+    pub monitor_match: MonitorMatch,
+}
+
+impl MatchEvent {
+    /// Feed event for a match dispatched by a worker
+    pub fn new(worker_id: &str, tenant_match: &TenantMonitorMatch) -> Self {
+        Self {
+            tenant_id: tenant_match.tenant_id,
+            monitor_name: tenant_match.monitor_name.clone(),
+            state: tenant_match.state,
+            worker_id: worker_id.to_string(),
+            detected_at: Utc::now(),
+            monitor_match: tenant_match.monitor_match.clone(),
+        }
+    }
+}
+
+/// Publishes and subscribes to per-tenant match channels
+pub struct MatchFeed {
+    redis: Arc<RedisClient>,
+    keyspace: RedisKeyspace,
+}
+
+impl MatchFeed {
+    /// Create a match feed on the given Redis client
+    pub fn new(redis: Arc<RedisClient>, keyspace: RedisKeyspace) -> Self {
+        Self { redis, keyspace }
+    }
+
+    /// Publish a match to its tenant's channel, returning how many subscribers received it
+    pub async fn publish(&self, event: &MatchEvent) -> Result<i64> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let payload = serde_json::to_string(event)?;
+        let receivers: i64 = conn
+            .publish(self.tenant_channel(event.tenant_id), payload)
+            .await?;
+        Ok(receivers)
+    }
+
+    /// Subscribe to a tenant's matches.
+    ///
+    /// The subscription ends once the returned receiver is dropped or the
+    /// Redis connection closes, which also closes the receiver.
+    pub async fn subscribe(&self, tenant_id: Uuid) -> Result<mpsc::Receiver<MatchEvent>> {
+        let channel = self.tenant_channel(tenant_id);
+        let mut pubsub = self.redis.get_async_pubsub().await?;
+        pubsub.subscribe(&channel).await?;
+        debug!("Subscribed to matches of tenant {}", tenant_id);
+
+        let (sender, receiver) = mpsc::channel(SUBSCRIBER_BUFFER);
+        tokio::spawn(async move {
+            let mut messages = pubsub.on_message();
+            loop {
+                let msg = tokio::select! {
+                    msg = messages.next() => match msg {
+                        Some(msg) => msg,
+                        None => break,
+                    },
+                    _ = sender.closed() => break,
+                };
+
+                let event = msg
+                    .get_payload::<String>()
+                    .map_err(anyhow::Error::from)
+                    .and_then(|payload| Ok(serde_json::from_str::<MatchEvent>(&payload)?));
+                match event {
+                    Ok(event) => {
+                        if sender.try_send(event).is_err() && sender.is_closed() {
+                            break;
+                        }
+                    }
+                    Err(e) => warn!("Ignoring malformed match event on {}: {}", channel, e),
+                }
+            }
+            debug!("Unsubscribed from matches of tenant {}", tenant_id);
+        });
+
+        Ok(receiver)
+    }
+
+    fn tenant_channel(&self, tenant_id: Uuid) -> String {
+        self.keyspace.key(&format!("matches:{}", tenant_id))
+    }
+}
@@ -0,0 +1,273 @@
+//! Match Severity
+//!
+//! Tags tenants' monitor matches with a severity and routes each severity to
+//! its own triggers and notification rate limit. A monitor's severity rules
+//! (`tenant_severity_rules`) are tried from critical down, running the rule's
+//! trigger script against the match like a trigger condition; the first one
+//! returning true gives the severity, `info` when none does. The tenant's
+//! route for that severity (`tenant_severity_routes`) then replaces the
+//! monitor's triggers and caps the notifications sent per minute. Rules and
+//! routes are cached briefly per tenant and dropped when the tenant's
+//! configuration is invalidated.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use openzeppelin_monitor::models::ScriptLanguage;
+use sqlx::PgPool;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::models::{MatchSeverity, SeverityRoute, SeverityRule};
+use crate::services::db_outage::cached_or;
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+
+/// How long loaded rules and routes are reused before re-reading the database
+const SEVERITY_TTL: Duration = Duration::from_secs(60);
+
+/// Length of the window notification limits are expressed in
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// Severity rule with the script it runs
+#[derive(Debug, Clone)]
+struct LoadedRule {
+    rule: SeverityRule,
+    language: ScriptLanguage,
+    content: String,
+}
+
+/// Severity configuration of one tenant
+#[derive(Debug, Default)]
+struct TenantSeverities {
+    /// Rules of every monitor, the most severe first
+    rules: Vec<LoadedRule>,
+    routes: HashMap<MatchSeverity, SeverityRoute>,
+}
+
+/// Severity classification and routing of tenants' matches
+pub struct MatchSeverityService {
+    db: Arc<PgPool>,
+    severities: DashMap<Uuid, (Instant, Arc<TenantSeverities>)>,
+    /// Notifications sent in the current window by tenant and severity
+    windows: DashMap<(Uuid, MatchSeverity), (Instant, u32)>,
+}
+
+impl MatchSeverityService {
+    /// Create a new severity service
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            severities: DashMap::new(),
+            windows: DashMap::new(),
+        }
+    }
+
+    /// Severity of a match under its monitor's rules.
+    ///
+    /// A rule whose script fails is skipped, so a broken rule can only lower
+    /// a match's severity, never drop the match.
+    pub async fn classify(&self, tenant_match: &TenantMonitorMatch) -> Result<MatchSeverity> {
+        use openzeppelin_monitor::services::trigger::ScriptExecutorFactory;
+
+        let severities = self.severities_for(tenant_match.tenant_id).await?;
+        let rules = severities
+            .rules
+            .iter()
+            .filter(|loaded| loaded.rule.monitor_name == tenant_match.monitor_name);
+        for loaded in rules {
+            let executor = ScriptExecutorFactory::create(&loaded.language, &loaded.content);
+            match executor
+                .execute(
+                    tenant_match.monitor_match.clone(),
+                    &loaded.rule.timeout_ms,
+                    loaded.rule.arguments.as_deref(),
+                    false,
+                )
+                .await
+            {
+                Ok(true) => return Ok(loaded.rule.severity),
+                Ok(false) => {}
+                Err(e) => warn!(
+                    "Skipping {} severity rule of monitor {} for tenant {}: script {} failed: {}",
+                    loaded.rule.severity.as_str(),
+                    tenant_match.monitor_name,
+                    tenant_match.tenant_id,
+                    loaded.rule.script_name,
+                    e
+                ),
+            }
+        }
+        Ok(MatchSeverity::Info)
+    }
+
+    /// The tenant's route for a severity, if it configured one
+    pub async fn route(
+        &self,
+        tenant_id: Uuid,
+        severity: MatchSeverity,
+    ) -> Result<Option<SeverityRoute>> {
+        Ok(self
+            .severities_for(tenant_id)
+            .await?
+            .routes
+            .get(&severity)
+            .cloned())
+    }
+
+    /// Count a notification of a severity against its per-minute limit,
+    /// returning false when the limit is already reached
+    pub fn admit(&self, tenant_id: Uuid, severity: MatchSeverity, limit: Option<u32>) -> bool {
+        let Some(limit) = limit else {
+            return true;
+        };
+        let mut window = self
+            .windows
+            .entry((tenant_id, severity))
+            .or_insert_with(|| (Instant::now(), 0));
+        if window.0.elapsed() >= WINDOW {
+            *window = (Instant::now(), 0);
+        }
+        if window.1 >= limit {
+            return false;
+        }
+        window.1 += 1;
+        true
+    }
+
+    /// Drop cached rules and routes so the next match re-reads them
+    pub fn invalidate(&self, tenant_id: Uuid) {
+        self.severities.remove(&tenant_id);
+    }
+
+    /// Get a tenant's rules and routes, loading them if the cache is stale
+    async fn severities_for(&self, tenant_id: Uuid) -> Result<Arc<TenantSeverities>> {
+        if let Some(entry) = self.severities.get(&tenant_id) {
+            let (loaded_at, severities) = entry.value();
+            if loaded_at.elapsed() < SEVERITY_TTL {
+                return Ok(severities.clone());
+            }
+        }
+
+        // Rules whose script is missing or inactive are left out
+        let rule_rows =
+            sqlx::query_as::<_, (String, String, String, Option<String>, i32, String, String)>(
+                r#"
+            SELECT r.monitor_name, r.severity, r.script_name, r.arguments, r.timeout_ms,
+                   s.language, s.content
+            FROM tenant_severity_rules r
+            JOIN trigger_scripts s
+                ON s.tenant_id = r.tenant_id AND s.name = r.script_name AND s.is_active = true
+            WHERE r.tenant_id = $1
+            "#,
+            )
+            .bind(tenant_id)
+            .fetch_all(&*self.db)
+            .await;
+        let rule_rows = match rule_rows {
+            Ok(rows) => rows,
+            Err(e) => return cached_or(e, self.cached(tenant_id)),
+        };
+
+        let mut rules = Vec::with_capacity(rule_rows.len());
+        for (monitor_name, severity, script_name, arguments, timeout_ms, language, content) in
+            rule_rows
+        {
+            let parsed = severity
+                .parse::<MatchSeverity>()
+                .map_err(anyhow::Error::from)
+                .and_then(|severity| {
+                    let language: ScriptLanguage =
+                        serde_json::from_value(serde_json::json!(language))?;
+                    Ok((severity, language))
+                });
+            match parsed {
+                Ok((severity, language)) => rules.push(LoadedRule {
+                    rule: SeverityRule {
+                        monitor_name,
+                        severity,
+                        script_name,
+                        arguments,
+                        timeout_ms: timeout_ms.max(1) as u32,
+                    },
+                    language,
+                    content,
+                }),
+                Err(e) => warn!(
+                    "Ignoring unreadable severity rule of monitor {} for tenant {}: {}",
+                    monitor_name, tenant_id, e
+                ),
+            }
+        }
+        rules.sort_by_key(|loaded| Reverse(loaded.rule.severity));
+
+        let route_rows = sqlx::query_as::<_, (String, Vec<String>, Option<i32>)>(
+            r#"
+            SELECT severity, triggers, max_notifications_per_minute
+            FROM tenant_severity_routes
+            WHERE tenant_id = $1
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await;
+        let route_rows = match route_rows {
+            Ok(rows) => rows,
+            Err(e) => return cached_or(e, self.cached(tenant_id)),
+        };
+
+        let mut routes = HashMap::with_capacity(route_rows.len());
+        for (severity, triggers, max_notifications_per_minute) in route_rows {
+            match severity.parse::<MatchSeverity>() {
+                Ok(severity) => {
+                    routes.insert(
+                        severity,
+                        SeverityRoute {
+                            severity,
+                            triggers,
+                            max_notifications_per_minute: max_notifications_per_minute
+                                .map(|limit| limit.max(0) as u32),
+                        },
+                    );
+                }
+                Err(e) => warn!(
+                    "Ignoring unreadable severity route of tenant {}: {}",
+                    tenant_id, e
+                ),
+            }
+        }
+
+        let severities = Arc::new(TenantSeverities { rules, routes });
+        self.severities
+            .insert(tenant_id, (Instant::now(), severities.clone()));
+        Ok(severities)
+    }
+
+    /// A tenant's rules and routes as last loaded, however stale
+    fn cached(&self, tenant_id: Uuid) -> Option<Arc<TenantSeverities>> {
+        self.severities.get(&tenant_id).map(|entry| entry.1.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_admit_caps_notifications_per_severity() {
+        let db = PgPool::connect_lazy("postgres://localhost/unused").unwrap();
+        let service = MatchSeverityService::new(Arc::new(db));
+        let tenant_id = Uuid::new_v4();
+
+        assert!(service.admit(tenant_id, MatchSeverity::Info, Some(2)));
+        assert!(service.admit(tenant_id, MatchSeverity::Info, Some(2)));
+        assert!(!service.admit(tenant_id, MatchSeverity::Info, Some(2)));
+
+        // Other severities and tenants have their own windows
+        assert!(service.admit(tenant_id, MatchSeverity::Critical, Some(1)));
+        assert!(service.admit(Uuid::new_v4(), MatchSeverity::Info, Some(2)));
+        assert!((0..100).all(|_| service.admit(tenant_id, MatchSeverity::Warning, None)));
+    }
+}
@@ -0,0 +1,126 @@
+//! Match Store
+//!
+//! Persists monitor matches in `monitor_matches` and tracks their lifecycle
+//! from first sight through finalization or orphaning by a reorg.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use openzeppelin_monitor::models::MonitorMatch;
+
+use crate::models::MatchState;
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+
+/// Persisted record of monitor matches
+pub struct MatchStore {
+    db: Arc<PgPool>,
+}
+
+impl MatchStore {
+    /// Create a new match store
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// Record a newly seen match in its current state, returning its id
+    pub async fn record(
+        &self,
+        tenant_match: &TenantMonitorMatch,
+        network_slug: &str,
+        block_number: Option<u64>,
+        block_hash: Option<&str>,
+    ) -> Result<Uuid> {
+        let id = Uuid::new_v4();
+        self.insert(
+            id,
+            tenant_match,
+            network_slug,
+            block_number,
+            block_hash,
+            Utc::now(),
+        )
+        .await?;
+        Ok(id)
+    }
+
+    /// Record a match under a given id, as seen at `seen_at`. Recording the
+    /// same id again is a no-op, so queued records can be replayed safely.
+    pub async fn insert(
+        &self,
+        id: Uuid,
+        tenant_match: &TenantMonitorMatch,
+        network_slug: &str,
+        block_number: Option<u64>,
+        block_hash: Option<&str>,
+        seen_at: DateTime<Utc>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO monitor_matches
+                (id, tenant_id, network_slug, monitor_name, block_number, block_hash, state,
+                 match_data, first_seen_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_match.tenant_id)
+        .bind(network_slug)
+        .bind(&tenant_match.monitor_name)
+        .bind(block_number.map(|n| n as i64))
+        .bind(block_hash)
+        .bind(tenant_match.state.as_str())
+        .bind(serde_json::to_value(&tenant_match.monitor_match)?)
+        .bind(seen_at)
+        .execute(&*self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Move a recorded match to a new state
+    pub async fn set_state(&self, id: Uuid, state: MatchState) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE monitor_matches
+            SET state = $2, updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(id)
+        .bind(state.as_str())
+        .execute(&*self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Latest non-orphaned match recorded for any of the given monitors of a tenant.
+    ///
+    /// Returns the match id, monitor name and match.
+    pub async fn latest(
+        &self,
+        tenant_id: Uuid,
+        monitor_names: &[String],
+    ) -> Result<Option<(Uuid, String, MonitorMatch)>> {
+        let row = sqlx::query_as::<_, (Uuid, String, serde_json::Value)>(
+            r#"
+            SELECT id, monitor_name, match_data
+            FROM monitor_matches
+            WHERE tenant_id = $1 AND monitor_name = ANY($2) AND state <> 'orphaned'
+            ORDER BY first_seen_at DESC
+            LIMIT 1
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(monitor_names)
+        .fetch_optional(&*self.db)
+        .await?;
+
+        row.map(|(id, monitor_name, match_data)| {
+            Ok((id, monitor_name, serde_json::from_value(match_data)?))
+        })
+        .transpose()
+    }
+}
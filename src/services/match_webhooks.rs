@@ -0,0 +1,379 @@
+//! Match Webhooks
+//!
+//! Firehose delivery of every match of a tenant's monitors to a single
+//! webhook the tenant registers, independently of the monitors' triggers.
+//! Workers collect matches per tenant and post them as a batch once
+//! `max_batch_size` matches are waiting or on every flush. Each delivery is
+//! signed with HMAC-SHA256 over `<timestamp>.<body>` using the webhook's
+//! secret, sent in the `X-Signature` and `X-Timestamp` headers.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use futures::future::join_all;
+use hmac::{Hmac, Mac};
+use moka::sync::Cache;
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::{MatchWebhook, MatchWebhookInput};
+use crate::repositories::RepositoryError;
+use crate::services::assignment_webhooks::is_retryable;
+use crate::services::match_feed::MatchEvent;
+use crate::services::metrics::MATCH_WEBHOOK_DELIVERIES;
+use crate::services::retry::RetryPolicy;
+use crate::services::ServiceError;
+
+/// Columns selected into [`MatchWebhook`]
+const COLUMNS: &str = "tenant_id, url, secret, is_active, created_at, updated_at";
+
+/// Header carrying the hex HMAC-SHA256 signature of a delivery
+pub const SIGNATURE_HEADER: &str = "X-Signature";
+
+/// Header carrying the Unix time a delivery was signed at
+pub const TIMESTAMP_HEADER: &str = "X-Timestamp";
+
+/// Shortest accepted signing secret, in bytes
+const MIN_SECRET_LEN: usize = 16;
+
+/// How long a tenant's webhook is cached before changes are picked up
+const WEBHOOK_LOOKUP_TTL: Duration = Duration::from_secs(60);
+
+/// Tenants whose webhook lookups are cached per worker
+const WEBHOOK_LOOKUP_CAPACITY: u64 = 10_000;
+
+/// Tenants' match webhooks
+pub struct MatchWebhookStore {
+    db: Arc<PgPool>,
+}
+
+impl MatchWebhookStore {
+    /// Create a store on the given database
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// Get a tenant's webhook
+    pub async fn get(&self, tenant_id: Uuid) -> Result<Option<MatchWebhook>> {
+        Ok(sqlx::query_as::<_, MatchWebhook>(&format!(
+            "SELECT {COLUMNS} FROM tenant_match_webhooks WHERE tenant_id = $1"
+        ))
+        .bind(tenant_id)
+        .fetch_optional(&*self.db)
+        .await?)
+    }
+
+    /// Register or replace a tenant's webhook
+    pub async fn upsert(
+        &self,
+        tenant_id: Uuid,
+        input: &MatchWebhookInput,
+    ) -> Result<MatchWebhook, ServiceError> {
+        validate(input)?;
+
+        Ok(sqlx::query_as::<_, MatchWebhook>(&format!(
+            r#"
+            INSERT INTO tenant_match_webhooks (tenant_id, url, secret, is_active)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (tenant_id) DO UPDATE
+            SET url = EXCLUDED.url,
+                secret = EXCLUDED.secret,
+                is_active = EXCLUDED.is_active,
+                updated_at = now()
+            RETURNING {COLUMNS}
+            "#
+        ))
+        .bind(tenant_id)
+        .bind(&input.url)
+        .bind(&input.secret)
+        .bind(input.is_active)
+        .fetch_one(&*self.db)
+        .await
+        .map_err(RepositoryError::from)?)
+    }
+
+    /// Remove a tenant's webhook, returning it if it existed
+    pub async fn delete(&self, tenant_id: Uuid) -> Result<Option<MatchWebhook>> {
+        Ok(sqlx::query_as::<_, MatchWebhook>(&format!(
+            "DELETE FROM tenant_match_webhooks WHERE tenant_id = $1 RETURNING {COLUMNS}"
+        ))
+        .bind(tenant_id)
+        .fetch_optional(&*self.db)
+        .await?)
+    }
+}
+
+/// Check a webhook before it is stored
+fn validate(input: &MatchWebhookInput) -> Result<(), ServiceError> {
+    let url = reqwest::Url::parse(&input.url).map_err(|e| {
+        ServiceError::InvalidState(format!("invalid webhook url {}: {}", input.url, e))
+    })?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(ServiceError::InvalidState(format!(
+            "webhook url {} must use http or https",
+            input.url
+        )));
+    }
+    if input.secret.len() < MIN_SECRET_LEN {
+        return Err(ServiceError::InvalidState(format!(
+            "webhook secret must be at least {} bytes",
+            MIN_SECRET_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// Hex HMAC-SHA256 of `<timestamp>.<body>` with the given secret
+pub fn sign(secret: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Body posted to a match webhook
+#[derive(Debug, Serialize)]
+struct MatchBatch<'a> {
+    /// Unique delivery identifier for idempotent consumers
+    id: Uuid,
+    tenant_id: Uuid,
+    sent_at: DateTime<Utc>,
+    matches: &'a [MatchEvent],
+}
+
+/// Matches waiting for delivery, per tenant
+struct PendingMatches {
+    batches: DashMap<Uuid, Vec<MatchEvent>>,
+    max_batch_size: usize,
+}
+
+impl PendingMatches {
+    fn new(max_batch_size: usize) -> Self {
+        Self {
+            batches: DashMap::new(),
+            max_batch_size,
+        }
+    }
+
+    /// Queue a match, returning its tenant's batch once it is full
+    fn push(&self, event: MatchEvent) -> Option<Vec<MatchEvent>> {
+        let mut batch = self.batches.entry(event.tenant_id).or_default();
+        batch.push(event);
+        (batch.len() >= self.max_batch_size).then(|| std::mem::take(&mut *batch))
+    }
+
+    /// Take every tenant's queued matches
+    fn drain(&self) -> Vec<(Uuid, Vec<MatchEvent>)> {
+        let tenant_ids: Vec<Uuid> = self.batches.iter().map(|entry| *entry.key()).collect();
+        tenant_ids
+            .into_iter()
+            .filter_map(|tenant_id| self.batches.remove(&tenant_id))
+            .filter(|(_, batch)| !batch.is_empty())
+            .collect()
+    }
+}
+
+/// Batches a worker's matches and posts them to their tenants' webhooks
+pub struct MatchWebhookDispatcher {
+    store: MatchWebhookStore,
+    client: reqwest::Client,
+    retry: RetryPolicy,
+    timeout: Duration,
+    pending: PendingMatches,
+    /// Tenants' active webhooks; `None` for tenants without one
+    webhooks: Cache<Uuid, Option<Arc<MatchWebhook>>>,
+}
+
+impl MatchWebhookDispatcher {
+    /// Create a dispatcher posting batches of up to `max_batch_size` matches
+    pub fn new(db: Arc<PgPool>, max_batch_size: usize, timeout: Duration) -> Self {
+        Self {
+            store: MatchWebhookStore::new(db),
+            client: reqwest::Client::new(),
+            retry: RetryPolicy::default().with_classifier(is_retryable),
+            timeout,
+            pending: PendingMatches::new(max_batch_size),
+            webhooks: Cache::builder()
+                .max_capacity(WEBHOOK_LOOKUP_CAPACITY)
+                .time_to_live(WEBHOOK_LOOKUP_TTL)
+                .build(),
+        }
+    }
+
+    /// Retry failed deliveries with the given policy.
+    ///
+    /// Client errors other than 429 are never retried.
+    pub fn with_retry_policy(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry.with_classifier(is_retryable);
+        self
+    }
+
+    /// Queue a match for its tenant's webhook, posting the batch in the
+    /// background once it is full. Matches of tenants without an active
+    /// webhook are dropped.
+    pub async fn enqueue(self: &Arc<Self>, event: &MatchEvent) {
+        if self.webhook(event.tenant_id).await.is_none() {
+            return;
+        }
+
+        if let Some(batch) = self.pending.push(event.clone()) {
+            let dispatcher = self.clone();
+            let tenant_id = event.tenant_id;
+            tokio::spawn(async move { dispatcher.deliver(tenant_id, batch).await });
+        }
+    }
+
+    /// Post every tenant's queued matches
+    pub async fn flush(&self) {
+        join_all(
+            self.pending
+                .drain()
+                .into_iter()
+                .map(|(tenant_id, batch)| self.deliver(tenant_id, batch)),
+        )
+        .await;
+    }
+
+    /// Start task posting queued matches on the given interval
+    pub fn start_flush(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let dispatcher = self.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                dispatcher.flush().await;
+            }
+        })
+    }
+
+    /// Tenant's active webhook, cached for [`WEBHOOK_LOOKUP_TTL`]
+    async fn webhook(&self, tenant_id: Uuid) -> Option<Arc<MatchWebhook>> {
+        if let Some(webhook) = self.webhooks.get(&tenant_id) {
+            return webhook;
+        }
+
+        match self.store.get(tenant_id).await {
+            Ok(webhook) => {
+                let webhook = webhook.filter(|w| w.is_active).map(Arc::new);
+                self.webhooks.insert(tenant_id, webhook.clone());
+                webhook
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to look up match webhook of tenant {}: {}",
+                    tenant_id, e
+                );
+                None
+            }
+        }
+    }
+
+    async fn deliver(&self, tenant_id: Uuid, matches: Vec<MatchEvent>) {
+        let Some(webhook) = self.webhook(tenant_id).await else {
+            return;
+        };
+
+        let batch = MatchBatch {
+            id: Uuid::new_v4(),
+            tenant_id,
+            sent_at: Utc::now(),
+            matches: &matches,
+        };
+        let body = match serde_json::to_vec(&batch) {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(
+                    "Failed to serialize matches of tenant {} for its webhook: {}",
+                    tenant_id, e
+                );
+                return;
+            }
+        };
+        let timestamp = batch.sent_at.timestamp().to_string();
+        let signature = sign(&webhook.secret, &timestamp, &body);
+
+        let result = self
+            .retry
+            .retry(|| {
+                let request = self
+                    .client
+                    .post(&webhook.url)
+                    .timeout(self.timeout)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .header(TIMESTAMP_HEADER, &timestamp)
+                    .header(SIGNATURE_HEADER, &signature)
+                    .body(body.clone());
+
+                async move { request.send().await?.error_for_status().map(|_| ()) }
+            })
+            .await;
+
+        let tenant_label = tenant_id.to_string();
+        match result {
+            Ok(()) => {
+                MATCH_WEBHOOK_DELIVERIES
+                    .with_label_values(&[&tenant_label, "delivered"])
+                    .inc();
+                debug!(
+                    "Delivered {} matches of tenant {} to {}",
+                    matches.len(),
+                    tenant_id,
+                    webhook.url
+                );
+            }
+            Err(e) => {
+                MATCH_WEBHOOK_DELIVERIES
+                    .with_label_values(&[&tenant_label, "failed"])
+                    .inc();
+                warn!(
+                    "Failed to deliver {} matches of tenant {} to {}: {}",
+                    matches.len(),
+                    tenant_id,
+                    webhook.url,
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signature_covers_timestamp_and_body() {
+        let signature = sign("0123456789abcdef", "1700000000", b"{}");
+
+        assert_eq!(signature.len(), 64);
+        assert_eq!(signature, sign("0123456789abcdef", "1700000000", b"{}"));
+        assert_ne!(signature, sign("0123456789abcdef", "1700000001", b"{}"));
+        assert_ne!(signature, sign("0123456789abcdef", "1700000000", b"[]"));
+        assert_ne!(signature, sign("fedcba9876543210", "1700000000", b"{}"));
+    }
+
+    #[test]
+    fn test_webhooks_are_validated_before_storing() {
+        let input = |url: &str, secret: &str| MatchWebhookInput {
+            url: url.to_string(),
+            secret: secret.to_string(),
+            is_active: true,
+        };
+
+        assert!(validate(&input(
+            "https://hooks.example.com/matches",
+            "0123456789abcdef"
+        ))
+        .is_ok());
+        assert!(validate(&input("ftp://hooks.example.com", "0123456789abcdef")).is_err());
+        assert!(validate(&input("not a url", "0123456789abcdef")).is_err());
+        assert!(validate(&input("https://hooks.example.com", "short")).is_err());
+    }
+}
@@ -0,0 +1,515 @@
+//! Prometheus Metrics
+//!
+//! Process-wide registry for orchestrator metrics. Metrics are registered on
+//! first use and exported in the Prometheus text format by [`gather`].
+//! Counters are incremented where the work happens; the worker, tenant and
+//! system gauges mirror the load balancer's metric structs and are refreshed
+//! by [`observe_workers`], [`observe_tenants`] and [`observe_system`].
+
+use once_cell::sync::Lazy;
+use prometheus::core::Collector;
+use prometheus::{
+    CounterVec, Encoder, Gauge, GaugeVec, IntCounterVec, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::models::{SystemMetrics, TenantMetrics, WorkerMetrics};
+
+/// Registry holding every orchestrator metric
+pub static REGISTRY: Lazy<Registry> = Lazy::new(Registry::new);
+
+/// Block events received by a worker but not yet processed
+pub static BLOCK_EVENTS_IN_FLIGHT: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "oz_monitor_block_events_in_flight",
+            "Block events queued for a worker and not yet processed",
+        ),
+        &["worker_id"],
+    ))
+});
+
+/// Block events the block watcher holds back until the event bus is reachable
+pub static BLOCK_EVENTS_UNPUBLISHED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "oz_monitor_block_events_unpublished",
+            "Block events buffered by the block watcher while the event bus is unreachable",
+        ),
+        &["network"],
+    ))
+});
+
+/// Block events a worker missed because it lagged behind the broadcast channel
+pub static BLOCK_EVENTS_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_block_events_dropped_total",
+            "Block events overwritten before a worker received them",
+        ),
+        &["worker_id"],
+    ))
+});
+
+/// Block events a worker received, by network
+pub static BLOCK_EVENTS_RECEIVED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_block_events_received_total",
+            "Block events a worker received from the broadcast channel",
+        ),
+        &["worker_id", "network"],
+    ))
+});
+
+/// Block events a worker skipped without processing, by reason
+pub static BLOCK_EVENTS_SKIPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_block_events_skipped_total",
+            "Block events a worker skipped without processing any block",
+        ),
+        &["worker_id", "network", "reason"],
+    ))
+});
+
+/// Times a worker fell behind the broadcast channel
+pub static BLOCK_EVENT_LAGS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_block_event_lags_total",
+            "Times a worker lagged behind the broadcast channel and lost events",
+        ),
+        &["worker_id"],
+    ))
+});
+
+/// Blocks of received events a worker has yet to process, by network
+pub static BLOCKS_QUEUED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "oz_monitor_worker_blocks_queued",
+            "Blocks of a received event a worker has not processed yet",
+        ),
+        &["worker_id", "network"],
+    ))
+});
+
+/// Whether a Stellar endpoint passed its latest health checks, by network,
+/// kind and endpoint fingerprint
+pub static STELLAR_ENDPOINT_HEALTHY: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "oz_monitor_stellar_endpoint_healthy",
+            "Whether a Horizon or Soroban RPC endpoint is used by new clients (1) or failed over (0)",
+        ),
+        &["network", "kind", "endpoint"],
+    ))
+});
+
+/// Blocks processed by a worker
+pub static BLOCKS_PROCESSED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_blocks_processed_total",
+            "Blocks processed by a worker for its tenants",
+        ),
+        &["worker_id", "network"],
+    ))
+});
+
+/// Monitor matches found by a worker
+pub static MATCHES_FOUND: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new("oz_monitor_matches_found_total", "Monitor matches found"),
+        &["worker_id", "tenant_id", "network"],
+    ))
+});
+
+/// Trigger deliveries by outcome
+pub static TRIGGER_EXECUTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_trigger_executions_total",
+            "Trigger executions by outcome (success or failure)",
+        ),
+        &["tenant_id", "network", "status"],
+    ))
+});
+
+/// Matches by the severity tenants' rules gave them
+pub static MATCH_SEVERITIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_match_severities_total",
+            "Matches by severity (info, warning or critical)",
+        ),
+        &["tenant_id", "severity"],
+    ))
+});
+
+/// Notifications dropped by their severity route's rate limit
+pub static NOTIFICATIONS_RATE_LIMITED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_notifications_rate_limited_total",
+            "Notifications dropped over their severity route's per-minute limit",
+        ),
+        &["tenant_id", "severity"],
+    ))
+});
+
+/// Block requests answered from the block cache
+pub static CACHE_HITS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_cache_hits_total",
+            "Block requests served from cache",
+        ),
+        &["network"],
+    ))
+});
+
+/// Block requests that fell through to RPC
+pub static CACHE_MISSES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_cache_misses_total",
+            "Block requests fetched from RPC after missing the cache",
+        ),
+        &["network"],
+    ))
+});
+
+/// Spikes in tenant activity flagged by the anomaly analyzer
+pub static TENANT_ANOMALIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_tenant_anomalies_total",
+            "Sudden spikes in tenant match or RPC rates",
+        ),
+        &["tenant_id", "kind"],
+    ))
+});
+
+/// Worker CPU time attributed to each tenant by its share of filter and trigger time
+pub static TENANT_CPU_SECONDS: Lazy<CounterVec> = Lazy::new(|| {
+    register(CounterVec::new(
+        Opts::new(
+            "oz_monitor_tenant_cpu_seconds_total",
+            "Approximate worker CPU time spent on a tenant's monitors and triggers",
+        ),
+        &["worker_id", "tenant_id"],
+    ))
+});
+
+/// Audit log entries of management API calls that could not be written
+pub static AUDIT_WRITE_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_audit_write_failures_total",
+            "Mutating API calls whose audit log entry failed to be written",
+        ),
+        &["action"],
+    ))
+});
+
+/// Match batches delivered to or failed at tenants' match webhooks
+pub static MATCH_WEBHOOK_DELIVERIES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_match_webhook_deliveries_total",
+            "Match batches posted to tenant match webhooks by outcome",
+        ),
+        &["tenant_id", "outcome"],
+    ))
+});
+
+/// Workers evicted after their heartbeat stopped
+pub static WORKERS_EVICTED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_workers_evicted_total",
+            "Workers evicted for missing heartbeats, their tenants reassigned",
+        ),
+        &["worker_id"],
+    ))
+});
+
+/// Block up to which every block of a network is accounted for per tenant
+pub static TENANT_WATERMARK: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "oz_monitor_tenant_watermark_block",
+            "Block up to which a tenant's monitors have evaluated every block of a network",
+        ),
+        &["tenant_id", "network"],
+    ))
+});
+
+/// Whether a worker is running in degraded mode without its database
+pub static WORKER_DATABASE_DEGRADED: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "oz_monitor_worker_database_degraded",
+            "Whether a worker lost its database and filters with cached configurations (1) or not (0)",
+        ),
+        &["worker_id"],
+    ))
+});
+
+/// Match writes a worker holds back until its database is reachable
+pub static QUEUED_WRITES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register(IntGaugeVec::new(
+        Opts::new(
+            "oz_monitor_worker_queued_writes",
+            "Match records and state changes queued while the database is unreachable",
+        ),
+        &["worker_id"],
+    ))
+});
+
+/// Queued writes dropped because the queue was full
+pub static QUEUED_WRITES_DROPPED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register(IntCounterVec::new(
+        Opts::new(
+            "oz_monitor_worker_queued_writes_dropped_total",
+            "Queued writes dropped over worker.max_queued_writes before the database came back",
+        ),
+        &["worker_id"],
+    ))
+});
+
+static WORKER_TENANTS: Lazy<GaugeVec> =
+    Lazy::new(|| worker_gauge("oz_monitor_worker_tenants", "Tenants assigned to a worker"));
+static WORKER_CPU: Lazy<GaugeVec> =
+    Lazy::new(|| worker_gauge("oz_monitor_worker_cpu_usage_percent", "Worker CPU usage"));
+static WORKER_MEMORY: Lazy<GaugeVec> = Lazy::new(|| {
+    worker_gauge(
+        "oz_monitor_worker_memory_usage_percent",
+        "Worker memory usage",
+    )
+});
+static WORKER_RPC_RATE: Lazy<GaugeVec> = Lazy::new(|| {
+    worker_gauge(
+        "oz_monitor_worker_rpc_rate",
+        "RPC calls per second made by a worker",
+    )
+});
+static WORKER_PROCESSING_TIME: Lazy<GaugeVec> = Lazy::new(|| {
+    worker_gauge(
+        "oz_monitor_worker_block_processing_ms",
+        "Average block processing time of a worker",
+    )
+});
+static WORKER_ERRORS: Lazy<GaugeVec> = Lazy::new(|| {
+    worker_gauge(
+        "oz_monitor_worker_errors_last_hour",
+        "Errors reported by a worker in the last hour",
+    )
+});
+static WORKER_UPTIME: Lazy<GaugeVec> =
+    Lazy::new(|| worker_gauge("oz_monitor_worker_uptime_seconds", "Worker uptime"));
+static WORKER_CAPACITY_WEIGHT: Lazy<GaugeVec> = Lazy::new(|| {
+    worker_gauge(
+        "oz_monitor_worker_capacity_weight",
+        "Worker capacity relative to a worker of weight 1",
+    )
+});
+
+static TENANT_MONITORS: Lazy<GaugeVec> =
+    Lazy::new(|| tenant_gauge("oz_monitor_tenant_monitors", "Active monitors of a tenant"));
+static TENANT_RPC_CALLS: Lazy<GaugeVec> = Lazy::new(|| {
+    tenant_gauge(
+        "oz_monitor_tenant_rpc_calls_per_minute",
+        "Average RPC calls per minute of a tenant",
+    )
+});
+static TENANT_FILTER_COMPLEXITY: Lazy<GaugeVec> = Lazy::new(|| {
+    tenant_gauge(
+        "oz_monitor_tenant_filter_complexity",
+        "Average filter complexity score of a tenant",
+    )
+});
+static TENANT_MATCHES: Lazy<GaugeVec> = Lazy::new(|| {
+    tenant_gauge(
+        "oz_monitor_tenant_matches_last_hour",
+        "Matches of a tenant in the last hour",
+    )
+});
+static TENANT_NOTIFICATIONS: Lazy<GaugeVec> = Lazy::new(|| {
+    tenant_gauge(
+        "oz_monitor_tenant_notifications_last_hour",
+        "Notifications sent for a tenant in the last hour",
+    )
+});
+static TENANT_ACTIVITY: Lazy<GaugeVec> = Lazy::new(|| {
+    tenant_gauge(
+        "oz_monitor_tenant_activity_score",
+        "Activity score used for load balancing (0-1)",
+    )
+});
+
+static SYSTEM_WORKERS: Lazy<Gauge> =
+    Lazy::new(|| system_gauge("oz_monitor_worker_count", "Active workers"));
+static SYSTEM_TENANTS: Lazy<Gauge> =
+    Lazy::new(|| system_gauge("oz_monitor_tenant_count", "Active tenants"));
+static SYSTEM_MONITORS: Lazy<Gauge> =
+    Lazy::new(|| system_gauge("oz_monitor_monitor_count", "Monitors being processed"));
+static SYSTEM_RPC_RATE: Lazy<Gauge> =
+    Lazy::new(|| system_gauge("oz_monitor_rpc_rate", "RPC calls per second across workers"));
+static SYSTEM_CACHE_HIT_RATE: Lazy<Gauge> =
+    Lazy::new(|| system_gauge("oz_monitor_cache_hit_rate", "Block cache hit rate (0-1)"));
+static SYSTEM_BLOCK_LAG: Lazy<Gauge> = Lazy::new(|| {
+    system_gauge(
+        "oz_monitor_block_lag",
+        "Average confirmed blocks not yet broadcast per network",
+    )
+});
+static SYSTEM_MATCHES: Lazy<Gauge> = Lazy::new(|| {
+    system_gauge(
+        "oz_monitor_matches_last_hour",
+        "Matches across tenants in the last hour",
+    )
+});
+static SYSTEM_HEALTH: Lazy<Gauge> =
+    Lazy::new(|| system_gauge("oz_monitor_health_score", "System health score (0-100)"));
+
+/// Replace the worker gauges with the given worker metrics
+pub fn observe_workers(workers: &[WorkerMetrics]) {
+    let gauges = [
+        &*WORKER_TENANTS,
+        &*WORKER_CPU,
+        &*WORKER_MEMORY,
+        &*WORKER_RPC_RATE,
+        &*WORKER_PROCESSING_TIME,
+        &*WORKER_ERRORS,
+        &*WORKER_UPTIME,
+        &*WORKER_CAPACITY_WEIGHT,
+    ];
+    gauges.iter().for_each(|gauge| gauge.reset());
+
+    for worker in workers {
+        let labels = [worker.worker_id.as_str()];
+        WORKER_TENANTS
+            .with_label_values(&labels)
+            .set(worker.tenant_count as f64);
+        WORKER_CPU.with_label_values(&labels).set(worker.cpu_usage);
+        WORKER_MEMORY
+            .with_label_values(&labels)
+            .set(worker.memory_usage);
+        WORKER_RPC_RATE
+            .with_label_values(&labels)
+            .set(worker.rpc_rate);
+        WORKER_PROCESSING_TIME
+            .with_label_values(&labels)
+            .set(worker.avg_processing_time_ms);
+        WORKER_ERRORS
+            .with_label_values(&labels)
+            .set(worker.errors_last_hour as f64);
+        WORKER_UPTIME
+            .with_label_values(&labels)
+            .set(worker.uptime_seconds as f64);
+        WORKER_CAPACITY_WEIGHT
+            .with_label_values(&labels)
+            .set(worker.capacity_weight);
+    }
+}
+
+/// Replace the tenant gauges with the given tenant metrics
+pub fn observe_tenants(tenants: &[TenantMetrics]) {
+    let gauges = [
+        &*TENANT_MONITORS,
+        &*TENANT_RPC_CALLS,
+        &*TENANT_FILTER_COMPLEXITY,
+        &*TENANT_MATCHES,
+        &*TENANT_NOTIFICATIONS,
+        &*TENANT_ACTIVITY,
+    ];
+    gauges.iter().for_each(|gauge| gauge.reset());
+
+    for tenant in tenants {
+        let tenant_id = tenant.tenant_id.to_string();
+        let labels = [tenant_id.as_str()];
+        TENANT_MONITORS
+            .with_label_values(&labels)
+            .set(tenant.monitors_count as f64);
+        TENANT_RPC_CALLS
+            .with_label_values(&labels)
+            .set(tenant.avg_rpc_calls_per_minute);
+        TENANT_FILTER_COMPLEXITY
+            .with_label_values(&labels)
+            .set(tenant.avg_filter_complexity);
+        TENANT_MATCHES
+            .with_label_values(&labels)
+            .set(tenant.total_matches_last_hour as f64);
+        TENANT_NOTIFICATIONS
+            .with_label_values(&labels)
+            .set(tenant.notifications_sent_last_hour as f64);
+        TENANT_ACTIVITY
+            .with_label_values(&labels)
+            .set(tenant.activity_score());
+    }
+}
+
+/// Set the system gauges
+pub fn observe_system(system: &SystemMetrics) {
+    SYSTEM_WORKERS.set(system.active_workers as f64);
+    SYSTEM_TENANTS.set(system.active_tenants as f64);
+    SYSTEM_MONITORS.set(system.total_monitors as f64);
+    SYSTEM_RPC_RATE.set(system.total_rpc_rate);
+    SYSTEM_CACHE_HIT_RATE.set(system.cache_hit_rate);
+    SYSTEM_BLOCK_LAG.set(system.avg_block_lag);
+    SYSTEM_MATCHES.set(system.total_matches_last_hour as f64);
+    SYSTEM_HEALTH.set(system.health_score);
+}
+
+/// Share of block requests served from cache since startup; 1 before any request
+pub fn cache_hit_rate() -> f64 {
+    let total = |counter: &IntCounterVec| -> u64 {
+        counter
+            .collect()
+            .iter()
+            .flat_map(|family| family.get_metric())
+            .map(|metric| metric.get_counter().get_value() as u64)
+            .sum()
+    };
+    let hits = total(&CACHE_HITS);
+    let requests = hits + total(&CACHE_MISSES);
+    if requests == 0 {
+        1.0
+    } else {
+        hits as f64 / requests as f64
+    }
+}
+
+fn worker_gauge(name: &str, help: &str) -> GaugeVec {
+    register(GaugeVec::new(Opts::new(name, help), &["worker_id"]))
+}
+
+fn tenant_gauge(name: &str, help: &str) -> GaugeVec {
+    register(GaugeVec::new(Opts::new(name, help), &["tenant_id"]))
+}
+
+fn system_gauge(name: &str, help: &str) -> Gauge {
+    register(Gauge::with_opts(Opts::new(name, help)))
+}
+
+fn register<M>(metric: prometheus::Result<M>) -> M
+where
+    M: prometheus::core::Collector + Clone + 'static,
+{
+    let metric = metric.expect("metric definition is valid");
+    REGISTRY
+        .register(Box::new(metric.clone()))
+        .expect("metric is registered once");
+    metric
+}
+
+/// Encode all registered metrics in the Prometheus text format
+pub fn gather() -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buffer)
+        .expect("text encoding does not fail");
+    String::from_utf8(buffer).expect("text encoding is UTF-8")
+}
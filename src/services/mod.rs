@@ -1,15 +1,118 @@
+pub mod activity_anomalies;
+pub mod address_index;
+pub mod assignment_store;
+pub mod assignment_webhooks;
+pub mod audit_log;
 pub mod block_cache;
+pub mod block_envelope;
 pub mod cached_client_pool;
+pub mod chain_support;
+pub mod checkpoints;
+pub mod confirmations;
+pub mod control_channel;
+pub mod coverage;
+pub mod db_outage;
+pub mod dead_letters;
+pub mod distributed_lock;
+pub mod endpoint_health;
 pub mod error;
+pub mod event_bus;
+pub mod filter_debug;
+pub mod hash_ring;
+pub mod hooks;
 pub mod load_balancer;
+pub mod match_feed;
+pub mod match_severity;
+pub mod match_store;
+pub mod match_webhooks;
+pub mod metrics;
+pub mod monitor_health;
+pub mod monitor_store;
+pub mod network_settings;
+pub mod notification_channels;
 pub mod oz_monitor_integration;
+pub mod publish_buffer;
+pub mod quiet_hours;
+pub mod rebalance_scheduler;
+pub mod redis_keyspace;
+pub mod resource_usage;
+pub mod retry;
+pub mod rpc_costs;
+pub mod rpc_limits;
+pub mod script_invalidation;
+pub mod session_recorder;
 pub mod shared_block_watcher;
+pub mod shutdown;
+pub mod spill_buffer;
+pub mod startup_checks;
+pub mod stellar_events;
+pub mod templates;
+pub mod tenant_activity;
+pub mod tenant_migration;
+pub mod tenant_pauses;
+pub mod tenant_store;
+pub mod trace_context;
+pub mod trial_tenants;
+pub mod trigger_scripts;
+pub mod watcher_handoff;
 pub mod worker_pool;
 
+pub use activity_anomalies::{ActivityAnomalyDetector, AnomalyConfig, AnomalyStore};
+pub use address_index::AddressIndex;
+pub use assignment_store::AssignmentStore;
+pub use assignment_webhooks::AssignmentWebhookNotifier;
+pub use audit_log::{AuditFilter, AuditLog, AuditRecord};
 pub use block_cache::{BlockCacheService, CachedBlockClient};
-pub use cached_client_pool::CachedClientPool;
-pub use error::ServiceError;
-pub use load_balancer::LoadBalancer;
-pub use oz_monitor_integration::{OzMonitorServices, TenantMonitorContext};
-pub use shared_block_watcher::SharedBlockWatcher;
-pub use worker_pool::{MonitorWorker, MonitorWorkerPool};
+pub use block_envelope::{EnvelopeHeader, EnvelopeNetworkType, BLOCK_EVENT_SCHEMA_VERSION};
+pub use cached_client_pool::{CachedClientPool, ClientReuseStats};
+pub use chain_support::ChainSupport;
+pub use checkpoints::{CheckpointLedger, CheckpointRewind};
+pub use confirmations::ConfirmationDepths;
+pub use control_channel::{ControlChannel, ControlCommand};
+pub use coverage::CoverageReporter;
+pub use dead_letters::DeadLetterStore;
+pub use distributed_lock::{DistributedLock, LockGuard};
+pub use endpoint_health::{EndpointHealth, EndpointHealthConfig};
+pub use error::{ErrorResponse, ServiceError};
+pub use event_bus::{
+    AssignmentChange, ConfigChange, Event, EventBus, EventBusBackend, InProcessEventBus,
+    RedisEventBus,
+};
+pub use filter_debug::FilterDebugService;
+pub use hash_ring::HashRing;
+pub use hooks::{LifecycleHook, LifecycleHooks};
+pub use load_balancer::{LoadBalancer, TenantSharding};
+pub use match_feed::{MatchEvent, MatchFeed};
+pub use match_severity::MatchSeverityService;
+pub use match_store::MatchStore;
+pub use match_webhooks::{MatchWebhookDispatcher, MatchWebhookStore};
+pub use monitor_health::MonitorHealth;
+pub use monitor_store::{MonitorFilter, MonitorStore};
+pub use network_settings::{EffectiveNetworkSettings, NetworkSettingsResolver};
+pub use notification_channels::{NotificationChannel, NotificationChannels};
+pub use oz_monitor_integration::{OzMonitorCacheConfig, OzMonitorServices, TenantMonitorContext};
+pub use publish_buffer::{Publication, PublishBuffer};
+pub use quiet_hours::QuietHoursService;
+pub use rebalance_scheduler::RebalanceScheduler;
+pub use redis_keyspace::RedisKeyspace;
+pub use resource_usage::ResourceSampler;
+pub use retry::RetryPolicy;
+pub use rpc_costs::{RpcCostConfig, RpcCostTracker};
+pub use rpc_limits::{RpcAdmission, TenantRpcLimiter};
+pub use script_invalidation::{ScriptInvalidation, ScriptInvalidationService};
+pub use session_recorder::{RecordedSession, ReplayMatch, SessionRecorder};
+pub use shared_block_watcher::{NetworkWatcherStatus, SharedBlockWatcher};
+pub use shutdown::{ShutdownSequence, ShutdownSignal};
+pub use spill_buffer::SpillBuffer;
+pub use startup_checks::{StartupCheckOptions, StartupChecks, StartupReport};
+pub use stellar_events::StellarEventFilter;
+pub use templates::{InstantiatedTemplate, TemplateCatalog, TemplateService};
+pub use tenant_activity::TenantActivity;
+pub use tenant_migration::TenantMigrationService;
+pub use tenant_pauses::TenantPauses;
+pub use tenant_store::{TenantFilter, TenantStore};
+pub use trace_context::TraceContext;
+pub use trial_tenants::{TrialLimits, TrialTenants};
+pub use trigger_scripts::TriggerScriptStore;
+pub use watcher_handoff::{WatcherCursor, WatcherHandoff};
+pub use worker_pool::{BlockOverflowPolicy, MonitorWorker, MonitorWorkerPool};
@@ -0,0 +1,131 @@
+//! Monitor Health Service
+//!
+//! Counts consecutive blocks on which a monitor failed, either because its
+//! configuration cannot be deserialized or because the filter service errors
+//! on it. Once a monitor reaches the failure threshold it is deactivated with
+//! the last error recorded, and the tenant is notified through
+//! `tenant_monitor_deactivations`.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use sqlx::PgPool;
+use std::sync::Arc;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+/// Tracks failing monitors and deactivates persistent failures
+pub struct MonitorHealth {
+    db: Arc<PgPool>,
+    /// Consecutive failing blocks before a monitor is deactivated
+    threshold: u32,
+    /// Consecutive failing blocks by tenant and monitor name
+    failures: DashMap<(Uuid, String), u32>,
+}
+
+impl MonitorHealth {
+    /// Create a new tracker deactivating monitors after `threshold` failing blocks
+    pub fn new(db: Arc<PgPool>, threshold: u32) -> Self {
+        Self {
+            db,
+            threshold: threshold.max(1),
+            failures: DashMap::new(),
+        }
+    }
+
+    /// Charge a monitor with a failed block.
+    ///
+    /// Returns true if the monitor reached the threshold and was deactivated.
+    pub async fn record_failure(&self, tenant_id: Uuid, monitor_name: &str, error: &str) -> bool {
+        let failures = {
+            let mut entry = self
+                .failures
+                .entry((tenant_id, monitor_name.to_string()))
+                .or_insert(0);
+            *entry += 1;
+            *entry
+        };
+
+        debug!(
+            "Monitor {} of tenant {} failed on {} consecutive blocks: {}",
+            monitor_name, tenant_id, failures, error
+        );
+        if failures < self.threshold {
+            return false;
+        }
+
+        match self
+            .deactivate(tenant_id, monitor_name, failures, error)
+            .await
+        {
+            Ok(()) => {
+                warn!(
+                    "Deactivated monitor {} of tenant {} after {} consecutive failing blocks: {}",
+                    monitor_name, tenant_id, failures, error
+                );
+                self.failures.remove(&(tenant_id, monitor_name.to_string()));
+                true
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to deactivate monitor {} of tenant {}: {}",
+                    monitor_name, tenant_id, e
+                );
+                false
+            }
+        }
+    }
+
+    /// Reset the failure count of monitors that processed a block successfully
+    pub fn record_success<'a>(
+        &self,
+        tenant_id: Uuid,
+        monitor_names: impl IntoIterator<Item = &'a String>,
+    ) {
+        if self.failures.is_empty() {
+            return;
+        }
+        for monitor_name in monitor_names {
+            self.failures.remove(&(tenant_id, monitor_name.clone()));
+        }
+    }
+
+    async fn deactivate(
+        &self,
+        tenant_id: Uuid,
+        monitor_name: &str,
+        failures: u32,
+        error: &str,
+    ) -> Result<()> {
+        let mut tx = self.db.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE tenant_monitors
+            SET is_active = false, error_message = $3, errored_at = now(), updated_at = now()
+            WHERE tenant_id = $1 AND name = $2
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(monitor_name)
+        .bind(error)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_monitor_deactivations
+                (tenant_id, monitor_name, consecutive_failures, last_error)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(monitor_name)
+        .bind(failures as i32)
+        .bind(error)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
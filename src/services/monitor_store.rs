@@ -0,0 +1,120 @@
+//! Monitor Store
+//!
+//! Platform-wide view of the `tenant_monitors` table for administrators,
+//! e.g. to audit which tenants watch a contract address. Filtering and
+//! pagination happen in Postgres so listings stay cheap across all tenants;
+//! address lookups use the `tenant_monitor_addresses` reverse index, which a
+//! trigger keeps in sync with monitor configuration changes.
+
+use anyhow::Result;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::MonitorSummary;
+
+/// Conditions on listed monitors; unset fields match every monitor
+#[derive(Debug, Clone, Default)]
+pub struct MonitorFilter {
+    /// Only monitors on this network
+    pub network_slug: Option<String>,
+
+    /// Only active or only inactive monitors
+    pub active: Option<bool>,
+
+    /// Only monitors of this tenant
+    pub tenant_id: Option<Uuid>,
+
+    /// Only monitors watching this contract address, compared case-insensitively
+    pub address: Option<String>,
+}
+
+/// WHERE clause applying a [`MonitorFilter`] bound as `$1`..`$4`; addresses
+/// are looked up in the `tenant_monitor_addresses` index
+const MONITOR_FILTER: &str = r#"
+    WHERE ($1::TEXT IS NULL OR n.network_id = $1)
+      AND ($2::BOOLEAN IS NULL OR m.is_active = $2)
+      AND ($3::UUID IS NULL OR m.tenant_id = $3)
+      AND ($4::TEXT IS NULL OR m.id IN (
+          SELECT i.monitor_id FROM tenant_monitor_addresses i
+          WHERE i.address = LOWER($4)
+      ))
+"#;
+
+/// Addresses in a monitor's configuration, one row per address
+const MONITOR_ADDRESSES: &str = r#"
+    jsonb_array_elements(
+        CASE WHEN jsonb_typeof(m.configuration::jsonb -> 'addresses') = 'array'
+             THEN m.configuration::jsonb -> 'addresses'
+             ELSE '[]'::jsonb
+        END
+    ) AS a(entry)
+"#;
+
+/// Access to monitors across all tenants
+pub struct MonitorStore {
+    db: Arc<PgPool>,
+}
+
+impl MonitorStore {
+    /// Create a new monitor store
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// One page of the monitors matching a filter, ordered by tenant and name
+    pub async fn list(
+        &self,
+        filter: &MonitorFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<MonitorSummary>> {
+        let monitors = sqlx::query_as::<_, MonitorSummary>(&format!(
+            r#"
+            SELECT m.id, m.tenant_id, t.name AS tenant_name, m.monitor_id, m.name,
+                   n.network_id AS network_slug,
+                   ARRAY(SELECT a.entry ->> 'address' FROM {addresses}) AS addresses,
+                   m.is_active IS TRUE AS is_active, m.is_critical, m.error_message,
+                   m.created_at, COALESCE(m.updated_at, m.created_at) AS updated_at
+            FROM tenant_monitors m
+            JOIN tenants t ON t.id = m.tenant_id
+            JOIN tenant_networks n ON n.id = m.network_id
+            {filter}
+            ORDER BY t.name, m.tenant_id, m.name, m.id
+            LIMIT $5 OFFSET $6
+            "#,
+            addresses = MONITOR_ADDRESSES,
+            filter = MONITOR_FILTER,
+        ))
+        .bind(&filter.network_slug)
+        .bind(filter.active)
+        .bind(filter.tenant_id)
+        .bind(&filter.address)
+        .bind(limit.min(i64::MAX as u64) as i64)
+        .bind(offset.min(i64::MAX as u64) as i64)
+        .fetch_all(&*self.db)
+        .await?;
+        Ok(monitors)
+    }
+
+    /// Number of monitors matching a filter
+    pub async fn count(&self, filter: &MonitorFilter) -> Result<u64> {
+        let count = sqlx::query_scalar::<_, i64>(&format!(
+            r#"
+            SELECT COUNT(*)
+            FROM tenant_monitors m
+            JOIN tenants t ON t.id = m.tenant_id
+            JOIN tenant_networks n ON n.id = m.network_id
+            {}
+            "#,
+            MONITOR_FILTER
+        ))
+        .bind(&filter.network_slug)
+        .bind(filter.active)
+        .bind(filter.tenant_id)
+        .bind(&filter.address)
+        .fetch_one(&*self.db)
+        .await?;
+        Ok(count.max(0) as u64)
+    }
+}
@@ -0,0 +1,289 @@
+//! Network Settings
+//!
+//! Resolves the settings a network runs with once every override layer is
+//! applied: the network's configuration in `tenant_networks`, the
+//! orchestrator's block watcher, block cache and retry configuration, and
+//! tenants' `tenant_networks.confirmation_blocks` overrides. Every value
+//! names the layer it came from, for debugging why a network behaves
+//! unexpectedly.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use openzeppelin_monitor::models::{BlockChainType, Network};
+
+use crate::repositories::RepositoryError;
+use crate::services::error::ServiceError;
+use crate::services::shared_block_watcher::poll_interval;
+
+const NETWORK_CONFIGURATION: &str = "network configuration";
+const TENANT_OVERRIDE: &str = "tenant override";
+const CHAIN_TYPE_DEFAULT: &str = "chain type default";
+
+/// Settings from the orchestrator configuration, the same for every network
+#[derive(Debug, Clone)]
+pub struct NetworkSettingsDefaults {
+    pub max_blocks_per_fetch: u64,
+    pub fetch_retry_attempts: u32,
+    pub fetch_retry_delay: Duration,
+    pub rpc_retry_attempts: u32,
+    pub rpc_retry_base_delay: Duration,
+    pub rpc_retry_max_delay: Duration,
+    pub block_ttl: Duration,
+    pub latest_block_ttl: Duration,
+}
+
+/// A resolved value and the layer it came from
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Setting<T> {
+    pub value: T,
+    /// `network configuration`, `tenant override`, `chain type default` or
+    /// the configuration key it was read from
+    pub source: &'static str,
+}
+
+impl<T> Setting<T> {
+    fn new(value: T, source: &'static str) -> Self {
+        Self { value, source }
+    }
+}
+
+/// Settings of one tenant on the network
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantNetworkSettings {
+    pub tenant_id: Uuid,
+    /// Confirmations before the tenant's matches are final
+    pub confirmation_blocks: Setting<u64>,
+    /// Match states that fire the tenant's triggers
+    pub trigger_on_states: Vec<String>,
+}
+
+/// Settings a network runs with after all override layers
+#[derive(Debug, Clone, Serialize)]
+pub struct EffectiveNetworkSettings {
+    pub network_slug: String,
+    pub poll_interval_secs: Setting<u64>,
+    /// Depth the block watcher runs at, the shallowest any tenant requires
+    pub confirmation_blocks: Setting<u64>,
+    pub max_blocks_per_fetch: Setting<u64>,
+    pub fetch_retry_attempts: Setting<u32>,
+    pub fetch_retry_delay_ms: Setting<u64>,
+    pub rpc_retry_attempts: Setting<u32>,
+    pub rpc_retry_base_delay_ms: Setting<u64>,
+    pub rpc_retry_max_delay_ms: Setting<u64>,
+    pub block_ttl_secs: Setting<u64>,
+    pub latest_block_ttl_secs: Setting<u64>,
+    /// Tenants with the network active, the most recently updated first
+    pub tenants: Vec<TenantNetworkSettings>,
+}
+
+/// A tenant's active configuration of the network
+#[derive(Debug, Clone)]
+struct TenantNetwork {
+    tenant_id: Uuid,
+    /// Depth in the tenant's network configuration
+    network_depth: u64,
+    /// `tenant_networks.confirmation_blocks`
+    override_depth: Option<u64>,
+    trigger_on_states: Vec<String>,
+}
+
+impl TenantNetwork {
+    fn confirmation_blocks(&self) -> Setting<u64> {
+        match self.override_depth {
+            Some(depth) => Setting::new(depth, TENANT_OVERRIDE),
+            None => Setting::new(self.network_depth, NETWORK_CONFIGURATION),
+        }
+    }
+}
+
+/// Resolves networks' effective settings
+pub struct NetworkSettingsResolver {
+    db: Arc<PgPool>,
+    defaults: NetworkSettingsDefaults,
+}
+
+impl NetworkSettingsResolver {
+    /// Create a new resolver over the given configuration
+    pub fn new(db: Arc<PgPool>, defaults: NetworkSettingsDefaults) -> Self {
+        Self { db, defaults }
+    }
+
+    /// Effective settings of a network some active tenant uses
+    pub async fn resolve(
+        &self,
+        network_slug: &str,
+    ) -> Result<EffectiveNetworkSettings, ServiceError> {
+        let rows = sqlx::query_as::<_, (Uuid, serde_json::Value, Option<i32>, Vec<String>)>(
+            r#"
+            SELECT tenant_id, configuration, confirmation_blocks, trigger_on_states
+            FROM tenant_networks
+            WHERE network_id = $1 AND is_active = true
+            ORDER BY updated_at DESC, tenant_id
+            "#,
+        )
+        .bind(network_slug)
+        .fetch_all(&*self.db)
+        .await
+        .map_err(RepositoryError::from)?;
+
+        let mut network_type = None;
+        let mut tenants = Vec::with_capacity(rows.len());
+        for (tenant_id, configuration, override_depth, trigger_on_states) in rows {
+            let network: Network = serde_json::from_value(configuration).map_err(|e| {
+                ServiceError::InvalidState(format!(
+                    "Invalid configuration of network {} for tenant {}: {}",
+                    network_slug, tenant_id, e
+                ))
+            })?;
+            network_type.get_or_insert(network.network_type);
+            tenants.push(TenantNetwork {
+                tenant_id,
+                network_depth: network.confirmation_blocks,
+                override_depth: override_depth.map(|depth| depth.max(0) as u64),
+                trigger_on_states,
+            });
+        }
+
+        let network_type = network_type.ok_or_else(|| RepositoryError::NotFound {
+            entity_type: "network".to_string(),
+            id: network_slug.to_string(),
+        })?;
+        Ok(resolve_settings(
+            &self.defaults,
+            network_slug,
+            &network_type,
+            tenants,
+        ))
+    }
+}
+
+/// Apply the override layers to a network's tenant configurations, the
+/// most recently updated first
+fn resolve_settings(
+    defaults: &NetworkSettingsDefaults,
+    network_slug: &str,
+    network_type: &BlockChainType,
+    tenants: Vec<TenantNetwork>,
+) -> EffectiveNetworkSettings {
+    // The watcher runs at the shallowest depth any tenant requires, or at the
+    // network's own depth when no override lowers it
+    let network_depth = tenants.first().map_or(0, |tenant| tenant.network_depth);
+    let confirmation_blocks = tenants
+        .iter()
+        .map(TenantNetwork::confirmation_blocks)
+        .filter(|setting| setting.value < network_depth)
+        .min_by_key(|setting| setting.value)
+        .unwrap_or(Setting::new(network_depth, NETWORK_CONFIGURATION));
+
+    EffectiveNetworkSettings {
+        network_slug: network_slug.to_string(),
+        poll_interval_secs: Setting::new(poll_interval(network_type).as_secs(), CHAIN_TYPE_DEFAULT),
+        confirmation_blocks,
+        max_blocks_per_fetch: Setting::new(
+            defaults.max_blocks_per_fetch,
+            "block_watcher.max_blocks_per_fetch",
+        ),
+        fetch_retry_attempts: Setting::new(
+            defaults.fetch_retry_attempts,
+            "block_watcher.retry_attempts",
+        ),
+        fetch_retry_delay_ms: Setting::new(
+            defaults.fetch_retry_delay.as_millis() as u64,
+            "block_watcher.retry_delay_ms",
+        ),
+        rpc_retry_attempts: Setting::new(defaults.rpc_retry_attempts, "retry.max_attempts"),
+        rpc_retry_base_delay_ms: Setting::new(
+            defaults.rpc_retry_base_delay.as_millis() as u64,
+            "retry.base_delay",
+        ),
+        rpc_retry_max_delay_ms: Setting::new(
+            defaults.rpc_retry_max_delay.as_millis() as u64,
+            "retry.max_delay",
+        ),
+        block_ttl_secs: Setting::new(defaults.block_ttl.as_secs(), "block_cache.block_ttl"),
+        latest_block_ttl_secs: Setting::new(
+            defaults.latest_block_ttl.as_secs(),
+            "block_cache.latest_block_ttl",
+        ),
+        tenants: tenants
+            .into_iter()
+            .map(|tenant| TenantNetworkSettings {
+                tenant_id: tenant.tenant_id,
+                confirmation_blocks: tenant.confirmation_blocks(),
+                trigger_on_states: tenant.trigger_on_states,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> NetworkSettingsDefaults {
+        NetworkSettingsDefaults {
+            max_blocks_per_fetch: 100,
+            fetch_retry_attempts: 3,
+            fetch_retry_delay: Duration::from_millis(1000),
+            rpc_retry_attempts: 3,
+            rpc_retry_base_delay: Duration::from_millis(500),
+            rpc_retry_max_delay: Duration::from_secs(30),
+            block_ttl: Duration::from_secs(60),
+            latest_block_ttl: Duration::from_secs(5),
+        }
+    }
+
+    fn tenant(network_depth: u64, override_depth: Option<u64>) -> TenantNetwork {
+        TenantNetwork {
+            tenant_id: Uuid::new_v4(),
+            network_depth,
+            override_depth,
+            trigger_on_states: vec!["provisional".to_string(), "finalized".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_shallowest_override_sets_the_watcher_depth() {
+        let settings = resolve_settings(
+            &defaults(),
+            "ethereum_mainnet",
+            &BlockChainType::EVM,
+            vec![tenant(12, None), tenant(12, Some(20)), tenant(12, Some(3))],
+        );
+
+        assert_eq!(
+            settings.confirmation_blocks,
+            Setting::new(3, TENANT_OVERRIDE)
+        );
+        assert_eq!(
+            settings.tenants[0].confirmation_blocks,
+            Setting::new(12, NETWORK_CONFIGURATION)
+        );
+        assert_eq!(settings.tenants[1].confirmation_blocks.value, 20);
+        assert_eq!(
+            settings.poll_interval_secs,
+            Setting::new(15, CHAIN_TYPE_DEFAULT)
+        );
+        assert_eq!(settings.block_ttl_secs.source, "block_cache.block_ttl");
+    }
+
+    #[test]
+    fn test_deeper_overrides_keep_the_network_depth() {
+        let settings = resolve_settings(
+            &defaults(),
+            "stellar_mainnet",
+            &BlockChainType::Stellar,
+            vec![tenant(2, Some(6)), tenant(2, None)],
+        );
+
+        assert_eq!(
+            settings.confirmation_blocks,
+            Setting::new(2, NETWORK_CONFIGURATION)
+        );
+        assert_eq!(settings.poll_interval_secs.value, 5);
+    }
+}
@@ -0,0 +1,81 @@
+//! Notification Channels
+//!
+//! Extension point for notification sinks that upstream OpenZeppelin Monitor
+//! does not support (PagerDuty, Opsgenie, Kafka, ...). Channels are registered
+//! under a trigger name; when a monitor references that trigger,
+//! `execute_triggers` routes the match to the channel instead of the upstream
+//! trigger execution service.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+use crate::services::retry::RetryPolicy;
+
+/// Custom notification sink
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Deliver a monitor match with the prepared trigger variables
+    async fn send(
+        &self,
+        tenant_match: &TenantMonitorMatch,
+        variables: &HashMap<String, String>,
+    ) -> Result<()>;
+}
+
+/// Registry of custom notification channels keyed by trigger name
+#[derive(Clone, Default)]
+pub struct NotificationChannels {
+    channels: HashMap<String, Arc<dyn NotificationChannel>>,
+    retry: RetryPolicy,
+}
+
+impl NotificationChannels {
+    /// Create an empty channel registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a channel for the given trigger name, replacing any existing one
+    pub fn register(
+        &mut self,
+        trigger_name: impl Into<String>,
+        channel: Arc<dyn NotificationChannel>,
+    ) {
+        self.channels.insert(trigger_name.into(), channel);
+    }
+
+    /// Retry failed deliveries with the given policy
+    pub fn set_retry_policy(&mut self, retry: RetryPolicy) {
+        self.retry = retry;
+    }
+
+    /// Policy failed deliveries are retried with
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Get the channel registered for a trigger name
+    pub fn get(&self, trigger_name: &str) -> Option<Arc<dyn NotificationChannel>> {
+        self.channels.get(trigger_name).cloned()
+    }
+
+    /// Deliver a match through a channel, retrying per the registry's policy
+    pub async fn send(
+        &self,
+        channel: &dyn NotificationChannel,
+        tenant_match: &TenantMonitorMatch,
+        variables: &HashMap<String, String>,
+    ) -> Result<()> {
+        self.retry
+            .retry(|| channel.send(tenant_match, variables))
+            .await
+    }
+
+    /// Check if any channels are registered
+    pub fn is_empty(&self) -> bool {
+        self.channels.is_empty()
+    }
+}
@@ -6,32 +6,98 @@
 
 use anyhow::Result;
 use dashmap::DashMap;
+use moka::sync::Cache;
 use sqlx::PgPool;
-use std::collections::{HashMap, HashSet};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
-use tracing::{error, info, instrument};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, instrument, warn, Instrument};
 use uuid::Uuid;
 
 // Import OpenZeppelin Monitor types and services
 use openzeppelin_monitor::{
     models::{
-        BlockType, ContractSpec, EVMBlock, Monitor, MonitorMatch, Network, StellarBlock, Trigger,
+        BlockChainType, BlockType, ContractSpec, EVMBlock, Monitor, MonitorMatch, Network,
+        StellarBlock, Trigger,
     },
     repositories::{
         MonitorRepositoryTrait, NetworkRepositoryTrait, TriggerRepositoryTrait, TriggerService,
     },
     services::{
-        blockchain::ClientPoolTrait,
-        filter::FilterService,
+        blockchain::{BlockChainClient, ClientPoolTrait},
+        filter::{BlockFilterFactory, FilterService},
         notification::NotificationService,
         trigger::{TriggerExecutionService, TriggerExecutionServiceTrait},
     },
 };
 
+use crate::models::{
+    DeliveryRoute, FilterDebugSample, HeldNotification, MatchState, ShardBy, TenantShard,
+    TriggerTestResult,
+};
 use crate::repositories::{
-    TenantAwareMonitorRepository, TenantAwareNetworkRepository, TenantAwareTriggerRepository,
+    RepositoryError, TenantAwareMonitorRepository, TenantAwareNetworkRepository,
+    TenantAwareTriggerRepository,
 };
+use crate::services::address_index::AddressIndex;
 use crate::services::cached_client_pool::CachedClientPool;
+use crate::services::checkpoints::CheckpointLedger;
+use crate::services::confirmations::ConfirmationDepths;
+use crate::services::db_outage::{self, DatabaseOutage};
+use crate::services::dead_letters::DeadLetterStore;
+use crate::services::error::ServiceError;
+use crate::services::filter_debug::FilterDebugService;
+use crate::services::match_severity::MatchSeverityService;
+use crate::services::match_store::MatchStore;
+use crate::services::metrics::{MATCH_SEVERITIES, NOTIFICATIONS_RATE_LIMITED, TRIGGER_EXECUTIONS};
+use crate::services::monitor_health::MonitorHealth;
+use crate::services::notification_channels::NotificationChannels;
+use crate::services::quiet_hours::QuietHoursService;
+use crate::services::resource_usage::ResourceSampler;
+use crate::services::rpc_costs::RpcCostTracker;
+use crate::services::rpc_limits::{RpcAdmission, TenantRpcLimiter};
+use crate::services::tenant_activity::TenantActivity;
+use crate::services::tenant_pauses::TenantPauses;
+use crate::services::trace_context::tenant_span;
+use crate::services::trial_tenants::{TrialLimits, TrialTenants};
+
+/// Size and expiry bounds of the per-worker configuration caches
+#[derive(Debug, Clone)]
+pub struct OzMonitorCacheConfig {
+    /// Maximum monitors cached across all tenants
+    pub monitor_capacity: u64,
+    /// How long a tenant's monitors are cached before being reloaded
+    pub monitor_ttl: Duration,
+    /// Maximum contract specs cached
+    pub contract_spec_capacity: u64,
+    /// How long a contract spec is cached
+    pub contract_spec_ttl: Duration,
+    /// Maximum trigger scripts cached
+    pub trigger_script_capacity: u64,
+    /// How long a trigger script is cached
+    pub trigger_script_ttl: Duration,
+    /// Maximum trigger condition results cached
+    pub trigger_condition_capacity: u64,
+    /// How long a trigger condition result is cached
+    pub trigger_condition_ttl: Duration,
+}
+
+impl Default for OzMonitorCacheConfig {
+    fn default() -> Self {
+        Self {
+            monitor_capacity: 10_000,
+            monitor_ttl: Duration::from_secs(600),
+            contract_spec_capacity: 10_000,
+            contract_spec_ttl: Duration::from_secs(3600),
+            trigger_script_capacity: 1_000,
+            trigger_script_ttl: Duration::from_secs(3600),
+            trigger_condition_capacity: 10_000,
+            trigger_condition_ttl: Duration::from_secs(86_400),
+        }
+    }
+}
 
 /// OpenZeppelin Monitor services wrapper with tenant awareness
 pub struct OzMonitorServices {
@@ -44,25 +110,94 @@ pub struct OzMonitorServices {
     /// Client pool for blockchain connections
     client_pool: Arc<CachedClientPool>,
 
+    /// Custom notification channels keyed by trigger name
+    notification_channels: Arc<NotificationChannels>,
+
+    /// Tenant quiet hours enforcement
+    quiet_hours: Arc<QuietHoursService>,
+
+    /// Tenant match severities and their routes
+    severities: Arc<MatchSeverityService>,
+
+    /// Tenants paused through the management API, whose blocks are skipped
+    pauses: Arc<TenantPauses>,
+
+    /// Trial tenants and the limits of their scheduling class
+    trials: Arc<TrialTenants>,
+
+    /// Sampled recording of filter runs for debugging
+    filter_debug: Arc<FilterDebugService>,
+
+    /// Tenant RPC cap enforcement; no enforcement if unset
+    rpc_limiter: Option<Arc<TenantRpcLimiter>>,
+
+    /// Attribution of filter RPC requests to tenants; disabled if unset
+    rpc_costs: Option<Arc<RpcCostTracker>>,
+
+    /// Deactivation of persistently failing monitors; disabled if unset
+    monitor_health: Option<Arc<MonitorHealth>>,
+
+    /// Per-tenant block processing checkpoints; not recorded if unset
+    checkpoints: Option<Arc<CheckpointLedger>>,
+
+    /// Degraded mode while the database is unreachable; block processing
+    /// fails with the database if unset
+    outage: Option<Arc<DatabaseOutage>>,
+
+    /// Context each tenant was last filtered with, reused while the database
+    /// is unreachable; only kept with `outage` set
+    last_contexts: DashMap<Uuid, TenantMonitorContext>,
+
+    /// Monitors by network and contract address, rebuilt as monitors are loaded
+    address_index: AddressIndex,
+
+    /// Per-tenant timing of filter and trigger spans for CPU attribution; not timed if unset
+    resource_usage: Option<Arc<ResourceSampler>>,
+
+    /// Per-tenant RPC calls, matches and notifications for tenant metrics; not recorded if unset
+    tenant_activity: Option<Arc<TenantActivity>>,
+
+    /// Confirmation depth each tenant requires per network
+    confirmations: Arc<ConfirmationDepths>,
+
+    /// Provisional matches awaiting their tenant's confirmation depth, by network
+    pending_confirmations: DashMap<String, Vec<PendingMatch>>,
+
+    /// Persisted matches and their lifecycle state
+    match_store: Arc<MatchStore>,
+
+    /// Failed trigger deliveries awaiting redelivery
+    dead_letters: Arc<DeadLetterStore>,
+
     /// Tenant-aware repositories
     monitor_repo: Arc<TenantAwareMonitorRepository>,
     network_repo: Arc<TenantAwareNetworkRepository>,
     trigger_repo: Arc<TenantAwareTriggerRepository>,
 
-    /// Cache for active monitors by tenant
-    monitor_cache: Arc<DashMap<Uuid, HashMap<String, Monitor>>>,
+    /// Cache for active monitors by tenant, weighted by monitor count
+    monitor_cache: Cache<Uuid, Arc<HashMap<String, Monitor>>>,
+
+    /// Shards held for tenants split across workers; absent means the whole tenant
+    shards: Arc<DashMap<Uuid, Vec<TenantShard>>>,
 
     /// Cache for trigger scripts
-    _trigger_script_cache: Arc<DashMap<String, String>>,
+    trigger_script_cache: Cache<String, String>,
+
+    /// Results of trigger condition scripts by script and match, so redelivered
+    /// matches only repeat delivery
+    trigger_condition_cache: Cache<String, bool>,
 
     /// Cache for contract specs
-    contract_spec_cache: Arc<DashMap<String, ContractSpec>>,
+    contract_spec_cache: Cache<String, ContractSpec>,
 
     /// Database connection pool
     _db: Arc<PgPool>,
 
     /// Tenant IDs this service instance is responsible for
     tenant_ids: Vec<Uuid>,
+
+    /// Set when replaying a recorded session; filter runs are never sampled
+    replay: bool,
 }
 
 impl OzMonitorServices {
@@ -106,69 +241,554 @@ impl OzMonitorServices {
             notification_service,
         ));
 
+        let cache_config = OzMonitorCacheConfig::default();
         Ok(Self {
             filter_service,
             trigger_execution_service,
             client_pool,
+            notification_channels: Arc::new(NotificationChannels::new()),
+            quiet_hours: Arc::new(QuietHoursService::new(db.clone())),
+            severities: Arc::new(MatchSeverityService::new(db.clone())),
+            pauses: Arc::new(TenantPauses::new(db.clone())),
+            trials: Arc::new(TrialTenants::new(db.clone(), TrialLimits::default())),
+            filter_debug: Arc::new(FilterDebugService::new(db.clone())),
+            rpc_limiter: None,
+            rpc_costs: None,
+            monitor_health: None,
+            checkpoints: None,
+            outage: None,
+            last_contexts: DashMap::new(),
+            address_index: AddressIndex::new(),
+            resource_usage: None,
+            tenant_activity: None,
+            confirmations: Arc::new(ConfirmationDepths::new(db.clone())),
+            pending_confirmations: DashMap::new(),
+            match_store: Arc::new(MatchStore::new(db.clone())),
+            dead_letters: Arc::new(DeadLetterStore::new(db.clone())),
             monitor_repo,
             network_repo,
             trigger_repo,
-            monitor_cache: Arc::new(DashMap::new()),
-            _trigger_script_cache: Arc::new(DashMap::new()),
-            contract_spec_cache: Arc::new(DashMap::new()),
+            monitor_cache: monitor_cache(&cache_config),
+            shards: Arc::new(DashMap::new()),
+            trigger_script_cache: bounded_cache(
+                cache_config.trigger_script_capacity,
+                cache_config.trigger_script_ttl,
+            ),
+            trigger_condition_cache: bounded_cache(
+                cache_config.trigger_condition_capacity,
+                cache_config.trigger_condition_ttl,
+            ),
+            contract_spec_cache: bounded_cache(
+                cache_config.contract_spec_capacity,
+                cache_config.contract_spec_ttl,
+            ),
             _db: db,
             tenant_ids,
+            replay: false,
         })
     }
 
+    /// Replace the configuration caches with ones using the given bounds
+    pub fn with_cache_config(mut self, config: OzMonitorCacheConfig) -> Self {
+        self.monitor_cache = monitor_cache(&config);
+        self.trigger_script_cache =
+            bounded_cache(config.trigger_script_capacity, config.trigger_script_ttl);
+        self.trigger_condition_cache = bounded_cache(
+            config.trigger_condition_capacity,
+            config.trigger_condition_ttl,
+        );
+        self.contract_spec_cache =
+            bounded_cache(config.contract_spec_capacity, config.contract_spec_ttl);
+        self
+    }
+
+    /// Route triggers with a registered custom channel to that channel
+    pub fn with_notification_channels(mut self, channels: Arc<NotificationChannels>) -> Self {
+        self.notification_channels = channels;
+        self
+    }
+
+    /// Schedule trial tenants with the given lookup and its limits
+    pub fn with_trial_tenants(mut self, trials: Arc<TrialTenants>) -> Self {
+        self.trials = trials;
+        self
+    }
+
+    /// Enforce tenant RPC caps with the given limiter
+    pub fn with_rpc_limiter(mut self, limiter: Arc<TenantRpcLimiter>) -> Self {
+        self.rpc_limiter = Some(limiter);
+        self
+    }
+
+    /// Charge each tenant's filter runs to it in the given tracker
+    pub fn with_rpc_costs(mut self, rpc_costs: Arc<RpcCostTracker>) -> Self {
+        self.rpc_costs = Some(rpc_costs);
+        self
+    }
+
+    /// Deactivate monitors that keep failing, as tracked by the given service
+    pub fn with_monitor_health(mut self, monitor_health: Arc<MonitorHealth>) -> Self {
+        self.monitor_health = Some(monitor_health);
+        self
+    }
+
+    /// Record the blocks each tenant's monitors are evaluated against in the given ledger
+    pub fn with_checkpoints(mut self, checkpoints: Arc<CheckpointLedger>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// Keep filtering with cached configurations and queue match writes
+    /// while the given tracker finds the database unreachable
+    pub fn with_database_outage(mut self, outage: Arc<DatabaseOutage>) -> Self {
+        self.outage = Some(outage);
+        self
+    }
+
+    /// Time each tenant's filter and trigger spans for the given sampler
+    pub fn with_resource_usage(mut self, resource_usage: Arc<ResourceSampler>) -> Self {
+        self.resource_usage = Some(resource_usage);
+        self
+    }
+
+    /// Record each tenant's activity in the given tracker
+    pub fn with_tenant_activity(mut self, tenant_activity: Arc<TenantActivity>) -> Self {
+        self.tenant_activity = Some(tenant_activity);
+        self
+    }
+
+    /// Only filter blocks, for replaying recorded sessions: no filter debug
+    /// sampling, monitor health tracking, RPC cap enforcement, cost attribution,
+    /// checkpoints, CPU attribution or tenant activity
+    pub fn for_replay(mut self) -> Self {
+        self.replay = true;
+        self.rpc_limiter = None;
+        self.rpc_costs = None;
+        self.monitor_health = None;
+        self.checkpoints = None;
+        self.resource_usage = None;
+        self.tenant_activity = None;
+        self
+    }
+
+    /// Filter a block against the given monitors of a tenant instead of its
+    /// current ones. Matches are neither recorded nor held for confirmation.
+    pub async fn replay_block(
+        &self,
+        tenant_id: Uuid,
+        network: &Network,
+        block: BlockType,
+        monitors: &[Monitor],
+    ) -> Result<Vec<TenantMonitorMatch>> {
+        let context = TenantMonitorContext {
+            tenant_id,
+            monitors: monitors
+                .iter()
+                .map(|monitor| (monitor.name.clone(), monitor.clone()))
+                .collect(),
+            networks: HashMap::new(),
+            triggers: HashMap::new(),
+        };
+
+        match BlockWrapper::from(block) {
+            BlockWrapper::Ethereum(eth_block) => {
+                self.process_ethereum_block(&context, network, &eth_block, None)
+                    .await
+            }
+            BlockWrapper::Stellar(stellar_block) => {
+                self.process_stellar_block(&context, network, &stellar_block, None)
+                    .await
+            }
+        }
+    }
+
     /// Process a block for all tenant monitors
+    ///
+    /// `latest_block` is the chain head when the block was fetched. Every match
+    /// is recorded in the match store. Matches in blocks with fewer
+    /// confirmations than a tenant requires are returned as provisional and
+    /// returned again as finalized or orphaned by [`Self::settle_pending`] once
+    /// the block is deep enough. Paused tenants are skipped.
     #[instrument(skip(self, block))]
     pub async fn process_block<B>(
         &self,
         network: &Network,
         block: B,
         tenant_ids: &[Uuid],
+        latest_block: Option<u64>,
     ) -> Result<Vec<TenantMonitorMatch>>
     where
         B: Into<BlockWrapper> + Clone,
     {
         let block_wrapper = block.into();
+        let block_number = block_wrapper.number();
+        let mut block_hash = None;
         let mut all_matches = Vec::new();
 
         // Process block for each tenant
         for tenant_id in tenant_ids {
-            let context = self.get_tenant_context(*tenant_id).await?;
+            if !self.owns_key(*tenant_id, ShardBy::Network, &network.slug) {
+                continue;
+            }
+            let Some(paused) =
+                self.unless_outage(*tenant_id, self.pauses.is_paused(*tenant_id).await)?
+            else {
+                continue;
+            };
+            if paused {
+                self.pass_block(*tenant_id, &network.slug, block_number);
+                continue;
+            }
+            if !self.trials.limits().evaluates_block(block_number) {
+                let Some(trial) =
+                    self.unless_outage(*tenant_id, self.trials.is_trial(*tenant_id).await)?
+                else {
+                    continue;
+                };
+                if trial {
+                    self.pass_block(*tenant_id, &network.slug, block_number);
+                    continue;
+                }
+            }
 
-            match &block_wrapper {
+            // Charge the filter run against the tenant's RPC cap
+            let critical_only = match &self.rpc_limiter {
+                Some(limiter) => {
+                    let cost = TenantRpcLimiter::block_cost(&network.network_type);
+                    let Some(admission) =
+                        self.unless_outage(*tenant_id, limiter.admit(*tenant_id, cost).await)?
+                    else {
+                        continue;
+                    };
+                    match admission {
+                        RpcAdmission::Allowed => None,
+                        RpcAdmission::CriticalOnly(critical) => Some(critical),
+                        RpcAdmission::Denied => {
+                            self.pass_block(*tenant_id, &network.slug, block_number);
+                            continue;
+                        }
+                    }
+                }
+                None => None,
+            };
+            if let Some(rpc_costs) = &self.rpc_costs {
+                let cost = TenantRpcLimiter::block_cost(&network.network_type);
+                rpc_costs.record_tenant(*tenant_id, &network.slug, cost as f64, false);
+            }
+
+            let started = Instant::now();
+            let Some(context) =
+                self.unless_outage(*tenant_id, self.get_tenant_context(*tenant_id).await)?
+            else {
+                continue;
+            };
+            self.charge_invalid_monitors(*tenant_id, &network.slug)
+                .await;
+
+            let mut matches = match &block_wrapper {
                 BlockWrapper::Ethereum(eth_block) => {
-                    let matches = self
-                        .process_ethereum_block(&context, network, eth_block)
-                        .await?;
-                    all_matches.extend(matches);
+                    self.process_ethereum_block(
+                        &context,
+                        network,
+                        eth_block,
+                        critical_only.as_deref(),
+                    )
+                    .instrument(tenant_span(*tenant_id))
+                    .await?
                 }
                 BlockWrapper::Stellar(stellar_block) => {
-                    let matches = self
-                        .process_stellar_block(&context, network, stellar_block)
-                        .await?;
-                    all_matches.extend(matches);
+                    self.process_stellar_block(
+                        &context,
+                        network,
+                        stellar_block,
+                        critical_only.as_deref(),
+                    )
+                    .instrument(tenant_span(*tenant_id))
+                    .await?
                 }
+            };
+            self.record_usage_span(*tenant_id, started);
+            if let Some(activity) = &self.tenant_activity {
+                let cost = TenantRpcLimiter::block_cost(&network.network_type);
+                activity.record_block(*tenant_id, &context.monitors, cost as u64);
+                activity.record_matches(*tenant_id, matches.len());
             }
+
+            // Only networks the tenant has monitors on get a checkpoint
+            if let (Some(checkpoints), Some(block_number)) = (&self.checkpoints, block_number) {
+                if context
+                    .monitors
+                    .values()
+                    .any(|monitor| monitor.networks.contains(&network.slug))
+                {
+                    checkpoints.record(*tenant_id, &network.slug, block_number);
+                }
+            }
+
+            if !matches.is_empty() {
+                let block_hash = block_hash
+                    .get_or_insert_with(|| block_wrapper.hash())
+                    .clone();
+                let required = self
+                    .unless_outage(
+                        *tenant_id,
+                        self.confirmations.required(*tenant_id, network).await,
+                    )?
+                    .unwrap_or(network.confirmation_blocks);
+                let state = MatchState::at(block_number, latest_block, required);
+                for tenant_match in matches.iter_mut() {
+                    tenant_match.state = state;
+                    let match_id = self
+                        .record_match(network, tenant_match, block_number, block_hash.as_deref())
+                        .await;
+                    if state == MatchState::Provisional {
+                        self.hold_for_confirmation(
+                            network,
+                            tenant_match,
+                            match_id,
+                            block_number,
+                            block_hash.clone(),
+                            required,
+                        );
+                    }
+                }
+            }
+            all_matches.extend(matches);
         }
 
         Ok(all_matches)
     }
 
-    /// Process Ethereum block for a tenant
+    /// A tenant's lookup result, or None when it failed because the database
+    /// is unreachable and the tenant should sit this block out. Without
+    /// degraded mode every failure is returned.
+    fn unless_outage<T>(&self, tenant_id: Uuid, result: Result<T>) -> Result<Option<T>> {
+        match (result, &self.outage) {
+            (Ok(value), _) => Ok(Some(value)),
+            (Err(e), Some(outage)) if outage.is_degraded() || db_outage::is_unreachable(&e) => {
+                outage.enter(&e);
+                warn!(
+                    "Skipping block for tenant {} without a cached configuration: {}",
+                    tenant_id, e
+                );
+                Ok(None)
+            }
+            (Err(e), _) => Err(e),
+        }
+    }
+
+    /// Record a match in the match store, or queue it while the database is
+    /// unreachable.
+    ///
+    /// Failures are logged and never affect block processing.
+    async fn record_match(
+        &self,
+        network: &Network,
+        tenant_match: &TenantMonitorMatch,
+        block_number: Option<u64>,
+        block_hash: Option<&str>,
+    ) -> Option<Uuid> {
+        let recorded = match &self.outage {
+            Some(outage) => {
+                outage
+                    .record_match(tenant_match, &network.slug, block_number, block_hash)
+                    .await
+            }
+            None => {
+                self.match_store
+                    .record(tenant_match, &network.slug, block_number, block_hash)
+                    .await
+            }
+        };
+        match recorded {
+            Ok(id) => Some(id),
+            Err(e) => {
+                warn!(
+                    "Failed to record match of monitor {} for tenant {} on {}: {}",
+                    tenant_match.monitor_name, tenant_match.tenant_id, network.slug, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Keep a provisional match until its block is deep enough
+    fn hold_for_confirmation(
+        &self,
+        network: &Network,
+        tenant_match: &TenantMonitorMatch,
+        match_id: Option<Uuid>,
+        block_number: Option<u64>,
+        block_hash: Option<String>,
+        required: u64,
+    ) {
+        let Some(block_number) = block_number else {
+            return;
+        };
+
+        self.pending_confirmations
+            .entry(network.slug.clone())
+            .or_default()
+            .push(PendingMatch {
+                confirmed_at: block_number.saturating_add(required),
+                block_number,
+                block_hash,
+                match_id,
+                tenant_match: tenant_match.clone(),
+            });
+    }
+
+    /// Settle the provisional matches on a network whose blocks have reached
+    /// their tenant's confirmation depth.
+    ///
+    /// A match is finalized if its block is still canonical and orphaned if a
+    /// reorg replaced it. Matches whose block cannot be checked stay pending
+    /// until the next call.
+    pub async fn settle_pending(
+        &self,
+        network: &Network,
+        latest_block: u64,
+    ) -> Vec<TenantMonitorMatch> {
+        let due: Vec<PendingMatch> = {
+            let Some(mut pending) = self.pending_confirmations.get_mut(&network.slug) else {
+                return Vec::new();
+            };
+            let (due, waiting) = pending
+                .drain(..)
+                .partition(|held| held.confirmed_at <= latest_block);
+            *pending = waiting;
+            due
+        };
+        if due.is_empty() {
+            return Vec::new();
+        }
+
+        // Look up each block once, however many matches it holds
+        let mut canonical = HashMap::new();
+        let block_numbers: BTreeSet<u64> = due
+            .iter()
+            .filter(|held| held.block_hash.is_some())
+            .map(|held| held.block_number)
+            .collect();
+        for block_number in block_numbers {
+            match self.canonical_block_hash(network, block_number).await {
+                Ok(hash) => {
+                    canonical.insert(block_number, hash);
+                }
+                Err(e) => warn!(
+                    "Failed to check block {} on network {} for reorgs, retrying later: {}",
+                    block_number, network.slug, e
+                ),
+            }
+        }
+
+        let mut settled = Vec::new();
+        let mut unchecked = Vec::new();
+        for held in due {
+            let state = match (&held.block_hash, canonical.get(&held.block_number)) {
+                (Some(seen), Some(Some(current))) if seen != current => MatchState::Orphaned,
+                (Some(_), None) => {
+                    unchecked.push(held);
+                    continue;
+                }
+                _ => MatchState::Finalized,
+            };
+
+            if let Some(match_id) = held.match_id {
+                let updated = match &self.outage {
+                    Some(outage) => outage.set_match_state(match_id, state).await,
+                    None => self.match_store.set_state(match_id, state).await,
+                };
+                if let Err(e) = updated {
+                    warn!(
+                        "Failed to mark match {} of tenant {} {}: {}",
+                        match_id,
+                        held.tenant_match.tenant_id,
+                        state.as_str(),
+                        e
+                    );
+                }
+            }
+            if state == MatchState::Orphaned {
+                info!(
+                    "Match of monitor {} for tenant {} orphaned by a reorg at block {} on {}",
+                    held.tenant_match.monitor_name,
+                    held.tenant_match.tenant_id,
+                    held.block_number,
+                    network.slug
+                );
+            }
+
+            settled.push(TenantMonitorMatch {
+                state,
+                ..held.tenant_match
+            });
+        }
+
+        if !unchecked.is_empty() {
+            self.pending_confirmations
+                .entry(network.slug.clone())
+                .or_default()
+                .extend(unchecked);
+        }
+
+        settled
+    }
+
+    /// Hash of the block currently at `block_number` on a network
+    async fn canonical_block_hash(
+        &self,
+        network: &Network,
+        block_number: u64,
+    ) -> Result<Option<String>> {
+        let blocks = match network.network_type {
+            BlockChainType::EVM => self
+                .client_pool
+                .get_evm_client(network)
+                .await
+                .map_err(|e| {
+                    ServiceError::CommunicationError(format!("Failed to get EVM client: {}", e))
+                })?
+                .get_blocks(block_number, Some(block_number))
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch block: {}", e))?,
+            BlockChainType::Stellar => self
+                .client_pool
+                .get_stellar_client(network)
+                .await
+                .map_err(|e| {
+                    ServiceError::CommunicationError(format!("Failed to get Stellar client: {}", e))
+                })?
+                .get_blocks(block_number, Some(block_number))
+                .await
+                .map_err(|e| anyhow::anyhow!("Failed to fetch ledger: {}", e))?,
+            _ => return Ok(None),
+        };
+
+        Ok(blocks
+            .into_iter()
+            .next()
+            .and_then(|block| BlockWrapper::from(block).hash()))
+    }
+
+    /// Process Ethereum block for a tenant, optionally restricted to the named monitors
     async fn process_ethereum_block(
         &self,
         context: &TenantMonitorContext,
         network: &Network,
         block: &EVMBlock,
+        only_monitors: Option<&HashSet<String>>,
     ) -> Result<Vec<TenantMonitorMatch>> {
         let mut all_matches = Vec::new();
 
         // Get monitors for this network
-        let monitors = context.get_monitors_for_network(&network.slug)?;
+        let mut monitors = context.get_monitors_for_network(&network.slug)?;
+        if let Some(only_monitors) = only_monitors {
+            monitors.retain(|name, _| only_monitors.contains(name));
+        }
+        if monitors.is_empty() {
+            return Ok(all_matches);
+        }
         let monitors_vec: Vec<Monitor> = monitors.values().cloned().collect();
 
         // Get the EVM client for this network
@@ -176,7 +796,9 @@ impl OzMonitorServices {
             .client_pool
             .get_evm_client(network)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to get EVM client: {}", e))?;
+            .map_err(|e| {
+                ServiceError::CommunicationError(format!("Failed to get EVM client: {}", e))
+            })?;
 
         // Convert to BlockType for the filter service
         let block_type = BlockType::EVM(Box::new(block.clone()));
@@ -188,16 +810,21 @@ impl OzMonitorServices {
 
         // Use OZ Monitor's filter service to process the entire block
         let filter_results = self
-            .filter_service
             .filter_block(
+                context.tenant_id,
                 &*client,
                 network,
                 &block_type,
                 &monitors_vec,
-                Some(&contract_specs),
+                &contract_specs,
             )
+            .await?;
+
+        // Keep the raw results if this run is sampled for debugging
+        let sampled_results = self
+            .sample_filter_run(context.tenant_id)
             .await
-            .map_err(|e| anyhow::anyhow!("Filter service error: {}", e))?;
+            .then(|| filter_results.clone());
 
         // Process each match
         for monitor_match in filter_results {
@@ -215,13 +842,23 @@ impl OzMonitorServices {
                 }
             };
 
-            if let Some((monitor_name, monitor)) = monitors.iter().find(|(_, m)| {
-                // Match based on monitor configuration
-                m.addresses.iter().any(|addr| {
-                    // Compare addresses as strings
-                    format!("{:?}", monitor_address).eq_ignore_ascii_case(&addr.address)
-                })
-            }) {
+            // Look the monitor up by address, scanning only for monitors that
+            // are not indexed, such as those passed to a replay
+            let address = format!("{:?}", monitor_address);
+            let watches = |monitor: &Monitor| {
+                monitor
+                    .addresses
+                    .iter()
+                    .any(|addr| address.eq_ignore_ascii_case(&addr.address))
+            };
+            let found = self
+                .address_index
+                .tenant_monitors(context.tenant_id, &network.slug, &address)
+                .iter()
+                .find_map(|name| monitors.get_key_value(name))
+                .filter(|(_, monitor)| watches(monitor))
+                .or_else(|| monitors.iter().find(|(_, monitor)| watches(monitor)));
+            if let Some((monitor_name, monitor)) = found {
                 // Check trigger conditions
                 if self
                     .evaluate_trigger_conditions(monitor, &monitor_match)
@@ -231,25 +868,45 @@ impl OzMonitorServices {
                         tenant_id: context.tenant_id,
                         monitor_name: monitor_name.clone(),
                         monitor_match,
+                        state: MatchState::Finalized,
                     });
                 }
             }
         }
 
+        if let Some(filter_matches) = sampled_results {
+            self.record_filter_run(
+                context.tenant_id,
+                network,
+                &block_type,
+                &monitors_vec,
+                &filter_matches,
+                &all_matches,
+            )
+            .await;
+        }
+
         Ok(all_matches)
     }
 
-    /// Process Stellar block for a tenant
+    /// Process Stellar block for a tenant, optionally restricted to the named monitors
     async fn process_stellar_block(
         &self,
         context: &TenantMonitorContext,
         network: &Network,
         block: &StellarBlock,
+        only_monitors: Option<&HashSet<String>>,
     ) -> Result<Vec<TenantMonitorMatch>> {
         let mut all_matches = Vec::new();
 
         // Get monitors for this network
-        let monitors = context.get_monitors_for_network(&network.slug)?;
+        let mut monitors = context.get_monitors_for_network(&network.slug)?;
+        if let Some(only_monitors) = only_monitors {
+            monitors.retain(|name, _| only_monitors.contains(name));
+        }
+        if monitors.is_empty() {
+            return Ok(all_matches);
+        }
         let monitors_vec: Vec<Monitor> = monitors.values().cloned().collect();
 
         // Get the Stellar client for this network
@@ -257,7 +914,9 @@ impl OzMonitorServices {
             .client_pool
             .get_stellar_client(network)
             .await
-            .map_err(|e| anyhow::anyhow!("Failed to get Stellar client: {}", e))?;
+            .map_err(|e| {
+                ServiceError::CommunicationError(format!("Failed to get Stellar client: {}", e))
+            })?;
 
         // Convert to BlockType for the filter service
         let block_type = BlockType::Stellar(Box::new(block.clone()));
@@ -269,16 +928,21 @@ impl OzMonitorServices {
 
         // Use OZ Monitor's filter service to process the entire block
         let filter_results = self
-            .filter_service
             .filter_block(
+                context.tenant_id,
                 &*client,
                 network,
                 &block_type,
                 &monitors_vec,
-                Some(&contract_specs),
+                &contract_specs,
             )
+            .await?;
+
+        // Keep the raw results if this run is sampled for debugging
+        let sampled_results = self
+            .sample_filter_run(context.tenant_id)
             .await
-            .map_err(|e| anyhow::anyhow!("Filter service error: {}", e))?;
+            .then(|| filter_results.clone());
 
         // Process each match
         for monitor_match in filter_results {
@@ -320,14 +984,201 @@ impl OzMonitorServices {
                         tenant_id: context.tenant_id,
                         monitor_name: monitor_name.clone(),
                         monitor_match,
+                        state: MatchState::Finalized,
                     });
                 }
             }
         }
 
+        if let Some(filter_matches) = sampled_results {
+            self.record_filter_run(
+                context.tenant_id,
+                network,
+                &block_type,
+                &monitors_vec,
+                &filter_matches,
+                &all_matches,
+            )
+            .await;
+        }
+
         Ok(all_matches)
     }
 
+    /// Run the filter service over a block for a tenant's monitors.
+    ///
+    /// With monitor health tracking, a failed run is retried one monitor at a
+    /// time: failing monitors are charged a failed block and the others still
+    /// produce matches. If every monitor fails on its own the block itself is
+    /// taken as the problem (e.g. an RPC outage) and no monitor is charged.
+    async fn filter_block<T>(
+        &self,
+        tenant_id: Uuid,
+        client: &T,
+        network: &Network,
+        block_type: &BlockType,
+        monitors: &[Monitor],
+        contract_specs: &[(String, ContractSpec)],
+    ) -> Result<Vec<MonitorMatch>>
+    where
+        T: BlockChainClient + BlockFilterFactory<T>,
+    {
+        let result = self
+            .filter_service
+            .filter_block(client, network, block_type, monitors, Some(contract_specs))
+            .await;
+        let health = match (&self.monitor_health, result) {
+            (Some(health), Err(e)) => {
+                warn!(
+                    "Filter run for tenant {} on {} failed, isolating failing monitors: {}",
+                    tenant_id, network.slug, e
+                );
+                health
+            }
+            (Some(health), Ok(matches)) => {
+                health.record_success(tenant_id, monitors.iter().map(|m| &m.name));
+                return Ok(matches);
+            }
+            (None, result) => {
+                return result.map_err(|e| {
+                    ServiceError::BlockProcessingError(format!("Filter service error: {}", e))
+                        .into()
+                })
+            }
+        };
+
+        let mut matches = Vec::new();
+        let mut failed = Vec::new();
+        for monitor in monitors {
+            match self
+                .filter_service
+                .filter_block(
+                    client,
+                    network,
+                    block_type,
+                    std::slice::from_ref(monitor),
+                    Some(contract_specs),
+                )
+                .await
+            {
+                Ok(found) => matches.extend(found),
+                Err(e) => failed.push((monitor, e.to_string())),
+            }
+        }
+        if failed.len() == monitors.len() {
+            let (_, error) = failed.swap_remove(0);
+            return Err(ServiceError::BlockProcessingError(format!(
+                "Filter service error: {}",
+                error
+            ))
+            .into());
+        }
+
+        health.record_success(
+            tenant_id,
+            monitors
+                .iter()
+                .filter(|m| !failed.iter().any(|(f, _)| f.name == m.name))
+                .map(|m| &m.name),
+        );
+        let mut deactivated = false;
+        for (monitor, error) in &failed {
+            deactivated |= health.record_failure(tenant_id, &monitor.name, error).await;
+        }
+        if deactivated {
+            self.monitor_cache.invalidate(&tenant_id);
+        }
+
+        Ok(matches)
+    }
+
+    /// Charge a tenant's monitors on a network whose configuration failed to load
+    async fn charge_invalid_monitors(&self, tenant_id: Uuid, network_slug: &str) {
+        let Some(health) = &self.monitor_health else {
+            return;
+        };
+
+        let mut deactivated = false;
+        for invalid in self.monitor_repo.invalid_monitors(tenant_id, network_slug) {
+            deactivated |= health
+                .record_failure(tenant_id, &invalid.name, &invalid.error)
+                .await;
+        }
+        if deactivated {
+            self.monitor_cache.invalidate(&tenant_id);
+        }
+    }
+
+    /// Count a block a tenant skipped by its own state towards its watermark
+    fn pass_block(&self, tenant_id: Uuid, network_slug: &str, block_number: Option<u64>) {
+        if let (Some(checkpoints), Some(block_number)) = (&self.checkpoints, block_number) {
+            checkpoints.pass(tenant_id, network_slug, block_number);
+        }
+    }
+
+    /// Charge the time since `started` to a tenant for CPU attribution
+    fn record_usage_span(&self, tenant_id: Uuid, started: Instant) {
+        if let Some(resource_usage) = &self.resource_usage {
+            resource_usage.record_span(tenant_id, started.elapsed());
+        }
+    }
+
+    /// Count a delivered notification towards a tenant's activity
+    fn record_notification(&self, tenant_id: Uuid) {
+        if let Some(activity) = &self.tenant_activity {
+            activity.record_notification(tenant_id);
+        }
+    }
+
+    /// Check if a tenant's filter run on the current block should be recorded
+    async fn sample_filter_run(&self, tenant_id: Uuid) -> bool {
+        if self.replay {
+            return false;
+        }
+        self.filter_debug
+            .should_sample(tenant_id)
+            .await
+            .unwrap_or_else(|e| {
+                warn!(
+                    "Failed to load filter debug settings for tenant {}: {}",
+                    tenant_id, e
+                );
+                false
+            })
+    }
+
+    /// Record the inputs and outputs of a sampled filter run.
+    ///
+    /// Failures are logged and never affect block processing.
+    async fn record_filter_run(
+        &self,
+        tenant_id: Uuid,
+        network: &Network,
+        block: &BlockType,
+        monitors: &[Monitor],
+        filter_matches: &[MonitorMatch],
+        accepted: &[TenantMonitorMatch],
+    ) {
+        let recorded = match filter_debug_sample(
+            tenant_id,
+            network,
+            block,
+            monitors,
+            filter_matches,
+            accepted,
+        ) {
+            Ok(sample) => self.filter_debug.record(&sample).await,
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = recorded {
+            warn!(
+                "Failed to record filter debug sample for tenant {} on {}: {}",
+                tenant_id, network.slug, e
+            );
+        }
+    }
+
     /// Extract contract address from Stellar monitor match
     fn extract_stellar_contract_address(
         &self,
@@ -367,7 +1218,11 @@ impl OzMonitorServices {
         ))
     }
 
-    /// Evaluate trigger conditions for a monitor match
+    /// Evaluate trigger conditions for a monitor match.
+    ///
+    /// Scripts are deterministic over the match, so each script's result is
+    /// cached per match and a redelivered match does not run them again.
+    /// Failed runs are not cached.
     async fn evaluate_trigger_conditions(
         &self,
         monitor: &Monitor,
@@ -378,17 +1233,19 @@ impl OzMonitorServices {
             return Ok(true);
         }
 
+        let match_json = serde_json::to_string(monitor_match)?;
+
         // Evaluate all trigger conditions - ALL must return true for the match to be included
         for condition in &monitor.trigger_conditions {
             // Check if we have the script cached
             let script_content =
-                if let Some(script) = self._trigger_script_cache.get(&condition.script_path) {
-                    script.clone()
+                if let Some(script) = self.trigger_script_cache.get(&condition.script_path) {
+                    script
                 } else {
                     // Load from database using script_path as the script name
                     match self.load_script_from_database(&condition.script_path).await {
                         Ok(content) => {
-                            self._trigger_script_cache
+                            self.trigger_script_cache
                                 .insert(condition.script_path.clone(), content.clone());
                             content
                         }
@@ -403,6 +1260,19 @@ impl OzMonitorServices {
                     }
                 };
 
+            let cache_key = condition_cache_key(
+                &condition.script_path,
+                &script_content,
+                condition.arguments.as_deref(),
+                &match_json,
+            );
+            if let Some(result) = self.trigger_condition_cache.get(&cache_key) {
+                if !result {
+                    return Ok(false);
+                }
+                continue;
+            }
+
             // Create script executor based on language
             use openzeppelin_monitor::services::trigger::ScriptExecutorFactory;
 
@@ -421,6 +1291,7 @@ impl OzMonitorServices {
                 .await
             {
                 Ok(result) => {
+                    self.trigger_condition_cache.insert(cache_key, result);
                     if !result {
                         // If any condition returns false, exclude the match
                         return Ok(false);
@@ -442,7 +1313,352 @@ impl OzMonitorServices {
     }
 
     /// Execute triggers for a monitor match
+    ///
+    /// The match is classified by the tenant's severity rules and sent to the
+    /// route of its severity, if the tenant configured one, within the route's
+    /// notification limit. Notifications for tenants in quiet hours are held
+    /// and delivered later by [`Self::flush_digests`].
+    #[instrument(
+        skip(self, tenant_match),
+        fields(tenant_id = %tenant_match.tenant_id, monitor = %tenant_match.monitor_name)
+    )]
     pub async fn execute_triggers(&self, tenant_match: &TenantMonitorMatch) -> Result<()> {
+        // Tenants choose which lifecycle states notify
+        if !self
+            .confirmations
+            .fires_on(
+                tenant_match.tenant_id,
+                match_network_slug(&tenant_match.monitor_match),
+                tenant_match.state,
+            )
+            .await?
+        {
+            return Ok(());
+        }
+
+        let severity = self.severities.classify(tenant_match).await?;
+        let tenant_id = tenant_match.tenant_id.to_string();
+        MATCH_SEVERITIES
+            .with_label_values(&[&tenant_id, severity.as_str()])
+            .inc();
+        let route = self
+            .severities
+            .route(tenant_match.tenant_id, severity)
+            .await?;
+        let limit = route
+            .as_ref()
+            .and_then(|route| route.max_notifications_per_minute);
+        if !self
+            .severities
+            .admit(tenant_match.tenant_id, severity, limit)
+        {
+            debug!(
+                "Dropping {} notification of monitor {} for tenant {}: over {} per minute",
+                severity.as_str(),
+                tenant_match.monitor_name,
+                tenant_match.tenant_id,
+                limit.unwrap_or_default()
+            );
+            NOTIFICATIONS_RATE_LIMITED
+                .with_label_values(&[&tenant_id, severity.as_str()])
+                .inc();
+            return Ok(());
+        }
+
+        if self
+            .quiet_hours
+            .is_quiet(tenant_match.tenant_id, chrono::Utc::now())
+            .await?
+        {
+            return self.quiet_hours.hold(tenant_match).await;
+        }
+
+        let mut variables = HashMap::new();
+        variables.insert("severity".to_string(), severity.as_str().to_string());
+        let triggers = route.as_ref().map(|route| route.triggers.as_slice());
+
+        let started = Instant::now();
+        let delivered = self
+            .deliver_triggers(tenant_match, variables, triggers)
+            .await;
+        self.record_usage_span(tenant_match.tenant_id, started);
+        if delivered.is_ok() {
+            self.record_notification(tenant_match.tenant_id);
+        }
+        delivered
+    }
+
+    /// Deliver notifications held during quiet hours that have since ended.
+    ///
+    /// Held matches are grouped per monitor and each group is delivered once
+    /// for its latest match, with `digest_count` and `digest_since` variables
+    /// describing the whole group. Returns the number of digests delivered.
+    pub async fn flush_digests(&self, tenant_ids: &[Uuid]) -> Result<usize> {
+        let released = self
+            .quiet_hours
+            .release_due(tenant_ids, chrono::Utc::now())
+            .await?;
+
+        let mut digests: HashMap<(Uuid, String), Vec<HeldNotification>> = HashMap::new();
+        for held in released {
+            digests
+                .entry((held.tenant_id, held.monitor_name.clone()))
+                .or_default()
+                .push(held);
+        }
+
+        let mut delivered = 0;
+        for ((tenant_id, monitor_name), mut held) in digests {
+            held.sort_by_key(|h| h.held_at);
+            let (Some(first), Some(latest)) = (held.first(), held.last()) else {
+                continue;
+            };
+
+            let monitor_match: MonitorMatch =
+                match serde_json::from_value(latest.match_data.clone()) {
+                    Ok(monitor_match) => monitor_match,
+                    Err(e) => {
+                        error!(
+                            "Dropping digest for monitor {} of tenant {}: unreadable match: {}",
+                            monitor_name, tenant_id, e
+                        );
+                        continue;
+                    }
+                };
+
+            let mut variables = HashMap::new();
+            variables.insert("digest_count".to_string(), held.len().to_string());
+            variables.insert("digest_since".to_string(), first.held_at.to_rfc3339());
+
+            let tenant_match = TenantMonitorMatch {
+                tenant_id,
+                monitor_name,
+                monitor_match,
+                state: MatchState::Finalized,
+            };
+            self.deliver_triggers(&tenant_match, variables, None)
+                .await?;
+            self.record_notification(tenant_id);
+            delivered += 1;
+        }
+
+        Ok(delivered)
+    }
+
+    /// Send a test notification through one of a tenant's triggers.
+    ///
+    /// The payload is the tenant's latest recorded match of a monitor using the
+    /// trigger, delivered with the `test` variable set so templates can flag
+    /// it. Quiet hours and match state filters do not apply. Delivery failures
+    /// are reported in the result rather than returned as errors.
+    pub async fn test_trigger(
+        &self,
+        tenant_id: Uuid,
+        trigger_name: &str,
+    ) -> Result<TriggerTestResult, ServiceError> {
+        if !self
+            .load_tenant_triggers(tenant_id)
+            .await?
+            .contains_key(trigger_name)
+        {
+            return Err(RepositoryError::NotFound {
+                entity_type: "trigger".to_string(),
+                id: trigger_name.to_string(),
+            }
+            .into());
+        }
+
+        let context = self.get_tenant_context(tenant_id).await?;
+        let monitor_names: Vec<String> = context
+            .monitors
+            .values()
+            .filter(|monitor| monitor.triggers.iter().any(|name| name == trigger_name))
+            .map(|monitor| monitor.name.clone())
+            .collect();
+        if monitor_names.is_empty() {
+            return Err(ServiceError::InvalidState(format!(
+                "No active monitor uses trigger {}",
+                trigger_name
+            )));
+        }
+
+        let Some((source_match_id, monitor_name, monitor_match)) =
+            self.match_store.latest(tenant_id, &monitor_names).await?
+        else {
+            return Err(ServiceError::InvalidState(format!(
+                "No match recorded yet for the monitors using trigger {}",
+                trigger_name
+            )));
+        };
+
+        let tenant_match = TenantMonitorMatch {
+            tenant_id,
+            monitor_name: monitor_name.clone(),
+            monitor_match,
+            state: MatchState::Finalized,
+        };
+
+        let mut variables = HashMap::new();
+        variables.insert("monitor_name".to_string(), monitor_name.clone());
+        variables.insert(
+            "network".to_string(),
+            match_network_slug(&tenant_match.monitor_match).to_string(),
+        );
+        variables.insert(
+            "match_state".to_string(),
+            tenant_match.state.as_str().to_string(),
+        );
+        variables.insert("test".to_string(), "true".to_string());
+
+        let started = Instant::now();
+        let (route, result) = match self.notification_channels.get(trigger_name) {
+            Some(channel) => (
+                DeliveryRoute::Custom,
+                self.notification_channels
+                    .send(channel.as_ref(), &tenant_match, &variables)
+                    .await,
+            ),
+            None => (
+                DeliveryRoute::Upstream,
+                self.trigger_execution_service
+                    .execute(
+                        &[trigger_name.to_string()],
+                        variables,
+                        &tenant_match.monitor_match,
+                        &HashMap::new(),
+                    )
+                    .await
+                    .map_err(|e| anyhow::anyhow!("{}", e)),
+            ),
+        };
+
+        info!(
+            "Test notification through trigger {} of tenant {}: {}",
+            trigger_name,
+            tenant_id,
+            if result.is_ok() {
+                "delivered"
+            } else {
+                "failed"
+            }
+        );
+
+        Ok(TriggerTestResult {
+            trigger_name: trigger_name.to_string(),
+            monitor_name,
+            source_match_id,
+            route,
+            delivered: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Redeliver requeued dead letters of the given tenants.
+    ///
+    /// Only the triggers that failed are retried; letters failing again return
+    /// to the failed state with the new error. Returns the number delivered.
+    pub async fn redrive_dead_letters(&self, tenant_ids: &[Uuid]) -> Result<usize> {
+        let claimed = self.dead_letters.claim_requeued(tenant_ids).await?;
+
+        let mut delivered = 0;
+        for letter in claimed {
+            let monitor_match: MonitorMatch = match serde_json::from_value(letter.match_data) {
+                Ok(monitor_match) => monitor_match,
+                Err(e) => {
+                    let error = format!("Unreadable match: {}", e);
+                    self.dead_letters
+                        .mark_failed(letter.id, &letter.trigger_names, &error)
+                        .await?;
+                    continue;
+                }
+            };
+            let tenant_match = TenantMonitorMatch {
+                tenant_id: letter.tenant_id,
+                monitor_name: letter.monitor_name,
+                monitor_match,
+                state: letter.match_state.parse().unwrap_or_default(),
+            };
+
+            // Monitors may have changed since the failure; results cached
+            // when the match was first evaluated spare rerunning the scripts
+            let context = self.get_tenant_context(tenant_match.tenant_id).await?;
+            if let Ok(monitor) = context.get_monitor(&tenant_match.monitor_name) {
+                if !self
+                    .evaluate_trigger_conditions(&monitor, &tenant_match.monitor_match)
+                    .await?
+                {
+                    self.dead_letters
+                        .mark_failed(
+                            letter.id,
+                            &letter.trigger_names,
+                            "Match no longer passes the monitor's trigger conditions",
+                        )
+                        .await?;
+                    continue;
+                }
+            }
+
+            let failures = match self
+                .send_triggers(&tenant_match, HashMap::new(), Some(&letter.trigger_names))
+                .await
+            {
+                Ok(failures) => failures,
+                Err(e) => vec![(letter.trigger_names.clone(), e.to_string())],
+            };
+            if failures.is_empty() {
+                self.dead_letters.mark_delivered(letter.id).await?;
+                delivered += 1;
+            } else {
+                let (trigger_names, errors): (Vec<Vec<String>>, Vec<String>) =
+                    failures.into_iter().unzip();
+                self.dead_letters
+                    .mark_failed(letter.id, &trigger_names.concat(), &errors.join("; "))
+                    .await?;
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    /// Deliver a match to the monitor's triggers, or to `triggers` instead
+    /// when given, with additional variables.
+    ///
+    /// Triggers that fail are dead-lettered so they can be requeued.
+    async fn deliver_triggers(
+        &self,
+        tenant_match: &TenantMonitorMatch,
+        extra_variables: HashMap<String, String>,
+        triggers: Option<&[String]>,
+    ) -> Result<()> {
+        let failures = self
+            .send_triggers(tenant_match, extra_variables, triggers)
+            .await?;
+
+        for (trigger_names, error) in failures {
+            if let Err(e) = self
+                .dead_letters
+                .record(tenant_match, &trigger_names, &error)
+                .await
+            {
+                error!(
+                    "Failed to dead-letter triggers {:?} of monitor {} for tenant {}: {}",
+                    trigger_names, tenant_match.monitor_name, tenant_match.tenant_id, e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Send a match to the monitor's triggers, or to `triggers` instead when
+    /// given, returning the triggers that failed with their error
+    async fn send_triggers(
+        &self,
+        tenant_match: &TenantMonitorMatch,
+        extra_variables: HashMap<String, String>,
+        triggers: Option<&[String]>,
+    ) -> Result<Vec<(Vec<String>, String)>> {
         let context = self.get_tenant_context(tenant_match.tenant_id).await?;
         let monitor = context.get_monitor(&tenant_match.monitor_name)?;
 
@@ -454,52 +1670,145 @@ impl OzMonitorServices {
         variables.insert("monitor_name".to_string(), monitor.name.clone());
         variables.insert(
             "network".to_string(),
-            match &tenant_match.monitor_match {
-                MonitorMatch::EVM(evm_match) => evm_match.network_slug.clone(),
-                MonitorMatch::Stellar(stellar_match) => stellar_match.network_slug.clone(),
-            },
+            match_network_slug(&tenant_match.monitor_match).to_string(),
+        );
+        variables.insert(
+            "match_state".to_string(),
+            tenant_match.state.as_str().to_string(),
         );
+        variables.extend(extra_variables);
+
+        let tenant_id = tenant_match.tenant_id.to_string();
+        let network_slug = match_network_slug(&tenant_match.monitor_match);
+        let count_execution = |ok: bool| {
+            TRIGGER_EXECUTIONS
+                .with_label_values(&[
+                    &tenant_id,
+                    network_slug,
+                    if ok { "success" } else { "failure" },
+                ])
+                .inc();
+        };
+
+        // Route triggers with a custom channel, leave the rest to OZ Monitor
+        let mut failures = Vec::new();
+        let mut upstream_triggers = Vec::new();
+        let triggers = triggers.unwrap_or(&monitor.triggers);
+        for trigger_name in triggers {
+            match self.notification_channels.get(trigger_name) {
+                Some(channel) => {
+                    let result = self
+                        .notification_channels
+                        .send(channel.as_ref(), tenant_match, &variables)
+                        .await;
+                    count_execution(result.is_ok());
+                    if let Err(e) = result {
+                        error!(
+                            "Failed to send trigger {} for monitor {} for tenant {}: {}",
+                            trigger_name, monitor.name, tenant_match.tenant_id, e
+                        );
+                        failures.push((vec![trigger_name.clone()], e.to_string()));
+                    }
+                }
+                None => upstream_triggers.push(trigger_name.clone()),
+            }
+        }
+
+        if upstream_triggers.is_empty() {
+            return Ok(failures);
+        }
 
         // Execute triggers
         let result = self
             .trigger_execution_service
             .execute(
-                &monitor.triggers,
+                &upstream_triggers,
                 variables,
                 &tenant_match.monitor_match,
                 &trigger_scripts,
             )
             .await;
+        count_execution(result.is_ok());
 
         if let Err(e) = result {
             error!(
                 "Failed to execute triggers for monitor {} for tenant {}: {}",
                 monitor.name, tenant_match.tenant_id, e
             );
+            failures.push((upstream_triggers, e.to_string()));
         }
 
-        Ok(())
+        Ok(failures)
     }
 
-    /// Get or create tenant context
+    /// Get or create tenant context.
+    ///
+    /// In degraded mode the context the tenant was last filtered with is
+    /// reused, as the repositories read an unreachable database as empty.
     async fn get_tenant_context(&self, tenant_id: Uuid) -> Result<TenantMonitorContext> {
+        if let Some(outage) = &self.outage {
+            if outage.is_degraded() {
+                return self
+                    .last_contexts
+                    .get(&tenant_id)
+                    .map(|context| context.clone())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("database unreachable and no cached configuration")
+                    });
+            }
+        }
+
+        let context = self.load_tenant_context(tenant_id).await?;
+        if let Some(outage) = &self.outage {
+            // A configuration that suddenly went empty is checked against the
+            // database before it replaces the last one
+            let emptied = self.last_contexts.get(&tenant_id).and_then(|last| {
+                let emptied = (context.monitors.is_empty() && !last.monitors.is_empty())
+                    || (context.networks.is_empty() && !last.networks.is_empty())
+                    || (context.triggers.is_empty() && !last.triggers.is_empty());
+                emptied.then(|| last.clone())
+            });
+            if let Some(last) = emptied {
+                outage.probe_and_replay().await;
+                if outage.is_degraded() {
+                    self.monitor_cache.invalidate(&tenant_id);
+                    return Ok(last);
+                }
+            }
+            self.last_contexts.insert(tenant_id, context.clone());
+        }
+        Ok(context)
+    }
+
+    /// Load a tenant's context from the monitor cache and the database
+    async fn load_tenant_context(&self, tenant_id: Uuid) -> Result<TenantMonitorContext> {
         // Check cache first
         if let Some(monitors) = self.monitor_cache.get(&tenant_id) {
             return Ok(TenantMonitorContext {
                 tenant_id,
-                monitors: monitors.clone(),
+                monitors: (*monitors).clone(),
                 networks: self.load_tenant_networks(tenant_id).await?,
                 triggers: self.load_tenant_triggers(tenant_id).await?,
             });
         }
 
-        // Load from database
-        let monitors = self.load_tenant_monitors(tenant_id).await?;
+        // Load from database, keeping only monitors in this worker's shards
+        let mut monitors: HashMap<String, Monitor> = self
+            .load_tenant_monitors(tenant_id)
+            .await?
+            .into_iter()
+            .filter(|(name, _)| self.owns_key(tenant_id, ShardBy::Monitor, name))
+            .collect();
+        if self.trials.is_trial(tenant_id).await? {
+            monitors = self.trials.limits().limit_monitors(monitors);
+        }
         let networks = self.load_tenant_networks(tenant_id).await?;
         let triggers = self.load_tenant_triggers(tenant_id).await?;
 
         // Cache the monitors
-        self.monitor_cache.insert(tenant_id, monitors.clone());
+        self.address_index.index_tenant(tenant_id, &monitors);
+        self.monitor_cache
+            .insert(tenant_id, Arc::new(monitors.clone()));
 
         Ok(TenantMonitorContext {
             tenant_id,
@@ -539,17 +1848,7 @@ impl OzMonitorServices {
     /// Load script from database by name
     async fn load_script_from_database(&self, script_name: &str) -> Result<String> {
         // Extract script name from path if it's a full path
-        let name = if script_name.contains('/') {
-            script_name
-                .split('/')
-                .last()
-                .unwrap_or(script_name)
-                .trim_end_matches(".py")
-                .trim_end_matches(".js")
-                .trim_end_matches(".sh")
-        } else {
-            script_name
-        };
+        let name = script_name_from_path(script_name);
 
         // Query database for script
         #[derive(sqlx::FromRow)]
@@ -595,21 +1894,105 @@ impl OzMonitorServices {
         }
     }
 
+    /// Evict cached trigger scripts whose name matches `script_name`
+    ///
+    /// Returns the number of evicted cache entries.
+    pub fn invalidate_trigger_script(&self, script_name: &str) -> usize {
+        let stale: Vec<Arc<String>> = self
+            .trigger_script_cache
+            .iter()
+            .map(|(script_path, _)| script_path)
+            .filter(|script_path| script_name_from_path(script_path) == script_name)
+            .collect();
+        for script_path in &stale {
+            self.trigger_script_cache.invalidate(script_path.as_str());
+        }
+        stale.len()
+    }
+
     /// Get tenant filter
     fn tenant_filter(&self) -> &[Uuid] {
         &self.tenant_ids
     }
 
+    /// Replace the tenant shards held by this instance
+    pub fn set_shards(&self, shards: Vec<TenantShard>) {
+        let previous: Vec<Uuid> = self.shards.iter().map(|entry| *entry.key()).collect();
+        self.shards.clear();
+        for shard in shards {
+            self.shards.entry(shard.tenant_id).or_default().push(shard);
+        }
+
+        // Monitors are filtered by shard when loaded, so reload affected tenants
+        for tenant_id in previous
+            .into_iter()
+            .chain(self.shards.iter().map(|entry| *entry.key()))
+        {
+            self.monitor_cache.invalidate(&tenant_id);
+        }
+    }
+
+    /// Check if a monitor name or network slug falls in this instance's shards of a tenant
+    fn owns_key(&self, tenant_id: Uuid, shard_by: ShardBy, key: &str) -> bool {
+        match self.shards.get(&tenant_id) {
+            Some(shards) => shards
+                .iter()
+                .any(|shard| shard.shard_by != shard_by || shard.owns(key)),
+            None => true,
+        }
+    }
+
+    /// Drop cached configuration so it is re-read on the next block
+    pub fn invalidate_tenants(&self, tenant_ids: &[Uuid]) {
+        for tenant_id in tenant_ids {
+            self.monitor_cache.invalidate(tenant_id);
+            self.quiet_hours.invalidate(*tenant_id);
+            self.severities.invalidate(*tenant_id);
+            self.pauses.invalidate(*tenant_id);
+            self.trials.invalidate(*tenant_id);
+            self.filter_debug.invalidate(*tenant_id);
+            self.confirmations.invalidate(*tenant_id);
+            if let Some(limiter) = &self.rpc_limiter {
+                limiter.invalidate(*tenant_id);
+            }
+        }
+    }
+
     /// Reload configuration for specific tenants
     pub async fn reload_configurations(&self, tenant_ids: &[Uuid]) -> Result<()> {
         info!("Reloading configuration for {} tenants", tenant_ids.len());
 
         // Clear cache for these tenants
-        for tenant_id in tenant_ids {
-            self.monitor_cache.remove(tenant_id);
-        }
+        self.invalidate_tenants(tenant_ids);
+        self.update_tenant_filters(tenant_ids).await;
+
+        Ok(())
+    }
 
-        // Update repository filters
+    /// Take on a new tenant list, reloading only the tenants that changed.
+    ///
+    /// Tenants the worker keeps stay cached; tenants joining are loaded on
+    /// their next block and tenants leaving are dropped from the caches.
+    pub async fn apply_assignment(&self, previous: &[Uuid], tenant_ids: &[Uuid]) -> Result<()> {
+        let previous: HashSet<Uuid> = previous.iter().copied().collect();
+        let current: HashSet<Uuid> = tenant_ids.iter().copied().collect();
+        let changed: Vec<Uuid> = previous.symmetric_difference(&current).copied().collect();
+        info!(
+            "Applying assignment of {} tenants, reloading {} that changed",
+            tenant_ids.len(),
+            changed.len()
+        );
+
+        self.invalidate_tenants(&changed);
+        self.last_contexts
+            .retain(|tenant_id, _| current.contains(tenant_id));
+        self.update_tenant_filters(tenant_ids).await;
+
+        Ok(())
+    }
+
+    /// Limit the repositories to the given tenants
+    async fn update_tenant_filters(&self, tenant_ids: &[Uuid]) {
         self.monitor_repo
             .update_tenant_filter(tenant_ids.to_vec())
             .await;
@@ -619,8 +2002,81 @@ impl OzMonitorServices {
         self.trigger_repo
             .update_tenant_filter(tenant_ids.to_vec())
             .await;
+    }
 
-        Ok(())
+    /// Reload one tenant's configuration now, returning the number of monitors loaded
+    pub async fn reload_tenant(&self, tenant_id: Uuid) -> Result<usize> {
+        self.reload_configurations(&[tenant_id]).await?;
+        Ok(self.get_tenant_context(tenant_id).await?.monitors.len())
+    }
+
+    /// Active monitors of a tenant, from the cache when loaded
+    pub async fn tenant_monitors(&self, tenant_id: Uuid) -> Result<HashMap<String, Monitor>> {
+        if let Some(monitors) = self.monitor_cache.get(&tenant_id) {
+            return Ok((*monitors).clone());
+        }
+        Ok(self.get_tenant_context(tenant_id).await?.monitors)
+    }
+
+    /// Aggregate the addresses monitored on a network by the given tenants.
+    ///
+    /// Returns None when any tenant's monitors are not loaded yet or a monitor
+    /// on the network watches all addresses, since every block must then be
+    /// processed.
+    pub fn monitored_addresses(
+        &self,
+        network_slug: &str,
+        tenant_ids: &[Uuid],
+    ) -> Option<HashSet<String>> {
+        let mut addresses = HashSet::new();
+
+        for tenant_id in tenant_ids {
+            let monitors = self.monitor_cache.get(tenant_id)?;
+            for monitor in monitors
+                .values()
+                .filter(|m| m.networks.iter().any(|n| n == network_slug))
+            {
+                if monitor.addresses.is_empty() {
+                    return None;
+                }
+                addresses.extend(monitor.addresses.iter().map(|a| a.address.to_lowercase()));
+            }
+        }
+
+        Some(addresses)
+    }
+
+    /// Aggregate the contracts whose events the given tenants watch on a network.
+    ///
+    /// Returns None unless every monitor on the network is limited to specific
+    /// addresses and matches only on events, since function and transaction
+    /// conditions need every ledger to be processed.
+    pub fn event_only_contracts(
+        &self,
+        network_slug: &str,
+        tenant_ids: &[Uuid],
+    ) -> Option<HashSet<String>> {
+        let mut contracts = HashSet::new();
+
+        for tenant_id in tenant_ids {
+            let monitors = self.monitor_cache.get(tenant_id)?;
+            for monitor in monitors
+                .values()
+                .filter(|m| m.networks.iter().any(|n| n == network_slug))
+            {
+                let conditions = &monitor.match_conditions;
+                if monitor.addresses.is_empty()
+                    || conditions.events.is_empty()
+                    || !conditions.functions.is_empty()
+                    || !conditions.transactions.is_empty()
+                {
+                    return None;
+                }
+                contracts.extend(monitor.addresses.iter().map(|a| a.address.clone()));
+            }
+        }
+
+        Some(contracts)
     }
 
     /// Get active networks across all assigned tenants
@@ -658,7 +2114,7 @@ impl OzMonitorServices {
                     // Check cache first
                     let cache_key = format!("{}:{}", network.slug, address.address);
                     if let Some(cached_spec) = self.contract_spec_cache.get(&cache_key) {
-                        specs.push((address.address.clone(), cached_spec.clone()));
+                        specs.push((address.address.clone(), cached_spec));
                     } else {
                         // Cache the spec
                         self.contract_spec_cache.insert(cache_key, spec.clone());
@@ -672,7 +2128,59 @@ impl OzMonitorServices {
     }
 }
 
+/// Build a tenant monitor cache bounded by the total number of monitors
+fn monitor_cache(config: &OzMonitorCacheConfig) -> Cache<Uuid, Arc<HashMap<String, Monitor>>> {
+    Cache::builder()
+        .max_capacity(config.monitor_capacity)
+        .weigher(|_, monitors: &Arc<HashMap<String, Monitor>>| {
+            monitors.len().try_into().unwrap_or(u32::MAX).max(1)
+        })
+        .time_to_live(config.monitor_ttl)
+        .build()
+}
+
+/// Build a cache bounded by entry count whose entries expire after `ttl`
+fn bounded_cache<V: Clone + Send + Sync + 'static>(
+    capacity: u64,
+    ttl: Duration,
+) -> Cache<String, V> {
+    Cache::builder()
+        .max_capacity(capacity)
+        .time_to_live(ttl)
+        .build()
+}
+
+/// Cache key of a trigger condition script's result over a serialized match
+fn condition_cache_key(
+    script_path: &str,
+    script: &str,
+    arguments: Option<&[String]>,
+    match_json: &str,
+) -> String {
+    let mut hasher = DefaultHasher::new();
+    script.hash(&mut hasher);
+    arguments.hash(&mut hasher);
+    match_json.hash(&mut hasher);
+    format!("{}:{:016x}", script_path, hasher.finish())
+}
+
+/// Derive the `trigger_scripts.name` for a trigger condition script path
+fn script_name_from_path(script_path: &str) -> &str {
+    if script_path.contains('/') {
+        script_path
+            .split('/')
+            .last()
+            .unwrap_or(script_path)
+            .trim_end_matches(".py")
+            .trim_end_matches(".js")
+            .trim_end_matches(".sh")
+    } else {
+        script_path
+    }
+}
+
 /// Tenant-specific monitor context
+#[derive(Clone)]
 pub struct TenantMonitorContext {
     pub tenant_id: Uuid,
     pub monitors: HashMap<String, Monitor>,
@@ -703,12 +2211,68 @@ impl TenantMonitorContext {
     }
 }
 
+/// Serialize a filter run into a debug sample
+fn filter_debug_sample(
+    tenant_id: Uuid,
+    network: &Network,
+    block: &BlockType,
+    monitors: &[Monitor],
+    filter_matches: &[MonitorMatch],
+    accepted: &[TenantMonitorMatch],
+) -> Result<FilterDebugSample> {
+    let accepted = accepted
+        .iter()
+        .map(|m| {
+            Ok(serde_json::json!({
+                "monitor_name": m.monitor_name,
+                "match": serde_json::to_value(&m.monitor_match)?,
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(FilterDebugSample {
+        tenant_id,
+        network_slug: network.slug.clone(),
+        block_number: block.number(),
+        block: serde_json::to_value(block)?,
+        monitors: serde_json::to_value(monitors)?,
+        filter_matches: serde_json::to_value(filter_matches)?,
+        accepted_matches: serde_json::Value::Array(accepted),
+    })
+}
+
+/// Network a match was found on
+fn match_network_slug(monitor_match: &MonitorMatch) -> &str {
+    match monitor_match {
+        MonitorMatch::EVM(evm_match) => &evm_match.network_slug,
+        MonitorMatch::Stellar(stellar_match) => &stellar_match.network_slug,
+    }
+}
+
 /// Monitor match with tenant information
 #[derive(Debug, Clone)]
 pub struct TenantMonitorMatch {
     pub tenant_id: Uuid,
     pub monitor_name: String,
     pub monitor_match: MonitorMatch,
+    /// Lifecycle state: provisional until the block reaches the tenant's
+    /// confirmation depth, then finalized or orphaned
+    pub state: MatchState,
+}
+
+/// Provisional match waiting for its block to be confirmed
+#[derive(Debug, Clone)]
+struct PendingMatch {
+    /// Chain head at which the block has enough confirmations
+    confirmed_at: u64,
+    /// Block the match was seen in
+    block_number: u64,
+    /// Hash of that block when seen, to detect reorgs
+    block_hash: Option<String>,
+    /// Id in the match store, if it was recorded
+    match_id: Option<Uuid>,
+    /// Match to emit once settled
+    tenant_match: TenantMonitorMatch,
 }
 
 /// Block wrapper to handle different blockchain types
@@ -718,6 +2282,27 @@ pub enum BlockWrapper {
     Stellar(StellarBlock),
 }
 
+impl BlockWrapper {
+    /// Block number or ledger sequence
+    pub fn number(&self) -> Option<u64> {
+        match self {
+            BlockWrapper::Ethereum(block) => block.number(),
+            BlockWrapper::Stellar(block) => Some(block.sequence as u64),
+        }
+    }
+
+    /// Block or ledger hash, read from the serialized block since both chain
+    /// types expose it as a `hash` field
+    pub fn hash(&self) -> Option<String> {
+        let block = match self {
+            BlockWrapper::Ethereum(block) => serde_json::to_value(block),
+            BlockWrapper::Stellar(block) => serde_json::to_value(block),
+        }
+        .ok()?;
+        block.get("hash")?.as_str().map(str::to_string)
+    }
+}
+
 impl From<EVMBlock> for BlockWrapper {
     fn from(block: EVMBlock) -> Self {
         BlockWrapper::Ethereum(block)
@@ -741,6 +2326,32 @@ impl From<BlockType> for BlockWrapper {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn test_condition_cache_key_covers_script_arguments_and_match() {
+        let args = vec!["100".to_string()];
+        let key = condition_cache_key("large.py", "print(True)", Some(&args), "{}");
+
+        assert_eq!(
+            key,
+            condition_cache_key("large.py", "print(True)", Some(&args), "{}")
+        );
+        assert!(key.starts_with("large.py:"));
+        assert_ne!(
+            key,
+            condition_cache_key("large.py", "print(False)", Some(&args), "{}")
+        );
+        assert_ne!(
+            key,
+            condition_cache_key("large.py", "print(True)", None, "{}")
+        );
+        assert_ne!(
+            key,
+            condition_cache_key("large.py", "print(True)", Some(&args), "{\"a\":1}")
+        );
+    }
+
     #[tokio::test]
     async fn test_oz_monitor_services_creation() {
         // Test service creation
@@ -0,0 +1,378 @@
+//! Publish Buffer
+//!
+//! Keeps the block watcher running while Redis is unreachable. Block events
+//! that cannot be published on the event bus are queued in a
+//! [`SpillBuffer`], at most `buffer_memory` of them in memory and the rest on
+//! disk, and published in their original order once Redis accepts events
+//! again. Events fetched while older ones are still queued wait behind them,
+//! so every network's blocks reach workers in order.
+//!
+//! A network's handoff cursor keeps pointing at the last block actually
+//! published while it has events queued, and is moved forward as they are
+//! replayed. A successor taking over from a replica that stopped with events
+//! queued therefore fetches those blocks again rather than skipping them.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+use crate::services::event_bus::{Event, EventBus};
+use crate::services::metrics;
+use crate::services::shared_block_watcher::BlockEvent;
+use crate::services::spill_buffer::SpillBuffer;
+use crate::services::watcher_handoff::WatcherHandoff;
+
+/// What became of a published block event
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Publication {
+    /// Published, reaching this many subscribers
+    Published(usize),
+    /// Queued until the event bus is reachable again
+    Buffered,
+}
+
+/// Block event waiting to be published
+#[derive(Debug, Serialize, Deserialize)]
+struct QueuedEvent {
+    event: BlockEvent,
+    /// Last block of the event to record as the network's cursor once it is
+    /// published; None for replays, which never move the cursor
+    end_block: Option<u64>,
+}
+
+/// Queued events of one network
+#[derive(Debug)]
+struct Backlog {
+    /// Last block of the network actually published
+    published_through: u64,
+    /// Queued events that move the network's cursor
+    queued: usize,
+}
+
+/// Local queue for block events the event bus could not take
+pub struct PublishBuffer {
+    events: Arc<dyn EventBus>,
+    handoff: Option<Arc<WatcherHandoff>>,
+    queue: SpillBuffer<QueuedEvent>,
+    /// Queued events, including the one being replayed
+    pending: AtomicUsize,
+    /// Networks with queued events, by slug
+    backlogs: Mutex<HashMap<String, Backlog>>,
+    /// When publishing first failed; None while the event bus is reachable
+    unreachable_since: Mutex<Option<Instant>>,
+    retry_interval: Duration,
+}
+
+impl PublishBuffer {
+    /// Create a buffer publishing on `events`, keeping `memory_capacity`
+    /// events in memory and spilling the rest to `<dir>/<name>.spill.jsonl`
+    pub async fn new(
+        events: Arc<dyn EventBus>,
+        dir: &Path,
+        name: &str,
+        memory_capacity: usize,
+        retry_interval: Duration,
+    ) -> Result<Self> {
+        Ok(Self {
+            events,
+            handoff: None,
+            queue: SpillBuffer::new(dir, name, memory_capacity).await?,
+            pending: AtomicUsize::new(0),
+            backlogs: Mutex::new(HashMap::new()),
+            unreachable_since: Mutex::new(None),
+            retry_interval,
+        })
+    }
+
+    /// Record networks' cursors through the given handoff as queued events are published
+    pub fn with_handoff(mut self, handoff: Arc<WatcherHandoff>) -> Self {
+        self.handoff = Some(handoff);
+        self
+    }
+
+    /// Bus events are published on
+    pub fn event_bus(&self) -> Arc<dyn EventBus> {
+        self.events.clone()
+    }
+
+    /// Publish a block event, or queue it if the event bus cannot take it or
+    /// older events are still queued.
+    ///
+    /// `cursor` is the network's last published block before the event and
+    /// the event's last block, None for replays.
+    pub async fn publish(
+        &self,
+        event: BlockEvent,
+        cursor: Option<(u64, u64)>,
+    ) -> Result<Publication> {
+        if self.pending.load(Ordering::SeqCst) == 0 {
+            match self.events.publish(Event::Block(event.clone())).await {
+                Ok(receivers) => return Ok(Publication::Published(receivers)),
+                Err(e) => self.enter_outage(&e),
+            }
+        }
+
+        let network_slug = event.network.slug.clone();
+        if let Some((published_through, _)) = cursor {
+            self.backlogs
+                .lock()
+                .unwrap()
+                .entry(network_slug.clone())
+                .or_insert(Backlog {
+                    published_through,
+                    queued: 0,
+                })
+                .queued += 1;
+        }
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        metrics::BLOCK_EVENTS_UNPUBLISHED
+            .with_label_values(&[&network_slug])
+            .inc();
+
+        self.queue
+            .push(QueuedEvent {
+                event,
+                end_block: cursor.map(|(_, end_block)| end_block),
+            })
+            .await?;
+        Ok(Publication::Buffered)
+    }
+
+    /// Last block of a network actually published while it has queued
+    /// events, the cursor to record instead of the last block fetched
+    pub fn published_through(&self, network_slug: &str) -> Option<u64> {
+        self.backlogs
+            .lock()
+            .unwrap()
+            .get(network_slug)
+            .map(|backlog| backlog.published_through)
+    }
+
+    /// Events waiting to be published
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::SeqCst)
+    }
+
+    /// Publish queued events in order, retrying each every retry interval
+    /// until the event bus takes it, until aborted
+    pub fn start(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let buffer = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let queued = match buffer.queue.recv().await {
+                    Ok(Some(queued)) => queued,
+                    Ok(None) => break,
+                    Err(e) => {
+                        // The spilled event is lost; the handoff cursor still
+                        // points before it, so a successor fetches it again
+                        error!("Failed to read buffered block event: {}", e);
+                        buffer.pending.fetch_sub(1, Ordering::SeqCst);
+                        continue;
+                    }
+                };
+                buffer.replay(queued).await;
+            }
+        })
+    }
+
+    /// Publish one queued event, waiting for the event bus to take it
+    async fn replay(&self, queued: QueuedEvent) {
+        let network_slug = queued.event.network.slug.clone();
+        loop {
+            match self
+                .events
+                .publish(Event::Block(queued.event.clone()))
+                .await
+            {
+                Ok(receivers) => {
+                    debug!(
+                        "Published buffered block event of network {} to {} subscribers",
+                        network_slug, receivers
+                    );
+                    break;
+                }
+                Err(e) => {
+                    debug!(
+                        "Event bus still unreachable, retrying in {:?}: {}",
+                        self.retry_interval, e
+                    );
+                    tokio::time::sleep(self.retry_interval).await;
+                }
+            }
+        }
+
+        metrics::BLOCK_EVENTS_UNPUBLISHED
+            .with_label_values(&[&network_slug])
+            .dec();
+        if let Some(end_block) = queued.end_block {
+            self.advance_cursor(&network_slug, end_block).await;
+        }
+        if self.pending.fetch_sub(1, Ordering::SeqCst) == 1 {
+            if let Some(since) = self.unreachable_since.lock().unwrap().take() {
+                info!(
+                    "Event bus reachable again after {:?}, published every buffered block event",
+                    since.elapsed()
+                );
+            }
+        }
+    }
+
+    /// Record a replayed event's last block as its network's cursor
+    async fn advance_cursor(&self, network_slug: &str, end_block: u64) {
+        {
+            let mut backlogs = self.backlogs.lock().unwrap();
+            if let Some(backlog) = backlogs.get_mut(network_slug) {
+                backlog.published_through = end_block;
+                backlog.queued -= 1;
+                if backlog.queued == 0 {
+                    backlogs.remove(network_slug);
+                }
+            }
+        }
+
+        if let Some(handoff) = &self.handoff {
+            match handoff.commit_range(network_slug, end_block).await {
+                Ok(true) => {}
+                Ok(false) => warn!(
+                    "Block watcher lease lost after publishing buffered blocks up to {} on network {}",
+                    end_block, network_slug
+                ),
+                Err(e) => warn!(
+                    "Failed to record cursor {} for network {}: {}",
+                    end_block, network_slug, e
+                ),
+            }
+        }
+    }
+
+    fn enter_outage(&self, error: &anyhow::Error) {
+        let mut since = self.unreachable_since.lock().unwrap();
+        if since.is_none() {
+            warn!(
+                "Failed to publish block events, buffering them until the event bus is reachable: {}",
+                error
+            );
+            *since = Some(Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::event_bus::EventChannels;
+    use async_trait::async_trait;
+    use std::sync::atomic::AtomicBool;
+
+    /// Bus failing every publish while `down` is set
+    struct FlakyBus {
+        down: AtomicBool,
+        channels: EventChannels,
+    }
+
+    #[async_trait]
+    impl EventBus for FlakyBus {
+        async fn publish(&self, event: Event) -> Result<usize> {
+            if self.down.load(Ordering::SeqCst) {
+                anyhow::bail!("connection refused");
+            }
+            Ok(self.channels.deliver(event))
+        }
+
+        fn channels(&self) -> &EventChannels {
+            &self.channels
+        }
+    }
+
+    fn block_event(slug: &str, replay: bool) -> BlockEvent {
+        let network: openzeppelin_monitor::models::Network =
+            serde_json::from_value(serde_json::json!({
+                "network_type": "EVM",
+                "slug": slug,
+                "name": slug,
+                "rpc_urls": [],
+                "chain_id": 1,
+                "block_time_ms": 12000,
+                "confirmation_blocks": 0,
+                "cron_schedule": "0 */1 * * * *",
+                "max_past_blocks": 10,
+                "store_blocks": false,
+            }))
+            .unwrap();
+        BlockEvent {
+            network,
+            blocks: Vec::new(),
+            timestamp: chrono::Utc::now(),
+            address_bloom: None,
+            latest_block: None,
+            replay,
+            tenant_ids: Vec::new(),
+            trace: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buffered_events_replay_in_order_and_hold_the_cursor() {
+        let bus = Arc::new(FlakyBus {
+            down: AtomicBool::new(true),
+            channels: EventChannels::new(16),
+        });
+        let mut received = bus.subscribe_blocks();
+        let dir = std::env::temp_dir().join(format!("publish-test-{}", uuid::Uuid::new_v4()));
+        let buffer = Arc::new(
+            PublishBuffer::new(bus.clone(), &dir, "watcher", 1, Duration::from_millis(10))
+                .await
+                .unwrap(),
+        );
+
+        let first = buffer
+            .publish(block_event("ethereum", false), Some((10, 20)))
+            .await
+            .unwrap();
+        assert_eq!(first, Publication::Buffered);
+
+        // Once Redis is back, new events still queue behind the buffered ones
+        bus.down.store(false, Ordering::SeqCst);
+        buffer
+            .publish(block_event("ethereum", true), None)
+            .await
+            .unwrap();
+        buffer
+            .publish(block_event("ethereum", false), Some((20, 30)))
+            .await
+            .unwrap();
+        assert_eq!(buffer.pending(), 3);
+        assert_eq!(buffer.published_through("ethereum"), Some(10));
+
+        let replay = buffer.start();
+        let replays: Vec<bool> = [
+            received.recv().await.unwrap(),
+            received.recv().await.unwrap(),
+            received.recv().await.unwrap(),
+        ]
+        .iter()
+        .map(|event| event.replay)
+        .collect();
+        assert_eq!(replays, vec![false, true, false]);
+
+        while buffer.pending() > 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        assert_eq!(buffer.published_through("ethereum"), None);
+        assert_eq!(
+            buffer
+                .publish(block_event("ethereum", false), Some((30, 40)))
+                .await
+                .unwrap(),
+            Publication::Published(1)
+        );
+
+        replay.abort();
+        tokio::fs::remove_dir_all(&dir).await.ok();
+    }
+}
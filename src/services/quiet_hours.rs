@@ -0,0 +1,137 @@
+//! Quiet Hours Service
+//!
+//! Holds tenant notifications during configured quiet hours and releases
+//! them afterwards so they can be delivered as a digest. Matches are still
+//! recorded while notifications are held.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::models::{HeldNotification, QuietHours};
+use crate::services::db_outage::cached_or;
+use crate::services::oz_monitor_integration::TenantMonitorMatch;
+
+/// How long loaded schedules are reused before re-reading the database
+const SCHEDULE_TTL: Duration = Duration::from_secs(60);
+
+/// Enforces tenant quiet hours for notification delivery
+pub struct QuietHoursService {
+    db: Arc<PgPool>,
+    schedules: DashMap<Uuid, (Instant, Arc<Vec<QuietHours>>)>,
+}
+
+impl QuietHoursService {
+    /// Create a new quiet hours service
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            schedules: DashMap::new(),
+        }
+    }
+
+    /// Check if a tenant is inside any of its quiet hours windows
+    pub async fn is_quiet(&self, tenant_id: Uuid, at: DateTime<Utc>) -> Result<bool> {
+        let schedules = self.schedules_for(tenant_id).await?;
+        Ok(schedules.iter().any(|window| window.contains(at)))
+    }
+
+    /// Record a match whose notification is held until quiet hours end
+    pub async fn hold(&self, tenant_match: &TenantMonitorMatch) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO held_notifications (tenant_id, monitor_name, match_data)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(tenant_match.tenant_id)
+        .bind(&tenant_match.monitor_name)
+        .bind(serde_json::to_value(&tenant_match.monitor_match)?)
+        .execute(&*self.db)
+        .await?;
+
+        debug!(
+            "Held notification for monitor {} of tenant {} during quiet hours",
+            tenant_match.monitor_name, tenant_match.tenant_id
+        );
+        Ok(())
+    }
+
+    /// Claim held notifications for tenants that are no longer in quiet hours.
+    ///
+    /// Claimed notifications are marked delivered, so each is released once.
+    pub async fn release_due(
+        &self,
+        tenant_ids: &[Uuid],
+        at: DateTime<Utc>,
+    ) -> Result<Vec<HeldNotification>> {
+        let mut due = Vec::new();
+        for tenant_id in tenant_ids {
+            if !self.is_quiet(*tenant_id, at).await? {
+                due.push(*tenant_id);
+            }
+        }
+
+        if due.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let released = sqlx::query_as::<_, HeldNotification>(
+            r#"
+            UPDATE held_notifications
+            SET delivered_at = now()
+            WHERE tenant_id = ANY($1) AND delivered_at IS NULL
+            RETURNING id, tenant_id, monitor_name, match_data, held_at
+            "#,
+        )
+        .bind(&due)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(released)
+    }
+
+    /// Drop cached schedules so the next check re-reads them
+    pub fn invalidate(&self, tenant_id: Uuid) {
+        self.schedules.remove(&tenant_id);
+    }
+
+    /// Get schedules for a tenant, loading them if the cache is stale
+    async fn schedules_for(&self, tenant_id: Uuid) -> Result<Arc<Vec<QuietHours>>> {
+        if let Some(entry) = self.schedules.get(&tenant_id) {
+            let (loaded_at, schedules) = entry.value();
+            if loaded_at.elapsed() < SCHEDULE_TTL {
+                return Ok(schedules.clone());
+            }
+        }
+
+        let schedules = match sqlx::query_as::<_, QuietHours>(
+            r#"
+            SELECT id, tenant_id, start_time, end_time, utc_offset_minutes, days_of_week
+            FROM tenant_quiet_hours
+            WHERE tenant_id = $1 AND is_active = true
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await
+        {
+            Ok(schedules) => Arc::new(schedules),
+            Err(e) => {
+                return cached_or(
+                    e,
+                    self.schedules.get(&tenant_id).map(|entry| entry.1.clone()),
+                )
+            }
+        };
+
+        self.schedules
+            .insert(tenant_id, (Instant::now(), schedules.clone()));
+        Ok(schedules)
+    }
+}
@@ -0,0 +1,153 @@
+//! Rebalance Scheduler
+//!
+//! Evens out tenants across workers without an operator stepping in. Every
+//! `min_rebalance_interval` the scheduler asks the load balancer whether the
+//! pool needs rebalancing and, if it does, rebalances it; the load balancer
+//! pushes the new assignments to workers over the control channel, which
+//! workers in this process listen on too. Nothing is done while fewer than
+//! two workers are registered. After a failed rebalance each further check
+//! waits twice as long as the previous one, up to `MAX_BACKOFF_DOUBLINGS`
+//! doublings, until a check succeeds.
+
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::models::RebalancePlan;
+use crate::services::load_balancer::LoadBalancer;
+use crate::services::shutdown::ShutdownSignal;
+
+/// Most times the wait after consecutive failures is doubled
+const MAX_BACKOFF_DOUBLINGS: u32 = 3;
+
+/// Periodic rebalancing of the worker pool
+pub struct RebalanceScheduler {
+    load_balancer: Arc<LoadBalancer>,
+    interval: Duration,
+    /// Stops the checks, letting a rebalance in progress finish
+    shutdown: ShutdownSignal,
+}
+
+impl RebalanceScheduler {
+    /// Create a scheduler checking the pool every `interval`
+    pub fn new(load_balancer: Arc<LoadBalancer>, interval: Duration) -> Self {
+        Self {
+            load_balancer,
+            interval,
+            shutdown: ShutdownSignal::new(),
+        }
+    }
+
+    /// Stop checking the pool once the given signal is triggered
+    pub fn with_shutdown(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Check the pool and rebalance it when needed until shut down or aborted
+    pub fn start(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let scheduler = self.clone();
+        tokio::spawn(async move {
+            info!("Checking worker balance every {:?}", scheduler.interval);
+            let mut failures = 0;
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff(scheduler.interval, failures)) => {}
+                    _ = scheduler.shutdown.wait() => break,
+                }
+                match scheduler.rebalance_if_needed().await {
+                    Ok(_) => failures = 0,
+                    Err(e) => {
+                        failures += 1;
+                        warn!(
+                            "Automatic rebalance failed, next check in {:?}: {:#}",
+                            backoff(scheduler.interval, failures),
+                            e
+                        );
+                    }
+                }
+            }
+            debug!("Rebalance scheduler stopped");
+        })
+    }
+
+    /// Rebalance the pool if it needs it, returning the applied plan
+    pub async fn rebalance_if_needed(&self) -> Result<Option<RebalancePlan>> {
+        let workers = self.load_balancer.worker_metrics().await.len();
+        if workers < 2 {
+            debug!("Skipping rebalance check with {} workers", workers);
+            return Ok(None);
+        }
+        if !self.load_balancer.needs_rebalancing().await {
+            return Ok(None);
+        }
+
+        let plan = self.load_balancer.rebalance().await?;
+        info!(
+            "Automatic rebalance moved {} tenants, affecting workers {:?}",
+            plan.tenants_moved, plan.affected_workers
+        );
+        for moved in &plan.moves {
+            info!(
+                "Moved tenant {} from worker {} to {}",
+                moved.tenant_id, moved.previous_worker_id, moved.worker_id
+            );
+        }
+        Ok(Some(plan))
+    }
+}
+
+/// Wait before the next check after `failures` consecutive failures
+fn backoff(interval: Duration, failures: u32) -> Duration {
+    interval * 2u32.pow(failures.min(MAX_BACKOFF_DOUBLINGS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::load_balancer::{LoadBalancerConfig, LoadBalancingStrategy};
+    use uuid::Uuid;
+
+    #[test]
+    fn test_backoff_doubles_up_to_a_limit() {
+        let interval = Duration::from_secs(60);
+        assert_eq!(backoff(interval, 0), interval);
+        assert_eq!(backoff(interval, 1), interval * 2);
+        assert_eq!(backoff(interval, 3), interval * 8);
+        assert_eq!(backoff(interval, 10), interval * 8);
+    }
+
+    #[tokio::test]
+    async fn test_rebalances_only_pools_that_need_it() {
+        let load_balancer = Arc::new(LoadBalancer::new(LoadBalancerConfig {
+            strategy: LoadBalancingStrategy::RoundRobin,
+            min_rebalance_interval: Duration::ZERO,
+            ..Default::default()
+        }));
+        let scheduler = RebalanceScheduler::new(load_balancer.clone(), Duration::from_secs(60));
+        load_balancer
+            .add_worker("a".to_string(), 1.0)
+            .await
+            .unwrap();
+        for _ in 0..6 {
+            load_balancer
+                .assign_tenant_to_worker(Uuid::new_v4(), "a")
+                .await
+                .unwrap();
+        }
+
+        // A single worker is never rebalanced
+        assert!(scheduler.rebalance_if_needed().await.unwrap().is_none());
+
+        load_balancer
+            .add_worker("b".to_string(), 1.0)
+            .await
+            .unwrap();
+        let plan = scheduler.rebalance_if_needed().await.unwrap().unwrap();
+        assert_eq!(plan.tenants_moved, 3);
+
+        // Balanced now
+        assert!(scheduler.rebalance_if_needed().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,32 @@
+//! Redis Keyspace
+//!
+//! Scopes every Redis key and channel under an optional deployment namespace
+//! so several environments (e.g. staging and prod) can share one Redis cluster.
+
+/// Namespace applied to all Redis keys and channels
+#[derive(Debug, Clone, Default)]
+pub struct RedisKeyspace {
+    namespace: Option<String>,
+}
+
+impl RedisKeyspace {
+    /// Create a keyspace; an empty namespace leaves keys unscoped
+    pub fn new(namespace: Option<String>) -> Self {
+        Self {
+            namespace: namespace.filter(|ns| !ns.is_empty()),
+        }
+    }
+
+    /// Get the configured namespace
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Scope a key or channel name to this keyspace
+    pub fn key(&self, key: &str) -> String {
+        match &self.namespace {
+            Some(namespace) => format!("{}:{}", namespace, key),
+            None => key.to_string(),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Resource Usage
+//!
+//! Samples the CPU and memory usage of the worker process and approximates
+//! how much of its CPU time each tenant accounts for. Workers time each
+//! tenant's filter and trigger spans; on every sample the CPU time the
+//! process used since the previous sample is split across tenants in
+//! proportion to those spans. Spans include time spent waiting on RPC, so the
+//! split is an approximation. Samples feed the load balancer's worker metrics
+//! and are published next to the worker heartbeat for coordinators running
+//! in other processes.
+
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use dashmap::DashMap;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::WorkerUsage;
+use crate::services::assignment_store::AssignmentStore;
+use crate::services::load_balancer::LoadBalancer;
+use crate::services::metrics::TENANT_CPU_SECONDS;
+
+/// Samples process usage and attributes CPU time to tenants
+pub struct ResourceSampler {
+    worker_id: String,
+    pid: Pid,
+    system: Mutex<System>,
+    /// Cores available to the process, for scaling CPU usage to 0-100
+    cores: f64,
+    /// Time spent in each tenant's filter and trigger spans since the last sample
+    tenant_spans: DashMap<Uuid, Duration>,
+    last_sample: Mutex<Instant>,
+}
+
+impl ResourceSampler {
+    /// Create a sampler for the current process, reporting as `worker_id`
+    pub fn new(worker_id: impl Into<String>) -> Result<Self> {
+        let pid = sysinfo::get_current_pid()
+            .map_err(|e| anyhow!("Failed to read the current process id: {}", e))?;
+        let cores = std::thread::available_parallelism()
+            .map(|cores| cores.get())
+            .unwrap_or(1);
+
+        Ok(Self {
+            worker_id: worker_id.into(),
+            pid,
+            system: Mutex::new(System::new()),
+            cores: cores as f64,
+            tenant_spans: DashMap::new(),
+            last_sample: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Record time spent filtering blocks or delivering triggers for a tenant
+    pub fn record_span(&self, tenant_id: Uuid, elapsed: Duration) {
+        *self.tenant_spans.entry(tenant_id).or_default() += elapsed;
+    }
+
+    /// Sample process usage and charge the CPU time since the last sample to tenants.
+    ///
+    /// CPU usage is measured between two samples, so the first one reports zero.
+    pub fn sample(&self) -> WorkerUsage {
+        let (cpu_usage, memory_usage) = {
+            let mut system = self.system.lock().unwrap_or_else(|e| e.into_inner());
+            system.refresh_memory();
+            system.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[self.pid]),
+                true,
+                ProcessRefreshKind::new().with_cpu().with_memory(),
+            );
+            // Containers are limited by their cgroup rather than the host's memory
+            let memory_limit = system
+                .cgroup_limits()
+                .map(|limits| limits.total_memory)
+                .filter(|total| *total > 0)
+                .unwrap_or_else(|| system.total_memory());
+            match system.process(self.pid) {
+                Some(process) => (
+                    process.cpu_usage() as f64 / self.cores,
+                    process.memory() as f64 * 100.0 / memory_limit.max(1) as f64,
+                ),
+                None => (0.0, 0.0),
+            }
+        };
+
+        let elapsed = {
+            let mut last_sample = self.last_sample.lock().unwrap_or_else(|e| e.into_inner());
+            let now = Instant::now();
+            let elapsed = now.duration_since(*last_sample);
+            *last_sample = now;
+            elapsed
+        };
+        let cpu_seconds = cpu_usage / 100.0 * self.cores * elapsed.as_secs_f64();
+        for (tenant_id, seconds) in attribute_cpu(&self.take_spans(), cpu_seconds) {
+            TENANT_CPU_SECONDS
+                .with_label_values(&[&self.worker_id, &tenant_id.to_string()])
+                .inc_by(seconds);
+        }
+
+        WorkerUsage {
+            cpu_usage: cpu_usage.clamp(0.0, 100.0),
+            memory_usage: memory_usage.clamp(0.0, 100.0),
+            collected_at: Utc::now(),
+        }
+    }
+
+    /// Sample every `interval` until aborted, updating the load balancer and,
+    /// if given, publishing each sample for coordinators in other processes
+    pub fn start(
+        self: &Arc<Self>,
+        interval: Duration,
+        load_balancer: Arc<LoadBalancer>,
+        store: Option<Arc<AssignmentStore>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let sampler = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let usage = sampler.sample();
+                debug!(
+                    "Worker {} using {:.1}% CPU and {:.1}% memory",
+                    sampler.worker_id, usage.cpu_usage, usage.memory_usage
+                );
+                load_balancer
+                    .record_worker_usage(&sampler.worker_id, &usage)
+                    .await;
+                if let Some(store) = &store {
+                    if let Err(e) = store.report_usage(&sampler.worker_id, &usage).await {
+                        warn!(
+                            "Failed to publish resource usage of worker {}: {}",
+                            sampler.worker_id, e
+                        );
+                    }
+                }
+            }
+        })
+    }
+
+    fn take_spans(&self) -> HashMap<Uuid, Duration> {
+        let tenant_ids: Vec<Uuid> = self.tenant_spans.iter().map(|e| *e.key()).collect();
+        tenant_ids
+            .into_iter()
+            .filter_map(|tenant_id| self.tenant_spans.remove(&tenant_id))
+            .collect()
+    }
+}
+
+/// Split CPU seconds across tenants in proportion to their span time
+fn attribute_cpu(spans: &HashMap<Uuid, Duration>, cpu_seconds: f64) -> HashMap<Uuid, f64> {
+    let total: f64 = spans.values().map(Duration::as_secs_f64).sum();
+    if total <= 0.0 || cpu_seconds <= 0.0 {
+        return HashMap::new();
+    }
+
+    spans
+        .iter()
+        .map(|(tenant_id, span)| (*tenant_id, cpu_seconds * span.as_secs_f64() / total))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cpu_is_split_by_span_time() {
+        let busy = Uuid::new_v4();
+        let quiet = Uuid::new_v4();
+        let spans = HashMap::from([
+            (busy, Duration::from_millis(300)),
+            (quiet, Duration::from_millis(100)),
+        ]);
+
+        let attributed = attribute_cpu(&spans, 2.0);
+        assert!((attributed[&busy] - 1.5).abs() < 1e-9);
+        assert!((attributed[&quiet] - 0.5).abs() < 1e-9);
+        assert!(attribute_cpu(&HashMap::new(), 2.0).is_empty());
+        assert!(attribute_cpu(&spans, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_sample_drains_recorded_spans() {
+        let sampler = ResourceSampler::new("worker-1").unwrap();
+        let tenant_id = Uuid::new_v4();
+        sampler.record_span(tenant_id, Duration::from_millis(5));
+        sampler.record_span(tenant_id, Duration::from_millis(5));
+        assert_eq!(
+            *sampler.tenant_spans.get(&tenant_id).unwrap(),
+            Duration::from_millis(10)
+        );
+
+        let usage = sampler.sample();
+        assert!(sampler.tenant_spans.is_empty());
+        assert!((0.0..=100.0).contains(&usage.cpu_usage));
+        assert!((0.0..=100.0).contains(&usage.memory_usage));
+    }
+}
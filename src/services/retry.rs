@@ -0,0 +1,190 @@
+//! Retry Policy
+//!
+//! Shared retry and backoff behaviour for RPC calls, cache connections and
+//! notification delivery, so every component backs off the same way and can
+//! be tuned from one place in the configuration.
+
+use anyhow::Result;
+use rand::Rng;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+/// Decides whether an error is worth retrying
+type RetryClassifier = Arc<dyn Fn(&anyhow::Error) -> bool + Send + Sync>;
+
+/// Exponential backoff policy with optional jitter
+#[derive(Clone)]
+pub struct RetryPolicy {
+    /// Maximum attempts including the first call
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound for any single delay
+    pub max_delay: Duration,
+    /// Fraction of each delay randomized away (0.0 to 1.0)
+    pub jitter: f64,
+    retryable: RetryClassifier,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            retryable: Arc::new(|_| true),
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .finish()
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with the given attempts and base delay
+    pub fn new(max_attempts: u32, base_delay: Duration) -> Self {
+        Self {
+            max_attempts,
+            base_delay,
+            ..Default::default()
+        }
+    }
+
+    /// Set the maximum number of attempts
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound for any single delay
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Set the randomized fraction of each delay
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Only retry errors accepted by the classifier
+    pub fn with_classifier<F>(mut self, retryable: F) -> Self
+    where
+        F: Fn(&anyhow::Error) -> bool + Send + Sync + 'static,
+    {
+        self.retryable = Arc::new(retryable);
+        self
+    }
+
+    /// Delay before the given retry (1-based), without jitter
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+
+    /// Delay before the given retry (1-based), with jitter applied
+    pub fn delay(&self, retry: u32) -> Duration {
+        let backoff = self.backoff(retry);
+        if self.jitter <= 0.0 {
+            return backoff;
+        }
+
+        let reduction = rand::thread_rng().gen_range(0.0..=self.jitter);
+        backoff.mul_f64(1.0 - reduction)
+    }
+
+    /// Run the operation until it succeeds, fails with a non-retryable error,
+    /// or runs out of attempts
+    pub async fn retry<F, Fut, T, E>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: Into<anyhow::Error>,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            match operation().await {
+                Ok(result) => return Ok(result),
+                Err(e) => {
+                    let e = e.into();
+                    if !(self.retryable)(&e) {
+                        return Err(e);
+                    }
+                    if attempt >= self.max_attempts {
+                        return Err(e.context(format!("Failed after {} attempts", attempt)));
+                    }
+
+                    let delay = self.delay(attempt);
+                    warn!("Attempt {} failed: {}, retrying in {:?}", attempt, e, delay);
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_is_exponential_and_capped() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(300))
+            .with_jitter(0.0);
+
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(3), Duration::from_millis(300));
+        assert_eq!(policy.delay(30), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_jitter_only_shortens_delay() {
+        let policy = RetryPolicy::new(3, Duration::from_millis(100)).with_jitter(0.5);
+
+        for _ in 0..100 {
+            let delay = policy.delay(1);
+            assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(100));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_errors_fail_fast() {
+        let policy = RetryPolicy::new(5, Duration::from_millis(1)).with_classifier(|_| false);
+        let mut calls = 0;
+
+        let result: Result<()> = policy
+            .retry(|| {
+                calls += 1;
+                async { Err(anyhow::anyhow!("fatal")) }
+            })
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+}
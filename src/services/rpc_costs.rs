@@ -0,0 +1,394 @@
+//! RPC Cost Attribution
+//!
+//! Attributes RPC usage to the tenants that caused it so shared
+//! infrastructure can be charged back. Filter runs are charged to their
+//! tenant directly. Block fetches by the shared watcher serve every tenant
+//! watching the network, so they are split across those tenants in proportion
+//! to their active monitors on it. Requests answered from the block cache are
+//! counted separately and priced at a fraction of an RPC request. Usage is
+//! accumulated in memory and added to daily totals in Postgres on every flush.
+
+use anyhow::Result;
+use chrono::{NaiveDate, Utc};
+use dashmap::DashMap;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::{NetworkRpcCost, TenantRpcCost};
+
+/// Pricing and flushing of attributed RPC usage
+#[derive(Debug, Clone)]
+pub struct RpcCostConfig {
+    /// Price of one million RPC requests
+    pub cost_per_million_requests: f64,
+    /// Share of an RPC request's price charged for a request served from cache
+    pub cached_request_weight: f64,
+    /// How often accumulated usage is written to the database
+    pub flush_interval: Duration,
+}
+
+impl Default for RpcCostConfig {
+    fn default() -> Self {
+        Self {
+            cost_per_million_requests: 1.0,
+            cached_request_weight: 0.1,
+            flush_interval: Duration::from_secs(60),
+        }
+    }
+}
+
+impl RpcCostConfig {
+    /// Estimated cost of the given requests
+    pub fn estimate(&self, rpc_requests: f64, cached_requests: f64) -> f64 {
+        (rpc_requests + cached_requests * self.cached_request_weight)
+            * self.cost_per_million_requests
+            / 1_000_000.0
+    }
+}
+
+/// Requests accumulated since the last flush
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct RpcUsage {
+    rpc: f64,
+    cached: f64,
+}
+
+impl RpcUsage {
+    fn add(&mut self, requests: f64, cached: bool) {
+        if cached {
+            self.cached += requests;
+        } else {
+            self.rpc += requests;
+        }
+    }
+
+    fn add_usage(&mut self, other: RpcUsage) {
+        self.rpc += other.rpc;
+        self.cached += other.cached;
+    }
+}
+
+/// Records RPC usage per tenant and estimates its cost
+pub struct RpcCostTracker {
+    db: Arc<PgPool>,
+    config: RpcCostConfig,
+    /// Usage charged to one tenant, by tenant and network
+    tenant_usage: DashMap<(Uuid, String), RpcUsage>,
+    /// Usage shared by every tenant of a network, split on flush
+    shared_usage: DashMap<String, RpcUsage>,
+}
+
+impl RpcCostTracker {
+    /// Create a tracker writing to the given database
+    pub fn new(db: Arc<PgPool>, config: RpcCostConfig) -> Self {
+        Self {
+            db,
+            config,
+            tenant_usage: DashMap::new(),
+            shared_usage: DashMap::new(),
+        }
+    }
+
+    /// Get the pricing configuration
+    pub fn config(&self) -> &RpcCostConfig {
+        &self.config
+    }
+
+    /// Charge requests made for one tenant
+    pub fn record_tenant(&self, tenant_id: Uuid, network_slug: &str, requests: f64, cached: bool) {
+        self.tenant_usage
+            .entry((tenant_id, network_slug.to_string()))
+            .or_default()
+            .add(requests, cached);
+    }
+
+    /// Charge requests made for every tenant watching a network
+    pub fn record_shared(&self, network_slug: &str, requests: f64, cached: bool) {
+        self.shared_usage
+            .entry(network_slug.to_string())
+            .or_default()
+            .add(requests, cached);
+    }
+
+    /// Write usage accumulated since the last flush to today's totals.
+    ///
+    /// Shared usage of networks without active monitors is dropped.
+    pub async fn flush(&self) -> Result<()> {
+        let mut usage: BTreeMap<(Uuid, String), RpcUsage> = BTreeMap::new();
+
+        let tenant_keys: Vec<(Uuid, String)> =
+            self.tenant_usage.iter().map(|e| e.key().clone()).collect();
+        for key in tenant_keys {
+            if let Some((key, tenant)) = self.tenant_usage.remove(&key) {
+                usage.entry(key).or_default().add_usage(tenant);
+            }
+        }
+
+        let networks: Vec<String> = self.shared_usage.iter().map(|e| e.key().clone()).collect();
+        for network_slug in networks {
+            let Some((_, shared)) = self.shared_usage.remove(&network_slug) else {
+                continue;
+            };
+            let weights = match self.network_weights(&network_slug).await {
+                Ok(weights) => weights,
+                Err(e) => {
+                    self.shared_usage
+                        .entry(network_slug)
+                        .or_default()
+                        .add_usage(shared);
+                    self.restore(usage);
+                    return Err(e);
+                }
+            };
+            if weights.is_empty() {
+                debug!(
+                    "No tenant monitors on network {}, dropping {} shared requests",
+                    network_slug,
+                    shared.rpc + shared.cached
+                );
+                continue;
+            }
+            for (tenant_id, share) in split_shared(shared, &weights) {
+                usage
+                    .entry((tenant_id, network_slug.clone()))
+                    .or_default()
+                    .add_usage(share);
+            }
+        }
+
+        if usage.is_empty() {
+            return Ok(());
+        }
+
+        if let Err(e) = self.write_usage(&usage).await {
+            self.restore(usage);
+            return Err(e);
+        }
+
+        debug!("Flushed RPC usage of {} tenant networks", usage.len());
+        Ok(())
+    }
+
+    /// Keep usage that could not be flushed for the next flush
+    fn restore(&self, usage: BTreeMap<(Uuid, String), RpcUsage>) {
+        for (key, usage) in usage {
+            self.tenant_usage.entry(key).or_default().add_usage(usage);
+        }
+    }
+
+    /// Flush accumulated usage every flush interval until aborted
+    pub fn start_flush(self: &Arc<Self>) -> tokio::task::JoinHandle<()> {
+        let tracker = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(tracker.config.flush_interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = tracker.flush().await {
+                    warn!("Failed to flush RPC usage: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Estimated usage and cost of every tenant since a day, most expensive first
+    pub async fn costs(&self, since: NaiveDate) -> Result<Vec<TenantRpcCost>> {
+        let rows = sqlx::query_as::<_, (Uuid, String, f64, f64)>(
+            r#"
+            SELECT tenant_id, network_slug, SUM(rpc_requests), SUM(cached_requests)
+            FROM tenant_rpc_usage
+            WHERE usage_date >= $1
+            GROUP BY tenant_id, network_slug
+            ORDER BY tenant_id, network_slug
+            "#,
+        )
+        .bind(since)
+        .fetch_all(&*self.db)
+        .await?;
+
+        let mut costs = self.summarize(since, rows);
+        costs.sort_by(|a, b| b.estimated_cost.total_cmp(&a.estimated_cost));
+        Ok(costs)
+    }
+
+    /// Estimated usage and cost of one tenant since a day
+    pub async fn tenant_cost(&self, tenant_id: Uuid, since: NaiveDate) -> Result<TenantRpcCost> {
+        let rows = sqlx::query_as::<_, (Uuid, String, f64, f64)>(
+            r#"
+            SELECT tenant_id, network_slug, SUM(rpc_requests), SUM(cached_requests)
+            FROM tenant_rpc_usage
+            WHERE tenant_id = $1 AND usage_date >= $2
+            GROUP BY tenant_id, network_slug
+            ORDER BY network_slug
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(since)
+        .fetch_all(&*self.db)
+        .await?;
+
+        Ok(self
+            .summarize(since, rows)
+            .pop()
+            .unwrap_or_else(|| TenantRpcCost {
+                tenant_id,
+                since,
+                rpc_requests: 0.0,
+                cached_requests: 0.0,
+                estimated_cost: 0.0,
+                networks: Vec::new(),
+            }))
+    }
+
+    /// Add usage to today's totals
+    async fn write_usage(&self, usage: &BTreeMap<(Uuid, String), RpcUsage>) -> Result<()> {
+        let today = Utc::now().date_naive();
+        let mut tx = self.db.begin().await?;
+        for ((tenant_id, network_slug), usage) in usage {
+            sqlx::query(
+                r#"
+                INSERT INTO tenant_rpc_usage
+                    (tenant_id, network_slug, usage_date, rpc_requests, cached_requests)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (tenant_id, network_slug, usage_date) DO UPDATE
+                SET rpc_requests = tenant_rpc_usage.rpc_requests + EXCLUDED.rpc_requests,
+                    cached_requests = tenant_rpc_usage.cached_requests + EXCLUDED.cached_requests
+                "#,
+            )
+            .bind(tenant_id)
+            .bind(network_slug)
+            .bind(today)
+            .bind(usage.rpc)
+            .bind(usage.cached)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Active monitors per tenant on a network
+    async fn network_weights(&self, network_slug: &str) -> Result<Vec<(Uuid, f64)>> {
+        let weights = sqlx::query_as::<_, (Uuid, i64)>(
+            r#"
+            SELECT m.tenant_id, COUNT(*)
+            FROM tenant_monitors m
+            JOIN tenant_networks n ON m.network_id = n.id
+            JOIN tenants t ON m.tenant_id = t.id
+            WHERE n.network_id = $1 AND m.is_active AND n.is_active AND t.is_active
+            GROUP BY m.tenant_id
+            "#,
+        )
+        .bind(network_slug)
+        .fetch_all(&*self.db)
+        .await?;
+        Ok(weights
+            .into_iter()
+            .map(|(tenant_id, monitors)| (tenant_id, monitors as f64))
+            .collect())
+    }
+
+    /// Group per-network usage rows by tenant and price them
+    fn summarize(
+        &self,
+        since: NaiveDate,
+        rows: Vec<(Uuid, String, f64, f64)>,
+    ) -> Vec<TenantRpcCost> {
+        let mut tenants: BTreeMap<Uuid, TenantRpcCost> = BTreeMap::new();
+        for (tenant_id, network_slug, rpc_requests, cached_requests) in rows {
+            let estimated_cost = self.config.estimate(rpc_requests, cached_requests);
+            let tenant = tenants.entry(tenant_id).or_insert_with(|| TenantRpcCost {
+                tenant_id,
+                since,
+                rpc_requests: 0.0,
+                cached_requests: 0.0,
+                estimated_cost: 0.0,
+                networks: Vec::new(),
+            });
+            tenant.rpc_requests += rpc_requests;
+            tenant.cached_requests += cached_requests;
+            tenant.estimated_cost += estimated_cost;
+            tenant.networks.push(NetworkRpcCost {
+                network_slug,
+                rpc_requests,
+                cached_requests,
+                estimated_cost,
+            });
+        }
+        tenants.into_values().collect()
+    }
+}
+
+/// Split shared usage across tenants in proportion to their weights
+fn split_shared(usage: RpcUsage, weights: &[(Uuid, f64)]) -> Vec<(Uuid, RpcUsage)> {
+    let total: f64 = weights.iter().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return Vec::new();
+    }
+
+    weights
+        .iter()
+        .map(|(tenant_id, weight)| {
+            let share = weight / total;
+            (
+                *tenant_id,
+                RpcUsage {
+                    rpc: usage.rpc * share,
+                    cached: usage.cached * share,
+                },
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_usage_splits_by_weight() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let usage = RpcUsage {
+            rpc: 100.0,
+            cached: 40.0,
+        };
+
+        let split = split_shared(usage, &[(a, 3.0), (b, 1.0)]);
+        assert_eq!(
+            split[0],
+            (
+                a,
+                RpcUsage {
+                    rpc: 75.0,
+                    cached: 30.0
+                }
+            )
+        );
+        assert_eq!(
+            split[1],
+            (
+                b,
+                RpcUsage {
+                    rpc: 25.0,
+                    cached: 10.0
+                }
+            )
+        );
+        assert!(split_shared(usage, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_cached_requests_are_priced_at_their_weight() {
+        let config = RpcCostConfig {
+            cost_per_million_requests: 2.0,
+            cached_request_weight: 0.5,
+            flush_interval: Duration::from_secs(60),
+        };
+        assert_eq!(config.estimate(1_000_000.0, 0.0), 2.0);
+        assert_eq!(config.estimate(0.0, 1_000_000.0), 1.0);
+    }
+}
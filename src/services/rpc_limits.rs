@@ -0,0 +1,381 @@
+//! Tenant RPC Limits
+//!
+//! Enforces `tenants.max_rpc_requests_per_minute`. Each filter run of a tenant
+//! is charged an estimated number of RPC requests, and once a tenant exceeds
+//! its cap within a minute the configured actions are applied: throttling the
+//! tenant, running only critical monitors, notifying the tenant or suspending it.
+//! A tenant throttled by the activity anomaly analyzer is held to the lower
+//! throttle cap until it expires; runs over it are denied without applying
+//! the configured actions. Trial tenants are held to the trial cap when it is
+//! lower than their own.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use openzeppelin_monitor::models::BlockChainType;
+
+use crate::models::RpcCapAction;
+use crate::services::db_outage::cached_or;
+
+/// Length of the usage window caps are expressed in
+const WINDOW: Duration = Duration::from_secs(60);
+
+/// How long loaded caps and critical monitors are reused before re-reading the database
+const LIMITS_TTL: Duration = Duration::from_secs(60);
+
+/// Estimated RPC requests for filtering one EVM block (logs for the block)
+const EVM_REQUESTS_PER_BLOCK: u32 = 1;
+
+/// Estimated RPC requests for filtering one Stellar ledger (transactions and events)
+const STELLAR_REQUESTS_PER_BLOCK: u32 = 2;
+
+/// Whether a tenant's filter run may proceed
+#[derive(Debug, Clone)]
+pub enum RpcAdmission {
+    /// Process all monitors
+    Allowed,
+    /// Process only the named critical monitors
+    CriticalOnly(Arc<HashSet<String>>),
+    /// Skip the tenant for this block
+    Denied,
+}
+
+/// RPC cap of a tenant
+#[derive(Debug, Clone, Copy)]
+struct RpcCap {
+    limit: u32,
+    /// Set by an unexpired anomaly throttle lower than the tenant's own cap
+    throttled: bool,
+}
+
+/// Requests counted in the current minute for a tenant
+#[derive(Debug, Clone)]
+struct RpcUsageWindow {
+    started: Instant,
+    requests: u32,
+    capped: bool,
+}
+
+impl RpcUsageWindow {
+    fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            requests: 0,
+            capped: false,
+        }
+    }
+
+    /// Charge requests, returning (over the cap, first time over in this window)
+    fn charge(&mut self, now: Instant, requests: u32, limit: u32) -> (bool, bool) {
+        if now.duration_since(self.started) >= WINDOW {
+            *self = Self::new(now);
+        }
+
+        self.requests = self.requests.saturating_add(requests);
+        let over = self.requests > limit;
+        let crossed = over && !self.capped;
+        self.capped |= over;
+        (over, crossed)
+    }
+}
+
+/// Tracks tenant RPC usage and applies cap actions
+pub struct TenantRpcLimiter {
+    db: Arc<PgPool>,
+    actions: Vec<RpcCapAction>,
+    /// Cap of trial tenants; 0 leaves them at their own cap
+    trial_cap: u32,
+    limits: DashMap<Uuid, (Instant, Option<RpcCap>)>,
+    critical: DashMap<Uuid, (Instant, Arc<HashSet<String>>)>,
+    usage: DashMap<Uuid, RpcUsageWindow>,
+    suspended: DashMap<Uuid, ()>,
+}
+
+impl TenantRpcLimiter {
+    /// Create a limiter applying the given actions to tenants over their cap
+    pub fn new(db: Arc<PgPool>, actions: Vec<RpcCapAction>) -> Self {
+        Self {
+            db,
+            actions,
+            trial_cap: 0,
+            limits: DashMap::new(),
+            critical: DashMap::new(),
+            usage: DashMap::new(),
+            suspended: DashMap::new(),
+        }
+    }
+
+    /// Hold trial tenants to `cap` requests per minute when lower than their own cap
+    pub fn with_trial_cap(mut self, cap: u32) -> Self {
+        self.trial_cap = cap;
+        self
+    }
+
+    /// Estimated RPC requests for filtering one block of a network type
+    pub fn block_cost(network_type: &BlockChainType) -> u32 {
+        match network_type {
+            BlockChainType::Stellar => STELLAR_REQUESTS_PER_BLOCK,
+            _ => EVM_REQUESTS_PER_BLOCK,
+        }
+    }
+
+    /// Charge a filter run to a tenant and decide whether it may proceed
+    pub async fn admit(&self, tenant_id: Uuid, requests: u32) -> Result<RpcAdmission> {
+        if self.suspended.contains_key(&tenant_id) {
+            return Ok(RpcAdmission::Denied);
+        }
+
+        let Some(cap) = self.limit_for(tenant_id).await? else {
+            return Ok(RpcAdmission::Allowed);
+        };
+        let limit = cap.limit;
+
+        let (over, crossed) = self
+            .usage
+            .entry(tenant_id)
+            .or_insert_with(|| RpcUsageWindow::new(Instant::now()))
+            .charge(Instant::now(), requests, limit);
+
+        if !over {
+            return Ok(RpcAdmission::Allowed);
+        }
+
+        if cap.throttled {
+            if crossed {
+                warn!(
+                    "Tenant {} exceeded its anomaly throttle of {} RPC requests per minute",
+                    tenant_id, limit
+                );
+            }
+            return Ok(RpcAdmission::Denied);
+        }
+
+        if crossed {
+            self.apply_actions(tenant_id, limit).await;
+        }
+
+        if self.has(RpcCapAction::Suspend) || self.has(RpcCapAction::Throttle) {
+            Ok(RpcAdmission::Denied)
+        } else if self.has(RpcCapAction::SkipNonCritical) {
+            Ok(RpcAdmission::CriticalOnly(
+                self.critical_monitors(tenant_id).await?,
+            ))
+        } else {
+            Ok(RpcAdmission::Allowed)
+        }
+    }
+
+    /// Drop cached caps and any suspension so the next check re-reads them
+    pub fn invalidate(&self, tenant_id: Uuid) {
+        self.limits.remove(&tenant_id);
+        self.critical.remove(&tenant_id);
+        self.suspended.remove(&tenant_id);
+    }
+
+    fn has(&self, action: RpcCapAction) -> bool {
+        self.actions.contains(&action)
+    }
+
+    /// Apply one-off actions when a tenant first exceeds its cap in a window
+    async fn apply_actions(&self, tenant_id: Uuid, limit: u32) {
+        let requests = self.usage.get(&tenant_id).map_or(0, |w| w.requests);
+        warn!(
+            "Tenant {} exceeded its RPC cap ({} > {} per minute), applying {:?}",
+            tenant_id, requests, limit, self.actions
+        );
+
+        if self.has(RpcCapAction::Notify) {
+            if let Err(e) = self.record_event(tenant_id, requests, limit).await {
+                warn!(
+                    "Failed to record RPC cap event for tenant {}: {}",
+                    tenant_id, e
+                );
+            }
+        }
+
+        if self.has(RpcCapAction::Suspend) {
+            self.suspended.insert(tenant_id, ());
+            match sqlx::query("UPDATE tenants SET is_active = false WHERE id = $1")
+                .bind(tenant_id)
+                .execute(&*self.db)
+                .await
+            {
+                Ok(_) => info!("Suspended tenant {} for exceeding its RPC cap", tenant_id),
+                Err(e) => warn!("Failed to suspend tenant {}: {}", tenant_id, e),
+            }
+        }
+    }
+
+    async fn record_event(&self, tenant_id: Uuid, requests: u32, limit: u32) -> Result<()> {
+        let actions: Vec<&str> = self.actions.iter().map(RpcCapAction::as_str).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_rpc_cap_events (tenant_id, actions, requests, request_limit)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(&actions)
+        .bind(requests as i32)
+        .bind(limit as i32)
+        .execute(&*self.db)
+        .await?;
+        Ok(())
+    }
+
+    /// Get a tenant's cap, loading it if the cache is stale; None or 0 means uncapped
+    async fn limit_for(&self, tenant_id: Uuid) -> Result<Option<RpcCap>> {
+        if let Some(entry) = self.limits.get(&tenant_id) {
+            let (loaded_at, cap) = *entry.value();
+            if loaded_at.elapsed() < LIMITS_TTL {
+                return Ok(cap);
+            }
+        }
+
+        let row = sqlx::query_as::<_, (Option<i32>, bool, Option<i32>)>(
+            r#"
+            SELECT t.max_rpc_requests_per_minute, t.is_trial,
+                   (SELECT MIN(a.throttle_limit)
+                    FROM tenant_activity_anomalies a
+                    WHERE a.tenant_id = t.id AND a.throttled_until > now())
+            FROM tenants t
+            WHERE t.id = $1
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&*self.db)
+        .await;
+        let (limit, is_trial, throttle) = match row {
+            Ok(row) => row.unwrap_or((None, false, None)),
+            Err(e) => return cached_or(e, self.limits.get(&tenant_id).map(|entry| entry.1)),
+        };
+
+        let limit = if is_trial {
+            trial_limit(limit, self.trial_cap)
+        } else {
+            limit
+        };
+        let cap = effective_cap(limit, throttle);
+        self.limits.insert(tenant_id, (Instant::now(), cap));
+        Ok(cap)
+    }
+
+    /// Get the names of a tenant's critical monitors
+    async fn critical_monitors(&self, tenant_id: Uuid) -> Result<Arc<HashSet<String>>> {
+        if let Some(entry) = self.critical.get(&tenant_id) {
+            let (loaded_at, names) = entry.value();
+            if loaded_at.elapsed() < LIMITS_TTL {
+                return Ok(names.clone());
+            }
+        }
+
+        let names = match sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT name FROM tenant_monitors
+            WHERE tenant_id = $1 AND is_active = true AND is_critical = true
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_all(&*self.db)
+        .await
+        {
+            Ok(names) => Arc::new(names.into_iter().collect::<HashSet<_>>()),
+            Err(e) => {
+                return cached_or(
+                    e,
+                    self.critical.get(&tenant_id).map(|entry| entry.1.clone()),
+                )
+            }
+        };
+
+        self.critical
+            .insert(tenant_id, (Instant::now(), names.clone()));
+        Ok(names)
+    }
+}
+
+/// Cap of a trial tenant: the lower of its own cap and the trial cap
+fn trial_limit(limit: Option<i32>, trial_cap: u32) -> Option<i32> {
+    let trial_cap = i32::try_from(trial_cap).unwrap_or(i32::MAX);
+    match limit.filter(|limit| *limit > 0) {
+        _ if trial_cap == 0 => limit,
+        Some(limit) => Some(limit.min(trial_cap)),
+        None => Some(trial_cap),
+    }
+}
+
+/// Combine a tenant's own cap with an anomaly throttle, keeping the lower one
+fn effective_cap(limit: Option<i32>, throttle: Option<i32>) -> Option<RpcCap> {
+    let limit = limit.filter(|limit| *limit > 0).map(|limit| limit as u32);
+    let throttle = throttle
+        .filter(|limit| *limit > 0)
+        .map(|limit| limit as u32);
+    match (limit, throttle) {
+        (Some(limit), Some(throttle)) if limit <= throttle => Some(RpcCap {
+            limit,
+            throttled: false,
+        }),
+        (_, Some(throttle)) => Some(RpcCap {
+            limit: throttle,
+            throttled: true,
+        }),
+        (Some(limit), None) => Some(RpcCap {
+            limit,
+            throttled: false,
+        }),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_window_caps_once_and_resets() {
+        let start = Instant::now();
+        let mut window = RpcUsageWindow::new(start);
+
+        assert_eq!(window.charge(start, 2, 3), (false, false));
+        assert_eq!(window.charge(start, 2, 3), (true, true));
+        assert_eq!(window.charge(start, 1, 3), (true, false));
+
+        // A new minute starts from zero
+        assert_eq!(window.charge(start + WINDOW, 3, 3), (false, false));
+    }
+
+    #[test]
+    fn test_trial_cap_lowers_own_cap() {
+        assert_eq!(trial_limit(Some(100), 60), Some(60));
+        assert_eq!(trial_limit(Some(30), 60), Some(30));
+        assert_eq!(trial_limit(None, 60), Some(60));
+        assert_eq!(trial_limit(Some(0), 60), Some(60));
+        assert_eq!(trial_limit(Some(100), 0), Some(100));
+    }
+
+    #[test]
+    fn test_anomaly_throttle_lowers_cap() {
+        assert!(effective_cap(None, None).is_none());
+        assert!(effective_cap(Some(0), None).is_none());
+
+        let cap = effective_cap(Some(100), None).unwrap();
+        assert_eq!((cap.limit, cap.throttled), (100, false));
+
+        let cap = effective_cap(Some(100), Some(60)).unwrap();
+        assert_eq!((cap.limit, cap.throttled), (60, true));
+
+        let cap = effective_cap(None, Some(60)).unwrap();
+        assert_eq!((cap.limit, cap.throttled), (60, true));
+
+        // A throttle above the tenant's own cap changes nothing
+        let cap = effective_cap(Some(30), Some(60)).unwrap();
+        assert_eq!((cap.limit, cap.throttled), (30, false));
+    }
+}
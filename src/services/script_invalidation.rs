@@ -0,0 +1,109 @@
+//! Script Invalidation Service
+//!
+//! Broadcasts trigger script invalidations over Redis pub/sub so that every
+//! worker drops stale entries from its trigger script cache when a script
+//! row changes, instead of serving the old script until restart.
+
+use anyhow::Result;
+use futures::StreamExt;
+use redis::{AsyncCommands, Client as RedisClient};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use uuid::Uuid;
+
+use crate::services::oz_monitor_integration::OzMonitorServices;
+
+/// Delay before re-subscribing after the pub/sub connection drops
+const RESUBSCRIBE_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Invalidation message published when a trigger script changes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScriptInvalidation {
+    /// Tenant owning the script
+    pub tenant_id: Uuid,
+
+    /// Script name as stored in `trigger_scripts.name`
+    pub script_name: String,
+}
+
+/// Publishes and consumes trigger script invalidations
+pub struct ScriptInvalidationService {
+    redis: Arc<RedisClient>,
+    channel: String,
+}
+
+impl ScriptInvalidationService {
+    /// Create a new invalidation service on the given Redis client
+    pub fn new(redis: Arc<RedisClient>, key_prefix: &str) -> Self {
+        Self {
+            redis,
+            channel: format!("{}:script_invalidation", key_prefix),
+        }
+    }
+
+    /// Publish an invalidation to all subscribed workers
+    pub async fn publish(&self, invalidation: &ScriptInvalidation) -> Result<()> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let payload = serde_json::to_string(invalidation)?;
+        let receivers: i64 = conn.publish(&self.channel, payload).await?;
+
+        info!(
+            "Published invalidation for script {} of tenant {} to {} subscribers",
+            invalidation.script_name, invalidation.tenant_id, receivers
+        );
+        Ok(())
+    }
+
+    /// Subscribe to invalidations and evict matching scripts from the given services.
+    ///
+    /// The returned task re-subscribes if the Redis connection drops.
+    pub fn subscribe(&self, oz_services: Arc<OzMonitorServices>) -> tokio::task::JoinHandle<()> {
+        let redis = self.redis.clone();
+        let channel = self.channel.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = listen(&redis, &channel, &oz_services).await {
+                    error!("Script invalidation subscription failed: {}", e);
+                }
+                warn!(
+                    "Script invalidation subscription ended, retrying in {:?}",
+                    RESUBSCRIBE_DELAY
+                );
+                tokio::time::sleep(RESUBSCRIBE_DELAY).await;
+            }
+        })
+    }
+}
+
+/// Consume invalidation messages until the connection closes
+async fn listen(redis: &RedisClient, channel: &str, oz_services: &OzMonitorServices) -> Result<()> {
+    let mut pubsub = redis.get_async_pubsub().await?;
+    pubsub.subscribe(channel).await?;
+    info!("Subscribed to script invalidations on {}", channel);
+
+    let mut messages = pubsub.on_message();
+    while let Some(msg) = messages.next().await {
+        let payload: String = match msg.get_payload() {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Ignoring unreadable script invalidation: {}", e);
+                continue;
+            }
+        };
+
+        match serde_json::from_str::<ScriptInvalidation>(&payload) {
+            Ok(invalidation) => {
+                let evicted = oz_services.invalidate_trigger_script(&invalidation.script_name);
+                debug!(
+                    "Evicted {} cached entries for script {}",
+                    evicted, invalidation.script_name
+                );
+            }
+            Err(e) => warn!("Ignoring malformed script invalidation: {}", e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,328 @@
+//! Session Recorder
+//!
+//! Records what a worker saw during a time window — every block event it
+//! received and each tenant's monitors whenever they change — to a JSON lines
+//! file, and replays a recording against the recorded or modified monitors.
+//! Replays run the filter service on the recorded blocks without recording
+//! matches or executing triggers, so a missed alert can be traced back to the
+//! exact blocks and configuration the worker had.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::fs::File;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use openzeppelin_monitor::models::Monitor;
+
+use crate::services::oz_monitor_integration::OzMonitorServices;
+use crate::services::shared_block_watcher::BlockEvent;
+
+/// One line of a session recording
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SessionEntry {
+    /// First line of every recording
+    Started {
+        worker_id: String,
+        started_at: DateTime<Utc>,
+    },
+    /// A tenant's monitors, recorded before the first block and after every change
+    Config {
+        tenant_id: Uuid,
+        recorded_at: DateTime<Utc>,
+        monitors: Vec<Monitor>,
+    },
+    /// Block event as received, before any prefiltering, with the tenants it was processed for
+    Block {
+        tenant_ids: Vec<Uuid>,
+        event: BlockEvent,
+    },
+}
+
+/// Appends a worker's block events and monitor snapshots to a recording file
+pub struct SessionRecorder {
+    path: PathBuf,
+    until: Instant,
+    state: Mutex<RecorderState>,
+}
+
+struct RecorderState {
+    /// Closed once the window ends or a write fails
+    writer: Option<File>,
+    /// Hash of the last recorded monitors of each tenant
+    config_hashes: HashMap<Uuid, u64>,
+}
+
+impl SessionRecorder {
+    /// Start recording to a new file in `dir`, stopping after `window`
+    pub async fn start(dir: &Path, worker_id: &str, window: Duration) -> Result<Self> {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Failed to create recording directory {}", dir.display()))?;
+
+        let started_at = Utc::now();
+        let path = dir.join(format!(
+            "{}-{}.session.jsonl",
+            worker_id,
+            started_at.format("%Y%m%dT%H%M%SZ")
+        ));
+        let mut writer = File::create(&path)
+            .await
+            .with_context(|| format!("Failed to create recording {}", path.display()))?;
+        write_entry(
+            &mut writer,
+            &SessionEntry::Started {
+                worker_id: worker_id.to_string(),
+                started_at,
+            },
+        )
+        .await?;
+
+        info!(
+            "Recording block events of worker {} to {} for {:?}",
+            worker_id,
+            path.display(),
+            window
+        );
+        Ok(Self {
+            path,
+            until: Instant::now() + window,
+            state: Mutex::new(RecorderState {
+                writer: Some(writer),
+                config_hashes: HashMap::new(),
+            }),
+        })
+    }
+
+    /// Recording file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Record a block event and any monitor changes of the tenants it is processed for.
+    ///
+    /// Failures stop the recording and never affect block processing.
+    pub async fn record(
+        &self,
+        oz_services: &OzMonitorServices,
+        tenant_ids: &[Uuid],
+        event: &BlockEvent,
+    ) {
+        let mut state = self.state.lock().await;
+        if state.writer.is_none() {
+            return;
+        }
+        if Instant::now() >= self.until {
+            info!("Recording window ended, closed {}", self.path.display());
+            state.writer = None;
+            return;
+        }
+
+        if let Err(e) = self
+            .record_entries(&mut state, oz_services, tenant_ids, event)
+            .await
+        {
+            warn!("Stopped recording to {}: {}", self.path.display(), e);
+            state.writer = None;
+        }
+    }
+
+    async fn record_entries(
+        &self,
+        state: &mut RecorderState,
+        oz_services: &OzMonitorServices,
+        tenant_ids: &[Uuid],
+        event: &BlockEvent,
+    ) -> Result<()> {
+        let RecorderState {
+            writer,
+            config_hashes,
+        } = state;
+        let writer = writer.as_mut().expect("recording is open");
+
+        for tenant_id in tenant_ids {
+            let mut monitors: Vec<Monitor> = oz_services
+                .tenant_monitors(*tenant_id)
+                .await?
+                .into_values()
+                .collect();
+            monitors.sort_by(|a, b| a.name.cmp(&b.name));
+
+            let mut hasher = DefaultHasher::new();
+            serde_json::to_string(&monitors)?.hash(&mut hasher);
+            let hash = hasher.finish();
+            if config_hashes.insert(*tenant_id, hash) == Some(hash) {
+                continue;
+            }
+
+            write_entry(
+                writer,
+                &SessionEntry::Config {
+                    tenant_id: *tenant_id,
+                    recorded_at: Utc::now(),
+                    monitors,
+                },
+            )
+            .await?;
+        }
+
+        write_entry(
+            writer,
+            &SessionEntry::Block {
+                tenant_ids: tenant_ids.to_vec(),
+                event: event.clone(),
+            },
+        )
+        .await
+    }
+}
+
+async fn write_entry(writer: &mut File, entry: &SessionEntry) -> Result<()> {
+    let mut line = serde_json::to_vec(entry)?;
+    line.push(b'\n');
+    writer.write_all(&line).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Match found while replaying a recording
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct ReplayMatch {
+    pub tenant_id: Uuid,
+    pub network_slug: String,
+    pub block_number: Option<u64>,
+    pub monitor_name: String,
+}
+
+/// Recording loaded for replay
+pub struct RecordedSession {
+    pub worker_id: String,
+    pub started_at: DateTime<Utc>,
+    entries: Vec<SessionEntry>,
+}
+
+impl RecordedSession {
+    /// Read a recording written by [`SessionRecorder`]
+    pub async fn load(path: &Path) -> Result<Self> {
+        let file = File::open(path)
+            .await
+            .with_context(|| format!("Failed to open recording {}", path.display()))?;
+        let mut lines = BufReader::new(file).lines();
+
+        let mut entries = Vec::new();
+        let mut line_number = 0;
+        while let Some(line) = lines.next_line().await? {
+            line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: SessionEntry = serde_json::from_str(&line).with_context(|| {
+                format!(
+                    "Invalid entry on line {} of {}",
+                    line_number,
+                    path.display()
+                )
+            })?;
+            entries.push(entry);
+        }
+
+        let Some(SessionEntry::Started {
+            worker_id,
+            started_at,
+        }) = entries.first().cloned()
+        else {
+            anyhow::bail!("{} is not a session recording", path.display());
+        };
+
+        Ok(Self {
+            worker_id,
+            started_at,
+            entries,
+        })
+    }
+
+    /// Tenants whose blocks were recorded
+    pub fn tenant_ids(&self) -> Vec<Uuid> {
+        let tenant_ids: HashSet<Uuid> = self
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                SessionEntry::Block { tenant_ids, .. } => Some(tenant_ids.iter().copied()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        tenant_ids.into_iter().collect()
+    }
+
+    /// Number of recorded blocks
+    pub fn block_count(&self) -> usize {
+        self.entries
+            .iter()
+            .map(|entry| match entry {
+                SessionEntry::Block { event, .. } => event.blocks.len(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Run the recorded blocks through the filters in recorded order.
+    ///
+    /// Each block is filtered against the monitors its tenant had at the time,
+    /// unless `overrides` replaces a tenant's monitors for the whole session.
+    pub async fn replay(
+        &self,
+        oz_services: &OzMonitorServices,
+        overrides: &HashMap<Uuid, Vec<Monitor>>,
+    ) -> Result<Vec<ReplayMatch>> {
+        let mut recorded_monitors: HashMap<Uuid, &[Monitor]> = HashMap::new();
+        let mut matches = Vec::new();
+
+        for entry in &self.entries {
+            match entry {
+                SessionEntry::Started { .. } => {}
+                SessionEntry::Config {
+                    tenant_id,
+                    monitors,
+                    ..
+                } => {
+                    recorded_monitors.insert(*tenant_id, monitors);
+                }
+                SessionEntry::Block { tenant_ids, event } => {
+                    for block in &event.blocks {
+                        for tenant_id in tenant_ids {
+                            let monitors = match overrides.get(tenant_id) {
+                                Some(monitors) => monitors.as_slice(),
+                                None => match recorded_monitors.get(tenant_id).copied() {
+                                    Some(monitors) => monitors,
+                                    None => continue,
+                                },
+                            };
+
+                            let found = oz_services
+                                .replay_block(*tenant_id, &event.network, block.clone(), monitors)
+                                .await?;
+                            matches.extend(found.into_iter().map(|m| ReplayMatch {
+                                tenant_id: *tenant_id,
+                                network_slug: event.network.slug.clone(),
+                                block_number: block.number(),
+                                monitor_name: m.monitor_name,
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(matches)
+    }
+}
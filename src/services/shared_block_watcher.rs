@@ -4,11 +4,13 @@
 //! distributes them to all worker instances.
 
 use anyhow::{Context, Result};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{broadcast, RwLock};
-use tracing::{debug, error, info, instrument, warn};
+use tracing::{debug, error, info, instrument, warn, Instrument};
+use uuid::Uuid;
 
 // Import OpenZeppelin Monitor types
 use openzeppelin_monitor::{
@@ -16,7 +18,28 @@ use openzeppelin_monitor::{
     services::blockchain::{BlockChainClient, ClientPoolTrait},
 };
 
-use crate::services::block_cache::BlockCacheService;
+use crate::models::AddressBloom;
+use crate::repositories::RepositoryError;
+use crate::services::block_cache::{BlockCacheService, CachedBlockClient};
+use crate::services::chain_support::ChainSupport;
+use crate::services::error::ServiceError;
+use crate::services::event_bus::{Event, EventBus, InProcessEventBus};
+use crate::services::publish_buffer::{Publication, PublishBuffer};
+use crate::services::retry::RetryPolicy;
+use crate::services::shutdown::ShutdownSignal;
+use crate::services::trace_context::TraceContext;
+use crate::services::watcher_handoff::WatcherHandoff;
+
+/// How often a full channel is re-checked when applying backpressure
+const BACKPRESSURE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Where block events are published: straight on the bus, or through a
+/// buffer queueing them while the bus is unreachable
+#[derive(Clone, Copy)]
+struct Publisher<'a> {
+    events: &'a dyn EventBus,
+    buffer: Option<&'a PublishBuffer>,
+}
 
 /// Block event sent to workers
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,6 +47,23 @@ pub struct BlockEvent {
     pub network: Network,
     pub blocks: Vec<BlockType>,
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Addresses touched by the blocks; `None` means workers must inspect every block
+    #[serde(default)]
+    pub address_bloom: Option<AddressBloom>,
+    /// Chain head when the blocks were fetched, for counting confirmations
+    #[serde(default)]
+    pub latest_block: Option<u64>,
+    /// Blocks re-broadcast on request rather than newly watched; workers
+    /// process them without advancing any progress of their own
+    #[serde(default)]
+    pub replay: bool,
+    /// Tenants a replay is limited to; empty for every tenant
+    #[serde(default)]
+    pub tenant_ids: Vec<Uuid>,
+    /// Trace the blocks were fetched under, continued by the workers
+    /// processing them; `None` from watchers predating trace propagation
+    #[serde(default)]
+    pub trace: Option<TraceContext>,
 }
 
 /// Shared block watcher configuration
@@ -33,10 +73,16 @@ pub struct SharedBlockWatcherConfig {
     pub channel_buffer_size: usize,
     /// Maximum blocks to fetch per iteration
     pub max_blocks_per_fetch: u64,
-    /// Block fetch retry attempts
-    pub retry_attempts: u32,
-    /// Retry delay in milliseconds
-    pub retry_delay_ms: u64,
+    /// Retry policy for block fetches
+    pub retry: RetryPolicy,
+    /// Number of latest blocks to pre-fetch per network on startup (0 disables)
+    pub warm_cache_depth: u64,
+    /// Maximum networks warmed concurrently
+    pub warm_cache_concurrency: usize,
+    /// Wait for the slowest subscriber instead of overwriting unreceived events
+    pub backpressure: bool,
+    /// Maximum blocks re-broadcast by a single replay request
+    pub max_replay_blocks: u64,
 }
 
 impl Default for SharedBlockWatcherConfig {
@@ -44,8 +90,11 @@ impl Default for SharedBlockWatcherConfig {
         Self {
             channel_buffer_size: 1000,
             max_blocks_per_fetch: 100,
-            retry_attempts: 3,
-            retry_delay_ms: 1000,
+            retry: RetryPolicy::new(3, std::time::Duration::from_millis(1000)),
+            warm_cache_depth: 10,
+            warm_cache_concurrency: 4,
+            backpressure: false,
+            max_replay_blocks: 1000,
         }
     }
 }
@@ -55,37 +104,176 @@ struct NetworkWatcherState {
     network: Network,
     last_processed_block: u64,
     is_running: bool,
+    /// Chain head at the last successful poll
+    latest_block: Option<u64>,
+    /// Failed polls since the last successful one
+    consecutive_failures: u32,
+    last_error: Option<String>,
+    last_polled_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl NetworkWatcherState {
+    fn new(network: Network) -> Self {
+        Self {
+            network,
+            last_processed_block: 0,
+            is_running: false,
+            latest_block: None,
+            consecutive_failures: 0,
+            last_error: None,
+            last_polled_at: None,
+        }
+    }
+
+    fn status(&self) -> NetworkWatcherStatus {
+        let last_processed_block =
+            (self.last_processed_block > 0).then_some(self.last_processed_block);
+        NetworkWatcherStatus {
+            network_slug: self.network.slug.clone(),
+            watching: self.is_running,
+            last_processed_block,
+            latest_block: self.latest_block,
+            lag: self.latest_block.map(|latest| {
+                latest
+                    .saturating_sub(self.network.confirmation_blocks)
+                    .saturating_sub(self.last_processed_block)
+            }),
+            confirmation_blocks: self.network.confirmation_blocks,
+            rpc_healthy: self.last_polled_at.is_some() && self.consecutive_failures == 0,
+            consecutive_failures: self.consecutive_failures,
+            last_error: self.last_error.clone(),
+            last_polled_at: self.last_polled_at,
+        }
+    }
+}
+
+/// Watcher state of one network
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkWatcherStatus {
+    pub network_slug: String,
+    /// Whether a watcher task is polling the network; false while paused or not started
+    pub watching: bool,
+    /// Last block broadcast to workers
+    pub last_processed_block: Option<u64>,
+    /// Newest chain head seen, at the last successful poll or in the block cache
+    pub latest_block: Option<u64>,
+    /// Confirmed blocks not yet broadcast
+    pub lag: Option<u64>,
+    /// Depth the network is watched at
+    pub confirmation_blocks: u64,
+    /// Whether the last poll of the network's RPC succeeded
+    pub rpc_healthy: bool,
+    /// Failed polls since the last successful one
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>,
+    pub last_polled_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl NetworkWatcherStatus {
+    /// Account for a chain head seen elsewhere, e.g. cached by another process
+    fn observe_latest_block(&mut self, latest_block: u64) {
+        if self.latest_block >= Some(latest_block) {
+            return;
+        }
+        self.latest_block = Some(latest_block);
+        self.lag = Some(
+            latest_block
+                .saturating_sub(self.confirmation_blocks)
+                .saturating_sub(self.last_processed_block.unwrap_or(0)),
+        );
+    }
 }
 
 /// Shared block watcher that fetches blocks once per network
 pub struct SharedBlockWatcher {
     networks: Arc<RwLock<HashMap<String, NetworkWatcherState>>>,
-    block_sender: broadcast::Sender<BlockEvent>,
+    /// Bus block events are published on
+    events: Arc<dyn EventBus>,
     cache: Arc<BlockCacheService>,
     config: SharedBlockWatcherConfig,
     watcher_handles: Arc<RwLock<Vec<tokio::task::JoinHandle<()>>>>,
+    /// Lease and cursors shared with other replicas during deploys
+    handoff: Option<Arc<WatcherHandoff>>,
+    /// Queue for block events the event bus cannot take
+    publish_buffer: Option<Arc<PublishBuffer>>,
+    /// Task publishing the queued block events
+    publish_handle: std::sync::Mutex<Option<tokio::task::JoinHandle<()>>>,
+    /// Chain types networks may be added for
+    chains: ChainSupport,
+    /// Signals network watchers to stop after their current cycle
+    shutdown: ShutdownSignal,
 }
 
 impl SharedBlockWatcher {
     pub fn new(cache: Arc<BlockCacheService>, config: SharedBlockWatcherConfig) -> Self {
-        let (block_sender, _) = broadcast::channel(config.channel_buffer_size);
-
+        let events = Arc::new(InProcessEventBus::new(config.channel_buffer_size));
         Self {
             networks: Arc::new(RwLock::new(HashMap::new())),
-            block_sender,
+            events,
             cache,
             config,
             watcher_handles: Arc::new(RwLock::new(Vec::new())),
+            handoff: None,
+            publish_buffer: None,
+            publish_handle: std::sync::Mutex::new(None),
+            chains: ChainSupport::default(),
+            shutdown: ShutdownSignal::new(),
+        }
+    }
+
+    /// Stop network watchers after their current cycle once the given signal
+    /// is triggered; [`Self::stop`] still releases the lease
+    pub fn with_shutdown(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Coordinate with other replicas so a successor resumes from this one's cursors
+    pub fn with_handoff(mut self, handoff: Arc<WatcherHandoff>) -> Self {
+        self.handoff = Some(handoff);
+        self
+    }
+
+    /// Only watch networks of the chain types enabled in the given registry
+    pub fn with_chain_support(mut self, chains: ChainSupport) -> Self {
+        self.chains = chains;
+        self
+    }
+
+    /// Publish block events on the given bus instead of in-process only
+    pub fn with_event_bus(mut self, events: Arc<dyn EventBus>) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Publish block events through the given buffer, queueing them while
+    /// its event bus is unreachable
+    pub fn with_publish_buffer(mut self, buffer: Arc<PublishBuffer>) -> Self {
+        self.events = buffer.event_bus();
+        self.publish_buffer = Some(buffer);
+        self
+    }
+
+    /// Bus block events are published on
+    pub fn event_bus(&self) -> Arc<dyn EventBus> {
+        self.events.clone()
+    }
+
+    fn publisher(&self) -> Publisher<'_> {
+        Publisher {
+            events: self.events.as_ref(),
+            buffer: self.publish_buffer.as_deref(),
         }
     }
 
     /// Subscribe to block events
     pub fn subscribe(&self) -> broadcast::Receiver<BlockEvent> {
-        self.block_sender.subscribe()
+        self.events.subscribe_blocks()
     }
 
-    /// Add a network to watch
+    /// Add a network to watch, failing for chain types that are not enabled
     pub async fn add_network(&self, network: Network) -> Result<()> {
+        self.chains.check(&network.network_type)?;
         let mut networks = self.networks.write().await;
 
         if networks.contains_key(&network.slug) {
@@ -93,13 +281,10 @@ impl SharedBlockWatcher {
             return Ok(());
         }
 
-        let state = NetworkWatcherState {
-            network: network.clone(),
-            last_processed_block: 0,
-            is_running: false,
-        };
-
-        networks.insert(network.slug.clone(), state);
+        networks.insert(
+            network.slug.clone(),
+            NetworkWatcherState::new(network.clone()),
+        );
         info!("Added network {} to shared block watcher", network.slug);
 
         Ok(())
@@ -117,6 +302,142 @@ impl SharedBlockWatcher {
         Ok(())
     }
 
+    /// Number of network watcher tasks still alive
+    pub async fn running_watchers(&self) -> usize {
+        self.watcher_handles
+            .read()
+            .await
+            .iter()
+            .filter(|handle| !handle.is_finished())
+            .count()
+    }
+
+    /// Watcher state of every network, ordered by slug.
+    ///
+    /// Latest blocks and lag also account for the chain head in the block
+    /// cache, which may be newer than this watcher's last poll.
+    pub async fn network_statuses(&self) -> Vec<NetworkWatcherStatus> {
+        let mut statuses: Vec<NetworkWatcherStatus> = self
+            .networks
+            .read()
+            .await
+            .values()
+            .map(NetworkWatcherState::status)
+            .collect();
+        statuses.sort_by(|a, b| a.network_slug.cmp(&b.network_slug));
+
+        for status in &mut statuses {
+            if let Some(latest_block) = self.cached_latest_block(&status.network_slug).await {
+                status.observe_latest_block(latest_block);
+            }
+        }
+        statuses
+    }
+
+    /// Latest block number of a network in the block cache, shared by every process
+    pub async fn cached_latest_block(&self, network_slug: &str) -> Option<u64> {
+        match self.cache.latest_block(network_slug).await {
+            Ok(latest_block) => latest_block,
+            Err(e) => {
+                debug!(
+                    "Failed to read cached latest block of {}: {}",
+                    network_slug, e
+                );
+                None
+            }
+        }
+    }
+
+    /// Re-broadcast blocks `from_block..=to_block` of a watched network as replay events.
+    ///
+    /// Blocks are read from the block cache where present and fetched from
+    /// RPC otherwise. Workers only process them for `tenant_ids`, or for all
+    /// their tenants if empty. The network's cursor is left untouched, so
+    /// normal watching continues where it was. Returns the number of blocks sent.
+    #[instrument(skip(self, client_pool))]
+    pub async fn replay_blocks<CP: ClientPoolTrait>(
+        &self,
+        client_pool: &CP,
+        network_slug: &str,
+        from_block: u64,
+        to_block: u64,
+        tenant_ids: &[Uuid],
+    ) -> Result<usize, ServiceError> {
+        let network = self
+            .networks
+            .read()
+            .await
+            .get(network_slug)
+            .map(|state| state.network.clone())
+            .ok_or_else(|| RepositoryError::NotFound {
+                entity_type: "watched network".to_string(),
+                id: network_slug.to_string(),
+            })?;
+
+        if from_block > to_block {
+            return Err(ServiceError::InvalidState(format!(
+                "from_block {} is after to_block {}",
+                from_block, to_block
+            )));
+        }
+        let block_count = to_block - from_block + 1;
+        if block_count > self.config.max_replay_blocks {
+            return Err(ServiceError::ResourceLimitExceeded(format!(
+                "Replay of {} blocks exceeds the limit of {}",
+                block_count, self.config.max_replay_blocks
+            )));
+        }
+
+        let replayed = match network.network_type {
+            openzeppelin_monitor::models::BlockChainType::EVM => {
+                let client = client_pool
+                    .get_evm_client(&network)
+                    .await
+                    .context("Failed to get EVM client")?;
+                let client = CachedBlockClient::from_arc(client, self.cache.clone(), &network);
+                replay_blocks_for_client(
+                    &client,
+                    &network,
+                    from_block,
+                    to_block,
+                    tenant_ids,
+                    &self.config,
+                    self.publisher(),
+                )
+                .await?
+            }
+            openzeppelin_monitor::models::BlockChainType::Stellar => {
+                let client = client_pool
+                    .get_stellar_client(&network)
+                    .await
+                    .context("Failed to get Stellar client")?;
+                let client = CachedBlockClient::from_arc(client, self.cache.clone(), &network);
+                replay_blocks_for_client(
+                    &client,
+                    &network,
+                    from_block,
+                    to_block,
+                    tenant_ids,
+                    &self.config,
+                    self.publisher(),
+                )
+                .await?
+            }
+            _ => {
+                return Err(ServiceError::InvalidState(format!(
+                    "Unsupported network type for {}",
+                    network.slug
+                )))
+            }
+        };
+
+        info!(
+            "Replayed {} blocks {}-{} on network {}",
+            replayed, from_block, to_block, network.slug
+        );
+        Ok(replayed)
+    }
+
     /// Start watching all networks
     #[instrument(skip(self, client_pool))]
     pub async fn start<CP: ClientPoolTrait + Send + Sync + 'static>(
@@ -144,6 +465,25 @@ impl SharedBlockWatcher {
                 .collect()
         };
 
+        // Take over from the previous replica before polling anything
+        if let Some(handoff) = &self.handoff {
+            handoff.acquire().await?;
+            self.restore_cursors(handoff, &networks_to_start).await?;
+            self.watch_lease(handoff.clone());
+        }
+
+        if let Some(buffer) = &self.publish_buffer {
+            let mut publish_handle = self.publish_handle.lock().unwrap();
+            if publish_handle.is_none() {
+                *publish_handle = Some(buffer.start());
+            }
+        }
+
+        // Warm the block cache before the first fetch cycle
+        if self.config.warm_cache_depth > 0 {
+            self.warm_cache(&networks_to_start, &client_pool).await;
+        }
+
         let mut started_count = 0;
 
         // Start a watcher task for each network
@@ -174,6 +514,111 @@ impl SharedBlockWatcher {
         Ok(())
     }
 
+    /// Resume each network from the cursor recorded by the previous replica
+    async fn restore_cursors(
+        &self,
+        handoff: &WatcherHandoff,
+        networks: &[(String, Network)],
+    ) -> Result<()> {
+        for (slug, _) in networks {
+            let Some(cursor) = handoff.load_cursor(slug).await? else {
+                continue;
+            };
+
+            if let Some((start, end)) = cursor.in_flight {
+                warn!(
+                    "Blocks {}-{} on network {} were in flight at handoff from {}, re-fetching",
+                    start, end, slug, cursor.owner
+                );
+            }
+
+            if let Some(state) = self.networks.write().await.get_mut(slug) {
+                state.last_processed_block = cursor.last_processed_block;
+            }
+            info!(
+                "Resuming network {} after block {} (handed off by {})",
+                slug, cursor.last_processed_block, cursor.owner
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Stop all watchers if the watcher lease is taken over by another replica
+    fn watch_lease(&self, handoff: Arc<WatcherHandoff>) -> tokio::task::JoinHandle<()> {
+        let shutdown = self.shutdown.clone();
+        let mut lease_lost = handoff.lease_lost();
+
+        tokio::spawn(async move {
+            tokio::select! {
+                Ok(_) = lease_lost.wait_for(|lost| *lost) => {
+                    error!(
+                        "Watcher {} lost the block watcher lease, stopping",
+                        handoff.owner()
+                    );
+                    shutdown.trigger();
+                }
+                _ = shutdown.wait() => {}
+            }
+        })
+    }
+
+    /// Stop all network watchers after their current cycle and hand off the lease.
+    ///
+    /// Cursors are already recorded after every broadcast, so the successor
+    /// resumes from the last block this replica sent. Block events still
+    /// buffered for the event bus are dropped; the successor fetches them again.
+    pub async fn stop(&self) -> Result<()> {
+        info!("Stopping shared block watcher");
+        self.shutdown.trigger();
+
+        let handles = std::mem::take(&mut *self.watcher_handles.write().await);
+        for handle in handles {
+            if let Err(e) = handle.await {
+                warn!("Network watcher task failed during shutdown: {}", e);
+            }
+        }
+
+        if let Some(handle) = self.publish_handle.lock().unwrap().take() {
+            handle.abort();
+        }
+
+        if let Some(handoff) = &self.handoff {
+            handoff.release().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Pre-fetch latest block numbers and blocks for the given networks.
+    ///
+    /// Networks are warmed with bounded concurrency; failures are logged and
+    /// leave the network to be fetched on its first cycle.
+    async fn warm_cache<CP: ClientPoolTrait + Send + Sync + 'static>(
+        &self,
+        networks: &[(String, Network)],
+        client_pool: &Arc<CP>,
+    ) {
+        info!("Warming block cache for {} networks", networks.len());
+
+        futures::stream::iter(networks)
+            .map(|(slug, network)| async move {
+                if let Err(e) = warm_network_cache(
+                    network,
+                    client_pool,
+                    &self.cache,
+                    self.config.warm_cache_depth,
+                )
+                .await
+                {
+                    warn!("Failed to warm block cache for network {}: {}", slug, e);
+                }
+            })
+            .buffer_unordered(self.config.warm_cache_concurrency)
+            .collect::<Vec<_>>()
+            .await;
+    }
+
     /// Run the block watcher - this method keeps the watcher alive
     pub async fn run(&self) -> Result<()> {
         info!("SharedBlockWatcher::run() - keeping block watcher alive");
@@ -182,13 +627,13 @@ impl SharedBlockWatcher {
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
 
         // Wait for all watcher tasks to complete (they run forever unless stopped)
-        let handles = self.watcher_handles.read().await;
-        if handles.is_empty() {
+        let handle_count = self.watcher_handles.read().await.len();
+        if handle_count == 0 {
             warn!("No network watcher tasks to wait for");
             return Ok(());
         }
 
-        info!("Waiting for {} network watcher tasks", handles.len());
+        info!("Waiting for {} network watcher tasks", handle_count);
 
         // This will block forever unless the tasks are cancelled
         loop {
@@ -216,9 +661,12 @@ impl SharedBlockWatcher {
         client_pool: Arc<CP>,
     ) -> Result<tokio::task::JoinHandle<()>> {
         let networks = self.networks.clone();
-        let block_sender = self.block_sender.clone();
+        let events = self.events.clone();
+        let publish_buffer = self.publish_buffer.clone();
         let cache = self.cache.clone();
         let config = self.config.clone();
+        let handoff = self.handoff.clone();
+        let shutdown = self.shutdown.clone();
         let network_slug = network.slug.clone();
         let network_slug_for_log = network_slug.clone();
 
@@ -250,6 +698,10 @@ impl SharedBlockWatcher {
 
             loop {
                 // Check if we should continue
+                if shutdown.is_triggered() {
+                    info!("Shutting down watcher for network {}", network_slug);
+                    break;
+                }
                 {
                     let networks_lock = networks.read().await;
                     if let Some(state) = networks_lock.get(&network_slug) {
@@ -268,16 +720,21 @@ impl SharedBlockWatcher {
                     "[SPAWNED TASK] About to fetch blocks for network {}",
                     network_slug
                 );
-                match fetch_and_broadcast_blocks(
+                let result = fetch_and_broadcast_blocks(
                     &network,
                     &networks,
                     &client_pool,
-                    &block_sender,
+                    Publisher {
+                        events: events.as_ref(),
+                        buffer: publish_buffer.as_deref(),
+                    },
                     &cache,
                     &config,
+                    handoff.as_deref(),
                 )
-                .await
-                {
+                .await;
+                record_poll(&networks, &network_slug, &result).await;
+                match result {
                     Ok(blocks_processed) => {
                         if blocks_processed > 0 {
                             info!(
@@ -296,9 +753,12 @@ impl SharedBlockWatcher {
                     }
                 }
 
-                // Sleep based on network's cron schedule or default interval
-                let sleep_duration = calculate_sleep_duration(&network);
-                tokio::time::sleep(sleep_duration).await;
+                // Sleep for the interval of the network's chain type
+                let sleep_duration = poll_interval(&network.network_type);
+                tokio::select! {
+                    _ = tokio::time::sleep(sleep_duration) => {}
+                    _ = shutdown.wait() => {}
+                }
             }
 
             // Mark as not running
@@ -317,14 +777,41 @@ impl SharedBlockWatcher {
     }
 }
 
+/// Warm the block cache for a single network
+async fn warm_network_cache<CP: ClientPoolTrait>(
+    network: &Network,
+    client_pool: &Arc<CP>,
+    cache: &Arc<BlockCacheService>,
+    depth: u64,
+) -> Result<usize> {
+    match network.network_type {
+        openzeppelin_monitor::models::BlockChainType::EVM => {
+            let client = client_pool
+                .get_evm_client(network)
+                .await
+                .context("Failed to get EVM client")?;
+            cache.warm_network(client.as_ref(), network, depth).await
+        }
+        openzeppelin_monitor::models::BlockChainType::Stellar => {
+            let client = client_pool
+                .get_stellar_client(network)
+                .await
+                .context("Failed to get Stellar client")?;
+            cache.warm_network(client.as_ref(), network, depth).await
+        }
+        _ => Ok(0),
+    }
+}
+
 /// Fetch blocks and broadcast to subscribers
 async fn fetch_and_broadcast_blocks<CP: ClientPoolTrait>(
     network: &Network,
     networks: &Arc<RwLock<HashMap<String, NetworkWatcherState>>>,
     client_pool: &Arc<CP>,
-    block_sender: &broadcast::Sender<BlockEvent>,
-    _cache: &Arc<BlockCacheService>,
+    publisher: Publisher<'_>,
+    cache: &Arc<BlockCacheService>,
     config: &SharedBlockWatcherConfig,
+    handoff: Option<&WatcherHandoff>,
 ) -> Result<usize> {
     // Get the last processed block
     let last_processed_block = {
@@ -342,14 +829,16 @@ async fn fetch_and_broadcast_blocks<CP: ClientPoolTrait>(
                 .get_evm_client(network)
                 .await
                 .context("Failed to get EVM client")?;
+            let client = CachedBlockClient::from_arc(client, cache.clone(), network);
 
             fetch_blocks_for_client(
-                client.as_ref(),
+                &client,
                 network,
                 last_processed_block,
                 config,
-                block_sender,
+                publisher,
                 networks,
+                handoff,
             )
             .await
         }
@@ -358,14 +847,16 @@ async fn fetch_and_broadcast_blocks<CP: ClientPoolTrait>(
                 .get_stellar_client(network)
                 .await
                 .context("Failed to get Stellar client")?;
+            let client = CachedBlockClient::from_arc(client, cache.clone(), network);
 
             fetch_blocks_for_client(
-                client.as_ref(),
+                &client,
                 network,
                 last_processed_block,
                 config,
-                block_sender,
+                publisher,
                 networks,
+                handoff,
             )
             .await
         }
@@ -382,16 +873,19 @@ async fn fetch_blocks_for_client<C: BlockChainClient>(
     network: &Network,
     last_processed_block: u64,
     config: &SharedBlockWatcherConfig,
-    block_sender: &broadcast::Sender<BlockEvent>,
+    publisher: Publisher<'_>,
     networks: &Arc<RwLock<HashMap<String, NetworkWatcherState>>>,
+    handoff: Option<&WatcherHandoff>,
 ) -> Result<usize> {
     // Get latest block number
-    let latest_block = retry_with_backoff(
-        || client.get_latest_block_number(),
-        config.retry_attempts,
-        config.retry_delay_ms,
-    )
-    .await?;
+    let latest_block = config
+        .retry
+        .retry(|| client.get_latest_block_number())
+        .await?;
+
+    if let Some(state) = networks.write().await.get_mut(&network.slug) {
+        state.latest_block = Some(latest_block);
+    }
 
     let latest_confirmed_block = latest_block.saturating_sub(network.confirmation_blocks);
 
@@ -414,59 +908,265 @@ async fn fetch_blocks_for_client<C: BlockChainClient>(
         start_block + config.max_blocks_per_fetch - 1,
     );
 
-    // Fetch blocks
-    let blocks = retry_with_backoff(
-        || client.get_blocks(start_block, Some(end_block)),
-        config.retry_attempts,
-        config.retry_delay_ms,
-    )
-    .await?;
+    // Fetch blocks under a new trace, continued by the workers processing them
+    let trace = TraceContext::new_root();
+    let span = trace.fetch_span(&network.slug, start_block, end_block);
+    let blocks = config
+        .retry
+        .retry(|| client.get_blocks(start_block, Some(end_block)))
+        .instrument(span.clone())
+        .await?;
 
     if blocks.is_empty() {
         return Ok(0);
     }
 
+    // Record the range before broadcasting so a successor never skips it.
+    // While earlier blocks wait in the publish buffer, the cursor stays on
+    // the last block actually published.
+    let published_through = publisher
+        .buffer
+        .and_then(|buffer| buffer.published_through(&network.slug))
+        .unwrap_or(last_processed_block);
+    if let Some(handoff) = handoff {
+        if !handoff
+            .begin_range(&network.slug, published_through, start_block, end_block)
+            .await?
+        {
+            anyhow::bail!(
+                "Block watcher lease lost, not broadcasting blocks {}-{} on network {}",
+                start_block,
+                end_block,
+                network.slug
+            );
+        }
+    }
+
     // Create block event
     let event = BlockEvent {
         network: network.clone(),
+        address_bloom: block_address_bloom(&blocks),
         blocks: blocks.clone(),
         timestamp: chrono::Utc::now(),
+        latest_block: Some(latest_block),
+        replay: false,
+        tenant_ids: Vec::new(),
+        trace: Some(trace),
     };
+    span.in_scope(|| {
+        debug!(
+            "Broadcasting {} blocks {}-{} on network {}",
+            event.blocks.len(),
+            start_block,
+            end_block,
+            network.slug
+        )
+    });
+    let buffered = broadcast_event(
+        publisher,
+        config,
+        event,
+        Some((published_through, end_block)),
+    )
+    .instrument(span)
+    .await;
 
-    // Broadcast to all subscribers
-    match block_sender.send(event) {
-        Ok(receiver_count) => {
-            info!(
-                "Broadcast {} blocks for network {} to {} subscribers",
-                blocks.len(),
-                network.slug,
-                receiver_count
+    // Update last processed block; buffered blocks are not fetched again
+    {
+        let mut networks_lock = networks.write().await;
+        if let Some(state) = networks_lock.get_mut(&network.slug) {
+            state.last_processed_block = end_block;
+        }
+    }
+
+    // The publish buffer records the cursor of buffered blocks once it publishes them
+    if let Some(handoff) = handoff.filter(|_| !buffered) {
+        if !handoff.commit_range(&network.slug, end_block).await? {
+            warn!(
+                "Block watcher lease lost after broadcasting blocks up to {} on network {}",
+                end_block, network.slug
+            );
+        }
+    }
+
+    Ok(blocks.len())
+}
+
+/// Fetch a range of blocks in batches and broadcast them as replay events
+async fn replay_blocks_for_client<C: BlockChainClient>(
+    client: &C,
+    network: &Network,
+    from_block: u64,
+    to_block: u64,
+    tenant_ids: &[Uuid],
+    config: &SharedBlockWatcherConfig,
+    publisher: Publisher<'_>,
+) -> Result<usize, ServiceError> {
+    let latest_block = config
+        .retry
+        .retry(|| client.get_latest_block_number())
+        .await?;
+    if to_block > latest_block {
+        return Err(ServiceError::InvalidState(format!(
+            "to_block {} is beyond the chain head {} of network {}",
+            to_block, latest_block, network.slug
+        )));
+    }
+
+    let mut replayed = 0;
+    let mut start_block = from_block;
+    while start_block <= to_block {
+        let end_block = std::cmp::min(to_block, start_block + config.max_blocks_per_fetch - 1);
+        let trace = TraceContext::new_root();
+        let span = trace.fetch_span(&network.slug, start_block, end_block);
+        let blocks = config
+            .retry
+            .retry(|| client.get_blocks(start_block, Some(end_block)))
+            .instrument(span.clone())
+            .await?;
+
+        if !blocks.is_empty() {
+            replayed += blocks.len();
+            let event = BlockEvent {
+                network: network.clone(),
+                address_bloom: block_address_bloom(&blocks),
+                blocks,
+                timestamp: chrono::Utc::now(),
+                latest_block: Some(latest_block),
+                replay: true,
+                tenant_ids: tenant_ids.to_vec(),
+                trace: Some(trace),
+            };
+            broadcast_event(publisher, config, event, None)
+                .instrument(span)
+                .await;
+        }
+
+        start_block = end_block + 1;
+    }
+
+    Ok(replayed)
+}
+
+/// Broadcast a block event, holding it until the slowest subscriber in this
+/// process has room if backpressure is enabled. Returns whether the event was
+/// left in the publish buffer instead.
+///
+/// `cursor` is the network's last published block and the event's last
+/// block, None for replays.
+async fn broadcast_event(
+    publisher: Publisher<'_>,
+    config: &SharedBlockWatcherConfig,
+    event: BlockEvent,
+    cursor: Option<(u64, u64)>,
+) -> bool {
+    if config.backpressure {
+        while publisher.events.channels().pending_blocks() >= config.channel_buffer_size {
+            debug!(
+                "Waiting for subscribers to drain block events on network {}",
+                event.network.slug
+            );
+            tokio::time::sleep(BACKPRESSURE_POLL_INTERVAL).await;
+        }
+    }
+
+    let block_count = event.blocks.len();
+    let network_slug = event.network.slug.clone();
+    let kind = if event.replay { "replayed" } else { "new" };
+    let result = match publisher.buffer {
+        Some(buffer) => buffer.publish(event, cursor).await,
+        None => publisher
+            .events
+            .publish(Event::Block(event))
+            .await
+            .map(Publication::Published),
+    };
+    match result {
+        Ok(Publication::Buffered) => {
+            debug!(
+                "Buffered {} {} blocks for network {} until the event bus is reachable",
+                block_count, kind, network_slug
             );
+            return true;
         }
-        Err(_) => {
+        Ok(Publication::Published(0)) => {
             warn!(
                 "No subscribers for block events on network {}",
-                network.slug
+                network_slug
+            );
+        }
+        Ok(Publication::Published(receiver_count)) => {
+            info!(
+                "Broadcast {} {} blocks for network {} to {} subscribers",
+                block_count, kind, network_slug, receiver_count
+            );
+        }
+        Err(e) => {
+            error!(
+                "Failed to publish {} blocks for network {}: {}",
+                kind, network_slug, e
             );
         }
     }
+    false
+}
 
-    // Update last processed block
-    {
-        let mut networks_lock = networks.write().await;
-        if let Some(state) = networks_lock.get_mut(&network.slug) {
-            state.last_processed_block = end_block;
+/// Record the outcome of a fetch cycle for the network's RPC health
+async fn record_poll(
+    networks: &RwLock<HashMap<String, NetworkWatcherState>>,
+    network_slug: &str,
+    result: &Result<usize>,
+) {
+    let mut networks = networks.write().await;
+    let Some(state) = networks.get_mut(network_slug) else {
+        return;
+    };
+
+    state.last_polled_at = Some(chrono::Utc::now());
+    match result {
+        Ok(_) => {
+            state.consecutive_failures = 0;
+            state.last_error = None;
+        }
+        Err(e) => {
+            state.consecutive_failures += 1;
+            state.last_error = Some(e.to_string());
         }
     }
+}
 
-    Ok(blocks.len())
+/// Build an address bloom over the transaction senders and recipients of the blocks.
+///
+/// Only EVM blocks are summarized, since worker matching is keyed on the
+/// transaction recipient there; Stellar blocks are always routed in full.
+fn block_address_bloom(blocks: &[BlockType]) -> Option<AddressBloom> {
+    let mut bloom = AddressBloom::new();
+
+    for block in blocks {
+        match block {
+            BlockType::EVM(evm_block) => {
+                for transaction in &evm_block.transactions {
+                    if let Some(to) = &transaction.to {
+                        bloom.insert(&format!("{:?}", to));
+                    }
+                    if let Some(from) = &transaction.from {
+                        bloom.insert(&format!("{:?}", from));
+                    }
+                }
+            }
+            BlockType::Stellar(_) => return None,
+        }
+    }
+
+    Some(bloom)
 }
 
-/// Calculate sleep duration based on network configuration
-fn calculate_sleep_duration(network: &Network) -> std::time::Duration {
-    // Parse cron schedule to determine interval
-    // For now, use a simple default based on network type
-    match network.network_type {
+/// Interval between polls of a network. The network's `cron_schedule` is
+/// not used; the interval follows typical block times of its chain type
+pub(crate) fn poll_interval(
+    network_type: &openzeppelin_monitor::models::BlockChainType,
+) -> std::time::Duration {
+    match network_type {
         openzeppelin_monitor::models::BlockChainType::EVM => {
             // Most EVM chains have ~12-15 second block times
             std::time::Duration::from_secs(15)
@@ -478,36 +1178,3 @@ fn calculate_sleep_duration(network: &Network) -> std::time::Duration {
         _ => std::time::Duration::from_secs(30),
     }
 }
-
-/// Retry a future with exponential backoff
-async fn retry_with_backoff<F, Fut, T, E>(
-    mut f: F,
-    max_attempts: u32,
-    base_delay_ms: u64,
-) -> Result<T>
-where
-    F: FnMut() -> Fut,
-    Fut: std::future::Future<Output = Result<T, E>>,
-    E: std::fmt::Display,
-{
-    let mut attempt = 0;
-    loop {
-        match f().await {
-            Ok(result) => return Ok(result),
-            Err(e) => {
-                attempt += 1;
-                if attempt >= max_attempts {
-                    return Err(anyhow::anyhow!(
-                        "Failed after {} attempts: {}",
-                        max_attempts,
-                        e
-                    ));
-                }
-
-                let delay = base_delay_ms * 2u64.pow(attempt - 1);
-                warn!("Attempt {} failed: {}, retrying in {}ms", attempt, e, delay);
-                tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
-            }
-        }
-    }
-}
@@ -0,0 +1,207 @@
+//! Shutdown Signal
+//!
+//! Process-wide shutdown notification shared by the API server, its
+//! streaming endpoints, the block watcher, workers and background loops. The
+//! orchestrator triggers it once on Ctrl+C or SIGTERM; every component holds
+//! a child of the process signal, so all of them observe the same trigger
+//! while one can still be stopped on its own.
+//!
+//! A [`ShutdownSequence`] then waits for the components to finish in order,
+//! the API draining first, then workers finishing the block they are on, then
+//! the block watcher handing off its lease, all within one grace period.
+//! Stages still running when it ends are abandoned, but cleanup added with
+//! [`ShutdownSequence::finally`] runs in any case.
+
+use futures::future::BoxFuture;
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
+
+/// Cloneable handle to the process shutdown signal
+#[derive(Debug, Clone)]
+pub struct ShutdownSignal {
+    token: CancellationToken,
+}
+
+impl Default for ShutdownSignal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShutdownSignal {
+    /// Create a signal that has not been triggered
+    pub fn new() -> Self {
+        Self {
+            token: CancellationToken::new(),
+        }
+    }
+
+    /// Signal triggered together with this one, or on its own without
+    /// affecting this one
+    pub fn child(&self) -> Self {
+        Self {
+            token: self.token.child_token(),
+        }
+    }
+
+    /// Start shutting down; later calls have no effect
+    pub fn trigger(&self) {
+        self.token.cancel();
+    }
+
+    /// Whether shutdown has started
+    pub fn is_triggered(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Wait until shutdown starts, returning at once if it already has
+    pub async fn wait(&self) {
+        self.token.cancelled().await;
+    }
+
+    /// Trigger shutdown once the process receives Ctrl+C or SIGTERM
+    pub fn trigger_on_signal(&self) -> tokio::task::JoinHandle<()> {
+        let signal = self.clone();
+        tokio::spawn(async move {
+            crate::orchestrator::wait_for_shutdown().await;
+            signal.trigger();
+        })
+    }
+}
+
+/// Longest a [`ShutdownSequence::finally`] stage may take
+const FINALLY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Ordered stop of a service mode's components within a grace period
+pub struct ShutdownSequence<'a> {
+    grace_period: Duration,
+    stages: Vec<(&'static str, BoxFuture<'a, ()>)>,
+    finally: Vec<(&'static str, BoxFuture<'a, ()>)>,
+}
+
+impl<'a> ShutdownSequence<'a> {
+    /// Create a sequence whose stages must all finish within `grace_period`
+    pub fn new(grace_period: Duration) -> Self {
+        Self {
+            grace_period,
+            stages: Vec::new(),
+            finally: Vec::new(),
+        }
+    }
+
+    /// Add a stage run after the stages added before it
+    pub fn stage(mut self, name: &'static str, stop: impl Future<Output = ()> + Send + 'a) -> Self {
+        self.stages.push((name, Box::pin(stop)));
+        self
+    }
+
+    /// Add a stage run after all others even when the grace period ran out,
+    /// within [`FINALLY_TIMEOUT`] of its own once it has
+    pub fn finally(
+        mut self,
+        name: &'static str,
+        stop: impl Future<Output = ()> + Send + 'a,
+    ) -> Self {
+        self.finally.push((name, Box::pin(stop)));
+        self
+    }
+
+    /// Run the stages in order, returning whether all of them finished
+    /// within the grace period. A stage still running when it ends is
+    /// abandoned, and the stages after it are skipped, except the
+    /// [`ShutdownSequence::finally`] stages.
+    pub async fn run(self) -> bool {
+        let deadline = Instant::now() + self.grace_period;
+        let mut finished = true;
+        let mut stages = self.stages.into_iter();
+        while let Some((name, stop)) = stages.next() {
+            if tokio::time::timeout_at(deadline, stop).await.is_err() {
+                let skipped: Vec<&str> = stages.map(|(name, _)| name).collect();
+                warn!(
+                    "Shutdown stage {} did not finish within the {:?} grace period, skipping {:?}",
+                    name, self.grace_period, skipped
+                );
+                finished = false;
+                break;
+            }
+            info!("Shutdown stage {} finished", name);
+        }
+
+        for (name, stop) in self.finally {
+            let deadline = deadline.max(Instant::now() + FINALLY_TIMEOUT);
+            if tokio::time::timeout_at(deadline, stop).await.is_err() {
+                warn!("Shutdown stage {} did not finish, abandoning it", name);
+                finished = false;
+                continue;
+            }
+            info!("Shutdown stage {} finished", name);
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[tokio::test]
+    async fn test_all_handles_observe_trigger() {
+        let signal = ShutdownSignal::new();
+        let handle = signal.clone();
+        assert!(!handle.is_triggered());
+
+        let waiter = tokio::spawn(async move { handle.wait().await });
+        signal.trigger();
+        waiter.await.unwrap();
+
+        assert!(signal.is_triggered());
+        // Waiting after the trigger returns immediately
+        signal.wait().await;
+    }
+
+    #[tokio::test]
+    async fn test_children_stop_alone_or_with_their_parent() {
+        let signal = ShutdownSignal::new();
+        let watcher = signal.child();
+        let workers = signal.child();
+
+        watcher.trigger();
+        assert!(!signal.is_triggered());
+        assert!(!workers.is_triggered());
+
+        signal.trigger();
+        workers.wait().await;
+    }
+
+    #[tokio::test]
+    async fn test_sequence_runs_stages_in_order_until_the_grace_period_ends() {
+        let stopped = Arc::new(Mutex::new(Vec::new()));
+        let stop = |name: &'static str, delay: Duration| {
+            let stopped = stopped.clone();
+            async move {
+                tokio::time::sleep(delay).await;
+                stopped.lock().unwrap().push(name);
+            }
+        };
+
+        let finished = ShutdownSequence::new(Duration::from_millis(200))
+            .stage("api", stop("api", Duration::from_millis(20)))
+            .stage("workers", stop("workers", Duration::from_millis(10)))
+            .stage(
+                "block watcher",
+                stop("block watcher", Duration::from_secs(30)),
+            )
+            .stage("metrics", stop("metrics", Duration::ZERO))
+            .finally("cleanup", stop("cleanup", Duration::ZERO))
+            .run()
+            .await;
+
+        // Cleanup still runs after the grace period ran out
+        assert!(!finished);
+        assert_eq!(*stopped.lock().unwrap(), vec!["api", "workers", "cleanup"]);
+    }
+}
@@ -0,0 +1,195 @@
+//! Spill Buffer
+//!
+//! Bounded in-memory queue that spills to a local JSON lines file once full,
+//! so a worker that falls behind keeps every block event and replays them in
+//! order after catching up instead of lagging off the broadcast channel.
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{Mutex, Notify};
+use tracing::{info, warn};
+
+/// FIFO queue that overflows to disk
+pub struct SpillBuffer<T> {
+    state: Mutex<SpillState<T>>,
+    notify: Notify,
+    path: PathBuf,
+    memory_capacity: usize,
+}
+
+struct SpillState<T> {
+    /// Events held in memory, always older than any spilled event
+    memory: VecDeque<T>,
+    /// Number of events on disk not yet replayed
+    spilled: usize,
+    /// Append handle for the spill file
+    writer: Option<File>,
+    /// Sequential reader over the spill file
+    reader: Option<BufReader<File>>,
+    /// Set once the producer is gone
+    closed: bool,
+}
+
+impl<T: Serialize + DeserializeOwned> SpillBuffer<T> {
+    /// Create a buffer spilling to `<dir>/<name>.spill.jsonl`.
+    ///
+    /// Any leftover spill file from a previous run is discarded.
+    pub async fn new(dir: &Path, name: &str, memory_capacity: usize) -> Result<Self> {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("Failed to create spill directory {}", dir.display()))?;
+
+        let path = dir.join(format!("{}.spill.jsonl", name));
+        if tokio::fs::try_exists(&path).await? {
+            warn!("Discarding stale spill file {}", path.display());
+            tokio::fs::remove_file(&path).await?;
+        }
+
+        Ok(Self {
+            state: Mutex::new(SpillState {
+                memory: VecDeque::new(),
+                spilled: 0,
+                writer: None,
+                reader: None,
+                closed: false,
+            }),
+            notify: Notify::new(),
+            path,
+            memory_capacity,
+        })
+    }
+
+    /// Enqueue an item, spilling to disk once the memory queue is full
+    pub async fn push(&self, item: T) -> Result<()> {
+        let mut state = self.state.lock().await;
+
+        // Once spilling, everything goes to disk until it is replayed to keep order
+        if state.spilled == 0 && state.memory.len() < self.memory_capacity {
+            state.memory.push_back(item);
+        } else {
+            if state.writer.is_none() {
+                info!(
+                    "Memory queue full ({} events), spilling to {}",
+                    self.memory_capacity,
+                    self.path.display()
+                );
+                let writer = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .await?;
+                let reader = File::open(&self.path).await?;
+                state.writer = Some(writer);
+                state.reader = Some(BufReader::new(reader));
+            }
+
+            let mut line = serde_json::to_vec(&item)?;
+            line.push(b'\n');
+            let writer = state.writer.as_mut().expect("spill writer is open");
+            writer.write_all(&line).await?;
+            writer.flush().await?;
+            state.spilled += 1;
+        }
+
+        drop(state);
+        self.notify.notify_one();
+        Ok(())
+    }
+
+    /// Dequeue the oldest item, waiting until one is available.
+    ///
+    /// Returns None once the buffer is closed and fully drained.
+    pub async fn recv(&self) -> Result<Option<T>> {
+        loop {
+            let notified = self.notify.notified();
+            {
+                let mut state = self.state.lock().await;
+                if let Some(item) = state.memory.pop_front() {
+                    return Ok(Some(item));
+                }
+                if state.spilled > 0 {
+                    return self.replay_next(&mut state).await.map(Some);
+                }
+                if state.closed {
+                    return Ok(None);
+                }
+            }
+            notified.await;
+        }
+    }
+
+    /// Mark the producer as finished; remaining items can still be received
+    pub async fn close(&self) {
+        self.state.lock().await.closed = true;
+        self.notify.notify_waiters();
+    }
+
+    /// Number of items waiting in memory and on disk
+    pub async fn len(&self) -> usize {
+        let state = self.state.lock().await;
+        state.memory.len() + state.spilled
+    }
+
+    /// Check if no items are waiting
+    pub async fn is_empty(&self) -> bool {
+        self.len().await == 0
+    }
+
+    /// Number of items waiting on disk
+    pub async fn spilled(&self) -> usize {
+        self.state.lock().await.spilled
+    }
+
+    /// Read the next spilled item, removing the spill file once drained
+    async fn replay_next(&self, state: &mut SpillState<T>) -> Result<T> {
+        let reader = state.reader.as_mut().expect("spill reader is open");
+        let mut line = String::new();
+        reader.read_line(&mut line).await?;
+        let item = serde_json::from_str(&line).context("Corrupt spill file entry")?;
+        state.spilled -= 1;
+
+        if state.spilled == 0 {
+            state.writer = None;
+            state.reader = None;
+            tokio::fs::remove_file(&self.path).await?;
+            info!("Replayed all spilled events from {}", self.path.display());
+        }
+
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_spilled_items_replay_in_order() {
+        let dir = std::env::temp_dir().join(format!("spill-test-{}", uuid::Uuid::new_v4()));
+        let buffer = SpillBuffer::<u64>::new(&dir, "worker", 2).await.unwrap();
+
+        for i in 0..5 {
+            buffer.push(i).await.unwrap();
+        }
+        assert_eq!(buffer.spilled().await, 3);
+
+        // New items queue behind the spilled ones while replaying
+        assert_eq!(buffer.recv().await.unwrap(), Some(0));
+        assert_eq!(buffer.recv().await.unwrap(), Some(1));
+        buffer.push(5).await.unwrap();
+        buffer.close().await;
+
+        let mut rest = Vec::new();
+        while let Some(item) = buffer.recv().await.unwrap() {
+            rest.push(item);
+        }
+        assert_eq!(rest, vec![2, 3, 4, 5]);
+        assert!(!dir.join("worker.spill.jsonl").exists());
+
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,634 @@
+//! Startup Checks
+//!
+//! Verifies the dependencies of a service mode before it starts, so a
+//! misconfigured deployment fails at boot with a report naming what is wrong
+//! and how to fix it instead of with obscure errors once blocks arrive. The
+//! database schema is compared against the tables and columns each migration
+//! in `sql/migrations` adds, Redis is asked whether the commands the
+//! orchestrator uses are available (managed Redis offerings may rename or
+//! disable some), and the RPC endpoints of every network with active monitors
+//! are asked for their latest block.
+
+use anyhow::{bail, Context, Result};
+use futures::future::join_all;
+use openzeppelin_monitor::models::{BlockChainType, Network};
+use openzeppelin_monitor::repositories::NetworkRepositoryTrait;
+use openzeppelin_monitor::services::blockchain::{BlockChainClient, ClientPoolTrait};
+use sqlx::PgPool;
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config::ServiceMode;
+use crate::repositories::TenantAwareNetworkRepository;
+use crate::services::block_cache::BlockCacheService;
+use crate::services::cached_client_pool::CachedClientPool;
+use crate::services::chain_support::ChainSupport;
+
+/// Tables and columns each schema migration adds, oldest first. `base` is the
+/// schema of the tenant isolation service the orchestrator reads tenants from.
+const SCHEMA_MIGRATIONS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "base",
+        &[
+            ("tenants", "id"),
+            ("tenant_monitors", "configuration"),
+            ("tenant_networks", "configuration"),
+            ("tenant_triggers", "configuration"),
+        ],
+    ),
+    (
+        "001_notification_schedules",
+        &[
+            ("tenant_quiet_hours", "tenant_id"),
+            ("held_notifications", "tenant_id"),
+        ],
+    ),
+    (
+        "002_filter_debug_samples",
+        &[
+            ("tenant_filter_debug", "tenant_id"),
+            ("filter_debug_samples", "tenant_id"),
+        ],
+    ),
+    (
+        "003_tenant_rpc_caps",
+        &[
+            ("tenant_monitors", "is_critical"),
+            ("tenant_rpc_cap_events", "tenant_id"),
+        ],
+    ),
+    (
+        "004_tenant_confirmation_depth",
+        &[("tenant_networks", "confirmation_blocks")],
+    ),
+    (
+        "005_monitor_match_lifecycle",
+        &[
+            ("monitor_matches", "tenant_id"),
+            ("tenant_networks", "trigger_on_states"),
+        ],
+    ),
+    (
+        "006_monitor_auto_deactivation",
+        &[
+            ("tenant_monitors", "error_message"),
+            ("tenant_monitor_deactivations", "tenant_id"),
+        ],
+    ),
+    (
+        "007_trigger_dead_letters",
+        &[("trigger_dead_letters", "tenant_id")],
+    ),
+    ("008_tenant_rpc_usage", &[("tenant_rpc_usage", "tenant_id")]),
+    (
+        "009_tenant_activity_anomalies",
+        &[
+            ("tenant_metrics_history", "tenant_id"),
+            ("tenant_activity_anomalies", "tenant_id"),
+        ],
+    ),
+    (
+        "010_tenant_block_checkpoints",
+        &[("tenant_block_checkpoints", "tenant_id")],
+    ),
+    (
+        "011_monitor_address_index",
+        &[("tenant_monitor_addresses", "address")],
+    ),
+    (
+        "012_trigger_script_management",
+        &[("trigger_scripts", "language")],
+    ),
+    (
+        "013_orchestrator_audit_log",
+        &[("orchestrator_audit_log", "payload_hash")],
+    ),
+    (
+        "014_tenant_match_webhooks",
+        &[("tenant_match_webhooks", "secret")],
+    ),
+    ("015_tenant_pauses", &[("tenant_pauses", "paused_at")]),
+    (
+        "016_tenant_assignments",
+        &[("tenant_assignments", "version")],
+    ),
+    ("017_tenant_trials", &[("tenants", "is_trial")]),
+    (
+        "018_tenant_block_watermarks",
+        &[("tenant_block_checkpoints", "watermark_block")],
+    ),
+    (
+        "019_tenant_assignment_history",
+        &[("tenant_assignment_history", "version")],
+    ),
+    (
+        "020_match_severity_routing",
+        &[
+            ("tenant_severity_rules", "script_name"),
+            ("tenant_severity_routes", "max_notifications_per_minute"),
+        ],
+    ),
+];
+
+/// Redis commands the block cache, locks, assignment store and pub/sub
+/// channels send
+const REDIS_COMMANDS: &[&str] = &[
+    "get",
+    "set",
+    "setex",
+    "mget",
+    "del",
+    "hset",
+    "hget",
+    "hgetall",
+    "hdel",
+    "zadd",
+    "zrem",
+    "zrangebyscore",
+    "sadd",
+    "srem",
+    "smembers",
+    "publish",
+    "subscribe",
+    "evalsha",
+    "script",
+    "multi",
+    "exec",
+];
+
+/// Outcome of one startup check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Passed,
+    /// Reported but does not stop the service from starting
+    Warning,
+    Failed,
+}
+
+/// Result of one startup check
+#[derive(Debug, Clone)]
+pub struct StartupCheck {
+    pub name: String,
+    pub status: CheckStatus,
+    pub detail: String,
+    /// How to fix a warning or failure
+    pub hint: Option<String>,
+}
+
+impl StartupCheck {
+    fn passed(name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Passed,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn failed(name: impl Into<String>, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: CheckStatus::Failed,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn warning(
+        name: impl Into<String>,
+        detail: impl Into<String>,
+        hint: impl Into<String>,
+    ) -> Self {
+        Self {
+            status: CheckStatus::Warning,
+            ..Self::failed(name, detail, hint)
+        }
+    }
+}
+
+/// Results of all startup checks of a service mode
+#[derive(Debug, Clone, Default)]
+pub struct StartupReport {
+    pub checks: Vec<StartupCheck>,
+}
+
+impl StartupReport {
+    /// Whether no check failed; warnings do not count
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status != CheckStatus::Failed)
+    }
+}
+
+impl fmt::Display for StartupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for check in &self.checks {
+            let status = match check.status {
+                CheckStatus::Passed => "ok",
+                CheckStatus::Warning => "warn",
+                CheckStatus::Failed => "FAIL",
+            };
+            writeln!(f, "  [{:>4}] {}: {}", status, check.name, check.detail)?;
+            if let Some(hint) = &check.hint {
+                writeln!(f, "         fix: {}", hint)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Limits of the startup checks
+#[derive(Debug, Clone)]
+pub struct StartupCheckOptions {
+    /// How long each check may take
+    pub timeout: Duration,
+    /// Fail instead of warn when a network's RPC endpoints are unreachable
+    pub require_rpc: bool,
+}
+
+impl Default for StartupCheckOptions {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            require_rpc: false,
+        }
+    }
+}
+
+/// Checks the database, Redis and RPC endpoints a service mode depends on
+pub struct StartupChecks {
+    db: Arc<PgPool>,
+    cache: Arc<BlockCacheService>,
+    client_pool: Arc<CachedClientPool>,
+    chains: ChainSupport,
+    options: StartupCheckOptions,
+}
+
+impl StartupChecks {
+    /// Create checks of the given dependencies
+    pub fn new(
+        db: Arc<PgPool>,
+        cache: Arc<BlockCacheService>,
+        client_pool: Arc<CachedClientPool>,
+        options: StartupCheckOptions,
+    ) -> Self {
+        Self {
+            db,
+            cache,
+            client_pool,
+            chains: ChainSupport::default(),
+            options,
+        }
+    }
+
+    /// Only probe networks of the chain types enabled in the deployment
+    pub fn with_chain_support(mut self, chains: ChainSupport) -> Self {
+        self.chains = chains;
+        self
+    }
+
+    /// Run the checks the service mode needs
+    pub async fn run(&self, mode: &ServiceMode) -> StartupReport {
+        let mut report = StartupReport::default();
+
+        let database = self.check_database().await;
+        let database_ok = database.status == CheckStatus::Passed;
+        report.checks.push(database);
+        if database_ok {
+            report.checks.push(self.check_schema().await);
+        }
+        report.checks.push(self.check_redis().await);
+
+        if database_ok
+            && matches!(
+                mode,
+                ServiceMode::Worker | ServiceMode::BlockWatcher | ServiceMode::All
+            )
+        {
+            report.checks.extend(self.check_rpc().await);
+        }
+        report
+    }
+
+    async fn check_database(&self) -> StartupCheck {
+        let result = self
+            .timed(async {
+                sqlx::query("SELECT 1").execute(&*self.db).await?;
+                Ok(())
+            })
+            .await;
+        match result {
+            Ok(()) => StartupCheck::passed("database", "connected"),
+            Err(e) => StartupCheck::failed(
+                "database",
+                format!("{:#}", e),
+                "check database_url and that Postgres accepts connections from this host",
+            ),
+        }
+    }
+
+    async fn check_schema(&self) -> StartupCheck {
+        let columns = self
+            .timed(async {
+                let rows = sqlx::query_as::<_, (String, String)>(
+                    r#"
+                    SELECT table_name::TEXT, column_name::TEXT
+                    FROM information_schema.columns
+                    WHERE table_schema = ANY(current_schemas(false))
+                    "#,
+                )
+                .fetch_all(&*self.db)
+                .await?;
+                Ok(rows.into_iter().collect::<HashSet<_>>())
+            })
+            .await;
+        let columns = match columns {
+            Ok(columns) => columns,
+            Err(e) => {
+                return StartupCheck::failed(
+                    "schema",
+                    format!("failed to read the schema: {:#}", e),
+                    "grant the database user access to information_schema",
+                )
+            }
+        };
+
+        let status = SchemaStatus::of(&columns);
+        if status.missing.is_empty() {
+            return StartupCheck::passed(
+                "schema",
+                format!("at {}", status.version.unwrap_or("base")),
+            );
+        }
+
+        let missing = status
+            .missing
+            .iter()
+            .map(|(migration, column)| format!("{} ({})", column, migration))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if status.version.is_none() {
+            return StartupCheck::failed(
+                "schema",
+                format!("required tables are missing: {}", missing),
+                "point database_url at the tenant isolation database, then apply sql/migrations in order",
+            );
+        }
+        StartupCheck::failed(
+            "schema",
+            format!(
+                "schema is at {}, missing {}",
+                status.version.unwrap_or("base"),
+                missing
+            ),
+            format!(
+                "apply sql/migrations/{}.sql and every later migration in order",
+                status.missing[0].0
+            ),
+        )
+    }
+
+    async fn check_redis(&self) -> StartupCheck {
+        let redis = self.cache.redis_client();
+        let result = self
+            .timed(async {
+                let mut conn = redis.get_multiplexed_async_connection().await?;
+                redis::cmd("PING").query_async::<()>(&mut conn).await?;
+                // Unknown or disabled commands come back as nil
+                let info = redis::cmd("COMMAND")
+                    .arg("INFO")
+                    .arg(REDIS_COMMANDS)
+                    .query_async::<Vec<redis::Value>>(&mut conn)
+                    .await;
+                Ok(info.map(|info| {
+                    REDIS_COMMANDS
+                        .iter()
+                        .zip(info)
+                        .filter(|(_, info)| matches!(info, redis::Value::Nil))
+                        .map(|(command, _)| command.to_uppercase())
+                        .collect::<Vec<_>>()
+                }))
+            })
+            .await;
+
+        match result {
+            Ok(Ok(missing)) if missing.is_empty() => {
+                StartupCheck::passed("redis", "connected, all required commands available")
+            }
+            Ok(Ok(missing)) => StartupCheck::failed(
+                "redis",
+                format!("commands unavailable: {}", missing.join(", ")),
+                "enable these commands on the Redis server (check rename-command and ACL rules)",
+            ),
+            Ok(Err(e)) => StartupCheck::warning(
+                "redis",
+                format!(
+                    "connected, but COMMAND INFO failed so commands were not verified: {}",
+                    e
+                ),
+                "allow the COMMAND command to have availability checked at startup",
+            ),
+            Err(e) => StartupCheck::failed(
+                "redis",
+                format!("{:#}", e),
+                "check redis_url and that Redis accepts connections from this host",
+            ),
+        }
+    }
+
+    /// Probe the RPC endpoints of every network with active monitors
+    async fn check_rpc(&self) -> Vec<StartupCheck> {
+        let networks = match self.timed(self.active_networks()).await {
+            Ok(networks) => networks,
+            Err(e) => {
+                return vec![StartupCheck::failed(
+                    "rpc",
+                    format!("failed to load active networks: {:#}", e),
+                    "check the tenant_networks and tenant_monitors tables",
+                )]
+            }
+        };
+
+        let probes = networks.iter().map(|network| async move {
+            let name = format!("rpc:{}", network.slug);
+            match self.timed(self.latest_block(network)).await {
+                Ok(block) => StartupCheck::passed(name, format!("latest block {}", block)),
+                Err(e) => {
+                    let detail = format!("{:#}", e);
+                    let hint = "check the network's rpc_urls and that the endpoints are reachable from this host";
+                    if self.options.require_rpc {
+                        StartupCheck::failed(name, detail, hint)
+                    } else {
+                        StartupCheck::warning(name, detail, hint)
+                    }
+                }
+            }
+        });
+        join_all(probes).await
+    }
+
+    /// Networks with active monitors whose chain type is enabled, by slug
+    async fn active_networks(&self) -> Result<Vec<Network>> {
+        let rows = sqlx::query_as::<_, (Uuid, String)>(
+            r#"
+            SELECT DISTINCT m.tenant_id, n.network_id
+            FROM tenant_monitors m
+            JOIN tenant_networks n ON n.id = m.network_id
+            WHERE m.is_active AND n.is_active
+            "#,
+        )
+        .fetch_all(&*self.db)
+        .await?;
+        let tenant_ids: Vec<Uuid> = rows
+            .iter()
+            .map(|(tenant_id, _)| *tenant_id)
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect();
+        let slugs: HashSet<String> = rows.into_iter().map(|(_, slug)| slug).collect();
+
+        let repo = TenantAwareNetworkRepository::new(self.db.clone(), tenant_ids);
+        let mut networks: Vec<Network> = repo
+            .get_all()
+            .into_values()
+            .filter(|network| slugs.contains(&network.slug))
+            .filter(|network| self.chains.check(&network.network_type).is_ok())
+            .collect();
+        networks.sort_by(|a, b| a.slug.cmp(&b.slug));
+        Ok(networks)
+    }
+
+    async fn latest_block(&self, network: &Network) -> Result<u64> {
+        match network.network_type {
+            BlockChainType::EVM => {
+                let client = self
+                    .client_pool
+                    .get_evm_client(network)
+                    .await
+                    .context("Failed to create EVM client")?;
+                client.get_latest_block_number().await
+            }
+            BlockChainType::Stellar => {
+                let client = self
+                    .client_pool
+                    .get_stellar_client(network)
+                    .await
+                    .context("Failed to create Stellar client")?;
+                client.get_latest_block_number().await
+            }
+            _ => bail!("chain type is not supported"),
+        }
+    }
+
+    async fn timed<T>(&self, check: impl Future<Output = Result<T>>) -> Result<T> {
+        tokio::time::timeout(self.options.timeout, check)
+            .await
+            .with_context(|| format!("Timed out after {:?}", self.options.timeout))?
+    }
+}
+
+/// Latest schema migration applied and the tables and columns missing
+#[derive(Debug, PartialEq)]
+struct SchemaStatus {
+    /// Last migration that is applied along with every earlier one; None if
+    /// the base schema is incomplete
+    version: Option<&'static str>,
+    /// Missing `table.column`s with the migration adding them, oldest first
+    missing: Vec<(&'static str, String)>,
+}
+
+impl SchemaStatus {
+    fn of(columns: &HashSet<(String, String)>) -> Self {
+        let mut version = None;
+        let mut complete = true;
+        let mut missing = Vec::new();
+
+        for (migration, required) in SCHEMA_MIGRATIONS {
+            let before = missing.len();
+            missing.extend(
+                required
+                    .iter()
+                    .filter(|(table, column)| {
+                        !columns.contains(&(table.to_string(), column.to_string()))
+                    })
+                    .map(|(table, column)| (*migration, format!("{}.{}", table, column))),
+            );
+            complete &= missing.len() == before;
+            if complete {
+                version = Some(*migration);
+            }
+        }
+
+        Self { version, missing }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns_up_to(last: &str) -> HashSet<(String, String)> {
+        let mut columns = HashSet::new();
+        for (migration, required) in SCHEMA_MIGRATIONS {
+            columns.extend(
+                required
+                    .iter()
+                    .map(|(table, column)| (table.to_string(), column.to_string())),
+            );
+            if *migration == last {
+                break;
+            }
+        }
+        columns
+    }
+
+    #[test]
+    fn test_schema_version_is_last_contiguous_migration() {
+        let latest = SCHEMA_MIGRATIONS.last().unwrap().0;
+        let status = SchemaStatus::of(&columns_up_to(latest));
+        assert_eq!(status.version, Some(latest));
+        assert!(status.missing.is_empty());
+
+        let mut columns = columns_up_to("008_tenant_rpc_usage");
+        columns.insert((
+            "tenant_block_checkpoints".to_string(),
+            "tenant_id".to_string(),
+        ));
+        let status = SchemaStatus::of(&columns);
+        assert_eq!(status.version, Some("008_tenant_rpc_usage"));
+        assert_eq!(status.missing[0].0, "009_tenant_activity_anomalies");
+        assert!(status
+            .missing
+            .iter()
+            .all(|(migration, _)| *migration != "010_tenant_block_checkpoints"));
+    }
+
+    #[test]
+    fn test_missing_base_tables_have_no_version() {
+        let status = SchemaStatus::of(&HashSet::new());
+        assert_eq!(status.version, None);
+        assert_eq!(status.missing[0], ("base", "tenants.id".to_string()));
+    }
+
+    #[test]
+    fn test_report_fails_only_on_failures() {
+        let mut report = StartupReport {
+            checks: vec![
+                StartupCheck::passed("database", "connected"),
+                StartupCheck::warning("rpc:sepolia", "timed out", "check rpc_urls"),
+            ],
+        };
+        assert!(report.passed());
+
+        report
+            .checks
+            .push(StartupCheck::failed("redis", "refused", "check redis_url"));
+        assert!(!report.passed());
+        assert!(report.to_string().contains("[FAIL] redis: refused"));
+    }
+}
@@ -0,0 +1,167 @@
+//! Stellar Event Prefilter
+//!
+//! When every monitor on a Stellar network only watches contract events of
+//! specific contracts, a single filtered Soroban `getEvents` call tells which
+//! ledgers in a range are worth processing. Ledgers without matching events
+//! are skipped instead of being fetched and filtered in full.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashSet;
+use tracing::debug;
+
+use openzeppelin_monitor::models::Network;
+
+/// Contract IDs accepted by a single `getEvents` filter
+const CONTRACTS_PER_FILTER: usize = 5;
+
+/// Filters accepted by a single `getEvents` request
+const FILTERS_PER_REQUEST: usize = 5;
+
+/// Events requested per page
+const PAGE_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct GetEventsResult {
+    events: Vec<RpcEvent>,
+    cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcEvent {
+    ledger: u64,
+}
+
+/// Finds ledgers containing events from specific contracts
+pub struct StellarEventFilter {
+    client: reqwest::Client,
+}
+
+impl Default for StellarEventFilter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StellarEventFilter {
+    /// Create a new event filter
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Ledgers in `start..=end` containing at least one event from the given contracts
+    pub async fn ledgers_with_events(
+        &self,
+        network: &Network,
+        start: u64,
+        end: u64,
+        contract_ids: &HashSet<String>,
+    ) -> Result<HashSet<u64>> {
+        let rpc_url = rpc_url(network)
+            .with_context(|| format!("No RPC URL configured for network {}", network.slug))?;
+
+        let contract_ids: Vec<&String> = contract_ids.iter().collect();
+        let mut ledgers = HashSet::new();
+
+        for batch in contract_ids.chunks(CONTRACTS_PER_FILTER * FILTERS_PER_REQUEST) {
+            let filters: Vec<_> = batch
+                .chunks(CONTRACTS_PER_FILTER)
+                .map(|ids| json!({ "type": "contract", "contractIds": ids }))
+                .collect();
+
+            let mut cursor: Option<String> = None;
+            loop {
+                // The RPC rejects a start ledger alongside a cursor; end is exclusive
+                let params = match &cursor {
+                    Some(cursor) => json!({
+                        "filters": filters,
+                        "pagination": { "cursor": cursor, "limit": PAGE_LIMIT },
+                    }),
+                    None => json!({
+                        "startLedger": start,
+                        "endLedger": end + 1,
+                        "filters": filters,
+                        "pagination": { "limit": PAGE_LIMIT },
+                    }),
+                };
+
+                let page = self.get_events(&rpc_url, params).await?;
+                let page_len = page.events.len();
+                let mut past_end = false;
+                for event in page.events {
+                    if event.ledger > end {
+                        past_end = true;
+                        break;
+                    }
+                    ledgers.insert(event.ledger);
+                }
+
+                match page.cursor {
+                    Some(next) if page_len == PAGE_LIMIT && !past_end => cursor = Some(next),
+                    _ => break,
+                }
+            }
+        }
+
+        debug!(
+            "Found contract events in {} of {} ledgers on network {}",
+            ledgers.len(),
+            end - start + 1,
+            network.slug
+        );
+        Ok(ledgers)
+    }
+
+    async fn get_events(
+        &self,
+        rpc_url: &str,
+        params: serde_json::Value,
+    ) -> Result<GetEventsResult> {
+        let response: RpcResponse<GetEventsResult> = self
+            .client
+            .post(rpc_url)
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "getEvents",
+                "params": params,
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        if let Some(error) = response.error {
+            anyhow::bail!("getEvents failed ({}): {}", error.code, error.message);
+        }
+        response
+            .result
+            .context("getEvents returned neither result nor error")
+    }
+}
+
+/// Highest-weighted RPC endpoint of the network
+fn rpc_url(network: &Network) -> Option<String> {
+    network
+        .rpc_urls
+        .iter()
+        .filter(|rpc_url| rpc_url.type_ == "rpc" && rpc_url.weight > 0)
+        .max_by_key(|rpc_url| rpc_url.weight)
+        .map(|rpc_url| rpc_url.url.as_ref().to_string())
+}
@@ -0,0 +1,174 @@
+//! Monitor Templates
+//!
+//! Platform-level catalog of monitor templates that tenants instantiate with
+//! parameters. Instantiation validates the rendered configuration against the
+//! OpenZeppelin Monitor models before writing the monitor and trigger rows.
+
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use openzeppelin_monitor::models::{Monitor, Trigger};
+
+use crate::models::{builtin_templates, MonitorTemplate};
+
+/// Available templates keyed by identifier
+#[derive(Debug, Clone)]
+pub struct TemplateCatalog {
+    templates: BTreeMap<String, MonitorTemplate>,
+}
+
+impl Default for TemplateCatalog {
+    fn default() -> Self {
+        builtin_templates()
+            .into_iter()
+            .fold(Self::empty(), |catalog, template| {
+                catalog.register(template)
+            })
+    }
+}
+
+impl TemplateCatalog {
+    /// Create a catalog without the built-in templates
+    pub fn empty() -> Self {
+        Self {
+            templates: BTreeMap::new(),
+        }
+    }
+
+    /// Add or replace a template
+    pub fn register(mut self, template: MonitorTemplate) -> Self {
+        self.templates.insert(template.id.clone(), template);
+        self
+    }
+
+    /// Get a template by identifier
+    pub fn get(&self, id: &str) -> Option<&MonitorTemplate> {
+        self.templates.get(id)
+    }
+
+    /// List all templates ordered by identifier
+    pub fn list(&self) -> impl Iterator<Item = &MonitorTemplate> {
+        self.templates.values()
+    }
+}
+
+/// Rows created for an instantiated template
+#[derive(Debug, Clone)]
+pub struct InstantiatedTemplate {
+    /// `tenant_monitors.id` of the new monitor
+    pub monitor_id: Uuid,
+    /// `tenant_triggers.id` of the new trigger
+    pub trigger_id: Uuid,
+}
+
+/// Instantiates catalog templates into tenant monitor and trigger rows
+pub struct TemplateService {
+    db: Arc<PgPool>,
+    catalog: TemplateCatalog,
+}
+
+impl TemplateService {
+    /// Create a template service over the given catalog
+    pub fn new(db: Arc<PgPool>, catalog: TemplateCatalog) -> Self {
+        Self { db, catalog }
+    }
+
+    /// Get the template catalog
+    pub fn catalog(&self) -> &TemplateCatalog {
+        &self.catalog
+    }
+
+    /// Render a template for a tenant network and insert the resulting rows
+    pub async fn instantiate(
+        &self,
+        tenant_id: Uuid,
+        template_id: &str,
+        network_slug: &str,
+        params: &HashMap<String, String>,
+    ) -> Result<InstantiatedTemplate> {
+        let template = self
+            .catalog
+            .get(template_id)
+            .with_context(|| format!("Unknown monitor template {}", template_id))?;
+
+        let mut rendered = template.render(params)?;
+        rendered.monitor["networks"] = serde_json::json!([network_slug]);
+
+        // Reject anything OpenZeppelin Monitor would fail to load
+        let monitor: Monitor = serde_json::from_value(rendered.monitor.clone())
+            .context("Template produced an invalid monitor")?;
+        let trigger: Trigger = serde_json::from_value(rendered.trigger.clone())
+            .context("Template produced an invalid trigger")?;
+        let trigger_type = rendered.trigger["trigger_type"]
+            .as_str()
+            .context("Template trigger has no trigger_type")?
+            .to_string();
+
+        let mut tx = self.db.begin().await?;
+
+        let network_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT id FROM tenant_networks
+            WHERE tenant_id = $1 AND network_id = $2 AND is_active = true
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(network_slug)
+        .fetch_optional(&mut *tx)
+        .await?
+        .with_context(|| {
+            format!(
+                "Tenant {} has no active network {}",
+                tenant_id, network_slug
+            )
+        })?;
+
+        let monitor_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO tenant_monitors (tenant_id, monitor_id, name, network_id, configuration, is_active)
+            VALUES ($1, $2, $3, $4, $5, true)
+            RETURNING id
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(&monitor.name)
+        .bind(&monitor.name)
+        .bind(network_id)
+        .bind(&rendered.monitor)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to insert monitor")?;
+
+        let trigger_id = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            INSERT INTO tenant_triggers (tenant_id, trigger_id, monitor_id, name, type, configuration, is_active)
+            VALUES ($1, $2, $3, $4, $5, $6, true)
+            RETURNING id
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(&trigger.name)
+        .bind(monitor_id)
+        .bind(&trigger.name)
+        .bind(&trigger_type)
+        .bind(&rendered.trigger)
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to insert trigger")?;
+
+        tx.commit().await?;
+
+        info!(
+            "Instantiated template {} as monitor {} for tenant {}",
+            template_id, monitor.name, tenant_id
+        );
+        Ok(InstantiatedTemplate {
+            monitor_id,
+            trigger_id,
+        })
+    }
+}
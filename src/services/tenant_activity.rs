@@ -0,0 +1,273 @@
+//! Tenant Activity
+//!
+//! Tracks the activity of the tenants a worker processes over a rolling hour:
+//! RPC calls charged for filter runs, matches found and notifications sent,
+//! together with the size and complexity of the tenant's monitors. Every
+//! interval the worker turns this into [`TenantMetrics`] for its load
+//! balancer and publishes them next to its heartbeat, so coordinators and
+//! API processes see the metrics of tenants processed elsewhere. Tenants the
+//! worker has not processed for an hour are dropped.
+
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use dashmap::DashMap;
+use openzeppelin_monitor::models::Monitor;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::models::TenantMetrics;
+use crate::services::assignment_store::AssignmentStore;
+use crate::services::load_balancer::LoadBalancer;
+
+/// Minutes of activity kept per tenant
+const WINDOW_MINUTES: i64 = 60;
+
+/// Counts of one minute of a tenant's activity
+#[derive(Debug, Clone, Copy, Default)]
+struct MinuteCounts {
+    /// Minutes since the Unix epoch
+    minute: i64,
+    rpc_calls: u64,
+    matches: u64,
+    notifications: u64,
+}
+
+/// Activity of one tenant over the last hour
+#[derive(Debug)]
+struct ActivityWindow {
+    monitors_count: usize,
+    filter_complexity: f64,
+    /// Per-minute counts, oldest first
+    minutes: VecDeque<MinuteCounts>,
+    first_seen: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    last_match: Option<DateTime<Utc>>,
+}
+
+impl ActivityWindow {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            monitors_count: 0,
+            filter_complexity: 0.0,
+            minutes: VecDeque::new(),
+            first_seen: now,
+            last_seen: now,
+            last_match: None,
+        }
+    }
+
+    /// Counts of the minute containing `now`
+    fn current(&mut self, now: DateTime<Utc>) -> &mut MinuteCounts {
+        let minute = now.timestamp().div_euclid(60);
+        if self.minutes.back().map(|counts| counts.minute) != Some(minute) {
+            self.minutes.push_back(MinuteCounts {
+                minute,
+                ..Default::default()
+            });
+        }
+        self.prune(now);
+        self.minutes
+            .back_mut()
+            .expect("current minute was just added")
+    }
+
+    fn prune(&mut self, now: DateTime<Utc>) {
+        let oldest = now.timestamp().div_euclid(60) - WINDOW_MINUTES + 1;
+        while self
+            .minutes
+            .front()
+            .is_some_and(|counts| counts.minute < oldest)
+        {
+            self.minutes.pop_front();
+        }
+    }
+
+    fn metrics(&self, tenant_id: Uuid, now: DateTime<Utc>) -> TenantMetrics {
+        let total =
+            |count: fn(&MinuteCounts) -> u64| -> u64 { self.minutes.iter().map(count).sum() };
+        // Tenants picked up within the hour are averaged over the time tracked
+        let minutes_tracked =
+            ((now - self.first_seen).num_seconds() as f64 / 60.0).clamp(1.0, WINDOW_MINUTES as f64);
+
+        TenantMetrics {
+            tenant_id,
+            monitors_count: self.monitors_count,
+            avg_rpc_calls_per_minute: total(|c| c.rpc_calls) as f64 / minutes_tracked,
+            avg_filter_complexity: self.filter_complexity,
+            total_matches_last_hour: total(|c| c.matches) as usize,
+            notifications_sent_last_hour: total(|c| c.notifications) as usize,
+            last_active: self.last_match.unwrap_or(self.last_seen),
+            collected_at: now,
+        }
+    }
+}
+
+/// Rolling hour of activity of the tenants processed by a worker
+#[derive(Debug, Default)]
+pub struct TenantActivity {
+    tenants: DashMap<Uuid, ActivityWindow>,
+}
+
+impl TenantActivity {
+    /// Create an empty tracker
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a filter run over a block, charged `rpc_calls` RPC calls, with
+    /// the tenant's current monitors
+    pub fn record_block(
+        &self,
+        tenant_id: Uuid,
+        monitors: &HashMap<String, Monitor>,
+        rpc_calls: u64,
+    ) {
+        let now = Utc::now();
+        let mut window = self
+            .tenants
+            .entry(tenant_id)
+            .or_insert_with(|| ActivityWindow::new(now));
+        window.monitors_count = monitors.len();
+        window.filter_complexity = filter_complexity(monitors);
+        window.last_seen = now;
+        window.current(now).rpc_calls += rpc_calls;
+    }
+
+    /// Record matches found for a tenant
+    pub fn record_matches(&self, tenant_id: Uuid, count: usize) {
+        if count == 0 {
+            return;
+        }
+        let now = Utc::now();
+        let mut window = self
+            .tenants
+            .entry(tenant_id)
+            .or_insert_with(|| ActivityWindow::new(now));
+        window.last_match = Some(now);
+        window.current(now).matches += count as u64;
+    }
+
+    /// Record a notification delivered for a tenant
+    pub fn record_notification(&self, tenant_id: Uuid) {
+        let now = Utc::now();
+        self.tenants
+            .entry(tenant_id)
+            .or_insert_with(|| ActivityWindow::new(now))
+            .current(now)
+            .notifications += 1;
+    }
+
+    /// Metrics of every tenant processed within the last hour
+    pub fn snapshot(&self) -> Vec<TenantMetrics> {
+        self.snapshot_at(Utc::now())
+    }
+
+    fn snapshot_at(&self, now: DateTime<Utc>) -> Vec<TenantMetrics> {
+        let idle_since = now - ChronoDuration::minutes(WINDOW_MINUTES);
+        self.tenants
+            .retain(|_, window| window.last_seen > idle_since);
+
+        self.tenants
+            .iter_mut()
+            .map(|mut entry| {
+                let tenant_id = *entry.key();
+                let window = entry.value_mut();
+                window.prune(now);
+                window.metrics(tenant_id, now)
+            })
+            .collect()
+    }
+
+    /// Publish metrics every `interval` until aborted, to the load balancer
+    /// and, if given, for coordinators and API processes elsewhere
+    pub fn start(
+        self: &Arc<Self>,
+        interval: Duration,
+        load_balancer: Arc<LoadBalancer>,
+        store: Option<Arc<AssignmentStore>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let activity = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                let metrics = activity.snapshot();
+                if metrics.is_empty() {
+                    continue;
+                }
+                debug!("Publishing activity metrics of {} tenants", metrics.len());
+                for tenant_metrics in &metrics {
+                    // Updating the in-memory map cannot fail
+                    let _ = load_balancer
+                        .update_tenant_metrics(tenant_metrics.clone())
+                        .await;
+                }
+                if let Some(store) = &store {
+                    if let Err(e) = store.report_tenant_metrics(&metrics).await {
+                        warn!("Failed to publish tenant metrics: {}", e);
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Mean number of match conditions per monitor
+fn filter_complexity(monitors: &HashMap<String, Monitor>) -> f64 {
+    if monitors.is_empty() {
+        return 0.0;
+    }
+
+    let conditions: usize = monitors
+        .values()
+        .map(|monitor| {
+            let conditions = &monitor.match_conditions;
+            conditions.functions.len() + conditions.events.len() + conditions.transactions.len()
+        })
+        .sum();
+    conditions as f64 / monitors.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 - 1_700_000_000 % 60, 0).unwrap()
+            + ChronoDuration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_counts_roll_off_after_an_hour() {
+        let tenant_id = Uuid::new_v4();
+        let mut window = ActivityWindow::new(at(0));
+        window.current(at(0)).rpc_calls += 30;
+        window.current(at(0)).matches += 2;
+        window.current(at(30)).rpc_calls += 30;
+        window.current(at(30)).notifications += 1;
+
+        let metrics = window.metrics(tenant_id, at(30));
+        assert_eq!(metrics.total_matches_last_hour, 2);
+        assert_eq!(metrics.notifications_sent_last_hour, 1);
+        assert!((metrics.avg_rpc_calls_per_minute - 2.0).abs() < 1e-9);
+
+        window.prune(at(75));
+        let metrics = window.metrics(tenant_id, at(75));
+        assert_eq!(metrics.total_matches_last_hour, 0);
+        assert_eq!(metrics.notifications_sent_last_hour, 1);
+        assert!((metrics.avg_rpc_calls_per_minute - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_idle_tenants_are_dropped() {
+        let activity = TenantActivity::new();
+        let tenant_id = Uuid::new_v4();
+        activity.record_notification(tenant_id);
+        assert_eq!(activity.snapshot().len(), 1);
+
+        let later = Utc::now() + ChronoDuration::minutes(WINDOW_MINUTES + 1);
+        assert!(activity.snapshot_at(later).is_empty());
+    }
+}
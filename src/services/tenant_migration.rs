@@ -0,0 +1,776 @@
+//! Tenant Migration
+//!
+//! Copies a tenant between orchestrator databases, e.g. from staging to
+//! production. A snapshot of the tenant's networks, monitors, triggers, quiet
+//! hours and recent matches is exported from the source and imported into the
+//! target, where records are matched by natural key (network slug, monitor
+//! and trigger name). Matched records keep their target ids, new records get
+//! fresh ids, and references between them are remapped. Importing first
+//! computes a plan that can be reviewed as a dry run; records only present on
+//! the target are left alone. Networks created or updated by an import must
+//! use a chain type enabled on the target.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::models::{
+    MatchRecord, MigrationAction, MigrationChange, MigrationPlan, MigrationRecordKind,
+    MonitorRecord, NetworkRecord, QuietHours, TenantRecord, TenantSnapshot, TriggerRecord,
+};
+use crate::services::chain_support::ChainSupport;
+use crate::services::ServiceError;
+
+/// Identity of a recorded match, used to skip matches already on the target
+type MatchKey = (String, String, Option<i64>, Option<String>);
+
+/// Exports tenants from and imports them into one database
+pub struct TenantMigrationService {
+    db: Arc<PgPool>,
+    /// Chain types imported networks may use
+    chains: ChainSupport,
+}
+
+impl TenantMigrationService {
+    /// Create a migration service on the given database
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            chains: ChainSupport::default(),
+        }
+    }
+
+    /// Reject imported networks of chain types not enabled in the given registry
+    pub fn with_chain_support(mut self, chains: ChainSupport) -> Self {
+        self.chains = chains;
+        self
+    }
+
+    /// Snapshot a tenant with the matches recorded since `matches_since`.
+    ///
+    /// Returns None if the tenant does not exist.
+    pub async fn export(
+        &self,
+        tenant_id: Uuid,
+        matches_since: Option<DateTime<Utc>>,
+    ) -> Result<Option<TenantSnapshot>> {
+        let Some(tenant) = load_tenant(&self.db, tenant_id).await? else {
+            return Ok(None);
+        };
+
+        let matches = match matches_since {
+            Some(since) => {
+                sqlx::query_as::<_, MatchRecord>(
+                    r#"
+                    SELECT network_slug, monitor_name, block_number, block_hash, state,
+                           match_data, first_seen_at, updated_at
+                    FROM monitor_matches
+                    WHERE tenant_id = $1 AND first_seen_at >= $2
+                    ORDER BY first_seen_at
+                    "#,
+                )
+                .bind(tenant_id)
+                .bind(since)
+                .fetch_all(&*self.db)
+                .await?
+            }
+            None => Vec::new(),
+        };
+
+        Ok(Some(TenantSnapshot {
+            tenant,
+            networks: load_networks(&self.db, tenant_id).await?,
+            monitors: load_monitors(&self.db, tenant_id).await?,
+            triggers: load_triggers(&self.db, tenant_id).await?,
+            quiet_hours: sqlx::query_as::<_, QuietHours>(
+                r#"
+                SELECT id, tenant_id, start_time, end_time, utc_offset_minutes, days_of_week
+                FROM tenant_quiet_hours
+                WHERE tenant_id = $1 AND is_active
+                "#,
+            )
+            .bind(tenant_id)
+            .fetch_all(&*self.db)
+            .await?,
+            matches,
+            matches_since,
+            exported_at: Utc::now(),
+        }))
+    }
+
+    /// Diff a snapshot against this database without writing anything.
+    ///
+    /// The tenant is imported as `target_tenant_id`, or under its own id if None.
+    /// Fails with [`ServiceError::UnsupportedChain`] if a
+    /// network to create or update uses a chain type that is not enabled.
+    pub async fn plan(
+        &self,
+        snapshot: &TenantSnapshot,
+        target_tenant_id: Option<Uuid>,
+    ) -> Result<MigrationPlan> {
+        let target_tenant_id = target_tenant_id.unwrap_or(snapshot.tenant.id);
+        let mut changes = Vec::new();
+
+        let existing_tenant = load_tenant(&self.db, target_tenant_id).await?;
+        let source_tenant = TenantRecord {
+            id: target_tenant_id,
+            ..snapshot.tenant.clone()
+        };
+        let mut tenant_fields = Vec::new();
+        if let Some(existing) = &existing_tenant {
+            diff_field(
+                &mut tenant_fields,
+                "name",
+                &existing.name,
+                &source_tenant.name,
+            );
+            diff_field(
+                &mut tenant_fields,
+                "is_active",
+                &existing.is_active,
+                &source_tenant.is_active,
+            );
+            diff_field(
+                &mut tenant_fields,
+                "max_monitors",
+                &existing.max_monitors,
+                &source_tenant.max_monitors,
+            );
+            diff_field(
+                &mut tenant_fields,
+                "max_rpc_requests_per_minute",
+                &existing.max_rpc_requests_per_minute,
+                &source_tenant.max_rpc_requests_per_minute,
+            );
+        }
+        changes.push(change(
+            MigrationRecordKind::Tenant,
+            snapshot.tenant.id.to_string(),
+            existing_tenant.is_some(),
+            snapshot.tenant.id,
+            target_tenant_id,
+            tenant_fields,
+        ));
+
+        let networks: HashMap<String, NetworkRecord> = load_networks(&self.db, target_tenant_id)
+            .await?
+            .into_iter()
+            .map(|network| (network.network_id.clone(), network))
+            .collect();
+        let mut network_ids = HashMap::new();
+        for network in &snapshot.networks {
+            let existing = networks.get(&network.network_id);
+            let mut fields = Vec::new();
+            if let Some(existing) = existing {
+                diff_field(&mut fields, "name", &existing.name, &network.name);
+                diff_field(
+                    &mut fields,
+                    "blockchain",
+                    &existing.blockchain,
+                    &network.blockchain,
+                );
+                diff_field(
+                    &mut fields,
+                    "configuration",
+                    &existing.configuration,
+                    &network.configuration,
+                );
+                diff_field(
+                    &mut fields,
+                    "is_active",
+                    &existing.is_active,
+                    &network.is_active,
+                );
+                diff_field(
+                    &mut fields,
+                    "confirmation_blocks",
+                    &existing.confirmation_blocks,
+                    &network.confirmation_blocks,
+                );
+                diff_field(
+                    &mut fields,
+                    "trigger_on_states",
+                    &existing.trigger_on_states,
+                    &network.trigger_on_states,
+                );
+            }
+            if existing.is_none() || !fields.is_empty() {
+                if let Err(ServiceError::UnsupportedChain(reason)) =
+                    self.chains.check_name(&network_chain(network))
+                {
+                    return Err(ServiceError::UnsupportedChain(format!(
+                        "network {}: {}",
+                        network.network_id, reason
+                    ))
+                    .into());
+                }
+            }
+            let target_id = existing.map_or_else(Uuid::new_v4, |existing| existing.id);
+            network_ids.insert(network.id, target_id);
+            changes.push(change(
+                MigrationRecordKind::Network,
+                network.network_id.clone(),
+                existing.is_some(),
+                network.id,
+                target_id,
+                fields,
+            ));
+        }
+
+        let monitors: HashMap<String, MonitorRecord> = load_monitors(&self.db, target_tenant_id)
+            .await?
+            .into_iter()
+            .map(|monitor| (monitor.name.clone(), monitor))
+            .collect();
+        let mut monitor_ids = HashMap::new();
+        for monitor in &snapshot.monitors {
+            let network_id = *network_ids.get(&monitor.network_id).with_context(|| {
+                format!(
+                    "Monitor {} references a network missing from the snapshot",
+                    monitor.name
+                )
+            })?;
+            let existing = monitors.get(&monitor.name);
+            let mut fields = Vec::new();
+            if let Some(existing) = existing {
+                diff_field(
+                    &mut fields,
+                    "monitor_id",
+                    &existing.monitor_id,
+                    &monitor.monitor_id,
+                );
+                diff_field(&mut fields, "network", &existing.network_id, &network_id);
+                diff_field(
+                    &mut fields,
+                    "configuration",
+                    &existing.configuration,
+                    &monitor.configuration,
+                );
+                diff_field(
+                    &mut fields,
+                    "is_active",
+                    &existing.is_active,
+                    &monitor.is_active,
+                );
+                diff_field(
+                    &mut fields,
+                    "is_critical",
+                    &existing.is_critical,
+                    &monitor.is_critical,
+                );
+            }
+            let target_id = existing.map_or_else(Uuid::new_v4, |existing| existing.id);
+            monitor_ids.insert(monitor.id, target_id);
+            changes.push(change(
+                MigrationRecordKind::Monitor,
+                monitor.name.clone(),
+                existing.is_some(),
+                monitor.id,
+                target_id,
+                fields,
+            ));
+        }
+
+        let triggers: HashMap<String, TriggerRecord> = load_triggers(&self.db, target_tenant_id)
+            .await?
+            .into_iter()
+            .map(|trigger| (trigger.name.clone(), trigger))
+            .collect();
+        for trigger in &snapshot.triggers {
+            let monitor_id = *monitor_ids.get(&trigger.monitor_id).with_context(|| {
+                format!(
+                    "Trigger {} references a monitor missing from the snapshot",
+                    trigger.name
+                )
+            })?;
+            let existing = triggers.get(&trigger.name);
+            let mut fields = Vec::new();
+            if let Some(existing) = existing {
+                diff_field(
+                    &mut fields,
+                    "trigger_id",
+                    &existing.trigger_id,
+                    &trigger.trigger_id,
+                );
+                diff_field(&mut fields, "monitor", &existing.monitor_id, &monitor_id);
+                diff_field(
+                    &mut fields,
+                    "type",
+                    &existing.trigger_type,
+                    &trigger.trigger_type,
+                );
+                diff_field(
+                    &mut fields,
+                    "configuration",
+                    &existing.configuration,
+                    &trigger.configuration,
+                );
+                diff_field(
+                    &mut fields,
+                    "is_active",
+                    &existing.is_active,
+                    &trigger.is_active,
+                );
+            }
+            changes.push(change(
+                MigrationRecordKind::Trigger,
+                trigger.name.clone(),
+                existing.is_some(),
+                trigger.id,
+                existing.map_or_else(Uuid::new_v4, |existing| existing.id),
+                fields,
+            ));
+        }
+
+        let recorded = self.recorded_matches(target_tenant_id, snapshot).await?;
+        let new_matches = snapshot
+            .matches
+            .iter()
+            .filter(|record| !recorded.contains(&match_key(record)))
+            .count();
+
+        Ok(MigrationPlan {
+            source_tenant_id: snapshot.tenant.id,
+            target_tenant_id,
+            changes,
+            quiet_hours: snapshot.quiet_hours.len(),
+            new_matches,
+            applied: false,
+        })
+    }
+
+    /// Plan a snapshot's import and, unless `dry_run`, write it in one transaction
+    pub async fn import(
+        &self,
+        snapshot: &TenantSnapshot,
+        target_tenant_id: Option<Uuid>,
+        dry_run: bool,
+    ) -> Result<MigrationPlan> {
+        let mut plan = self.plan(snapshot, target_tenant_id).await?;
+        if dry_run {
+            return Ok(plan);
+        }
+
+        let recorded = self
+            .recorded_matches(plan.target_tenant_id, snapshot)
+            .await?;
+        let mut tx = self.db.begin().await?;
+        apply_plan(&mut tx, snapshot, &plan, &recorded).await?;
+        tx.commit().await?;
+
+        plan.applied = true;
+        info!(
+            "Imported tenant {} as {}: {} created, {} updated, {} unchanged, {} matches",
+            plan.source_tenant_id,
+            plan.target_tenant_id,
+            plan.count(MigrationAction::Create),
+            plan.count(MigrationAction::Update),
+            plan.count(MigrationAction::Unchanged),
+            plan.new_matches
+        );
+        Ok(plan)
+    }
+
+    /// Keys of the target tenant's matches in the snapshot's time range
+    async fn recorded_matches(
+        &self,
+        target_tenant_id: Uuid,
+        snapshot: &TenantSnapshot,
+    ) -> Result<HashSet<MatchKey>> {
+        let Some(since) = snapshot.matches_since else {
+            return Ok(HashSet::new());
+        };
+        let keys = sqlx::query_as::<_, MatchKey>(
+            r#"
+            SELECT network_slug, monitor_name, block_number, block_hash
+            FROM monitor_matches
+            WHERE tenant_id = $1 AND first_seen_at >= $2
+            "#,
+        )
+        .bind(target_tenant_id)
+        .bind(since)
+        .fetch_all(&*self.db)
+        .await?;
+        Ok(keys.into_iter().collect())
+    }
+}
+
+/// Write a plan to the target
+async fn apply_plan(
+    tx: &mut Transaction<'_, Postgres>,
+    snapshot: &TenantSnapshot,
+    plan: &MigrationPlan,
+    recorded: &HashSet<MatchKey>,
+) -> Result<()> {
+    let tenant_id = plan.target_tenant_id;
+    let tenant = &snapshot.tenant;
+    sqlx::query(
+        r#"
+        INSERT INTO tenants (id, name, is_active, max_monitors, max_rpc_requests_per_minute)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (id) DO UPDATE
+        SET name = EXCLUDED.name,
+            is_active = EXCLUDED.is_active,
+            max_monitors = EXCLUDED.max_monitors,
+            max_rpc_requests_per_minute = EXCLUDED.max_rpc_requests_per_minute,
+            updated_at = now()
+        "#,
+    )
+    .bind(tenant_id)
+    .bind(&tenant.name)
+    .bind(tenant.is_active)
+    .bind(tenant.max_monitors)
+    .bind(tenant.max_rpc_requests_per_minute)
+    .execute(&mut **tx)
+    .await
+    .context("Failed to write tenant")?;
+
+    for network in &snapshot.networks {
+        let Some((action, id)) = planned(plan, MigrationRecordKind::Network, network.id) else {
+            continue;
+        };
+        if action == MigrationAction::Unchanged {
+            continue;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_networks
+                (id, tenant_id, network_id, name, blockchain, configuration, is_active,
+                 confirmation_blocks, trigger_on_states)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (id) DO UPDATE
+            SET name = EXCLUDED.name,
+                blockchain = EXCLUDED.blockchain,
+                configuration = EXCLUDED.configuration,
+                is_active = EXCLUDED.is_active,
+                confirmation_blocks = EXCLUDED.confirmation_blocks,
+                trigger_on_states = EXCLUDED.trigger_on_states,
+                updated_at = now()
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .bind(&network.network_id)
+        .bind(&network.name)
+        .bind(&network.blockchain)
+        .bind(&network.configuration)
+        .bind(network.is_active)
+        .bind(network.confirmation_blocks)
+        .bind(&network.trigger_on_states)
+        .execute(&mut **tx)
+        .await
+        .with_context(|| format!("Failed to write network {}", network.network_id))?;
+    }
+
+    for monitor in &snapshot.monitors {
+        let Some((action, id)) = planned(plan, MigrationRecordKind::Monitor, monitor.id) else {
+            continue;
+        };
+        if action == MigrationAction::Unchanged {
+            continue;
+        }
+        let network_id = plan
+            .target_id(MigrationRecordKind::Network, monitor.network_id)
+            .with_context(|| format!("No network planned for monitor {}", monitor.name))?;
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_monitors
+                (id, tenant_id, monitor_id, name, network_id, configuration, is_active, is_critical)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE
+            SET monitor_id = EXCLUDED.monitor_id,
+                network_id = EXCLUDED.network_id,
+                configuration = EXCLUDED.configuration,
+                is_active = EXCLUDED.is_active,
+                is_critical = EXCLUDED.is_critical,
+                updated_at = now()
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .bind(&monitor.monitor_id)
+        .bind(&monitor.name)
+        .bind(network_id)
+        .bind(&monitor.configuration)
+        .bind(monitor.is_active)
+        .bind(monitor.is_critical)
+        .execute(&mut **tx)
+        .await
+        .with_context(|| format!("Failed to write monitor {}", monitor.name))?;
+    }
+
+    for trigger in &snapshot.triggers {
+        let Some((action, id)) = planned(plan, MigrationRecordKind::Trigger, trigger.id) else {
+            continue;
+        };
+        if action == MigrationAction::Unchanged {
+            continue;
+        }
+        let monitor_id = plan
+            .target_id(MigrationRecordKind::Monitor, trigger.monitor_id)
+            .with_context(|| format!("No monitor planned for trigger {}", trigger.name))?;
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_triggers
+                (id, tenant_id, trigger_id, monitor_id, name, type, configuration, is_active)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (id) DO UPDATE
+            SET trigger_id = EXCLUDED.trigger_id,
+                monitor_id = EXCLUDED.monitor_id,
+                type = EXCLUDED.type,
+                configuration = EXCLUDED.configuration,
+                is_active = EXCLUDED.is_active,
+                updated_at = now()
+            "#,
+        )
+        .bind(id)
+        .bind(tenant_id)
+        .bind(&trigger.trigger_id)
+        .bind(monitor_id)
+        .bind(&trigger.name)
+        .bind(&trigger.trigger_type)
+        .bind(&trigger.configuration)
+        .bind(trigger.is_active)
+        .execute(&mut **tx)
+        .await
+        .with_context(|| format!("Failed to write trigger {}", trigger.name))?;
+    }
+
+    sqlx::query("DELETE FROM tenant_quiet_hours WHERE tenant_id = $1")
+        .bind(tenant_id)
+        .execute(&mut **tx)
+        .await?;
+    for window in &snapshot.quiet_hours {
+        sqlx::query(
+            r#"
+            INSERT INTO tenant_quiet_hours
+                (tenant_id, start_time, end_time, utc_offset_minutes, days_of_week)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(window.start_time)
+        .bind(window.end_time)
+        .bind(window.utc_offset_minutes)
+        .bind(&window.days_of_week)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to write quiet hours")?;
+    }
+
+    for record in &snapshot.matches {
+        if recorded.contains(&match_key(record)) {
+            continue;
+        }
+        sqlx::query(
+            r#"
+            INSERT INTO monitor_matches
+                (tenant_id, network_slug, monitor_name, block_number, block_hash, state,
+                 match_data, first_seen_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(&record.network_slug)
+        .bind(&record.monitor_name)
+        .bind(record.block_number)
+        .bind(&record.block_hash)
+        .bind(&record.state)
+        .bind(&record.match_data)
+        .bind(record.first_seen_at)
+        .bind(record.updated_at)
+        .execute(&mut **tx)
+        .await
+        .context("Failed to write match")?;
+    }
+
+    Ok(())
+}
+
+async fn load_tenant(db: &PgPool, tenant_id: Uuid) -> Result<Option<TenantRecord>> {
+    Ok(sqlx::query_as::<_, TenantRecord>(
+        r#"
+        SELECT id, name, is_active, max_monitors, max_rpc_requests_per_minute
+        FROM tenants
+        WHERE id = $1
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_optional(db)
+    .await?)
+}
+
+async fn load_networks(db: &PgPool, tenant_id: Uuid) -> Result<Vec<NetworkRecord>> {
+    Ok(sqlx::query_as::<_, NetworkRecord>(
+        r#"
+        SELECT id, network_id, name, blockchain, configuration, is_active,
+               confirmation_blocks, trigger_on_states
+        FROM tenant_networks
+        WHERE tenant_id = $1
+        ORDER BY network_id
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(db)
+    .await?)
+}
+
+async fn load_monitors(db: &PgPool, tenant_id: Uuid) -> Result<Vec<MonitorRecord>> {
+    Ok(sqlx::query_as::<_, MonitorRecord>(
+        r#"
+        SELECT id, monitor_id, name, network_id, configuration, is_active, is_critical
+        FROM tenant_monitors
+        WHERE tenant_id = $1
+        ORDER BY name
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(db)
+    .await?)
+}
+
+async fn load_triggers(db: &PgPool, tenant_id: Uuid) -> Result<Vec<TriggerRecord>> {
+    Ok(sqlx::query_as::<_, TriggerRecord>(
+        r#"
+        SELECT id, trigger_id, monitor_id, name, type, configuration, is_active
+        FROM tenant_triggers
+        WHERE tenant_id = $1
+        ORDER BY name
+        "#,
+    )
+    .bind(tenant_id)
+    .fetch_all(db)
+    .await?)
+}
+
+/// Planned action and target id of a source record
+fn planned(
+    plan: &MigrationPlan,
+    kind: MigrationRecordKind,
+    source_id: Uuid,
+) -> Option<(MigrationAction, Uuid)> {
+    plan.changes
+        .iter()
+        .find(|change| change.kind == kind && change.source_id == source_id)
+        .map(|change| (change.action, change.target_id))
+}
+
+fn change(
+    kind: MigrationRecordKind,
+    key: String,
+    exists: bool,
+    source_id: Uuid,
+    target_id: Uuid,
+    changed_fields: Vec<String>,
+) -> MigrationChange {
+    let action = match (exists, changed_fields.is_empty()) {
+        (false, _) => MigrationAction::Create,
+        (true, false) => MigrationAction::Update,
+        (true, true) => MigrationAction::Unchanged,
+    };
+    MigrationChange {
+        kind,
+        key,
+        action,
+        source_id,
+        target_id,
+        changed_fields,
+    }
+}
+
+fn diff_field<T: PartialEq>(fields: &mut Vec<String>, name: &str, target: &T, source: &T) {
+    if target != source {
+        fields.push(name.to_string());
+    }
+}
+
+/// Chain type of an exported network, from its configuration or else its `blockchain` column
+fn network_chain(network: &NetworkRecord) -> String {
+    network
+        .configuration
+        .get("network_type")
+        .and_then(|network_type| network_type.as_str())
+        .unwrap_or(&network.blockchain)
+        .to_string()
+}
+
+fn match_key(record: &MatchRecord) -> MatchKey {
+    (
+        record.network_slug.clone(),
+        record.monitor_name.clone(),
+        record.block_number,
+        record.block_hash.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_change_action_follows_existence_and_differences() {
+        let id = Uuid::new_v4();
+        let create = change(
+            MigrationRecordKind::Network,
+            "a".into(),
+            false,
+            id,
+            id,
+            vec![],
+        );
+        assert_eq!(create.action, MigrationAction::Create);
+
+        let unchanged = change(
+            MigrationRecordKind::Network,
+            "a".into(),
+            true,
+            id,
+            id,
+            vec![],
+        );
+        assert_eq!(unchanged.action, MigrationAction::Unchanged);
+
+        let update = change(
+            MigrationRecordKind::Network,
+            "a".into(),
+            true,
+            id,
+            id,
+            vec!["configuration".into()],
+        );
+        assert_eq!(update.action, MigrationAction::Update);
+    }
+
+    #[test]
+    fn test_diff_field_records_differing_fields() {
+        let mut fields = Vec::new();
+        diff_field(&mut fields, "same", &1, &1);
+        diff_field(&mut fields, "different", &"a", &"b");
+        assert_eq!(fields, vec!["different".to_string()]);
+    }
+
+    #[test]
+    fn test_network_chain_prefers_configuration() {
+        let mut network = NetworkRecord {
+            id: Uuid::new_v4(),
+            network_id: "midnight_testnet".into(),
+            name: "Midnight Testnet".into(),
+            blockchain: "evm".into(),
+            configuration: serde_json::json!({ "network_type": "Midnight" }),
+            is_active: true,
+            confirmation_blocks: None,
+            trigger_on_states: vec![],
+        };
+        assert_eq!(network_chain(&network), "Midnight");
+        assert!(ChainSupport::default()
+            .check_name(&network_chain(&network))
+            .is_err());
+
+        network.configuration = serde_json::json!({});
+        assert_eq!(network_chain(&network), "evm");
+    }
+}
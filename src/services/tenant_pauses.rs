@@ -0,0 +1,108 @@
+//! Tenant Pauses
+//!
+//! Pauses a tenant's block processing without taking it off its workers, e.g.
+//! while a misconfigured monitor floods notifications. Pauses are stored in
+//! `tenant_pauses` so a restarted or reloaded worker keeps skipping the
+//! tenant; workers cache each tenant's pause briefly and drop the cached
+//! value when the tenant's configuration is invalidated.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::models::TenantPause;
+use crate::services::db_outage::cached_or;
+
+/// How long a loaded pause state is reused before re-reading the database
+const PAUSE_TTL: Duration = Duration::from_secs(60);
+
+/// Stored tenant pauses and a per-tenant cache of whether each is paused
+pub struct TenantPauses {
+    db: Arc<PgPool>,
+    paused: DashMap<Uuid, (Instant, bool)>,
+}
+
+impl TenantPauses {
+    /// Create a new pause store
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            paused: DashMap::new(),
+        }
+    }
+
+    /// Pause of a tenant, if it is paused
+    pub async fn get(&self, tenant_id: Uuid) -> Result<Option<TenantPause>> {
+        Ok(sqlx::query_as::<_, TenantPause>(
+            "SELECT tenant_id, reason, paused_at FROM tenant_pauses WHERE tenant_id = $1",
+        )
+        .bind(tenant_id)
+        .fetch_optional(&*self.db)
+        .await?)
+    }
+
+    /// Pause a tenant. Pausing a paused tenant only replaces the reason.
+    pub async fn pause(&self, tenant_id: Uuid, reason: Option<&str>) -> Result<TenantPause> {
+        let pause = sqlx::query_as::<_, TenantPause>(
+            r#"
+            INSERT INTO tenant_pauses (tenant_id, reason)
+            VALUES ($1, $2)
+            ON CONFLICT (tenant_id) DO UPDATE SET reason = EXCLUDED.reason
+            RETURNING tenant_id, reason, paused_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(reason)
+        .fetch_one(&*self.db)
+        .await?;
+        self.invalidate(tenant_id);
+        Ok(pause)
+    }
+
+    /// Resume a tenant, returning the pause it was under, if any
+    pub async fn resume(&self, tenant_id: Uuid) -> Result<Option<TenantPause>> {
+        let pause = sqlx::query_as::<_, TenantPause>(
+            r#"
+            DELETE FROM tenant_pauses
+            WHERE tenant_id = $1
+            RETURNING tenant_id, reason, paused_at
+            "#,
+        )
+        .bind(tenant_id)
+        .fetch_optional(&*self.db)
+        .await?;
+        self.invalidate(tenant_id);
+        Ok(pause)
+    }
+
+    /// Whether a tenant's blocks are skipped
+    pub async fn is_paused(&self, tenant_id: Uuid) -> Result<bool> {
+        if let Some(entry) = self.paused.get(&tenant_id) {
+            let (loaded_at, paused) = *entry.value();
+            if loaded_at.elapsed() < PAUSE_TTL {
+                return Ok(paused);
+            }
+        }
+
+        let paused = match sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM tenant_pauses WHERE tenant_id = $1)",
+        )
+        .bind(tenant_id)
+        .fetch_one(&*self.db)
+        .await
+        {
+            Ok(paused) => paused,
+            Err(e) => return cached_or(e, self.paused.get(&tenant_id).map(|entry| entry.1)),
+        };
+        self.paused.insert(tenant_id, (Instant::now(), paused));
+        Ok(paused)
+    }
+
+    /// Drop the cached pause state so the next lookup re-reads it
+    pub fn invalidate(&self, tenant_id: Uuid) {
+        self.paused.remove(&tenant_id);
+    }
+}
@@ -0,0 +1,156 @@
+//! Tenant Store
+//!
+//! Reads tenants from the `tenants` table and flips their active flag when
+//! they are suspended or activated through the management API. Tenants
+//! suspended here are stopped the same way as those suspended for exceeding
+//! their RPC cap.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::{TenantInfo, TenantPriority, TenantStatus};
+
+/// Row of the `tenants` table
+#[derive(sqlx::FromRow)]
+struct TenantRow {
+    id: Uuid,
+    name: String,
+    is_active: bool,
+    is_trial: bool,
+    max_monitors: i32,
+    max_rpc_requests_per_minute: i32,
+    created_at: DateTime<Utc>,
+    updated_at: Option<DateTime<Utc>>,
+}
+
+impl From<TenantRow> for TenantInfo {
+    fn from(row: TenantRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            status: match (row.is_active, row.is_trial) {
+                (true, true) => TenantStatus::Trial,
+                (true, false) => TenantStatus::Active,
+                (false, _) => TenantStatus::Suspended,
+            },
+            priority: TenantPriority::default(),
+            max_monitors: row.max_monitors.max(0) as usize,
+            max_rpc_requests_per_minute: row.max_rpc_requests_per_minute.max(0) as u32,
+            created_at: row.created_at,
+            last_active_at: row.updated_at.unwrap_or(row.created_at),
+        }
+    }
+}
+
+/// Conditions on listed tenants; unset fields match every tenant
+#[derive(Debug, Clone, Default)]
+pub struct TenantFilter {
+    /// Only active or only suspended tenants
+    pub active: Option<bool>,
+
+    /// Only tenants on trial or only tenants not on trial
+    pub trial: Option<bool>,
+
+    /// Only these tenants
+    pub tenant_ids: Option<Vec<Uuid>>,
+
+    /// Only tenants with this network configured
+    pub network_slug: Option<String>,
+}
+
+/// WHERE clause applying a [`TenantFilter`] bound as `$1`..`$4`
+const TENANT_FILTER: &str = r#"
+    WHERE ($1::BOOLEAN IS NULL OR t.is_active = $1)
+      AND ($2::UUID[] IS NULL OR t.id = ANY($2))
+      AND ($3::TEXT IS NULL OR EXISTS (
+          SELECT 1 FROM tenant_networks n
+          WHERE n.tenant_id = t.id AND n.network_id = $3 AND n.is_active
+      ))
+      AND ($4::BOOLEAN IS NULL OR t.is_trial = $4)
+"#;
+
+/// Access to tenant records
+pub struct TenantStore {
+    db: Arc<PgPool>,
+}
+
+impl TenantStore {
+    /// Create a new tenant store
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self { db }
+    }
+
+    /// One page of the tenants matching a filter, ordered by name
+    pub async fn list(
+        &self,
+        filter: &TenantFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<TenantInfo>> {
+        let rows = sqlx::query_as::<_, TenantRow>(&format!(
+            r#"
+            SELECT id, name, is_active, is_trial, max_monitors, max_rpc_requests_per_minute,
+                   created_at, updated_at
+            FROM tenants t
+            {}
+            ORDER BY name, id
+            LIMIT $5 OFFSET $6
+            "#,
+            TENANT_FILTER
+        ))
+        .bind(filter.active)
+        .bind(&filter.tenant_ids)
+        .bind(&filter.network_slug)
+        .bind(filter.trial)
+        .bind(limit.min(i64::MAX as u64) as i64)
+        .bind(offset.min(i64::MAX as u64) as i64)
+        .fetch_all(&*self.db)
+        .await?;
+        Ok(rows.into_iter().map(TenantInfo::from).collect())
+    }
+
+    /// Number of tenants matching a filter
+    pub async fn count(&self, filter: &TenantFilter) -> Result<u64> {
+        let count = sqlx::query_scalar::<_, i64>(&format!(
+            "SELECT COUNT(*) FROM tenants t {}",
+            TENANT_FILTER
+        ))
+        .bind(filter.active)
+        .bind(&filter.tenant_ids)
+        .bind(&filter.network_slug)
+        .bind(filter.trial)
+        .fetch_one(&*self.db)
+        .await?;
+        Ok(count.max(0) as u64)
+    }
+
+    /// A tenant by id, if it exists
+    pub async fn get(&self, tenant_id: Uuid) -> Result<Option<TenantInfo>> {
+        let filter = TenantFilter {
+            tenant_ids: Some(vec![tenant_id]),
+            ..Default::default()
+        };
+        Ok(self.list(&filter, 1, 0).await?.into_iter().next())
+    }
+
+    /// Activate or suspend a tenant, returning the updated tenant if it exists
+    pub async fn set_active(&self, tenant_id: Uuid, active: bool) -> Result<Option<TenantInfo>> {
+        let row = sqlx::query_as::<_, TenantRow>(
+            r#"
+            UPDATE tenants
+            SET is_active = $2, updated_at = now()
+            WHERE id = $1
+            RETURNING id, name, is_active, is_trial, max_monitors,
+                      max_rpc_requests_per_minute, created_at, updated_at
+            "#,
+        )
+        .bind(tenant_id)
+        .bind(active)
+        .fetch_optional(&*self.db)
+        .await?;
+        Ok(row.map(TenantInfo::from))
+    }
+}
@@ -0,0 +1,98 @@
+//! Trace Context
+//!
+//! Trace context carried on block events from the block watcher to the
+//! workers. The watcher starts a trace when it fetches blocks, and workers
+//! open their processing spans as children of it, so the watcher fetch,
+//! worker processing, filter runs and trigger execution of one block all log
+//! the same `trace_id`, even when the event crossed processes on the event
+//! bus. Tenant spans below the block span carry the tenant ID the way
+//! `baggage` would.
+
+use serde::{Deserialize, Serialize};
+use tracing::{info_span, Span};
+use uuid::Uuid;
+
+/// Trace and span a block event was sent under
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceContext {
+    /// 32 lowercase hex digits shared by every span of the trace, as in W3C
+    /// `traceparent`
+    pub trace_id: String,
+    /// 16 lowercase hex digits of the span that sent the event
+    pub span_id: String,
+}
+
+impl TraceContext {
+    /// Start a new trace
+    pub fn new_root() -> Self {
+        Self {
+            trace_id: Uuid::new_v4().simple().to_string(),
+            span_id: new_span_id(),
+        }
+    }
+
+    /// Context of a new span in the same trace
+    pub fn child(&self) -> Self {
+        Self {
+            trace_id: self.trace_id.clone(),
+            span_id: new_span_id(),
+        }
+    }
+
+    /// Span of the watcher fetching a range of blocks under this context
+    pub fn fetch_span(&self, network_slug: &str, start_block: u64, end_block: u64) -> Span {
+        info_span!(
+            "fetch_blocks",
+            trace_id = %self.trace_id,
+            span_id = %self.span_id,
+            network = %network_slug,
+            start_block,
+            end_block,
+        )
+    }
+
+    /// Span of a worker processing one block of an event sent under this context
+    pub fn block_span(
+        &self,
+        worker_id: &str,
+        network_slug: &str,
+        block_number: Option<u64>,
+    ) -> Span {
+        let child = self.child();
+        info_span!(
+            "block",
+            trace_id = %child.trace_id,
+            span_id = %child.span_id,
+            parent_span_id = %self.span_id,
+            worker_id = %worker_id,
+            network = %network_slug,
+            block_number,
+        )
+    }
+}
+
+/// Span of one tenant's work on a block, nested in the block span
+pub fn tenant_span(tenant_id: Uuid) -> Span {
+    info_span!("tenant", tenant_id = %tenant_id)
+}
+
+fn new_span_id() -> String {
+    Uuid::new_v4().simple().to_string()[..16].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_child_stays_in_the_trace() {
+        let root = TraceContext::new_root();
+        assert_eq!(root.trace_id.len(), 32);
+        assert_eq!(root.span_id.len(), 16);
+
+        let child = root.child();
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_ne!(child.span_id, root.span_id);
+        assert_ne!(TraceContext::new_root().trace_id, root.trace_id);
+    }
+}
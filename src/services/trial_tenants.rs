@@ -0,0 +1,175 @@
+//! Trial Tenants
+//!
+//! Scheduling class of tenants in their trial period (`tenants.is_trial`).
+//! Trial tenants are only evaluated on every `block_interval`-th block, run
+//! at most `max_monitors` of their monitors and are held to
+//! `max_rpc_requests_per_minute` when it is lower than their own cap, so free
+//! trials cannot take the resources of paying tenants. Workers cache whether
+//! each tenant is on trial briefly and drop the cached value when the
+//! tenant's configuration is invalidated.
+
+use anyhow::Result;
+use dashmap::DashMap;
+use openzeppelin_monitor::models::Monitor;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::services::db_outage::cached_or;
+
+/// How long a loaded trial state is reused before re-reading the database
+const TRIAL_TTL: Duration = Duration::from_secs(60);
+
+/// Limits applied to trial tenants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrialLimits {
+    /// Evaluate trial tenants on every Nth block only (1 evaluates every block)
+    #[serde(default = "default_block_interval")]
+    pub block_interval: u64,
+
+    /// Monitors run per trial tenant, by name; the rest are skipped (0 disables)
+    #[serde(default = "default_max_monitors")]
+    pub max_monitors: usize,
+
+    /// RPC cap of trial tenants when lower than their own (0 keeps their own).
+    /// Enforced by the worker RPC limiter, which is off when
+    /// `worker.rpc_cap_actions` is empty
+    #[serde(default = "default_max_rpc_requests_per_minute")]
+    pub max_rpc_requests_per_minute: u32,
+}
+
+fn default_block_interval() -> u64 {
+    5
+}
+
+fn default_max_monitors() -> usize {
+    5
+}
+
+fn default_max_rpc_requests_per_minute() -> u32 {
+    60
+}
+
+impl Default for TrialLimits {
+    fn default() -> Self {
+        Self {
+            block_interval: default_block_interval(),
+            max_monitors: default_max_monitors(),
+            max_rpc_requests_per_minute: default_max_rpc_requests_per_minute(),
+        }
+    }
+}
+
+impl TrialLimits {
+    /// Validate the limits
+    pub fn validate(&self) -> Result<(), String> {
+        if self.block_interval == 0 {
+            return Err("trial.block_interval must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+
+    /// Whether a trial tenant is evaluated on a block; blocks without a
+    /// number are always evaluated
+    pub fn evaluates_block(&self, block_number: Option<u64>) -> bool {
+        block_number.map_or(true, |number| number % self.block_interval.max(1) == 0)
+    }
+
+    /// The monitors a trial tenant runs, the first `max_monitors` by name
+    pub fn limit_monitors(&self, monitors: HashMap<String, Monitor>) -> HashMap<String, Monitor> {
+        if self.max_monitors == 0 || monitors.len() <= self.max_monitors {
+            return monitors;
+        }
+        let mut monitors: Vec<(String, Monitor)> = monitors.into_iter().collect();
+        monitors.sort_by(|(a, _), (b, _)| a.cmp(b));
+        monitors.truncate(self.max_monitors);
+        monitors.into_iter().collect()
+    }
+}
+
+/// Per-tenant cache of whether each tenant is on trial
+pub struct TrialTenants {
+    db: Arc<PgPool>,
+    limits: TrialLimits,
+    trials: DashMap<Uuid, (Instant, bool)>,
+}
+
+impl TrialTenants {
+    /// Create a new trial lookup applying the given limits
+    pub fn new(db: Arc<PgPool>, limits: TrialLimits) -> Self {
+        Self {
+            db,
+            limits,
+            trials: DashMap::new(),
+        }
+    }
+
+    /// Limits applied to trial tenants
+    pub fn limits(&self) -> &TrialLimits {
+        &self.limits
+    }
+
+    /// Whether a tenant is on trial
+    pub async fn is_trial(&self, tenant_id: Uuid) -> Result<bool> {
+        if let Some(entry) = self.trials.get(&tenant_id) {
+            let (loaded_at, trial) = *entry.value();
+            if loaded_at.elapsed() < TRIAL_TTL {
+                return Ok(trial);
+            }
+        }
+
+        let trial =
+            match sqlx::query_scalar::<_, bool>("SELECT is_trial FROM tenants WHERE id = $1")
+                .bind(tenant_id)
+                .fetch_optional(&*self.db)
+                .await
+            {
+                Ok(trial) => trial.unwrap_or(false),
+                Err(e) => return cached_or(e, self.trials.get(&tenant_id).map(|entry| entry.1)),
+            };
+        self.trials.insert(tenant_id, (Instant::now(), trial));
+        Ok(trial)
+    }
+
+    /// Drop the cached trial state so the next lookup re-reads it
+    pub fn invalidate(&self, tenant_id: Uuid) {
+        self.trials.remove(&tenant_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluates_every_nth_block() {
+        let limits = TrialLimits {
+            block_interval: 5,
+            ..Default::default()
+        };
+        let evaluated: Vec<u64> = (1..=20)
+            .filter(|number| limits.evaluates_block(Some(*number)))
+            .collect();
+        assert_eq!(evaluated, vec![5, 10, 15, 20]);
+        assert!(limits.evaluates_block(None));
+
+        let every_block = TrialLimits {
+            block_interval: 1,
+            ..Default::default()
+        };
+        assert!((1..=20).all(|number| every_block.evaluates_block(Some(number))));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(TrialLimits::default().validate().is_ok());
+        let limits = TrialLimits {
+            block_interval: 0,
+            ..Default::default()
+        };
+        assert!(limits.validate().is_err());
+    }
+}
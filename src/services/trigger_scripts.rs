@@ -0,0 +1,183 @@
+//! Trigger Script Store
+//!
+//! Manages the `trigger_scripts` rows workers load trigger condition scripts
+//! from. Scripts are checked against the languages OpenZeppelin Monitor's
+//! script executors run and a size limit before they are stored; callers
+//! publish a [`ScriptInvalidation`](crate::services::ScriptInvalidation)
+//! after every change so workers drop their cached copy.
+
+use anyhow::Result;
+use openzeppelin_monitor::models::ScriptLanguage;
+use sqlx::PgPool;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::models::{TriggerScript, TriggerScriptInput};
+use crate::repositories::RepositoryError;
+use crate::services::ServiceError;
+
+/// Columns selected into [`TriggerScript`]
+const COLUMNS: &str = "tenant_id, name, language, content, is_active, created_at, updated_at";
+
+/// Default largest accepted script, in bytes
+pub const DEFAULT_MAX_SCRIPT_SIZE: usize = 64 * 1024;
+
+/// Tenants' trigger condition scripts
+pub struct TriggerScriptStore {
+    db: Arc<PgPool>,
+    max_size: usize,
+}
+
+impl TriggerScriptStore {
+    /// Create a store accepting scripts up to the default size
+    pub fn new(db: Arc<PgPool>) -> Self {
+        Self {
+            db,
+            max_size: DEFAULT_MAX_SCRIPT_SIZE,
+        }
+    }
+
+    /// Reject scripts larger than `max_size` bytes
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Get a tenant's script by name
+    pub async fn get(&self, tenant_id: Uuid, name: &str) -> Result<Option<TriggerScript>> {
+        Ok(sqlx::query_as::<_, TriggerScript>(&format!(
+            "SELECT {COLUMNS} FROM trigger_scripts WHERE tenant_id = $1 AND name = $2"
+        ))
+        .bind(tenant_id)
+        .bind(name)
+        .fetch_optional(&*self.db)
+        .await?)
+    }
+
+    /// Create or replace a tenant's script, storing the language in the
+    /// form the executors expect
+    pub async fn upsert(
+        &self,
+        tenant_id: Uuid,
+        name: &str,
+        input: &TriggerScriptInput,
+    ) -> Result<TriggerScript, ServiceError> {
+        let language = validate(name, input, self.max_size)?;
+
+        Ok(sqlx::query_as::<_, TriggerScript>(&format!(
+            r#"
+            INSERT INTO trigger_scripts (tenant_id, name, language, content, is_active)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tenant_id, name) DO UPDATE
+            SET language = EXCLUDED.language,
+                content = EXCLUDED.content,
+                is_active = EXCLUDED.is_active,
+                updated_at = now()
+            RETURNING {COLUMNS}
+            "#
+        ))
+        .bind(tenant_id)
+        .bind(name)
+        .bind(language)
+        .bind(&input.content)
+        .bind(input.is_active)
+        .fetch_one(&*self.db)
+        .await
+        .map_err(RepositoryError::from)?)
+    }
+
+    /// Delete a tenant's script, returning it if it existed
+    pub async fn delete(&self, tenant_id: Uuid, name: &str) -> Result<Option<TriggerScript>> {
+        Ok(sqlx::query_as::<_, TriggerScript>(&format!(
+            "DELETE FROM trigger_scripts WHERE tenant_id = $1 AND name = $2 RETURNING {COLUMNS}"
+        ))
+        .bind(tenant_id)
+        .bind(name)
+        .fetch_optional(&*self.db)
+        .await?)
+    }
+}
+
+/// Check a script, returning its language's stored name
+fn validate(
+    name: &str,
+    input: &TriggerScriptInput,
+    max_size: usize,
+) -> Result<String, ServiceError> {
+    // Conditions name scripts by the last segment of their script path
+    if name.is_empty() || name.contains('/') {
+        return Err(ServiceError::InvalidState(format!(
+            "invalid script name {:?}, expected a file name without /",
+            name
+        )));
+    }
+
+    let language = canonical_language(&input.language).ok_or_else(|| {
+        ServiceError::InvalidState(format!(
+            "unsupported script language {}, expected Python, JavaScript or Bash",
+            input.language
+        ))
+    })?;
+
+    if input.content.trim().is_empty() {
+        return Err(ServiceError::InvalidState(
+            "script content cannot be empty".to_string(),
+        ));
+    }
+    if input.content.len() > max_size {
+        return Err(ServiceError::ResourceLimitExceeded(format!(
+            "script is {} bytes, at most {} bytes are accepted",
+            input.content.len(),
+            max_size
+        )));
+    }
+
+    Ok(language)
+}
+
+/// Name of a language the script executors support, matched case-insensitively
+fn canonical_language(language: &str) -> Option<String> {
+    ["Python", "JavaScript", "Bash"]
+        .into_iter()
+        .find(|supported| supported.eq_ignore_ascii_case(language))
+        // Only languages OpenZeppelin Monitor can deserialize reach an executor
+        .filter(|supported| {
+            serde_json::from_value::<ScriptLanguage>(serde_json::json!(supported)).is_ok()
+        })
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input(language: &str, content: &str) -> TriggerScriptInput {
+        TriggerScriptInput {
+            language: language.to_string(),
+            content: content.to_string(),
+            is_active: true,
+        }
+    }
+
+    #[test]
+    fn test_scripts_are_validated_before_storing() {
+        let validate = |name: &str, input: &TriggerScriptInput| validate(name, input, 16);
+
+        assert_eq!(
+            validate("large.py", &input("python", "print(True)")).unwrap(),
+            "Python"
+        );
+        assert!(matches!(
+            validate("large.py", &input("ruby", "puts true")),
+            Err(ServiceError::InvalidState(_))
+        ));
+        assert!(matches!(
+            validate("scripts/large.py", &input("Python", "print(True)")),
+            Err(ServiceError::InvalidState(_))
+        ));
+        assert!(matches!(
+            validate("large.py", &input("Python", &"#".repeat(17))),
+            Err(ServiceError::ResourceLimitExceeded(_))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+//! Watcher Handoff
+//!
+//! Lets a replacement block watcher replica resume exactly where the previous
+//! one stopped. The active replica holds a lease (a [`DistributedLock`]) and
+//! records a per-network cursor around every broadcast, so during a deploy the
+//! successor waits for the lease and continues from the recorded cursors
+//! without missing a polling cycle or re-broadcasting a range.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use redis::{AsyncCommands, Client as RedisClient, Script};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+use crate::services::distributed_lock::{DistributedLock, LockGuard};
+use crate::services::redis_keyspace::RedisKeyspace;
+
+/// Write a cursor only while still holding the lease
+const WRITE_CURSOR_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    redis.call("SET", KEYS[2], ARGV[2])
+    return 1
+end
+return 0
+"#;
+
+/// Per-network position of the active watcher
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatcherCursor {
+    /// Last block broadcast to workers
+    pub last_processed_block: u64,
+
+    /// Range fetched but not yet confirmed as broadcast
+    pub in_flight: Option<(u64, u64)>,
+
+    /// Replica that wrote the cursor
+    pub owner: String,
+
+    /// When the cursor was written
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Lease and cursor store shared by block watcher replicas
+pub struct WatcherHandoff {
+    redis: Arc<RedisClient>,
+    keyspace: RedisKeyspace,
+    lease: DistributedLock,
+    /// Held lease; fences cursor writes
+    guard: Mutex<Option<LockGuard>>,
+    /// Set once the held lease is found taken over
+    lease_lost: watch::Sender<bool>,
+}
+
+impl WatcherHandoff {
+    /// Create a handoff store for the replica identified by `owner`
+    pub fn new(
+        redis: Arc<RedisClient>,
+        keyspace: RedisKeyspace,
+        owner: impl Into<String>,
+        lease_ttl: Duration,
+    ) -> Self {
+        let lease =
+            DistributedLock::new(redis.clone(), &keyspace, "watcher:lease", owner, lease_ttl);
+        Self {
+            redis,
+            keyspace,
+            lease,
+            guard: Mutex::new(None),
+            lease_lost: watch::channel(false).0,
+        }
+    }
+
+    /// Identifier of this replica
+    pub fn owner(&self) -> &str {
+        self.lease.owner()
+    }
+
+    /// Wait until this replica holds the watcher lease.
+    ///
+    /// A predecessor that shuts down cleanly releases the lease immediately;
+    /// one that crashed loses it once the TTL expires. The lease is renewed in
+    /// the background until [`Self::release`].
+    pub async fn acquire(&self) -> Result<()> {
+        let guard = self.lease.acquire().await?;
+        info!(
+            "Watcher {} acquired the block watcher lease (fencing token {})",
+            self.owner(),
+            guard.token()
+        );
+
+        let mut lost = guard.lost_signal();
+        let lease_lost = self.lease_lost.clone();
+        tokio::spawn(async move {
+            if lost.wait_for(|lost| *lost).await.is_ok() {
+                lease_lost.send_replace(true);
+            }
+        });
+
+        *self.guard.lock().expect("lease guard lock poisoned") = Some(guard);
+        Ok(())
+    }
+
+    /// Signal set once the held lease is found taken over by another replica
+    pub fn lease_lost(&self) -> watch::Receiver<bool> {
+        self.lease_lost.subscribe()
+    }
+
+    /// Hand the lease to the next replica
+    pub async fn release(&self) -> Result<()> {
+        let guard = self.guard.lock().expect("lease guard lock poisoned").take();
+        let Some(guard) = guard else {
+            return Ok(());
+        };
+
+        if guard.release().await? {
+            info!("Watcher {} released the block watcher lease", self.owner());
+        } else {
+            warn!(
+                "Watcher {} no longer held the lease on release",
+                self.owner()
+            );
+        }
+        Ok(())
+    }
+
+    /// Load the recorded cursor for a network
+    pub async fn load_cursor(&self, network_slug: &str) -> Result<Option<WatcherCursor>> {
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let payload: Option<String> = conn.get(self.cursor_key(network_slug)).await?;
+        Ok(payload
+            .map(|payload| serde_json::from_str(&payload))
+            .transpose()?)
+    }
+
+    /// Record a range about to be broadcast.
+    ///
+    /// Returns false if the lease was lost, in which case the range must not
+    /// be broadcast since the successor now owns the network.
+    pub async fn begin_range(
+        &self,
+        network_slug: &str,
+        last_processed_block: u64,
+        start: u64,
+        end: u64,
+    ) -> Result<bool> {
+        self.write_cursor(network_slug, last_processed_block, Some((start, end)))
+            .await
+    }
+
+    /// Record a range as broadcast
+    pub async fn commit_range(&self, network_slug: &str, end: u64) -> Result<bool> {
+        self.write_cursor(network_slug, end, None).await
+    }
+
+    async fn write_cursor(
+        &self,
+        network_slug: &str,
+        last_processed_block: u64,
+        in_flight: Option<(u64, u64)>,
+    ) -> Result<bool> {
+        let lease_value = match &*self.guard.lock().expect("lease guard lock poisoned") {
+            Some(guard) => guard.value().to_string(),
+            None => return Ok(false),
+        };
+        let cursor = WatcherCursor {
+            last_processed_block,
+            in_flight,
+            owner: self.owner().to_string(),
+            updated_at: Utc::now(),
+        };
+
+        let mut conn = self.redis.get_multiplexed_async_connection().await?;
+        let written: i64 = Script::new(WRITE_CURSOR_SCRIPT)
+            .key(self.lease.key())
+            .key(self.cursor_key(network_slug))
+            .arg(lease_value)
+            .arg(serde_json::to_string(&cursor)?)
+            .invoke_async(&mut conn)
+            .await?;
+
+        debug!(
+            "Recorded cursor {} for network {} (in flight: {:?})",
+            last_processed_block, network_slug, in_flight
+        );
+        Ok(written == 1)
+    }
+
+    fn cursor_key(&self, network_slug: &str) -> String {
+        self.keyspace
+            .key(&format!("watcher:cursor:{}", network_slug))
+    }
+}
@@ -3,26 +3,70 @@
 //! Manages a pool of OpenZeppelin Monitor instances, each handling
 //! a subset of tenant configurations.
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{error, info, instrument, warn};
+use tokio::sync::{watch, RwLock};
+use tracing::{debug, error, info, instrument, warn, Instrument};
 use uuid::Uuid;
 
+use openzeppelin_monitor::models::BlockChainType;
+
 // // Import OpenZeppelin Monitor types
 // use openzeppelin_monitor::{
 //     models::{BlockType, Monitor, Network},
 //     services::blockchain::ClientPoolTrait,
 // };
 
+use crate::models::{RpcCapAction, TenantShard, WorkerAssignment};
+use crate::repositories::RepositoryError;
 use crate::services::{
     block_cache::BlockCacheService,
     cached_client_pool::CachedClientPool,
-    oz_monitor_integration::OzMonitorServices,
+    checkpoints::CheckpointLedger,
+    control_channel::{ControlChannel, ControlCommand},
+    db_outage::DatabaseOutage,
+    error::ServiceError,
+    event_bus::{AssignmentChange, ConfigChange, Event, EventBus},
+    hooks::LifecycleHooks,
+    match_feed::{MatchEvent, MatchFeed},
+    match_webhooks::MatchWebhookDispatcher,
+    metrics::{
+        BLOCKS_PROCESSED, BLOCKS_QUEUED, BLOCK_EVENTS_DROPPED, BLOCK_EVENTS_IN_FLIGHT,
+        BLOCK_EVENTS_RECEIVED, BLOCK_EVENTS_SKIPPED, BLOCK_EVENT_LAGS, MATCHES_FOUND,
+    },
+    monitor_health::MonitorHealth,
+    notification_channels::NotificationChannels,
+    oz_monitor_integration::{OzMonitorCacheConfig, OzMonitorServices, TenantMonitorMatch},
+    resource_usage::ResourceSampler,
+    rpc_limits::TenantRpcLimiter,
+    script_invalidation::ScriptInvalidationService,
+    session_recorder::SessionRecorder,
     shared_block_watcher::{BlockEvent, SharedBlockWatcher},
+    shutdown::ShutdownSignal,
+    spill_buffer::SpillBuffer,
+    stellar_events::StellarEventFilter,
+    tenant_activity::TenantActivity,
+    trace_context::TraceContext,
+    trial_tenants::{TrialLimits, TrialTenants},
 };
+use tokio::sync::broadcast::{self, error::RecvError};
+
+/// How a worker handles block events arriving faster than it processes them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockOverflowPolicy {
+    /// Slow the block watcher down to the slowest worker
+    Block,
+    /// Overwrite the oldest unreceived events once the channel is full
+    #[default]
+    DropOldest,
+    /// Queue events on disk and replay them in order
+    Spill,
+}
 
 /// Worker configuration
 #[derive(Debug, Clone)]
@@ -33,6 +77,42 @@ pub struct WorkerConfig {
     pub health_check_interval: std::time::Duration,
     /// Tenant reload interval
     pub tenant_reload_interval: std::time::Duration,
+    /// Interval for delivering notifications held during quiet hours
+    pub digest_flush_interval: std::time::Duration,
+    /// Interval for writing tenant block checkpoints
+    pub checkpoint_flush_interval: std::time::Duration,
+    /// What happens to block events when the worker falls behind
+    pub overflow_policy: BlockOverflowPolicy,
+    /// Directory for spilling block events with the spill overflow policy
+    pub spill_dir: Option<std::path::PathBuf>,
+    /// Block events held in memory before spilling to disk
+    pub spill_threshold: usize,
+    /// Skip Stellar ledgers without events from monitored contracts when all monitors are event-only
+    pub stellar_event_prefilter: bool,
+    /// Actions applied to tenants exceeding their RPC cap (empty disables enforcement)
+    pub rpc_cap_actions: Vec<RpcCapAction>,
+    /// Bounds of the monitor, contract spec and trigger script caches
+    pub cache: OzMonitorCacheConfig,
+    /// Consecutive failing blocks after which a monitor is deactivated (0 disables)
+    pub monitor_failure_threshold: u32,
+    /// Directory to record block events and monitor snapshots to for replay; None disables recording
+    pub record_dir: Option<std::path::PathBuf>,
+    /// How long a worker records after starting
+    pub record_window: std::time::Duration,
+    /// Start warm without tenants and wait to be promoted
+    pub standby: bool,
+    /// Matches posted to a tenant's match webhook in one request
+    pub match_webhook_batch_size: usize,
+    /// Interval for posting matches waiting for tenants' match webhooks
+    pub match_webhook_flush_interval: std::time::Duration,
+    /// Timeout of each match webhook request
+    pub match_webhook_timeout: std::time::Duration,
+    /// Scheduling limits of trial tenants
+    pub trial: TrialLimits,
+    /// Interval for checking the database, entering and leaving degraded mode
+    pub db_probe_interval: std::time::Duration,
+    /// Match writes held while the database is unreachable before the oldest are dropped
+    pub max_queued_writes: usize,
 }
 
 impl Default for WorkerConfig {
@@ -41,6 +121,24 @@ impl Default for WorkerConfig {
             max_tenants_per_worker: 50,
             health_check_interval: std::time::Duration::from_secs(30),
             tenant_reload_interval: std::time::Duration::from_secs(300), // 5 minutes
+            digest_flush_interval: std::time::Duration::from_secs(60),
+            checkpoint_flush_interval: std::time::Duration::from_secs(10),
+            overflow_policy: BlockOverflowPolicy::DropOldest,
+            spill_dir: None,
+            spill_threshold: 1000,
+            stellar_event_prefilter: true,
+            rpc_cap_actions: vec![RpcCapAction::Throttle, RpcCapAction::Notify],
+            cache: OzMonitorCacheConfig::default(),
+            monitor_failure_threshold: 100,
+            record_dir: None,
+            record_window: std::time::Duration::from_secs(3600),
+            standby: false,
+            match_webhook_batch_size: 100,
+            match_webhook_flush_interval: std::time::Duration::from_secs(5),
+            match_webhook_timeout: std::time::Duration::from_secs(10),
+            trial: TrialLimits::default(),
+            db_probe_interval: std::time::Duration::from_secs(5),
+            max_queued_writes: 10_000,
         }
     }
 }
@@ -49,22 +147,163 @@ impl Default for WorkerConfig {
 pub struct MonitorWorker {
     pub id: String,
     pub assigned_tenants: Arc<RwLock<Vec<Uuid>>>,
-    pub status: Arc<RwLock<WorkerStatus>>,
+    /// Shards of tenants split across workers; their tenants are also in `assigned_tenants`
+    pub assigned_shards: Arc<RwLock<Vec<TenantShard>>>,
+    pub status: Arc<RwLock<WorkerState>>,
+    /// Set while the coordinator has paused block processing
+    paused: Arc<watch::Sender<bool>>,
     db: Arc<PgPool>,
-    _cache: Arc<BlockCacheService>,
+    cache: Arc<BlockCacheService>,
     config: WorkerConfig,
-    oz_services: Option<Arc<OzMonitorServices>>,
+    /// Set once the worker has started
+    oz_services: Arc<RwLock<Option<Arc<OzMonitorServices>>>>,
     client_pool: Option<Arc<CachedClientPool>>,
+    hooks: Arc<LifecycleHooks>,
+    notification_channels: Arc<NotificationChannels>,
+    /// Sampler the worker's tenant spans are timed for; not timed if unset
+    resource_usage: Option<Arc<ResourceSampler>>,
+    /// Activity of the worker's tenants, published as tenant metrics
+    tenant_activity: Arc<TenantActivity>,
+    /// Stops the worker after the block it is processing
+    shutdown: ShutdownSignal,
 }
 
-#[derive(Debug, Clone)]
+/// Source of block events for the monitoring loop
+enum BlockEventSource {
+    /// Directly from the shared block watcher channel
+    Channel(broadcast::Receiver<BlockEvent>),
+    /// Through a disk-backed spill buffer fed from the channel
+    Spill(Arc<SpillBuffer<BlockEvent>>),
+}
+
+impl BlockEventSource {
+    /// Events received from the watcher and waiting to be processed
+    async fn depth(&self) -> usize {
+        match self {
+            BlockEventSource::Channel(receiver) => receiver.len(),
+            BlockEventSource::Spill(buffer) => buffer.len().await,
+        }
+    }
+
+    async fn recv(&mut self) -> Result<BlockEvent, RecvError> {
+        match self {
+            BlockEventSource::Channel(receiver) => receiver.recv().await,
+            BlockEventSource::Spill(buffer) => match buffer.recv().await {
+                Ok(Some(event)) => Ok(event),
+                Ok(None) => Err(RecvError::Closed),
+                Err(e) => {
+                    error!("Failed to read spilled block event: {}", e);
+                    Err(RecvError::Closed)
+                }
+            },
+        }
+    }
+}
+
+/// What a worker error came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WorkerErrorKind {
+    /// Loading tenant configuration or writing results
+    Database,
+    /// Reaching a network's RPC endpoints
+    Rpc,
+    /// Running monitors' filters over a block
+    Filter,
+    Other,
+}
+
+impl WorkerErrorKind {
+    /// Kind of the first recognized error in the error's chain
+    pub fn of(error: &anyhow::Error) -> Self {
+        for cause in error.chain() {
+            if cause.is::<sqlx::Error>() || cause.is::<RepositoryError>() {
+                return WorkerErrorKind::Database;
+            }
+            match cause.downcast_ref::<ServiceError>() {
+                Some(ServiceError::Repository(_)) => return WorkerErrorKind::Database,
+                Some(ServiceError::CommunicationError(_)) => return WorkerErrorKind::Rpc,
+                Some(ServiceError::BlockProcessingError(_)) => return WorkerErrorKind::Filter,
+                _ => {}
+            }
+        }
+        WorkerErrorKind::Other
+    }
+}
+
+/// Failure that put a worker in the error status
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerError {
+    pub message: String,
+    pub kind: WorkerErrorKind,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl WorkerError {
+    /// Worker error for a failure occurring now
+    pub fn new(error: &anyhow::Error) -> Self {
+        Self {
+            message: error.to_string(),
+            kind: WorkerErrorKind::of(error),
+            occurred_at: Utc::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
 pub enum WorkerStatus {
     Starting,
+    /// Warm and waiting to be promoted
+    Standby,
     Running,
+    Paused,
     Reloading,
     Stopping,
     Stopped,
-    Error(String),
+    Error(WorkerError),
+}
+
+impl WorkerStatus {
+    /// Name of the status, without the error message
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WorkerStatus::Starting => "starting",
+            WorkerStatus::Standby => "standby",
+            WorkerStatus::Running => "running",
+            WorkerStatus::Paused => "paused",
+            WorkerStatus::Reloading => "reloading",
+            WorkerStatus::Stopping => "stopping",
+            WorkerStatus::Stopped => "stopped",
+            WorkerStatus::Error(_) => "error",
+        }
+    }
+}
+
+/// Worker status with the time the worker entered it
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerState {
+    #[serde(flatten)]
+    pub status: WorkerStatus,
+    pub since: DateTime<Utc>,
+}
+
+impl WorkerState {
+    /// State entering the given status now
+    pub fn new(status: WorkerStatus) -> Self {
+        Self {
+            status,
+            since: Utc::now(),
+        }
+    }
+
+    /// Move to a status; `since` only changes when the status does, or for a new error
+    pub fn set(&mut self, status: WorkerStatus) {
+        if self.status.as_str() != status.as_str() || matches!(status, WorkerStatus::Error(_)) {
+            self.since = Utc::now();
+        }
+        self.status = status;
+    }
 }
 
 impl MonitorWorker {
@@ -77,15 +316,52 @@ impl MonitorWorker {
         Self {
             id,
             assigned_tenants: Arc::new(RwLock::new(Vec::new())),
-            status: Arc::new(RwLock::new(WorkerStatus::Starting)),
+            assigned_shards: Arc::new(RwLock::new(Vec::new())),
+            status: Arc::new(RwLock::new(WorkerState::new(WorkerStatus::Starting))),
+            paused: Arc::new(watch::channel(false).0),
             db,
-            _cache: cache,
+            cache,
             config,
-            oz_services: None,
+            oz_services: Arc::new(RwLock::new(None)),
             client_pool: None,
+            hooks: Arc::new(LifecycleHooks::new()),
+            notification_channels: Arc::new(NotificationChannels::new()),
+            resource_usage: None,
+            tenant_activity: Arc::new(TenantActivity::new()),
+            shutdown: ShutdownSignal::new(),
         }
     }
 
+    /// Attach lifecycle hooks to this worker
+    pub fn with_hooks(mut self, hooks: Arc<LifecycleHooks>) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
+    /// Attach custom notification channels to this worker
+    pub fn with_notification_channels(mut self, channels: Arc<NotificationChannels>) -> Self {
+        self.notification_channels = channels;
+        self
+    }
+
+    /// Time tenant filter and trigger spans for CPU attribution by the given sampler
+    pub fn with_resource_usage(mut self, resource_usage: Arc<ResourceSampler>) -> Self {
+        self.resource_usage = Some(resource_usage);
+        self
+    }
+
+    /// Record the activity of this worker's tenants in the given tracker
+    pub fn with_tenant_activity(mut self, tenant_activity: Arc<TenantActivity>) -> Self {
+        self.tenant_activity = tenant_activity;
+        self
+    }
+
+    /// Stop the worker after the block it is processing once the given signal is triggered
+    pub fn with_shutdown(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
     /// Assign tenants to this worker
     pub async fn assign_tenants(&self, tenant_ids: Vec<Uuid>) {
         let mut tenants = self.assigned_tenants.write().await;
@@ -93,6 +369,20 @@ impl MonitorWorker {
         info!("Worker {} assigned {} tenants", self.id, tenants.len());
     }
 
+    /// Assign tenant shards to this worker
+    pub async fn assign_shards(&self, shards: Vec<TenantShard>) {
+        if let Some(oz_services) = self.oz_services.read().await.as_ref() {
+            oz_services.set_shards(shards.clone());
+        }
+        let mut assigned = self.assigned_shards.write().await;
+        *assigned = shards;
+        info!(
+            "Worker {} assigned {} tenant shards",
+            self.id,
+            assigned.len()
+        );
+    }
+
     /// Start the worker
     #[instrument(skip(self, block_watcher, client_pool), fields(worker_id = %self.id))]
     pub async fn start(
@@ -100,49 +390,153 @@ impl MonitorWorker {
         block_watcher: Arc<SharedBlockWatcher>,
         client_pool: Arc<CachedClientPool>,
     ) -> Result<()> {
-        *self.status.write().await = WorkerStatus::Running;
+        self.status.write().await.set(WorkerStatus::Running);
         info!("Starting worker {}", self.id);
 
         // Initialize OZ Monitor services for assigned tenants
         let tenant_ids = self.assigned_tenants.read().await.clone();
         if tenant_ids.is_empty() {
-            warn!("Worker {} has no assigned tenants", self.id);
-            return Ok(());
+            if !self.config.standby {
+                warn!("Worker {} has no assigned tenants", self.id);
+                return Ok(());
+            }
+            // Connections, clients and the block subscription are set up now so
+            // promotion only has to load the pushed tenants
+            info!("Worker {} standing by for promotion", self.id);
+            self.status.write().await.set(WorkerStatus::Standby);
         }
 
         // Store client pool
         self.client_pool = Some(client_pool.clone());
 
+        let checkpoints = Arc::new(CheckpointLedger::new(self.db.clone()));
+        let outage = Arc::new(DatabaseOutage::new(
+            &self.id,
+            self.db.clone(),
+            self.config.max_queued_writes,
+        ));
         let oz_services =
             match OzMonitorServices::new(self.db.clone(), tenant_ids.clone(), client_pool).await {
-                Ok(services) => Arc::new(services),
+                Ok(services) => {
+                    let mut services = services
+                        .with_notification_channels(self.notification_channels.clone())
+                        .with_cache_config(self.config.cache.clone())
+                        .with_checkpoints(checkpoints.clone())
+                        .with_database_outage(outage.clone())
+                        .with_tenant_activity(self.tenant_activity.clone())
+                        .with_trial_tenants(Arc::new(TrialTenants::new(
+                            self.db.clone(),
+                            self.config.trial.clone(),
+                        )));
+                    if let Some(rpc_costs) = self.cache.rpc_costs() {
+                        services = services.with_rpc_costs(rpc_costs);
+                    }
+                    if let Some(resource_usage) = &self.resource_usage {
+                        services = services.with_resource_usage(resource_usage.clone());
+                    }
+                    if self.config.monitor_failure_threshold > 0 {
+                        services = services.with_monitor_health(Arc::new(MonitorHealth::new(
+                            self.db.clone(),
+                            self.config.monitor_failure_threshold,
+                        )));
+                    }
+                    if self.config.rpc_cap_actions.is_empty() {
+                        Arc::new(services)
+                    } else {
+                        Arc::new(
+                            services.with_rpc_limiter(Arc::new(
+                                TenantRpcLimiter::new(
+                                    self.db.clone(),
+                                    self.config.rpc_cap_actions.clone(),
+                                )
+                                .with_trial_cap(self.config.trial.max_rpc_requests_per_minute),
+                            )),
+                        )
+                    }
+                }
                 Err(e) => {
                     error!("Failed to initialize OZ Monitor services: {}", e);
-                    *self.status.write().await = WorkerStatus::Error(e.to_string());
+                    self.status
+                        .write()
+                        .await
+                        .set(WorkerStatus::Error(WorkerError::new(&e)));
                     return Err(e);
                 }
             };
 
-        self.oz_services = Some(oz_services.clone());
+        oz_services.set_shards(self.assigned_shards.read().await.clone());
+        *self.oz_services.write().await = Some(oz_services.clone());
+        self.hooks.worker_started(&self.id, &tenant_ids).await;
 
-        // Subscribe to block events
-        let block_receiver = block_watcher.subscribe();
+        // Subscribe to block events on the bus the watcher publishes to
+        let events = block_watcher.event_bus();
+        let block_receiver = events.subscribe_blocks();
 
         // Start background tasks
         let health_handle = self.start_health_check();
         let reload_handle = self.start_tenant_reload();
+        let digest_handle = self.start_digest_flush(oz_services.clone());
+        let checkpoint_handle = checkpoints.start_flush(self.config.checkpoint_flush_interval);
+        let outage_handle = outage.start(self.config.db_probe_interval);
+        let invalidation_handle =
+            ScriptInvalidationService::new(self.cache.redis_client(), &self.cache.key_prefix())
+                .subscribe(oz_services.clone());
+        let control_handle = self.start_control_listener(oz_services.clone(), events.clone());
+        let match_webhooks = Arc::new(
+            MatchWebhookDispatcher::new(
+                self.db.clone(),
+                self.config.match_webhook_batch_size,
+                self.config.match_webhook_timeout,
+            )
+            .with_retry_policy(self.notification_channels.retry_policy().clone()),
+        );
+        let match_webhook_handle =
+            match_webhooks.start_flush(self.config.match_webhook_flush_interval);
         let monitor_handle = self
-            .start_monitoring_with_events(oz_services, block_receiver)
+            .start_monitoring_with_events(oz_services, events, match_webhooks, block_receiver)
             .await?;
 
-        // Wait for any task to complete (they should run forever)
-        tokio::select! {
-            _ = health_handle => warn!("Health check task stopped"),
-            _ = reload_handle => warn!("Tenant reload task stopped"),
-            _ = monitor_handle => warn!("Monitor task stopped"),
+        // Wait for shutdown or any task to complete (they should run forever)
+        let mut tasks = vec![
+            ("Health check", health_handle),
+            ("Tenant reload", reload_handle),
+            ("Digest flush", digest_handle),
+            ("Checkpoint flush", checkpoint_handle),
+            ("Database probe", outage_handle),
+            ("Script invalidation", invalidation_handle),
+            ("Control channel", control_handle),
+            ("Match webhook flush", match_webhook_handle),
+            ("Monitor", monitor_handle),
+        ];
+        let stopped = tokio::select! {
+            (_, index, _) = futures::future::select_all(tasks.iter_mut().map(|(_, handle)| handle)) => Some(index),
+            _ = self.shutdown.wait() => None,
+        };
+        match stopped {
+            Some(index) => warn!("{} task stopped", tasks[index].0),
+            None => {
+                // The monitor task stops after the block it is processing
+                info!("Worker {} stopping", self.id);
+                self.status.write().await.set(WorkerStatus::Stopping);
+                if let Some((_, monitor_handle)) = tasks.pop() {
+                    if let Err(e) = monitor_handle.await {
+                        warn!("Monitor task of worker {} failed: {}", self.id, e);
+                    }
+                }
+            }
+        }
+        for (_, handle) in &tasks {
+            handle.abort();
+        }
+        if let Err(e) = checkpoints.flush().await {
+            warn!(
+                "Failed to flush checkpoints of worker {} on shutdown: {}",
+                self.id, e
+            );
         }
 
-        *self.status.write().await = WorkerStatus::Stopped;
+        self.hooks.shutdown(&self.id).await;
+        self.status.write().await.set(WorkerStatus::Stopped);
         Ok(())
     }
 
@@ -172,10 +566,145 @@ impl MonitorWorker {
             let mut interval = tokio::time::interval(interval);
             loop {
                 interval.tick().await;
+                if matches!(
+                    status.read().await.status,
+                    WorkerStatus::Standby | WorkerStatus::Stopping
+                ) {
+                    continue;
+                }
                 info!("Worker {} reloading tenant configurations", worker_id);
-                *status.write().await = WorkerStatus::Reloading;
+                status.write().await.set(WorkerStatus::Reloading);
                 // Actual reload logic would go here
-                *status.write().await = WorkerStatus::Running;
+                status.write().await.set(WorkerStatus::Running);
+            }
+        })
+    }
+
+    /// Start task delivering notifications held during quiet hours and requeued dead letters
+    fn start_digest_flush(
+        &self,
+        oz_services: Arc<OzMonitorServices>,
+    ) -> tokio::task::JoinHandle<()> {
+        let tenants = self.assigned_tenants.clone();
+        let interval = self.config.digest_flush_interval;
+        let worker_id = self.id.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            loop {
+                interval.tick().await;
+                let tenant_ids = tenants.read().await.clone();
+                match oz_services.flush_digests(&tenant_ids).await {
+                    Ok(0) => {}
+                    Ok(delivered) => {
+                        info!(
+                            "Worker {} delivered {} notification digests",
+                            worker_id, delivered
+                        )
+                    }
+                    Err(e) => error!("Worker {} failed to flush digests: {}", worker_id, e),
+                }
+                match oz_services.redrive_dead_letters(&tenant_ids).await {
+                    Ok(0) => {}
+                    Ok(delivered) => {
+                        info!(
+                            "Worker {} redelivered {} dead-lettered notifications",
+                            worker_id, delivered
+                        )
+                    }
+                    Err(e) => error!("Worker {} failed to redrive dead letters: {}", worker_id, e),
+                }
+            }
+        })
+    }
+
+    /// Start task applying commands pushed by the coordinator
+    fn start_control_listener(
+        &self,
+        oz_services: Arc<OzMonitorServices>,
+        events: Arc<dyn EventBus>,
+    ) -> tokio::task::JoinHandle<()> {
+        let mut commands =
+            ControlChannel::new(self.cache.redis_client(), self.cache.keyspace().clone())
+                .subscribe(&self.id);
+        let tenants = self.assigned_tenants.clone();
+        let shards = self.assigned_shards.clone();
+        let status = self.status.clone();
+        let paused = self.paused.clone();
+        let worker_id = self.id.clone();
+
+        tokio::spawn(async move {
+            while let Some(command) = commands.recv().await {
+                info!(
+                    "Worker {} received control command {:?}",
+                    worker_id, command
+                );
+                match command {
+                    ControlCommand::AssignTenants {
+                        tenant_ids,
+                        shards: assigned_shards,
+                    } => {
+                        let mut assignment = WorkerAssignment::new(worker_id.clone());
+                        assignment.tenant_ids = tenant_ids;
+                        assignment.shards = assigned_shards;
+                        let tenant_ids = assignment.processed_tenant_ids();
+
+                        oz_services.set_shards(assignment.shards.clone());
+                        *shards.write().await = assignment.shards.clone();
+                        let previous =
+                            std::mem::replace(&mut *tenants.write().await, tenant_ids.clone());
+                        let mut status = status.write().await;
+                        if matches!(status.status, WorkerStatus::Standby) && !tenant_ids.is_empty()
+                        {
+                            info!("Worker {} promoted from standby", worker_id);
+                            status.set(WorkerStatus::Running);
+                        }
+                        drop(status);
+                        if let Err(e) = oz_services.apply_assignment(&previous, &tenant_ids).await {
+                            error!(
+                                "Worker {} failed to reload pushed assignment: {}",
+                                worker_id, e
+                            );
+                        }
+                        publish(
+                            events.as_ref(),
+                            &worker_id,
+                            Event::AssignmentChanged(AssignmentChange {
+                                worker_id: worker_id.clone(),
+                                tenant_ids,
+                                shards: assignment.shards,
+                            }),
+                        )
+                        .await;
+                    }
+                    ControlCommand::Pause => {
+                        paused.send_replace(true);
+                        status.write().await.set(WorkerStatus::Paused);
+                    }
+                    ControlCommand::Resume => {
+                        paused.send_replace(false);
+                        status.write().await.set(WorkerStatus::Running);
+                    }
+                    ControlCommand::Stop => {
+                        info!("Worker {} drained, stopping", worker_id);
+                        paused.send_replace(true);
+                        status.write().await.set(WorkerStatus::Stopping);
+                    }
+                    ControlCommand::InvalidateConfig { tenant_ids } => {
+                        let tenant_ids = if tenant_ids.is_empty() {
+                            tenants.read().await.clone()
+                        } else {
+                            tenant_ids
+                        };
+                        oz_services.invalidate_tenants(&tenant_ids);
+                        publish(
+                            events.as_ref(),
+                            &worker_id,
+                            Event::ConfigChanged(ConfigChange { tenant_ids }),
+                        )
+                        .await;
+                    }
+                }
             }
         })
     }
@@ -184,60 +713,271 @@ impl MonitorWorker {
     async fn start_monitoring_with_events(
         &self,
         oz_services: Arc<OzMonitorServices>,
-        mut block_receiver: tokio::sync::broadcast::Receiver<BlockEvent>,
+        events: Arc<dyn EventBus>,
+        match_webhooks: Arc<MatchWebhookDispatcher>,
+        block_receiver: broadcast::Receiver<BlockEvent>,
     ) -> Result<tokio::task::JoinHandle<()>> {
+        let mut block_receiver = match self.config.overflow_policy {
+            BlockOverflowPolicy::Spill => {
+                let spill_dir = self
+                    .config
+                    .spill_dir
+                    .as_ref()
+                    .context("The spill overflow policy requires a spill directory")?;
+                let buffer = Arc::new(
+                    SpillBuffer::new(spill_dir, &self.id, self.config.spill_threshold).await?,
+                );
+                self.start_spill_intake(block_receiver, buffer.clone());
+                BlockEventSource::Spill(buffer)
+            }
+            // Blocking is enforced by the watcher holding broadcasts
+            BlockOverflowPolicy::Block | BlockOverflowPolicy::DropOldest => {
+                BlockEventSource::Channel(block_receiver)
+            }
+        };
+        let in_flight = BLOCK_EVENTS_IN_FLIGHT.with_label_values(&[&self.id]);
+        let dropped = BLOCK_EVENTS_DROPPED.with_label_values(&[&self.id]);
+        let lags = BLOCK_EVENT_LAGS.with_label_values(&[&self.id]);
+        let recorder = match &self.config.record_dir {
+            Some(record_dir) => {
+                Some(SessionRecorder::start(record_dir, &self.id, self.config.record_window).await?)
+            }
+            None => None,
+        };
         let tenants = self.assigned_tenants.clone();
         let worker_id = self.id.clone();
         let status = self.status.clone();
+        let hooks = self.hooks.clone();
+        let match_feed = MatchFeed::new(self.cache.redis_client(), self.cache.keyspace().clone());
+        let mut paused = self.paused.subscribe();
+        let shutdown = self.shutdown.clone();
+        let event_filter = self
+            .config
+            .stellar_event_prefilter
+            .then(StellarEventFilter::new);
 
         let handle = tokio::spawn(async move {
             loop {
+                // Leave events queued while paused by the coordinator
+                tokio::select! {
+                    unpaused = paused.wait_for(|paused| !paused) => {
+                        if unpaused.is_err() {
+                            break;
+                        }
+                    }
+                    _ = shutdown.wait() => break,
+                }
+
                 // Wait for block events
-                match block_receiver.recv().await {
-                    Ok(block_event) => {
-                        let tenant_ids = tenants.read().await.clone();
+                let received = tokio::select! {
+                    received = block_receiver.recv() => received,
+                    _ = shutdown.wait() => break,
+                };
+                in_flight.set(block_receiver.depth().await as i64);
+
+                match received {
+                    Ok(mut block_event) => {
+                        let network_slug = block_event.network.slug.clone();
+                        BLOCK_EVENTS_RECEIVED
+                            .with_label_values(&[&worker_id, &network_slug])
+                            .inc();
+                        let skipped = |reason: &str| {
+                            BLOCK_EVENTS_SKIPPED
+                                .with_label_values(&[&worker_id, &network_slug, reason])
+                                .inc();
+                        };
+
+                        let mut tenant_ids = tenants.read().await.clone();
+                        // Replays can be limited to some tenants
+                        if !block_event.tenant_ids.is_empty() {
+                            tenant_ids.retain(|id| block_event.tenant_ids.contains(id));
+                        }
                         if tenant_ids.is_empty() {
+                            skipped("no_tenants");
                             continue;
                         }
 
+                        if let Some(recorder) = &recorder {
+                            recorder
+                                .record(&oz_services, &tenant_ids, &block_event)
+                                .await;
+                        }
+
+                        // Finalize or orphan provisional matches whose blocks are now deep enough;
+                        // replayed ranges leave settlement to newly watched blocks
+                        if let Some(latest_block) =
+                            block_event.latest_block.filter(|_| !block_event.replay)
+                        {
+                            let settled = oz_services
+                                .settle_pending(&block_event.network, latest_block)
+                                .await;
+                            dispatch_matches(
+                                &worker_id,
+                                &hooks,
+                                &match_feed,
+                                events.as_ref(),
+                                &match_webhooks,
+                                &oz_services,
+                                &settled,
+                            )
+                            .await;
+                        }
+
+                        // Skip events that cannot touch any monitored address
+                        if let Some(bloom) = &block_event.address_bloom {
+                            if let Some(addresses) = oz_services
+                                .monitored_addresses(&block_event.network.slug, &tenant_ids)
+                            {
+                                if !bloom.contains_any(&addresses) {
+                                    skipped(if addresses.is_empty() {
+                                        "network_not_monitored"
+                                    } else {
+                                        "no_monitored_addresses"
+                                    });
+                                    debug!(
+                                        "Worker {} skipping {} blocks on network {} with no monitored addresses",
+                                        worker_id,
+                                        block_event.blocks.len(),
+                                        block_event.network.slug
+                                    );
+                                    continue;
+                                }
+                            }
+                        }
+
+                        // Keep only Stellar ledgers with events from monitored contracts
+                        if let Some(event_filter) = &event_filter {
+                            if matches!(block_event.network.network_type, BlockChainType::Stellar) {
+                                if let Some(contracts) = oz_services
+                                    .event_only_contracts(&block_event.network.slug, &tenant_ids)
+                                {
+                                    prefilter_stellar_ledgers(
+                                        event_filter,
+                                        &mut block_event,
+                                        &contracts,
+                                    )
+                                    .await;
+                                    if block_event.blocks.is_empty() {
+                                        skipped("no_contract_events");
+                                        debug!(
+                                            "Worker {} skipping ledgers on network {} with no monitored contract events",
+                                            worker_id, block_event.network.slug
+                                        );
+                                        continue;
+                                    }
+                                }
+                            }
+                        }
+
                         info!(
-                            "Worker {} processing {} blocks for network {} ({} tenants)",
+                            "Worker {} processing {} {} blocks for network {} ({} tenants)",
                             worker_id,
                             block_event.blocks.len(),
+                            if block_event.replay {
+                                "replayed"
+                            } else {
+                                "new"
+                            },
                             block_event.network.slug,
                             tenant_ids.len()
                         );
 
-                        // Process each block
+                        // Process each block in a span continuing the watcher's trace
+                        let trace = block_event
+                            .trace
+                            .clone()
+                            .unwrap_or_else(TraceContext::new_root);
+                        let queued = BLOCKS_QUEUED.with_label_values(&[&worker_id, &network_slug]);
+                        queued.set(block_event.blocks.len() as i64);
                         for block in block_event.blocks {
-                            match oz_services
-                                .process_block(&block_event.network, block, &tenant_ids)
-                                .await
-                            {
-                                Ok(results) => {
-                                    let total_matches = results.len();
+                            let block_number = block.number();
+                            let span = trace.block_span(
+                                &worker_id,
+                                &block_event.network.slug,
+                                block_number,
+                            );
+                            async {
+                                match oz_services
+                                    .process_block(
+                                        &block_event.network,
+                                        block,
+                                        &tenant_ids,
+                                        block_event.latest_block,
+                                    )
+                                    .await
+                                {
+                                    Ok(results) => {
+                                        let total_matches = results.len();
+                                        BLOCKS_PROCESSED
+                                            .with_label_values(&[
+                                                &worker_id,
+                                                &block_event.network.slug,
+                                            ])
+                                            .inc();
+                                        for result in &results {
+                                            MATCHES_FOUND
+                                                .with_label_values(&[
+                                                    &worker_id,
+                                                    &result.tenant_id.to_string(),
+                                                    &block_event.network.slug,
+                                                ])
+                                                .inc();
+                                        }
+
+                                        if total_matches > 0 {
+                                            info!(
+                                                "Worker {} found {} matches on network {}",
+                                                worker_id, total_matches, block_event.network.slug
+                                            );
+                                        }
 
-                                    if total_matches > 0 {
-                                        info!(
-                                            "Worker {} found {} matches on network {}",
-                                            worker_id, total_matches, block_event.network.slug
+                                        dispatch_matches(
+                                            &worker_id,
+                                            &hooks,
+                                            &match_feed,
+                                            events.as_ref(),
+                                            &match_webhooks,
+                                            &oz_services,
+                                            &results,
+                                        )
+                                        .await;
+                                        if !block_event.replay {
+                                            hooks
+                                                .block_processed(
+                                                    &worker_id,
+                                                    &block_event.network,
+                                                    block_number,
+                                                    total_matches,
+                                                )
+                                                .await;
+                                        }
+                                    }
+                                    Err(e) => {
+                                        error!(
+                                            "Worker {} failed to process block on network {}: {}",
+                                            worker_id, block_event.network.slug, e
                                         );
+                                        status
+                                            .write()
+                                            .await
+                                            .set(WorkerStatus::Error(WorkerError::new(&e)));
                                     }
                                 }
-                                Err(e) => {
-                                    error!(
-                                        "Worker {} failed to process block on network {}: {}",
-                                        worker_id, block_event.network.slug, e
-                                    );
-                                    *status.write().await = WorkerStatus::Error(e.to_string());
-                                }
                             }
+                            .instrument(span)
+                            .await;
+                            queued.dec();
                         }
                     }
-                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
-                        warn!("Worker {} lagged behind by {} messages", worker_id, skipped);
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Worker {} lagged behind and dropped {} block events",
+                            worker_id, skipped
+                        );
+                        lags.inc();
+                        dropped.inc_by(skipped);
                     }
-                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                    Err(RecvError::Closed) => {
                         info!("Block event channel closed, stopping worker {}", worker_id);
                         break;
                     }
@@ -247,14 +987,143 @@ impl MonitorWorker {
 
         Ok(handle)
     }
+
+    /// Drain the block event channel into the spill buffer as fast as possible
+    fn start_spill_intake(
+        &self,
+        mut block_receiver: broadcast::Receiver<BlockEvent>,
+        buffer: Arc<SpillBuffer<BlockEvent>>,
+    ) -> tokio::task::JoinHandle<()> {
+        let worker_id = self.id.clone();
+        let dropped = BLOCK_EVENTS_DROPPED.with_label_values(&[&self.id]);
+        let lags = BLOCK_EVENT_LAGS.with_label_values(&[&self.id]);
+
+        tokio::spawn(async move {
+            loop {
+                match block_receiver.recv().await {
+                    Ok(block_event) => {
+                        if let Err(e) = buffer.push(block_event).await {
+                            error!("Worker {} failed to buffer block event: {}", worker_id, e);
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(
+                            "Worker {} intake lagged behind by {} messages",
+                            worker_id, skipped
+                        );
+                        lags.inc();
+                        dropped.inc_by(skipped);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+            buffer.close().await;
+        })
+    }
+}
+
+/// Run match hooks and deliver triggers for matches found by a worker
+async fn dispatch_matches(
+    worker_id: &str,
+    hooks: &LifecycleHooks,
+    match_feed: &MatchFeed,
+    events: &dyn EventBus,
+    match_webhooks: &Arc<MatchWebhookDispatcher>,
+    oz_services: &OzMonitorServices,
+    matches: &[TenantMonitorMatch],
+) {
+    for tenant_match in matches {
+        hooks.matched(worker_id, tenant_match).await;
+        let match_event = MatchEvent::new(worker_id, tenant_match);
+        if let Err(e) = match_feed.publish(&match_event).await {
+            debug!(
+                "Worker {} failed to publish match of monitor {}: {}",
+                worker_id, tenant_match.monitor_name, e
+            );
+        }
+        match_webhooks.enqueue(&match_event).await;
+        publish(events, worker_id, Event::MatchFound(match_event)).await;
+        if let Err(e) = oz_services.execute_triggers(tenant_match).await {
+            error!(
+                "Worker {} failed to execute triggers for monitor {}: {}",
+                worker_id, tenant_match.monitor_name, e
+            );
+        }
+    }
+}
+
+/// Publish a worker event on the bus; the worker carries on if it cannot be published
+async fn publish(events: &dyn EventBus, worker_id: &str, event: Event) {
+    let topic = event.topic();
+    if let Err(e) = events.publish(event).await {
+        debug!(
+            "Worker {} failed to publish {} event: {}",
+            worker_id, topic, e
+        );
+    }
+}
+
+/// Drop ledgers without events from the given contracts from a Stellar block event.
+///
+/// The event is left untouched if the RPC lookup fails, so every ledger is
+/// still processed in full.
+async fn prefilter_stellar_ledgers(
+    event_filter: &StellarEventFilter,
+    block_event: &mut BlockEvent,
+    contracts: &HashSet<String>,
+) {
+    let numbers: Vec<u64> = block_event
+        .blocks
+        .iter()
+        .filter_map(|b| b.number())
+        .collect();
+    let (Some(&start), Some(&end)) = (numbers.iter().min(), numbers.iter().max()) else {
+        return;
+    };
+
+    match event_filter
+        .ledgers_with_events(&block_event.network, start, end, contracts)
+        .await
+    {
+        Ok(ledgers) => block_event
+            .blocks
+            .retain(|block| block.number().is_some_and(|n| ledgers.contains(&n))),
+        Err(e) => warn!(
+            "Stellar event prefilter failed on network {}, processing all ledgers: {}",
+            block_event.network.slug, e
+        ),
+    }
+}
+
+/// Worker in the pool with the state readable while it runs.
+///
+/// A started worker holds its own write lock for as long as it runs, so
+/// status, assignments and services are reached through these shared handles
+/// instead.
+struct PooledWorker {
+    worker: Arc<RwLock<MonitorWorker>>,
+    status: Arc<RwLock<WorkerState>>,
+    assigned_tenants: Arc<RwLock<Vec<Uuid>>>,
+    assigned_shards: Arc<RwLock<Vec<TenantShard>>>,
+    oz_services: Arc<RwLock<Option<Arc<OzMonitorServices>>>>,
+    /// Task running the worker, taken when the pool shuts down
+    task: Option<tokio::task::JoinHandle<()>>,
+    /// Stops this worker alone after the block it is processing
+    shutdown: ShutdownSignal,
 }
 
 /// Monitor worker pool manager
 pub struct MonitorWorkerPool {
-    workers: Arc<RwLock<HashMap<String, Arc<RwLock<MonitorWorker>>>>>,
+    workers: Arc<RwLock<HashMap<String, PooledWorker>>>,
     db: Arc<PgPool>,
     _cache: Arc<BlockCacheService>,
     config: WorkerConfig,
+    hooks: Arc<LifecycleHooks>,
+    notification_channels: Arc<NotificationChannels>,
+    resource_usage: Option<Arc<ResourceSampler>>,
+    tenant_activity: Arc<TenantActivity>,
+    /// Parent of every worker's shutdown signal
+    shutdown: ShutdownSignal,
 }
 
 impl MonitorWorkerPool {
@@ -264,9 +1133,48 @@ impl MonitorWorkerPool {
             db,
             _cache: cache,
             config,
+            hooks: Arc::new(LifecycleHooks::new()),
+            notification_channels: Arc::new(NotificationChannels::new()),
+            resource_usage: None,
+            tenant_activity: Arc::new(TenantActivity::new()),
+            shutdown: ShutdownSignal::new(),
         }
     }
 
+    /// Stop the pool's workers once the given signal is triggered
+    pub fn with_shutdown(mut self, shutdown: ShutdownSignal) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Attach lifecycle hooks shared by all workers in the pool
+    pub fn with_hooks(mut self, hooks: LifecycleHooks) -> Self {
+        self.hooks = Arc::new(hooks);
+        self
+    }
+
+    /// Attach custom notification channels shared by all workers in the pool
+    pub fn with_notification_channels(mut self, channels: NotificationChannels) -> Self {
+        self.notification_channels = Arc::new(channels);
+        self
+    }
+
+    /// Time tenant filter and trigger spans of all workers in the pool for the given sampler
+    pub fn with_resource_usage(mut self, resource_usage: Arc<ResourceSampler>) -> Self {
+        self.resource_usage = Some(resource_usage);
+        self
+    }
+
+    /// Custom notification channels handed to workers
+    pub fn notification_channels(&self) -> Arc<NotificationChannels> {
+        self.notification_channels.clone()
+    }
+
+    /// Activity of the tenants processed by all workers in the pool
+    pub fn tenant_activity(&self) -> Arc<TenantActivity> {
+        self.tenant_activity.clone()
+    }
+
     /// Create and start a new worker
     pub async fn create_worker(
         &self,
@@ -275,54 +1183,92 @@ impl MonitorWorkerPool {
         block_watcher: Arc<SharedBlockWatcher>,
         client_pool: Arc<CachedClientPool>,
     ) -> Result<()> {
-        let worker = MonitorWorker::new(
+        let mut assignment = WorkerAssignment::new(worker_id);
+        assignment.tenant_ids = tenant_ids;
+        self.create_worker_with_assignment(assignment, block_watcher, client_pool)
+            .await
+    }
+
+    /// Create and start a new worker for whole tenants and tenant shards
+    pub async fn create_worker_with_assignment(
+        &self,
+        assignment: WorkerAssignment,
+        block_watcher: Arc<SharedBlockWatcher>,
+        client_pool: Arc<CachedClientPool>,
+    ) -> Result<()> {
+        let worker_id = assignment.worker_id.clone();
+        let shutdown = self.shutdown.child();
+        let mut worker = MonitorWorker::new(
             worker_id.clone(),
             self.db.clone(),
             self._cache.clone(),
             self.config.clone(),
-        );
+        )
+        .with_hooks(self.hooks.clone())
+        .with_notification_channels(self.notification_channels.clone())
+        .with_tenant_activity(self.tenant_activity.clone())
+        .with_shutdown(shutdown.clone());
+        if let Some(resource_usage) = &self.resource_usage {
+            worker = worker.with_resource_usage(resource_usage.clone());
+        }
 
-        worker.assign_tenants(tenant_ids).await;
+        worker
+            .assign_tenants(assignment.processed_tenant_ids())
+            .await;
+        worker.assign_shards(assignment.shards).await;
 
         // Add to pool
-        let worker_arc = Arc::new(RwLock::new(worker));
-        self.workers
-            .write()
-            .await
-            .insert(worker_id.clone(), worker_arc.clone());
+        let mut pooled = PooledWorker {
+            status: worker.status.clone(),
+            assigned_tenants: worker.assigned_tenants.clone(),
+            assigned_shards: worker.assigned_shards.clone(),
+            oz_services: worker.oz_services.clone(),
+            worker: Arc::new(RwLock::new(worker)),
+            task: None,
+            shutdown,
+        };
+        let worker_arc = pooled.worker.clone();
 
         // Start worker in background
-        tokio::spawn(async move {
+        pooled.task = Some(tokio::spawn(async move {
             let mut worker_lock = worker_arc.write().await;
             if let Err(e) = worker_lock.start(block_watcher, client_pool).await {
                 error!("Worker failed to start: {}", e);
             }
-        });
+        }));
+        self.workers.write().await.insert(worker_id.clone(), pooled);
 
         Ok(())
     }
 
-    /// Get worker status
-    pub async fn get_worker_status(&self, worker_id: &str) -> Option<WorkerStatus> {
+    /// Get worker status and when the worker entered it
+    pub async fn get_worker_status(&self, worker_id: &str) -> Option<WorkerState> {
         let workers = self.workers.read().await;
-        if let Some(worker) = workers.get(worker_id) {
-            let worker_lock = worker.read().await;
-            let status = worker_lock.status.read().await.clone();
-            Some(status)
-        } else {
-            None
-        }
+        let worker = workers.get(worker_id)?;
+        let status = worker.status.read().await.clone();
+        Some(status)
+    }
+
+    /// Get the tenants and tenant shards a worker is processing
+    pub async fn get_worker_tenants(
+        &self,
+        worker_id: &str,
+    ) -> Option<(Vec<Uuid>, Vec<TenantShard>)> {
+        let workers = self.workers.read().await;
+        let worker = workers.get(worker_id)?;
+        let tenant_ids = worker.assigned_tenants.read().await.clone();
+        let shards = worker.assigned_shards.read().await.clone();
+        Some((tenant_ids, shards))
     }
 
     /// List all workers
-    pub async fn list_workers(&self) -> Vec<(String, WorkerStatus, usize)> {
+    pub async fn list_workers(&self) -> Vec<(String, WorkerState, usize)> {
         let workers = self.workers.read().await;
         let mut result = Vec::new();
 
         for (id, worker) in workers.iter() {
-            let worker_lock = worker.read().await;
-            let status = worker_lock.status.read().await.clone();
-            let tenant_count = worker_lock.assigned_tenants.read().await.len();
+            let status = worker.status.read().await.clone();
+            let tenant_count = worker.assigned_tenants.read().await.len();
             result.push((id.clone(), status, tenant_count));
         }
 
@@ -333,12 +1279,16 @@ impl MonitorWorkerPool {
     pub async fn reassign_tenants(&self, worker_id: &str, tenant_ids: Vec<Uuid>) -> Result<()> {
         let workers = self.workers.read().await;
         if let Some(worker) = workers.get(worker_id) {
-            let worker_lock = worker.read().await;
-            worker_lock.assign_tenants(tenant_ids.clone()).await;
+            let previous = std::mem::replace(
+                &mut *worker.assigned_tenants.write().await,
+                tenant_ids.clone(),
+            );
+            info!("Worker {} assigned {} tenants", worker_id, tenant_ids.len());
 
-            // Reload OZ Monitor services with new tenant list if worker is running
-            if let Some(oz_services) = &worker_lock.oz_services {
-                oz_services.reload_configurations(&tenant_ids).await?;
+            // Reload the tenants that changed if the worker is running
+            let oz_services = worker.oz_services.read().await.clone();
+            if let Some(oz_services) = oz_services {
+                oz_services.apply_assignment(&previous, &tenant_ids).await?;
             }
 
             Ok(())
@@ -347,15 +1297,119 @@ impl MonitorWorkerPool {
         }
     }
 
-    /// Stop and remove a worker
-    pub async fn remove_worker(&self, worker_id: &str) -> Result<()> {
-        let mut workers = self.workers.write().await;
-        if let Some(worker) = workers.remove(worker_id) {
-            let worker_lock = worker.write().await;
-            *worker_lock.status.write().await = WorkerStatus::Stopping;
+    /// Reload one tenant's configuration on a running worker.
+    ///
+    /// Returns the number of monitors loaded, or None if the worker's
+    /// services have not started yet.
+    pub async fn reload_tenant(&self, worker_id: &str, tenant_id: Uuid) -> Result<Option<usize>> {
+        let workers = self.workers.read().await;
+        let worker = workers
+            .get(worker_id)
+            .ok_or_else(|| anyhow::anyhow!("Worker {} not found", worker_id))?;
+
+        let oz_services = worker.oz_services.read().await.clone();
+        match oz_services {
+            Some(oz_services) => Ok(Some(oz_services.reload_tenant(tenant_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the tenant shards held by a worker.
+    ///
+    /// Tenants of new shards must also be passed to `reassign_tenants`.
+    pub async fn reassign_shards(&self, worker_id: &str, shards: Vec<TenantShard>) -> Result<()> {
+        let workers = self.workers.read().await;
+        if let Some(worker) = workers.get(worker_id) {
+            if let Some(oz_services) = worker.oz_services.read().await.as_ref() {
+                oz_services.set_shards(shards.clone());
+            }
+            *worker.assigned_shards.write().await = shards;
             Ok(())
         } else {
             anyhow::bail!("Worker {} not found", worker_id)
         }
     }
+
+    /// Stop a worker after the block it is processing, remove it and wait
+    /// for it to stop
+    pub async fn remove_worker(&self, worker_id: &str) -> Result<()> {
+        let Some(mut worker) = self.workers.write().await.remove(worker_id) else {
+            anyhow::bail!("Worker {} not found", worker_id)
+        };
+        worker.status.write().await.set(WorkerStatus::Stopping);
+        worker.shutdown.trigger();
+        if let Some(task) = worker.task.take() {
+            if let Err(e) = task.await {
+                warn!("Worker {} failed while stopping: {}", worker_id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop every worker in the pool after the block it is processing and
+    /// wait for them; each worker notifies its shutdown hooks as it stops
+    pub async fn shutdown(&self) {
+        self.shutdown.trigger();
+        let tasks: Vec<(String, tokio::task::JoinHandle<()>)> = self
+            .workers
+            .write()
+            .await
+            .iter_mut()
+            .filter_map(|(worker_id, worker)| Some((worker_id.clone(), worker.task.take()?)))
+            .collect();
+        for (worker_id, task) in tasks {
+            if let Err(e) = task.await {
+                warn!("Worker {} failed during shutdown: {}", worker_id, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_kind_from_chain() {
+        let rpc = anyhow::Error::from(ServiceError::CommunicationError("timeout".into()))
+            .context("Failed to process block");
+        assert_eq!(WorkerErrorKind::of(&rpc), WorkerErrorKind::Rpc);
+
+        let filter = anyhow::Error::from(ServiceError::BlockProcessingError("bad".into()));
+        assert_eq!(WorkerErrorKind::of(&filter), WorkerErrorKind::Filter);
+
+        let db = anyhow::Error::from(sqlx::Error::PoolTimedOut);
+        assert_eq!(WorkerErrorKind::of(&db), WorkerErrorKind::Database);
+
+        assert_eq!(
+            WorkerErrorKind::of(&anyhow::anyhow!("boom")),
+            WorkerErrorKind::Other
+        );
+    }
+
+    #[test]
+    fn test_state_serialization() {
+        let state = WorkerState::new(WorkerStatus::Error(WorkerError::new(&anyhow::Error::from(
+            ServiceError::CommunicationError("timeout".into()),
+        ))));
+        let json = serde_json::to_value(&state).unwrap();
+        assert_eq!(json["state"], "error");
+        assert_eq!(json["kind"], "rpc");
+        assert!(json["message"].as_str().unwrap().contains("timeout"));
+        assert!(json["since"].is_string());
+
+        let json = serde_json::to_value(WorkerState::new(WorkerStatus::Running)).unwrap();
+        assert_eq!(json["state"], "running");
+    }
+
+    #[test]
+    fn test_since_kept_for_same_status() {
+        let mut state = WorkerState::new(WorkerStatus::Running);
+        let since = state.since;
+        state.set(WorkerStatus::Running);
+        assert_eq!(state.since, since);
+        state.set(WorkerStatus::Paused);
+        assert!(state.since >= since);
+        assert_eq!(state.status.as_str(), "paused");
+    }
 }